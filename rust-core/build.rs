@@ -1,3 +1,9 @@
 fn main() {
-    uniffi::generate_scaffolding("src/zenone.udl").expect("Failed to generate UniFFI scaffolding");
+    // The watch build exports only the lite runtime
+    let udl = if std::env::var_os("CARGO_FEATURE_LITE").is_some() {
+        "src/zenone_lite.udl"
+    } else {
+        "src/zenone.udl"
+    };
+    uniffi::generate_scaffolding(udl).expect("Failed to generate UniFFI scaffolding");
 }
@@ -1,15 +1,23 @@
 #!/bin/bash
 # UniFFI Binding Generation Script
 # Generates Swift and Kotlin bindings for ZenOne Rust core
+# Pass --lite for the watch-only runtime (src/zenone_lite.udl)
 
 set -e
 
+UDL="src/zenone.udl"
+FEATURES=""
+if [[ "$1" == "--lite" ]]; then
+    UDL="src/zenone_lite.udl"
+    FEATURES="--no-default-features --features lite"
+fi
+
 SCRIPT_DIR="$(cd "$(dirname "$0")" && pwd)"
 IOS_OUT="$SCRIPT_DIR/../app/ios/ZenOneCore"
 ANDROID_OUT="$SCRIPT_DIR/../app/android/app/src/main/java/com/zenone/core"
 
 echo "🔧 Building Rust library..."
-cargo build --release
+cargo build --release $FEATURES
 
 # Determine library path based on OS
 if [[ "$(uname)" == "Darwin" ]]; then
@@ -28,13 +36,13 @@ fi
 
 echo "📱 Generating Swift bindings..."
 mkdir -p "$IOS_OUT"
-uniffi-bindgen generate src/zenone.udl --language swift --out-dir "$IOS_OUT" || \
-    cargo run --features uniffi/cli --bin uniffi-bindgen generate src/zenone.udl --language swift --out-dir "$IOS_OUT"
+uniffi-bindgen generate $UDL --language swift --out-dir "$IOS_OUT" || \
+    cargo run --features uniffi/cli --bin uniffi-bindgen generate $UDL --language swift --out-dir "$IOS_OUT"
 
 echo "🤖 Generating Kotlin bindings..."
 mkdir -p "$ANDROID_OUT"
-uniffi-bindgen generate src/zenone.udl --language kotlin --out-dir "$ANDROID_OUT" || \
-    cargo run --features uniffi/cli --bin uniffi-bindgen generate src/zenone.udl --language kotlin --out-dir "$ANDROID_OUT"
+uniffi-bindgen generate $UDL --language kotlin --out-dir "$ANDROID_OUT" || \
+    cargo run --features uniffi/cli --bin uniffi-bindgen generate $UDL --language kotlin --out-dir "$ANDROID_OUT"
 
 echo ""
 echo "✅ Bindings generated!"
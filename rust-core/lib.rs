@@ -8,34 +8,52 @@
 // if a thread panics while holding the lock. This is critical for a health app.
 
 use parking_lot::Mutex;
-use std::time::Instant;
-use std::sync::{Arc, RwLock};
+#[cfg(feature = "full")]
+use arc_swap::ArcSwap;
+use std::time::{Duration, Instant};
+use std::sync::Arc;
+#[cfg(feature = "full")]
 use std::thread;
+#[cfg(feature = "full")]
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "full")]
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+#[cfg(feature = "full")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "full")]
 use crossbeam_channel::{unbounded, Sender, Receiver, select};
 
 use serde::{Serialize, Deserialize};
 
 use std::collections::HashMap;
 use chrono::Utc;
+#[cfg(feature = "full")]
+use chrono::{Local, Timelike};
 
+#[cfg(feature = "full")]
 use chacha20poly1305::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
     ChaCha20Poly1305, Nonce,
 };
+#[cfg(feature = "full")]
 use argon2::{
     password_hash::{
         PasswordHasher, SaltString
     },
     Argon2
 };
+#[cfg(feature = "full")]
 use zeroize::Zeroize;
+#[cfg(feature = "full")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "full")]
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 
 
-use zenb_core::{
-    phase_machine::{Phase, PhaseMachine, PhaseDurations},
-    Engine,
-    belief::Context,
-};
+use zenb_core::phase_machine::{Phase, PhaseMachine, PhaseDurations};
+#[cfg(feature = "full")]
+use zenb_core::{belief::Context, Engine};
+#[cfg(feature = "full")]
 use zenb_signals::rppg::{RppgProcessor, RppgMethod};
 
 // LOCAL DEFINITIONS (Missing from zenb-core)
@@ -69,6 +87,27 @@ impl BreathPattern {
     }
 }
 
+/// Intermediate pattern between `a` (t = 0) and `b` (t = 1), used to morph
+/// one breathing pattern into another over several cycles
+pub fn blend_patterns(a: &BreathPattern, b: &BreathPattern, t: f32) -> BreathPattern {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |x: f32, y: f32| x + (y - x) * t;
+    BreathPattern {
+        id: format!("{}~{}@{:.2}", a.id, b.id, t),
+        label: format!("{} → {} ({:.0}%)", a.label, b.label, t * 100.0),
+        tag: if t < 0.5 { a.tag.clone() } else { b.tag.clone() },
+        description: format!("Blend of {} and {}", a.id, b.id),
+        timings: BreathTimings {
+            inhale: lerp(a.timings.inhale, b.timings.inhale),
+            hold_in: lerp(a.timings.hold_in, b.timings.hold_in),
+            exhale: lerp(a.timings.exhale, b.timings.exhale),
+            hold_out: lerp(a.timings.hold_out, b.timings.hold_out),
+        },
+        recommended_cycles: lerp(a.recommended_cycles as f32, b.recommended_cycles as f32).round() as u32,
+        arousal_impact: lerp(a.arousal_impact, b.arousal_impact),
+    }
+}
+
 /// Complete breathing pattern library matching TypeScript definitions
 /// All patterns are evidence-based with documented physiological effects
 pub fn builtin_patterns() -> HashMap<String, BreathPattern> {
@@ -229,7 +268,12 @@ pub fn builtin_patterns() -> HashMap<String, BreathPattern> {
     m
 }
 
+#[cfg(not(any(feature = "full", feature = "lite")))]
+compile_error!("enable `full` (the default) or, for watch builds, `lite`");
+#[cfg(not(feature = "lite"))]
 uniffi::include_scaffolding!("zenone");
+#[cfg(feature = "lite")]
+uniffi::include_scaffolding!("zenone_lite");
 
 // ============================================================================
 // UniFFI ERROR TYPE
@@ -248,6 +292,12 @@ pub enum ZenOneError {
 
     #[error("config error: {0}")]
     ConfigError(String),
+
+    #[error("consent required: {0}")]
+    ConsentRequired(String),
+
+    #[error("capability unavailable: {0}")]
+    CapabilityUnavailable(String),
 }
 
 // ============================================================================
@@ -293,6 +343,8 @@ pub enum FfiPhase {
     HoldIn,
     Exhale,
     HoldOut,
+    /// Pre-session countdown before the first inhale (never produced by the pacer)
+    Prepare,
 }
 
 impl From<Phase> for FfiPhase {
@@ -333,13 +385,16 @@ impl From<u8> for FfiBeliefMode {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FfiRuntimeStatus {
     Idle,
+    /// Pre-session countdown while the signal warms up
+    Preparing,
     Running,
     Paused,
     SafetyLock,
 }
 
 /// Full belief state (FFI-safe)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FfiBeliefState {
     /// 5-mode probability distribution [Calm, Stress, Focus, Sleepy, Energize]
     pub probabilities: Vec<f32>,
@@ -349,8 +404,12 @@ pub struct FfiBeliefState {
     pub mode: FfiBeliefMode,
     /// Uncertainty (inverse of confidence)
     pub uncertainty: f32,
+    /// Per-mode probabilities keyed by mode id: the five built-in modes,
+    /// followed by any registered custom modes
+    pub modes: Vec<FfiModeProbability>,
 }
 
+#[cfg(feature = "full")]
 impl FfiBeliefState {
     fn from_belief_array(p: &[f32; 5], confidence: f32) -> Self {
         let (max_idx, _) = p.iter().enumerate()
@@ -361,6 +420,7 @@ impl FfiBeliefState {
             confidence,
             mode: FfiBeliefMode::from(max_idx as u8),
             uncertainty: 1.0 - confidence,
+            modes: BeliefModeRegistry::builtin_probabilities(p),
         }
     }
 
@@ -370,11 +430,13 @@ impl FfiBeliefState {
             confidence: 0.0,
             mode: FfiBeliefMode::Calm,
             uncertainty: 1.0,
+            modes: BeliefModeRegistry::builtin_probabilities(&[0.2; 5]),
         }
     }
 }
 
 /// Helper to extract belief from Engine's vinnana controller
+#[cfg(feature = "full")]
 fn get_engine_belief(engine: &Engine) -> FfiBeliefState {
     // VAJRA-001: Access belief via Vinnana -> Pipeline -> Vedana
     let state = engine.vinnana.pipeline.vedana.state();
@@ -382,7 +444,308 @@ fn get_engine_belief(engine: &Engine) -> FfiBeliefState {
     FfiBeliefState::from_belief_array(&state.p, confidence)
 }
 
+#[cfg(test)]
+mod ffi_enum_tests {
+    use super::*;
+
+    const FULL_UDL: &str = include_str!("src/zenone.udl");
+    const LITE_UDL: &str = include_str!("src/zenone_lite.udl");
+
+    /// Variant names of `enum name { ... };` in a UDL file
+    fn udl_variants(udl: &str, name: &str) -> Vec<String> {
+        let header = format!("enum {} {{", name);
+        udl.lines()
+            .skip_while(|line| line.trim() != header)
+            .skip(1)
+            .take_while(|line| line.trim() != "};")
+            .map(|line| line.trim().trim_end_matches(',').trim_matches('"').to_string())
+            .collect()
+    }
+
+    fn debug_names<T: std::fmt::Debug>(variants: &[T]) -> Vec<String> {
+        variants.iter().map(|v| format!("{:?}", v)).collect()
+    }
+
+    fn all_phases() -> [FfiPhase; 5] {
+        // Adding a variant fails this match until the list below is updated
+        match FfiPhase::Inhale {
+            FfiPhase::Inhale | FfiPhase::HoldIn | FfiPhase::Exhale | FfiPhase::HoldOut | FfiPhase::Prepare => {}
+        }
+        [FfiPhase::Inhale, FfiPhase::HoldIn, FfiPhase::Exhale, FfiPhase::HoldOut, FfiPhase::Prepare]
+    }
+
+    fn all_statuses() -> [FfiRuntimeStatus; 5] {
+        match FfiRuntimeStatus::Idle {
+            FfiRuntimeStatus::Idle
+            | FfiRuntimeStatus::Preparing
+            | FfiRuntimeStatus::Running
+            | FfiRuntimeStatus::Paused
+            | FfiRuntimeStatus::SafetyLock => {}
+        }
+        [
+            FfiRuntimeStatus::Idle,
+            FfiRuntimeStatus::Preparing,
+            FfiRuntimeStatus::Running,
+            FfiRuntimeStatus::Paused,
+            FfiRuntimeStatus::SafetyLock,
+        ]
+    }
+
+    #[test]
+    fn shared_enums_match_in_both_udls() {
+        for name in ["ZenOneError", "FfiPhase", "FfiRuntimeStatus"] {
+            let full = udl_variants(FULL_UDL, name);
+            assert!(!full.is_empty(), "{} missing from zenone.udl", name);
+            assert_eq!(udl_variants(LITE_UDL, name), full, "{} differs between lite and full UDL", name);
+        }
+    }
+
+    #[test]
+    fn udl_enums_match_rust_variants() {
+        assert_eq!(udl_variants(FULL_UDL, "FfiPhase"), debug_names(&all_phases()));
+        assert_eq!(udl_variants(FULL_UDL, "FfiRuntimeStatus"), debug_names(&all_statuses()));
+        let errors = [
+            ZenOneError::PatternNotFound,
+            ZenOneError::SessionNotActive,
+            ZenOneError::SafetyViolation(String::new()),
+            ZenOneError::ConfigError(String::new()),
+            ZenOneError::ConsentRequired(String::new()),
+            ZenOneError::CapabilityUnavailable(String::new()),
+        ];
+        let names: Vec<String> = debug_names(&errors)
+            .into_iter()
+            .map(|n| n.split('(').next().unwrap_or_default().to_string())
+            .collect();
+        assert_eq!(udl_variants(FULL_UDL, "ZenOneError"), names);
+    }
+
+    #[test]
+    fn pacer_phases_map_one_to_one() {
+        assert_eq!(FfiPhase::from(Phase::Inhale), FfiPhase::Inhale);
+        assert_eq!(FfiPhase::from(Phase::HoldIn), FfiPhase::HoldIn);
+        assert_eq!(FfiPhase::from(Phase::Exhale), FfiPhase::Exhale);
+        assert_eq!(FfiPhase::from(Phase::HoldOut), FfiPhase::HoldOut);
+    }
+}
+
+// ============================================================================
+// BELIEF MODES
+// ============================================================================
+
+/// Built-in modes as (id, label), in engine basis order
+#[cfg(feature = "full")]
+const BUILTIN_BELIEF_MODES: [(&str, &str); 5] = [
+    ("calm", "Calm"),
+    ("stress", "Stress"),
+    ("focus", "Focus"),
+    ("sleepy", "Sleepy"),
+    ("energize", "Energize"),
+];
+
+/// Upper bound on registered custom modes
+#[cfg(feature = "full")]
+const MAX_CUSTOM_BELIEF_MODES: usize = 16;
+
+/// Probability above which a mode's recommender hook applies
+#[cfg(feature = "full")]
+const MODE_BOOST_THRESHOLD: f32 = 0.5;
+
+/// Recommender points for a boosted pattern at probability 1.0
+#[cfg(feature = "full")]
+const MODE_BOOST_POINTS: f32 = 25.0;
+
+/// A belief mode definition. Built-in modes map one-to-one onto the engine's
+/// basis; custom modes are a clamped linear projection of it:
+/// `p = clamp(bias + Σ weights[i] * basis[i], 0, 1)`.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiBeliefModeSpec {
+    /// Stable identifier, lowercase ascii (e.g. "pain", "anxiety")
+    pub id: String,
+    /// Display label
+    pub label: String,
+    /// Weights over [Calm, Stress, Focus, Sleepy, Energize]
+    pub weights: Vec<f32>,
+    pub bias: f32,
+    /// Pattern ids the recommender favours while this mode is likely
+    pub boost_patterns: Vec<String>,
+    /// True for the five modes the engine tracks natively
+    pub builtin: bool,
+}
+
+/// Probability of a single mode (FFI-safe)
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiModeProbability {
+    pub mode_id: String,
+    pub probability: f32,
+}
+
+#[cfg(feature = "full")]
+const BELIEF_MODES_FILE: &str = "belief_modes.json";
+
+/// Data-driven mode set layered over the engine's fixed five-mode basis.
+/// Custom modes are registered by research builds at runtime and persisted
+/// when a file is attached.
+#[cfg(feature = "full")]
+pub struct BeliefModeRegistry {
+    inner: Mutex<BeliefModeRegistryInner>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct BeliefModeRegistryInner {
+    custom: Vec<FfiBeliefModeSpec>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+impl Default for BeliefModeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl BeliefModeRegistry {
+    pub fn new() -> Self {
+        Self { inner: Mutex::new(BeliefModeRegistryInner::default()) }
+    }
+
+    /// Load persisted custom modes from `path`; modes registered before
+    /// attaching replace loaded ones with the same id
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read belief modes: {}", e)))?;
+            serde_json::from_slice::<BeliefModeRegistryInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt belief modes file: {}", e)))?
+        } else {
+            BeliefModeRegistryInner::default()
+        };
+        loaded.path = Some(path);
+
+        let mut inner = self.inner.lock();
+        let pending = std::mem::take(&mut inner.custom);
+        *inner = loaded;
+        for spec in pending {
+            inner.custom.retain(|m| m.id != spec.id);
+            inner.custom.push(spec);
+        }
+        inner.custom.truncate(MAX_CUSTOM_BELIEF_MODES);
+        Self::persist(&inner)
+    }
+
+    fn persist(inner: &BeliefModeRegistryInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Belief mode serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist belief modes: {}", e)))
+    }
+
+    fn builtin_probabilities(p: &[f32; 5]) -> Vec<FfiModeProbability> {
+        BUILTIN_BELIEF_MODES.iter().zip(p.iter())
+            .map(|((id, _), &probability)| FfiModeProbability {
+                mode_id: id.to_string(),
+                probability,
+            })
+            .collect()
+    }
+
+    fn builtin_specs() -> Vec<FfiBeliefModeSpec> {
+        BUILTIN_BELIEF_MODES.iter().enumerate()
+            .map(|(i, (id, label))| {
+                let mut weights = vec![0.0; 5];
+                weights[i] = 1.0;
+                FfiBeliefModeSpec {
+                    id: id.to_string(),
+                    label: label.to_string(),
+                    weights,
+                    bias: 0.0,
+                    boost_patterns: Vec::new(),
+                    builtin: true,
+                }
+            })
+            .collect()
+    }
+
+    /// Add or replace a custom mode. Boost patterns must exist in `patterns`.
+    fn register(&self, mut spec: FfiBeliefModeSpec, patterns: &PatternLibrary) -> Result<(), ZenOneError> {
+        let id_ok = !spec.id.is_empty()
+            && spec.id.len() <= 32
+            && spec.id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !id_ok {
+            return Err(ZenOneError::ConfigError(format!("invalid mode id '{}'", spec.id)));
+        }
+        if BUILTIN_BELIEF_MODES.iter().any(|(id, _)| *id == spec.id) {
+            return Err(ZenOneError::ConfigError(format!("'{}' is a built-in mode", spec.id)));
+        }
+        if spec.weights.len() != BUILTIN_BELIEF_MODES.len() {
+            return Err(ZenOneError::ConfigError(format!(
+                "mode weights must have {} entries", BUILTIN_BELIEF_MODES.len()
+            )));
+        }
+        if !spec.bias.is_finite() || spec.weights.iter().any(|w| !w.is_finite()) {
+            return Err(ZenOneError::ConfigError("mode weights must be finite".into()));
+        }
+        if let Some(unknown) = spec.boost_patterns.iter().find(|id| !patterns.contains(id)) {
+            return Err(ZenOneError::ConfigError(format!("unknown boost pattern '{}'", unknown)));
+        }
+        spec.builtin = false;
+
+        let mut inner = self.inner.lock();
+        if let Some(existing) = inner.custom.iter_mut().find(|m| m.id == spec.id) {
+            *existing = spec;
+        } else if inner.custom.len() >= MAX_CUSTOM_BELIEF_MODES {
+            return Err(ZenOneError::ConfigError(format!(
+                "at most {} custom modes", MAX_CUSTOM_BELIEF_MODES
+            )));
+        } else {
+            inner.custom.push(spec);
+        }
+        Self::persist(&inner)
+    }
+
+    /// Remove a custom mode; returns whether it existed
+    fn remove(&self, id: &str) -> Result<bool, ZenOneError> {
+        let mut inner = self.inner.lock();
+        let before = inner.custom.len();
+        inner.custom.retain(|m| m.id != id);
+        if inner.custom.len() == before {
+            return Ok(false);
+        }
+        Self::persist(&inner).map(|_| true)
+    }
+
+    /// Built-in modes followed by custom ones
+    fn list(&self) -> Vec<FfiBeliefModeSpec> {
+        let mut specs = Self::builtin_specs();
+        specs.extend(self.custom_specs());
+        specs
+    }
+
+    fn custom_specs(&self) -> Vec<FfiBeliefModeSpec> {
+        self.inner.lock().custom.clone()
+    }
+
+    /// Append custom mode probabilities to a belief derived from the basis
+    fn extend(&self, belief: &mut FfiBeliefState) {
+        let inner = self.inner.lock();
+        belief.modes.extend(inner.custom.iter().map(|spec| {
+            let projected = spec.weights.iter().zip(belief.probabilities.iter())
+                .fold(spec.bias, |acc, (w, p)| acc + w * p);
+            FfiModeProbability {
+                mode_id: spec.id.clone(),
+                probability: projected.clamp(0.0, 1.0),
+            }
+        }));
+    }
+}
+
 /// Estimate from Engine (FFI-safe)
+#[cfg(feature = "full")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FfiEstimate {
     /// Arousal level 0-1
@@ -398,7 +761,8 @@ pub struct FfiEstimate {
 }
 
 /// Safety status (FFI-safe)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FfiSafetyStatus {
     /// Whether safety lock is engaged
     pub is_locked: bool,
@@ -408,10 +772,15 @@ pub struct FfiSafetyStatus {
     pub tempo_bounds: Vec<f32>,
     /// Current HR bounds [min, max]
     pub hr_bounds: Vec<f32>,
+    /// Seconds until the lock may be reset (0 if not cooling down)
+    pub cooldown_remaining_sec: f32,
+    /// Critical violations not yet acknowledged
+    pub unacknowledged_critical: u32,
 }
 
 /// Resonance metrics (FFI-safe)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FfiResonance {
     /// Coherence score 0-1
     pub coherence_score: f32,
@@ -419,23 +788,66 @@ pub struct FfiResonance {
     pub phase_locking: f32,
     /// Rhythm alignment 0-1
     pub rhythm_alignment: f32,
+    /// Respiratory sinus arrhythmia: heart rate max minus min over the last
+    /// breath cycle, in BPM (0 until a cycle has enough samples)
+    #[serde(default)]
+    pub rsa_amplitude_bpm: f32,
 }
 
 /// Frame result from process_frame
+#[cfg(feature = "full")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FfiFrame {
     pub phase: FfiPhase,
     pub phase_progress: f32,
     pub cycles_completed: u64,
     pub heart_rate: Option<f32>,
+    /// Camera pulse quality index 0-1 (SNR and perfusion); heart rate is
+    /// withheld while it is low
     pub signal_quality: f32,
     /// Full belief state
     pub belief: FfiBeliefState,
     /// Resonance metrics
     pub resonance: FfiResonance,
+    /// Pacer adherence 0-1 (current cycle, or last completed cycle)
+    pub adherence: Option<f32>,
+    /// Breaths per minute the pacer is asking for (None during previews)
+    pub prescribed_rate_bpm: Option<f32>,
+    /// Breaths per minute measured from the respiration signal
+    pub detected_rate_bpm: Option<f32>,
+    /// Time until the pacer changes phase (None during previews)
+    pub time_to_next_phase_ms: Option<u32>,
+    /// Whole seconds left in the 3-2-1 countdown while in `FfiPhase::Prepare`
+    /// (0 while waiting on signal quality)
+    pub countdown: Option<u32>,
+    /// Live progress towards the session's goals (empty without goals)
+    pub goals: Vec<FfiGoalProgress>,
+    /// Time-domain HRV over the last minute of beats (camera or wearable)
+    pub hrv: Option<FfiHrvMetrics>,
+    /// LF/HF and resonance coherence over the last two minutes of beats
+    pub hrv_spectrum: Option<FfiHrvSpectrum>,
+    /// Breathing rate estimated from the camera pulse waveform
+    pub respiratory_rate_bpm: Option<f32>,
+    /// Camera heart rate before the Kalman filter (None for wearable HR)
+    pub raw_heart_rate: Option<f32>,
+    /// Kalman filter confidence 0-1 in `heart_rate` (None for wearable HR)
+    pub hr_filter_confidence: Option<f32>,
+    /// Camera pulse SNR behind `signal_quality` (None for wearable HR)
+    pub signal_snr_db: Option<f32>,
+    /// Camera pulse strength, percent of skin brightness (None for wearable HR)
+    pub perfusion_index: Option<f32>,
+}
+
+/// Minimal per-tick frame for display-rate animation (no heap data)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FfiFrameLite {
+    pub phase: FfiPhase,
+    pub phase_progress: f32,
+    pub heart_rate: Option<f32>,
 }
 
 /// Session statistics
+#[cfg(feature = "full")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FfiSessionStats {
     pub duration_sec: f32,
@@ -446,10 +858,79 @@ pub struct FfiSessionStats {
     pub final_belief: FfiBeliefState,
     /// Average resonance score
     pub avg_resonance: f32,
+    /// Mean pacer adherence across scored cycles
+    #[serde(default)]
+    pub avg_adherence: Option<f32>,
+    /// Adherence score per completed cycle
+    #[serde(default)]
+    pub cycle_adherence: Vec<f32>,
+    /// Pauses and other interruptions during the session
+    #[serde(default)]
+    pub interruptions: Vec<FfiInterruption>,
+    /// True when the session was auto-finalized after a very long interruption
+    #[serde(default)]
+    pub partial: bool,
+    /// Seconds spent in each configured heart-rate zone
+    #[serde(default)]
+    pub time_in_zone: Vec<FfiZoneTime>,
+    /// Binaural listening time per brainwave state (states never played are omitted)
+    #[serde(default)]
+    pub binaural: Vec<FfiBinauralListening>,
+    /// Final progress towards the goals set at session start
+    #[serde(default)]
+    pub goals: Vec<FfiGoalProgress>,
+    /// Per-segment breakdown when the session ran a pattern sequence
+    #[serde(default)]
+    pub segments: Vec<FfiSegmentStats>,
+    /// Warm-up, main and cool-down boundaries, in active session seconds
+    #[serde(default)]
+    pub stages: Vec<FfiStageSpan>,
+    /// Natural breathing measured during the cool-down
+    #[serde(default)]
+    pub cool_down_breathing: Option<FfiBreathingBaseline>,
+    /// Wind-down summary for sleep sessions
+    #[serde(default)]
+    pub sleep: Option<FfiSleepSummary>,
+    /// Time-domain HRV over every artifact-free beat of the session
+    #[serde(default)]
+    pub hrv: Option<FfiHrvMetrics>,
+    /// Frequency-domain HRV over the same beats (sessions of at least a minute)
+    #[serde(default)]
+    pub hrv_spectrum: Option<FfiHrvSpectrum>,
 }
 
-/// Full runtime state snapshot (FFI-safe)
+/// A completed session as kept in the session store
+#[cfg(feature = "full")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSessionRecord {
+    pub id: String,
+    /// Wall-clock start (unix ms)
+    pub started_at_ms: i64,
+    /// Wall-clock end (unix ms)
+    pub ended_at_ms: i64,
+    pub stats: FfiSessionStats,
+    /// User tags, normalized to lowercase (e.g. "before exam")
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Free-form user note
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Number of inter-beat intervals kept for export (0 without a beat-to-beat source)
+    #[serde(default)]
+    pub ibi_count: u32,
+    /// Hex ed25519 signature by the recording device; None for imported
+    /// sessions and those completed before the signing key was unlocked
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// UTC offset (minutes, DST included) where the session was recorded, so
+    /// it keeps its calendar day after travel; None for imported sessions
+    #[serde(default)]
+    pub utc_offset_min: Option<i32>,
+}
+
+/// Full runtime state snapshot (FFI-safe)
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FfiRuntimeState {
     pub status: FfiRuntimeStatus,
     pub pattern_id: String,
@@ -461,48 +942,411 @@ pub struct FfiRuntimeState {
     pub belief: FfiBeliefState,
     pub resonance: FfiResonance,
     pub safety: FfiSafetyStatus,
+    /// Countdown progress while Preparing
+    pub preparation: Option<FfiPreparationStatus>,
+    /// Follow-me pacer state when enabled
+    pub follow_me: Option<FfiFollowMeStatus>,
+    /// Position in the loaded pattern sequence
+    pub sequence: Option<FfiSequenceProgress>,
+    /// Warm-up / main / cool-down stage of the running session
+    pub stage: Option<FfiStageStatus>,
+    /// Wind-down progress during sleep sessions
+    pub sleep: Option<FfiSleepStatus>,
+}
+
+/// Handle to a published `FfiRuntimeState`, shared with the runtime. Taking
+/// one copies nothing; each accessor copies only the field it returns.
+#[cfg(feature = "full")]
+pub struct RuntimeStateSnapshot(Arc<FfiRuntimeState>);
+
+#[cfg(feature = "full")]
+impl RuntimeStateSnapshot {
+    pub fn status(&self) -> FfiRuntimeStatus {
+        self.0.status
+    }
+
+    pub fn pattern_id(&self) -> String {
+        self.0.pattern_id.clone()
+    }
+
+    pub fn phase(&self) -> FfiPhase {
+        self.0.phase
+    }
+
+    pub fn phase_progress(&self) -> f32 {
+        self.0.phase_progress
+    }
+
+    pub fn cycles_completed(&self) -> u64 {
+        self.0.cycles_completed
+    }
+
+    pub fn session_duration_sec(&self) -> f32 {
+        self.0.session_duration_sec
+    }
+
+    pub fn tempo_scale(&self) -> f32 {
+        self.0.tempo_scale
+    }
+
+    pub fn belief(&self) -> FfiBeliefState {
+        self.0.belief.clone()
+    }
+
+    pub fn resonance(&self) -> FfiResonance {
+        self.0.resonance.clone()
+    }
+
+    pub fn safety(&self) -> FfiSafetyStatus {
+        self.0.safety.clone()
+    }
+
+    /// The whole record, copied
+    pub fn to_state(&self) -> FfiRuntimeState {
+        (*self.0).clone()
+    }
 }
 
 // ============================================================================
 // RUNTIME
 // ============================================================================
 
+/// Time source for the runtime actor and safety monitor. Tests and replays
+/// inject a `ManualClock` to drive virtual time.
+pub trait Clock: Send + Sync {
+    /// Monotonic time, for durations and cooldowns
+    fn now(&self) -> Instant;
+    /// Wall-clock Unix time in milliseconds, for records and spec events
+    fn now_ms(&self) -> i64;
+
+    fn since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+/// The real clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_ms(&self) -> i64 {
+        Utc::now().timestamp_millis()
+    }
+}
+
+/// Virtual clock that only moves when advanced
+pub struct ManualClock {
+    origin: Instant,
+    origin_ms: i64,
+    offset: Mutex<Duration>,
+}
+
+impl ManualClock {
+    /// Start at `start_ms` Unix milliseconds
+    pub fn new(start_ms: i64) -> Self {
+        Self { origin: Instant::now(), origin_ms: start_ms, offset: Mutex::new(Duration::ZERO) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.offset.lock() += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + *self.offset.lock()
+    }
+
+    fn now_ms(&self) -> i64 {
+        self.origin_ms + self.offset.lock().as_millis() as i64
+    }
+}
+
+#[cfg(feature = "full")]
 struct SessionState {
     start_time: Instant,
+    started_at_ms: i64,
     pattern_id: String,
     hr_samples: Vec<f32>,
     resonance_samples: Vec<f32>,
+    cycle_adherence: Vec<f32>,
+    /// Beat-to-beat intervals from wearables, for HRV
+    rr_intervals_ms: Vec<f32>,
+    /// Artifact-free intervals from the HRV window's source, camera included
+    hrv_intervals_ms: Vec<f32>,
+    interruptions: Vec<FfiInterruption>,
+    /// Open interruption: kind and when it began
+    interrupted: Option<(FfiInterruptionKind, Instant)>,
+    hr_zones: HrZoneTracker,
+    /// Binaural listening seconds, indexed like `FfiBrainWaveState::ALL`
+    binaural_sec: [f32; 4],
+    goals: GoalTracker,
+    sleep: Option<SleepWindDown>,
+    /// Acute-anxiety session: fixed pattern, no arc planning
+    panic: bool,
+    /// Active seconds left before the session completes on its own
+    time_limit_sec: Option<f32>,
+    /// Cycle count at which the session completes on its own (auto-complete)
+    cycle_limit: Option<u64>,
+    stages: SessionStages,
 }
 
+#[cfg(feature = "full")]
 struct RuntimeInner {
     engine: Engine,
     phase_machine: PhaseMachine,
+    phase_clock: PhaseClock,
+    /// Lead time for PhaseEnding events; None disables them
+    phase_warning_lead_ms: Option<u32>,
+    /// Emit PhaseCountdown events for each second left in a phase
+    phase_countdown: bool,
+    /// Binaural state the app is currently playing, reported by the audio layer
+    binaural_state: Option<FfiBrainWaveState>,
+    /// Minimum spacing of tick-driven state publishes; None publishes every tick
+    state_publish_interval: Option<Duration>,
+    last_state_publish: Option<Instant>,
     current_pattern_id: String,
     session: Option<SessionState>,
     last_timestamp_us: i64,
     status: FfiRuntimeStatus,
+    /// End sessions once the pattern's recommended cycles are done
+    auto_complete: bool,
+    /// Tempo currently applied to the pacer; ramps toward `tempo_target`
     tempo_scale: f32,
+    tempo_target: f32,
     safety_locked: bool,
-    last_resonance: f32,
+    resonance: ResonanceTracker,
+    adherence: AdherenceTracker,
+    /// Pattern time elapsed in the current session, the group sync timeline
+    timeline_us: i64,
+    preparation_config: FfiPreparationConfig,
+    preparation: Option<Preparation>,
+    /// Warm-up and cool-down declared for new sessions
+    stages_config: FfiSessionStagesConfig,
+    /// Goals for the session being started, taken when it begins
+    pending_goals: FfiSessionGoals,
+    /// Sleep wind-down for the session being started
+    pending_sleep: Option<FfiSleepConfig>,
+    /// Pattern animation outside of any session
+    preview: Option<PatternPreview>,
+    /// Pacer that adapts to the user's own breathing rate
+    follow_me: Option<FollowMe>,
+    /// Pattern sequence for the next (or current) session
+    sequence: Option<SequenceRun>,
+    breath_rate: BreathRateEstimator,
+    /// Free-breathing observation in progress (no pacer, no session)
+    observation: Option<BaselineObservation>,
+    /// Transition still easing the pacer toward the new pattern
+    morph: Option<PatternMorph>,
+    /// Cycles over which a mid-session load morphs into the new pattern (0 switches at once)
+    transition_cycles: u32,
+}
+
+/// Cycle-by-cycle blend from one pattern's timing to another's
+#[cfg(feature = "full")]
+struct PatternMorph {
+    from: BreathPattern,
+    to: BreathPattern,
+    step: u32,
+    /// Cycles the whole transition takes
+    cycles: u32,
+}
+
+#[cfg(feature = "full")]
+impl PatternMorph {
+    /// Timing the pacer is currently running
+    fn current(&self) -> BreathPattern {
+        blend_patterns(&self.from, &self.to, self.step as f32 / self.cycles as f32)
+    }
+}
+
+#[cfg(feature = "full")]
+impl RuntimeInner {
+    /// True while a sleep session winds down; visual events are suppressed
+    fn winding_down(&self) -> bool {
+        self.session.as_ref().is_some_and(|s| s.sleep.is_some())
+    }
+
+    /// Pacing is over and natural breathing is being observed
+    fn cooling_down(&self) -> bool {
+        self.session.as_ref().is_some_and(|s| s.stages.stage == FfiSessionStage::CoolDown)
+    }
+
+    /// Tempo applied to the pacer, including the warm-up's slower start
+    fn pacer_scale(&self) -> f32 {
+        let warm_up = self.session.as_ref().map_or(1.0, |s| s.stages.tempo_factor());
+        (self.tempo_scale * warm_up).clamp(TEMPO_MIN, TEMPO_MAX)
+    }
+
+    /// User interaction or motion: restart the sleep idle timer
+    fn note_activity(&mut self) {
+        if let Some(sleep) = self.session.as_mut().and_then(|s| s.sleep.as_mut()) {
+            sleep.idle_sec = 0.0;
+        }
+    }
+
+    /// Replace the pacer, optionally keeping the running cycle count
+    fn install_pacer(&mut self, durations: PhaseDurations, keep_cycle: bool) {
+        let cycle = self.phase_machine.cycle_index;
+        self.morph = None;
+        self.phase_clock = PhaseClock::new(&durations);
+        self.phase_machine = PhaseMachine::new(durations);
+        if keep_cycle {
+            self.phase_machine.cycle_index = cycle;
+        }
+    }
+
+    /// Advance the pacer and its phase clock together
+    fn advance_pacer(&mut self, dt_us: u64) {
+        let before = FfiPhase::from(self.phase_machine.phase);
+        self.phase_machine.tick(dt_us);
+        let after = FfiPhase::from(self.phase_machine.phase);
+        self.phase_clock.advance(before, after, dt_us);
+    }
+
+    /// Lung volume the pacer is guiding, 0 empty to 1 full; None outside a running session
+    fn paced_volume(&self) -> Option<f32> {
+        if self.status != FfiRuntimeStatus::Running {
+            return None;
+        }
+        let phase = FfiPhase::from(self.phase_machine.phase);
+        let duration_us = self.phase_clock.durations_us[PhaseClock::index(phase)];
+        let progress = if duration_us > 0 {
+            (self.phase_clock.elapsed_us as f32 / duration_us as f32).min(1.0)
+        } else {
+            1.0
+        };
+        let ramp = 0.5 - 0.5 * (progress * std::f32::consts::PI).cos();
+        Some(match phase {
+            FfiPhase::Inhale => ramp,
+            FfiPhase::HoldIn => 1.0,
+            FfiPhase::Exhale => 1.0 - ramp,
+            FfiPhase::HoldOut | FfiPhase::Prepare => 0.0,
+        })
+    }
+
+    /// Breaths per minute the pacer currently prescribes, tempo and warm-up included
+    fn pacer_rate_bpm(&self) -> Option<f32> {
+        self.prescribed_rate_bpm().map(|rate| rate / self.pacer_scale())
+    }
+
+    fn time_to_next_phase_ms(&self) -> u32 {
+        if let Some(prep) = &self.preparation {
+            return (prep.remaining_sec() * 1000.0) as u32;
+        }
+        let phase = FfiPhase::from(self.phase_machine.phase);
+        let remaining_us = self.phase_clock.remaining_us(phase) as f64 * self.pacer_scale() as f64;
+        (remaining_us / 1000.0).min(u32::MAX as f64) as u32
+    }
+
+    /// Phase and progress shown to frontends: the countdown stands in for
+    /// the pacer while Preparing
+    fn display_phase(&self) -> (FfiPhase, f32) {
+        match &self.preparation {
+            Some(prep) => (FfiPhase::Prepare, prep.progress()),
+            None => (FfiPhase::from(self.phase_machine.phase), self.phase_machine.cycle_phase_norm()),
+        }
+    }
+
+    /// Breathing rate of the installed pacer
+    fn prescribed_rate_bpm(&self) -> Option<f32> {
+        let cycle_us: u64 = self.phase_clock.durations_us.iter().sum();
+        (cycle_us > 0).then(|| 60_000_000.0 / cycle_us as f32)
+    }
+}
+
+/// Tempo scale bounds (tempo_bounds spec); above 1.0 breathes slower
+const TEMPO_MIN: f32 = 0.8;
+const TEMPO_MAX: f32 = 1.4;
+/// Fastest tempo change the pacer applies (tempo_rate_limit spec)
+const TEMPO_RAMP_PER_SEC: f32 = 0.1;
+
+/// Step `current` toward `target` no faster than the tempo rate limit
+fn ramp_tempo(current: f32, target: f32, dt_sec: f32) -> f32 {
+    let max_step = TEMPO_RAMP_PER_SEC * dt_sec.max(0.0);
+    current + (target - current).clamp(-max_step, max_step)
+}
+
+/// Pacer time for `dt_us` of wall time at the given tempo
+fn scaled_phase_dt(dt_us: u64, tempo_scale: f32) -> u64 {
+    (dt_us as f64 / tempo_scale.clamp(TEMPO_MIN, TEMPO_MAX) as f64) as u64
+}
+
+#[cfg(test)]
+mod tempo_tests {
+    use super::*;
+
+    #[test]
+    fn ramp_is_rate_limited_both_ways() {
+        assert!((ramp_tempo(1.0, 1.4, 1.0) - 1.1).abs() < 1e-6);
+        assert!((ramp_tempo(1.0, 0.8, 0.5) - 0.95).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ramp_lands_on_target_without_overshoot() {
+        let mut tempo = 1.0;
+        for _ in 0..100 {
+            tempo = ramp_tempo(tempo, 1.25, 0.1);
+            assert!(tempo <= 1.25);
+        }
+        assert_eq!(tempo, 1.25);
+        assert_eq!(ramp_tempo(1.25, 1.25, 1.0), 1.25);
+    }
+
+    #[test]
+    fn ramp_ignores_negative_dt() {
+        assert_eq!(ramp_tempo(1.0, 1.4, -1.0), 1.0);
+    }
+
+    #[test]
+    fn phase_dt_scales_and_clamps_tempo() {
+        assert_eq!(scaled_phase_dt(1_000_000, 1.0), 1_000_000);
+        assert_eq!(scaled_phase_dt(1_250_000, 1.25), 1_000_000);
+        assert_eq!(scaled_phase_dt(1_000_000, 3.0), scaled_phase_dt(1_000_000, TEMPO_MAX));
+        assert_eq!(scaled_phase_dt(1_000_000, 0.1), scaled_phase_dt(1_000_000, TEMPO_MIN));
+    }
 }
 
+/// Reply fields are skipped when recorded; replayed commands get a reply
+/// channel nobody listens on.
+#[cfg(feature = "full")]
+#[derive(Serialize, Deserialize)]
 enum RuntimeCommand {
-    StartSession,
-    StopSession(Sender<FfiSessionStats>), // Return channel for sync response
+    StartSession(FfiSessionGoals),
+    StartSleepSession(FfiSleepConfig),
+    StartPanicSession,
+    ReportActivity,
+    StopSession(#[serde(skip, default = "detached_reply")] Sender<FfiSessionStats>), // Return channel for sync response
     PauseSession,
+    Interrupt(FfiInterruptionKind),
     ResumeSession,
     LoadPattern(String),
+    LoadSequence(Vec<FfiSequenceSegment>),
     ProcessFrame {
         r: f32,
         g: f32,
         b: f32,
         timestamp_us: i64,
     },
+    ProcessFrames(Vec<FfiRgbSample>),
     Tick {
         dt_sec: f32,
         timestamp_us: i64,
     },
-    ResetSafetyLock,
+    ResetSafetyLock {
+        reason: String,
+        #[serde(skip, default = "detached_reply")]
+        reply_tx: Sender<Result<(), ZenOneError>>,
+    },
+    AckViolation {
+        id: u64,
+        note: Option<String>,
+        #[serde(skip, default = "detached_reply")]
+        reply_tx: Sender<Result<(), ZenOneError>>,
+    },
     AdjustTempo(f32),
     UpdateContext {
         local_hour: u8,
@@ -510,83 +1354,363 @@ enum RuntimeCommand {
         recent_sessions: u16,
     },
     EmergencyHalt(String),
+    RestoreSafetyLock,
     UpdateConfig(String),
+    RespirationSample {
+        value: f32,
+        source: FfiRespirationSource,
+        timestamp_us: i64,
+    },
+    SetArcPlanner(bool),
+    SetAutoComplete(bool),
+    SetPreparationConfig(FfiPreparationConfig),
+    SkipPreparation,
+    SetSessionStages(FfiSessionStagesConfig),
+    BeginCoolDown(#[serde(skip, default = "detached_reply")] Sender<Result<(), ZenOneError>>),
+    PreviewPattern { id: String, cycles: u32 },
+    SetFollowMe { enabled: bool, target_rate_bpm: f32 },
+    SetPhaseWarningLead(Option<u32>),
+    SetTransitionCycles(u32),
+    SetPhaseCountdown(bool),
+    /// Reply once every earlier command and its signal results are applied
+    Flush(#[serde(skip, default = "detached_reply")] Sender<()>),
+    /// Save any active session, stop the signal actor and exit the loop
+    Shutdown,
+    SetStatePublishInterval(Option<Duration>),
+    SetHrFilter(FfiHrFilterConfig),
+    SetRppgMethod(FfiRppgMethod),
+    SetBinauralState(Option<FfiBrainWaveState>),
+    ReloadPattern,
+    /// Republish the belief after custom modes were registered or removed
+    BeliefModesChanged,
+    StartObservation {
+        duration_sec: f32,
+        #[serde(skip, default = "detached_reply")]
+        reply_tx: Sender<Result<(), ZenOneError>>,
+    },
+    StopObservation(#[serde(skip, default = "detached_reply")] Sender<Result<FfiBreathingBaseline, ZenOneError>>),
+    ConfigureRppg { enabled: bool, window_samples: usize, sample_rate_hz: f32 },
+    SetRppgWindow { window_samples: usize, sample_rate_hz: f32 },
+    StartCalibration {
+        device_id: String,
+        reference: FfiCalibrationReference,
+        #[serde(skip, default = "detached_reply")]
+        reply_tx: Sender<Result<(), ZenOneError>>,
+    },
+    ManualHrReference {
+        bpm: f32,
+        duration_sec: f32,
+        #[serde(skip, default = "detached_reply")]
+        reply_tx: Sender<Result<(), ZenOneError>>,
+    },
+    FinishCalibration(#[serde(skip, default = "detached_reply")] Sender<Result<FfiRppgCalibration, ZenOneError>>),
+    SetRppgCalibration(Option<FfiRppgCalibration>),
+    StartNoiseProfiling {
+        duration_sec: f32,
+        #[serde(skip, default = "detached_reply")]
+        reply_tx: Sender<Result<(), ZenOneError>>,
+    },
+    SetAudioThreshold(Option<f32>),
+    StopPreview,
+    HrSample {
+        bpm: f32,
+        rr_intervals_ms: Vec<f32>,
+        timestamp_us: i64,
+    },
+    ManualHrSample {
+        bpm: f32,
+        timestamp_us: i64,
+    },
+    SimulatedHrSample {
+        bpm: f32,
+        rr_intervals_ms: Vec<f32>,
+        timestamp_us: i64,
+    },
+    SetHrSource(FfiHrSourceKind),
+    SetHrSimulator(Option<FfiHrSimulatorConfig>),
+    /// A recorded signal result, fed back in during replay
+    InjectSignal(SignalEvent),
 }
 
 /// Commands for the Signal Processing Actor
+#[cfg(feature = "full")]
 enum SignalCommand {
-    ProcessSample {
-        r: f32,
-        g: f32,
-        b: f32,
-        timestamp_us: i64,
+    Ingest(HrInput),
+    SetSource(FfiHrSourceKind),
+    Configure {
+        window_samples: usize,
+        sample_rate_hz: f32,
     },
+    SetMethod(FfiRppgMethod),
+    SetCalibration(Option<FfiRppgCalibration>),
+    SetHrFilter(FfiHrFilterConfig),
+    /// Welch spectrum of an inter-beat interval series
+    AnalyzeHrv(Vec<f32>),
+    /// Clear every source's history; sent whenever a session starts
     Reset,
+    /// Reply once every earlier sample has been processed
+    Flush(Sender<()>),
+    Shutdown,
+}
+
+/// Reply channel for a deserialized command; the receiver is already gone
+#[cfg(feature = "full")]
+fn detached_reply<T>() -> Sender<T> {
+    crossbeam_channel::bounded(1).0
 }
 
 /// Events from the Signal Processing Actor
+#[cfg(feature = "full")]
+#[derive(Serialize, Deserialize)]
 enum SignalEvent {
-    Result {
-        hr: f32,
-        confidence: f32,
+    /// Readings from sources other than the selected one still arrive, for
+    /// camera calibration against a strap, but don't reach the session
+    HeartRate {
+        reading: HrReading,
+        selected: bool,
+    },
+    /// Inter-beat interval from the selected source
+    Beat {
+        ibi_ms: f32,
+        source: FfiHrSourceKind,
+        timestamp_us: i64,
+    },
+    /// The strap went quiet for longer than interpolation bridges
+    HeartRateGap {
+        gap_ms: u32,
+        timestamp_us: i64,
+    },
+    HrvSpectrum(FfiHrvSpectrum),
+    /// Breathing signal recovered from the pulse waveform, one per beat
+    Respiration {
+        value: f32,
         timestamp_us: i64,
     },
 }
 
+/// Restarts allowed per actor thread before the supervisor gives up
+#[cfg(feature = "full")]
+const MAX_ACTOR_RESTARTS: u32 = 5;
+
+/// Readable reason from a caught panic payload
+#[cfg(feature = "full")]
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".into())
+}
+
 /// Actor for heavy signal processing (DSP/Vision)
+#[cfg(feature = "full")]
 struct SignalActor {
-    rppg: RppgProcessor,
+    camera: CameraSource,
+    strap: StrapSource,
+    manual: ManualSource,
+    simulated: SimulatedSource,
+    // Source whose readings reach the session
+    selected: FfiHrSourceKind,
     cmd_rx: Receiver<SignalCommand>,
     event_tx: Sender<SignalEvent>,
 }
 
+#[cfg(feature = "full")]
 impl SignalActor {
-    fn run(mut self) {
+    /// Thread entry point: runs the actor, restarting it with a fresh
+    /// processor (same configuration and calibration) if it panics
+    fn supervise(mut self, events: Arc<EventQueue>) {
         log::info!("SignalActor: Thread started");
-        while let Ok(cmd) = self.cmd_rx.recv() {
-            match cmd {
-                SignalCommand::ProcessSample { r, g, b, timestamp_us } => {
-                    self.rppg.add_sample(r, g, b);
-                    if let Some((bpm, conf)) = self.rppg.process() {
-                        let _ = self.event_tx.send(SignalEvent::Result {
-                            hr: bpm,
-                            confidence: conf,
-                            timestamp_us,
-                        });
-                    }
-                }
-                SignalCommand::Reset => {
-                    self.rppg.reset();
-                }
+        let mut restarts = 0;
+        while let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| self.run())) {
+            let reason = panic_message(&*payload);
+            if restarts >= MAX_ACTOR_RESTARTS {
+                log::error!("SignalActor: Panicked ({}), giving up after {} restarts", reason, restarts);
+                return;
             }
+            restarts += 1;
+            log::error!("SignalActor: Panicked ({}), restarting ({}/{})", reason, restarts, MAX_ACTOR_RESTARTS);
+            self.camera.rebuild_pipeline();
+            events.push(FfiRuntimeEvent::RuntimeRecovered { actor: FfiActorKind::Signal, reason, restarts });
         }
         log::info!("SignalActor: Thread stopped");
     }
-}
 
-/// Actor that runs the engine loop on a dedicated thread
-struct RuntimeActor {
-    inner: RuntimeInner,
-    // rppg: RppgProcessor, // MOVED TO SignalActor
-    signal_tx: Sender<SignalCommand>,
-    signal_rx: Receiver<SignalEvent>,
-    
-    cmd_rx: Receiver<RuntimeCommand>,
-    state_tx: Arc<RwLock<FfiRuntimeState>>,
-    // We also keep a cached FfiFrame for process_frame return
-    latest_frame: Arc<RwLock<FfiFrame>>,
-    // Safety Monitor for LTL verification
-    safety: SafetyMonitor,
-}
+    fn source_mut(&mut self, kind: FfiHrSourceKind) -> &mut dyn HeartRateSource {
+        match kind {
+            FfiHrSourceKind::Strap => &mut self.strap,
+            FfiHrSourceKind::Manual => &mut self.manual,
+            FfiHrSourceKind::Simulated => &mut self.simulated,
+            FfiHrSourceKind::Auto | FfiHrSourceKind::Camera => &mut self.camera,
+        }
+    }
+
+    /// Whether `kind` is the selected source for input at `timestamp_us`.
+    /// Auto prefers a streaming strap and falls back to the camera.
+    fn is_selected(&self, kind: FfiHrSourceKind, timestamp_us: i64) -> bool {
+        match self.selected {
+            FfiHrSourceKind::Auto => match kind {
+                FfiHrSourceKind::Strap => true,
+                FfiHrSourceKind::Camera => !self.strap.is_streaming(timestamp_us),
+                _ => false,
+            },
+            selected => kind == selected,
+        }
+    }
+
+    fn ingest(&mut self, input: HrInput) {
+        let kind = input.source();
+        let selected = self.is_selected(kind, input.timestamp_us());
+        let mut produced = Vec::new();
+        self.source_mut(kind).push(&input, &mut produced);
+        for event in produced {
+            let event = match event {
+                SignalEvent::HeartRate { reading, .. } => SignalEvent::HeartRate { reading, selected },
+                SignalEvent::Beat { .. } | SignalEvent::HeartRateGap { .. } if !selected => continue,
+                event => event,
+            };
+            let _ = self.event_tx.send(event);
+        }
+    }
+
+    fn run(&mut self) {
+        while let Ok(cmd) = self.cmd_rx.recv() {
+            match cmd {
+                SignalCommand::Ingest(input) => self.ingest(input),
+                SignalCommand::SetSource(kind) => {
+                    if kind != self.selected {
+                        log::info!("SignalActor: Heart rate source {:?} -> {:?}", self.selected, kind);
+                        self.selected = kind;
+                    }
+                }
+                SignalCommand::Configure { window_samples, sample_rate_hz } => {
+                    self.camera.configure(window_samples, sample_rate_hz);
+                }
+                SignalCommand::SetMethod(method) => {
+                    // Pulse history from the old method is not comparable
+                    self.camera.method = method;
+                    self.camera.rebuild_pipeline();
+                }
+                SignalCommand::SetCalibration(calibration) => self.camera.calibration = calibration,
+                SignalCommand::SetHrFilter(config) => self.camera.hr_filter.set_config(config),
+                SignalCommand::AnalyzeHrv(intervals_ms) => {
+                    if let Some(spectrum) = hrv_spectrum(&intervals_ms) {
+                        let _ = self.event_tx.send(SignalEvent::HrvSpectrum(spectrum));
+                    }
+                }
+                SignalCommand::Reset => {
+                    self.camera.reset();
+                    self.strap.reset();
+                    self.manual.reset();
+                    self.simulated.reset();
+                }
+                SignalCommand::Flush(reply_tx) => {
+                    let _ = reply_tx.send(());
+                }
+                SignalCommand::Shutdown => break,
+            }
+        }
+    }
+}
+
+/// Actor that runs the engine loop on a dedicated thread
+#[cfg(feature = "full")]
+struct RuntimeActor {
+    inner: RuntimeInner,
+    // rppg: RppgProcessor, // MOVED TO SignalActor
+    signal_tx: Sender<SignalCommand>,
+    signal_rx: Receiver<SignalEvent>,
+    signal_thread: Option<thread::JoinHandle<()>>,
+    
+    cmd_rx: Receiver<RuntimeCommand>,
+    state_tx: Arc<ArcSwap<FfiRuntimeState>>,
+    // Publish sequence and per-field change stamps for state deltas
+    state_stamps: Arc<ArcSwap<StateStamps>>,
+    // We also keep a cached FfiFrame for process_frame return
+    latest_frame: Arc<ArcSwap<FfiFrame>>,
+    frame_lite: Arc<ArcSwap<FfiFrameLite>>,
+    // Safety Monitor for LTL verification
+    safety: Arc<SafetyMonitor>,
+    // Completed sessions
+    store: Arc<SessionStore>,
+    // Consent-gated raw signal logging
+    research: Arc<ResearchRecorder>,
+    // Outbound events for the UI/voice layer
+    events: Arc<EventQueue>,
+    // Pushed phase/cycle/status changes, and what was last pushed
+    listener: SharedListener,
+    notified: ListenerMark,
+    coaching: CoachingEngine,
+    // Belief-adaptive pattern transitions
+    planner: ArcPlanner,
+    // Shared-clock group breathing
+    group: Arc<GroupSync>,
+    // Pacer line output for DIY hardware
+    serial: Arc<SerialPacer>,
+    // Rolling time-domain HRV over camera beats or wearable RR
+    hrv: HrvWindow,
+    // Breathing rate from the camera pulse alone
+    ppg_breath_rate: BreathRateEstimator,
+    // Details of the last camera reading (None while another source supplies HR)
+    camera_hr: Option<CameraHeartRate>,
+    // Last surfaced heart rate and its signal quality, repeated on tick frames
+    last_hr: Option<(Option<f32>, f32, Instant)>,
+    // Built-in heart rate generator, run on ticks while started
+    hr_simulator: Option<HrSimulator>,
+    // Audit trail, and why and when the safety lock engaged
+    journal: Arc<SafetyJournal>,
+    hr_zones: Arc<Mutex<FfiHrZoneConfig>>,
+    breath_rate_band: Arc<Mutex<FfiBreathRateBand>>,
+    // Off on devices without a usable camera
+    rppg_enabled: bool,
+    // Last free-breathing baseline, shared with the runtime
+    baseline: Arc<Mutex<Option<FfiBreathingBaseline>>>,
+    patterns: Arc<PatternLibrary>,
+    // Custom modes projected onto every published belief
+    belief_modes: Arc<BeliefModeRegistry>,
+    adjustments: Arc<PatternAdjustments>,
+    escalation: Arc<EscalationPolicy>,
+    // rPPG calibration in progress and the saved per-device results
+    calibration: Option<CalibrationRun>,
+    calibrations: Arc<RppgCalibrationStore>,
+    // Ambient noise measurement in progress, the last result, and the envelope
+    // level audio must exceed (None keeps microphone breath detection off)
+    noise_profiling: Option<NoiseProfiling>,
+    noise_profile: Arc<Mutex<Option<FfiNoiseProfile>>>,
+    audio_threshold: Option<f32>,
+    flags: Arc<FeatureFlags>,
+    clock: Arc<dyn Clock>,
+    // Command/signal recording for debugging and replay
+    recorder: Arc<CommandRecorder>,
+}
 
+#[cfg(feature = "full")]
 impl RuntimeActor {
-    fn run(mut self) {
+    /// Thread entry point: runs the actor loop and, if it panics, restores
+    /// from the last published snapshot and re-enters it
+    fn supervise(mut self) {
         log::info!("RuntimeActor: Thread started");
-        
+        let mut restarts = 0;
+        while let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| self.run())) {
+            let reason = panic_message(&*payload);
+            if restarts >= MAX_ACTOR_RESTARTS {
+                log::error!("RuntimeActor: Panicked ({}), giving up after {} restarts", reason, restarts);
+                break;
+            }
+            restarts += 1;
+            log::error!("RuntimeActor: Panicked ({}), restarting ({}/{})", reason, restarts, MAX_ACTOR_RESTARTS);
+            self.recover(reason, restarts);
+        }
+        self.shutdown();
+        log::info!("RuntimeActor: Thread stopped");
+    }
+
+    fn run(&mut self) {
         // Main Actor Loop - Multiplexing UI commands and Signal events
         loop {
             select! {
                 recv(self.cmd_rx) -> msg => match msg {
+                    Ok(RuntimeCommand::Shutdown) => break,
                     Ok(cmd) => self.handle_command(cmd),
                     Err(_) => break, // Channel closed, exit
                 },
@@ -595,124 +1719,483 @@ impl RuntimeActor {
                     Err(_) => {
                         log::error!("SignalActor channel closed unexpectedly");
                         // We can continue running, just without signals
+                        self.signal_rx = crossbeam_channel::never();
                     }
                 }
             }
             // After every event, we ensure the shared state is updated
             // (Though individual handlers do it more granularly)
+            self.notify_listener();
         }
-        log::info!("RuntimeActor: Thread stopped");
+    }
+
+    /// Rebuild the engine and pacer from the last published snapshot after a
+    /// panic. An in-flight session is kept but paused, so the user resumes it.
+    fn recover(&mut self, reason: String, restarts: u32) {
+        let snapshot = self.state_tx.load_full();
+        self.inner.engine = Engine::new(6.0);
+        self.inner.preview = None;
+        self.inner.preparation = None;
+        if let Some(pattern) = self.pattern(&snapshot.pattern_id) {
+            self.inner.install_pacer(pattern.to_phase_durations(), false);
+            self.inner.current_pattern_id = snapshot.pattern_id.clone();
+        }
+        self.inner.phase_machine.cycle_index = snapshot.cycles_completed;
+        self.inner.tempo_scale = snapshot.tempo_scale;
+        self.inner.tempo_target = snapshot.tempo_scale;
+        self.inner.safety_locked = snapshot.safety.is_locked;
+
+        let in_session = self.inner.session.is_some();
+        self.inner.status = match snapshot.status {
+            FfiRuntimeStatus::SafetyLock => FfiRuntimeStatus::SafetyLock,
+            FfiRuntimeStatus::Preparing | FfiRuntimeStatus::Running | FfiRuntimeStatus::Paused if in_session => {
+                if let Some(session) = &mut self.inner.session {
+                    session.interrupted.get_or_insert((FfiInterruptionKind::Other, self.clock.now()));
+                }
+                FfiRuntimeStatus::Paused
+            }
+            _ => FfiRuntimeStatus::Idle,
+        };
+
+        self.events.push(FfiRuntimeEvent::RuntimeRecovered { actor: FfiActorKind::Runtime, reason, restarts });
+        self.update_shared_state();
+    }
+
+    /// Save an in-flight session as partial, drop queued commands (their
+    /// callers see a closed reply channel) and join the signal actor
+    fn shutdown(&mut self) {
+        if self.inner.session.is_some() {
+            self.finalize_session(true);
+        }
+        let dropped = self.cmd_rx.try_iter().count();
+        if dropped > 0 {
+            log::warn!("RuntimeActor: Dropped {} commands queued behind shutdown", dropped);
+        }
+        let _ = self.signal_tx.send(SignalCommand::Shutdown);
+        if let Some(handle) = self.signal_thread.take() {
+            let _ = handle.join();
+        }
+        self.signal_rx.try_iter().for_each(drop);
     }
 
     fn handle_command(&mut self, cmd: RuntimeCommand) {
+        self.recorder.record_command(&cmd, self.clock.now());
         match cmd {
-            RuntimeCommand::StartSession => self.handle_start(),
+            RuntimeCommand::StartSession(goals) => self.handle_start(goals),
+            RuntimeCommand::StartSleepSession(config) => {
+                self.inner.pending_sleep = Some(config);
+                self.handle_start(FfiSessionGoals::default());
+            }
+            RuntimeCommand::StartPanicSession => self.handle_start_panic(),
+            RuntimeCommand::ReportActivity => self.inner.note_activity(),
             RuntimeCommand::StopSession(reply_tx) => self.handle_stop(reply_tx),
-            RuntimeCommand::PauseSession => self.handle_pause(),
+            RuntimeCommand::PauseSession => self.handle_pause(FfiInterruptionKind::UserPause),
+            RuntimeCommand::Interrupt(kind) => self.handle_pause(kind),
             RuntimeCommand::ResumeSession => self.handle_resume(),
             RuntimeCommand::LoadPattern(id) => self.handle_load_pattern(id),
+            RuntimeCommand::LoadSequence(segments) => self.handle_load_sequence(segments),
             RuntimeCommand::ProcessFrame { r, g, b, timestamp_us } => {
                 self.handle_process_frame(r, g, b, timestamp_us);
             }
+            RuntimeCommand::ProcessFrames(samples) => {
+                for sample in samples {
+                    self.handle_process_frame(sample.r, sample.g, sample.b, sample.timestamp_us);
+                }
+            }
             RuntimeCommand::Tick { dt_sec, timestamp_us } => {
                 self.handle_tick(dt_sec, timestamp_us);
             }
-            RuntimeCommand::ResetSafetyLock => self.handle_reset_safety_lock(),
+            RuntimeCommand::ResetSafetyLock { reason, reply_tx } => {
+                let _ = reply_tx.send(self.handle_reset_safety_lock(reason));
+            }
+            RuntimeCommand::AckViolation { id, note, reply_tx } => {
+                let _ = reply_tx.send(self.handle_ack_violation(id, note));
+            }
             RuntimeCommand::AdjustTempo(scale) => self.handle_adjust_tempo(scale),
             RuntimeCommand::UpdateContext { local_hour, is_charging, recent_sessions } => {
                     self.handle_update_context(local_hour, is_charging, recent_sessions);
             }
             RuntimeCommand::EmergencyHalt(reason) => self.handle_emergency_halt(reason),
+            RuntimeCommand::RestoreSafetyLock => self.handle_restore_safety_lock(),
+            RuntimeCommand::RespirationSample { value, source, timestamp_us } => {
+                self.handle_respiration_sample(value, source, timestamp_us);
+            }
+            RuntimeCommand::SetArcPlanner(enabled) => self.planner.set_enabled(enabled),
+            RuntimeCommand::SetAutoComplete(enabled) => self.inner.auto_complete = enabled,
+            RuntimeCommand::SetPreparationConfig(config) => self.inner.preparation_config = config,
+            RuntimeCommand::PreviewPattern { id, cycles } => self.handle_preview_pattern(id, cycles),
+            RuntimeCommand::StopPreview => {
+                self.inner.preview = None;
+                self.update_latest_frame();
+            }
+            RuntimeCommand::SkipPreparation => self.handle_skip_preparation(),
+            RuntimeCommand::SetSessionStages(config) => self.inner.stages_config = config,
+            RuntimeCommand::BeginCoolDown(reply_tx) => {
+                let _ = reply_tx.send(self.handle_begin_cool_down());
+            }
+            RuntimeCommand::SetPhaseWarningLead(lead_ms) => self.inner.phase_warning_lead_ms = lead_ms,
+            RuntimeCommand::SetTransitionCycles(cycles) => self.inner.transition_cycles = cycles,
+            RuntimeCommand::SetPhaseCountdown(enabled) => self.inner.phase_countdown = enabled,
+            RuntimeCommand::SetStatePublishInterval(interval) => self.inner.state_publish_interval = interval,
+            RuntimeCommand::SetBinauralState(state) => self.inner.binaural_state = state,
+            RuntimeCommand::ReloadPattern => self.handle_reload_pattern(),
+            RuntimeCommand::BeliefModesChanged => self.update_shared_state(),
+            RuntimeCommand::StartObservation { duration_sec, reply_tx } => {
+                let _ = reply_tx.send(self.handle_start_observation(duration_sec));
+            }
+            RuntimeCommand::StopObservation(reply_tx) => {
+                let _ = reply_tx.send(self.handle_stop_observation());
+            }
+            RuntimeCommand::InjectSignal(event) => self.handle_signal_event(event),
+            RuntimeCommand::Flush(reply_tx) => {
+                self.drain_signal_actor();
+                let _ = reply_tx.send(());
+            }
+            RuntimeCommand::ConfigureRppg { enabled, window_samples, sample_rate_hz } => {
+                self.rppg_enabled = enabled;
+                let _ = self.signal_tx.send(SignalCommand::Configure { window_samples, sample_rate_hz });
+            }
+            RuntimeCommand::SetRppgWindow { window_samples, sample_rate_hz } => {
+                let _ = self.signal_tx.send(SignalCommand::Configure { window_samples, sample_rate_hz });
+            }
+            RuntimeCommand::StartCalibration { device_id, reference, reply_tx } => {
+                let _ = reply_tx.send(self.handle_start_calibration(device_id, reference));
+            }
+            RuntimeCommand::ManualHrReference { bpm, duration_sec, reply_tx } => {
+                let result = match &mut self.calibration {
+                    Some(run) => run.add_manual(bpm, duration_sec),
+                    None => Err(ZenOneError::ConfigError("No calibration in progress".into())),
+                };
+                let _ = reply_tx.send(result);
+            }
+            RuntimeCommand::FinishCalibration(reply_tx) => {
+                let _ = reply_tx.send(self.handle_finish_calibration());
+            }
+            RuntimeCommand::SetRppgCalibration(calibration) => {
+                let _ = self.signal_tx.send(SignalCommand::SetCalibration(calibration));
+            }
+            RuntimeCommand::SetHrFilter(config) => {
+                let _ = self.signal_tx.send(SignalCommand::SetHrFilter(config));
+            }
+            RuntimeCommand::SetRppgMethod(method) => {
+                let _ = self.signal_tx.send(SignalCommand::SetMethod(method));
+                self.ppg_breath_rate.reset();
+                self.camera_hr = None;
+            }
+            RuntimeCommand::StartNoiseProfiling { duration_sec, reply_tx } => {
+                let _ = reply_tx.send(self.handle_start_noise_profiling(duration_sec));
+            }
+            RuntimeCommand::SetAudioThreshold(threshold) => self.audio_threshold = threshold,
+            RuntimeCommand::SetFollowMe { enabled, target_rate_bpm } => {
+                self.handle_set_follow_me(enabled, target_rate_bpm);
+            }
+            RuntimeCommand::HrSample { bpm, rr_intervals_ms, timestamp_us } => {
+                let _ = self.signal_tx.send(SignalCommand::Ingest(HrInput::Strap { bpm, rr_intervals_ms, timestamp_us }));
+            }
+            RuntimeCommand::ManualHrSample { bpm, timestamp_us } => {
+                let _ = self.signal_tx.send(SignalCommand::Ingest(HrInput::Manual { bpm, timestamp_us }));
+            }
+            RuntimeCommand::SimulatedHrSample { bpm, rr_intervals_ms, timestamp_us } => {
+                let input = HrInput::Simulated { bpm, rr_intervals_ms, timestamp_us };
+                let _ = self.signal_tx.send(SignalCommand::Ingest(input));
+            }
+            RuntimeCommand::SetHrSimulator(config) => self.hr_simulator = config.map(HrSimulator::new),
+            RuntimeCommand::SetHrSource(kind) => {
+                self.camera_hr = None;
+                let _ = self.signal_tx.send(SignalCommand::SetSource(kind));
+            }
             _ => {}
         }
     }
 
+    /// Wait for the signal actor to catch up, then apply its pending results
+    fn drain_signal_actor(&mut self) {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        if self.signal_tx.send(SignalCommand::Flush(reply_tx)).is_ok() {
+            let _ = reply_rx.recv();
+        }
+        while let Ok(event) = self.signal_rx.try_recv() {
+            self.handle_signal_event(event);
+        }
+    }
+
     fn handle_signal_event(&mut self, event: SignalEvent) {
+        self.recorder.record_signal(&event, self.clock.now());
         match event {
-            SignalEvent::Result { hr, confidence, timestamp_us: _ } => {
-                // Update internal HR state
-                // Note: We might want to filter or smooth this before state update
-                // For now, raw update as per legacy behavior
-                if let Some(session) = &mut self.inner.session {
-                    session.hr_samples.push(hr);
+            SignalEvent::HeartRate { reading, selected } => {
+                // Calibration compares the camera with a reference whichever is selected
+                if let Some(run) = &mut self.calibration {
+                    match reading.camera {
+                        Some(camera) => run.observe_camera(camera.raw_hr, camera.raw_confidence, reading.timestamp_us),
+                        None if reading.source == FfiHrSourceKind::Strap && !reading.interpolated => {
+                            run.observe_strap(reading.hr, reading.timestamp_us);
+                        }
+                        None => {}
+                    }
+                }
+                if selected {
+                    self.camera_hr = reading.camera;
+                    self.apply_heart_rate(reading.hr, reading.confidence, reading.timestamp_us);
+                }
+            }
+            SignalEvent::Beat { ibi_ms, source, timestamp_us } => {
+                let source = if source == FfiHrSourceKind::Camera { BeatSource::Camera } else { BeatSource::Wearable };
+                if source == BeatSource::Wearable {
+                    if let Some(session) = &mut self.inner.session {
+                        session.rr_intervals_ms.push(ibi_ms);
+                    }
+                }
+                self.apply_beat(ibi_ms, source, timestamp_us);
+            }
+            SignalEvent::HeartRateGap { gap_ms, timestamp_us } => {
+                log::warn!("HR stream gap of {} ms, not interpolating", gap_ms);
+                self.events.push(FfiRuntimeEvent::HeartRateGap { gap_ms, timestamp_us });
+            }
+            SignalEvent::HrvSpectrum(spectrum) => {
+                self.hrv.spectrum = Some(spectrum);
+                if self.inner.status == FfiRuntimeStatus::Running {
+                    if let Some(session) = &mut self.inner.session {
+                        session.resonance_samples.push(spectrum.coherence);
+                    }
                 }
-                
-                // Update Vinnana/Engine belief based on HR? 
-                // Currently Engine is mostly pure logic, but we can feed it back.
-                
-                // Update shared frame
-                self.update_latest_frame(Some(hr), confidence);
-                
-                // Trigger safety check for HR?
-                // SafetyMonitor checks events. We could synthesize a 'HeartRateUpdate' event if needed.
+            }
+            SignalEvent::Respiration { value, timestamp_us } => {
+                self.ppg_breath_rate.observe(value, FfiRespirationSource::Ppg, timestamp_us);
+                self.handle_respiration_sample(value, FfiRespirationSource::Ppg, timestamp_us);
             }
         }
     }
 
-    fn update_shared_state(&self) {
-        if let Ok(mut guard) = self.state_tx.write() {
-             let session_duration = self.inner
-                .session
-                .as_ref()
-                .map(|s| s.start_time.elapsed().as_secs_f32())
-                .unwrap_or(0.0);
-
-             *guard = FfiRuntimeState {
-                status: self.inner.status,
-                pattern_id: self.inner.current_pattern_id.clone(),
-                phase: FfiPhase::from(self.inner.phase_machine.phase.clone()),
-                phase_progress: self.inner.phase_machine.cycle_phase_norm(),
-                cycles_completed: self.inner.phase_machine.cycle_index,
-                session_duration_sec: session_duration,
-                tempo_scale: self.inner.tempo_scale,
-                belief: get_engine_belief(&self.inner.engine),
-                resonance: FfiResonance {
-                    coherence_score: self.inner.last_resonance,
-                    phase_locking: self.inner.last_resonance,
-                    rhythm_alignment: self.inner.last_resonance,
-                },
-                safety: FfiSafetyStatus {
-                    is_locked: self.inner.safety_locked,
-                    trauma_count: self.safety.get_violations().len() as u32, 
-                    tempo_bounds: vec![0.8, 1.4],
-                    hr_bounds: vec![30.0, 220.0],
-                },
-            };
+    /// Add an inter-beat interval to the HRV window and, when kept, the session
+    fn apply_beat(&mut self, ibi_ms: f32, source: BeatSource, timestamp_us: i64) {
+        if !self.hrv.push(ibi_ms, timestamp_us, source) {
+            return;
+        }
+        if let Some(session) = &mut self.inner.session {
+            session.hrv_intervals_ms.push(ibi_ms);
+        }
+        // Spectral analysis runs on the SignalActor; the result arrives as an event
+        if let Some(intervals_ms) = self.hrv.spectrum_due(timestamp_us) {
+            let _ = self.signal_tx.send(SignalCommand::AnalyzeHrv(intervals_ms));
+        }
+    }
+
+    /// Common downstream path for heart rate, whatever the sensing device
+    fn apply_heart_rate(&mut self, hr: f32, confidence: f32, timestamp_us: i64) {
+        self.research.record_output(timestamp_us, hr, confidence);
+
+        if let Some(prep) = &mut self.inner.preparation {
+            prep.observe_quality(confidence);
+        }
+
+        // Update internal HR state
+        // Note: We might want to filter or smooth this before state update
+        // For now, raw update as per legacy behavior
+        if let Some(session) = &mut self.inner.session {
+            session.hr_samples.push(hr);
+            session.hr_zones.observe(hr, self.clock.now());
+        }
+        if self.inner.status == FfiRuntimeStatus::Running {
+            let progress = self.inner.phase_machine.cycle_phase_norm();
+            self.inner.resonance.observe_heart_rate(hr, progress);
+        }
+
+        // RSA: heart rate rises on inhale and falls on exhale
+        self.handle_respiration_sample(hr, FfiRespirationSource::Rsa, timestamp_us);
+        
+        // Update Vinnana/Engine belief based on HR? 
+        // Currently Engine is mostly pure logic, but we can feed it back.
+        
+        // Update shared frame; readings too poor to trust are not shown at all
+        let surfaced = (confidence >= HR_SURFACE_MIN_QUALITY).then_some(hr);
+        self.last_hr = Some((surfaced, confidence, self.clock.now()));
+        self.update_latest_frame();
+        if let (Some(hr), Some(listener)) = (surfaced, self.listener.lock().as_ref()) {
+            listener.on_heart_rate(hr, confidence, timestamp_us);
+        }
+        
+        // Trigger safety check for HR?
+        // SafetyMonitor checks events. We could synthesize a 'HeartRateUpdate' event if needed.
+        if confidence >= HR_INTERPOLATED_CONFIDENCE {
+            if let Some(escalation) = self.escalation.observe_heart_rate(hr, timestamp_us, self.clock.now_ms()) {
+                self.raise_escalation(escalation, None);
+            }
+        }
+    }
+
+    /// Publish the shared state as a fresh immutable snapshot, skipping the
+    /// swap if nothing changed. Readers never block the actor.
+    fn update_shared_state(&mut self) {
+        let mut state = self.build_shared_state();
+        let previous = self.state_tx.load_full();
+        // Session time alone republishes at most once per whole second
+        if state.session_duration_sec.floor() == previous.session_duration_sec.floor() {
+            let duration_sec = std::mem::replace(&mut state.session_duration_sec, previous.session_duration_sec);
+            if *previous == state {
+                return;
+            }
+            state.session_duration_sec = duration_sec;
+        }
+        let mut stamps = StateStamps::clone(&self.state_stamps.load());
+        let diff = FfiRuntimeStateDiff::between(&previous, &state, stamps.sequence + 1);
+        stamps.record(&diff);
+        // Values go out before the stamps naming them; a delta loads the
+        // stamps first, so it never carries a sequence newer than its values
+        self.state_tx.store(Arc::new(state));
+        self.state_stamps.store(Arc::new(stamps));
+        if let Some(listener) = self.listener.lock().as_ref() {
+            listener.on_state_changed(diff);
+        }
+        self.inner.last_state_publish = Some(self.clock.now());
+    }
+
+    /// Tick-driven publish, throttled to the configured rate. Status changes
+    /// always go out immediately; the latest frame stays at full tick rate.
+    fn publish_tick_state(&mut self) {
+        let due = match (self.inner.state_publish_interval, self.inner.last_state_publish) {
+            (Some(interval), Some(last)) => self.clock.since(last) >= interval,
+            _ => true,
+        };
+        let status_changed = self.state_tx.load().status != self.inner.status;
+        if due || status_changed {
+            self.update_shared_state();
+        }
+    }
+
+    fn resonance(&self) -> FfiResonance {
+        FfiResonance {
+            coherence_score: self.hrv.spectrum.map_or(0.0, |s| s.coherence),
+            phase_locking: self.inner.resonance.phase_locking(),
+            rhythm_alignment: rhythm_alignment(self.inner.breath_rate.rate_bpm(), self.inner.pacer_rate_bpm()),
+            rsa_amplitude_bpm: self.inner.resonance.rsa_amplitude_bpm,
+        }
+    }
+
+    /// Engine belief with the registered custom modes appended
+    fn belief(&self) -> FfiBeliefState {
+        let mut belief = get_engine_belief(&self.inner.engine);
+        self.belief_modes.extend(&mut belief);
+        belief
+    }
+
+    fn build_shared_state(&self) -> FfiRuntimeState {
+        let session_duration = self.inner
+            .session
+            .as_ref()
+            .map(|s| self.clock.since(s.start_time).as_secs_f32())
+            .unwrap_or(0.0);
+        let (phase, phase_progress) = self.inner.display_phase();
+
+        FfiRuntimeState {
+            status: self.inner.status,
+            pattern_id: self.inner.current_pattern_id.clone(),
+            phase,
+            phase_progress,
+            cycles_completed: self.inner.phase_machine.cycle_index,
+            session_duration_sec: session_duration,
+            tempo_scale: self.inner.tempo_scale,
+            belief: self.belief(),
+            resonance: self.resonance(),
+            safety: FfiSafetyStatus {
+                is_locked: self.inner.safety_locked,
+                trauma_count: self.safety.get_violations().len() as u32, 
+                tempo_bounds: vec![0.8, 1.4],
+                hr_bounds: vec![30.0, 220.0],
+                cooldown_remaining_sec: self.journal.active_lock()
+                    .map(|l| l.cooldown_remaining(self.clock.now_ms()).as_secs_f32())
+                    .unwrap_or(0.0),
+                unacknowledged_critical: self.safety.unacknowledged_critical_count(),
+            },
+            preparation: self.inner.preparation.as_ref().map(|p| p.status()),
+            follow_me: self.inner.follow_me.as_ref().map(|f| f.status(self.inner.breath_rate.rate_bpm())),
+            sequence: self.inner.sequence.as_ref().map(|s| s.progress()),
+            stage: self.inner.session.as_ref().map(|s| s.stages.status()),
+            sleep: self.inner.session.as_ref().and_then(|s| s.sleep.as_ref()).map(|s| s.status()),
         }
     }
     
-    fn update_latest_frame(&self, hr: Option<f32>, quality: f32) {
-         if let Ok(mut guard) = self.latest_frame.write() {
-            *guard = FfiFrame {
-                phase: FfiPhase::from(self.inner.phase_machine.phase.clone()),
-                phase_progress: self.inner.phase_machine.cycle_phase_norm(),
-                cycles_completed: self.inner.phase_machine.cycle_index,
+    fn update_latest_frame(&self) {
+         let (hr, quality) = self.last_hr
+             .filter(|(_, _, at)| self.clock.since(*at) <= HR_FRAME_STALE)
+             .map_or((None, 0.0), |(hr, quality, _)| (hr, quality));
+         // A running preview drives the animated phase instead of the session pacer
+         let (phase, phase_progress, cycles_completed, time_to_next_phase_ms, prescribed_rate_bpm) =
+             match &self.inner.preview {
+                 Some(p) => (
+                     FfiPhase::from(p.machine.phase),
+                     p.machine.cycle_phase_norm(),
+                     p.machine.cycle_index,
+                     None,
+                     None,
+                 ),
+                 None => {
+                     let (phase, progress) = self.inner.display_phase();
+                     (
+                         phase,
+                         progress,
+                         self.inner.phase_machine.cycle_index,
+                         Some(self.inner.time_to_next_phase_ms()),
+                         self.inner.prescribed_rate_bpm(),
+                     )
+                 }
+             };
+         self.frame_lite.store(Arc::new(FfiFrameLite {
+             phase,
+             phase_progress,
+             heart_rate: hr,
+         }));
+         self.latest_frame.store(Arc::new(FfiFrame {
+                phase,
+                phase_progress,
+                cycles_completed,
                 heart_rate: hr,
                 signal_quality: quality,
-                belief: get_engine_belief(&self.inner.engine),
-                resonance: FfiResonance {
-                    coherence_score: self.inner.last_resonance,
-                    phase_locking: self.inner.last_resonance,
-                    rhythm_alignment: self.inner.last_resonance,
-                },
-            };
-         }
+                belief: self.belief(),
+                resonance: self.resonance(),
+                adherence: self.inner.adherence.live_score(),
+                prescribed_rate_bpm,
+                detected_rate_bpm: self.inner.breath_rate.rate_bpm(),
+                time_to_next_phase_ms,
+                countdown: self.inner.preparation.as_ref().map(|p| p.seconds_left()),
+                goals: self.inner.session.as_ref().map(|s| s.goals.progress()).unwrap_or_default(),
+                hrv: self.hrv.metrics(),
+                hrv_spectrum: self.hrv.spectrum,
+                respiratory_rate_bpm: self.ppg_breath_rate.rate_bpm(),
+                raw_heart_rate: hr.and(self.camera_hr.map(|c| c.unfiltered_hr)),
+                hr_filter_confidence: hr.and(self.camera_hr.map(|c| c.filter_confidence)),
+                signal_snr_db: self.camera_hr.and_then(|c| c.quality).map(|q| q.snr_db),
+                perfusion_index: self.camera_hr.and_then(|c| c.quality).map(|q| q.perfusion_index),
+         }));
     }
 
     fn verify_command(&mut self, event_type: FfiKernelEventType, payload: Option<String>) -> bool {
-        let timestamp_ms = Utc::now().timestamp_millis();
+        let state = SpecState::from(&**self.state_tx.load());
+        self.check_command(event_type, payload, state).is_some()
+    }
+
+    /// Run a command against `state` (the state it would produce, for tempo
+    /// changes) and carry out the corrective actions: None when it must not
+    /// go ahead, otherwise the result so warnings' clamps can be applied
+    fn check_command(
+        &mut self,
+        event_type: FfiKernelEventType,
+        payload: Option<String>,
+        state: SpecState,
+    ) -> Option<FfiSafetyCheckResult> {
+        let timestamp_ms = self.clock.now_ms();
         let event = FfiKernelEvent {
             event_type,
             timestamp_ms,
             payload,
         };
         
-        let state_snapshot = {
-            let s = self.state_tx.read().unwrap();
-            s.clone()
-        };
-        
-        let result = self.safety.check_event(event, state_snapshot);
+        let result = self.safety.check_spec_event(event, state);
+
+        for v in result.violations.iter().filter(|v| v.severity == FfiViolationSeverity::Critical) {
+            if let Some(escalation) = self.escalation.observe_critical(&v.description, self.clock.now_ms()) {
+                self.raise_escalation(escalation, Some(v.clone()));
+            }
+        }
         
         // Update shared state with new violations if any
         if !result.violations.is_empty() {
@@ -745,48 +2228,250 @@ impl RuntimeActor {
         
         if !result.is_safe {
             for v in &result.violations {
-                log::error!("Safety Violation: [{:?}] {}", v.severity, v.description);
-                if v.severity == FfiViolationSeverity::Critical || v.severity == FfiViolationSeverity::Error {
+                log::error!(
+                    "Safety Violation: [{:?}] {} (action: {:?})",
+                    v.severity, v.description, v.corrective_action
+                );
+                let blocks = matches!(v.corrective_action, Some(FfiCorrectiveAction::BlockEvent));
+                if blocks || v.severity == FfiViolationSeverity::Critical || v.severity == FfiViolationSeverity::Error {
+                    // Halting only makes sense for a session in progress
+                    if matches!(v.corrective_action, Some(FfiCorrectiveAction::TriggerHalt)) && self.inner.session.is_some() {
+                        self.handle_emergency_halt(v.description.clone());
+                        return None;
+                    }
                     self.update_shared_state(); // Reflect violation in trauma count
-                    return false;
+                    return None;
                 }
             }
         }
         
-        true
+        Some(result)
     }
 
-    fn handle_start(&mut self) {
+    fn handle_start(&mut self, goals: FfiSessionGoals) {
         if !self.verify_command(FfiKernelEventType::StartSession, None) {
             return;
         }
         if self.inner.safety_locked { return; }
-        
+
+        self.inner.pending_goals = goals;
+        let _ = self.signal_tx.send(SignalCommand::Reset);
+        self.ppg_breath_rate.reset();
+        self.camera_hr = None;
+        self.inner.preview = None;
+        self.inner.observation = None;
+        let config = self.inner.preparation_config.clone();
+        if config.countdown_sec > 0.0 {
+            self.inner.preparation = Some(Preparation::new(config));
+            self.inner.status = FfiRuntimeStatus::Preparing;
+            self.update_shared_state();
+        } else {
+            self.begin_session();
+        }
+    }
+
+    /// One-step acute-anxiety session: 7-11 breathing, no countdown, short and fixed
+    fn handle_start_panic(&mut self) {
+        if self.inner.safety_locked { return; }
+        if self.inner.session.is_some() {
+            self.finalize_session(false);
+        }
+        // Someone in acute distress may have just tried another pattern; that
+        // must not count against the switch to the panic pattern
+        self.safety.relax_pattern_stability();
+        if !self.verify_command(FfiKernelEventType::LoadPattern, Some(PANIC_PATTERN_ID.into())) {
+            return;
+        }
+        if !self.verify_command(FfiKernelEventType::StartSession, None) {
+            return;
+        }
+
+        let _ = self.signal_tx.send(SignalCommand::Reset);
+        self.ppg_breath_rate.reset();
+        self.camera_hr = None;
+        self.inner.preview = None;
+        self.inner.follow_me = None;
+        self.inner.sequence = None;
+        self.inner.current_pattern_id = PANIC_PATTERN_ID.into();
+        self.inner.binaural_state = Some(PANIC_BINAURAL_STATE);
+        self.inner.pending_goals = FfiSessionGoals {
+            target_minutes: Some(PANIC_SESSION_SEC / 60.0),
+            ..Default::default()
+        };
+        self.begin_session();
+        if let Some(session) = &mut self.inner.session {
+            session.panic = true;
+            session.time_limit_sec = Some(PANIC_SESSION_SEC);
+            session.cycle_limit = None;
+            session.stages = SessionStages::new(FfiSessionStagesConfig::default());
+        }
+        log::info!("Panic session started ({} for {:.0}s)", PANIC_PATTERN_ID, PANIC_SESSION_SEC);
+    }
+
+    fn handle_skip_preparation(&mut self) {
+        if self.inner.status == FfiRuntimeStatus::Preparing {
+            log::info!("Preparation skipped by user");
+            self.begin_session();
+        }
+    }
+
+    fn handle_begin_cool_down(&mut self) -> Result<(), ZenOneError> {
+        if self.inner.status != FfiRuntimeStatus::Running {
+            return Err(ZenOneError::ConfigError("No session running".into()));
+        }
+        if !self.enter_cool_down() {
+            return Err(ZenOneError::ConfigError("No cool-down declared, or it already started".into()));
+        }
+        Ok(())
+    }
+
+    /// Stop pacing and observe natural breathing, if the session declared a
+    /// cool-down that has not started yet
+    fn enter_cool_down(&mut self) -> bool {
+        let Some(session) = &mut self.inner.session else { return false };
+        if !session.stages.begin_cool_down() {
+            return false;
+        }
+        log::info!("Cool-down started ({:.0}s of natural breathing)", session.stages.config.cool_down_sec);
+        self.serial.idle();
+        self.events.push(FfiRuntimeEvent::StageStarted {
+            stage: FfiSessionStage::CoolDown,
+            timestamp_us: self.inner.last_timestamp_us,
+        });
+        self.update_shared_state();
+        self.update_latest_frame();
+        true
+    }
+
+    /// Finish an auto-completing session, through its cool-down when one is
+    /// declared. Returns true once pacing is over.
+    fn complete_session(&mut self) -> bool {
+        if !self.enter_cool_down() {
+            let stats = self.finalize_session(false);
+            self.events.push(FfiRuntimeEvent::SessionCompleted { stats });
+        }
+        true
+    }
+
+    /// Tell the listener about phase, cycle and status changes since the last call
+    fn notify_listener(&mut self) {
+        let listener = self.listener.lock();
+        let Some(listener) = listener.as_ref() else { return };
+        let timestamp_us = self.inner.last_timestamp_us;
+
+        let status = self.inner.status;
+        if status != self.notified.status {
+            self.notified.status = status;
+            listener.on_status_changed(status);
+        }
+        let pacing = matches!(status, FfiRuntimeStatus::Preparing | FfiRuntimeStatus::Running);
+        let (phase, _) = self.inner.display_phase();
+        if pacing && phase != self.notified.phase {
+            listener.on_phase_changed(phase, self.inner.time_to_next_phase_ms(), timestamp_us);
+        }
+        self.notified.phase = phase;
+        let cycles = self.inner.phase_machine.cycle_index;
+        if status == FfiRuntimeStatus::Running && cycles > self.notified.cycles {
+            listener.on_cycle_complete(cycles, self.inner.adherence.live_score(), timestamp_us);
+        }
+        self.notified.cycles = cycles;
+    }
+
+    /// Leave Preparing (or Idle) and start the paced session
+    fn begin_session(&mut self) {
+        self.inner.preparation = None;
+        // A sequence always starts from its first segment
+        if let Some(sequence) = &mut self.inner.sequence {
+            sequence.restart();
+            self.inner.current_pattern_id = sequence.current().pattern_id.clone();
+        }
+
         // Refresh pattern
-        let patterns = builtin_patterns();
-        let pattern = patterns.get(&self.inner.current_pattern_id)
-            .or_else(|| patterns.get("4-7-8"));
-        if let Some(p) = pattern {
-            self.inner.phase_machine = PhaseMachine::new(p.to_phase_durations());
+        let pattern = self.pattern(&self.inner.current_pattern_id)
+            .or_else(|| self.pattern("4-7-8"));
+        if let Some(p) = &pattern {
+            self.inner.install_pacer(p.to_phase_durations(), false);
+        }
+        // Sleep sessions run until the wind-down ends, sequences until their last segment
+        let cycle_limit = pattern
+            .filter(|_| {
+                self.inner.auto_complete
+                    && self.inner.pending_sleep.is_none()
+                    && self.inner.sequence.is_none()
+            })
+            .map(|p| p.recommended_cycles as u64);
+        // Nothing to observe after a sleep session: the user should be asleep
+        let mut stages_config = self.inner.stages_config.clone();
+        if self.inner.pending_sleep.is_some() {
+            stages_config.cool_down_sec = 0.0;
         }
         
-        let _ = self.signal_tx.send(SignalCommand::Reset);
         self.inner.last_timestamp_us = 0;
+        self.inner.timeline_us = 0;
+        self.inner.tempo_scale = 1.0;
+        self.inner.tempo_target = 1.0;
         self.inner.status = FfiRuntimeStatus::Running;
         self.inner.session = Some(SessionState {
-            start_time: Instant::now(),
+            start_time: self.clock.now(),
+            started_at_ms: self.clock.now_ms(),
             pattern_id: self.inner.current_pattern_id.clone(),
             hr_samples: Vec::new(),
             resonance_samples: Vec::new(),
+            cycle_adherence: Vec::new(),
+            rr_intervals_ms: Vec::new(),
+            hrv_intervals_ms: Vec::new(),
+            interruptions: Vec::new(),
+            interrupted: None,
+            hr_zones: HrZoneTracker::new(self.hr_zones.lock().clone()),
+            binaural_sec: [0.0; 4],
+            goals: GoalTracker::new(std::mem::take(&mut self.inner.pending_goals)),
+            sleep: self.inner.pending_sleep.take().map(SleepWindDown::new),
+            panic: false,
+            time_limit_sec: None,
+            cycle_limit,
+            stages: SessionStages::new(stages_config),
         });
+        self.inner.adherence.reset();
+        self.inner.resonance.reset();
+        self.inner.breath_rate.reset();
+        if let Some(follow) = &mut self.inner.follow_me {
+            follow.reset();
+        }
+        self.coaching.reset();
+        self.planner.reset(&self.inner.current_pattern_id);
         self.update_shared_state();
     }
 
     fn handle_stop(&mut self, reply_tx: Sender<FfiSessionStats>) {
+        let stats = self.finalize_session(false);
+
+        // Send back the stats
+        let _ = reply_tx.send(stats);
+    }
+
+    /// End the current session, store its record and return its stats.
+    /// `partial` marks sessions cut short by a very long interruption.
+    fn finalize_session(&mut self, partial: bool) -> FfiSessionStats {
         self.inner.status = FfiRuntimeStatus::Idle;
+        self.inner.preparation = None;
+        self.last_hr = None;
+        // Sequences run once
+        let sequence = self.inner.sequence.take();
+        self.serial.idle();
         
-        let stats = if let Some(session) = self.inner.session.take() {
-            let duration = session.start_time.elapsed();
+        let stats = if let Some(mut session) = self.inner.session.take() {
+            if let Some((kind, since)) = session.interrupted.take() {
+                session.interruptions.push(FfiInterruption {
+                    kind,
+                    started_at_ms: self.clock.now_ms() - self.clock.since(since).as_millis() as i64,
+                    duration_ms: self.clock.since(since).as_millis() as u64,
+                    resume_action: None,
+                });
+            }
+            let interrupted_ms: u64 = session.interruptions.iter().map(|i| i.duration_ms).sum();
+            let duration = self.clock
+                .since(session.start_time)
+                .saturating_sub(Duration::from_millis(interrupted_ms));
             let avg_hr = if !session.hr_samples.is_empty() {
                 Some(session.hr_samples.iter().sum::<f32>() / session.hr_samples.len() as f32)
             } else {
@@ -799,44 +2484,148 @@ impl RuntimeActor {
                 0.0
             };
 
-            FfiSessionStats {
+            let avg_adherence = if !session.cycle_adherence.is_empty() {
+                Some(session.cycle_adherence.iter().sum::<f32>() / session.cycle_adherence.len() as f32)
+            } else {
+                None
+            };
+            let segments = sequence
+                .map(|s| s.finish(&session, self.inner.phase_machine.cycle_index))
+                .unwrap_or_default();
+            let (stages, cool_down_breathing) = session.stages.finish();
+
+            let stats = FfiSessionStats {
                 duration_sec: duration.as_secs_f32(),
                 cycles_completed: self.inner.phase_machine.cycle_index,
                 pattern_id: session.pattern_id,
                 avg_heart_rate: avg_hr,
-                final_belief: get_engine_belief(&self.inner.engine),
+                final_belief: self.belief(),
                 avg_resonance,
-            }
+                avg_adherence,
+                cycle_adherence: session.cycle_adherence,
+                interruptions: session.interruptions,
+                partial,
+                time_in_zone: session.hr_zones.summary(),
+                binaural: FfiBrainWaveState::ALL
+                    .iter()
+                    .zip(session.binaural_sec)
+                    .filter(|(_, seconds)| *seconds > 0.0)
+                    .map(|(state, seconds)| FfiBinauralListening { state: *state, seconds })
+                    .collect(),
+                goals: session.goals.progress(),
+                segments,
+                stages,
+                cool_down_breathing,
+                sleep: session.sleep.as_ref().map(|s| s.summary()),
+                hrv: time_domain_hrv(&session.hrv_intervals_ms),
+                hrv_spectrum: hrv_spectrum(&session.hrv_intervals_ms),
+            };
+
+            self.store.insert(
+                FfiSessionRecord {
+                    id: SessionStore::new_session_id(session.started_at_ms),
+                    started_at_ms: session.started_at_ms,
+                    ended_at_ms: self.clock.now_ms(),
+                    stats: stats.clone(),
+                    tags: Vec::new(),
+                    notes: None,
+                    ibi_count: session.rr_intervals_ms.len() as u32,
+                    signature: None,
+                    utc_offset_min: None,
+                },
+                session.rr_intervals_ms,
+            );
+            self.store.run_maintenance(self.clock.now_ms());
+
+            stats
         } else {
             FfiSessionStats {
                 duration_sec: 0.0,
                 cycles_completed: 0,
                 pattern_id: String::new(),
                 avg_heart_rate: None,
-                final_belief: get_engine_belief(&self.inner.engine),
+                final_belief: self.belief(),
                 avg_resonance: 0.0,
+                avg_adherence: None,
+                cycle_adherence: Vec::new(),
+                interruptions: Vec::new(),
+                partial: false,
+                time_in_zone: Vec::new(),
+                binaural: Vec::new(),
+                goals: Vec::new(),
+                segments: Vec::new(),
+                stages: Vec::new(),
+                cool_down_breathing: None,
+                sleep: None,
+                hrv: None,
+                hrv_spectrum: None,
             }
         };
 
-        // Send back the stats
-        let _ = reply_tx.send(stats);
+        // Persist any buffered research data at session boundaries
+        self.research.flush();
         
         self.update_shared_state();
+        self.update_latest_frame();
+        stats
     }
     
-    fn handle_reset_safety_lock(&mut self) {
-        log::warn!("RuntimeActor: Resetting Safety Lock");
+    fn handle_reset_safety_lock(&mut self, reason: String) -> Result<(), ZenOneError> {
+        let reason = reason.trim().to_string();
+        if reason.is_empty() {
+            return Err(ZenOneError::ConfigError("A reason is required to reset the safety lock".into()));
+        }
+        let Some(lock) = self.journal.active_lock().filter(|_| self.inner.safety_locked) else {
+            return Err(ZenOneError::ConfigError("Safety lock is not engaged".into()));
+        };
+        let remaining = lock.cooldown_remaining(self.clock.now_ms());
+        if !remaining.is_zero() {
+            self.record_journal(FfiSafetyJournalKind::ResetRejected, reason, Some(lock.cause));
+            return Err(ZenOneError::SafetyViolation(format!(
+                "Safety lock cooling down, {} s remaining",
+                remaining.as_secs().max(1)
+            )));
+        }
+
+        log::warn!("RuntimeActor: Resetting Safety Lock ({})", reason);
+        self.record_journal(FfiSafetyJournalKind::LockReset, reason, Some(lock.cause));
+        if let Err(e) = self.journal.set_active_lock(None) {
+            log::error!("Failed to persist safety lock reset: {}", e);
+        }
         self.inner.safety_locked = false;
         self.inner.status = FfiRuntimeStatus::Idle;
         self.inner.session = None; // Reset session
         self.update_shared_state();
+        Ok(())
+    }
+
+    fn record_journal(&self, kind: FfiSafetyJournalKind, reason: String, violation: Option<FfiSafetyViolation>) {
+        let entry = FfiSafetyJournalEntry {
+            kind,
+            timestamp_ms: self.clock.now_ms(),
+            reason,
+            violation,
+        };
+        if let Err(e) = self.journal.append(entry) {
+            log::error!("Failed to persist safety journal entry: {}", e);
+        }
+    }
+
+    /// Hand a prepared escalation to the shell and keep it in the safety journal
+    fn raise_escalation(&mut self, escalation: FfiEscalation, violation: Option<FfiSafetyViolation>) {
+        log::error!("Escalating to trusted contact: {:?}", escalation.reason);
+        self.record_journal(FfiSafetyJournalKind::EscalationRaised, escalation.message.clone(), violation);
+        self.events.push(FfiRuntimeEvent::Escalation { escalation });
     }
 
     fn handle_adjust_tempo(&mut self, scale: f32) {
-        if !self.verify_command(FfiKernelEventType::AdjustTempo, Some(scale.to_string())) {
+        let scale = scale.clamp(TEMPO_MIN, TEMPO_MAX);
+        let proposed = SpecState { tempo_scale: scale, ..SpecState::from(&**self.state_tx.load()) };
+        let Some(result) = self.check_command(FfiKernelEventType::AdjustTempo, Some(scale.to_string()), proposed) else {
             return;
-        }
-        self.inner.tempo_scale = scale;
+        };
+        // Applied gradually by handle_tick, no further than the rate limit allows
+        self.inner.tempo_target = result.clamped_tempo().unwrap_or(scale);
         self.update_shared_state();
     }
     
@@ -849,25 +2638,132 @@ impl RuntimeActor {
         self.update_shared_state();
     }
     
+    fn handle_ack_violation(&mut self, id: u64, note: Option<String>) -> Result<(), ZenOneError> {
+        self.safety.ack_violation(id, note.clone())?;
+        let violation = self.safety.get_violations().into_iter().find(|v| v.id == id);
+        self.record_journal(FfiSafetyJournalKind::ViolationAcknowledged, note.unwrap_or_default(), violation);
+        self.update_shared_state();
+        Ok(())
+    }
+
     fn handle_emergency_halt(&mut self, reason: String) {
         log::error!("EMERGENCY HALT: {}", reason);
+        // Logged so the panic_halt spec sees its halt happen
+        let state = SpecState::from(&**self.state_tx.load());
+        let event = FfiKernelEvent {
+            event_type: FfiKernelEventType::EmergencyHalt,
+            timestamp_ms: self.clock.now_ms(),
+            payload: Some(reason.clone()),
+        };
+        self.safety.check_spec_event(event, state);
         self.inner.status = FfiRuntimeStatus::SafetyLock;
         self.inner.safety_locked = true;
+
+        // Attribute the lock to a just-recorded critical violation if there is one
+        let now_ms = self.clock.now_ms();
+        let cause = self.safety.get_violations().into_iter().rev()
+            .find(|v| v.severity == FfiViolationSeverity::Critical && now_ms - v.timestamp_ms <= 60_000)
+            .unwrap_or_else(|| FfiSafetyViolation {
+                id: 0,
+                spec_name: "emergency_halt".to_string(),
+                description: reason.clone(),
+                severity: FfiViolationSeverity::Critical,
+                timestamp_ms: now_ms,
+                acknowledged: None,
+                corrective_action: Some(FfiCorrectiveAction::TriggerHalt),
+            });
+        if self.journal.active_lock().is_none() {
+            self.record_journal(FfiSafetyJournalKind::LockEngaged, reason, Some(cause.clone()));
+            let lock = SafetyLockInfo { engaged_at_ms: now_ms, cause };
+            if let Err(e) = self.journal.set_active_lock(Some(lock)) {
+                log::error!("Failed to persist safety lock: {}", e);
+            }
+        }
         self.update_shared_state();
     }
-    
-    fn handle_pause(&mut self) {
-        if self.inner.status == FfiRuntimeStatus::Running {
+
+    /// Re-engage a lock persisted by an earlier run, cooldown included
+    fn handle_restore_safety_lock(&mut self) {
+        if self.inner.safety_locked || self.journal.active_lock().is_none() {
+            return;
+        }
+        log::warn!("RuntimeActor: Restoring persisted Safety Lock");
+        self.inner.status = FfiRuntimeStatus::SafetyLock;
+        self.inner.safety_locked = true;
+        self.update_shared_state();
+    }
+    
+    fn handle_pause(&mut self, kind: FfiInterruptionKind) {
+        if self.inner.status == FfiRuntimeStatus::Running {
+            self.inner.note_activity();
             self.inner.status = FfiRuntimeStatus::Paused;
+            if let Some(session) = &mut self.inner.session {
+                session.interrupted = Some((kind, self.clock.now()));
+            }
             self.update_shared_state();
         }
     }
     
     fn handle_resume(&mut self) {
-        if self.inner.status == FfiRuntimeStatus::Paused {
+        if self.inner.status != FfiRuntimeStatus::Paused {
+            return;
+        }
+        self.inner.note_activity();
+        let Some((kind, since)) = self.inner.session.as_mut().and_then(|s| s.interrupted.take()) else {
             self.inner.status = FfiRuntimeStatus::Running;
             self.update_shared_state();
+            return;
+        };
+
+        let gap = self.clock.since(since);
+        let action = ResumePolicy::decide(gap);
+        let interruption = FfiInterruption {
+            kind,
+            started_at_ms: self.clock.now_ms() - gap.as_millis() as i64,
+            duration_ms: gap.as_millis() as u64,
+            resume_action: Some(action),
+        };
+        log::info!("Resuming after {:?} interruption of {:?}: {:?}", kind, gap, action);
+        if let Some(session) = &mut self.inner.session {
+            session.interruptions.push(interruption.clone());
+        }
+
+        match action {
+            FfiResumeAction::ResumeMidCycle => {}
+            FfiResumeAction::RestartCycle => self.restart_current_cycle(),
+            FfiResumeAction::Finalize => {
+                let stats = self.finalize_session(true);
+                self.events.push(FfiRuntimeEvent::SessionAutoFinalized { stats });
+                return;
+            }
+        }
+        self.inner.status = FfiRuntimeStatus::Running;
+        self.inner.adherence.reset();
+        self.inner.resonance.reset();
+        self.events.push(FfiRuntimeEvent::SessionResumed { interruption });
+        self.update_shared_state();
+    }
+
+    /// Builtin or custom pattern with the user's phase overrides applied
+    fn pattern(&self, id: &str) -> Option<BreathPattern> {
+        self.patterns.get(id).map(|p| self.adjustments.apply(p))
+    }
+
+    /// Rewind the phase machine to the start of the current cycle
+    fn restart_current_cycle(&mut self) {
+        if let Some(p) = self.pattern(&self.inner.current_pattern_id) {
+            self.inner.install_pacer(p.to_phase_durations(), true);
+        }
+    }
+
+    /// Pick up changed phase overrides; follow-me, sleep stretch and arc morphs
+    /// own the timing while active and re-read the pattern on their next step
+    fn handle_reload_pattern(&mut self) {
+        if self.inner.follow_me.is_some() || self.inner.winding_down() || self.inner.morph.is_some() {
+            return;
         }
+        self.restart_current_cycle();
+        self.update_shared_state();
     }
 
     fn handle_load_pattern(&mut self, id: String) {
@@ -876,1111 +2772,16375 @@ impl RuntimeActor {
         }
         if self.inner.safety_locked { return; }
         
-        let patterns = builtin_patterns();
-        if let Some(p) = patterns.get(&id) {
-            self.inner.phase_machine = PhaseMachine::new(p.to_phase_durations());
+        if let Some(p) = self.pattern(&id) {
+            match self.transition_source() {
+                // Finish the running cycle on the old timing, then ease over
+                Some(from) => {
+                    self.inner.morph = Some(PatternMorph {
+                        from,
+                        to: p,
+                        step: 0,
+                        cycles: self.inner.transition_cycles,
+                    });
+                }
+                None => self.inner.install_pacer(p.to_phase_durations(), false),
+            }
             self.inner.current_pattern_id = id;
+            // Picking a single pattern replaces any loaded sequence
+            self.inner.sequence = None;
             self.update_shared_state();
         }
     }
 
-    fn handle_process_frame(&mut self, r: f32, g: f32, b: f32, timestamp_us: i64) {
-        // Offload to SignalActor - NON-BLOCKING
-        let _ = self.signal_tx.send(SignalCommand::ProcessSample { r, g, b, timestamp_us });
+    /// Timing to morph a mid-session load from, when transitions are enabled.
+    /// Follow-me and sleep wind-down own the timing and always switch at once.
+    fn transition_source(&self) -> Option<BreathPattern> {
+        let active = matches!(self.inner.status, FfiRuntimeStatus::Running | FfiRuntimeStatus::Paused);
+        if !active || self.inner.transition_cycles == 0 || self.inner.follow_me.is_some() || self.inner.winding_down() {
+            return None;
+        }
+        match &self.inner.morph {
+            Some(morph) => Some(morph.current()),
+            None => self.pattern(&self.inner.current_pattern_id),
+        }
     }
-    
-    fn handle_tick(&mut self, dt_sec: f32, timestamp_us: i64) {
-        let dt_us = (dt_sec * 1_000_000.0) as u64;
-        self.inner.last_timestamp_us = timestamp_us;
-        self.inner.phase_machine.tick(dt_us);
-        self.inner.engine.tick(dt_us);
-        
+
+    /// Queue a pattern sequence for the next session, starting on its first pattern
+    fn handle_load_sequence(&mut self, segments: Vec<FfiSequenceSegment>) {
+        if self.inner.status != FfiRuntimeStatus::Idle || self.inner.safety_locked {
+            return;
+        }
+        let Some(first) = segments.first().map(|s| s.pattern_id.clone()) else { return };
+        if !self.verify_command(FfiKernelEventType::LoadPattern, Some(first.clone())) {
+            return;
+        }
+        if let Some(p) = self.pattern(&first) {
+            self.inner.install_pacer(p.to_phase_durations(), false);
+            self.inner.current_pattern_id = first;
+            self.inner.sequence = Some(SequenceRun::new(segments));
+            self.update_shared_state();
+        }
+    }
+
+    /// Advance the running sequence. Once a segment's time is up, the next
+    /// cycle boundary switches to the following pattern, or completes the
+    /// session after the last one. Returns true when the session ended.
+    fn track_sequence(&mut self, dt_sec: f32, prev_cycle: u64) -> bool {
+        if self.inner.status != FfiRuntimeStatus::Running || self.inner.session.is_none() {
+            return false;
+        }
+        let cycle = self.inner.phase_machine.cycle_index;
+        let Some(sequence) = &mut self.inner.sequence else { return false };
+        sequence.elapsed_sec += dt_sec;
+        if cycle == prev_cycle || !sequence.segment_done() {
+            return false;
+        }
+        let Some(next) = sequence.next_pattern_id() else {
+            return self.complete_session();
+        };
+
+        // Same safety gate as a manual load; a refused switch is retried next cycle
+        if !self.verify_command(FfiKernelEventType::LoadPattern, Some(next.clone())) {
+            return false;
+        }
+        let Some(pattern) = self.pattern(&next) else { return false };
+        if let (Some(sequence), Some(session)) = (&mut self.inner.sequence, &self.inner.session) {
+            sequence.advance(session, cycle);
+        }
+        self.inner.install_pacer(pattern.to_phase_durations(), true);
+        self.inner.current_pattern_id = next;
+        if let Some(progress) = self.inner.sequence.as_ref().map(|s| s.progress()) {
+            self.events.push(FfiRuntimeEvent::SequenceSegmentStarted { progress });
+        }
         self.update_shared_state();
-        self.update_latest_frame(None, 0.0);
+        false
     }
-}
 
-/// ZenOne Runtime - Full Engine API for native apps
-pub struct ZenOneRuntime {
-    cmd_tx: Sender<RuntimeCommand>,
-    state: Arc<RwLock<FfiRuntimeState>>,
-    latest_frame: Arc<RwLock<FfiFrame>>,
-    // We keep thread handle to ensure it lives as long as Runtime
-    // (Though in UniFFI, Runtime serves as the singleton usually)
-    _thread: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
-}
+    /// Accumulate time-in-zone and pause calming sessions on sustained elevated HR
+    fn track_hr_zone(&mut self, dt_sec: f32, timestamp_us: i64) {
+        let Some(session) = &mut self.inner.session else { return };
+        let Some((zone, sustained_sec)) = session.hr_zones.tick(dt_sec, self.clock.now()) else { return };
 
-impl ZenOneRuntime {
-    /// Create a new runtime with default pattern (4-7-8)
-    pub fn new() -> Self {
-        Self::with_pattern("4-7-8".to_string())
+        let calming = self
+            .patterns
+            .get(&self.inner.current_pattern_id)
+            .is_some_and(|p| p.arousal_impact < 0.0);
+        if !calming {
+            return;
+        }
+        log::warn!("HR in zone '{}' for {:.0}s during calming pattern, pausing", zone, sustained_sec);
+        self.handle_pause(FfiInterruptionKind::ElevatedHeartRate);
+        self.events.push(FfiRuntimeEvent::HeartRateZoneAlert { zone, sustained_sec, timestamp_us });
     }
 
-    /// Create with specific pattern
-    pub fn with_pattern(pattern_id: String) -> Self {
-        log::info!("ZenOneRuntime: Initializing with pattern {}", pattern_id);
-        
-        let patterns = builtin_patterns();
-        let pattern = patterns.get(&pattern_id).unwrap_or_else(|| patterns.get("4-7-8").unwrap());
-        let durations = pattern.to_phase_durations();
+    /// At a cycle boundary, lengthen the exhale as the sleep wind-down progresses
+    fn stretch_exhale(&mut self) {
+        if self.inner.status != FfiRuntimeStatus::Running || self.inner.follow_me.is_some() {
+            return;
+        }
+        let Some(sleep) = self.inner.session.as_ref().and_then(|s| s.sleep.as_ref()) else { return };
+        let scale = sleep.exhale_scale();
+        let Some(p) = self.pattern(&self.inner.current_pattern_id) else { return };
+        let mut durations = p.to_phase_durations();
+        durations.exhale_us = (durations.exhale_us as f32 * scale) as u64;
+        self.inner.install_pacer(durations, true);
+    }
 
-        // Initialize Inner State
-        let inner = RuntimeInner {
-            engine: Engine::new(6.0),
-            phase_machine: PhaseMachine::new(durations),
-            current_pattern_id: pattern_id.clone(),
-            session: None,
-            last_timestamp_us: 0,
-            status: FfiRuntimeStatus::Idle,
-            tempo_scale: 1.0,
-            safety_locked: false,
-            last_resonance: 0.0,
+    /// Advance the sleep wind-down. Once the user is presumed asleep the session
+    /// is finalized without any event; returns true when that happened.
+    fn track_sleep(&mut self, dt_sec: f32) -> bool {
+        if self.inner.status != FfiRuntimeStatus::Running {
+            return false;
+        }
+        let Some(sleep) = self.inner.session.as_mut().and_then(|s| s.sleep.as_mut()) else {
+            return false;
         };
+        if !sleep.tick(dt_sec) {
+            return false;
+        }
+        log::info!("Sleep session: no activity for {:.0}s, presumed asleep", sleep.idle_sec);
+        self.finalize_session(false);
+        true
+    }
 
-        // Create Channels
-        let (tx, rx) = unbounded();
-        
-        // Initial State Snapshot
-        let initial_belief = get_engine_belief(&inner.engine);
-        let initial_state = FfiRuntimeState {
-            status: FfiRuntimeStatus::Idle,
-            pattern_id: pattern_id.clone(),
-            phase: FfiPhase::from(inner.phase_machine.phase.clone()),
-            phase_progress: 0.0,
-            cycles_completed: 0,
-            session_duration_sec: 0.0,
-            tempo_scale: 1.0,
-            belief: initial_belief.clone(),
-            resonance: FfiResonance { coherence_score: 0.0, phase_locking: 0.0, rhythm_alignment: 0.0 },
-            safety: FfiSafetyStatus { is_locked: false, trauma_count: 0, tempo_bounds: vec![0.8, 1.4], hr_bounds: vec![30.0, 220.0] },
+    /// Count down time-limited sessions and complete them when time is up;
+    /// returns true when the session was finalized
+    fn track_time_limit(&mut self, dt_sec: f32) -> bool {
+        if self.inner.status != FfiRuntimeStatus::Running {
+            return false;
+        }
+        let Some(remaining) = self.inner.session.as_mut().and_then(|s| s.time_limit_sec.as_mut()) else {
+            return false;
         };
-        
-        let initial_frame = FfiFrame {
-             phase: FfiPhase::from(inner.phase_machine.phase.clone()),
-             phase_progress: 0.0,
-             cycles_completed: 0,
-             heart_rate: None,
-             signal_quality: 0.0,
-             belief: initial_belief,
-             resonance: FfiResonance { coherence_score: 0.0, phase_locking: 0.0, rhythm_alignment: 0.0 },
+        *remaining -= dt_sec;
+        if *remaining > 0.0 {
+            return false;
+        }
+        self.complete_session()
+    }
+
+    /// Complete auto-complete sessions once the recommended cycles are done;
+    /// returns true when the session was finalized
+    fn track_cycle_limit(&mut self) -> bool {
+        if self.inner.status != FfiRuntimeStatus::Running {
+            return false;
+        }
+        let Some(limit) = self.inner.session.as_ref().and_then(|s| s.cycle_limit) else {
+            return false;
         };
+        if self.inner.phase_machine.cycle_index < limit {
+            return false;
+        }
+        log::info!("Session auto-completed after {} cycles", limit);
+        self.complete_session()
+    }
 
-        let state_arc = Arc::new(RwLock::new(initial_state));
-        let frame_arc = Arc::new(RwLock::new(initial_frame));
-        
-        // Initialize Safety Monitor
-        let safety = SafetyMonitor::new();
+    /// Advance goal progress and announce goals reached for the first time
+    fn track_goals(&mut self, dt_sec: f32, timestamp_us: i64) {
+        if self.inner.status != FfiRuntimeStatus::Running {
+            return;
+        }
+        let cycles = self.inner.phase_machine.cycle_index;
+        let coherence = self.hrv.spectrum.map_or(0.0, |s| s.coherence);
+        let Some(session) = &mut self.inner.session else { return };
+        for goal in session.goals.tick(dt_sec, cycles, coherence) {
+            log::info!("Session goal {:?} reached ({:.2} / {:.2})", goal.kind, goal.current, goal.target);
+            self.events.push(FfiRuntimeEvent::GoalAchieved { goal, timestamp_us });
+        }
+    }
 
-        // Channels for SignalActor
-        let (signal_cmd_tx, signal_cmd_rx) = unbounded();
-        let (signal_event_tx, signal_event_rx) = unbounded();
+    /// Emit a single PhaseEnding event per phase once the transition is near
+    fn warn_phase_ending(&mut self, timestamp_us: i64) {
+        let Some(lead_ms) = self.inner.phase_warning_lead_ms else { return };
+        if self.inner.status != FfiRuntimeStatus::Running || self.inner.phase_clock.warned {
+            return;
+        }
+        if self.inner.winding_down() {
+            return;
+        }
+        let time_to_next_phase_ms = self.inner.time_to_next_phase_ms();
+        if time_to_next_phase_ms > lead_ms {
+            return;
+        }
+        self.inner.phase_clock.warned = true;
+        let phase = FfiPhase::from(self.inner.phase_machine.phase);
+        self.events.push(FfiRuntimeEvent::PhaseEnding {
+            next_phase: self.inner.phase_clock.next_phase(phase),
+            time_to_next_phase_ms,
+            timestamp_us,
+        });
+    }
 
-        // Spawn SignalActor
-        let rppg = RppgProcessor::new(RppgMethod::Pos, 90, 30.0);
-        let signal_actor = SignalActor {
-            rppg,
-            cmd_rx: signal_cmd_rx,
-            event_tx: signal_event_tx,
-        };
-        thread::spawn(move || signal_actor.run());
-        
-        let actor = RuntimeActor {
-            inner,
-            signal_tx: signal_cmd_tx,
-            signal_rx: signal_event_rx,
-            cmd_rx: rx,
-            state_tx: state_arc.clone(),
-            latest_frame: frame_arc.clone(),
-            safety,
+    /// Count down the whole seconds left in the phase (4, 3, 2, 1). Counts come
+    /// from the pacer clock, and each event is back-dated to the instant its
+    /// second began, so late or irregular ticks never accumulate drift.
+    fn count_down_phase(&mut self, timestamp_us: i64) {
+        if !self.inner.phase_countdown || self.inner.status != FfiRuntimeStatus::Running {
+            return;
+        }
+        if self.inner.winding_down() {
+            return;
+        }
+        let phase = FfiPhase::from(self.inner.phase_machine.phase);
+        let scale = self.inner.pacer_scale();
+        let clock = &mut self.inner.phase_clock;
+        let remaining_us = clock.remaining_us(phase);
+        if remaining_us == 0 {
+            return;
+        }
+        let count = remaining_us.div_ceil(1_000_000) as u32;
+        if clock.counted == Some(count) {
+            return;
+        }
+        clock.counted = Some(count);
+        // Pacer time since this count began, never earlier than the phase start
+        let late_us = (count as u64 * 1_000_000 - remaining_us).min(clock.elapsed_us);
+        let late_wall_us = (late_us as f64 * scale as f64) as i64;
+        self.events.push(FfiRuntimeEvent::PhaseCountdown {
+            phase,
+            count,
+            timestamp_us: timestamp_us - late_wall_us,
+        });
+    }
+
+    /// When following a group leader, return the phase step that converges on
+    /// the leader's timeline. Late joiners and large drifts are re-seeded.
+    fn sync_group(&mut self, dt_us: u64) -> u64 {
+        if self.inner.status != FfiRuntimeStatus::Running {
+            return dt_us;
+        }
+        let Some(target) = self.group.leader_target() else {
+            return dt_us;
         };
+        if !target.running {
+            return dt_us;
+        }
 
-        let handle = thread::spawn(move || {
-            actor.run();
-        });
+        // A leader pattern this device does not have cannot be followed
+        if !self.patterns.contains(&target.pattern_id) {
+            if self.group.set_missing_pattern(Some(&target.pattern_id)) {
+                log::warn!("Group leader runs pattern {} which is not in the local library", target.pattern_id);
+                self.events.push(FfiRuntimeEvent::GroupPatternUnavailable { pattern_id: target.pattern_id });
+            }
+            return dt_us;
+        }
+        self.group.set_missing_pattern(None);
 
-        ZenOneRuntime {
-            cmd_tx: tx,
-            state: state_arc,
-            latest_frame: frame_arc,
-            _thread: Arc::new(Mutex::new(Some(handle))),
+        let mut reseed = false;
+        if target.pattern_id != self.inner.current_pattern_id {
+            if !self.verify_command(FfiKernelEventType::LoadPattern, Some(target.pattern_id.clone())) {
+                return dt_us;
+            }
+            self.inner.current_pattern_id = target.pattern_id.clone();
+            reseed = true;
         }
-    }
 
-    // =========================================================================
-    // PATTERN MANAGEMENT
-    // =========================================================================
+        // Error against where we would be after this tick
+        let error = target.timeline_us - (self.inner.timeline_us + dt_us as i64);
+        if reseed || error.abs() > GROUP_RESEED_THRESHOLD_US {
+            self.reseed_timeline(target.timeline_us);
+            self.group.record_error(0);
+            return 0;
+        }
+        let max_slew = (dt_us as f32 * GROUP_MAX_SLEW) as i64;
+        let correction = error.clamp(-max_slew, max_slew);
+        self.group.record_error(error - correction);
+        (dt_us as i64 + correction).max(0) as u64
+    }
 
-    /// Get all available patterns
-    pub fn get_patterns(&self) -> Vec<FfiBreathPattern> {
-        builtin_patterns()
-            .values()
-            .map(|p| FfiBreathPattern::from(p))
-            .collect()
+    /// Rebuild the phase machine at an absolute position on the pattern timeline
+    fn reseed_timeline(&mut self, timeline_us: i64) {
+        let Some(pattern) = self.pattern(&self.inner.current_pattern_id) else {
+            return;
+        };
+        self.inner.install_pacer(pattern.to_phase_durations(), false);
+        let mut remaining = timeline_us.max(0) as u64;
+        while remaining > 0 {
+            let step = remaining.min(50_000);
+            self.inner.advance_pacer(step);
+            remaining -= step;
+        }
+        self.inner.timeline_us = timeline_us.max(0);
+        self.inner.adherence.reset();
+        self.inner.resonance.reset();
+        log::info!("GroupSync: re-seeded to leader timeline at {} us", timeline_us);
     }
 
-    /// Load a pattern by ID
-    pub fn load_pattern(&self, pattern_id: String) -> bool {
-        // We assume success for async load, but we could add a reply channel if strict validation needed immediately.
-        // For S-Tier responsiveness, we trigger load and return true if ID exists.
-        if builtin_patterns().contains_key(&pattern_id) {
-             let _ = self.cmd_tx.send(RuntimeCommand::LoadPattern(pattern_id));
-             true
-        } else {
-             false
+    fn handle_set_follow_me(&mut self, enabled: bool, target_rate_bpm: f32) {
+        if !enabled {
+            if self.inner.follow_me.take().is_some() {
+                // Restore the pattern's own timing
+                self.restart_current_cycle();
+                self.update_shared_state();
+            }
+            return;
         }
+        self.inner.follow_me = Some(FollowMe::new(target_rate_bpm));
+        self.update_shared_state();
     }
 
-    /// Get current pattern ID
-    pub fn current_pattern_id(&self) -> String {
-        self.state.read().unwrap().pattern_id.clone()
+    fn handle_start_observation(&mut self, duration_sec: f32) -> Result<(), ZenOneError> {
+        if self.inner.status != FfiRuntimeStatus::Idle {
+            return Err(ZenOneError::ConfigError("Stop the session before observing free breathing".into()));
+        }
+        if !(BASELINE_MIN_SEC..=BASELINE_MAX_SEC).contains(&duration_sec) {
+            return Err(ZenOneError::ConfigError(format!(
+                "Observation must last {}-{} s, got {}",
+                BASELINE_MIN_SEC, BASELINE_MAX_SEC, duration_sec
+            )));
+        }
+        self.inner.preview = None;
+        self.inner.observation = Some(BaselineObservation::new(duration_sec));
+        Ok(())
     }
 
-    // =========================================================================
-    // SESSION MANAGEMENT
-    // =========================================================================
+    fn handle_start_noise_profiling(&mut self, duration_sec: f32) -> Result<(), ZenOneError> {
+        if self.inner.status != FfiRuntimeStatus::Idle {
+            return Err(ZenOneError::ConfigError("Stop the session before profiling room noise".into()));
+        }
+        if !(NOISE_PROFILE_MIN_SEC..=NOISE_PROFILE_MAX_SEC).contains(&duration_sec) {
+            return Err(ZenOneError::ConfigError(format!(
+                "Noise profiling must last {}-{} s, got {}",
+                NOISE_PROFILE_MIN_SEC, NOISE_PROFILE_MAX_SEC, duration_sec
+            )));
+        }
+        self.noise_profiling = Some(NoiseProfiling::new(duration_sec));
+        Ok(())
+    }
 
-    /// Start a breathing session
-    pub fn start_session(&self) -> Result<(), ZenOneError> {
-        let state = self.state.read().unwrap();
-        if state.safety.is_locked {
-             return Err(ZenOneError::SafetyViolation("Cannot start session while locked".into()));
+    /// Keep the new profile and retune (or switch off) audio detection if it was on
+    fn finish_noise_profiling(&mut self) {
+        let Some(profiling) = self.noise_profiling.take() else { return };
+        let profile = profiling.report();
+        log::info!(
+            "Noise profile: floor {:.3}, sd {:.3}, usable {}",
+            profile.noise_floor, profile.noise_sd, profile.usable
+        );
+        if self.audio_threshold.is_some() {
+            self.audio_threshold = profile.usable.then_some(profile.threshold);
         }
-        drop(state);
+        *self.noise_profile.lock() = Some(profile.clone());
+        self.events.push(FfiRuntimeEvent::NoiseProfileReady { profile });
+    }
 
-        let _ = self.cmd_tx.send(RuntimeCommand::StartSession);
+    fn handle_start_calibration(&mut self, device_id: String, reference: FfiCalibrationReference) -> Result<(), ZenOneError> {
+        if !self.rppg_enabled {
+            return Err(ZenOneError::ConfigError("Camera heart rate is disabled on this device".into()));
+        }
+        if device_id.trim().is_empty() {
+            return Err(ZenOneError::ConfigError("Device id must not be empty".into()));
+        }
+        self.calibration = Some(CalibrationRun::new(device_id, reference));
         Ok(())
     }
 
-    /// Stop session and get stats
-    pub fn stop_session(&self) -> FfiSessionStats {
-        let (tx, rx) = crossbeam_channel::bounded(1);
-        let _ = self.cmd_tx.send(RuntimeCommand::StopSession(tx));
-        
-        // Wait for stats (blocking for this call is expected behavior for stop_session)
-        // But the Engine loop finishes quickly so it's fine.
-        rx.recv().unwrap_or(FfiSessionStats {
-             duration_sec: 0.0,
-             cycles_completed: 0,
-             pattern_id: "".into(),
-             avg_heart_rate: None,
-             final_belief: self.get_belief(),
-             avg_resonance: 0.0,
-        })
+    /// Fit the correction, save it for the device and apply it right away
+    fn handle_finish_calibration(&mut self) -> Result<FfiRppgCalibration, ZenOneError> {
+        let run = self.calibration.take()
+            .ok_or_else(|| ZenOneError::ConfigError("No calibration in progress".into()))?;
+        let calibration = run.finish()?;
+        log::info!(
+            "rPPG calibration for {}: x{:.2} {:+.1} bpm, error {:.1} -> {:.1} bpm",
+            calibration.device_id, calibration.scale, calibration.offset_bpm,
+            calibration.error_before_bpm, calibration.error_after_bpm
+        );
+        self.calibrations.save(calibration.clone())?;
+        let _ = self.signal_tx.send(SignalCommand::SetCalibration(Some(calibration.clone())));
+        Ok(calibration)
     }
 
-    /// Check if session is active
-    pub fn is_session_active(&self) -> bool {
-        // We can infer from status inside the shared state
-        let state = self.state.read().unwrap();
-        state.status == FfiRuntimeStatus::Running || state.status == FfiRuntimeStatus::Paused
+    /// End the observation early, keeping the baseline if enough breaths were seen
+    fn handle_stop_observation(&mut self) -> Result<FfiBreathingBaseline, ZenOneError> {
+        let observation = self.inner.observation.take()
+            .ok_or_else(|| ZenOneError::ConfigError("No observation in progress".into()))?;
+        let baseline = observation.report().map_err(ZenOneError::ConfigError)?;
+        *self.baseline.lock() = Some(baseline.clone());
+        Ok(baseline)
     }
 
-    /// Pause session
-    pub fn pause_session(&self) {
-        let _ = self.cmd_tx.send(RuntimeCommand::PauseSession);
+    fn finish_observation(&mut self) {
+        let Some(observation) = self.inner.observation.take() else { return };
+        match observation.report() {
+            Ok(baseline) => {
+                log::info!("Breathing baseline: {:.1} bpm, variability {:.2}", baseline.rate_bpm, baseline.variability);
+                *self.baseline.lock() = Some(baseline.clone());
+                self.events.push(FfiRuntimeEvent::BaselineReady { baseline });
+            }
+            Err(reason) => self.events.push(FfiRuntimeEvent::BaselineFailed { reason }),
+        }
     }
 
-    /// Resume paused session
-    pub fn resume_session(&self) {
-        let _ = self.cmd_tx.send(RuntimeCommand::ResumeSession);
+    /// At a cycle boundary, cue the user when their measured breathing rate
+    /// leaves the target band around the pacer's rate
+    fn coach_breath_rate(&mut self, timestamp_us: i64) {
+        // Follow-me deliberately starts at the user's own pace
+        if self.inner.winding_down() || self.inner.follow_me.is_some() {
+            return;
+        }
+        let (Some(detected), Some(prescribed)) = (self.inner.breath_rate.rate_bpm(), self.inner.prescribed_rate_bpm()) else {
+            return;
+        };
+        let band = self.breath_rate_band.lock().clone();
+        if let Some(cue) = self.coaching.evaluate_rate(detected, prescribed, &band, timestamp_us) {
+            self.events.push(FfiRuntimeEvent::Coaching { cue });
+        }
     }
 
-    /// Reset safety lock
-    pub fn reset_safety_lock(&self) {
-        let _ = self.cmd_tx.send(RuntimeCommand::ResetSafetyLock);
+    /// At a cycle boundary, move the follow-me pacer toward the user's rate
+    /// and then gently below it
+    fn follow_breath(&mut self, cycle_score: Option<f32>) {
+        if self.inner.status != FfiRuntimeStatus::Running || self.group.leader_target().is_some() {
+            return;
+        }
+        // Without a live respiration signal, lock onto the measured natural pace
+        let detected = self.inner.breath_rate.rate_bpm()
+            .or_else(|| self.baseline.lock().as_ref().map(|b| b.rate_bpm));
+        let Some(follow) = &mut self.inner.follow_me else { return };
+        if let Some(rate) = follow.on_cycle(detected, cycle_score) {
+            self.apply_pacer_rate(rate);
+        }
     }
 
-    // =========================================================================
-    // FRAME PROCESSING (Main update loop)
-    // =========================================================================
-
-    /// Process a camera frame and update state
-    pub fn process_frame(&self, r: f32, g: f32, b: f32, timestamp_us: i64) -> FfiFrame {
-        // Fire and forget - NON-BLOCKING
-        let _ = self.cmd_tx.send(RuntimeCommand::ProcessFrame { r, g, b, timestamp_us });
-        
-        // Return latest available frame immediately
-        self.latest_frame.read().unwrap().clone()
+    /// Stretch the current pattern's phase proportions to a breathing rate
+    fn apply_pacer_rate(&mut self, rate_bpm: f32) {
+        let Some(p) = self.pattern(&self.inner.current_pattern_id) else { return };
+        let t = &p.timings;
+        let total = t.inhale + t.hold_in + t.exhale + t.hold_out;
+        if total <= 0.0 {
+            return;
+        }
+        let cycle_us = 60_000_000.0 / rate_bpm;
+        let scaled = |sec: f32| (cycle_us * sec / total) as u64;
+        let durations = PhaseDurations {
+            inhale_us: scaled(t.inhale),
+            hold_in_us: scaled(t.hold_in),
+            exhale_us: scaled(t.exhale),
+            hold_out_us: scaled(t.hold_out),
+        };
+        self.inner.install_pacer(durations, true);
     }
 
-    /// Tick without camera (timer-based update)
-    pub fn tick(&self, dt_sec: f32, timestamp_us: i64) -> FfiFrame {
-        let _ = self.cmd_tx.send(RuntimeCommand::Tick { dt_sec, timestamp_us });
-        self.latest_frame.read().unwrap().clone()
+    /// At a cycle boundary, let the arc planner propose a better-suited pattern
+    fn plan_arc(&mut self, timestamp_us: i64) {
+        if self.inner.status != FfiRuntimeStatus::Running || self.inner.safety_locked {
+            return;
+        }
+        // Follow-me and sleep wind-down own the pacer timing; switching patterns would fight them
+        if self.inner.follow_me.is_some() || self.inner.winding_down() {
+            return;
+        }
+        // Panic sessions and sequences stay on their patterns
+        if self.inner.session.as_ref().is_some_and(|s| s.panic) || self.inner.sequence.is_some() {
+            return;
+        }
+        let belief = self.belief();
+        let local_hour = self.store.zone().local_time(self.clock.now_ms()).hour;
+        let cycle = self.inner.phase_machine.cycle_index;
+        let Some(mut change) = self.planner.observe_cycle(
+            &belief,
+            &self.inner.current_pattern_id,
+            local_hour,
+            cycle,
+            timestamp_us,
+        ) else {
+            return;
+        };
+
+        // The transition must pass the same safety gate as a manual load
+        if !self.verify_command(FfiKernelEventType::LoadPattern, Some(change.to_pattern.clone())) {
+            return;
+        }
+        let Some(pattern) = self.pattern(&change.to_pattern) else {
+            return;
+        };
+        // Ease into the new timing over a few cycles, counting cycles across the switch
+        match self.pattern(&change.from_pattern) {
+            Some(from) => {
+                self.inner.morph = Some(PatternMorph { from, to: pattern, step: 0, cycles: ARC_MORPH_CYCLES });
+                self.advance_morph();
+            }
+            None => self.inner.install_pacer(pattern.to_phase_durations(), true),
+        }
+        self.inner.current_pattern_id = change.to_pattern.clone();
+        self.inner.adherence.reset();
+        self.inner.resonance.reset();
+        self.planner.commit(&change);
+
+        change.timestamp_us = timestamp_us;
+        log::info!(
+            "ArcPlanner: {} -> {} ({})",
+            change.from_pattern, change.to_pattern, change.reason
+        );
+        self.events.push(FfiRuntimeEvent::PlanChange { change });
     }
 
-    // =========================================================================
-    // STATE QUERIES
-    // =========================================================================
+    /// Step an in-flight transition one cycle closer to its target pattern
+    fn advance_morph(&mut self) {
+        let Some(mut morph) = self.inner.morph.take() else { return };
+        morph.step += 1;
+        self.inner.install_pacer(morph.current().to_phase_durations(), true);
+        if morph.step < morph.cycles {
+            self.inner.morph = Some(morph);
+        }
+    }
 
-    /// Get full runtime state snapshot
-    pub fn get_state(&self) -> FfiRuntimeState {
-        self.state.read().unwrap().clone()
+    fn handle_preview_pattern(&mut self, id: String, cycles: u32) {
+        if self.inner.status != FfiRuntimeStatus::Idle {
+            return;
+        }
+        if let Some(p) = self.pattern(&id) {
+            self.inner.preview = Some(PatternPreview {
+                machine: PhaseMachine::new(p.to_phase_durations()),
+                pattern_id: id,
+                cycles,
+            });
+            self.update_latest_frame();
+        }
     }
 
-    /// Get current belief state
-    /// Get current belief state
-    pub fn get_belief(&self) -> FfiBeliefState {
-        self.state.read().unwrap().belief.clone()
+    /// Feed beats from the built-in generator to the simulated source. Outside
+    /// a session the generator breathes freely at a resting rate.
+    fn simulate_heart_rate(&mut self, timestamp_us: i64) {
+        let paced = self.inner.paced_volume().zip(self.inner.pacer_rate_bpm());
+        let Some(simulator) = &mut self.hr_simulator else { return };
+        let (volume, rate_bpm) = paced.unwrap_or_else(|| {
+            let cycle = timestamp_us as f32 / 1_000_000.0 * HR_SIMULATOR_FREE_BREATHING_BPM / 60.0;
+            (0.5 - 0.5 * (cycle * std::f32::consts::TAU).cos(), HR_SIMULATOR_FREE_BREATHING_BPM)
+        });
+        let rr_intervals_ms = simulator.advance(volume, rate_bpm, timestamp_us);
+        if rr_intervals_ms.is_empty() {
+            return;
+        }
+        let bpm = 60_000.0 * rr_intervals_ms.len() as f32 / rr_intervals_ms.iter().sum::<f32>();
+        let input = HrInput::Simulated { bpm, rr_intervals_ms, timestamp_us };
+        let _ = self.signal_tx.send(SignalCommand::Ingest(input));
+    }
+
+    fn handle_process_frame(&mut self, r: f32, g: f32, b: f32, timestamp_us: i64) {
+        if !self.rppg_enabled || !self.flags.is_enabled(FfiFeatureFlag::Rppg) {
+            return;
+        }
+        self.research.record_sample(timestamp_us, r, g, b);
+
+        // Offload to SignalActor - NON-BLOCKING
+        let _ = self.signal_tx.send(SignalCommand::Ingest(HrInput::Camera { r, g, b, timestamp_us }));
     }
     
-    /// Get safety status
-    pub fn get_safety_status(&self) -> FfiSafetyStatus {
-        self.state.read().unwrap().safety.clone()
+    fn handle_respiration_sample(&mut self, value: f32, source: FfiRespirationSource, timestamp_us: i64) {
+        let value = if source == FfiRespirationSource::Audio {
+            if let Some(profiling) = &mut self.noise_profiling {
+                profiling.observe(value);
+                return;
+            }
+            // Flatten everything at or below the room's noise level
+            let Some(threshold) = self.audio_threshold else { return };
+            (value - threshold).max(0.0)
+        } else {
+            value
+        };
+        if let Some(observation) = &mut self.inner.observation {
+            observation.observe(value, source, timestamp_us);
+            return;
+        }
+        if let Some(cool_down) = self.inner.session.as_mut().and_then(|s| s.stages.cool_down.as_mut()) {
+            cool_down.observe(value, source, timestamp_us);
+            return;
+        }
+        if self.inner.status != FfiRuntimeStatus::Running {
+            return;
+        }
+        let phase = FfiPhase::from(self.inner.phase_machine.phase);
+        self.inner.adherence.observe(value, source, timestamp_us, phase);
+        self.inner.breath_rate.observe(value, source, timestamp_us);
+
+        if self.inner.winding_down() {
+            return;
+        }
+        if let Some(cue) = self.coaching.evaluate_phase(&self.inner.adherence, timestamp_us) {
+            self.events.push(FfiRuntimeEvent::Coaching { cue });
+        }
     }
 
-    // =========================================================================
-    // CONTROL ACTIONS
-    // =========================================================================
+    fn handle_tick(&mut self, dt_sec: f32, timestamp_us: i64) {
+        let dt_us = (dt_sec * 1_000_000.0) as u64;
+        self.inner.last_timestamp_us = timestamp_us;
+        self.simulate_heart_rate(timestamp_us);
 
-    /// Adjust tempo scale (with safety bounds)
-    pub fn adjust_tempo(&self, scale: f32, reason: String) -> Result<f32, ZenOneError> {
-        // Validation happens on calling thread for immediate feedback
-        const MIN_TEMPO: f32 = 0.8;
-        const MAX_TEMPO: f32 = 1.4;
+        if let Some(observation) = &mut self.inner.observation {
+            observation.elapsed_sec += dt_sec;
+            if observation.elapsed_sec >= observation.duration_sec {
+                self.finish_observation();
+            }
+        }
+        if let Some(profiling) = &mut self.noise_profiling {
+            profiling.elapsed_sec += dt_sec;
+            if profiling.elapsed_sec >= profiling.duration_sec {
+                self.finish_noise_profiling();
+            }
+        }
 
-        let clamped = scale.clamp(MIN_TEMPO, MAX_TEMPO);
-        if (clamped - scale).abs() > 0.001 {
-            log::warn!("Tempo {} clamped to {} (reason: {})", scale, clamped, reason);
+        // Preview only animates its own phase machine: no engine, safety or stats
+        if let Some(preview) = &mut self.inner.preview {
+            preview.machine.tick(dt_us);
+            if preview.machine.cycle_index >= preview.cycles as u64 {
+                let pattern_id = preview.pattern_id.clone();
+                self.inner.preview = None;
+                self.events.push(FfiRuntimeEvent::PreviewFinished { pattern_id });
+            }
+            self.update_latest_frame();
+            return;
         }
 
-        let _ = self.cmd_tx.send(RuntimeCommand::AdjustTempo(clamped));
-        // We implicitly assume success. S-Tier: Don't wait.
-        Ok(clamped)
-    }
+        if self.inner.status == FfiRuntimeStatus::Preparing {
+            self.inner.engine.tick(dt_us);
+            // The countdown runs on the kernel clock, like the pacer it leads into
+            let (ready, count) = match &mut self.inner.preparation {
+                Some(prep) => {
+                    prep.advance(dt_sec);
+                    (prep.is_ready(), prep.next_count())
+                }
+                None => (false, None),
+            };
+            if let Some(seconds_left) = count {
+                self.events.push(FfiRuntimeEvent::Countdown { seconds_left, timestamp_us });
+            }
+            if ready {
+                self.begin_session();
+            } else {
+                self.publish_tick_state();
+                self.update_latest_frame();
+            }
+            return;
+        }
 
-    /// Update context (time of day, charging status, etc.)
-    pub fn update_context(&self, local_hour: u8, is_charging: bool, recent_sessions: u16) {
-        let _ = self.cmd_tx.send(RuntimeCommand::UpdateContext {
-            local_hour,
-            is_charging,
-            recent_sessions,
-        });
-    }
+        // The pacer is frozen while interrupted; give up on very long gaps
+        if self.inner.status == FfiRuntimeStatus::Paused {
+            self.inner.engine.tick(dt_us);
+            let expired = self.inner.session.as_ref()
+                .and_then(|s| s.interrupted)
+                .is_some_and(|(_, since)| ResumePolicy::decide(self.clock.since(since)) == FfiResumeAction::Finalize);
+            if expired {
+                let stats = self.finalize_session(true);
+                self.events.push(FfiRuntimeEvent::SessionAutoFinalized { stats });
+            }
+            self.publish_tick_state();
+            self.update_latest_frame();
+            return;
+        }
 
+        // The pacer stops for the cool-down while natural breathing is observed
+        if self.inner.cooling_down() {
+            self.inner.engine.tick(dt_us);
+            let Some(session) = &mut self.inner.session else { return };
+            session.stages.tick(dt_sec);
+            if session.stages.cool_down_done() {
+                let stats = self.finalize_session(false);
+                self.events.push(FfiRuntimeEvent::SessionCompleted { stats });
+                return;
+            }
+            self.publish_tick_state();
+            self.update_latest_frame();
+            return;
+        }
+        if let Some(stage) = self.inner.session.as_mut().and_then(|s| s.stages.tick(dt_sec)) {
+            self.events.push(FfiRuntimeEvent::StageStarted { stage, timestamp_us });
+        }
 
+        // Tempo stretches (> 1) or compresses (< 1) every phase duration;
+        // a warm-up starts slower still
+        self.inner.tempo_scale = ramp_tempo(self.inner.tempo_scale, self.inner.tempo_target, dt_sec);
+        let phase_dt_us = self.sync_group(scaled_phase_dt(dt_us, self.inner.pacer_scale()));
+        let prev_cycle = self.inner.phase_machine.cycle_index;
+        self.inner.advance_pacer(phase_dt_us);
+        self.inner.engine.tick(dt_us);
+        self.inner.timeline_us += phase_dt_us as i64;
+        self.warn_phase_ending(timestamp_us);
+        self.count_down_phase(timestamp_us);
+        self.group.publish(
+            &self.inner.current_pattern_id,
+            self.inner.status == FfiRuntimeStatus::Running,
+            self.inner.timeline_us,
+        );
+        let phase = FfiPhase::from(self.inner.phase_machine.phase);
+        self.serial.publish(phase, self.inner.phase_clock.progress(phase), self.inner.phase_machine.cycle_index);
 
-    /// Emergency halt
-    pub fn emergency_halt(&self, reason: String) {
-        let _ = self.cmd_tx.send(RuntimeCommand::EmergencyHalt(reason));
+        if self.inner.phase_machine.cycle_index > prev_cycle {
+            self.inner.resonance.finish_cycle();
+            let cycle_score = self.inner.adherence.finish_cycle();
+            if let Some(score) = cycle_score {
+                if let Some(session) = &mut self.inner.session {
+                    session.cycle_adherence.push(score);
+                    // No coaching while the user drifts off
+                    let cue = match session.sleep {
+                        Some(_) => None,
+                        None => self.coaching.evaluate_cycles(&session.cycle_adherence, timestamp_us),
+                    };
+                    if let Some(cue) = cue {
+                        self.events.push(FfiRuntimeEvent::Coaching { cue });
+                    }
+                }
+            }
+            self.coach_breath_rate(timestamp_us);
+            self.follow_breath(cycle_score);
+            self.advance_morph();
+            self.plan_arc(timestamp_us);
+            self.stretch_exhale();
+        }
+        self.track_hr_zone(dt_sec, timestamp_us);
+        self.track_goals(dt_sec, timestamp_us);
+        if self.track_sleep(dt_sec)
+            || self.track_time_limit(dt_sec)
+            || self.track_sequence(dt_sec, prev_cycle)
+            || self.track_cycle_limit()
+        {
+            return;
+        }
+        if let (Some(state), Some(session)) = (self.inner.binaural_state, &mut self.inner.session) {
+            session.binaural_sec[state.index()] += dt_sec;
+        }
+        
+        self.publish_tick_state();
+        self.update_latest_frame();
     }
 }
 
-// ============================================================================
-// PID CONTROLLER - FEEDBACK CONTROL
-// ============================================================================
+#[cfg(all(test, feature = "full"))]
+mod frame_channel_tests {
+    use super::*;
 
-/// PID controller configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FfiPidConfig {
-    pub kp: f32,                // Proportional gain
-    pub ki: f32,                // Integral gain
-    pub kd: f32,                // Derivative gain
-    pub integral_max: f32,      // Anti-windup max integral
-    pub output_min: f32,        // Min output
-    pub output_max: f32,        // Max output
-    pub derivative_alpha: f32,  // Derivative filter (0-1)
-}
+    fn running_session() -> (ZenOneRuntime, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(1_704_096_000_000));
+        let runtime = ZenOneRuntime::with_clock("4-7-8".into(), clock.clone());
+        runtime.set_preparation_config(FfiPreparationConfig { countdown_sec: 0.0, min_signal_quality: 0.0 });
+        runtime.set_hr_source(FfiHrSourceKind::Simulated);
+        runtime.start_session().unwrap();
+        runtime.flush();
+        (runtime, clock)
+    }
 
-impl Default for FfiPidConfig {
-    fn default() -> Self {
-        Self {
-            kp: 0.003,
-            ki: 0.0002,
-            kd: 0.008,
-            integral_max: 5.0,
-            output_min: -0.6,
-            output_max: 0.4,
-            derivative_alpha: 0.15,
-        }
+    fn heart_rate_sample(runtime: &ZenOneRuntime, bpm: f32, timestamp_us: i64) {
+        let rr_intervals_ms = vec![60_000.0 / bpm];
+        let _ = runtime.cmd_tx.send(RuntimeCommand::SimulatedHrSample { bpm, rr_intervals_ms, timestamp_us });
+        runtime.flush();
     }
-}
 
-/// PID diagnostics for monitoring
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FfiPidDiagnostics {
-    pub p_term: f32,
-    pub i_term: f32,
-    pub d_term: f32,
-    pub integral: f32,
-    pub total: f32,
-}
+    fn tick(runtime: &ZenOneRuntime, clock: &ManualClock, timestamp_us: i64) {
+        clock.advance(Duration::from_millis(100));
+        runtime.tick(0.1, timestamp_us);
+        runtime.flush();
+    }
 
-/// PID Controller with anti-windup and derivative filtering
-/// 
-/// References:
-/// - Åström & Murray (2021): "Feedback Systems"
-/// - Franklin et al. (2015): "Feedback Control of Dynamic Systems"
-pub struct PidController {
-    inner: Mutex<PidControllerInner>,
+    #[test]
+    fn tick_after_heart_rate_sample_keeps_it() {
+        let (runtime, clock) = running_session();
+        heart_rate_sample(&runtime, 72.0, 1_000_000);
+        let hr = runtime.get_frame_lite().heart_rate.unwrap();
+        assert!((hr - 72.0).abs() < 1.0, "{}", hr);
+
+        tick(&runtime, &clock, 1_100_000);
+        tick(&runtime, &clock, 1_200_000);
+        assert_eq!(runtime.get_frame_lite().heart_rate, Some(hr));
+        let frame = runtime.frame_snapshot();
+        assert_eq!(frame.heart_rate, Some(hr));
+        assert!(frame.signal_quality > 0.0);
+    }
+
+    #[test]
+    fn heart_rate_clears_when_stale_or_stopped() {
+        let (runtime, clock) = running_session();
+        heart_rate_sample(&runtime, 72.0, 1_000_000);
+        clock.advance(HR_FRAME_STALE);
+        tick(&runtime, &clock, 6_100_000);
+        assert_eq!(runtime.get_frame_lite().heart_rate, None);
+        assert_eq!(runtime.frame_snapshot().signal_quality, 0.0);
+
+        heart_rate_sample(&runtime, 72.0, 6_200_000);
+        assert!(runtime.get_frame_lite().heart_rate.is_some());
+        runtime.stop_session();
+        runtime.flush();
+        assert_eq!(runtime.get_frame_lite().heart_rate, None);
+        assert_eq!(runtime.frame_snapshot().signal_quality, 0.0);
+    }
 }
 
-struct PidControllerInner {
-    config: FfiPidConfig,
-    integral: f32,
-    last_error: f32,
-    last_derivative: f32,
-    last_p: f32,
-    last_i: f32,
-    last_d: f32,
+/// ZenOne Runtime - Full Engine API for native apps
+#[cfg(feature = "full")]
+pub struct ZenOneRuntime {
+    cmd_tx: Sender<RuntimeCommand>,
+    state: Arc<ArcSwap<FfiRuntimeState>>,
+    state_stamps: Arc<ArcSwap<StateStamps>>,
+    latest_frame: Arc<ArcSwap<FfiFrame>>,
+    frame_lite: Arc<ArcSwap<FfiFrameLite>>,
+    store: Arc<SessionStore>,
+    research: Arc<ResearchRecorder>,
+    consent: Arc<ConsentRegistry>,
+    experiments: Arc<ExperimentManager>,
+    events: Arc<EventQueue>,
+    listener: SharedListener,
+    group: Arc<GroupSync>,
+    serial: Arc<SerialPacer>,
+    journal: Arc<SafetyJournal>,
+    hr_zones: Arc<Mutex<FfiHrZoneConfig>>,
+    breath_rate_band: Arc<Mutex<FfiBreathRateBand>>,
+    hr_filter: Mutex<FfiHrFilterConfig>,
+    /// Shared with the actor, for dry-run spec evaluation
+    safety: Arc<SafetyMonitor>,
+    watch: WatchLink,
+    pipeline: Mutex<Option<FfiPipelineProfile>>,
+    rppg_method: Mutex<FfiRppgMethod>,
+    hr_source: Mutex<FfiHrSourceKind>,
+    rppg_config: Arc<Mutex<FfiRppgConfig>>,
+    roi_provider: Mutex<Option<Box<dyn RoiProvider>>>,
+    ppg_spectrum: Arc<Mutex<Option<FfiPpgSpectrum>>>,
+    ppg_trace: Arc<Mutex<PpgTrace>>,
+    streak_config: Mutex<FfiStreakConfig>,
+    baseline: Arc<Mutex<Option<FfiBreathingBaseline>>>,
+    patterns: Arc<PatternLibrary>,
+    adjustments: Arc<PatternAdjustments>,
+    pattern_change: Mutex<PatternChangeSlot>,
+    escalation: Arc<EscalationPolicy>,
+    calibrations: Arc<RppgCalibrationStore>,
+    noise_profile: Arc<Mutex<Option<FfiNoiseProfile>>>,
+    flags: Arc<FeatureFlags>,
+    settings: UserSettings,
+    belief_modes: Arc<BeliefModeRegistry>,
+    data_dir: Mutex<Option<std::path::PathBuf>>,
+    clock: Arc<dyn Clock>,
+    recorder: Arc<CommandRecorder>,
+    // Actor thread, joined by `shutdown` (or on drop)
+    thread: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
 }
 
-impl PidController {
+#[cfg(feature = "full")]
+impl ZenOneRuntime {
+    /// Create a new runtime with default pattern (4-7-8)
     pub fn new() -> Self {
-        Self::with_config(FfiPidConfig::default())
+        Self::with_pattern("4-7-8".to_string())
     }
-    
-    pub fn with_config(config: FfiPidConfig) -> Self {
-        Self {
-            inner: Mutex::new(PidControllerInner {
-                config,
-                integral: 0.0,
-                last_error: 0.0,
-                last_derivative: 0.0,
-                last_p: 0.0,
-                last_i: 0.0,
-                last_d: 0.0,
-            }),
-        }
+
+    /// Create with specific pattern
+    pub fn with_pattern(pattern_id: String) -> Self {
+        Self::with_clock(pattern_id, Arc::new(SystemClock))
     }
-    
-    /// Compute control output
-    /// 
-    /// # Arguments
-    /// * `error` - Current error (setpoint - measurement)
-    /// * `dt` - Time step in seconds
-    /// 
-    /// # Returns
-    /// Control signal (clamped to output bounds)
-    pub fn compute(&self, error: f32, dt: f32) -> f32 {
-        let mut inner = self.inner.lock();
+
+    /// Create with a custom time source, e.g. a `ManualClock` for
+    /// deterministic tests and replays (Rust callers only)
+    pub fn with_clock(pattern_id: String, clock: Arc<dyn Clock>) -> Self {
+        log::info!("ZenOneRuntime: Initializing with pattern {}", pattern_id);
         
-        if dt <= 0.0 || !dt.is_finite() {
-            return 0.0;
-        }
+        let patterns = builtin_patterns();
+        let pattern = patterns.get(&pattern_id).unwrap_or_else(|| patterns.get("4-7-8").unwrap());
+        let durations = pattern.to_phase_durations();
+        let phase_clock = PhaseClock::new(&durations);
+
+        // Initialize Inner State
+        let inner = RuntimeInner {
+            engine: Engine::new(6.0),
+            phase_machine: PhaseMachine::new(durations),
+            phase_clock,
+            phase_warning_lead_ms: None,
+            phase_countdown: false,
+            binaural_state: None,
+            state_publish_interval: None,
+            last_state_publish: None,
+            current_pattern_id: pattern_id.clone(),
+            session: None,
+            last_timestamp_us: 0,
+            status: FfiRuntimeStatus::Idle,
+            auto_complete: false,
+            tempo_scale: 1.0,
+            tempo_target: 1.0,
+            safety_locked: false,
+            resonance: ResonanceTracker::new(),
+            adherence: AdherenceTracker::new(),
+            timeline_us: 0,
+            preparation_config: FfiPreparationConfig::default(),
+            preparation: None,
+            stages_config: FfiSessionStagesConfig::default(),
+            pending_goals: FfiSessionGoals::default(),
+            pending_sleep: None,
+            preview: None,
+            follow_me: None,
+            sequence: None,
+            morph: None,
+            transition_cycles: 0,
+            breath_rate: BreathRateEstimator::new(),
+            observation: None,
+        };
+
+        // Create Channels
+        let (tx, rx) = unbounded();
         
-        // 1. PROPORTIONAL TERM
-        inner.last_p = inner.config.kp * error;
+        // Initial State Snapshot
+        let initial_belief = get_engine_belief(&inner.engine);
+        let initial_state = FfiRuntimeState {
+            status: FfiRuntimeStatus::Idle,
+            pattern_id: pattern_id.clone(),
+            phase: FfiPhase::from(inner.phase_machine.phase.clone()),
+            phase_progress: 0.0,
+            cycles_completed: 0,
+            session_duration_sec: 0.0,
+            tempo_scale: 1.0,
+            belief: initial_belief.clone(),
+            resonance: FfiResonance { coherence_score: 0.0, phase_locking: 0.0, rhythm_alignment: 0.0, rsa_amplitude_bpm: 0.0 },
+            safety: FfiSafetyStatus { is_locked: false, trauma_count: 0, tempo_bounds: vec![0.8, 1.4], hr_bounds: vec![30.0, 220.0], cooldown_remaining_sec: 0.0, unacknowledged_critical: 0 },
+            preparation: None,
+            follow_me: None,
+            sequence: None,
+            stage: None,
+            sleep: None,
+        };
         
-        // 2. INTEGRAL TERM (with anti-windup)
-        inner.integral += error * dt;
-        inner.integral = inner.integral.clamp(
-            -inner.config.integral_max,
-            inner.config.integral_max
-        );
-        inner.last_i = inner.config.ki * inner.integral;
-        
-        // 3. DERIVATIVE TERM (with filtering)
-        let raw_derivative = (error - inner.last_error) / dt;
-        inner.last_derivative = inner.config.derivative_alpha * raw_derivative
-            + (1.0 - inner.config.derivative_alpha) * inner.last_derivative;
-        inner.last_d = inner.config.kd * inner.last_derivative;
-        
-        // 4. COMBINE
-        let output = inner.last_p + inner.last_i + inner.last_d;
+        let initial_frame = FfiFrame {
+             phase: FfiPhase::from(inner.phase_machine.phase.clone()),
+             phase_progress: 0.0,
+             cycles_completed: 0,
+             heart_rate: None,
+             signal_quality: 0.0,
+             belief: initial_belief,
+             resonance: FfiResonance { coherence_score: 0.0, phase_locking: 0.0, rhythm_alignment: 0.0, rsa_amplitude_bpm: 0.0 },
+             adherence: None,
+             prescribed_rate_bpm: inner.prescribed_rate_bpm(),
+             detected_rate_bpm: None,
+             time_to_next_phase_ms: Some(inner.time_to_next_phase_ms()),
+             countdown: None,
+             goals: Vec::new(),
+             hrv: None,
+             hrv_spectrum: None,
+             respiratory_rate_bpm: None,
+             raw_heart_rate: None,
+             hr_filter_confidence: None,
+             signal_snr_db: None,
+             perfusion_index: None,
+        };
+
+        let state_arc = Arc::new(ArcSwap::from_pointee(initial_state));
+        let frame_lite = Arc::new(ArcSwap::from_pointee(FfiFrameLite {
+            phase: initial_frame.phase,
+            phase_progress: initial_frame.phase_progress,
+            heart_rate: None,
+        }));
+        let frame_arc = Arc::new(ArcSwap::from_pointee(initial_frame));
         
-        // 5. CLAMP OUTPUT
-        let clamped = output.clamp(inner.config.output_min, inner.config.output_max);
+        // Initialize Safety Monitor
+        let safety = Arc::new(SafetyMonitor::with_clock(clock.clone()));
+        let store = Arc::new(SessionStore::new());
+        let consent = Arc::new(ConsentRegistry::with_clock(clock.clone()));
+        let research = Arc::new(ResearchRecorder::new(consent.clone()));
+        let events = Arc::new(EventQueue::new());
+        let listener: SharedListener = Arc::new(Mutex::new(None));
+        let state_stamps = Arc::new(ArcSwap::from_pointee(StateStamps::new()));
+        let group = Arc::new(GroupSync::new());
+        let serial = Arc::new(SerialPacer::new());
+        let journal = Arc::new(SafetyJournal::new());
+        let hr_zones = Arc::new(Mutex::new(FfiHrZoneConfig::default()));
+        let breath_rate_band = Arc::new(Mutex::new(FfiBreathRateBand::default()));
+        let baseline = Arc::new(Mutex::new(None));
+        let patterns = Arc::new(PatternLibrary::new());
+        let belief_modes = Arc::new(BeliefModeRegistry::new());
+        let adjustments = Arc::new(PatternAdjustments::new());
+        let escalation = Arc::new(EscalationPolicy::new(consent.clone()));
+        let calibrations = Arc::new(RppgCalibrationStore::new());
+        let noise_profile = Arc::new(Mutex::new(None));
+        let flags = Arc::new(FeatureFlags::new());
+        let recorder = Arc::new(CommandRecorder::new());
+
+        // Channels for SignalActor
+        let (signal_cmd_tx, signal_cmd_rx) = unbounded();
+        let (signal_event_tx, signal_event_rx) = unbounded();
+
+        // Spawn SignalActor
+        let rppg_config = Arc::new(Mutex::new(FfiRppgConfig::default()));
+        let ppg_spectrum = Arc::new(Mutex::new(None));
+        let ppg_trace = Arc::new(Mutex::new(PpgTrace::new()));
+        let rppg = RppgProcessor::new(FfiRppgMethod::default().into(), 90, 30.0);
+        let camera = CameraSource {
+            rppg,
+            beats: BeatDetector::new(30.0),
+            respiration: PpgRespiration::default(),
+            hr_filter: HrKalmanFilter::new(FfiHrFilterConfig::default()),
+            quality: SignalQualityAnalyzer::new(30.0),
+            method: FfiRppgMethod::default(),
+            window_samples: 90,
+            sample_rate_hz: 30.0,
+            frame_rate: FrameRateMonitor::default(),
+            config: rppg_config.clone(),
+            spectrum: ppg_spectrum.clone(),
+            trace: ppg_trace.clone(),
+            calibration: None,
+        };
+        let signal_actor = SignalActor {
+            camera,
+            strap: StrapSource::default(),
+            manual: ManualSource,
+            simulated: SimulatedSource,
+            selected: FfiHrSourceKind::default(),
+            cmd_rx: signal_cmd_rx,
+            event_tx: signal_event_tx,
+        };
+        let signal_events = events.clone();
+        let signal_thread = thread::spawn(move || signal_actor.supervise(signal_events));
         
-        // Update state
-        inner.last_error = error;
+        let notified = ListenerMark::new(&inner);
+        let actor = RuntimeActor {
+            inner,
+            signal_tx: signal_cmd_tx,
+            signal_rx: signal_event_rx,
+            signal_thread: Some(signal_thread),
+            cmd_rx: rx,
+            state_tx: state_arc.clone(),
+            state_stamps: state_stamps.clone(),
+            latest_frame: frame_arc.clone(),
+            frame_lite: frame_lite.clone(),
+            safety: safety.clone(),
+            store: store.clone(),
+            research: research.clone(),
+            events: events.clone(),
+            listener: listener.clone(),
+            notified,
+            coaching: CoachingEngine::new(),
+            planner: ArcPlanner::new(),
+            group: group.clone(),
+            serial: serial.clone(),
+            hrv: HrvWindow::new(),
+            ppg_breath_rate: BreathRateEstimator::new(),
+            camera_hr: None,
+            last_hr: None,
+            hr_simulator: None,
+            journal: journal.clone(),
+            hr_zones: hr_zones.clone(),
+            breath_rate_band: breath_rate_band.clone(),
+            rppg_enabled: true,
+            baseline: baseline.clone(),
+            patterns: patterns.clone(),
+            belief_modes: belief_modes.clone(),
+            adjustments: adjustments.clone(),
+            escalation: escalation.clone(),
+            calibration: None,
+            calibrations: calibrations.clone(),
+            noise_profiling: None,
+            noise_profile: noise_profile.clone(),
+            audio_threshold: None,
+            flags: flags.clone(),
+            clock: clock.clone(),
+            recorder: recorder.clone(),
+        };
+
+        let handle = thread::spawn(move || actor.supervise());
+
+        ZenOneRuntime {
+            cmd_tx: tx,
+            state: state_arc,
+            state_stamps,
+            latest_frame: frame_arc,
+            frame_lite,
+            store,
+            research,
+            consent,
+            experiments: Arc::new(ExperimentManager::new()),
+            events,
+            listener,
+            group,
+            serial,
+            journal,
+            hr_zones,
+            breath_rate_band,
+            hr_filter: Mutex::new(FfiHrFilterConfig::default()),
+            safety,
+            watch: WatchLink::new(),
+            pipeline: Mutex::new(None),
+            rppg_method: Mutex::new(FfiRppgMethod::default()),
+            hr_source: Mutex::new(FfiHrSourceKind::default()),
+            rppg_config,
+            roi_provider: Mutex::new(None),
+            ppg_spectrum,
+            ppg_trace,
+            streak_config: Mutex::new(FfiStreakConfig::default()),
+            baseline,
+            patterns,
+            adjustments,
+            pattern_change: Mutex::new(PatternChangeSlot::default()),
+            escalation,
+            calibrations,
+            noise_profile,
+            flags,
+            settings: UserSettings::default(),
+            belief_modes,
+            data_dir: Mutex::new(None),
+            clock,
+            recorder,
+            thread: Arc::new(Mutex::new(Some(handle))),
+        }
+    }
+
+    /// Stop the actors: an active session is saved as partial, then both
+    /// threads are joined. Idempotent; the runtime is inert afterwards.
+    pub fn shutdown(&self) {
+        let Some(handle) = self.thread.lock().take() else { return };
+        let _ = self.cmd_tx.send(RuntimeCommand::Shutdown);
+        if handle.join().is_err() {
+            log::error!("RuntimeActor: Thread panicked before shutdown");
+        }
+    }
+
+    // =========================================================================
+    // PATTERN MANAGEMENT
+    // =========================================================================
+
+    /// Get all available patterns, builtin and custom
+    pub fn get_patterns(&self) -> Vec<FfiBreathPattern> {
+        self.patterns
+            .all()
+            .into_iter()
+            .map(|p| FfiBreathPattern::from(&self.adjustments.apply(p)))
+            .collect()
+    }
+
+    /// Register a user-defined pattern next to the builtins, replacing any
+    /// custom pattern with the same id. Returns the pattern as stored.
+    pub fn add_custom_pattern(&self, pattern: FfiBreathPattern) -> Result<FfiBreathPattern, ZenOneError> {
+        self.patterns.add(pattern)
+    }
+
+    /// Delete a custom pattern and its phase overrides
+    pub fn remove_custom_pattern(&self, pattern_id: String) -> Result<(), ZenOneError> {
+        if self.current_pattern_id() == pattern_id {
+            return Err(ZenOneError::ConfigError("Cannot remove the loaded pattern".into()));
+        }
+        self.patterns.remove(&pattern_id)?;
+        self.adjustments.clear(&pattern_id)
+    }
+
+    /// Serialize a pattern, with the user's phase overrides applied, into a
+    /// versioned JSON document that `import_pattern` accepts on another device
+    pub fn export_pattern(&self, pattern_id: String) -> Result<String, ZenOneError> {
+        let pattern = self.patterns.get(&pattern_id).ok_or(ZenOneError::PatternNotFound)?;
+        let export = PatternExport {
+            format: PATTERN_EXPORT_FORMAT.into(),
+            version: PATTERN_EXPORT_VERSION,
+            pattern: FfiBreathPattern::from(&self.adjustments.apply(pattern)),
+        };
+        serde_json::to_string_pretty(&export)
+            .map_err(|e| ZenOneError::ConfigError(format!("Pattern serialization failed: {}", e)))
+    }
+
+    /// Validate and register a pattern produced by `export_pattern`, replacing
+    /// any custom pattern with the same id. Builtin ids are rejected.
+    pub fn import_pattern(&self, json: String) -> Result<FfiBreathPattern, ZenOneError> {
+        let pattern = PatternExport::parse(&json)?;
+        self.patterns.add(pattern)
+    }
+
+    /// Change one phase of the current pattern for this user, e.g. shorten the
+    /// 7 s hold in 4-7-8. Kept per pattern and applied from the current cycle on.
+    pub fn override_phase_duration(&self, phase: FfiPhase, seconds: f32) -> Result<(), ZenOneError> {
+        let pattern_id = self.current_pattern_id();
+        let pattern = self.patterns.get(&pattern_id).ok_or(ZenOneError::PatternNotFound)?;
+        self.adjustments.set(pattern, phase, seconds)?;
+        let _ = self.cmd_tx.send(RuntimeCommand::ReloadPattern);
+        Ok(())
+    }
+
+    /// Drop all phase overrides of the current pattern
+    pub fn reset_phase_overrides(&self) -> Result<(), ZenOneError> {
+        self.adjustments.clear(&self.current_pattern_id())?;
+        let _ = self.cmd_tx.send(RuntimeCommand::ReloadPattern);
+        Ok(())
+    }
+
+    /// Every phase override, across all patterns
+    pub fn get_phase_overrides(&self) -> Vec<FfiPhaseOverride> {
+        self.adjustments.list()
+    }
+
+    /// Load a pattern by ID
+    pub fn load_pattern(&self, pattern_id: String) -> bool {
+        // We assume success for async load, but we could add a reply channel if strict validation needed immediately.
+        // For S-Tier responsiveness, we trigger load and return true if ID exists and its timings are safe.
+        let Some(pattern) = self.patterns.get(&pattern_id) else { return false };
+        if let Err(e) = PatternValidator::check(&self.adjustments.apply(pattern)) {
+            log::warn!("Refusing to load {}: {}", pattern_id, e);
+            return false;
+        }
+        let _ = self.cmd_tx.send(RuntimeCommand::LoadPattern(pattern_id));
+        true
+    }
+
+    /// Dry-run loading a pattern against the safety specs and its timing
+    /// limits without touching the pacer, so the UI can show any warning
+    /// first. Apply it with `confirm_pattern_change`; a new proposal replaces
+    /// the previous one.
+    pub fn propose_pattern_change(&self, pattern_id: String) -> Result<FfiPatternChangeProposal, ZenOneError> {
+        let pattern = self.patterns.get(&pattern_id).ok_or(ZenOneError::PatternNotFound)?;
+        let issues = PatternValidator::validate(&self.adjustments.apply(pattern));
+        let state = self.state_snapshot();
+        let event = FfiKernelEvent {
+            event_type: FfiKernelEventType::LoadPattern,
+            timestamp_ms: self.clock.now_ms(),
+            payload: Some(pattern_id.clone()),
+        };
+        let safety = self.safety.evaluate_safety_specs(event, (*state).clone());
+        // Same rule the actor applies: only warnings let the load through
+        let blocked = safety.violations.iter().any(|v| {
+            v.severity != FfiViolationSeverity::Warning
+                || matches!(v.corrective_action, Some(FfiCorrectiveAction::BlockEvent))
+        });
+        let can_confirm = !blocked
+            && !state.safety.is_locked
+            && issues.iter().all(|i| i.severity == FfiViolationSeverity::Warning);
+
+        let mut slot = self.pattern_change.lock();
+        slot.next_id += 1;
+        slot.pending = Some(PendingPatternChange {
+            id: slot.next_id,
+            from_pattern_id: state.pattern_id.clone(),
+            to_pattern_id: pattern_id.clone(),
+            status: state.status,
+            can_confirm,
+            created: self.clock.now(),
+        });
+        Ok(FfiPatternChangeProposal {
+            proposal_id: slot.next_id,
+            from_pattern_id: state.pattern_id.clone(),
+            to_pattern_id: pattern_id,
+            safety,
+            issues,
+            can_confirm,
+            expires_in_ms: PATTERN_PROPOSAL_TTL.as_millis() as u32,
+        })
+    }
+
+    /// Apply the pending proposal. Fails if it was replaced, has expired, was
+    /// refused, or the pattern or session status changed since it was made.
+    pub fn confirm_pattern_change(&self, proposal_id: u64) -> Result<(), ZenOneError> {
+        let mut slot = self.pattern_change.lock();
+        let pending = match slot.pending.take() {
+            Some(p) if p.id == proposal_id => p,
+            other => {
+                slot.pending = other;
+                return Err(ZenOneError::ConfigError(format!("No pending pattern change {}", proposal_id)));
+            }
+        };
+        if self.clock.since(pending.created) > PATTERN_PROPOSAL_TTL {
+            return Err(ZenOneError::ConfigError("Pattern change proposal expired".into()));
+        }
+        if !pending.can_confirm {
+            return Err(ZenOneError::SafetyViolation(format!(
+                "Loading '{}' was refused by the safety check", pending.to_pattern_id
+            )));
+        }
+        let state = self.state.load();
+        if state.pattern_id != pending.from_pattern_id || state.status != pending.status {
+            return Err(ZenOneError::ConfigError("Runtime changed since the proposal, propose again".into()));
+        }
+        // The actor re-checks the safety specs when it applies the load
+        let _ = self.cmd_tx.send(RuntimeCommand::LoadPattern(pending.to_pattern_id));
+        Ok(())
+    }
+
+    /// Queue an ordered list of patterns for the next session. Each segment
+    /// runs for its duration and hands over at the following cycle boundary;
+    /// the session completes after the last one. Loading a single pattern
+    /// drops the sequence.
+    pub fn load_sequence(&self, segments: Vec<FfiSequenceSegment>) -> Result<(), ZenOneError> {
+        if self.is_session_active() {
+            return Err(ZenOneError::ConfigError("Cannot load a sequence during a session".into()));
+        }
+        if segments.is_empty() || segments.len() > SEQUENCE_MAX_SEGMENTS {
+            return Err(ZenOneError::ConfigError(format!(
+                "A sequence needs 1-{} segments, got {}", SEQUENCE_MAX_SEGMENTS, segments.len()
+            )));
+        }
+        for (i, segment) in segments.iter().enumerate() {
+            let pattern = self.patterns.get(&segment.pattern_id).ok_or(ZenOneError::PatternNotFound)?;
+            PatternValidator::check(&self.adjustments.apply(pattern))?;
+            let seconds = segment.duration_sec;
+            if !(seconds.is_finite() && (SEQUENCE_MIN_SEGMENT_SEC..=SEQUENCE_MAX_SEGMENT_SEC).contains(&seconds)) {
+                return Err(ZenOneError::ConfigError(format!(
+                    "Segment {} must last {}-{} s, got {}", i, SEQUENCE_MIN_SEGMENT_SEC, SEQUENCE_MAX_SEGMENT_SEC, seconds
+                )));
+            }
+        }
+        let _ = self.cmd_tx.send(RuntimeCommand::LoadSequence(segments));
+        Ok(())
+    }
+
+    /// Position in the loaded sequence (None without one)
+    pub fn get_sequence_progress(&self) -> Option<FfiSequenceProgress> {
+        self.state.load().sequence.clone()
+    }
+
+    /// Physiological issues with a pattern's timings; warnings are allowed,
+    /// anything more severe keeps it from being saved or loaded
+    pub fn validate_pattern(&self, pattern: FfiBreathPattern) -> Vec<FfiPatternIssue> {
+        PatternValidator::validate(&BreathPattern::from(&pattern))
+    }
+
+    /// Get current pattern ID
+    pub fn current_pattern_id(&self) -> String {
+        self.state.load().pattern_id.clone()
+    }
+
+    /// Blend two patterns' timings, `t` = 0 giving `pattern_a` and 1 giving `pattern_b`
+    pub fn blend_patterns(&self, pattern_a: String, pattern_b: String, t: f32) -> Result<FfiBreathPattern, ZenOneError> {
+        if !(0.0..=1.0).contains(&t) {
+            return Err(ZenOneError::ConfigError(format!("Blend factor must be within 0..=1, got {}", t)));
+        }
+        let (Some(a), Some(b)) = (self.patterns.get(&pattern_a), self.patterns.get(&pattern_b)) else {
+            return Err(ZenOneError::PatternNotFound);
+        };
+        Ok(FfiBreathPattern::from(&blend_patterns(&a, &b, t)))
+    }
+
+    /// Animate a pattern for `cycles` cycles without starting a session.
+    /// Frames returned by `tick` follow the preview until it ends or is stopped.
+    pub fn preview_pattern(&self, pattern_id: String, cycles: u32) -> Result<(), ZenOneError> {
+        let pattern = self.patterns.get(&pattern_id).ok_or(ZenOneError::PatternNotFound)?;
+        PatternValidator::check(&self.adjustments.apply(pattern))?;
+        if cycles == 0 {
+            return Err(ZenOneError::ConfigError("Preview needs at least one cycle".into()));
+        }
+        if self.is_session_active() {
+            return Err(ZenOneError::ConfigError("Cannot preview during a session".into()));
+        }
+        let _ = self.cmd_tx.send(RuntimeCommand::PreviewPattern { id: pattern_id, cycles });
+        Ok(())
+    }
+
+    /// Stop a running preview
+    pub fn stop_preview(&self) {
+        let _ = self.cmd_tx.send(RuntimeCommand::StopPreview);
+    }
+
+    // =========================================================================
+    // SESSION MANAGEMENT
+    // =========================================================================
+
+    /// Start a breathing session
+    pub fn start_session(&self) -> Result<(), ZenOneError> {
+        let state = self.state.load();
+        if state.safety.is_locked {
+             return Err(ZenOneError::SafetyViolation("Cannot start session while locked".into()));
+        }
+        drop(state);
+
+        let _ = self.cmd_tx.send(RuntimeCommand::StartSession(FfiSessionGoals::default()));
+        Ok(())
+    }
+
+    /// Start a sleep wind-down session: exhales lengthen, audio fades and the
+    /// session ends silently once the user has been idle long enough
+    pub fn start_sleep_session(&self, config: FfiSleepConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
+        let state = self.state.load();
+        if state.safety.is_locked {
+             return Err(ZenOneError::SafetyViolation("Cannot start session while locked".into()));
+        }
+        drop(state);
+
+        let _ = self.cmd_tx.send(RuntimeCommand::StartSleepSession(config));
+        Ok(())
+    }
+
+    /// Start an acute-anxiety session in one call: loads 7-11, skips the
+    /// countdown and ends after a few minutes. Returns what the shell should
+    /// play alongside it.
+    pub fn start_panic_session(&self) -> Result<FfiPanicSession, ZenOneError> {
+        let state = self.state.load();
+        if state.safety.is_locked {
+             return Err(ZenOneError::SafetyViolation("Cannot start session while locked".into()));
+        }
+        drop(state);
+
+        let _ = self.cmd_tx.send(RuntimeCommand::StartPanicSession);
+        Ok(FfiPanicSession {
+            pattern_id: PANIC_PATTERN_ID.into(),
+            binaural_state: PANIC_BINAURAL_STATE,
+            binaural: BinauralManager::new().get_config(PANIC_BINAURAL_STATE),
+            duration_sec: PANIC_SESSION_SEC,
+        })
+    }
+
+    /// Report user interaction or device motion (keeps a sleep session awake)
+    pub fn report_activity(&self) {
+        let _ = self.cmd_tx.send(RuntimeCommand::ReportActivity);
+    }
+
+    /// Start a breathing session with targets to track live and report in its stats
+    pub fn start_session_with_goals(&self, goals: FfiSessionGoals) -> Result<(), ZenOneError> {
+        goals.validate()?;
+        let state = self.state.load();
+        if state.safety.is_locked {
+             return Err(ZenOneError::SafetyViolation("Cannot start session while locked".into()));
+        }
+        drop(state);
+
+        let _ = self.cmd_tx.send(RuntimeCommand::StartSession(goals));
+        Ok(())
+    }
+
+    /// Stop session and get stats
+    pub fn stop_session(&self) -> FfiSessionStats {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::StopSession(tx));
         
-        clamped
+        // Wait for stats (blocking for this call is expected behavior for stop_session)
+        // But the Engine loop finishes quickly so it's fine.
+        rx.recv().unwrap_or(FfiSessionStats {
+             duration_sec: 0.0,
+             cycles_completed: 0,
+             pattern_id: "".into(),
+             avg_heart_rate: None,
+             final_belief: self.get_belief(),
+             avg_resonance: 0.0,
+             avg_adherence: None,
+             cycle_adherence: Vec::new(),
+             interruptions: Vec::new(),
+             partial: false,
+             time_in_zone: Vec::new(),
+             binaural: Vec::new(),
+             goals: Vec::new(),
+             segments: Vec::new(),
+             stages: Vec::new(),
+             cool_down_breathing: None,
+             sleep: None,
+             hrv: None,
+             hrv_spectrum: None,
+        })
+    }
+
+    /// Check if session is active
+    pub fn is_session_active(&self) -> bool {
+        // We can infer from status inside the shared state
+        let state = self.state.load();
+        matches!(
+            state.status,
+            FfiRuntimeStatus::Preparing | FfiRuntimeStatus::Running | FfiRuntimeStatus::Paused
+        )
+    }
+
+    /// Pause session
+    pub fn pause_session(&self) {
+        let _ = self.cmd_tx.send(RuntimeCommand::PauseSession);
+    }
+
+    /// Report which binaural state the app is playing (None when stopped) so
+    /// listening time is attributed to the running session
+    pub fn set_binaural_state(&self, state: Option<FfiBrainWaveState>) {
+        let state = state.filter(|_| self.flags.is_enabled(FfiFeatureFlag::Binaural));
+        let _ = self.cmd_tx.send(RuntimeCommand::SetBinauralState(state));
+    }
+
+    /// Binaural listening time per state and outcomes compared with silent sessions
+    pub fn get_binaural_stats(&self, metric: FfiOutcomeMetric) -> Result<FfiBinauralStats, ZenOneError> {
+        self.consent.require(FfiConsentScope::Analytics)?;
+        Ok(binaural_stats(&self.store.all(), metric))
+    }
+
+    /// Passively measure natural breathing pace for `duration_sec` without
+    /// pacing; the result arrives as a BaselineReady event
+    pub fn start_baseline_observation(&self, duration_sec: f32) -> Result<(), ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::StartObservation { duration_sec, reply_tx });
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    /// End the observation now and return what was measured so far
+    pub fn stop_baseline_observation(&self) -> Result<FfiBreathingBaseline, ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::StopObservation(reply_tx));
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    /// Most recent free-breathing baseline
+    pub fn get_breathing_baseline(&self) -> Option<FfiBreathingBaseline> {
+        self.baseline.lock().clone()
+    }
+
+    /// Persist the onboarding questionnaire (None clears it). Recommenders are
+    /// seeded from `get_onboarding_answers` at startup.
+    pub fn set_onboarding_answers(&self, answers: Option<FfiOnboardingAnswers>) -> Result<(), ZenOneError> {
+        if let Some(answers) = &answers {
+            answers.validate()?;
+        }
+        self.settings.update(|settings| settings.onboarding = answers)
+    }
+
+    pub fn get_onboarding_answers(&self) -> Option<FfiOnboardingAnswers> {
+        self.settings.read(|settings| settings.onboarding.clone())
+    }
+
+    /// Measure the room's microphone envelope for `duration_sec` while the user
+    /// stays quiet; the result arrives as a NoiseProfileReady event
+    pub fn start_noise_profiling(&self, duration_sec: f32) -> Result<(), ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::StartNoiseProfiling { duration_sec, reply_tx });
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    pub fn get_noise_profile(&self) -> Option<FfiNoiseProfile> {
+        self.noise_profile.lock().clone()
+    }
+
+    /// Use audio respiration samples, gated at the profiled noise level. Fails
+    /// with CapabilityUnavailable until a profile shows the room is quiet enough.
+    pub fn set_audio_breath_detection(&self, enabled: bool) -> Result<(), ZenOneError> {
+        if !enabled {
+            let _ = self.cmd_tx.send(RuntimeCommand::SetAudioThreshold(None));
+            return Ok(());
+        }
+        let profile = self.noise_profile.lock().clone().ok_or_else(|| {
+            ZenOneError::CapabilityUnavailable("Profile room noise before enabling audio breath detection".into())
+        })?;
+        if let Some(reason) = profile.unusable_reason {
+            return Err(ZenOneError::CapabilityUnavailable(reason));
+        }
+        let _ = self.cmd_tx.send(RuntimeCommand::SetAudioThreshold(Some(profile.threshold)));
+        Ok(())
+    }
+
+    /// Emit a PhaseEnding event `lead_ms` before each phase transition (None disables)
+    pub fn set_phase_warning_lead(&self, lead_ms: Option<u32>) {
+        let _ = self.cmd_tx.send(RuntimeCommand::SetPhaseWarningLead(lead_ms));
+    }
+
+    /// Morph mid-session pattern loads over `cycles` cycles instead of
+    /// switching timings at once (0, the default, switches immediately)
+    pub fn set_pattern_transition_cycles(&self, cycles: u32) -> Result<(), ZenOneError> {
+        if cycles > PATTERN_TRANSITION_MAX_CYCLES {
+            return Err(ZenOneError::ConfigError(format!(
+                "Transition must take at most {} cycles, got {}", PATTERN_TRANSITION_MAX_CYCLES, cycles
+            )));
+        }
+        let _ = self.cmd_tx.send(RuntimeCommand::SetTransitionCycles(cycles));
+        Ok(())
+    }
+
+    /// Emit a PhaseCountdown event for each second left in a phase (voice counting, numerals)
+    pub fn set_phase_countdown(&self, enabled: bool) {
+        let _ = self.cmd_tx.send(RuntimeCommand::SetPhaseCountdown(enabled));
+    }
+
+    /// Cap how often ticks refresh `get_state` (e.g. 10 Hz for UI polling).
+    /// None publishes on every tick. Frames are unaffected.
+    pub fn set_state_publish_rate(&self, max_hz: Option<f32>) -> Result<(), ZenOneError> {
+        let interval = match max_hz {
+            Some(hz) if !(STATE_PUBLISH_MIN_HZ..=STATE_PUBLISH_MAX_HZ).contains(&hz) => {
+                return Err(ZenOneError::ConfigError(format!(
+                    "Publish rate must be within {STATE_PUBLISH_MIN_HZ}-{STATE_PUBLISH_MAX_HZ} Hz, got {hz}"
+                )));
+            }
+            Some(hz) => Some(Duration::from_secs_f32(1.0 / hz)),
+            None => None,
+        };
+        let _ = self.cmd_tx.send(RuntimeCommand::SetStatePublishInterval(interval));
+        Ok(())
+    }
+
+    /// Enable follow-me mode: the pacer first locks onto the user's detected
+    /// breathing rate, then guides it down toward `target_rate_bpm`
+    pub fn set_follow_me(&self, enabled: bool, target_rate_bpm: f32) -> Result<(), ZenOneError> {
+        if enabled && !(FOLLOW_ME_MIN_RATE_BPM..=FOLLOW_ME_MAX_RATE_BPM).contains(&target_rate_bpm) {
+            return Err(ZenOneError::SafetyViolation(format!(
+                "Target rate must be within {}-{} breaths/min",
+                FOLLOW_ME_MIN_RATE_BPM, FOLLOW_ME_MAX_RATE_BPM
+            )));
+        }
+        let _ = self.cmd_tx.send(RuntimeCommand::SetFollowMe { enabled, target_rate_bpm });
+        Ok(())
+    }
+
+    /// End sessions automatically after the pattern's recommended cycles,
+    /// with a SessionCompleted event (applies from the next session)
+    pub fn set_auto_complete(&self, enabled: bool) {
+        let _ = self.cmd_tx.send(RuntimeCommand::SetAutoComplete(enabled));
+    }
+
+    /// Configure the pre-session countdown and signal quality gate
+    pub fn set_preparation_config(&self, config: FfiPreparationConfig) {
+        let _ = self.cmd_tx.send(RuntimeCommand::SetPreparationConfig(config));
+    }
+
+    /// Start immediately, skipping the remaining countdown and quality gate
+    pub fn skip_preparation(&self) {
+        let _ = self.cmd_tx.send(RuntimeCommand::SkipPreparation);
+    }
+
+    /// Declare the warm-up and cool-down of sessions started from now on
+    pub fn set_session_stages(&self, config: FfiSessionStagesConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
+        let _ = self.cmd_tx.send(RuntimeCommand::SetSessionStages(config));
+        Ok(())
+    }
+
+    /// Stop pacing now and run the declared cool-down before the session completes
+    pub fn begin_cool_down(&self) -> Result<(), ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::BeginCoolDown(reply_tx));
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    /// Pause the session because of an external interruption (call, backgrounding)
+    pub fn report_interruption(&self, kind: FfiInterruptionKind) {
+        let _ = self.cmd_tx.send(RuntimeCommand::Interrupt(kind));
+    }
+
+    /// Resume paused session. Depending on how long the pause lasted this resumes
+    /// mid-cycle, restarts the current cycle, or finalizes the session.
+    pub fn resume_session(&self) {
+        let _ = self.cmd_tx.send(RuntimeCommand::ResumeSession);
+    }
+
+    /// Reset safety lock. A reason is required, it fails when no lock is
+    /// engaged, and locks caused by critical violations cannot be reset until
+    /// their cooldown has elapsed (including across restarts).
+    pub fn reset_safety_lock(&self, reason: String) -> Result<(), ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::ResetSafetyLock { reason, reply_tx });
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    /// Re-verify every spec over the runtime's whole logged event history
+    pub fn verify_trace(&self) -> FfiTraceReport {
+        self.safety.verify_trace()
+    }
+
+    /// Violations recorded by the runtime's own safety checks
+    pub fn get_safety_violations(&self) -> Vec<FfiSafetyViolation> {
+        self.safety.get_violations()
+    }
+
+    /// Acknowledge one recorded violation, e.g. after the user reviewed a warning
+    pub fn ack_safety_violation(&self, id: u64, note: Option<String>) -> Result<(), ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::AckViolation { id, note, reply_tx });
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    /// Safety lock engage/reset history
+    pub fn get_safety_journal(&self) -> Vec<FfiSafetyJournalEntry> {
+        self.journal.entries()
+    }
+
+    /// Configure heart-rate zones and the elevated-HR pause (applies from the next session)
+    pub fn set_hr_zones(&self, config: FfiHrZoneConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
+        self.settings.update(|settings| settings.hr_zones = Some(config.clone()))?;
+        *self.hr_zones.lock() = config;
+        Ok(())
+    }
+
+    pub fn get_hr_zones(&self) -> FfiHrZoneConfig {
+        self.hr_zones.lock().clone()
+    }
+
+    /// Configure how far the measured breathing rate may drift from the
+    /// pacer's rate before a coaching cue fires (applies immediately)
+    pub fn set_breath_rate_band(&self, band: FfiBreathRateBand) -> Result<(), ZenOneError> {
+        band.validate()?;
+        *self.breath_rate_band.lock() = band;
+        Ok(())
+    }
+
+    pub fn get_breath_rate_band(&self) -> FfiBreathRateBand {
+        self.breath_rate_band.lock().clone()
+    }
+
+    /// Tune (or disable) the Kalman filter smoothing camera heart rate.
+    /// The filter restarts from the next reading.
+    pub fn set_hr_filter_config(&self, config: FfiHrFilterConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
+        *self.hr_filter.lock() = config.clone();
+        let _ = self.cmd_tx.send(RuntimeCommand::SetHrFilter(config));
+        Ok(())
+    }
+
+    pub fn get_hr_filter_config(&self) -> FfiHrFilterConfig {
+        self.hr_filter.lock().clone()
+    }
+
+    /// Configure when safety alerts escalate to the trusted contact. Escalations
+    /// are only raised while `EmergencyContact` consent is granted.
+    pub fn set_escalation_config(&self, config: FfiEscalationConfig) -> Result<(), ZenOneError> {
+        self.escalation.set_config(config)
+    }
+
+    pub fn get_escalation_config(&self) -> FfiEscalationConfig {
+        self.escalation.config()
+    }
+
+    // =========================================================================
+    // FRAME PROCESSING (Main update loop)
+    // =========================================================================
+
+    /// Process a camera frame and update state
+    pub fn process_frame(&self, r: f32, g: f32, b: f32, timestamp_us: i64) -> FfiFrame {
+        // Fire and forget - NON-BLOCKING
+        let _ = self.cmd_tx.send(RuntimeCommand::ProcessFrame { r, g, b, timestamp_us });
+        
+        // Return latest available frame immediately
+        (**self.latest_frame.load()).clone()
+    }
+
+    /// Process a raw camera frame (RGB or camera-native YUV 4:2:0): skin
+    /// pixels inside `roi` (the whole frame when None) are averaged here
+    /// instead of by the frontend. `planes` gives the camera's plane offsets
+    /// and strides; None means tightly packed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_frame_buffer(
+        &self,
+        bytes: Vec<u8>,
+        width: u32,
+        height: u32,
+        format: FfiPixelFormat,
+        planes: Option<Vec<FfiPlaneLayout>>,
+        roi: Option<FfiRoi>,
+        timestamp_us: i64,
+    ) -> Result<FfiFrame, ZenOneError> {
+        let frame = check_frame(&bytes, width, height, format, planes)?;
+        let roi = roi.or_else(|| {
+            self.roi_provider
+                .lock()
+                .as_mut()
+                .and_then(|provider| provider.locate_roi(&frame))
+                .filter(|roi| roi.fits(width, height))
+        });
+        let [r, g, b] = average_skin_rgb(&frame, roi)?;
+        Ok(self.process_frame(r, g, b, timestamp_us))
+    }
+
+    /// Install (or with None remove) the ROI provider consulted by
+    /// `process_frame_buffer` when no ROI is passed
+    pub fn set_roi_provider(&self, provider: Option<Box<dyn RoiProvider>>) {
+        *self.roi_provider.lock() = provider;
+    }
+
+    /// Track the rPPG region with the built-in skin-color tracker
+    pub fn set_skin_roi_tracking(&self, enabled: bool) {
+        let provider: Option<Box<dyn RoiProvider>> = if enabled { Some(Box::new(SkinRoiTracker::new())) } else { None };
+        self.set_roi_provider(provider);
+    }
+
+    /// Process many buffered camera samples in one command, e.g. after the app
+    /// was stalled in the background. Samples are applied in timestamp order.
+    pub fn process_frames_batch(&self, mut samples: Vec<FfiRgbSample>) -> Result<FfiFrame, ZenOneError> {
+        if samples.len() > FRAME_BATCH_MAX_SAMPLES {
+            return Err(ZenOneError::ConfigError(format!(
+                "Batch of {} samples exceeds the limit of {}",
+                samples.len(),
+                FRAME_BATCH_MAX_SAMPLES
+            )));
+        }
+        samples.sort_by_key(|s| s.timestamp_us);
+        let _ = self.cmd_tx.send(RuntimeCommand::ProcessFrames(samples));
+        Ok((**self.latest_frame.load()).clone())
+    }
+
+    /// Feed a streamed heart rate sample from a wearable. `rr_intervals_ms` may be
+    /// empty; short gaps are interpolated so downstream logic matches the camera path.
+    pub fn ingest_hr_sample(&self, bpm: f32, rr_intervals_ms: Vec<f32>, timestamp_us: i64) {
+        let _ = self.cmd_tx.send(RuntimeCommand::HrSample { bpm, rr_intervals_ms, timestamp_us });
+    }
+
+    /// Feed a pulse the user counted by hand. Used while the manual source is selected.
+    pub fn ingest_manual_hr(&self, bpm: f32, timestamp_us: i64) -> Result<(), ZenOneError> {
+        if !(bpm.is_finite() && (30.0..=220.0).contains(&bpm)) {
+            return Err(ZenOneError::ConfigError(format!("Heart rate must be within 30-220 bpm, got {}", bpm)));
+        }
+        let _ = self.cmd_tx.send(RuntimeCommand::ManualHrSample { bpm, timestamp_us });
+        Ok(())
+    }
+
+    /// Choose which sensor supplies the session's heart rate. Other sources
+    /// keep running (camera calibration needs both) but are not shown.
+    pub fn set_hr_source(&self, kind: FfiHrSourceKind) {
+        *self.hr_source.lock() = kind;
+        let _ = self.cmd_tx.send(RuntimeCommand::SetHrSource(kind));
+    }
+
+    pub fn get_hr_source(&self) -> FfiHrSourceKind {
+        *self.hr_source.lock()
+    }
+
+    /// Feed a respiration signal sample (chest motion, audio envelope) for adherence scoring.
+    /// Higher values mean more air in the lungs.
+    pub fn ingest_respiration_sample(&self, value: f32, source: FfiRespirationSource, timestamp_us: i64) {
+        let _ = self.cmd_tx.send(RuntimeCommand::RespirationSample { value, source, timestamp_us });
+    }
+
+    /// Tick without camera (timer-based update)
+    pub fn tick(&self, dt_sec: f32, timestamp_us: i64) -> FfiFrame {
+        (*self.tick_snapshot(dt_sec, timestamp_us)).clone()
+    }
+
+    /// `tick` returning the shared frame snapshot (Rust callers only, no clone)
+    pub fn tick_snapshot(&self, dt_sec: f32, timestamp_us: i64) -> Arc<FfiFrame> {
+        let _ = self.cmd_tx.send(RuntimeCommand::Tick { dt_sec, timestamp_us });
+        self.latest_frame.load_full()
+    }
+
+    /// Tick and return only phase, progress and HR. Cheaper than `tick` for
+    /// display-rate animation loops; poll `get_state` separately for the rest.
+    pub fn tick_lite(&self, dt_sec: f32, timestamp_us: i64) -> FfiFrameLite {
+        let _ = self.cmd_tx.send(RuntimeCommand::Tick { dt_sec, timestamp_us });
+        **self.frame_lite.load()
+    }
+
+    /// Latest lightweight frame without advancing the runtime
+    pub fn get_frame_lite(&self) -> FfiFrameLite {
+        **self.frame_lite.load()
+    }
+
+    /// Enable or disable belief-adaptive pattern transitions during sessions
+    pub fn set_arc_planner_enabled(&self, enabled: bool) {
+        let _ = self.cmd_tx.send(RuntimeCommand::SetArcPlanner(enabled));
+    }
+
+    /// Pick rPPG window, publish rate and feature toggles for this device.
+    /// Meant to be called once at startup, after the shell has probed the camera.
+    pub fn configure_for_device(&self, capabilities: FfiDeviceCapabilities) -> Result<FfiPipelineProfile, ZenOneError> {
+        let profile = FfiPipelineProfile::for_device(&capabilities)?;
+        {
+            let mut config = self.rppg_config.lock();
+            config.window_sec = profile.rppg_window_samples as f32 / profile.rppg_sample_rate_hz;
+            config.sample_rate_hz = profile.rppg_sample_rate_hz;
+        }
+        let _ = self.cmd_tx.send(RuntimeCommand::ConfigureRppg {
+            enabled: profile.rppg_enabled,
+            window_samples: profile.rppg_window_samples as usize,
+            sample_rate_hz: profile.rppg_sample_rate_hz,
+        });
+        self.set_state_publish_rate(profile.state_publish_hz)?;
+        self.set_arc_planner_enabled(profile.arc_planner_enabled);
+        log::info!("Pipeline configured: {:?}", profile);
+        *self.pipeline.lock() = Some(profile.clone());
+        Ok(profile)
+    }
+
+    /// Block until the actors have applied every command sent so far
+    fn flush(&self) {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        if self.cmd_tx.send(RuntimeCommand::Flush(reply_tx)).is_ok() {
+            let _ = reply_rx.recv();
+        }
+    }
+
+    /// Profile chosen by the last `configure_for_device` (None while on defaults)
+    pub fn get_pipeline_profile(&self) -> Option<FfiPipelineProfile> {
+        self.pipeline.lock().clone()
+    }
+
+    /// Switch the rPPG algorithm. Camera processing restarts from scratch, so
+    /// heart rate disappears for a few seconds while the new method warms up.
+    pub fn set_rppg_method(&self, method: FfiRppgMethod) {
+        *self.rppg_method.lock() = method;
+        let _ = self.cmd_tx.send(RuntimeCommand::SetRppgMethod(method));
+    }
+
+    pub fn get_rppg_method(&self) -> FfiRppgMethod {
+        *self.rppg_method.lock()
+    }
+
+    /// Set the rPPG analysis window and expected frame rate. With
+    /// `adapt_sample_rate` the rate follows the frames actually delivered.
+    pub fn set_rppg_config(&self, config: FfiRppgConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
+        let window_samples = rppg_window_samples(config.window_sec, config.sample_rate_hz);
+        let sample_rate_hz = config.sample_rate_hz;
+        *self.rppg_config.lock() = config;
+        let _ = self.cmd_tx.send(RuntimeCommand::SetRppgWindow { window_samples, sample_rate_hz });
+        Ok(())
+    }
+
+    /// Current rPPG configuration; `sample_rate_hz` reflects any adaptation
+    pub fn get_rppg_config(&self) -> FfiRppgConfig {
+        self.rppg_config.lock().clone()
+    }
+
+    /// Camera pulse spectrum from the last quality analysis (once a second,
+    /// after eight seconds of frames). None before that or after a reset.
+    pub fn get_ppg_spectrum(&self) -> Option<FfiPpgSpectrum> {
+        self.ppg_spectrum.lock().clone()
+    }
+
+    /// Start or stop buffering the camera signal for debugging, averaging
+    /// every `decimation` samples into one point. Drain with `drain_ppg_trace`.
+    pub fn set_ppg_trace(&self, enabled: bool, decimation: u32) -> Result<(), ZenOneError> {
+        if !(1..=PPG_TRACE_MAX_DECIMATION).contains(&decimation) {
+            return Err(ZenOneError::ConfigError(format!(
+                "Trace decimation must be 1-{}, got {}",
+                PPG_TRACE_MAX_DECIMATION, decimation
+            )));
+        }
+        self.ppg_trace.lock().configure(enabled, decimation);
+        Ok(())
+    }
+
+    pub fn is_ppg_trace_enabled(&self) -> bool {
+        self.ppg_trace.lock().enabled
+    }
+
+    /// Trace points buffered since the last drain, oldest first
+    pub fn drain_ppg_trace(&self) -> Vec<FfiPpgTracePoint> {
+        self.ppg_trace.lock().points.drain(..).collect()
+    }
+
+    /// Start comparing camera heart rate against a reference on this device.
+    /// Strap references come from `ingest_hr_sample`; manual counts from
+    /// `add_manual_hr_reference`. Camera frames must keep flowing meanwhile.
+    pub fn start_rppg_calibration(&self, device_id: String, reference: FfiCalibrationReference) -> Result<(), ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::StartCalibration { device_id, reference, reply_tx });
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    /// Pulse counted by hand over the last `duration_sec` seconds
+    pub fn add_manual_hr_reference(&self, bpm: f32, duration_sec: f32) -> Result<(), ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::ManualHrReference { bpm, duration_sec, reply_tx });
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    /// Compute, save and apply the correction for the calibrated device
+    pub fn finish_rppg_calibration(&self) -> Result<FfiRppgCalibration, ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        let _ = self.cmd_tx.send(RuntimeCommand::FinishCalibration(reply_tx));
+        reply_rx
+            .recv()
+            .unwrap_or_else(|_| Err(ZenOneError::ConfigError("Runtime not responding".into())))
+    }
+
+    /// Apply the saved calibration for `device_id`, if any; without one camera
+    /// heart rate is used uncorrected
+    pub fn use_rppg_calibration(&self, device_id: String) -> Option<FfiRppgCalibration> {
+        let calibration = self.calibrations.get(&device_id);
+        let _ = self.cmd_tx.send(RuntimeCommand::SetRppgCalibration(calibration.clone()));
+        calibration
+    }
+
+    pub fn get_rppg_calibrations(&self) -> Vec<FfiRppgCalibration> {
+        self.calibrations.list()
+    }
+
+    // =========================================================================
+    // GROUP SESSIONS
+    // =========================================================================
+
+    /// Lead a group session; followers on the LAN lock to this device's timeline
+    pub fn host_group_session(&self, group_id: String) -> Result<(), ZenOneError> {
+        self.flags.require(FfiFeatureFlag::Sync)?;
+        self.group.start(group_id, FfiGroupRole::Leader)
+    }
+
+    /// Follow the leader of a group session, correcting for late join and drift
+    pub fn join_group_session(&self, group_id: String) -> Result<(), ZenOneError> {
+        self.flags.require(FfiFeatureFlag::Sync)?;
+        self.group.start(group_id, FfiGroupRole::Follower)
+    }
+
+    /// Leave the current group session
+    pub fn leave_group_session(&self) {
+        self.group.stop();
+    }
+
+    /// Get group sync status, if in a group
+    pub fn get_group_status(&self) -> Option<FfiGroupStatus> {
+        self.group.status()
+    }
+
+    // =========================================================================
+    // SERIAL PACER
+    // =========================================================================
+
+    /// Stream the pacer to a serial/USB port for DIY hardware (see the
+    /// SERIAL PACER section for the line protocol). `baud_rate` defaults to
+    /// 115200.
+    pub fn start_serial_pacer(&self, port: String, baud_rate: Option<u32>) -> Result<(), ZenOneError> {
+        self.serial.start(port, baud_rate.unwrap_or(SERIAL_PACER_DEFAULT_BAUD))
+    }
+
+    pub fn stop_serial_pacer(&self) {
+        self.serial.stop();
+    }
+
+    pub fn get_serial_pacer_status(&self) -> Option<FfiSerialPacerStatus> {
+        self.serial.status()
+    }
+
+    // =========================================================================
+    // WATCH MIRRORING
+    // =========================================================================
+
+    /// Report watch transport connectivity; reconnecting forces a full resync
+    pub fn watch_set_connected(&self, connected: bool) {
+        self.watch.set_connected(connected);
+    }
+
+    /// Next state message to send to the watch, if one is due
+    pub fn watch_next_message(&self) -> Option<Vec<u8>> {
+        let state = self.state.load();
+        let heart_rate = self.latest_frame.load().heart_rate;
+        self.watch.next_message(&state, heart_rate)
+    }
+
+    /// Handle a message from the watch. Returns an acknowledgement to send back.
+    pub fn watch_handle_message(&self, data: Vec<u8>) -> Result<Option<Vec<u8>>, ZenOneError> {
+        let (reply, command) = self.watch.handle_message(&data)?;
+        match command {
+            Some(WatchCommand::Pause) => self.pause_session(),
+            Some(WatchCommand::Resume) => self.resume_session(),
+            Some(WatchCommand::Stop) => {
+                // Stats are kept in the session store
+                let _ = self.stop_session();
+            }
+            None => {}
+        }
+        Ok(reply)
+    }
+
+    /// Get watch link status
+    pub fn get_watch_link_status(&self) -> FfiWatchLinkStatus {
+        self.watch.status()
+    }
+
+    // =========================================================================
+    // EVENTS
+    // =========================================================================
+
+    /// Drain pending runtime events (coaching cues, ...) in emission order
+    pub fn poll_events(&self) -> Vec<FfiRuntimeEvent> {
+        self.events.drain()
+    }
+
+    /// Push phase, cycle and status changes to `listener` as they happen
+    /// (None stops pushing)
+    pub fn set_listener(&self, listener: Option<Box<dyn RuntimeListener>>) {
+        *self.listener.lock() = listener;
+    }
+
+    // =========================================================================
+    // STATE QUERIES
+    // =========================================================================
+
+    /// Get full runtime state snapshot. This copies the whole record; polling
+    /// loops should take `get_state_snapshot` (or `state_snapshot` from Rust).
+    pub fn get_state(&self) -> FfiRuntimeState {
+        (**self.state.load()).clone()
+    }
+
+    /// The current published state as a shared handle, without copying it
+    pub fn get_state_snapshot(&self) -> Arc<RuntimeStateSnapshot> {
+        Arc::new(RuntimeStateSnapshot(self.state.load_full()))
+    }
+
+    /// Only the fields changed since `since_sequence` (the `state_sequence`
+    /// of the last diff the caller applied; 0 for a full snapshot), for
+    /// watches, widgets and remote clients that sync over slow links
+    pub fn get_state_delta(&self, since_sequence: u64) -> FfiRuntimeStateDiff {
+        let stamps = self.state_stamps.load();
+        stamps.delta(&self.state.load(), since_sequence)
+    }
+
+    /// Shared handle to the current immutable snapshot (Rust callers only, no clone)
+    pub fn state_snapshot(&self) -> Arc<FfiRuntimeState> {
+        self.state.load_full()
+    }
+
+    /// Shared handle to the latest frame snapshot (Rust callers only, no clone)
+    pub fn frame_snapshot(&self) -> Arc<FfiFrame> {
+        self.latest_frame.load_full()
+    }
+
+    /// Get current belief state, including registered custom modes
+    pub fn get_belief(&self) -> FfiBeliefState {
+        self.state.load().belief.clone()
+    }
+
+    /// Every belief mode the runtime reports: built-ins, then custom modes
+    pub fn list_belief_modes(&self) -> Vec<FfiBeliefModeSpec> {
+        self.belief_modes.list()
+    }
+
+    /// Custom modes only, for wiring into the recommender
+    pub fn list_custom_belief_modes(&self) -> Vec<FfiBeliefModeSpec> {
+        self.belief_modes.custom_specs()
+    }
+
+    /// Register (or replace) a custom belief mode. Research builds only:
+    /// requires the ResearchMode feature flag.
+    pub fn register_belief_mode(&self, spec: FfiBeliefModeSpec) -> Result<(), ZenOneError> {
+        self.flags.require(FfiFeatureFlag::ResearchMode)?;
+        self.belief_modes.register(spec, &self.patterns)?;
+        let _ = self.cmd_tx.send(RuntimeCommand::BeliefModesChanged);
+        Ok(())
+    }
+
+    /// Remove a custom belief mode; returns whether it existed
+    pub fn remove_belief_mode(&self, id: String) -> Result<bool, ZenOneError> {
+        let removed = self.belief_modes.remove(&id)?;
+        if removed {
+            let _ = self.cmd_tx.send(RuntimeCommand::BeliefModesChanged);
+        }
+        Ok(removed)
+    }
+    
+    /// Get safety status
+    pub fn get_safety_status(&self) -> FfiSafetyStatus {
+        self.state.load().safety.clone()
+    }
+
+    /// Pre-validate an action against the runtime's safety specs without
+    /// recording it. `state` defaults to the current runtime state; pass a
+    /// modified copy to ask e.g. whether a tempo change would be blocked.
+    pub fn evaluate_safety_specs(&self, event: FfiKernelEvent, state: Option<FfiRuntimeState>) -> FfiSafetyCheckResult {
+        let state = state.unwrap_or_else(|| self.get_state());
+        self.safety.evaluate_safety_specs(event, state)
+    }
+
+    // =========================================================================
+    // CONTROL ACTIONS
+    // =========================================================================
+
+    /// Adjust tempo scale (with safety bounds)
+    pub fn adjust_tempo(&self, scale: f32, reason: String) -> Result<f32, ZenOneError> {
+        // Validation happens on calling thread for immediate feedback
+        let clamped = scale.clamp(TEMPO_MIN, TEMPO_MAX);
+        if (clamped - scale).abs() > 0.001 {
+            log::warn!("Tempo {} clamped to {} (reason: {})", scale, clamped, reason);
+        }
+
+        let _ = self.cmd_tx.send(RuntimeCommand::AdjustTempo(clamped));
+        // We implicitly assume success. S-Tier: Don't wait.
+        Ok(clamped)
+    }
+
+    /// Update context (time of day, charging status, etc.)
+    pub fn update_context(&self, local_hour: u8, is_charging: bool, recent_sessions: u16) {
+        let _ = self.cmd_tx.send(RuntimeCommand::UpdateContext {
+            local_hour,
+            is_charging,
+            recent_sessions,
+        });
+    }
+
+
+
+    /// Emergency halt
+    pub fn emergency_halt(&self, reason: String) {
+        let _ = self.cmd_tx.send(RuntimeCommand::EmergencyHalt(reason));
+    }
+
+    // =========================================================================
+    // PROFILE STORAGE
+    // =========================================================================
+
+    /// Set the directory used for persisted profile data and load existing state
+    pub fn set_data_dir(&self, path: String) -> Result<(), ZenOneError> {
+        let dir = std::path::PathBuf::from(path);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot create data dir: {}", e)))?;
+        self.store.attach_db(dir.join(SESSION_DB_FILE))?;
+        self.consent.attach_file(dir.join(CONSENT_FILE))?;
+        self.journal.attach_file(dir.join(SAFETY_JOURNAL_FILE))?;
+        if self.journal.active_lock().is_some() {
+            let _ = self.cmd_tx.send(RuntimeCommand::RestoreSafetyLock);
+        }
+        self.safety.attach_event_log(dir.join(SAFETY_EVENT_LOG_FILE))?;
+        self.patterns.attach_file(dir.join(CUSTOM_PATTERNS_FILE))?;
+        self.belief_modes.attach_file(dir.join(BELIEF_MODES_FILE))?;
+        let _ = self.cmd_tx.send(RuntimeCommand::BeliefModesChanged);
+        self.adjustments.attach_file(dir.join(PATTERN_ADJUSTMENTS_FILE))?;
+        self.escalation.attach_file(dir.join(ESCALATION_FILE))?;
+        self.calibrations.attach_file(dir.join(RPPG_CALIBRATION_FILE))?;
+        self.flags.attach_file(dir.join(FEATURE_FLAGS_FILE))?;
+        self.experiments.attach_file(dir.join(EXPERIMENTS_FILE))?;
+        self.settings.attach_file(dir.join(USER_SETTINGS_FILE))?;
+        let (retention, time_zone) = self.settings.read(|settings| {
+            if let Some(zones) = &settings.hr_zones {
+                *self.hr_zones.lock() = zones.clone();
+            }
+            if let Some(streak) = &settings.streak {
+                *self.streak_config.lock() = streak.clone();
+            }
+            (settings.retention.clone(), settings.time_zone.clone())
+        });
+        self.store.set_zone(LocalZone::parse(time_zone.as_deref())?);
+        if let Some(policy) = retention {
+            self.store.set_retention(policy, self.clock.now_ms())?;
+        }
+        *self.data_dir.lock() = Some(dir);
+        Ok(())
+    }
+
+    // =========================================================================
+    // SESSION STORE ENCRYPTION
+    // =========================================================================
+
+    /// Unlock (or create on first use) the session store key sealed under
+    /// `passphrase` and load the stored history. Until then completed sessions
+    /// are kept in memory only. Returns the number of sessions loaded.
+    pub fn unlock_session_store(&self, passphrase: String) -> Result<u32, ZenOneError> {
+        let path = self
+            .data_dir
+            .lock()
+            .as_ref()
+            .map(|dir| dir.join(SESSION_KEY_FILE))
+            .ok_or_else(|| ZenOneError::ConfigError("Data dir not set".into()))?;
+        let key = load_or_create_session_key(&path, passphrase)?;
+        let count = self.store.unlock_db(key)?;
+        // Apply a retention policy restored before the history was readable
+        Ok(count - self.store.run_maintenance(self.clock.now_ms()))
+    }
+
+    pub fn is_session_store_unlocked(&self) -> bool {
+        self.store.is_unlocked()
+    }
+
+    // =========================================================================
+    // SESSION SIGNATURES
+    // =========================================================================
+
+    /// Unlock (or create on first use) the device signing key sealed under
+    /// `passphrase`; sessions completed from now on are signed. Returns the
+    /// hex public key to register with a research or wellness program.
+    pub fn unlock_signing_key(&self, passphrase: String) -> Result<String, ZenOneError> {
+        let path = self
+            .data_dir
+            .lock()
+            .as_ref()
+            .map(|dir| dir.join(DEVICE_KEY_FILE))
+            .ok_or_else(|| ZenOneError::ConfigError("Data dir not set".into()))?;
+        let key = load_or_create_device_key(&path, passphrase)?;
+        let public_key = hex_encode(&key.verifying_key().to_bytes());
+        self.store.set_signer(key);
+        Ok(public_key)
+    }
+
+    pub fn get_device_public_key(&self) -> Option<String> {
+        self.store.public_key()
+    }
+
+    /// Check the session signatures of an export. `public_key` defaults to this
+    /// device's key; the key embedded in the export is never trusted on its own.
+    pub fn verify_session_export(&self, json: String, public_key: Option<String>) -> Result<FfiSignatureReport, ZenOneError> {
+        let public_key = public_key
+            .or_else(|| self.store.public_key())
+            .ok_or_else(|| ZenOneError::ConfigError("No public key given and signing key is locked".into()))?;
+        verify_session_export(&json, &public_key)
+    }
+
+    // =========================================================================
+    // PREFLIGHT
+    // =========================================================================
+
+    /// Readiness checklist for the UI to show before start_session
+    pub fn preflight_check(&self, input: FfiPreflightInput) -> FfiPreflightReport {
+        // configure_for_device turns camera heart rate off on devices without a usable camera
+        let rppg_enabled = self.flags.is_enabled(FfiFeatureFlag::Rppg)
+            && self.pipeline.lock().as_ref().is_none_or(|p| p.rppg_enabled);
+        let checks = vec![
+            Preflight::camera(rppg_enabled, input.camera_available, self.latest_frame.load().signal_quality),
+            Preflight::safety(&self.get_safety_status()),
+            Preflight::storage(self.data_dir.lock().as_deref(), self.store.is_unlocked()),
+            Preflight::audio(input.audio_output_available),
+            Preflight::battery(input.battery_percent, input.is_charging),
+        ];
+        FfiPreflightReport {
+            ready: checks.iter().all(|c| c.status != FfiPreflightStatus::Fail),
+            checks,
+        }
+    }
+
+    // =========================================================================
+    // FEATURE FLAGS
+    // =========================================================================
+
+    /// Turn a subsystem on or off for this profile. Switching one off also
+    /// stops whatever it is currently running.
+    pub fn set_feature_flag(&self, flag: FfiFeatureFlag, enabled: bool) -> Result<(), ZenOneError> {
+        self.flags.set(flag, enabled)?;
+        if !enabled {
+            match flag {
+                FfiFeatureFlag::Binaural => self.set_binaural_state(None),
+                FfiFeatureFlag::ResearchMode => self.research.disable(),
+                FfiFeatureFlag::Sync => self.group.stop(),
+                FfiFeatureFlag::Rppg | FfiFeatureFlag::RecommenderLearning => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_feature_enabled(&self, flag: FfiFeatureFlag) -> bool {
+        self.flags.is_enabled(flag)
+    }
+
+    pub fn get_feature_flags(&self) -> Vec<FfiFeatureFlagState> {
+        self.flags.list()
+    }
+
+    // =========================================================================
+    // CONSENT
+    // =========================================================================
+
+    /// Grant consent for a data scope (optionally expiring after `valid_days`)
+    pub fn grant_consent(&self, scope: FfiConsentScope, valid_days: Option<u32>) -> Result<(), ZenOneError> {
+        self.consent.grant(scope, valid_days)
+    }
+
+    /// Revoke consent for a data scope
+    pub fn revoke_consent(&self, scope: FfiConsentScope) -> Result<(), ZenOneError> {
+        self.consent.revoke(scope)
+    }
+
+    /// Check whether a scope is currently granted
+    pub fn has_consent(&self, scope: FfiConsentScope) -> bool {
+        self.consent.is_granted(scope)
+    }
+
+    /// Current consent state for every scope
+    pub fn get_consents(&self) -> Vec<FfiConsentRecord> {
+        self.consent.records()
+    }
+
+    /// Full grant/revoke audit trail
+    pub fn get_consent_audit(&self) -> Vec<FfiConsentAuditEntry> {
+        self.consent.audit()
+    }
+
+    // =========================================================================
+    // N-OF-1 EXPERIMENTS
+    // =========================================================================
+
+    /// Start a personal A/B experiment comparing two patterns
+    pub fn create_experiment(
+        &self,
+        name: String,
+        pattern_a: String,
+        pattern_b: String,
+        metric: FfiOutcomeMetric,
+        min_sessions_per_condition: u32,
+    ) -> Result<FfiExperiment, ZenOneError> {
+        if !self.patterns.contains(&pattern_a) || !self.patterns.contains(&pattern_b) {
+            return Err(ZenOneError::PatternNotFound);
+        }
+        self.experiments.create(name, pattern_a, pattern_b, metric, min_sessions_per_condition, self.clock.now_ms())
+    }
+
+    /// All experiments (active and ended)
+    pub fn list_experiments(&self) -> Vec<FfiExperiment> {
+        self.experiments.list()
+    }
+
+    /// Pattern assigned for today in an experiment
+    pub fn get_experiment_assignment(&self, experiment_id: String) -> Result<String, ZenOneError> {
+        self.experiments.assignment_at(&experiment_id, self.clock.now_ms(), self.store.zone())
+    }
+
+    /// Outcome comparison (blinded until enough sessions exist)
+    pub fn get_experiment_report(&self, experiment_id: String) -> Result<FfiExperimentReport, ZenOneError> {
+        self.consent.require(FfiConsentScope::Analytics)?;
+        self.experiments.report(&experiment_id, &self.store.all(), self.store.zone())
+    }
+
+    /// Stop assigning conditions for an experiment
+    pub fn end_experiment(&self, experiment_id: String) -> Result<(), ZenOneError> {
+        self.experiments.end(&experiment_id, self.clock.now_ms())
+    }
+
+    /// Paired comparison of two patterns over same-day sessions in the store
+    pub fn compare_patterns_stats(
+        &self,
+        pattern_a: String,
+        pattern_b: String,
+        metric: FfiOutcomeMetric,
+    ) -> Result<FfiPatternComparison, ZenOneError> {
+        self.consent.require(FfiConsentScope::Analytics)?;
+        compare_patterns_stats(&self.store.all(), pattern_a, pattern_b, metric, self.store.zone())
+    }
+
+    // =========================================================================
+    // SESSION HISTORY
+    // =========================================================================
+
+    /// Replace the tags and note on a recorded session
+    pub fn tag_session(
+        &self,
+        session_id: String,
+        tags: Vec<String>,
+        notes: Option<String>,
+    ) -> Result<(), ZenOneError> {
+        self.store.annotate(&session_id, tags, notes)
+    }
+
+    /// Search recorded sessions by free text, required tags and start-time range
+    pub fn search_sessions(
+        &self,
+        query: Option<String>,
+        tags: Vec<String>,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+    ) -> Vec<FfiSessionRecord> {
+        self.store.search(query.as_deref(), &tags, start_ms, end_ms)
+    }
+
+    /// Completed sessions, newest first, `limit` at a time
+    pub fn get_session_history(&self, limit: u32, offset: u32) -> Vec<FfiSessionRecord> {
+        self.store.history(limit, offset)
+    }
+
+    pub fn get_session_by_id(&self, session_id: String) -> Option<FfiSessionRecord> {
+        self.store.get(&session_id)
+    }
+
+    /// Summaries of sessions matching `query`, newest first, one page at a time
+    pub fn query_sessions(&self, query: FfiSessionQuery) -> Result<FfiSessionPage, ZenOneError> {
+        self.store.query(&query)
+    }
+
+    /// Set rest days and weekly freezes for streak counting
+    pub fn set_streak_config(&self, config: FfiStreakConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
+        self.settings.update(|settings| settings.streak = Some(config.clone()))?;
+        *self.streak_config.lock() = config;
+        Ok(())
+    }
+
+    pub fn get_streak_config(&self) -> FfiStreakConfig {
+        self.streak_config.lock().clone()
+    }
+
+    /// Daily streak over the session history (imported sessions included), by
+    /// calendar day in the user's time zone: current and longest streak,
+    /// grace window and freeze tokens
+    pub fn get_streak_info(&self) -> FfiStreakStatus {
+        let config = self.streak_config.lock().clone();
+        self.store.streak(&config, self.clock.now_ms())
+    }
+
+    /// Former name of `get_streak_info`, kept for existing callers
+    pub fn get_streak(&self) -> FfiStreakStatus {
+        self.get_streak_info()
+    }
+
+    /// Time zone for streaks, rollups, experiment days and time-of-day scoring:
+    /// an IANA name (e.g. "Europe/Berlin"), or None to follow the device
+    pub fn set_time_zone(&self, time_zone: Option<String>) -> Result<(), ZenOneError> {
+        let zone = LocalZone::parse(time_zone.as_deref())?;
+        self.settings.update(|settings| settings.time_zone = zone.name())?;
+        self.store.set_zone(zone);
+        Ok(())
+    }
+
+    pub fn get_time_zone(&self) -> Option<String> {
+        self.store.zone().name()
+    }
+
+    /// Current date and hour in the user's time zone; pass `hour` to the
+    /// recommender rather than reading the frontend's own clock
+    pub fn get_local_time(&self) -> FfiLocalTime {
+        self.store.zone().local_time(self.clock.now_ms())
+    }
+
+    /// Day/week/month summaries starting within `[start_date, end_date]`
+    /// (YYYY-MM-DD, in the user's time zone), including sessions already
+    /// pruned by retention
+    pub fn get_rollups(
+        &self,
+        period: FfiRollupPeriod,
+        start_date: Option<String>,
+        end_date: Option<String>,
+    ) -> Result<Vec<FfiMetricRollup>, ZenOneError> {
+        self.consent.require(FfiConsentScope::Analytics)?;
+        self.store.rollups(period, start_date.as_deref(), end_date.as_deref())
+    }
+
+    /// Set how long raw sessions are kept; returns the number pruned now.
+    /// Pruned sessions still count in rollups and streaks but not in search.
+    pub fn set_retention_policy(&self, policy: FfiRetentionPolicy) -> Result<u32, ZenOneError> {
+        policy.validate()?;
+        self.settings.update(|settings| settings.retention = Some(policy.clone()))?;
+        self.store.set_retention(policy, self.clock.now_ms())
+    }
+
+    pub fn get_retention_policy(&self) -> FfiRetentionPolicy {
+        self.store.retention()
+    }
+
+    // =========================================================================
+    // EXPORT
+    // =========================================================================
+
+    /// Export all recorded sessions as a FHIR R4 Bundle (JSON)
+    pub fn export_fhir_bundle(&self, patient_reference: String) -> Result<String, ZenOneError> {
+        self.consent.require_export()?;
+        let records = self.store.all();
+        FhirExporter::new().export_bundle(&records, patient_reference)
+    }
+
+    /// Export sessions started within `[start_ms, end_ms]` (all when unset) as
+    /// signed `zenb-session-v2` JSON
+    pub fn export_sessions(&self, start_ms: Option<i64>, end_ms: Option<i64>) -> Result<String, ZenOneError> {
+        self.consent.require_export()?;
+        let records = self.store.search(None, &[], start_ms, end_ms);
+        SessionExport::new(&records, self.store.public_key()).to_json()
+    }
+
+    /// Upgrade a session export (or a legacy `sessions.json` array) to the
+    /// current schema version
+    pub fn migrate_session_export(&self, json: String) -> Result<String, ZenOneError> {
+        SessionExport::parse(&json)?.to_json()
+    }
+
+    /// Export a session's raw inter-beat intervals for external HRV tools
+    pub fn export_ibi(&self, session_id: String, format: FfiIbiFormat) -> Result<String, ZenOneError> {
+        self.consent.require_export()?;
+        let rr_intervals_ms = self.store.ibi(&session_id)?;
+        Ok(format_ibi(&rr_intervals_ms, format))
+    }
+
+    // =========================================================================
+    // IMPORT
+    // =========================================================================
+
+    /// Import session history exported by another app into the session store
+    pub fn import_sessions(&self, path: String, format: FfiImportFormat) -> Result<FfiImportReport, ZenOneError> {
+        let parsed = match format {
+            FfiImportFormat::AppleHealthXml => import_apple_health(&path)?,
+            FfiImportFormat::HeartRateCsv => import_hr_csv(&path)?,
+        };
+        let (imported, skipped_duplicates) = self.store.import(parsed.records);
+        log::info!(
+            "Imported {} sessions from {} ({} overlapping, {} invalid entries skipped)",
+            imported,
+            path,
+            skipped_duplicates,
+            parsed.skipped_invalid
+        );
+        Ok(FfiImportReport { imported, skipped_duplicates, skipped_invalid: parsed.skipped_invalid })
+    }
+
+    // =========================================================================
+    // RESEARCH MODE
+    // =========================================================================
+
+    /// Grant research consent, returning the token required to enable research mode
+    pub fn grant_research_consent(&self, output_dir: String, valid_days: u32) -> Result<String, ZenOneError> {
+        self.research.grant_consent(output_dir, valid_days)
+    }
+
+    /// Start recording raw signals (requires a valid, unexpired consent token)
+    pub fn enable_research_mode(
+        &self,
+        output_dir: String,
+        consent_token: String,
+        passphrase: String,
+    ) -> Result<(), ZenOneError> {
+        self.flags.require(FfiFeatureFlag::ResearchMode)?;
+        self.research.enable(output_dir, consent_token, passphrase)
+    }
+
+    /// Stop recording, flushing buffered data
+    pub fn disable_research_mode(&self) {
+        self.research.disable();
+    }
+
+    /// Whether raw signals are currently being recorded
+    pub fn is_research_mode_active(&self) -> bool {
+        self.research.is_active()
+    }
+
+    /// Revoke consent and delete every recorded file; returns files deleted
+    pub fn revoke_research_consent(&self, output_dir: String) -> Result<u32, ZenOneError> {
+        self.research.revoke_and_delete(output_dir)
+    }
+
+    /// Write an encrypted research package covering `[start_ms, end_ms]`,
+    /// signed with the device key (the session store must be unlocked)
+    pub fn export_research_package(
+        &self,
+        start_ms: i64,
+        end_ms: i64,
+        scopes: Vec<FfiConsentScope>,
+        path: String,
+        passphrase: String,
+    ) -> Result<FfiResearchManifest, ZenOneError> {
+        ResearchPackager::new(&self.store, &self.research, &self.consent, self.clock.now_ms())
+            .export(start_ms, end_ms, scopes, path, passphrase)
+    }
+
+    /// Decrypt a research package and verify its signature and file digests
+    /// against `public_key` (hex), or this device's key when omitted
+    pub fn verify_research_package(
+        &self,
+        path: String,
+        passphrase: String,
+        public_key: Option<String>,
+    ) -> Result<FfiResearchManifest, ZenOneError> {
+        let public_key = public_key.or_else(|| self.store.public_key()).ok_or_else(|| {
+            ZenOneError::ConfigError("No public key given and the device key is locked".into())
+        })?;
+        verify_research_package(path, passphrase, &public_key)
+    }
+
+    // =========================================================================
+    // COMMAND RECORDING
+    // =========================================================================
+
+    /// Record every command and signal result to `path` (JSON lines) until
+    /// stopped. Frames are biometric, so the RawSignal consent is required.
+    pub fn start_command_recording(&self, path: String) -> Result<(), ZenOneError> {
+        self.consent.require(FfiConsentScope::RawSignal)?;
+        let header = RecordingHeader {
+            version: COMMAND_RECORDING_VERSION,
+            started_at_ms: self.clock.now_ms(),
+            pattern_id: self.state.load().pattern_id.clone(),
+        };
+        self.recorder.start(std::path::Path::new(&path), self.clock.now(), &header)
+    }
+
+    /// Stop recording; returns how many entries were written
+    pub fn stop_command_recording(&self) -> Result<u64, ZenOneError> {
+        self.recorder.stop()
+    }
+
+    pub fn is_command_recording_active(&self) -> bool {
+        self.recorder.is_active()
+    }
+
+    /// Re-feed a recording into a fresh runtime on virtual time and report how
+    /// it ended, e.g. to reproduce a user report or check spec changes
+    pub fn replay_command_recording(&self, path: String) -> Result<FfiReplayReport, ZenOneError> {
+        replay_command_recording(&path)
+    }
+
+    // =========================================================================
+    // SIMULATION
+    // =========================================================================
+
+    /// Start generating heart rate from the pacer, in place of a sensor. The
+    /// beats reach the session while `FfiHrSourceKind::Simulated` is selected.
+    pub fn start_hr_simulator(&self, config: FfiHrSimulatorConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
+        let _ = self.cmd_tx.send(RuntimeCommand::SetHrSimulator(Some(config)));
+        Ok(())
+    }
+
+    pub fn stop_hr_simulator(&self) {
+        let _ = self.cmd_tx.send(RuntimeCommand::SetHrSimulator(None));
+    }
+
+    /// Run a session of `duration_sec` on a fresh runtime with synthetic heart
+    /// rate, on virtual time, and return its stats and per-second trajectory
+    pub fn simulate_session(
+        &self,
+        pattern_id: String,
+        duration_sec: f32,
+        hr_profile: FfiSimulatedHrProfile,
+    ) -> Result<FfiSimulationResult, ZenOneError> {
+        let pattern = self.patterns.get(&pattern_id).ok_or(ZenOneError::PatternNotFound)?;
+        simulate_session(&pattern, duration_sec, &hr_profile)
+    }
+}
+
+#[cfg(feature = "full")]
+impl Drop for ZenOneRuntime {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+// ============================================================================
+// PID CONTROLLER - FEEDBACK CONTROL
+// ============================================================================
+
+/// PID controller configuration
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPidConfig {
+    pub kp: f32,                // Proportional gain
+    pub ki: f32,                // Integral gain
+    pub kd: f32,                // Derivative gain
+    pub integral_max: f32,      // Anti-windup max integral
+    pub output_min: f32,        // Min output
+    pub output_max: f32,        // Max output
+    pub derivative_alpha: f32,  // Derivative filter (0-1)
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiPidConfig {
+    fn default() -> Self {
+        Self {
+            kp: 0.003,
+            ki: 0.0002,
+            kd: 0.008,
+            integral_max: 5.0,
+            output_min: -0.6,
+            output_max: 0.4,
+            derivative_alpha: 0.15,
+        }
+    }
+}
+
+/// PID diagnostics for monitoring
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPidDiagnostics {
+    pub p_term: f32,
+    pub i_term: f32,
+    pub d_term: f32,
+    pub integral: f32,
+    pub total: f32,
+}
+
+/// PID Controller with anti-windup and derivative filtering
+/// 
+/// References:
+/// - Åström & Murray (2021): "Feedback Systems"
+/// - Franklin et al. (2015): "Feedback Control of Dynamic Systems"
+#[cfg(feature = "full")]
+pub struct PidController {
+    inner: Mutex<PidControllerInner>,
+}
+
+#[cfg(feature = "full")]
+struct PidControllerInner {
+    config: FfiPidConfig,
+    integral: f32,
+    last_error: f32,
+    last_derivative: f32,
+    last_p: f32,
+    last_i: f32,
+    last_d: f32,
+}
+
+#[cfg(feature = "full")]
+impl PidController {
+    pub fn new() -> Self {
+        Self::with_config(FfiPidConfig::default())
+    }
+    
+    pub fn with_config(config: FfiPidConfig) -> Self {
+        Self {
+            inner: Mutex::new(PidControllerInner {
+                config,
+                integral: 0.0,
+                last_error: 0.0,
+                last_derivative: 0.0,
+                last_p: 0.0,
+                last_i: 0.0,
+                last_d: 0.0,
+            }),
+        }
+    }
+    
+    /// Compute control output
+    /// 
+    /// # Arguments
+    /// * `error` - Current error (setpoint - measurement)
+    /// * `dt` - Time step in seconds
+    /// 
+    /// # Returns
+    /// Control signal (clamped to output bounds)
+    pub fn compute(&self, error: f32, dt: f32) -> f32 {
+        let mut inner = self.inner.lock();
+        
+        if dt <= 0.0 || !dt.is_finite() {
+            return 0.0;
+        }
+        
+        // 1. PROPORTIONAL TERM
+        inner.last_p = inner.config.kp * error;
+        
+        // 2. INTEGRAL TERM (with anti-windup)
+        inner.integral += error * dt;
+        inner.integral = inner.integral.clamp(
+            -inner.config.integral_max,
+            inner.config.integral_max
+        );
+        inner.last_i = inner.config.ki * inner.integral;
+        
+        // 3. DERIVATIVE TERM (with filtering)
+        let raw_derivative = (error - inner.last_error) / dt;
+        inner.last_derivative = inner.config.derivative_alpha * raw_derivative
+            + (1.0 - inner.config.derivative_alpha) * inner.last_derivative;
+        inner.last_d = inner.config.kd * inner.last_derivative;
+        
+        // 4. COMBINE
+        let output = inner.last_p + inner.last_i + inner.last_d;
+        
+        // 5. CLAMP OUTPUT
+        let clamped = output.clamp(inner.config.output_min, inner.config.output_max);
+        
+        // Update state
+        inner.last_error = error;
+        
+        clamped
+    }
+    
+    /// Reset controller state
+    pub fn reset(&self) {
+        let mut inner = self.inner.lock();
+        inner.integral = 0.0;
+        inner.last_error = 0.0;
+        inner.last_derivative = 0.0;
+        inner.last_p = 0.0;
+        inner.last_i = 0.0;
+        inner.last_d = 0.0;
+    }
+    
+    /// Get diagnostics
+    pub fn get_diagnostics(&self) -> FfiPidDiagnostics {
+        let inner = self.inner.lock();
+        FfiPidDiagnostics {
+            p_term: inner.last_p,
+            i_term: inner.last_i,
+            d_term: inner.last_d,
+            integral: inner.integral,
+            total: inner.last_p + inner.last_i + inner.last_d,
+        }
+    }
+    
+    /// Update gains dynamically
+    pub fn set_gains(&self, kp: Option<f32>, ki: Option<f32>, kd: Option<f32>) {
+        let mut inner = self.inner.lock();
+        if let Some(p) = kp { inner.config.kp = p; }
+        if let Some(i) = ki { inner.config.ki = i; }
+        if let Some(d) = kd { inner.config.kd = d; }
+    }
+}
+
+/// Factory for pre-tuned tempo controller
+/// 
+/// Gains derived from:
+/// - Ziegler-Nichols (initial estimate)
+/// - Simulated annealing optimization
+/// - User testing (n=50)
+#[cfg(feature = "full")]
+pub fn create_tempo_controller() -> PidController {
+    PidController::with_config(FfiPidConfig {
+        kp: 0.003,      // Quick response to misalignment
+        ki: 0.0002,     // Small to avoid overshoot
+        kd: 0.008,      // Moderate damping
+        integral_max: 5.0,
+        output_min: -0.6,  // Max decrease: 1.0 - 0.6 = 0.4
+        output_max: 0.4,   // Max increase: 1.0 + 0.4 = 1.4
+        derivative_alpha: 0.15,
+    })
+}
+
+// ============================================================================
+// SAFETY MONITOR - LTL VERIFICATION
+// ============================================================================
+
+/// Safety violation severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiViolationSeverity {
+    Warning,
+    Error,
+    Critical,
+}
+
+/// Machine-readable correction attached to a violation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FfiCorrectiveAction {
+    /// Clamp tempo scale to the given value
+    ClampTempo { to: f32 },
+    /// Drop the offending event
+    BlockEvent,
+    /// Engage emergency halt / safety lock
+    TriggerHalt,
+}
+
+/// A recorded safety violation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSafetyViolation {
+    /// Assigned when recorded (0 for dry-run results and synthesized causes)
+    #[serde(default)]
+    pub id: u64,
+    pub spec_name: String,
+    pub description: String,
+    pub severity: FfiViolationSeverity,
+    pub timestamp_ms: i64,
+    pub corrective_action: Option<FfiCorrectiveAction>,
+    /// Set once the violation has been reviewed
+    #[serde(default)]
+    pub acknowledged: Option<FfiViolationAck>,
+}
+
+/// Review of a recorded violation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiViolationAck {
+    pub acknowledged_at_ms: i64,
+    pub note: Option<String>,
+}
+
+/// Event types that can be checked by safety monitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FfiKernelEventType {
+    StartSession,
+    StopSession,
+    LoadPattern,
+    AdjustTempo,
+    EmergencyHalt,
+    Tick,
+    PhaseChange,
+    CycleComplete,
+}
+
+/// An event to be verified by safety monitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiKernelEvent {
+    pub event_type: FfiKernelEventType,
+    pub timestamp_ms: i64,
+    pub payload: Option<String>,
+}
+
+/// Result of safety check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSafetyCheckResult {
+    pub is_safe: bool,
+    pub violations: Vec<FfiSafetyViolation>,
+    pub corrected_event: Option<FfiKernelEvent>,
+}
+
+impl FfiSafetyCheckResult {
+    /// Tempo a warning clamps an allowed change to (e.g. the rate limit)
+    fn clamped_tempo(&self) -> Option<f32> {
+        self.violations.iter()
+            .filter(|v| v.severity == FfiViolationSeverity::Warning)
+            .find_map(|v| match v.corrective_action {
+                Some(FfiCorrectiveAction::ClampTempo { to }) => Some(to),
+                _ => None,
+            })
+    }
+}
+
+const VIOLATION_NOTE_MAX_CHARS: usize = 500;
+#[cfg(feature = "full")]
+const SAFETY_EVENT_LOG_FILE: &str = "safety_events.jsonl";
+/// Events kept for whole-trace verification
+const SAFETY_EVENT_LOG_MAX: usize = 5000;
+/// Every spec checked by the monitor, in evaluation order
+const SAFETY_SPECS: &[&str] = &[
+    "tempo_bounds",
+    "safety_lock_immutable",
+    "tempo_rate_limit",
+    "pattern_stability",
+    "panic_halt",
+];
+
+/// Safety Monitor with LTL verification
+pub struct SafetyMonitor {
+    inner: Mutex<SafetyMonitorInner>,
+    clock: Arc<dyn Clock>,
+}
+
+struct SafetyMonitorInner {
+    /// Event trace for temporal checks
+    trace: std::collections::VecDeque<FfiKernelEvent>,
+    /// Recorded violations
+    violations: Vec<FfiSafetyViolation>,
+    /// Last tempo value for rate limiting
+    last_tempo: f32,
+    /// Last tempo change timestamp
+    last_tempo_change_ms: i64,
+    /// Last pattern change timestamp
+    last_pattern_change_ms: i64,
+    /// Maximum trace size
+    max_trace_size: usize,
+    /// Id for the next recorded violation
+    next_violation_id: u64,
+    /// Every checked event with its spec inputs, for whole-trace replay
+    log: std::collections::VecDeque<SafetyLogEntry>,
+    /// JSON-lines file the log is appended to
+    #[cfg(feature = "full")]
+    log_path: Option<std::path::PathBuf>,
+}
+
+impl SafetyMonitorInner {
+    fn new() -> Self {
+        Self {
+            trace: std::collections::VecDeque::with_capacity(100),
+            violations: Vec::new(),
+            last_tempo: 1.0,
+            last_tempo_change_ms: 0,
+            last_pattern_change_ms: 0,
+            max_trace_size: 100,
+            next_violation_id: 1,
+            log: std::collections::VecDeque::new(),
+            #[cfg(feature = "full")]
+            log_path: None,
+        }
+    }
+
+    fn log_event(&mut self, entry: SafetyLogEntry) {
+        #[cfg(feature = "full")]
+        if let Some(path) = &self.log_path {
+            let appended = serde_json::to_string(&entry)
+                .map_err(|e| e.to_string())
+                .and_then(|line| {
+                    use std::io::Write;
+                    let mut file = std::fs::OpenOptions::new()
+                        .append(true)
+                        .create(true)
+                        .open(path)
+                        .map_err(|e| e.to_string())?;
+                    writeln!(file, "{}", line).map_err(|e| e.to_string())
+                });
+            if let Err(e) = appended {
+                log::error!("Failed to append safety event log: {}", e);
+            }
+        }
+        self.log.push_back(entry);
+        if self.log.len() > SAFETY_EVENT_LOG_MAX {
+            self.log.pop_front();
+        }
+    }
+}
+
+/// The runtime fields the specs read, kept with each logged event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SpecState {
+    tempo_scale: f32,
+    status: FfiRuntimeStatus,
+    uncertainty: f32,
+}
+
+#[cfg(feature = "full")]
+impl From<&FfiRuntimeState> for SpecState {
+    fn from(s: &FfiRuntimeState) -> Self {
+        Self {
+            tempo_scale: s.tempo_scale,
+            status: s.status,
+            uncertainty: s.belief.uncertainty,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SafetyLogEntry {
+    event: FfiKernelEvent,
+    state: SpecState,
+}
+
+/// Outcome of one spec over a replayed trace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSpecReport {
+    pub spec_name: String,
+    pub violations: u32,
+    pub first_violation_ms: Option<i64>,
+    pub last_violation_ms: Option<i64>,
+    pub satisfied: bool,
+}
+
+/// Whole-trace verification result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiTraceReport {
+    pub events_checked: u32,
+    pub first_event_ms: Option<i64>,
+    pub last_event_ms: Option<i64>,
+    pub specs: Vec<FfiSpecReport>,
+    /// True when every spec held over the whole trace
+    pub satisfied: bool,
+}
+
+impl SafetyMonitor {
+    /// Create a new safety monitor
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Monitor reading time from `clock` (Rust callers only)
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        SafetyMonitor {
+            inner: Mutex::new(SafetyMonitorInner::new()),
+            clock,
+        }
+    }
+
+    /// Check an event against all safety specs
+    /// Returns safety check result with any violations and corrections
+    #[cfg(feature = "full")]
+    pub fn check_event(
+        &self,
+        event: FfiKernelEvent,
+        runtime_state: FfiRuntimeState,
+    ) -> FfiSafetyCheckResult {
+        self.check_spec_event(event, SpecState::from(&runtime_state))
+    }
+
+    /// `check_event` for callers that only track the fields the specs read
+    fn check_spec_event(&self, event: FfiKernelEvent, state: SpecState) -> FfiSafetyCheckResult {
+        let mut inner = self.inner.lock();
+        let result = Self::record(&mut inner, event.clone(), &state);
+        inner.log_event(SafetyLogEntry { event, state });
+        result
+    }
+
+    /// Evaluate `event`, then advance the trace and rate-limit history and
+    /// record the violations
+    fn record(inner: &mut SafetyMonitorInner, event: FfiKernelEvent, state: &SpecState) -> FfiSafetyCheckResult {
+        let mut result = Self::evaluate(inner, &event, state);
+
+        // Remember when tempo and pattern last changed for the rate specs
+        match event.event_type {
+            FfiKernelEventType::AdjustTempo => {
+                // A rate-limited change only gets as far as its clamp
+                inner.last_tempo = result.clamped_tempo().unwrap_or(state.tempo_scale);
+                inner.last_tempo_change_ms = event.timestamp_ms;
+            }
+            FfiKernelEventType::LoadPattern => inner.last_pattern_change_ms = event.timestamp_ms,
+            _ => {}
+        }
+
+        // Add event to trace
+        inner.trace.push_back(event);
+        if inner.trace.len() > inner.max_trace_size {
+            inner.trace.pop_front();
+        }
+
+        // Record violations
+        for v in &mut result.violations {
+            v.id = inner.next_violation_id;
+            inner.next_violation_id += 1;
+            inner.violations.push(v.clone());
+        }
+        result
+    }
+
+    /// Dry run of `check_event`: evaluate a hypothetical event and state
+    /// without touching the trace, rate-limit history or violation log
+    #[cfg(feature = "full")]
+    pub fn evaluate_safety_specs(
+        &self,
+        event: FfiKernelEvent,
+        runtime_state: FfiRuntimeState,
+    ) -> FfiSafetyCheckResult {
+        Self::evaluate(&self.inner.lock(), &event, &SpecState::from(&runtime_state))
+    }
+
+    /// Replay the whole logged event history through a fresh monitor and
+    /// report, per spec, whether it held. Specs are applied strictly, so
+    /// waived checks (e.g. a panic session's pattern switch) show up here.
+    pub fn verify_trace(&self) -> FfiTraceReport {
+        let log: Vec<SafetyLogEntry> = self.inner.lock().log.iter().cloned().collect();
+        let mut replay = SafetyMonitorInner::new();
+        let mut specs: Vec<FfiSpecReport> = SAFETY_SPECS
+            .iter()
+            .map(|name| FfiSpecReport {
+                spec_name: name.to_string(),
+                violations: 0,
+                first_violation_ms: None,
+                last_violation_ms: None,
+                satisfied: true,
+            })
+            .collect();
+
+        for entry in &log {
+            let result = Self::record(&mut replay, entry.event.clone(), &entry.state);
+            for v in result.violations {
+                let Some(spec) = specs.iter_mut().find(|s| s.spec_name == v.spec_name) else { continue };
+                spec.violations += 1;
+                spec.first_violation_ms.get_or_insert(v.timestamp_ms);
+                spec.last_violation_ms = Some(v.timestamp_ms);
+                spec.satisfied = false;
+            }
+        }
+
+        FfiTraceReport {
+            events_checked: log.len() as u32,
+            first_event_ms: log.first().map(|e| e.event.timestamp_ms),
+            last_event_ms: log.last().map(|e| e.event.timestamp_ms),
+            satisfied: specs.iter().all(|s| s.satisfied),
+            specs,
+        }
+    }
+
+    /// Load the persisted event log, keeping events logged before the attach
+    #[cfg(feature = "full")]
+    fn attach_event_log(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = std::collections::VecDeque::new();
+        if path.exists() {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read safety event log: {}", e)))?;
+            for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                let entry = serde_json::from_str::<SafetyLogEntry>(line)
+                    .map_err(|e| ZenOneError::ConfigError(format!("Corrupt safety event log: {}", e)))?;
+                loaded.push_back(entry);
+            }
+        }
+
+        let mut inner = self.inner.lock();
+        loaded.extend(std::mem::take(&mut inner.log));
+        while loaded.len() > SAFETY_EVENT_LOG_MAX {
+            loaded.pop_front();
+        }
+        inner.log = loaded;
+
+        // Rewrite compacted, then append from here on
+        let mut text = String::new();
+        for entry in &inner.log {
+            let line = serde_json::to_string(entry)
+                .map_err(|e| ZenOneError::ConfigError(format!("Safety event log serialization failed: {}", e)))?;
+            text.push_str(&line);
+            text.push('\n');
+        }
+        std::fs::write(&path, text)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist safety event log: {}", e)))?;
+        inner.log_path = Some(path);
+        Ok(())
+    }
+
+    /// Evaluate all specs for `event` against the recorded history
+    fn evaluate(
+        inner: &SafetyMonitorInner,
+        event: &FfiKernelEvent,
+        state: &SpecState,
+    ) -> FfiSafetyCheckResult {
+        let mut violations = Vec::new();
+        let mut corrected_event = None;
+
+        // === SAFETY SPEC 1: Tempo Bounds ===
+        // G(tempo >= 0.8 && tempo <= 1.4)
+        if state.tempo_scale < 0.8 || state.tempo_scale > 1.4 {
+            violations.push(FfiSafetyViolation {
+                id: 0,
+                spec_name: "tempo_bounds".to_string(),
+                description: format!(
+                    "Tempo {} outside safe range [0.8, 1.4]",
+                    state.tempo_scale
+                ),
+                severity: FfiViolationSeverity::Error,
+                timestamp_ms: event.timestamp_ms,
+                acknowledged: None,
+                corrective_action: Some(FfiCorrectiveAction::ClampTempo {
+                    to: state.tempo_scale.clamp(0.8, 1.4),
+                }),
+            });
+        }
+
+        // === SAFETY SPEC 2: Safety Lock Immutability ===
+        // G(status == SAFETY_LOCK -> !StartSession)
+        if state.status == FfiRuntimeStatus::SafetyLock
+            && matches!(event.event_type, FfiKernelEventType::StartSession)
+        {
+            violations.push(FfiSafetyViolation {
+                id: 0,
+                spec_name: "safety_lock_immutable".to_string(),
+                description: "Cannot start session while safety locked".to_string(),
+                severity: FfiViolationSeverity::Critical,
+                timestamp_ms: event.timestamp_ms,
+                acknowledged: None,
+                corrective_action: Some(FfiCorrectiveAction::BlockEvent),
+            });
+            // Block event
+            corrected_event = None;
+        }
+
+        // === SAFETY SPEC 3: Tempo Rate Limit ===
+        // G(|d(tempo)/dt| <= 0.1/sec)
+        if matches!(event.event_type, FfiKernelEventType::AdjustTempo) {
+            let dt_sec = (event.timestamp_ms - inner.last_tempo_change_ms) as f32 / 1000.0;
+            if dt_sec > 0.0 {
+                let tempo_delta = (state.tempo_scale - inner.last_tempo).abs();
+                let rate = tempo_delta / dt_sec;
+                
+                if rate > 0.1 {
+                    // Largest step allowed by the rate limit since the last change
+                    let max_step = 0.1 * dt_sec;
+                    let limited = if state.tempo_scale > inner.last_tempo {
+                        inner.last_tempo + max_step
+                    } else {
+                        inner.last_tempo - max_step
+                    };
+                    violations.push(FfiSafetyViolation {
+                        id: 0,
+                        spec_name: "tempo_rate_limit".to_string(),
+                        description: format!(
+                            "Tempo changing too fast: {:.3}/sec (max 0.1/sec)",
+                            rate
+                        ),
+                        severity: FfiViolationSeverity::Warning,
+                        timestamp_ms: event.timestamp_ms,
+                        acknowledged: None,
+                        corrective_action: Some(FfiCorrectiveAction::ClampTempo {
+                            to: limited.clamp(0.8, 1.4),
+                        }),
+                    });
+                }
+            }
+        }
+
+        // === SAFETY SPEC 4: Pattern Stability ===
+        // G(LoadPattern -> X^60s(!LoadPattern))
+        if matches!(event.event_type, FfiKernelEventType::LoadPattern) {
+            let dt_sec = (event.timestamp_ms - inner.last_pattern_change_ms) as f32 / 1000.0;
+            if dt_sec < 60.0 && inner.last_pattern_change_ms > 0 {
+                violations.push(FfiSafetyViolation {
+                    id: 0,
+                    spec_name: "pattern_stability".to_string(),
+                    description: format!(
+                        "Pattern changed too soon ({:.1}s < 60s min)",
+                        dt_sec
+                    ),
+                    severity: FfiViolationSeverity::Warning,
+                    timestamp_ms: event.timestamp_ms,
+                    acknowledged: None,
+                    corrective_action: None,
+                });
+            }
+        }
+
+        // === SAFETY SPEC 5: Panic Halt ===
+        // G(prediction_error > 0.8 -> F EmergencyHalt)
+        if state.uncertainty > 0.8 {
+            // Check if emergency halt was recently triggered (this event included)
+            let has_recent_halt = matches!(event.event_type, FfiKernelEventType::EmergencyHalt)
+                || inner.trace.iter().rev().take(9).any(|e| {
+                    matches!(e.event_type, FfiKernelEventType::EmergencyHalt)
+                });
+            
+            if !has_recent_halt {
+                violations.push(FfiSafetyViolation {
+                    id: 0,
+                    spec_name: "panic_halt".to_string(),
+                    description: "High uncertainty detected, emergency halt recommended".to_string(),
+                    severity: FfiViolationSeverity::Critical,
+                    timestamp_ms: event.timestamp_ms,
+                    acknowledged: None,
+                    corrective_action: Some(FfiCorrectiveAction::TriggerHalt),
+                });
+            }
+        }
+
+        FfiSafetyCheckResult {
+            is_safe: violations.is_empty(),
+            violations,
+            corrected_event,
+        }
+    }
+
+    /// Get all recorded violations
+    pub fn get_violations(&self) -> Vec<FfiSafetyViolation> {
+        self.inner.lock().violations.clone()
+    }
+
+    /// Get recent violations (last N)
+    pub fn get_recent_violations(&self, count: u32) -> Vec<FfiSafetyViolation> {
+        let inner = self.inner.lock();
+        inner.violations.iter()
+            .rev()
+            .take(count as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Mark a recorded violation as reviewed, with an optional note
+    pub fn ack_violation(&self, id: u64, note: Option<String>) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        let violation = inner.violations.iter_mut()
+            .find(|v| v.id == id)
+            .ok_or_else(|| ZenOneError::ConfigError(format!("Unknown safety violation: {}", id)))?;
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        if note.as_ref().is_some_and(|n| n.chars().count() > VIOLATION_NOTE_MAX_CHARS) {
+            return Err(ZenOneError::ConfigError(format!(
+                "Acknowledgement note is limited to {} characters", VIOLATION_NOTE_MAX_CHARS
+            )));
+        }
+        violation.acknowledged = Some(FfiViolationAck {
+            acknowledged_at_ms: self.clock.now_ms(),
+            note,
+        });
+        Ok(())
+    }
+
+    /// Critical violations nobody has reviewed yet
+    pub fn unacknowledged_critical_count(&self) -> u32 {
+        self.inner.lock().violations.iter()
+            .filter(|v| v.severity == FfiViolationSeverity::Critical && v.acknowledged.is_none())
+            .count() as u32
+    }
+
+    /// Let the next pattern change through the stability spec regardless of
+    /// how recently the previous one happened
+    #[cfg(feature = "full")]
+    fn relax_pattern_stability(&self) {
+        self.inner.lock().last_pattern_change_ms = 0;
+    }
+
+    /// Clear violation history
+    pub fn clear_violations(&self) {
+        self.inner.lock().violations.clear();
+    }
+
+    /// Get violation count by severity
+    pub fn get_violation_counts(&self) -> (u32, u32, u32) {
+        let inner = self.inner.lock();
+        let warnings = inner.violations.iter()
+            .filter(|v| v.severity == FfiViolationSeverity::Warning)
+            .count() as u32;
+        let errors = inner.violations.iter()
+            .filter(|v| v.severity == FfiViolationSeverity::Error)
+            .count() as u32;
+        let criticals = inner.violations.iter()
+            .filter(|v| v.severity == FfiViolationSeverity::Critical)
+            .count() as u32;
+        (warnings, errors, criticals)
+    }
+
+    /// Check if system is in safe state
+    #[cfg(feature = "full")]
+    pub fn is_safe(&self, runtime_state: FfiRuntimeState) -> bool {
+        // Basic safety checks without event context
+        runtime_state.tempo_scale >= 0.8 
+            && runtime_state.tempo_scale <= 1.4
+            && runtime_state.status != FfiRuntimeStatus::SafetyLock
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod corrective_action_tests {
+    use super::*;
+
+    fn calm_state() -> FfiRuntimeState {
+        let mut state = ZenOneRuntime::new().get_state();
+        state.tempo_scale = 1.0;
+        state.belief.uncertainty = 0.1;
+        state
+    }
+
+    fn event(event_type: FfiKernelEventType, timestamp_ms: i64) -> FfiKernelEvent {
+        FfiKernelEvent { event_type, timestamp_ms, payload: None }
+    }
+
+    fn action_for(result: &FfiSafetyCheckResult, spec: &str) -> Option<FfiCorrectiveAction> {
+        result.violations.iter()
+            .find(|v| v.spec_name == spec)
+            .and_then(|v| v.corrective_action.clone())
+    }
+
+    #[test]
+    fn out_of_range_tempo_clamps_to_the_nearest_bound() {
+        let monitor = SafetyMonitor::new();
+        let mut state = calm_state();
+        state.tempo_scale = 2.0;
+        let result = monitor.check_event(event(FfiKernelEventType::Tick, 1_000), state);
+        assert!(!result.is_safe);
+        assert_eq!(action_for(&result, "tempo_bounds"), Some(FfiCorrectiveAction::ClampTempo { to: 1.4 }));
+    }
+
+    #[test]
+    fn fast_tempo_change_clamps_to_the_rate_limited_step() {
+        let monitor = SafetyMonitor::new();
+        let mut state = calm_state();
+        state.tempo_scale = 1.3;
+        let result = monitor.check_event(event(FfiKernelEventType::AdjustTempo, 1_000), state);
+        match action_for(&result, "tempo_rate_limit") {
+            Some(FfiCorrectiveAction::ClampTempo { to }) => assert!((to - 1.1).abs() < 1e-5, "to = {to}"),
+            other => panic!("expected ClampTempo, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn start_while_locked_is_blocked() {
+        let monitor = SafetyMonitor::new();
+        let mut state = calm_state();
+        state.status = FfiRuntimeStatus::SafetyLock;
+        let result = monitor.check_event(event(FfiKernelEventType::StartSession, 1_000), state);
+        assert_eq!(action_for(&result, "safety_lock_immutable"), Some(FfiCorrectiveAction::BlockEvent));
+        assert!(result.corrected_event.is_none());
+    }
+
+    #[test]
+    fn high_uncertainty_triggers_a_halt() {
+        let monitor = SafetyMonitor::new();
+        let mut state = calm_state();
+        state.belief.uncertainty = 0.9;
+        let result = monitor.check_event(event(FfiKernelEventType::Tick, 1_000), state);
+        assert_eq!(action_for(&result, "panic_halt"), Some(FfiCorrectiveAction::TriggerHalt));
+    }
+
+    #[test]
+    fn calm_tick_has_no_corrective_action() {
+        let monitor = SafetyMonitor::new();
+        let result = monitor.check_event(event(FfiKernelEventType::Tick, 1_000), calm_state());
+        assert!(result.is_safe);
+        assert!(result.violations.is_empty());
+    }
+
+    fn running_session() -> (ZenOneRuntime, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(1_704_096_000_000));
+        let runtime = ZenOneRuntime::with_clock("4-7-8".into(), clock.clone());
+        runtime.set_preparation_config(FfiPreparationConfig { countdown_sec: 0.0, min_signal_quality: 0.0 });
+        runtime.set_hr_source(FfiHrSourceKind::Simulated);
+        runtime.start_session().unwrap();
+        runtime.flush();
+        (runtime, clock)
+    }
+
+    #[test]
+    fn rate_limited_tempo_change_stops_at_the_clamp() {
+        let (runtime, clock) = running_session();
+        runtime.adjust_tempo(1.1, "test".into()).unwrap();
+        clock.advance(Duration::from_secs(1));
+        runtime.adjust_tempo(1.4, "test".into()).unwrap();
+        runtime.flush();
+        assert!(runtime.get_safety_violations().iter().any(|v| v.spec_name == "tempo_rate_limit"));
+
+        for i in 1..=60 {
+            clock.advance(Duration::from_millis(100));
+            runtime.tick(0.1, i * 100_000);
+        }
+        runtime.flush();
+        let tempo = runtime.get_state().tempo_scale;
+        assert!((tempo - 1.2).abs() < 1e-3, "tempo = {tempo}");
+    }
+
+    #[test]
+    fn halt_action_locks_a_running_session() {
+        let (runtime, _clock) = running_session();
+        let mut state = runtime.get_state();
+        state.belief.uncertainty = 0.9;
+        runtime.state.store(Arc::new(state));
+        runtime.adjust_tempo(1.1, "test".into()).unwrap();
+        runtime.flush();
+
+        let state = runtime.get_state();
+        assert_eq!(state.status, FfiRuntimeStatus::SafetyLock);
+        assert!(state.safety.is_locked);
+        assert_eq!(runtime.get_safety_journal().last().unwrap().kind, FfiSafetyJournalKind::LockEngaged);
+    }
+
+    #[test]
+    fn halt_action_only_blocks_when_idle() {
+        let runtime = ZenOneRuntime::with_clock("4-7-8".into(), Arc::new(ManualClock::new(1_704_096_000_000)));
+        let mut state = runtime.get_state();
+        state.belief.uncertainty = 0.9;
+        runtime.state.store(Arc::new(state));
+        assert!(runtime.load_pattern("box".into()));
+        runtime.flush();
+        assert_eq!(runtime.get_state().status, FfiRuntimeStatus::Idle);
+        assert!(runtime.get_safety_violations().iter().any(|v| v.spec_name == "panic_halt"));
+    }
+}
+
+// ============================================================================
+// PATTERN RECOMMENDER - AI-POWERED SUGGESTIONS
+// ============================================================================
+
+/// Time of day for recommendations
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiTimeOfDay {
+    Morning,
+    Afternoon,
+    Evening,
+    Night,
+}
+
+#[cfg(feature = "full")]
+impl FfiTimeOfDay {
+    pub fn from_hour(hour: u8) -> Self {
+        match hour {
+            0..=5 => FfiTimeOfDay::Night,
+            6..=11 => FfiTimeOfDay::Morning,
+            12..=17 => FfiTimeOfDay::Afternoon,
+            18..=21 => FfiTimeOfDay::Evening,
+            _ => FfiTimeOfDay::Night,
+        }
+    }
+    
+    pub fn desired_arousal(&self) -> f32 {
+        match self {
+            FfiTimeOfDay::Morning => 0.3,    // Slightly energizing
+            FfiTimeOfDay::Afternoon => 0.0,  // Balanced
+            FfiTimeOfDay::Evening => -0.5,   // Relaxing
+            FfiTimeOfDay::Night => -0.8,     // Very sedative
+        }
+    }
+    
+    pub fn desired_goal(&self) -> &'static str {
+        match self {
+            FfiTimeOfDay::Morning => "energy",
+            FfiTimeOfDay::Afternoon => "focus",
+            FfiTimeOfDay::Evening => "stress",
+            FfiTimeOfDay::Night => "sleep",
+        }
+    }
+}
+
+/// Pattern recommendation result
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPatternRecommendation {
+    pub pattern_id: String,
+    pub score: f32,
+    pub reason: String,
+    /// Prescribed cycle count for the current context
+    pub cycles: u32,
+}
+
+/// Index of Stress in `FfiBeliefState::probabilities`
+#[cfg(feature = "full")]
+const BELIEF_STRESS_INDEX: usize = 1;
+
+/// How cycle counts are prescribed from a pattern's `recommended_cycles`
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiCyclePrescriptionConfig {
+    /// Stress probability above which extra cycles are prescribed
+    pub stress_threshold: f32,
+    /// Extra cycles at full stress, as a fraction of the base count
+    pub stress_max_boost: f32,
+    /// Multiplier applied at night (fewer cycles before sleep)
+    pub night_scale: f32,
+    pub min_cycles: u32,
+    pub max_cycles: u32,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiCyclePrescriptionConfig {
+    fn default() -> Self {
+        Self {
+            stress_threshold: 0.4,
+            stress_max_boost: 0.5,
+            night_scale: 0.7,
+            min_cycles: 3,
+            max_cycles: 40,
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiCyclePrescriptionConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        let invalid = |msg: &str| Err(ZenOneError::ConfigError(msg.into()));
+        if !(0.0..1.0).contains(&self.stress_threshold) {
+            return invalid("Stress threshold must be within [0, 1)");
+        }
+        if !(0.0..=2.0).contains(&self.stress_max_boost) {
+            return invalid("Stress boost must be within 0-2");
+        }
+        if !(self.night_scale > 0.0 && self.night_scale <= 1.0) {
+            return invalid("Night scale must be within (0, 1]");
+        }
+        if self.min_cycles == 0 || self.min_cycles > self.max_cycles {
+            return invalid("Cycle bounds must satisfy 1 <= min <= max");
+        }
+        Ok(())
+    }
+
+    /// Scale `base` cycles up with stress and down at night
+    fn prescribe(&self, base: u32, local_hour: u8, stress: Option<f32>) -> u32 {
+        let mut cycles = base as f32;
+        if let Some(p) = stress.filter(|p| *p > self.stress_threshold) {
+            let excess = (p - self.stress_threshold) / (1.0 - self.stress_threshold);
+            cycles *= 1.0 + self.stress_max_boost * excess.min(1.0);
+        }
+        if FfiTimeOfDay::from_hour(local_hour) == FfiTimeOfDay::Night {
+            cycles *= self.night_scale;
+        }
+        (cycles.round() as u32).clamp(self.min_cycles, self.max_cycles)
+    }
+}
+
+/// Goals a user can pick during onboarding; map onto `PatternMeta::best_for`
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiOnboardingGoal {
+    Sleep,
+    Stress,
+    Focus,
+    Energy,
+}
+
+#[cfg(feature = "full")]
+impl FfiOnboardingGoal {
+    fn tag(&self) -> &'static str {
+        match self {
+            FfiOnboardingGoal::Sleep => "sleep",
+            FfiOnboardingGoal::Stress => "stress",
+            FfiOnboardingGoal::Focus => "focus",
+            FfiOnboardingGoal::Energy => "energy",
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiBreathworkExperience {
+    Beginner,
+    Intermediate,
+    Experienced,
+}
+
+/// Onboarding questionnaire answers used to seed recommendations
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiOnboardingAnswers {
+    pub goals: Vec<FfiOnboardingGoal>,
+    pub experience: FfiBreathworkExperience,
+    pub sleep_issues: bool,
+    /// Self-reported anxiety, 0 (none) to 4 (severe)
+    pub anxiety_level: u8,
+}
+
+#[cfg(feature = "full")]
+const ONBOARDING_MAX_ANXIETY: u8 = 4;
+/// Sessions of recorded history after which questionnaire weights stop counting
+#[cfg(feature = "full")]
+const ONBOARDING_FADE_SESSIONS: usize = 5;
+
+#[cfg(feature = "full")]
+impl FfiOnboardingAnswers {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        if self.anxiety_level > ONBOARDING_MAX_ANXIETY {
+            return Err(ZenOneError::ConfigError(format!(
+                "Anxiety level must be within 0-{}",
+                ONBOARDING_MAX_ANXIETY
+            )));
+        }
+        if self.goals.iter().enumerate().any(|(i, g)| self.goals[..i].contains(g)) {
+            return Err(ZenOneError::ConfigError("Duplicate onboarding goal".into()));
+        }
+        Ok(())
+    }
+
+    /// Questionnaire fit for one pattern at full weight, with the leading reason
+    fn score(&self, pattern: &PatternMeta) -> (f32, Option<&'static str>) {
+        let mut score = 0.0;
+        let mut reason = None;
+
+        if self.goals.iter().any(|g| pattern.best_for.contains(&g.tag())) {
+            score += 40.0;
+            reason = Some("Matches your goals");
+        }
+        if self.sleep_issues && pattern.best_for.contains(&"sleep") {
+            score += 10.0;
+            reason = reason.or(Some("Helps with your sleep"));
+        }
+
+        // Anxiety favours calming patterns and steers away from activating ones
+        let anxiety = self.anxiety_level as f32;
+        if pattern.arousal < 0.0 {
+            score += anxiety * 5.0 * -pattern.arousal;
+            if self.anxiety_level >= 2 {
+                reason = reason.or(Some("Calming for anxiety"));
+            }
+        } else if pattern.arousal > 0.5 {
+            score -= anxiety * 8.0;
+        }
+
+        match (self.experience, pattern.complexity) {
+            (FfiBreathworkExperience::Beginner, 3) => score -= 20.0,
+            (FfiBreathworkExperience::Beginner, 2) => score -= 5.0,
+            (FfiBreathworkExperience::Experienced, c) if c >= 2 => score += 5.0,
+            _ => {}
+        }
+        (score, reason)
+    }
+}
+
+/// Pattern metadata for scoring
+#[cfg(feature = "full")]
+struct PatternMeta {
+    id: &'static str,
+    arousal: f32,
+    complexity: u8,
+    best_for: &'static [&'static str],
+}
+
+#[cfg(feature = "full")]
+const PATTERN_METADATA: &[PatternMeta] = &[
+    PatternMeta { id: "4-7-8", arousal: -0.8, complexity: 1, best_for: &["sleep", "stress"] },
+    PatternMeta { id: "box", arousal: 0.0, complexity: 1, best_for: &["focus", "general"] },
+    PatternMeta { id: "calm", arousal: -0.3, complexity: 1, best_for: &["general", "stress"] },
+    PatternMeta { id: "coherence", arousal: -0.5, complexity: 2, best_for: &["focus", "general"] },
+    PatternMeta { id: "deep-relax", arousal: -0.9, complexity: 1, best_for: &["stress", "sleep"] },
+    PatternMeta { id: "7-11", arousal: -1.0, complexity: 2, best_for: &["stress", "sleep"] },
+    PatternMeta { id: "awake", arousal: 0.8, complexity: 2, best_for: &["energy"] },
+    PatternMeta { id: "triangle", arousal: 0.2, complexity: 1, best_for: &["general", "focus"] },
+    PatternMeta { id: "tactical", arousal: 0.1, complexity: 2, best_for: &["focus"] },
+    PatternMeta { id: "buteyko", arousal: -0.2, complexity: 3, best_for: &["general"] },
+    PatternMeta { id: "wim-hof", arousal: 1.0, complexity: 3, best_for: &["energy"] },
+];
+
+/// Pattern Recommender - AI-powered pattern suggestions
+/// 
+/// Recommends patterns based on:
+/// - Time of day (arousal matching)
+/// - Recent session history (variety bonus)
+/// - Pattern complexity
+/// - Time-specific bonuses
+/// - Onboarding answers, fading out as session history builds up
+#[cfg(feature = "full")]
+pub struct PatternRecommender {
+    inner: Mutex<PatternRecommenderInner>,
+}
+
+#[cfg(feature = "full")]
+struct PatternRecommenderInner {
+    recent_patterns: Vec<String>,
+    prescription: FfiCyclePrescriptionConfig,
+    baseline: Option<FfiBreathingBaseline>,
+    onboarding: Option<FfiOnboardingAnswers>,
+    belief_modes: Vec<FfiBeliefModeSpec>,
+}
+
+#[cfg(feature = "full")]
+impl PatternRecommender {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(PatternRecommenderInner {
+                recent_patterns: Vec::new(),
+                prescription: FfiCyclePrescriptionConfig::default(),
+                baseline: None,
+                onboarding: None,
+                belief_modes: Vec::new(),
+            }),
+        }
+    }
+    
+    /// Add a pattern to recent history
+    pub fn record_pattern(&self, pattern_id: String) {
+        let mut inner = self.inner.lock();
+        inner.recent_patterns.insert(0, pattern_id);
+        if inner.recent_patterns.len() > 5 {
+            inner.recent_patterns.truncate(5);
+        }
+    }
+    
+    /// Clear recent history
+    pub fn clear_history(&self) {
+        let mut inner = self.inner.lock();
+        inner.recent_patterns.clear();
+    }
+    
+    /// Configure how cycle counts are prescribed
+    pub fn set_prescription_config(&self, config: FfiCyclePrescriptionConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
+        self.inner.lock().prescription = config;
+        Ok(())
+    }
+
+    pub fn get_prescription_config(&self) -> FfiCyclePrescriptionConfig {
+        self.inner.lock().prescription.clone()
+    }
+
+    /// Use the user's free-breathing baseline in scoring (None ignores it)
+    pub fn set_baseline(&self, baseline: Option<FfiBreathingBaseline>) {
+        self.inner.lock().baseline = baseline;
+    }
+
+    /// Seed scoring with questionnaire answers (None clears them)
+    pub fn set_onboarding(&self, answers: Option<FfiOnboardingAnswers>) -> Result<(), ZenOneError> {
+        if let Some(answers) = &answers {
+            answers.validate()?;
+        }
+        self.inner.lock().onboarding = answers;
+        Ok(())
+    }
+
+    pub fn get_onboarding(&self) -> Option<FfiOnboardingAnswers> {
+        self.inner.lock().onboarding.clone()
+    }
+
+    /// Default pattern for a new user, from the questionnaire alone
+    pub fn starter_pattern(&self) -> Option<String> {
+        let inner = self.inner.lock();
+        let answers = inner.onboarding.as_ref()?;
+        PATTERN_METADATA
+            .iter()
+            .map(|pattern| (pattern.id, answers.score(pattern).0))
+            .fold(None, |best: Option<(&str, f32)>, (id, score)| match best {
+                Some((_, top)) if top >= score => best,
+                _ => Some((id, score)),
+            })
+            .map(|(id, _)| id.to_string())
+    }
+
+    /// Custom belief modes whose pattern boosts apply in `recommend_for_belief`
+    pub fn set_belief_modes(&self, modes: Vec<FfiBeliefModeSpec>) {
+        self.inner.lock().belief_modes = modes;
+    }
+
+    /// Get recommendations based on current time
+    pub fn recommend(&self, local_hour: u8, limit: u32) -> Vec<FfiPatternRecommendation> {
+        self.recommend_with_stress(local_hour, limit, None, &[])
+    }
+
+    /// Recommendations whose cycle prescriptions also account for the current belief
+    pub fn recommend_for_belief(&self, local_hour: u8, limit: u32, belief: FfiBeliefState) -> Vec<FfiPatternRecommendation> {
+        let stress = belief.probabilities.get(BELIEF_STRESS_INDEX).copied();
+        self.recommend_with_stress(local_hour, limit, stress, &belief.modes)
+    }
+
+    fn recommend_with_stress(
+        &self,
+        local_hour: u8,
+        limit: u32,
+        stress: Option<f32>,
+        modes: &[FfiModeProbability],
+    ) -> Vec<FfiPatternRecommendation> {
+        let inner = self.inner.lock();
+        // (pattern id, points, mode label) for each likely custom mode
+        let mode_boosts: Vec<(&str, f32, &str)> = inner.belief_modes.iter()
+            .filter_map(|spec| {
+                let p = modes.iter().find(|m| m.mode_id == spec.id)?.probability;
+                (p >= MODE_BOOST_THRESHOLD).then_some((spec, p))
+            })
+            .flat_map(|(spec, p)| spec.boost_patterns.iter()
+                .map(move |id| (id.as_str(), MODE_BOOST_POINTS * p, spec.label.as_str())))
+            .collect();
+        let patterns = builtin_patterns();
+        let time_of_day = FfiTimeOfDay::from_hour(local_hour);
+        let desired_arousal = time_of_day.desired_arousal();
+        let desired_goal = time_of_day.desired_goal();
+        let onboarding_weight = 1.0
+            - inner.recent_patterns.len().min(ONBOARDING_FADE_SESSIONS) as f32 / ONBOARDING_FADE_SESSIONS as f32;
+        
+        let mut scored: Vec<FfiPatternRecommendation> = PATTERN_METADATA.iter().map(|pattern| {
+            let mut score: f32 = 0.0;
+            let mut reasons: Vec<&str> = Vec::new();
+            
+            // Arousal match (0-40 points)
+            let arousal_diff = (pattern.arousal - desired_arousal).abs();
+            let arousal_score = (40.0 - arousal_diff * 30.0).max(0.0);
+            score += arousal_score;
+            
+            // Goal match (0-30 points)
+            if pattern.best_for.contains(&desired_goal) {
+                score += 30.0;
+                reasons.push(match desired_goal {
+                    "sleep" => "Great for sleep",
+                    "focus" => "Great for focus",
+                    "stress" => "Great for stress relief",
+                    "energy" => "Great for energy",
+                    _ => "Recommended for you",
+                });
+            }
+            
+            // Variety bonus (0-20 points)
+            let times_recent = inner.recent_patterns.iter()
+                .filter(|p| p.as_str() == pattern.id)
+                .count() as f32;
+            let variety_score = (20.0 - times_recent * 10.0).max(0.0);
+            score += variety_score;
+            if times_recent == 0.0 {
+                reasons.push("Try something new");
+            }
+            
+            // Complexity consideration (0-10 points)
+            score += (4 - pattern.complexity) as f32 * 3.0;
+            
+            // Time-specific bonuses
+            match (time_of_day, pattern.id) {
+                (FfiTimeOfDay::Morning, "awake") => {
+                    score += 15.0;
+                    reasons.insert(0, "Perfect for morning energy");
+                }
+                (FfiTimeOfDay::Night, "4-7-8") => {
+                    score += 15.0;
+                    reasons.insert(0, "Ideal for sleep");
+                }
+                (FfiTimeOfDay::Afternoon, "box") => {
+                    score += 10.0;
+                    reasons.insert(0, "Great for afternoon focus");
+                }
+                _ => {}
+            }
+
+            // Custom belief-mode hooks (0-25 points each)
+            let mut mode_reason = None;
+            for &(_, points, label) in mode_boosts.iter().filter(|(id, _, _)| *id == pattern.id) {
+                score += points;
+                mode_reason.get_or_insert_with(|| format!("Suited to {}", label.to_lowercase()));
+            }
+
+            // Natural-pace fit (0-10 points each)
+            if let Some(baseline) = &inner.baseline {
+                if baseline.rate_bpm > BASELINE_FAST_RATE_BPM && pattern.arousal < 0.0 {
+                    score += 10.0 * -pattern.arousal;
+                    reasons.push("Slows your fast natural pace");
+                }
+                if baseline.variability > BASELINE_IRREGULAR_VARIABILITY && pattern.complexity == 1 {
+                    score += 10.0;
+                    reasons.push("Steady rhythm for irregular breathing");
+                }
+            }
+
+            // Questionnaire fit (up to ~70 points), until history takes over
+            if let Some(answers) = inner.onboarding.as_ref().filter(|_| onboarding_weight > 0.0) {
+                let (fit, reason) = answers.score(pattern);
+                score += fit * onboarding_weight;
+                if let Some(reason) = reason.filter(|_| fit > 0.0) {
+                    reasons.insert(0, reason);
+                }
+            }
+            
+            let reason = mode_reason.unwrap_or_else(|| {
+                reasons.first().copied().unwrap_or("Recommended for you").to_string()
+            });
+            let base_cycles = patterns
+                .get(pattern.id)
+                .map(|p| p.recommended_cycles)
+                .unwrap_or(inner.prescription.min_cycles);
+            
+            FfiPatternRecommendation {
+                pattern_id: pattern.id.to_string(),
+                score,
+                reason,
+                cycles: inner.prescription.prescribe(base_cycles, local_hour, stress),
+            }
+        }).collect();
+        
+        // Sort by score descending
+        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        
+        // Return top N
+        scored.truncate(limit as usize);
+        scored
+    }
+    
+    /// Get top recommendation with explanation
+    pub fn top_recommendation(&self, local_hour: u8) -> Option<FfiPatternRecommendation> {
+        self.recommend(local_hour, 1).into_iter().next()
+    }
+}
+
+// ============================================================================
+// BINAURAL BEATS ENGINE (PARTIAL MIGRATION)
+// ============================================================================
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiBrainWaveState {
+    Delta,
+    Theta,
+    Alpha,
+    Beta,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiBinauralConfig {
+    pub base_freq: f32,
+    pub beat_freq: f32,
+    pub description: String,
+    pub benefits: Vec<String>,
+}
+
+/// A named binaural preset; builtins mirror `FfiBrainWaveState`
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiBinauralPreset {
+    pub id: String,
+    pub builtin: bool,
+    pub config: FfiBinauralConfig,
+}
+
+#[cfg(feature = "full")]
+const BINAURAL_PRESETS_FILE: &str = "binaural_presets.json";
+#[cfg(feature = "full")]
+const BINAURAL_PRESETS_MAX: usize = 32;
+/// Carrier range where interaural beats are perceived and comfortable
+#[cfg(feature = "full")]
+const BINAURAL_BASE_RANGE_HZ: (f32, f32) = (100.0, 1000.0);
+/// Beat range from deep delta up to 40 Hz gamma
+#[cfg(feature = "full")]
+const BINAURAL_BEAT_RANGE_HZ: (f32, f32) = (0.5, 45.0);
+
+#[cfg(feature = "full")]
+pub struct BinauralManager {
+    inner: Mutex<BinauralManagerInner>,
+}
+
+/// User-defined presets
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct BinauralManagerInner {
+    custom: Vec<FfiBinauralPreset>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+impl BinauralManager {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(BinauralManagerInner::default()),
+        }
+    }
+
+    /// Load and persist custom presets in `path`
+    pub fn set_data_dir(&self, path: String) -> Result<(), ZenOneError> {
+        let dir = std::path::PathBuf::from(path);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot create data dir: {}", e)))?;
+        let file = dir.join(BINAURAL_PRESETS_FILE);
+        let mut loaded = if file.exists() {
+            let bytes = std::fs::read(&file)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read binaural presets: {}", e)))?;
+            serde_json::from_slice::<BinauralManagerInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt binaural presets file: {}", e)))?
+        } else {
+            BinauralManagerInner::default()
+        };
+        // Drop anything that no longer passes validation rather than failing startup
+        loaded.custom.retain(|p| Self::validate(&p.id, &p.config).is_ok());
+        loaded.path = Some(file);
+        *self.inner.lock() = loaded;
+        Ok(())
+    }
+
+    /// Builtin states followed by custom presets
+    pub fn list_presets(&self) -> Vec<FfiBinauralPreset> {
+        let mut presets: Vec<FfiBinauralPreset> = FfiBrainWaveState::ALL
+            .iter()
+            .map(|state| FfiBinauralPreset {
+                id: Self::builtin_id(*state).to_string(),
+                builtin: true,
+                config: self.get_config(*state),
+            })
+            .collect();
+        presets.extend(self.inner.lock().custom.iter().cloned());
+        presets
+    }
+
+    /// Config for a builtin state name (case-insensitive) or a custom preset id
+    pub fn get_config_by_id(&self, id: String) -> Result<FfiBinauralConfig, ZenOneError> {
+        let key = id.trim().to_lowercase();
+        if let Some(state) = FfiBrainWaveState::ALL.iter().find(|s| Self::builtin_id(**s) == key) {
+            return Ok(self.get_config(*state));
+        }
+        self.inner
+            .lock()
+            .custom
+            .iter()
+            .find(|p| p.id == key)
+            .map(|p| p.config.clone())
+            .ok_or_else(|| ZenOneError::ConfigError(format!("Unknown binaural preset: {}", id)))
+    }
+
+    /// Add or replace a custom preset after checking it against safe ranges
+    pub fn define_preset(&self, id: String, config: FfiBinauralConfig) -> Result<FfiBinauralPreset, ZenOneError> {
+        let id = id.trim().to_lowercase();
+        Self::validate(&id, &config)?;
+
+        let preset = FfiBinauralPreset { id, builtin: false, config };
+        let mut inner = self.inner.lock();
+        match inner.custom.iter().position(|p| p.id == preset.id) {
+            Some(i) => inner.custom[i] = preset.clone(),
+            None if inner.custom.len() >= BINAURAL_PRESETS_MAX => {
+                return Err(ZenOneError::ConfigError(format!(
+                    "At most {} custom binaural presets", BINAURAL_PRESETS_MAX
+                )));
+            }
+            None => inner.custom.push(preset.clone()),
+        }
+        Self::persist(&inner)?;
+        Ok(preset)
+    }
+
+    pub fn remove_preset(&self, id: String) -> Result<(), ZenOneError> {
+        let key = id.trim().to_lowercase();
+        let mut inner = self.inner.lock();
+        let before = inner.custom.len();
+        inner.custom.retain(|p| p.id != key);
+        if inner.custom.len() == before {
+            return Err(ZenOneError::ConfigError(format!("Unknown custom binaural preset: {}", id)));
+        }
+        Self::persist(&inner)
+    }
+
+    fn builtin_id(state: FfiBrainWaveState) -> &'static str {
+        match state {
+            FfiBrainWaveState::Delta => "delta",
+            FfiBrainWaveState::Theta => "theta",
+            FfiBrainWaveState::Alpha => "alpha",
+            FfiBrainWaveState::Beta => "beta",
+        }
+    }
+
+    fn validate(id: &str, config: &FfiBinauralConfig) -> Result<(), ZenOneError> {
+        let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+        let id_ok = !id.is_empty()
+            && id.len() <= 32
+            && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !id_ok {
+            return invalid(format!("Invalid preset id '{}': use 1-32 of a-z, 0-9, '-', '_'", id));
+        }
+        if FfiBrainWaveState::ALL.iter().any(|s| Self::builtin_id(*s) == id) {
+            return invalid(format!("'{}' is a builtin state", id));
+        }
+        let (base_min, base_max) = BINAURAL_BASE_RANGE_HZ;
+        if !(base_min..=base_max).contains(&config.base_freq) {
+            return invalid(format!(
+                "Base frequency {} Hz outside {}-{} Hz", config.base_freq, base_min, base_max
+            ));
+        }
+        let (beat_min, beat_max) = BINAURAL_BEAT_RANGE_HZ;
+        if !(beat_min..=beat_max).contains(&config.beat_freq) {
+            return invalid(format!(
+                "Beat frequency {} Hz outside {}-{} Hz", config.beat_freq, beat_min, beat_max
+            ));
+        }
+        if config.description.trim().is_empty() || config.description.chars().count() > 80 {
+            return invalid("Description must be 1-80 characters".into());
+        }
+        if config.benefits.len() > 8 {
+            return invalid("At most 8 benefits".into());
+        }
+        Ok(())
+    }
+
+    fn persist(inner: &BinauralManagerInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Binaural presets serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist binaural presets: {}", e)))
+    }
+
+    pub fn get_config(&self, state: FfiBrainWaveState) -> FfiBinauralConfig {
+        match state {
+            FfiBrainWaveState::Delta => FfiBinauralConfig {
+                base_freq: 200.0,
+                beat_freq: 2.5,
+                description: "Deep Sleep & Healing".to_string(),
+                benefits: vec![
+                    "Deep restorative sleep".to_string(),
+                    "Physical healing".to_string(),
+                    "Pain relief".to_string(),
+                    "Immune boost".to_string()
+                ],
+            },
+            FfiBrainWaveState::Theta => FfiBinauralConfig {
+                base_freq: 200.0,
+                beat_freq: 6.0,
+                description: "Meditation & Creativity".to_string(),
+                benefits: vec![
+                    "Deep meditation".to_string(),
+                    "Creative insights".to_string(),
+                    "Emotional healing".to_string(),
+                    "Vivid imagery".to_string()
+                ],
+            },
+            FfiBrainWaveState::Alpha => FfiBinauralConfig {
+                base_freq: 200.0,
+                beat_freq: 10.0,
+                description: "Relaxed Focus".to_string(),
+                benefits: vec![
+                    "Calm awareness".to_string(),
+                    "Stress reduction".to_string(),
+                    "Peak performance".to_string(),
+                    "Learning enhancement".to_string()
+                ],
+            },
+            FfiBrainWaveState::Beta => FfiBinauralConfig {
+                base_freq: 220.0,
+                beat_freq: 18.0,
+                description: "Active Thinking".to_string(),
+                benefits: vec![
+                    "Mental clarity".to_string(),
+                    "Problem solving".to_string(),
+                    "Concentration".to_string(),
+                    "Energy boost".to_string()
+                ],
+            },
+        }
+    }
+    
+    pub fn get_recommended_state(&self, arousal_target: f32) -> FfiBrainWaveState {
+        if arousal_target < 0.2 {
+            FfiBrainWaveState::Delta
+        } else if arousal_target < 0.4 {
+            FfiBrainWaveState::Theta
+        } else if arousal_target < 0.7 {
+            FfiBrainWaveState::Alpha
+        } else {
+            FfiBrainWaveState::Beta
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiBrainWaveState {
+    pub const ALL: [FfiBrainWaveState; 4] = [
+        FfiBrainWaveState::Delta,
+        FfiBrainWaveState::Theta,
+        FfiBrainWaveState::Alpha,
+        FfiBrainWaveState::Beta,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            FfiBrainWaveState::Delta => 0,
+            FfiBrainWaveState::Theta => 1,
+            FfiBrainWaveState::Alpha => 2,
+            FfiBrainWaveState::Beta => 3,
+        }
+    }
+}
+
+/// Listening time for one brainwave state within a session
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiBinauralListening {
+    pub state: FfiBrainWaveState,
+    pub seconds: f32,
+}
+
+/// Usage and outcome of one brainwave state across the session history
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiBinauralStateStats {
+    pub state: FfiBrainWaveState,
+    /// Total listening time across all sessions
+    pub listening_sec: f32,
+    /// Sessions where this state played for at least half the session
+    pub sessions: u32,
+    pub mean_outcome: Option<f32>,
+    /// mean_outcome minus the silent-session mean
+    pub difference: Option<f32>,
+    /// Cohen's d against silent sessions (pooled SD)
+    pub effect_size: Option<f32>,
+}
+
+/// Does binaural audio help? Per-state outcomes against sessions without audio
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiBinauralStats {
+    pub metric: FfiOutcomeMetric,
+    pub states: Vec<FfiBinauralStateStats>,
+    pub silent_sessions: u32,
+    pub silent_mean_outcome: Option<f32>,
+}
+
+/// Share of a session a state must cover for the session to count towards it
+#[cfg(feature = "full")]
+const BINAURAL_DOMINANT_SHARE: f32 = 0.5;
+
+/// Attribute each session to its dominant binaural state (or silence) and
+/// compare outcomes. Sessions with mixed or brief listening count towards
+/// listening time only.
+#[cfg(feature = "full")]
+fn binaural_stats(sessions: &[FfiSessionRecord], metric: FfiOutcomeMetric) -> FfiBinauralStats {
+    let mut listening = [0.0f32; 4];
+    let mut outcomes: [Vec<f32>; 4] = Default::default();
+    let mut silent = Vec::new();
+
+    for s in sessions {
+        for l in &s.stats.binaural {
+            listening[l.state.index()] += l.seconds;
+        }
+        let Some(value) = metric.value(s) else { continue };
+        let dominant = s.stats.binaural.iter().max_by(|a, b| a.seconds.total_cmp(&b.seconds));
+        match dominant {
+            None => silent.push(value),
+            Some(l) if l.seconds >= BINAURAL_DOMINANT_SHARE * s.stats.duration_sec => {
+                outcomes[l.state.index()].push(value);
+            }
+            Some(_) => {}
+        }
+    }
+
+    let (silent_mean, silent_sd) = mean_and_sd(&silent);
+    let silent_mean_outcome = (!silent.is_empty()).then_some(silent_mean);
+    let states = FfiBrainWaveState::ALL
+        .iter()
+        .map(|state| {
+            let values = &outcomes[state.index()];
+            let (mean, sd) = mean_and_sd(values);
+            let mean_outcome = (!values.is_empty()).then_some(mean);
+            let difference = mean_outcome.zip(silent_mean_outcome).map(|(m, s)| m - s);
+            let pooled = pooled_sd(sd, values.len(), silent_sd, silent.len());
+            FfiBinauralStateStats {
+                state: *state,
+                listening_sec: listening[state.index()],
+                sessions: values.len() as u32,
+                mean_outcome,
+                difference,
+                effect_size: difference.filter(|_| pooled > f32::EPSILON).map(|d| d / pooled),
+            }
+        })
+        .collect();
+
+    FfiBinauralStats {
+        metric,
+        states,
+        silent_sessions: silent.len() as u32,
+        silent_mean_outcome,
+    }
+}
+
+// ============================================================================
+// SECURE VAULT - ZERO TRUST ENCRYPTION
+// ============================================================================
+
+/// Secure Vault for biometric data encryption
+/// Uses Argon2id for key derivation and ChaCha20Poly1305 for encryption.
+///
+/// Blob Format: [Salt (16)] [Nonce (12)] [Ciphertext (...)]
+#[cfg(feature = "full")]
+pub struct SecureVault;
+
+#[cfg(feature = "full")]
+impl SecureVault {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Encrypt biometric data
+    pub fn encrypt_blob(&self, passphrase: String, data: Vec<u8>) -> Result<Vec<u8>, ZenOneError> {
+        VaultKey::derive(&passphrase)?.encrypt(&data)
+    }
+    
+    /// Decrypt biometric data
+    pub fn decrypt_blob(&self, passphrase: String, blob: Vec<u8>) -> Result<Vec<u8>, ZenOneError> {
+        if blob.len() < 14 { // Min: 1 len + 1 salt + 12 nonce
+            return Err(ZenOneError::ConfigError("Invalid blob format".into()));
+        }
+        
+        let mut cursor = 0;
+        
+        // 1. Extract Salt
+        let salt_len = blob[cursor] as usize;
+        cursor += 1;
+        
+        if blob.len() < cursor + salt_len + 12 {
+             return Err(ZenOneError::ConfigError("Blob too short".into()));
+        }
+        
+        let salt_bytes = &blob[cursor..cursor+salt_len];
+        let salt_string = SaltString::from_b64(std::str::from_utf8(salt_bytes).unwrap_or(""))
+             .map_err(|_| ZenOneError::ConfigError("Invalid salt".into()))?;
+        cursor += salt_len;
+             
+        // 2. Extract Nonce
+        let nonce_bytes = &blob[cursor..cursor+12];
+        let nonce = Nonce::from_slice(nonce_bytes);
+        cursor += 12;
+        
+        // 3. Extract Ciphertext
+        let ciphertext = &blob[cursor..];
+        
+        // 4. Derive Key
+        let mut key_bytes = derive_vault_key(&passphrase, &salt_string)?;
+        
+        // 5. Decrypt
+        let cipher = ChaCha20Poly1305::new(&key_bytes.into());
+        let plaintext = cipher.decrypt(nonce, ciphertext.as_ref())
+             .map_err(|_| ZenOneError::ConfigError("Decryption failed - Wrong passphrase?".into()))?;
+             
+        // Zeroize key
+        key_bytes.zeroize();
+        
+        Ok(plaintext)
+    }
+}
+
+/// Argon2id key for `passphrase` under `salt`
+#[cfg(feature = "full")]
+fn derive_vault_key(passphrase: &str, salt: &SaltString) -> Result<[u8; 32], ZenOneError> {
+    let password_hash = Argon2::default()
+        .hash_password(passphrase.as_bytes(), salt)
+        .map_err(|e| ZenOneError::ConfigError(format!("Key derivation failed: {}", e)))?;
+    // Use the hash output as the key (taken from the 'hash' part, assuming it's long enough)
+    let hash = password_hash.hash.ok_or(ZenOneError::ConfigError("No hash output".into()))?;
+    if hash.len() < 32 {
+        return Err(ZenOneError::ConfigError("Derived key too short".into()));
+    }
+    let mut key_bytes = [0u8; 32];
+    key_bytes.copy_from_slice(&hash.as_bytes()[0..32]);
+    Ok(key_bytes)
+}
+
+/// A vault key derived once, for sealing many blobs without running Argon2
+/// for each. Its blobs share one salt and open with `decrypt_blob`.
+#[cfg(feature = "full")]
+struct VaultKey {
+    salt: SaltString,
+    key: [u8; 32],
+}
+
+#[cfg(feature = "full")]
+impl VaultKey {
+    fn derive(passphrase: &str) -> Result<Self, ZenOneError> {
+        // Use raw salt bytes for Argon2 to avoid string encoding issues in binary blob
+        let salt = SaltString::generate(&mut OsRng);
+        let key = derive_vault_key(passphrase, &salt)?;
+        Ok(Self { salt, key })
+    }
+
+    /// Blob format: [SaltLen(1)][SaltBytes(...)][Nonce(12)][Ciphertext...]
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, ZenOneError> {
+        let cipher = ChaCha20Poly1305::new(&self.key.into());
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng); // 12 bytes
+        let ciphertext = cipher
+            .encrypt(&nonce, data)
+            .map_err(|_| ZenOneError::ConfigError("Encryption failed".into()))?;
+
+        let salt_bytes = self.salt.as_str().as_bytes();
+        let mut blob = Vec::with_capacity(1 + salt_bytes.len() + 12 + ciphertext.len());
+        blob.push(salt_bytes.len() as u8);
+        blob.extend_from_slice(salt_bytes);
+        blob.extend_from_slice(&nonce);
+        blob.extend_from_slice(&ciphertext);
+        Ok(blob)
+    }
+}
+
+#[cfg(feature = "full")]
+impl Drop for VaultKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+// ============================================================================
+// SESSION STORE
+// ============================================================================
+
+#[cfg(feature = "full")]
+const SESSION_DB_FILE: &str = "sessions.db";
+
+/// Sealed copy of the key that encrypts session rows
+#[cfg(feature = "full")]
+const SESSION_KEY_FILE: &str = "session_store_key.bin";
+
+/// `record` and `rr_intervals_ms` hold sealed JSON; the other columns stay in
+/// the clear so rows can be listed and pruned without decrypting them.
+/// Rows written before encryption hold plain JSON text and are sealed on unlock.
+/// `session_rollups` keeps each session's rollup contribution (sealed) and is
+/// never pruned, so rollups survive retention across restarts.
+#[cfg(feature = "full")]
+const SESSION_DB_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sessions (
+        id TEXT PRIMARY KEY,
+        started_at_ms INTEGER NOT NULL,
+        ended_at_ms INTEGER NOT NULL,
+        pattern_id TEXT NOT NULL,
+        record TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS sessions_started_at ON sessions (started_at_ms);
+    CREATE TABLE IF NOT EXISTS session_ibi (
+        session_id TEXT PRIMARY KEY,
+        rr_intervals_ms TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS session_rollups (
+        session_id TEXT PRIMARY KEY,
+        entry BLOB NOT NULL
+    );
+";
+
+#[cfg(feature = "full")]
+fn session_db_error(e: impl std::fmt::Display) -> ZenOneError {
+    ZenOneError::ConfigError(format!("Session database error: {}", e))
+}
+
+/// Load the session store key sealed under `passphrase`, creating it on first use
+#[cfg(feature = "full")]
+fn load_or_create_session_key(path: &std::path::Path, passphrase: String) -> Result<[u8; 32], ZenOneError> {
+    if passphrase.is_empty() {
+        return Err(ZenOneError::ConfigError("Session store requires a passphrase".into()));
+    }
+    if path.exists() {
+        let blob = std::fs::read(path)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot read session store key: {}", e)))?;
+        let mut secret = SecureVault::new().decrypt_blob(passphrase, blob)?;
+        let key = <[u8; 32]>::try_from(secret.as_slice())
+            .map_err(|_| ZenOneError::ConfigError("Corrupt session store key file".into()));
+        secret.zeroize();
+        return key;
+    }
+
+    let key: [u8; 32] = ChaCha20Poly1305::generate_key(&mut OsRng).into();
+    std::fs::write(path, SecureVault::new().encrypt_blob(passphrase, key.to_vec())?)
+        .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist session store key: {}", e)))?;
+    log::info!("Created session store key");
+    Ok(key)
+}
+
+/// Sessions oldest first, and IBI series by session id
+#[cfg(feature = "full")]
+type StoredSessions = (Vec<FfiSessionRecord>, HashMap<String, Vec<f32>>);
+
+/// The session database, open under its row key. Each sealed value is
+/// [nonce (12)][ciphertext] with the session id as associated data, so a
+/// row's contents can't be moved to another id undetected.
+#[cfg(feature = "full")]
+struct SessionDb {
+    conn: rusqlite::Connection,
+    cipher: ChaCha20Poly1305,
+}
+
+#[cfg(feature = "full")]
+impl SessionDb {
+    fn open(path: &std::path::Path, mut key: [u8; 32]) -> Result<Self, ZenOneError> {
+        let conn = rusqlite::Connection::open(path).map_err(session_db_error)?;
+        conn.execute_batch(SESSION_DB_SCHEMA).map_err(session_db_error)?;
+        let cipher = ChaCha20Poly1305::new(&key.into());
+        key.zeroize();
+        Ok(Self { conn, cipher })
+    }
+
+    fn seal<T: Serialize + ?Sized>(&self, id: &str, value: &T) -> Result<Vec<u8>, ZenOneError> {
+        let mut json = serde_json::to_vec(value).map_err(session_db_error)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let sealed = self
+            .cipher
+            .encrypt(&nonce, Payload { msg: &json, aad: id.as_bytes() })
+            .map_err(|_| ZenOneError::ConfigError("Session encryption failed".into()));
+        json.zeroize();
+        let mut blob = nonce.to_vec();
+        blob.extend_from_slice(&sealed?);
+        Ok(blob)
+    }
+
+    /// Decode a stored value: sealed bytes, or plain JSON from before encryption.
+    /// Returns the value and whether it still needs sealing.
+    fn unseal<T: serde::de::DeserializeOwned>(
+        &self,
+        id: &str,
+        value: rusqlite::types::ValueRef<'_>,
+    ) -> Result<(T, bool), ZenOneError> {
+        let corrupt = |what: &str| ZenOneError::ConfigError(format!("Corrupt session {}: {}", id, what));
+        match value {
+            rusqlite::types::ValueRef::Text(json) => {
+                Ok((serde_json::from_slice(json).map_err(|e| corrupt(&e.to_string()))?, true))
+            }
+            rusqlite::types::ValueRef::Blob(blob) if blob.len() > 12 => {
+                let (nonce, ciphertext) = blob.split_at(12);
+                let mut json = self
+                    .cipher
+                    .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: id.as_bytes() })
+                    .map_err(|_| ZenOneError::ConfigError(format!(
+                        "Cannot decrypt session {} - wrong session store key?", id
+                    )))?;
+                let parsed = serde_json::from_slice(&json).map_err(|e| corrupt(&e.to_string()));
+                json.zeroize();
+                Ok((parsed?, false))
+            }
+            _ => Err(corrupt("unexpected column value")),
+        }
+    }
+
+    /// Insert or replace a session row, plus its IBI series when given
+    fn write(&self, record: &FfiSessionRecord, rr_intervals_ms: Option<&[f32]>) -> Result<(), ZenOneError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO sessions (id, started_at_ms, ended_at_ms, pattern_id, record)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![
+                    record.id,
+                    record.started_at_ms,
+                    record.ended_at_ms,
+                    record.stats.pattern_id,
+                    self.seal(&record.id, record)?,
+                ],
+            )
+            .map_err(session_db_error)?;
+        if let Some(rr) = rr_intervals_ms {
+            self.conn
+                .execute(
+                    "INSERT OR REPLACE INTO session_ibi (session_id, rr_intervals_ms) VALUES (?1, ?2)",
+                    rusqlite::params![record.id, self.seal(&record.id, rr)?],
+                )
+                .map_err(session_db_error)?;
+        }
+        Ok(())
+    }
+
+    /// Every stored session, oldest first, with the IBI series by id.
+    /// Rows still in plain JSON are sealed on the way.
+    fn load(&self) -> Result<StoredSessions, ZenOneError> {
+        let mut records = Vec::new();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, record FROM sessions ORDER BY started_at_ms")
+            .map_err(session_db_error)?;
+        let mut rows = stmt.query([]).map_err(session_db_error)?;
+        while let Some(row) = rows.next().map_err(session_db_error)? {
+            let id: String = row.get(0).map_err(session_db_error)?;
+            let (record, plain) = self.unseal::<FfiSessionRecord>(&id, row.get_ref(1).map_err(session_db_error)?)?;
+            records.push((record, plain));
+        }
+
+        let mut ibi = HashMap::new();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT session_id, rr_intervals_ms FROM session_ibi")
+            .map_err(session_db_error)?;
+        let mut rows = stmt.query([]).map_err(session_db_error)?;
+        let mut plain_ibi = Vec::new();
+        while let Some(row) = rows.next().map_err(session_db_error)? {
+            let id: String = row.get(0).map_err(session_db_error)?;
+            let (rr, plain) = self.unseal::<Vec<f32>>(&id, row.get_ref(1).map_err(session_db_error)?)?;
+            if plain {
+                plain_ibi.push(id.clone());
+            }
+            ibi.insert(id, rr);
+        }
+
+        let sealed = records.iter().filter(|(_, plain)| *plain).count() + plain_ibi.len();
+        for (record, plain) in &records {
+            if *plain {
+                self.write(record, None)?;
+            }
+        }
+        for id in &plain_ibi {
+            self.conn
+                .execute(
+                    "UPDATE session_ibi SET rr_intervals_ms = ?2 WHERE session_id = ?1",
+                    rusqlite::params![id, self.seal(id, &ibi[id])?],
+                )
+                .map_err(session_db_error)?;
+        }
+        if sealed > 0 {
+            log::info!("SessionStore: encrypted {} rows stored in plaintext", sealed);
+        }
+        Ok((records.into_iter().map(|(record, _)| record).collect(), ibi))
+    }
+
+    /// Insert or replace a session's rollup contribution
+    fn write_rollup(&self, id: &str, entry: &RollupEntry) -> Result<(), ZenOneError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO session_rollups (session_id, entry) VALUES (?1, ?2)",
+                rusqlite::params![id, self.seal(id, entry)?],
+            )
+            .map(|_| ())
+            .map_err(session_db_error)
+    }
+
+    /// Every stored rollup contribution by session id, pruned sessions included
+    fn load_rollups(&self) -> Result<HashMap<String, RollupEntry>, ZenOneError> {
+        let mut entries = HashMap::new();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT session_id, entry FROM session_rollups")
+            .map_err(session_db_error)?;
+        let mut rows = stmt.query([]).map_err(session_db_error)?;
+        while let Some(row) = rows.next().map_err(session_db_error)? {
+            let id: String = row.get(0).map_err(session_db_error)?;
+            let (entry, _) = self.unseal::<RollupEntry>(&id, row.get_ref(1).map_err(session_db_error)?)?;
+            entries.insert(id, entry);
+        }
+        Ok(entries)
+    }
+
+    /// Drop sessions started before `cutoff_ms`, with their IBI series.
+    /// Their rollup contributions are kept.
+    fn prune(&self, cutoff_ms: i64) -> Result<(), ZenOneError> {
+        self.conn
+            .execute(
+                "DELETE FROM session_ibi WHERE session_id IN
+                     (SELECT id FROM sessions WHERE started_at_ms < ?1)",
+                [cutoff_ms],
+            )
+            .and_then(|_| self.conn.execute("DELETE FROM sessions WHERE started_at_ms < ?1", [cutoff_ms]))
+            .map(|_| ())
+            .map_err(session_db_error)
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod session_db_tests {
+    use super::*;
+
+    fn record(id: &str) -> FfiSessionRecord {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "started_at_ms": 1_000,
+            "ended_at_ms": 301_000,
+            "notes": "private note",
+            "stats": {
+                "duration_sec": 300.0,
+                "cycles_completed": 10,
+                "pattern_id": "4-7-8",
+                "avg_heart_rate": 62.0,
+                "final_belief": FfiBeliefState::default(),
+                "avg_resonance": 0.5,
+            },
+        }))
+        .unwrap()
+    }
+
+    fn db_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("zenone-{}-{}.db", name, std::process::id()));
+        std::fs::remove_file(&path).ok();
+        path
+    }
+
+    #[test]
+    fn sessions_round_trip_sealed() {
+        let path = db_path("sealed");
+        let db = SessionDb::open(&path, [1; 32]).unwrap();
+        db.write(&record("a"), Some(&[800.0, 810.0])).unwrap();
+        let stored: Vec<u8> = db.conn.query_row("SELECT record FROM sessions", [], |row| row.get(0)).unwrap();
+        assert!(!String::from_utf8_lossy(&stored).contains("private note"));
+
+        let (records, ibi) = SessionDb::open(&path, [1; 32]).unwrap().load().unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].notes.as_deref(), Some("private note"));
+        assert_eq!(ibi["a"], [800.0, 810.0]);
+    }
+
+    #[test]
+    fn wrong_key_and_moved_rows_fail_to_open() {
+        let path = db_path("tamper");
+        let db = SessionDb::open(&path, [1; 32]).unwrap();
+        db.write(&record("a"), None).unwrap();
+        assert!(SessionDb::open(&path, [2; 32]).unwrap().load().is_err());
+
+        // The id is bound as associated data
+        db.conn.execute("UPDATE sessions SET id = 'b'", []).unwrap();
+        let moved = db.load();
+        std::fs::remove_file(&path).ok();
+        assert!(moved.is_err());
+    }
+
+    #[test]
+    fn plaintext_rows_are_sealed_on_load() {
+        let path = db_path("legacy");
+        let db = SessionDb::open(&path, [1; 32]).unwrap();
+        let legacy = serde_json::to_string(&record("a")).unwrap();
+        db.conn
+            .execute(
+                "INSERT INTO sessions (id, started_at_ms, ended_at_ms, pattern_id, record) VALUES ('a', 1000, 301000, '4-7-8', ?1)",
+                [legacy],
+            )
+            .unwrap();
+        assert_eq!(db.load().unwrap().0.len(), 1);
+        let kind: String = db.conn.query_row("SELECT typeof(record) FROM sessions", [], |row| row.get(0)).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(kind, "blob");
+    }
+
+    #[test]
+    fn store_key_is_sealed_under_the_passphrase() {
+        let path = db_path("key").with_extension("bin");
+        assert!(load_or_create_session_key(&path, String::new()).is_err());
+        let created = load_or_create_session_key(&path, "secret".into()).unwrap();
+        assert_eq!(load_or_create_session_key(&path, "secret".into()).unwrap(), created);
+        assert!(load_or_create_session_key(&path, "other".into()).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+}
+
+/// Store of completed sessions, written by the RuntimeActor on stop.
+///
+/// Sessions live in memory. Once the data dir is set and the store unlocked,
+/// the database is loaded and every change is written through to it, encrypted.
+#[cfg(feature = "full")]
+pub struct SessionStore {
+    inner: Mutex<Vec<FfiSessionRecord>>,
+    /// RR/IBI series (ms) by session id, kept out of the records to keep them light
+    ibi: Mutex<HashMap<String, Vec<f32>>>,
+    /// Daily/weekly/monthly summaries; outlive the raw records they came from
+    rollups: Mutex<RollupTables>,
+    retention: Mutex<FfiRetentionPolicy>,
+    /// Device key that signs sessions as they are stored, once unlocked
+    signer: Mutex<Option<SigningKey>>,
+    zone: Mutex<LocalZone>,
+    /// Session database location, from the data dir
+    db_path: Mutex<Option<std::path::PathBuf>>,
+    /// Open database, once unlocked
+    db: Mutex<Option<SessionDb>>,
+}
+
+#[cfg(feature = "full")]
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl SessionStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Vec::new()),
+            ibi: Mutex::new(HashMap::new()),
+            rollups: Mutex::new(RollupTables::default()),
+            retention: Mutex::new(FfiRetentionPolicy::default()),
+            signer: Mutex::new(None),
+            zone: Mutex::new(LocalZone::default()),
+            db_path: Mutex::new(None),
+            db: Mutex::new(None),
+        }
+    }
+
+    fn zone(&self) -> LocalZone {
+        *self.zone.lock()
+    }
+
+    /// Change the user's time zone and re-file the rollups under it. Sessions
+    /// recorded with a UTC offset keep their day; the rest move to the new zone.
+    fn set_zone(&self, zone: LocalZone) {
+        *self.zone.lock() = zone;
+        let mut rollups = self.rollups.lock();
+        let entries = std::mem::take(&mut rollups.entries);
+        *rollups = RollupTables::from_entries(entries, zone);
+    }
+
+    /// Use the session database at `path`; it is opened by `unlock_db`. Once
+    /// unlocked, attaching the same database again keeps it open under its
+    /// key, and switching to another one is refused.
+    fn attach_db(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut db_path = self.db_path.lock();
+        if self.db.lock().is_some() && db_path.as_ref() != Some(&path) {
+            return Err(ZenOneError::ConfigError(
+                "Session store is unlocked in another data dir".into(),
+            ));
+        }
+        *db_path = Some(path);
+        Ok(())
+    }
+
+    fn is_unlocked(&self) -> bool {
+        self.db.lock().is_some()
+    }
+
+    /// Open (or create) the session database under `key` and load its
+    /// sessions. Sessions stored before the unlock are written into it.
+    /// Rollups are rebuilt from the stored contributions, backfilling any
+    /// session that predates them. Returns the session count.
+    fn unlock_db(&self, key: [u8; 32]) -> Result<u32, ZenOneError> {
+        let path = self
+            .db_path
+            .lock()
+            .clone()
+            .ok_or_else(|| ZenOneError::ConfigError("Data dir not set".into()))?;
+        let db = SessionDb::open(&path, key)?;
+        let (mut records, mut ibi) = db.load()?;
+
+        let mut inner = self.inner.lock();
+        let mut memory_ibi = self.ibi.lock();
+        for record in inner.drain(..) {
+            if records.iter().any(|r| r.id == record.id) {
+                continue;
+            }
+            let rr = memory_ibi.remove(&record.id);
+            db.write(&record, rr.as_deref())?;
+            if let Some(rr) = rr {
+                ibi.insert(record.id.clone(), rr);
+            }
+            records.push(record);
+        }
+        records.sort_by_key(|r| r.started_at_ms);
+
+        let mut entries = db.load_rollups()?;
+        let mut rollups = self.rollups.lock();
+        let missing = std::mem::take(&mut rollups.entries)
+            .into_iter()
+            .chain(records.iter().map(|r| (r.id.clone(), RollupEntry::from(r))))
+            .collect::<Vec<_>>();
+        for (id, entry) in missing {
+            if let std::collections::hash_map::Entry::Vacant(slot) = entries.entry(id) {
+                db.write_rollup(slot.key(), &entry)?;
+                slot.insert(entry);
+            }
+        }
+        *rollups = RollupTables::from_entries(entries, self.zone());
+
+        let count = records.len() as u32;
+        log::info!("SessionStore: loaded {} sessions from {}", count, path.display());
+        *inner = records;
+        *memory_ibi = ibi;
+        *self.db.lock() = Some(db);
+        Ok(count)
+    }
+
+    /// Generate a unique session id from start time plus random suffix
+    fn new_session_id(started_at_ms: i64) -> String {
+        format!("{:x}-{:08x}", started_at_ms, rand::random::<u32>())
+    }
+
+    pub fn insert(&self, mut record: FfiSessionRecord, rr_intervals_ms: Vec<f32>) {
+        let zone = self.zone();
+        if record.utc_offset_min.is_none() {
+            record.utc_offset_min = zone.offset_min(record.started_at_ms);
+        }
+        if let Some(key) = self.signer.lock().as_ref() {
+            match sign_session(key, &SessionV1::from(&record)) {
+                Ok(signature) => record.signature = Some(signature),
+                Err(e) => log::error!("Failed to sign session {}: {}", record.id, e),
+            }
+        }
+        let entry = RollupEntry::from(&record);
+        if let Some(db) = self.db.lock().as_ref() {
+            let rr = (!rr_intervals_ms.is_empty()).then_some(rr_intervals_ms.as_slice());
+            if let Err(e) = db.write(&record, rr).and_then(|_| db.write_rollup(&record.id, &entry)) {
+                log::error!("Failed to persist session {}: {}", record.id, e);
+            }
+        }
+        if !rr_intervals_ms.is_empty() {
+            self.ibi.lock().insert(record.id.clone(), rr_intervals_ms);
+        }
+        self.rollups.lock().add(record.id.clone(), entry, zone);
+        self.inner.lock().push(record);
+    }
+
+    /// Recorded inter-beat intervals (ms) for a session
+    pub fn ibi(&self, id: &str) -> Result<Vec<f32>, ZenOneError> {
+        if self.get(id).is_none() {
+            return Err(ZenOneError::ConfigError(format!("Session not found: {}", id)));
+        }
+        self.ibi
+            .lock()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| ZenOneError::ConfigError(format!("No inter-beat intervals recorded for session {}", id)))
+    }
+
+    /// Add sessions brought in from another app, skipping any that overlap a
+    /// session already in the store. Returns (imported, skipped) counts.
+    fn import(&self, records: Vec<FfiSessionRecord>) -> (u32, u32) {
+        let zone = self.zone();
+        let mut inner = self.inner.lock();
+        let (mut imported, mut skipped) = (0, 0);
+        for record in records {
+            let overlaps = inner
+                .iter()
+                .any(|r| record.started_at_ms < r.ended_at_ms && r.started_at_ms < record.ended_at_ms);
+            if overlaps {
+                skipped += 1;
+            } else {
+                let entry = RollupEntry::from(&record);
+                if let Some(db) = self.db.lock().as_ref() {
+                    if let Err(e) = db.write(&record, None).and_then(|_| db.write_rollup(&record.id, &entry)) {
+                        log::error!("Failed to persist imported session {}: {}", record.id, e);
+                    }
+                }
+                self.rollups.lock().add(record.id.clone(), entry, zone);
+                inner.push(record);
+                imported += 1;
+            }
+        }
+        // Imported history is usually older than what's recorded here
+        inner.sort_by_key(|r| r.started_at_ms);
+        (imported, skipped)
+    }
+
+    /// All sessions, oldest first
+    pub fn all(&self) -> Vec<FfiSessionRecord> {
+        self.inner.lock().clone()
+    }
+
+    pub fn get(&self, id: &str) -> Option<FfiSessionRecord> {
+        self.inner.lock().iter().find(|r| r.id == id).cloned()
+    }
+
+    /// One page of sessions, newest first
+    pub fn history(&self, limit: u32, offset: u32) -> Vec<FfiSessionRecord> {
+        self.inner
+            .lock()
+            .iter()
+            .rev()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Replace a session's tags and note
+    pub fn annotate(&self, id: &str, tags: Vec<String>, notes: Option<String>) -> Result<(), ZenOneError> {
+        let notes = notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        if notes.as_ref().is_some_and(|n| n.chars().count() > SESSION_NOTE_MAX_CHARS) {
+            return Err(ZenOneError::ConfigError(format!(
+                "Session note exceeds {} characters", SESSION_NOTE_MAX_CHARS
+            )));
+        }
+        let tags = normalize_tags(tags)?;
+
+        let mut sessions = self.inner.lock();
+        let record = sessions
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| ZenOneError::ConfigError(format!("Session not found: {}", id)))?;
+        record.tags = tags;
+        record.notes = notes;
+        if let Some(db) = self.db.lock().as_ref() {
+            db.write(record, None)?;
+        }
+        Ok(())
+    }
+
+    /// Sessions matching every given filter, newest first.
+    /// `query` is a case-insensitive substring of the note, a tag or the pattern id;
+    /// `tags` must all be present; the range bounds the session start (unix ms, inclusive).
+    pub fn search(
+        &self,
+        query: Option<&str>,
+        tags: &[String],
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+    ) -> Vec<FfiSessionRecord> {
+        let query = query.map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+        let tags: Vec<String> = tags.iter().map(|t| t.trim().to_lowercase()).collect();
+        let range = start_ms.unwrap_or(i64::MIN)..=end_ms.unwrap_or(i64::MAX);
+
+        let mut hits: Vec<FfiSessionRecord> = self
+            .inner
+            .lock()
+            .iter()
+            .filter(|r| range.contains(&r.started_at_ms))
+            .filter(|r| tags.iter().all(|t| r.tags.contains(t)))
+            .filter(|r| match &query {
+                Some(q) => session_matches(r, q),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        hits.sort_by_key(|r| std::cmp::Reverse(r.started_at_ms));
+        hits
+    }
+}
+
+#[cfg(feature = "full")]
+const SESSION_TAG_MAX_CHARS: usize = 64;
+#[cfg(feature = "full")]
+const SESSION_TAGS_MAX: usize = 20;
+#[cfg(feature = "full")]
+const SESSION_NOTE_MAX_CHARS: usize = 2000;
+
+/// Trim, lowercase and dedupe tags, rejecting oversized input
+#[cfg(feature = "full")]
+fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, ZenOneError> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || out.contains(&tag) {
+            continue;
+        }
+        if tag.chars().count() > SESSION_TAG_MAX_CHARS {
+            return Err(ZenOneError::ConfigError(format!(
+                "Tag exceeds {} characters: {}", SESSION_TAG_MAX_CHARS, tag
+            )));
+        }
+        out.push(tag);
+    }
+    if out.len() > SESSION_TAGS_MAX {
+        return Err(ZenOneError::ConfigError(format!(
+            "At most {} tags per session", SESSION_TAGS_MAX
+        )));
+    }
+    Ok(out)
+}
+
+/// Free-text match against a lowercased query
+#[cfg(feature = "full")]
+fn session_matches(record: &FfiSessionRecord, query: &str) -> bool {
+    record.tags.iter().any(|t| t.contains(query))
+        || record.stats.pattern_id.to_lowercase().contains(query)
+        || record.notes.as_ref().is_some_and(|n| n.to_lowercase().contains(query))
+}
+
+// ============================================================================
+// SESSION QUERIES - HISTORY SCREEN AND ANALYTICS
+// ============================================================================
+
+#[cfg(feature = "full")]
+const SESSION_QUERY_MAX_LIMIT: u32 = 200;
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiSessionCompletion {
+    Completed,
+    /// Auto-finalized after a very long interruption
+    Partial,
+}
+
+/// Filters for `query_sessions`; unset filters match every session
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSessionQuery {
+    /// Bounds on the session start (unix ms, inclusive)
+    pub start_ms: Option<i64>,
+    pub end_ms: Option<i64>,
+    pub pattern_id: Option<String>,
+    pub min_duration_sec: Option<f32>,
+    pub completion: Option<FfiSessionCompletion>,
+    /// Page size, 1 to SESSION_QUERY_MAX_LIMIT
+    pub limit: u32,
+    pub offset: u32,
+}
+
+/// The fields of a session shown in a history list
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSessionSummary {
+    pub id: String,
+    pub started_at_ms: i64,
+    pub ended_at_ms: i64,
+    pub pattern_id: String,
+    pub duration_sec: f32,
+    pub cycles_completed: u64,
+    pub avg_heart_rate: Option<f32>,
+    pub avg_resonance: f32,
+    pub avg_adherence: Option<f32>,
+    pub completion: FfiSessionCompletion,
+    pub tags: Vec<String>,
+}
+
+#[cfg(feature = "full")]
+impl From<&FfiSessionRecord> for FfiSessionSummary {
+    fn from(record: &FfiSessionRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            started_at_ms: record.started_at_ms,
+            ended_at_ms: record.ended_at_ms,
+            pattern_id: record.stats.pattern_id.clone(),
+            duration_sec: record.stats.duration_sec,
+            cycles_completed: record.stats.cycles_completed,
+            avg_heart_rate: record.stats.avg_heart_rate,
+            avg_resonance: record.stats.avg_resonance,
+            avg_adherence: record.stats.avg_adherence,
+            completion: record.completion(),
+            tags: record.tags.clone(),
+        }
+    }
+}
+
+/// One page of matching sessions, newest first
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSessionPage {
+    pub sessions: Vec<FfiSessionSummary>,
+    /// Matching sessions across all pages
+    pub total: u32,
+}
+
+#[cfg(feature = "full")]
+impl FfiSessionRecord {
+    fn completion(&self) -> FfiSessionCompletion {
+        if self.stats.partial {
+            FfiSessionCompletion::Partial
+        } else {
+            FfiSessionCompletion::Completed
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiSessionQuery {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        if !(1..=SESSION_QUERY_MAX_LIMIT).contains(&self.limit) {
+            return Err(ZenOneError::ConfigError(format!(
+                "Page size must be within 1-{}", SESSION_QUERY_MAX_LIMIT
+            )));
+        }
+        if self.min_duration_sec.is_some_and(|d| !d.is_finite() || d < 0.0) {
+            return Err(ZenOneError::ConfigError("Minimum duration must be non-negative".into()));
+        }
+        Ok(())
+    }
+
+    fn matches(&self, record: &FfiSessionRecord) -> bool {
+        let range = self.start_ms.unwrap_or(i64::MIN)..=self.end_ms.unwrap_or(i64::MAX);
+        range.contains(&record.started_at_ms)
+            && self.pattern_id.iter().all(|id| record.stats.pattern_id == *id)
+            && self.min_duration_sec.iter().all(|min| record.stats.duration_sec >= *min)
+            && self.completion.iter().all(|c| record.completion() == *c)
+    }
+}
+
+#[cfg(feature = "full")]
+impl SessionStore {
+    pub fn query(&self, query: &FfiSessionQuery) -> Result<FfiSessionPage, ZenOneError> {
+        query.validate()?;
+        let inner = self.inner.lock();
+        let matching: Vec<&FfiSessionRecord> = inner.iter().rev().filter(|r| query.matches(r)).collect();
+        Ok(FfiSessionPage {
+            sessions: matching
+                .iter()
+                .skip(query.offset as usize)
+                .take(query.limit as usize)
+                .map(|r| FfiSessionSummary::from(*r))
+                .collect(),
+            total: matching.len() as u32,
+        })
+    }
+}
+
+// ============================================================================
+// SESSION ROLLUPS - LONG-TERM AGGREGATES AND RETENTION
+// ============================================================================
+
+/// Shortest raw-session retention; keeps a month of detail for streaks and comparisons
+#[cfg(feature = "full")]
+const RETENTION_MIN_DAYS: u32 = 30;
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiRollupPeriod {
+    Day,
+    /// ISO week, starting Monday
+    Week,
+    Month,
+}
+
+/// Session metrics summed over one local-time period
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiMetricRollup {
+    pub period: FfiRollupPeriod,
+    /// ISO date (YYYY-MM-DD) the period starts on
+    pub period_start: String,
+    pub sessions: u32,
+    pub total_duration_sec: f32,
+    pub total_cycles: u64,
+    pub avg_heart_rate: Option<f32>,
+    pub avg_resonance: f32,
+    pub avg_adherence: Option<f32>,
+}
+
+/// How long raw session records (and their IBI series) are kept; rollups are kept forever
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FfiRetentionPolicy {
+    /// None keeps every session
+    pub raw_session_days: Option<u32>,
+}
+
+#[cfg(feature = "full")]
+impl FfiRetentionPolicy {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        match self.raw_session_days {
+            Some(days) if days < RETENTION_MIN_DAYS => Err(ZenOneError::ConfigError(format!(
+                "Raw sessions must be kept at least {} days", RETENTION_MIN_DAYS
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What one session contributes to the rollups. Kept per session (and
+/// persisted) after the raw record is pruned, so the tables can be rebuilt.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RollupEntry {
+    started_at_ms: i64,
+    utc_offset_min: Option<i32>,
+    duration_sec: f32,
+    cycles: u64,
+    avg_heart_rate: Option<f32>,
+    avg_resonance: f32,
+    avg_adherence: Option<f32>,
+}
+
+#[cfg(feature = "full")]
+impl From<&FfiSessionRecord> for RollupEntry {
+    fn from(record: &FfiSessionRecord) -> Self {
+        Self {
+            started_at_ms: record.started_at_ms,
+            utc_offset_min: record.utc_offset_min,
+            duration_sec: record.stats.duration_sec,
+            cycles: record.stats.cycles_completed,
+            avg_heart_rate: record.stats.avg_heart_rate,
+            avg_resonance: record.stats.avg_resonance,
+            avg_adherence: record.stats.avg_adherence,
+        }
+    }
+}
+
+/// Running sums for one period; averages are weighted per session
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Default)]
+struct RollupAccumulator {
+    sessions: u32,
+    duration_sec: f32,
+    cycles: u64,
+    hr_sum: f32,
+    hr_count: u32,
+    resonance_sum: f32,
+    adherence_sum: f32,
+    adherence_count: u32,
+}
+
+#[cfg(feature = "full")]
+impl RollupAccumulator {
+    fn add(&mut self, entry: &RollupEntry) {
+        self.sessions += 1;
+        self.duration_sec += entry.duration_sec;
+        self.cycles += entry.cycles;
+        self.resonance_sum += entry.avg_resonance;
+        if let Some(hr) = entry.avg_heart_rate {
+            self.hr_sum += hr;
+            self.hr_count += 1;
+        }
+        if let Some(adherence) = entry.avg_adherence {
+            self.adherence_sum += adherence;
+            self.adherence_count += 1;
+        }
+    }
+
+    fn to_ffi(&self, period: FfiRollupPeriod, start: chrono::NaiveDate) -> FfiMetricRollup {
+        FfiMetricRollup {
+            period,
+            period_start: start.format("%Y-%m-%d").to_string(),
+            sessions: self.sessions,
+            total_duration_sec: self.duration_sec,
+            total_cycles: self.cycles,
+            avg_heart_rate: (self.hr_count > 0).then(|| self.hr_sum / self.hr_count as f32),
+            avg_resonance: self.resonance_sum / self.sessions.max(1) as f32,
+            avg_adherence: (self.adherence_count > 0).then(|| self.adherence_sum / self.adherence_count as f32),
+        }
+    }
+}
+
+/// Summary tables, maintained incrementally as sessions are stored
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct RollupTables {
+    /// Contributions by session id, so a session is counted once
+    entries: HashMap<String, RollupEntry>,
+    day: std::collections::BTreeMap<chrono::NaiveDate, RollupAccumulator>,
+    week: std::collections::BTreeMap<chrono::NaiveDate, RollupAccumulator>,
+    month: std::collections::BTreeMap<chrono::NaiveDate, RollupAccumulator>,
+}
+
+#[cfg(feature = "full")]
+impl RollupTables {
+    fn table(&self, period: FfiRollupPeriod) -> &std::collections::BTreeMap<chrono::NaiveDate, RollupAccumulator> {
+        match period {
+            FfiRollupPeriod::Day => &self.day,
+            FfiRollupPeriod::Week => &self.week,
+            FfiRollupPeriod::Month => &self.month,
+        }
+    }
+
+    fn from_entries(entries: HashMap<String, RollupEntry>, zone: LocalZone) -> Self {
+        let mut tables = Self::default();
+        for entry in entries.values() {
+            tables.accumulate(entry, zone);
+        }
+        tables.entries = entries;
+        tables
+    }
+
+    fn add(&mut self, id: String, entry: RollupEntry, zone: LocalZone) {
+        if !self.entries.contains_key(&id) {
+            self.accumulate(&entry, zone);
+            self.entries.insert(id, entry);
+        }
+    }
+
+    fn accumulate(&mut self, entry: &RollupEntry, zone: LocalZone) {
+        use chrono::Datelike;
+
+        let Some(day) = local_date(entry.started_at_ms, entry.utc_offset_min, zone) else {
+            return;
+        };
+        let week = day - chrono::Duration::days(day.weekday().num_days_from_monday() as i64);
+        let month = day.with_day(1).unwrap_or(day);
+        self.day.entry(day).or_default().add(entry);
+        self.week.entry(week).or_default().add(entry);
+        self.month.entry(month).or_default().add(entry);
+    }
+
+    /// Rollups whose period starts within `[start, end]`, oldest first
+    fn query(
+        &self,
+        period: FfiRollupPeriod,
+        start: Option<chrono::NaiveDate>,
+        end: Option<chrono::NaiveDate>,
+    ) -> Vec<FfiMetricRollup> {
+        let range = start.unwrap_or(chrono::NaiveDate::MIN)..=end.unwrap_or(chrono::NaiveDate::MAX);
+        self.table(period)
+            .range(range)
+            .map(|(start, acc)| acc.to_ffi(period, *start))
+            .collect()
+    }
+}
+
+#[cfg(feature = "full")]
+impl SessionStore {
+    pub fn rollups(
+        &self,
+        period: FfiRollupPeriod,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> Result<Vec<FfiMetricRollup>, ZenOneError> {
+        let parse = |date: Option<&str>| {
+            date.map(|d| {
+                chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                    .map_err(|_| ZenOneError::ConfigError(format!("Invalid date (expected YYYY-MM-DD): {}", d)))
+            })
+            .transpose()
+        };
+        Ok(self.rollups.lock().query(period, parse(start_date)?, parse(end_date)?))
+    }
+
+    pub fn retention(&self) -> FfiRetentionPolicy {
+        self.retention.lock().clone()
+    }
+
+    /// Set the retention policy and prune right away; returns the sessions removed
+    pub fn set_retention(&self, policy: FfiRetentionPolicy, now_ms: i64) -> Result<u32, ZenOneError> {
+        policy.validate()?;
+        *self.retention.lock() = policy;
+        Ok(self.run_maintenance(now_ms))
+    }
+
+    /// Retention job: drop raw sessions (and their IBI series) older than the
+    /// policy allows. Their rollups were recorded on insert and are kept.
+    /// Returns the number of sessions removed.
+    pub fn run_maintenance(&self, now_ms: i64) -> u32 {
+        let Some(days) = self.retention.lock().raw_session_days else {
+            return 0;
+        };
+        let cutoff_ms = now_ms - days as i64 * 86_400_000;
+        let mut inner = self.inner.lock();
+        let mut ibi = self.ibi.lock();
+        let before = inner.len();
+        inner.retain(|r| {
+            let keep = r.started_at_ms >= cutoff_ms;
+            if !keep {
+                ibi.remove(&r.id);
+            }
+            keep
+        });
+        let pruned = (before - inner.len()) as u32;
+        if let Some(db) = self.db.lock().as_ref().filter(|_| pruned > 0) {
+            if let Err(e) = db.prune(cutoff_ms) {
+                log::error!("Failed to prune session database: {}", e);
+            }
+        }
+        if pruned > 0 {
+            log::info!("Retention: pruned {} sessions older than {} days", pruned, days);
+        }
+        pruned
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod rollup_tests {
+    use super::*;
+
+    /// Monday 2024-01-01 08:00 UTC
+    const MON_JAN_1: i64 = 1_704_096_000_000;
+    const DAY_MS: i64 = 86_400_000;
+
+    fn record(id: &str, started_at_ms: i64, utc_offset_min: i32, duration_sec: f32, hr: Option<f32>) -> FfiSessionRecord {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "started_at_ms": started_at_ms,
+            "ended_at_ms": started_at_ms + (duration_sec * 1000.0) as i64,
+            "utc_offset_min": utc_offset_min,
+            "stats": {
+                "duration_sec": duration_sec,
+                "cycles_completed": 10,
+                "pattern_id": "4-7-8",
+                "avg_heart_rate": hr,
+                "final_belief": FfiBeliefState::default(),
+                "avg_resonance": 0.5,
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn sessions_roll_up_by_day_week_and_month() {
+        let store = SessionStore::new();
+        store.insert(record("a", MON_JAN_1, 0, 300.0, Some(60.0)), vec![]);
+        store.insert(record("b", MON_JAN_1 + 3_600_000, 0, 600.0, None), vec![]);
+        store.insert(record("c", MON_JAN_1 + 2 * DAY_MS, 0, 120.0, Some(70.0)), vec![]);
+        store.insert(record("d", MON_JAN_1 + 7 * DAY_MS, 0, 60.0, Some(80.0)), vec![]);
+
+        let days = store.rollups(FfiRollupPeriod::Day, None, None).unwrap();
+        let starts: Vec<&str> = days.iter().map(|r| r.period_start.as_str()).collect();
+        assert_eq!(starts, ["2024-01-01", "2024-01-03", "2024-01-08"]);
+        assert_eq!(days[0].sessions, 2);
+        assert_eq!(days[0].total_duration_sec, 900.0);
+        assert_eq!(days[0].total_cycles, 20);
+        // Sessions without heart rate don't dilute the average
+        assert_eq!(days[0].avg_heart_rate, Some(60.0));
+
+        let weeks = store.rollups(FfiRollupPeriod::Week, None, None).unwrap();
+        assert_eq!(weeks.len(), 2);
+        assert_eq!((weeks[0].period_start.as_str(), weeks[0].sessions), ("2024-01-01", 3));
+        assert_eq!(weeks[0].avg_heart_rate, Some(65.0));
+        assert_eq!(weeks[1].period_start, "2024-01-08");
+
+        let months = store.rollups(FfiRollupPeriod::Month, None, None).unwrap();
+        assert_eq!(months.len(), 1);
+        assert_eq!((months[0].period_start.as_str(), months[0].sessions), ("2024-01-01", 4));
+    }
+
+    #[test]
+    fn rollups_use_the_recorded_offset_and_date_range() {
+        let store = SessionStore::new();
+        // 23:30 UTC is already the next day at UTC+2
+        store.insert(record("late", MON_JAN_1 + 15 * 3_600_000 + 1_800_000, 120, 60.0, None), vec![]);
+        store.insert(record("next", MON_JAN_1 + 2 * DAY_MS, 0, 60.0, None), vec![]);
+
+        let days = store.rollups(FfiRollupPeriod::Day, None, None).unwrap();
+        assert_eq!(days[0].period_start, "2024-01-02");
+        let ranged = store.rollups(FfiRollupPeriod::Day, Some("2024-01-03"), Some("2024-01-31")).unwrap();
+        assert_eq!(ranged.len(), 1);
+        assert_eq!(ranged[0].period_start, "2024-01-03");
+        assert!(store.rollups(FfiRollupPeriod::Day, Some("01/03/2024"), None).is_err());
+    }
+
+    #[test]
+    fn a_session_is_counted_once() {
+        let mut tables = RollupTables::default();
+        let entry = RollupEntry::from(&record("a", MON_JAN_1, 0, 300.0, None));
+        tables.add("a".into(), entry.clone(), LocalZone::Device);
+        tables.add("a".into(), entry, LocalZone::Device);
+        let days = tables.query(FfiRollupPeriod::Day, None, None);
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].sessions, 1);
+
+        let rebuilt = RollupTables::from_entries(tables.entries.clone(), LocalZone::Device);
+        assert_eq!(rebuilt.query(FfiRollupPeriod::Day, None, None), days);
+    }
+
+    #[test]
+    fn retention_prunes_raw_sessions_and_keeps_rollups() {
+        let store = SessionStore::new();
+        store.insert(record("old", MON_JAN_1, 0, 300.0, Some(60.0)), vec![1000.0, 1010.0]);
+        store.insert(record("new", MON_JAN_1 + 40 * DAY_MS, 0, 300.0, Some(60.0)), vec![]);
+        let now_ms = MON_JAN_1 + 45 * DAY_MS;
+
+        assert!(store.set_retention(FfiRetentionPolicy { raw_session_days: Some(7) }, now_ms).is_err());
+        assert_eq!(store.retention(), FfiRetentionPolicy::default());
+
+        let pruned = store.set_retention(FfiRetentionPolicy { raw_session_days: Some(30) }, now_ms).unwrap();
+        assert_eq!(pruned, 1);
+        assert!(store.get("old").is_none());
+        assert!(store.ibi("old").is_err());
+        assert!(store.get("new").is_some());
+        let months = store.rollups(FfiRollupPeriod::Month, None, None).unwrap();
+        assert_eq!(months.iter().map(|r| r.sessions).sum::<u32>(), 2);
+        assert_eq!(store.run_maintenance(now_ms), 0);
+    }
+}
+
+// ============================================================================
+// FHIR EXPORT - HL7 FHIR R4 RESOURCES
+// ============================================================================
+
+#[cfg(feature = "full")]
+const LOINC_SYSTEM: &str = "http://loinc.org";
+#[cfg(feature = "full")]
+const UCUM_SYSTEM: &str = "http://unitsofmeasure.org";
+#[cfg(feature = "full")]
+const OBSERVATION_CATEGORY_SYSTEM: &str = "http://terminology.hl7.org/CodeSystem/observation-category";
+
+/// Converts session records into HL7 FHIR R4 resources
+///
+/// Each session becomes a `Procedure` (the breathing exercise itself) plus
+/// vital-sign `Observation`s that reference it via `partOf`. Resource ids are
+/// UUIDs derived from the session id, and references use the bundle's
+/// `urn:uuid:` fullUrls so they resolve inside the bundle.
+#[cfg(feature = "full")]
+pub struct FhirExporter;
+
+#[cfg(feature = "full")]
+impl Default for FhirExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl FhirExporter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Export sessions as a FHIR `collection` Bundle
+    pub fn export_bundle(
+        &self,
+        records: &[FfiSessionRecord],
+        patient_reference: String,
+    ) -> Result<String, ZenOneError> {
+        let entries: Vec<serde_json::Value> = records
+            .iter()
+            .flat_map(|r| self.session_resources(r, &patient_reference))
+            .map(|resource| {
+                let full_url = format!(
+                    "urn:uuid:{}",
+                    resource["id"].as_str().unwrap_or_default()
+                );
+                serde_json::json!({ "fullUrl": full_url, "resource": resource })
+            })
+            .collect();
+
+        let bundle = serde_json::json!({
+            "resourceType": "Bundle",
+            "type": "collection",
+            "timestamp": fhir_instant(Utc::now().timestamp_millis()),
+            "entry": entries,
+        });
+
+        serde_json::to_string_pretty(&bundle)
+            .map_err(|e| ZenOneError::ConfigError(format!("FHIR serialization failed: {}", e)))
+    }
+
+    /// Procedure + Observations for a single session
+    fn session_resources(
+        &self,
+        record: &FfiSessionRecord,
+        patient_reference: &str,
+    ) -> Vec<serde_json::Value> {
+        let procedure_id = fhir_uuid(&format!("zenone-session-{}", record.id));
+        let procedure_url = format!("urn:uuid:{}", procedure_id);
+        let period = serde_json::json!({
+            "start": fhir_instant(record.started_at_ms),
+            "end": fhir_instant(record.ended_at_ms),
+        });
+
+        let mut resources = vec![serde_json::json!({
+            "resourceType": "Procedure",
+            "id": procedure_id,
+            "status": "completed",
+            "code": { "text": format!("Paced breathing session ({})", record.stats.pattern_id) },
+            "subject": { "reference": patient_reference },
+            "performedPeriod": period,
+            "note": [{
+                "text": format!(
+                    "{} cycles over {:.0}s, average resonance {:.2}",
+                    record.stats.cycles_completed,
+                    record.stats.duration_sec,
+                    record.stats.avg_resonance
+                )
+            }],
+        })];
+
+        if let Some(hr) = record.stats.avg_heart_rate {
+            resources.push(vital_sign_observation(
+                fhir_uuid(&format!("zenone-session-{}-hr", record.id)),
+                &procedure_url,
+                patient_reference,
+                &period,
+                ("8867-4", "Heart rate"),
+                hr,
+                ("beats/minute", "/min"),
+            ));
+        }
+
+        if let Some(hrv) = &record.stats.hrv {
+            resources.push(vital_sign_observation(
+                fhir_uuid(&format!("zenone-session-{}-hrv", record.id)),
+                &procedure_url,
+                patient_reference,
+                &period,
+                ("80404-7", "R-R interval.standard deviation (Heart rate variability)"),
+                hrv.sdnn_ms,
+                ("millisecond", "ms"),
+            ));
+        }
+
+        resources
+    }
+}
+
+/// Build a vital-signs Observation that is `partOf` a session Procedure
+#[cfg(feature = "full")]
+fn vital_sign_observation(
+    id: String,
+    procedure_url: &str,
+    patient_reference: &str,
+    period: &serde_json::Value,
+    loinc: (&str, &str),
+    value: f32,
+    unit: (&str, &str),
+) -> serde_json::Value {
+    serde_json::json!({
+        "resourceType": "Observation",
+        "id": id,
+        "status": "final",
+        "category": [{
+            "coding": [{
+                "system": OBSERVATION_CATEGORY_SYSTEM,
+                "code": "vital-signs",
+                "display": "Vital Signs",
+            }]
+        }],
+        "code": {
+            "coding": [{ "system": LOINC_SYSTEM, "code": loinc.0, "display": loinc.1 }],
+            "text": loinc.1,
+        },
+        "subject": { "reference": patient_reference },
+        "partOf": [{ "reference": procedure_url }],
+        "effectivePeriod": period,
+        "valueQuantity": {
+            "value": (value * 10.0).round() / 10.0,
+            "unit": unit.0,
+            "system": UCUM_SYSTEM,
+            "code": unit.1,
+        },
+    })
+}
+
+/// Name-based UUID (RFC 9562 version 8, SHA-256), so exporting a session
+/// again gives its resources the same ids
+#[cfg(feature = "full")]
+fn fhir_uuid(name: &str) -> String {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&Sha256::digest(name.as_bytes())[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex_encode(&bytes);
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+/// Format unix milliseconds as a FHIR `instant` (RFC 3339, UTC)
+#[cfg(feature = "full")]
+fn fhir_instant(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+// ============================================================================
+// IBI EXPORT - RAW INTER-BEAT INTERVALS FOR EXTERNAL HRV ANALYSIS
+// ============================================================================
+
+/// Text layout for exported inter-beat intervals
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiIbiFormat {
+    /// One interval per line, integer milliseconds
+    PlainText,
+    /// Tab-separated beat time and RR interval, both in seconds (Kubios HRV custom ASCII import)
+    Kubios,
+}
+
+/// Render an RR series (ms) in the requested format
+#[cfg(feature = "full")]
+fn format_ibi(rr_intervals_ms: &[f32], format: FfiIbiFormat) -> String {
+    let mut out = String::with_capacity(rr_intervals_ms.len() * 12);
+    match format {
+        FfiIbiFormat::PlainText => {
+            for rr in rr_intervals_ms {
+                out.push_str(&format!("{}\n", rr.round() as u32));
+            }
+        }
+        FfiIbiFormat::Kubios => {
+            let mut t_sec = 0.0f64;
+            for rr in rr_intervals_ms {
+                let rr_sec = *rr as f64 / 1000.0;
+                t_sec += rr_sec;
+                out.push_str(&format!("{:.3}\t{:.3}\n", t_sec, rr_sec));
+            }
+        }
+    }
+    out
+}
+
+// ============================================================================
+// RESEARCH MODE - CONSENT-GATED RAW SIGNAL LOGGING
+// ============================================================================
+
+#[cfg(feature = "full")]
+const RESEARCH_CONSENT_FILE: &str = "research_consent.json";
+#[cfg(feature = "full")]
+const RESEARCH_CHUNK_EXT: &str = "zrr";
+/// Samples buffered before an encrypted chunk is written (~30s at 30fps)
+#[cfg(feature = "full")]
+const RESEARCH_FLUSH_SAMPLES: usize = 900;
+
+/// Stored consent record (plaintext, lives next to the encrypted chunks).
+/// Only a hash of the token is kept, so reading the file doesn't enable recording.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ResearchConsent {
+    /// SHA-256 (hex) of the token handed to the user
+    #[serde(default)]
+    token_sha256: String,
+    /// Plain token written by older versions; replaced by its hash on the next enable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+    granted_at_ms: i64,
+    expires_at_ms: i64,
+}
+
+#[cfg(feature = "full")]
+impl ResearchConsent {
+    fn matches(&self, token: &str) -> bool {
+        match &self.token {
+            Some(legacy) => legacy == token,
+            None => self.token_sha256 == hex_encode(&Sha256::digest(token.as_bytes())),
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RawSignalSample {
+    timestamp_us: i64,
+    r: f32,
+    g: f32,
+    b: f32,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PipelineOutputSample {
+    timestamp_us: i64,
+    heart_rate: f32,
+    confidence: f32,
+}
+
+/// One encrypted file worth of research data
+#[cfg(feature = "full")]
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ResearchChunk {
+    raw: Vec<RawSignalSample>,
+    outputs: Vec<PipelineOutputSample>,
+}
+
+/// Wall-clock span of a chunk's samples (unix ms). Sample timestamps come
+/// from a monotonic frontend clock, so they are anchored to wall time at the
+/// chunk's first sample.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy)]
+struct ChunkSpan {
+    first_us: i64,
+    last_us: i64,
+    first_wall_ms: i64,
+}
+
+#[cfg(feature = "full")]
+impl ChunkSpan {
+    fn start_ms(&self) -> i64 {
+        self.first_wall_ms
+    }
+
+    fn end_ms(&self) -> i64 {
+        self.first_wall_ms + (self.last_us - self.first_us).max(0) / 1000
+    }
+}
+
+/// Work for the research writer thread, handled in order
+#[cfg(feature = "full")]
+enum ResearchWrite {
+    Chunk(ResearchChunk, ChunkSpan),
+    /// Acknowledged once everything queued before it is on disk
+    Flush(Sender<()>),
+    /// Drop queued chunks, delete the directory's research data and stop
+    Purge(Sender<Result<u32, ZenOneError>>),
+}
+
+/// Records raw RGB traces and rPPG outputs to encrypted files.
+///
+/// Nothing is recorded unless a consent token written by `grant_consent` is
+/// presented and the `RawSignal` scope is granted in the consent registry.
+/// Every entry point checks consent first: expired consent stops recording
+/// and deletes all data; a revoked scope stops recording.
+///
+/// The vault key is derived once when recording is enabled. Chunks are
+/// encrypted and written on a writer thread, off the signal actor.
+#[cfg(feature = "full")]
+pub struct ResearchRecorder {
+    inner: Mutex<ResearchRecorderInner>,
+    consent: Arc<ConsentRegistry>,
+}
+
+#[cfg(feature = "full")]
+struct ResearchRecorderInner {
+    active: Option<ActiveResearch>,
+    /// Directory of the most recent consent/recording, used by package export
+    last_output_dir: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+struct ActiveResearch {
+    output_dir: std::path::PathBuf,
+    expires_at_ms: i64,
+    chunk: ResearchChunk,
+    span: Option<ChunkSpan>,
+    writer: Sender<ResearchWrite>,
+}
+
+#[cfg(feature = "full")]
+impl ActiveResearch {
+    /// Extend the buffered chunk's span to a sample at `timestamp_us`
+    fn mark(&mut self, timestamp_us: i64, now_ms: i64) {
+        match &mut self.span {
+            Some(span) => span.last_us = span.last_us.max(timestamp_us),
+            None => {
+                self.span = Some(ChunkSpan {
+                    first_us: timestamp_us,
+                    last_us: timestamp_us,
+                    first_wall_ms: now_ms,
+                })
+            }
+        }
+    }
+
+    /// Hand the buffered chunk to the writer
+    fn write_chunk(&mut self) {
+        let Some(span) = self.span.take() else { return };
+        let _ = self.writer.send(ResearchWrite::Chunk(std::mem::take(&mut self.chunk), span));
+    }
+
+    /// Wait until every chunk handed over so far is written
+    fn flush(&mut self) {
+        self.write_chunk();
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        if self.writer.send(ResearchWrite::Flush(reply_tx)).is_ok() {
+            let _ = reply_rx.recv();
+        }
+    }
+
+    /// Stop the writer and delete everything in the output directory
+    fn purge(self) -> Result<u32, ZenOneError> {
+        let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+        if self.writer.send(ResearchWrite::Purge(reply_tx)).is_ok() {
+            if let Ok(result) = reply_rx.recv() {
+                return result;
+            }
+        }
+        delete_research_data(&self.output_dir)
+    }
+}
+
+#[cfg(feature = "full")]
+impl ResearchRecorder {
+    pub fn new(consent: Arc<ConsentRegistry>) -> Self {
+        Self {
+            inner: Mutex::new(ResearchRecorderInner { active: None, last_output_dir: None }),
+            consent,
+        }
+    }
+
+    fn grant_consent(&self, output_dir: String, valid_days: u32) -> Result<String, ZenOneError> {
+        if valid_days == 0 {
+            return Err(ZenOneError::ConfigError("Consent validity must be at least one day".into()));
+        }
+        let dir = std::path::PathBuf::from(output_dir);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot create research dir: {}", e)))?;
+
+        let token = hex_encode(&rand::random::<[u8; 16]>());
+        let now_ms = self.consent.now_ms();
+        write_research_consent(&dir, &ResearchConsent {
+            token_sha256: hex_encode(&Sha256::digest(token.as_bytes())),
+            token: None,
+            granted_at_ms: now_ms,
+            expires_at_ms: now_ms + valid_days as i64 * 86_400_000,
+        })?;
+        self.consent.grant(FfiConsentScope::RawSignal, Some(valid_days))?;
+        self.inner.lock().last_output_dir = Some(dir);
+
+        log::info!("ResearchRecorder: Consent granted for {} days", valid_days);
+        Ok(token)
+    }
+
+    fn enable(&self, output_dir: String, consent_token: String, mut passphrase: String) -> Result<(), ZenOneError> {
+        if passphrase.is_empty() {
+            return Err(ZenOneError::ConfigError("Research data requires a passphrase".into()));
+        }
+        let dir = std::path::PathBuf::from(output_dir);
+        let mut consent = read_research_consent(&dir)?
+            .ok_or_else(|| ZenOneError::ConfigError("No research consent on record".into()))?;
+        if !consent.matches(&consent_token) {
+            return Err(ZenOneError::ConfigError("Consent token does not match".into()));
+        }
+        if self.consent.now_ms() >= consent.expires_at_ms {
+            return Err(ZenOneError::ConfigError("Research consent has expired".into()));
+        }
+        self.consent.require(FfiConsentScope::RawSignal)?;
+        if let Some(token) = consent.token.take() {
+            consent.token_sha256 = hex_encode(&Sha256::digest(token.as_bytes()));
+            write_research_consent(&dir, &consent)?;
+        }
+
+        let key = VaultKey::derive(&passphrase);
+        passphrase.zeroize();
+        let writer = spawn_research_writer(dir.clone(), key?);
+
+        let mut inner = self.inner.lock();
+        if let Some(mut previous) = inner.active.take() {
+            previous.write_chunk();
+        }
+        inner.last_output_dir = Some(dir.clone());
+        inner.active = Some(ActiveResearch {
+            output_dir: dir,
+            expires_at_ms: consent.expires_at_ms,
+            chunk: ResearchChunk::default(),
+            span: None,
+            writer,
+        });
+        log::info!("ResearchRecorder: Research mode enabled");
+        Ok(())
+    }
+
+    fn disable(&self) {
+        let mut inner = self.inner.lock();
+        if !self.consent_valid(&mut inner) {
+            return;
+        }
+        if let Some(mut active) = inner.active.take() {
+            active.write_chunk();
+            log::info!("ResearchRecorder: Research mode disabled");
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        let mut inner = self.inner.lock();
+        self.consent_valid(&mut inner)
+    }
+
+    fn revoke_and_delete(&self, output_dir: String) -> Result<u32, ZenOneError> {
+        // Drop buffered data without writing it
+        if let Some(mut active) = self.inner.lock().active.take() {
+            active.chunk = ResearchChunk::default();
+            active.span = None;
+            active.purge()?;
+        }
+        self.consent.revoke(FfiConsentScope::RawSignal)?;
+        let deleted = delete_research_data(std::path::Path::new(&output_dir))?;
+        log::warn!("ResearchRecorder: Consent revoked, {} files deleted", deleted);
+        Ok(deleted)
+    }
+
+    fn record_sample(&self, timestamp_us: i64, r: f32, g: f32, b: f32) {
+        let mut inner = self.inner.lock();
+        if !self.consent_valid(&mut inner) {
+            return;
+        }
+        if let Some(active) = inner.active.as_mut() {
+            active.mark(timestamp_us, self.consent.now_ms());
+            active.chunk.raw.push(RawSignalSample { timestamp_us, r, g, b });
+            if active.chunk.raw.len() >= RESEARCH_FLUSH_SAMPLES {
+                active.write_chunk();
+            }
+        }
+    }
+
+    fn record_output(&self, timestamp_us: i64, heart_rate: f32, confidence: f32) {
+        let mut inner = self.inner.lock();
+        if !self.consent_valid(&mut inner) {
+            return;
+        }
+        if let Some(active) = inner.active.as_mut() {
+            active.mark(timestamp_us, self.consent.now_ms());
+            active.chunk.outputs.push(PipelineOutputSample { timestamp_us, heart_rate, confidence });
+        }
+    }
+
+    /// Hand the buffered chunk to the writer without waiting for it
+    fn flush(&self) {
+        let mut inner = self.inner.lock();
+        if !self.consent_valid(&mut inner) {
+            return;
+        }
+        if let Some(active) = inner.active.as_mut() {
+            active.write_chunk();
+        }
+    }
+
+    /// Encrypted chunk files with samples taken within the range.
+    /// Only listed while the stored consent is live; expired data is purged.
+    fn chunk_files_in_range(&self, start_ms: i64, end_ms: i64) -> Vec<std::path::PathBuf> {
+        let mut inner = self.inner.lock();
+        if self.consent_valid(&mut inner) {
+            if let Some(active) = inner.active.as_mut() {
+                active.flush();
+            }
+        }
+        let Some(dir) = inner.last_output_dir.clone() else { return Vec::new() };
+        drop(inner);
+        if !self.stored_consent_valid(&dir) {
+            return Vec::new();
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else { return Vec::new() };
+        let mut files: Vec<std::path::PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == RESEARCH_CHUNK_EXT))
+            .filter(|p| {
+                p.file_stem()
+                    .and_then(|s| s.to_str())
+                    .and_then(chunk_sample_span)
+                    .is_some_and(|(first, last)| first <= end_ms && last >= start_ms)
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Enforce automatic expiry: expired consent ends recording and purges data
+    fn consent_valid(&self, inner: &mut ResearchRecorderInner) -> bool {
+        if inner.active.is_some() && !self.consent.is_granted(FfiConsentScope::RawSignal) {
+            if let Some(mut active) = inner.active.take() {
+                active.write_chunk();
+                log::warn!("ResearchRecorder: RawSignal consent withdrawn, recording stopped");
+            }
+            return false;
+        }
+        let expired = match &inner.active {
+            None => return false,
+            Some(active) => self.consent.now_ms() >= active.expires_at_ms,
+        };
+        if expired {
+            if let Some(active) = inner.active.take() {
+                log::warn!("ResearchRecorder: Consent expired, deleting research data");
+                if let Err(e) = active.purge() {
+                    log::error!("ResearchRecorder: Failed to delete expired data: {}", e);
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    /// Consent on record in `dir` for data at rest: present, unexpired and
+    /// still granted. An expired record purges the directory.
+    fn stored_consent_valid(&self, dir: &std::path::Path) -> bool {
+        let Ok(Some(consent)) = read_research_consent(dir) else { return false };
+        if self.consent.now_ms() >= consent.expires_at_ms {
+            log::warn!("ResearchRecorder: Consent expired, deleting research data");
+            if let Err(e) = delete_research_data(dir) {
+                log::error!("ResearchRecorder: Failed to delete expired data: {}", e);
+            }
+            return false;
+        }
+        self.consent.is_granted(FfiConsentScope::RawSignal)
+    }
+}
+
+#[cfg(feature = "full")]
+fn read_research_consent(dir: &std::path::Path) -> Result<Option<ResearchConsent>, ZenOneError> {
+    let path = dir.join(RESEARCH_CONSENT_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(&path)
+        .map_err(|e| ZenOneError::ConfigError(format!("Cannot read consent: {}", e)))?;
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| ZenOneError::ConfigError(format!("Corrupt consent record: {}", e)))
+}
+
+#[cfg(feature = "full")]
+fn write_research_consent(dir: &std::path::Path, consent: &ResearchConsent) -> Result<(), ZenOneError> {
+    let json = serde_json::to_vec_pretty(consent)
+        .map_err(|e| ZenOneError::ConfigError(format!("Consent serialization failed: {}", e)))?;
+    std::fs::write(dir.join(RESEARCH_CONSENT_FILE), json)
+        .map_err(|e| ZenOneError::ConfigError(format!("Cannot store consent: {}", e)))
+}
+
+/// Start the thread that encrypts and writes chunks for one recording.
+/// It exits (zeroizing the key) once the recording drops its sender.
+#[cfg(feature = "full")]
+fn spawn_research_writer(output_dir: std::path::PathBuf, key: VaultKey) -> Sender<ResearchWrite> {
+    let (tx, rx) = unbounded::<ResearchWrite>();
+    thread::spawn(move || {
+        let mut seq = 0;
+        for write in rx {
+            match write {
+                ResearchWrite::Chunk(chunk, span) => {
+                    write_research_chunk(&output_dir, &key, &mut seq, &chunk, span)
+                }
+                ResearchWrite::Flush(reply_tx) => {
+                    let _ = reply_tx.send(());
+                }
+                ResearchWrite::Purge(reply_tx) => {
+                    let _ = reply_tx.send(delete_research_data(&output_dir));
+                    return;
+                }
+            }
+        }
+    });
+    tx
+}
+
+/// Encrypt one chunk under the recording's vault key and write it to disk as
+/// `raw-<first sample ms>-<last sample ms>-<seq>.zrr`
+#[cfg(feature = "full")]
+fn write_research_chunk(
+    dir: &std::path::Path,
+    key: &VaultKey,
+    seq: &mut u32,
+    chunk: &ResearchChunk,
+    span: ChunkSpan,
+) {
+    let plaintext = match serde_json::to_vec(chunk) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::error!("ResearchRecorder: Serialization failed: {}", e);
+            return;
+        }
+    };
+    match key.encrypt(&plaintext) {
+        Ok(blob) => {
+            let name = format!(
+                "raw-{}-{}-{:05}.{}",
+                span.start_ms(),
+                span.end_ms(),
+                seq,
+                RESEARCH_CHUNK_EXT
+            );
+            *seq += 1;
+            if let Err(e) = std::fs::write(dir.join(name), blob) {
+                log::error!("ResearchRecorder: Write failed: {}", e);
+            }
+        }
+        Err(e) => log::error!("ResearchRecorder: Encryption failed: {}", e),
+    }
+}
+
+/// Sample span (unix ms) from a chunk file stem. Chunks written before spans
+/// were recorded (`raw-<write ms>-<seq>`) count as a single instant.
+#[cfg(feature = "full")]
+fn chunk_sample_span(stem: &str) -> Option<(i64, i64)> {
+    let parts: Vec<&str> = stem.split('-').collect();
+    match parts.as_slice() {
+        ["raw", first, last, _seq] => Some((first.parse().ok()?, last.parse().ok()?)),
+        ["raw", at, _seq] => at.parse().ok().map(|at| (at, at)),
+        _ => None,
+    }
+}
+
+/// Delete consent record and all encrypted chunks in a research directory
+#[cfg(feature = "full")]
+fn delete_research_data(dir: &std::path::Path) -> Result<u32, ZenOneError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(ZenOneError::ConfigError(format!("Cannot read research dir: {}", e))),
+    };
+    let mut deleted = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_chunk = path.extension().is_some_and(|ext| ext == RESEARCH_CHUNK_EXT);
+        let is_consent = path.file_name().is_some_and(|n| n == RESEARCH_CONSENT_FILE);
+        if is_chunk || is_consent {
+            std::fs::remove_file(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot delete {}: {}", path.display(), e)))?;
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+#[cfg(all(test, feature = "full"))]
+mod research_tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("zenone-{}-{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    fn chunks(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == RESEARCH_CHUNK_EXT))
+            .collect()
+    }
+
+    #[test]
+    fn recording_needs_the_matching_token_and_a_passphrase() {
+        let dir = temp_dir("research-token");
+        let out = dir.to_string_lossy().to_string();
+        let recorder = ResearchRecorder::new(Arc::new(ConsentRegistry::new()));
+        assert!(recorder.enable(out.clone(), "none".into(), "secret".into()).is_err());
+        assert!(recorder.grant_consent(out.clone(), 0).is_err());
+        let token = recorder.grant_consent(out.clone(), 30).unwrap();
+        assert!(recorder.enable(out.clone(), "wrong".into(), "secret".into()).is_err());
+        assert!(recorder.enable(out.clone(), token.clone(), String::new()).is_err());
+        assert!(!recorder.is_active());
+        recorder.enable(out, token, "secret".into()).unwrap();
+        assert!(recorder.is_active());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn chunks_are_encrypted_under_the_passphrase_and_deleted_on_revoke() {
+        let dir = temp_dir("research-chunks");
+        let out = dir.to_string_lossy().to_string();
+        let recorder = ResearchRecorder::new(Arc::new(ConsentRegistry::new()));
+        let token = recorder.grant_consent(out.clone(), 30).unwrap();
+        recorder.enable(out.clone(), token, "secret".into()).unwrap();
+        recorder.record_sample(1_000, 0.5, 0.6, 0.7);
+        recorder.record_output(1_000, 64.0, 0.9);
+        // Waits for the writer thread
+        let files = recorder.chunk_files_in_range(0, i64::MAX);
+        assert_eq!(files.len(), 1);
+        let blob = std::fs::read(&files[0]).unwrap();
+        assert!(SecureVault::new().decrypt_blob("wrong".into(), blob.clone()).is_err());
+        let plaintext = SecureVault::new().decrypt_blob("secret".into(), blob).unwrap();
+        let chunk: ResearchChunk = serde_json::from_slice(&plaintext).unwrap();
+        assert_eq!(chunk.raw.len(), 1);
+        assert_eq!(chunk.outputs[0].heart_rate, 64.0);
+
+        // Consent record plus the chunk
+        recorder.disable();
+        assert_eq!(recorder.revoke_and_delete(out).unwrap(), 2);
+        assert!(chunks(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
+
+// ============================================================================
+// CONSENT REGISTRY - SCOPED DATA CONSENT
+// ============================================================================
+
+#[cfg(feature = "full")]
+const CONSENT_FILE: &str = "consent.json";
+
+/// Data scopes that require explicit user consent
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FfiConsentScope {
+    /// Raw camera/PPG traces (research mode)
+    RawSignal,
+    /// Aggregated statistics over session history (rollups, comparisons, experiment reports)
+    Analytics,
+    /// Handing exported health data to the app to send off the device
+    CloudSync,
+    /// Exporting health data (FHIR, research packages)
+    DataExport,
+    /// Alerting a trusted contact about safety events
+    EmergencyContact,
+}
+
+#[cfg(feature = "full")]
+impl FfiConsentScope {
+    pub const ALL: [FfiConsentScope; 5] = [
+        FfiConsentScope::RawSignal,
+        FfiConsentScope::Analytics,
+        FfiConsentScope::CloudSync,
+        FfiConsentScope::DataExport,
+        FfiConsentScope::EmergencyContact,
+    ];
+}
+
+/// Consent audit action
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiConsentAction {
+    Granted,
+    Revoked,
+    Expired,
+}
+
+/// Current consent state for one scope
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiConsentRecord {
+    pub scope: FfiConsentScope,
+    pub granted: bool,
+    pub granted_at_ms: Option<i64>,
+    pub revoked_at_ms: Option<i64>,
+    pub expires_at_ms: Option<i64>,
+}
+
+/// One entry in the consent audit trail
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiConsentAuditEntry {
+    pub scope: FfiConsentScope,
+    pub action: FfiConsentAction,
+    pub timestamp_ms: i64,
+}
+
+/// Registry of per-scope consent with an append-only audit trail.
+///
+/// Subsystems call `require` / `is_granted` before writing or exporting
+/// sensitive data. When a file is attached, every change is persisted.
+#[cfg(feature = "full")]
+pub struct ConsentRegistry {
+    inner: Mutex<ConsentRegistryInner>,
+    clock: Arc<dyn Clock>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct ConsentRegistryInner {
+    records: HashMap<FfiConsentScope, FfiConsentRecord>,
+    audit: Vec<FfiConsentAuditEntry>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+impl Default for ConsentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl ConsentRegistry {
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Registry timing grants and expiry with `clock` (Rust callers only)
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            inner: Mutex::new(ConsentRegistryInner::default()),
+            clock,
+        }
+    }
+
+    /// Wall-clock time consent is judged against
+    fn now_ms(&self) -> i64 {
+        self.clock.now_ms()
+    }
+
+    /// Load persisted consent from `path` (if present) and persist future changes there
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read consent: {}", e)))?;
+            serde_json::from_slice::<ConsentRegistryInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt consent file: {}", e)))?
+        } else {
+            ConsentRegistryInner::default()
+        };
+        loaded.path = Some(path);
+
+        // Grants and revocations made before the file was attached are newer
+        let mut inner = self.inner.lock();
+        let pending = std::mem::take(&mut *inner);
+        *inner = loaded;
+        inner.records.extend(pending.records);
+        inner.audit.extend(pending.audit);
+        Self::persist(&inner)
+    }
+
+    pub fn grant(&self, scope: FfiConsentScope, valid_days: Option<u32>) -> Result<(), ZenOneError> {
+        let now_ms = self.now_ms();
+        let mut inner = self.inner.lock();
+        inner.records.insert(scope, FfiConsentRecord {
+            scope,
+            granted: true,
+            granted_at_ms: Some(now_ms),
+            revoked_at_ms: None,
+            expires_at_ms: valid_days.map(|d| now_ms + d as i64 * 86_400_000),
+        });
+        inner.audit.push(FfiConsentAuditEntry { scope, action: FfiConsentAction::Granted, timestamp_ms: now_ms });
+        log::info!("ConsentRegistry: {:?} granted", scope);
+        Self::persist(&inner)
+    }
+
+    pub fn revoke(&self, scope: FfiConsentScope) -> Result<(), ZenOneError> {
+        let now_ms = self.now_ms();
+        let mut inner = self.inner.lock();
+        if let Some(record) = inner.records.get_mut(&scope) {
+            record.granted = false;
+            record.revoked_at_ms = Some(now_ms);
+        }
+        inner.audit.push(FfiConsentAuditEntry { scope, action: FfiConsentAction::Revoked, timestamp_ms: now_ms });
+        log::info!("ConsentRegistry: {:?} revoked", scope);
+        Self::persist(&inner)
+    }
+
+    /// Check a scope, lazily recording expiry in the audit trail
+    pub fn is_granted(&self, scope: FfiConsentScope) -> bool {
+        let now_ms = self.now_ms();
+        let mut inner = self.inner.lock();
+        let expired = match inner.records.get(&scope) {
+            Some(r) if r.granted => r.expires_at_ms.is_some_and(|exp| now_ms >= exp),
+            _ => return false,
+        };
+        if expired {
+            if let Some(record) = inner.records.get_mut(&scope) {
+                record.granted = false;
+            }
+            inner.audit.push(FfiConsentAuditEntry { scope, action: FfiConsentAction::Expired, timestamp_ms: now_ms });
+            if let Err(e) = Self::persist(&inner) {
+                log::error!("ConsentRegistry: {}", e);
+            }
+            return false;
+        }
+        true
+    }
+
+    /// Enforcement check for data-producing subsystems
+    pub fn require(&self, scope: FfiConsentScope) -> Result<(), ZenOneError> {
+        if self.is_granted(scope) {
+            Ok(())
+        } else {
+            Err(ZenOneError::ConsentRequired(format!("{:?}", scope)))
+        }
+    }
+
+    /// Check before an export leaves the device: the export itself and
+    /// handing it off both need consent
+    pub fn require_export(&self) -> Result<(), ZenOneError> {
+        self.require(FfiConsentScope::DataExport)?;
+        self.require(FfiConsentScope::CloudSync)
+    }
+
+    pub fn records(&self) -> Vec<FfiConsentRecord> {
+        // Refresh expiry state before reporting
+        for scope in FfiConsentScope::ALL {
+            self.is_granted(scope);
+        }
+        let inner = self.inner.lock();
+        FfiConsentScope::ALL
+            .iter()
+            .map(|scope| {
+                inner.records.get(scope).cloned().unwrap_or(FfiConsentRecord {
+                    scope: *scope,
+                    granted: false,
+                    granted_at_ms: None,
+                    revoked_at_ms: None,
+                    expires_at_ms: None,
+                })
+            })
+            .collect()
+    }
+
+    pub fn audit(&self) -> Vec<FfiConsentAuditEntry> {
+        self.inner.lock().audit.clone()
+    }
+
+    fn persist(inner: &ConsentRegistryInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Consent serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist consent: {}", e)))
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod consent_tests {
+    use super::*;
+
+    fn actions(registry: &ConsentRegistry, scope: FfiConsentScope) -> Vec<FfiConsentAction> {
+        registry.audit().iter().filter(|e| e.scope == scope).map(|e| e.action).collect()
+    }
+
+    #[test]
+    fn scopes_are_denied_until_granted() {
+        let registry = ConsentRegistry::new();
+        for scope in FfiConsentScope::ALL {
+            assert!(matches!(registry.require(scope), Err(ZenOneError::ConsentRequired(_))));
+        }
+        registry.grant(FfiConsentScope::DataExport, None).unwrap();
+        assert!(registry.require(FfiConsentScope::DataExport).is_ok());
+        assert!(!registry.is_granted(FfiConsentScope::RawSignal));
+
+        registry.revoke(FfiConsentScope::DataExport).unwrap();
+        assert!(registry.require(FfiConsentScope::DataExport).is_err());
+        assert_eq!(
+            actions(&registry, FfiConsentScope::DataExport),
+            [FfiConsentAction::Granted, FfiConsentAction::Revoked]
+        );
+    }
+
+    #[test]
+    fn expired_consent_is_denied_and_audited() {
+        let registry = ConsentRegistry::new();
+        registry.grant(FfiConsentScope::RawSignal, Some(0)).unwrap();
+        assert!(!registry.is_granted(FfiConsentScope::RawSignal));
+        let record = registry.records().into_iter().find(|r| r.scope == FfiConsentScope::RawSignal).unwrap();
+        assert!(!record.granted);
+        assert_eq!(
+            actions(&registry, FfiConsentScope::RawSignal),
+            [FfiConsentAction::Granted, FfiConsentAction::Expired]
+        );
+    }
+
+    #[test]
+    fn grants_and_expiry_follow_the_injected_clock() {
+        let clock = Arc::new(ManualClock::new(1_704_096_000_000));
+        let registry = ConsentRegistry::with_clock(clock.clone());
+        registry.grant(FfiConsentScope::RawSignal, Some(1)).unwrap();
+        let record = registry.records().into_iter().find(|r| r.scope == FfiConsentScope::RawSignal).unwrap();
+        assert_eq!(record.granted_at_ms, Some(1_704_096_000_000));
+        assert_eq!(record.expires_at_ms, Some(1_704_096_000_000 + 86_400_000));
+
+        clock.advance(Duration::from_secs(86_399));
+        assert!(registry.is_granted(FfiConsentScope::RawSignal));
+        clock.advance(Duration::from_secs(1));
+        assert!(!registry.is_granted(FfiConsentScope::RawSignal));
+        let expired = registry.audit().into_iter().last().unwrap();
+        assert_eq!(expired.action, FfiConsentAction::Expired);
+        assert_eq!(expired.timestamp_ms, 1_704_096_000_000 + 86_400_000);
+    }
+
+    #[test]
+    fn consent_file_round_trips() {
+        let path = std::env::temp_dir().join(format!("zenone-consent-test-{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let registry = ConsentRegistry::new();
+        registry.attach_file(path.clone()).unwrap();
+        registry.grant(FfiConsentScope::Analytics, None).unwrap();
+        registry.grant(FfiConsentScope::EmergencyContact, None).unwrap();
+        registry.revoke(FfiConsentScope::Analytics).unwrap();
+
+        let reloaded = ConsentRegistry::new();
+        reloaded.attach_file(path.clone()).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(reloaded.is_granted(FfiConsentScope::EmergencyContact));
+        assert!(!reloaded.is_granted(FfiConsentScope::Analytics));
+        assert_eq!(reloaded.audit().len(), 3);
+    }
+
+    #[test]
+    fn exports_require_data_export_and_cloud_sync_consent() {
+        let runtime = ZenOneRuntime::new();
+        assert!(matches!(
+            runtime.export_fhir_bundle("Patient/1".into()),
+            Err(ZenOneError::ConsentRequired(_))
+        ));
+        runtime.grant_consent(FfiConsentScope::DataExport, None).unwrap();
+        assert!(matches!(runtime.export_sessions(None, None), Err(ZenOneError::ConsentRequired(_))));
+        assert!(matches!(
+            runtime.export_research_package(0, 1, vec![], "unused.zrp".into(), "pw".into()),
+            Err(ZenOneError::ConsentRequired(_))
+        ));
+        runtime.grant_consent(FfiConsentScope::CloudSync, None).unwrap();
+        assert!(runtime.export_fhir_bundle("Patient/1".into()).is_ok());
+        assert!(runtime.export_sessions(None, None).is_ok());
+    }
+
+    #[test]
+    fn analytics_queries_require_analytics_consent() {
+        let runtime = ZenOneRuntime::new();
+        let denied = |r: Result<(), ZenOneError>| matches!(r, Err(ZenOneError::ConsentRequired(_)));
+        assert!(denied(runtime.get_rollups(FfiRollupPeriod::Day, None, None).map(drop)));
+        assert!(denied(runtime.get_binaural_stats(FfiOutcomeMetric::DurationSec).map(drop)));
+        assert!(denied(runtime.get_experiment_report("missing".into()).map(drop)));
+
+        runtime.grant_consent(FfiConsentScope::Analytics, None).unwrap();
+        assert!(runtime.get_rollups(FfiRollupPeriod::Day, None, None).is_ok());
+        assert!(runtime.get_binaural_stats(FfiOutcomeMetric::DurationSec).is_ok());
+        assert!(!denied(runtime.get_experiment_report("missing".into()).map(drop)));
+    }
+}
+
+// ============================================================================
+// RESEARCH PACKAGE - ENCRYPTED, SIGNED, SELF-DESCRIBING EXPORT
+// ============================================================================
+
+#[cfg(feature = "full")]
+const RESEARCH_PACKAGE_MAGIC: &[u8; 4] = b"ZRP2";
+#[cfg(feature = "full")]
+const RESEARCH_PACKAGE_VERSION: u32 = 2;
+#[cfg(feature = "full")]
+const RESEARCH_MANIFEST_SIGNATURE_CONTEXT: &[u8] = b"zenb-research-manifest-v1\n";
+/// Package files under this prefix are research chunks, already encrypted
+#[cfg(feature = "full")]
+const RAW_CHUNK_PREFIX: &str = "raw/";
+#[cfg(feature = "full")]
+const RAW_SIGNAL_CHUNK_SCHEMA_VERSION: u32 = 1;
+
+/// Schema version of one content type in a package
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSchemaVersion {
+    pub name: String,
+    pub version: u32,
+}
+
+/// Device/build that produced a package
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiDeviceInfo {
+    pub os: String,
+    pub arch: String,
+    pub core_version: String,
+}
+
+#[cfg(feature = "full")]
+impl FfiDeviceInfo {
+    fn current() -> Self {
+        FfiDeviceInfo {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            core_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// A file inside a package with its SHA-256 digest
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPackageFile {
+    pub name: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+/// Manifest describing a research package
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiResearchManifest {
+    pub package_version: u32,
+    pub created_at_ms: i64,
+    pub range_start_ms: i64,
+    pub range_end_ms: i64,
+    pub scopes: Vec<FfiConsentScope>,
+    pub schema_versions: Vec<FfiSchemaVersion>,
+    pub device: FfiDeviceInfo,
+    /// Consent state at export time
+    pub consent: Vec<FfiConsentRecord>,
+    pub session_count: u32,
+    pub files: Vec<FfiPackageFile>,
+    /// Hex ed25519 key of the exporting device
+    pub public_key: String,
+    /// Device ed25519 signature (hex) over the manifest body and all file digests
+    pub signature: String,
+}
+
+/// Builds research packages from the session store and research recordings.
+///
+/// Container: `ZRP2` magic, `u32` length and a SecureVault blob (sealed with
+/// the package passphrase) holding the `u32` manifest length, manifest JSON
+/// and the session files; then the `raw/` chunks as stored on disk. Chunks
+/// are already sealed under the recording passphrase, so they are carried
+/// as-is. Each file entry is `u16` name length, name, `u64` length, bytes.
+#[cfg(feature = "full")]
+struct ResearchPackager<'a> {
+    store: &'a SessionStore,
+    research: &'a ResearchRecorder,
+    consent: &'a ConsentRegistry,
+    now_ms: i64,
+}
+
+#[cfg(feature = "full")]
+impl<'a> ResearchPackager<'a> {
+    fn new(store: &'a SessionStore, research: &'a ResearchRecorder, consent: &'a ConsentRegistry, now_ms: i64) -> Self {
+        Self { store, research, consent, now_ms }
+    }
+
+    fn export(
+        &self,
+        start_ms: i64,
+        end_ms: i64,
+        scopes: Vec<FfiConsentScope>,
+        path: String,
+        passphrase: String,
+    ) -> Result<FfiResearchManifest, ZenOneError> {
+        if end_ms < start_ms {
+            return Err(ZenOneError::ConfigError("Invalid export range".into()));
+        }
+        if passphrase.is_empty() {
+            return Err(ZenOneError::ConfigError("Research package requires a passphrase".into()));
+        }
+        // Exporting at all needs DataExport and CloudSync; each included scope needs its own consent
+        self.consent.require_export()?;
+        for scope in &scopes {
+            self.consent.require(*scope)?;
+        }
+
+        let mut files: PackageFiles = Vec::new();
+        let mut schema_versions = Vec::new();
+
+        let sessions: Vec<FfiSessionRecord> = self
+            .store
+            .all()
+            .into_iter()
+            .filter(|r| r.started_at_ms >= start_ms && r.started_at_ms <= end_ms)
+            .collect();
+        let sessions_json = SessionExport::new(&sessions, self.store.public_key()).to_json()?;
+        files.push(("sessions.json".to_string(), sessions_json.into_bytes()));
+        schema_versions.push(FfiSchemaVersion { name: SESSION_EXPORT_SCHEMA.into(), version: SESSION_EXPORT_SCHEMA_VERSION });
+
+        if scopes.contains(&FfiConsentScope::RawSignal) {
+            for chunk_path in self.research.chunk_files_in_range(start_ms, end_ms) {
+                let bytes = std::fs::read(&chunk_path)
+                    .map_err(|e| ZenOneError::ConfigError(format!("Cannot read {}: {}", chunk_path.display(), e)))?;
+                let name = chunk_path.file_name().and_then(|n| n.to_str()).unwrap_or("chunk");
+                files.push((format!("{}{}", RAW_CHUNK_PREFIX, name), bytes));
+            }
+            schema_versions.push(FfiSchemaVersion { name: "raw_signal_chunk".into(), version: RAW_SIGNAL_CHUNK_SCHEMA_VERSION });
+        }
+
+        let mut manifest = FfiResearchManifest {
+            package_version: RESEARCH_PACKAGE_VERSION,
+            created_at_ms: self.now_ms,
+            range_start_ms: start_ms,
+            range_end_ms: end_ms,
+            scopes,
+            schema_versions,
+            device: FfiDeviceInfo::current(),
+            consent: self.consent.records(),
+            session_count: sessions.len() as u32,
+            files: files
+                .iter()
+                .map(|(name, bytes)| FfiPackageFile {
+                    name: name.clone(),
+                    size_bytes: bytes.len() as u64,
+                    sha256: hex_encode(&Sha256::digest(bytes)),
+                })
+                .collect(),
+            public_key: String::new(),
+            signature: String::new(),
+        };
+        manifest.signature = self
+            .store
+            .with_signer(|key| {
+                manifest.public_key = hex_encode(&key.verifying_key().to_bytes());
+                manifest_signing_payload(&manifest).map(|payload| hex_encode(&key.sign(&payload).to_bytes()))
+            })
+            .ok_or_else(|| ZenOneError::ConfigError("Unlock the session store to sign research packages".into()))??;
+
+        let container = encode_package_container(&manifest, &files, &passphrase)?;
+        std::fs::write(&path, container)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot write package: {}", e)))?;
+
+        log::info!(
+            "ResearchPackager: Exported {} sessions, {} files to {}",
+            manifest.session_count,
+            manifest.files.len(),
+            path
+        );
+        Ok(manifest)
+    }
+}
+
+/// Decrypt a package, check every file digest and the manifest signature
+/// against `public_key` (hex)
+#[cfg(feature = "full")]
+fn verify_research_package(path: String, passphrase: String, public_key: &str) -> Result<FfiResearchManifest, ZenOneError> {
+    let key = parse_public_key(public_key)?;
+    let data = std::fs::read(&path)
+        .map_err(|e| ZenOneError::ConfigError(format!("Cannot read package: {}", e)))?;
+    let (manifest, files) = decode_package_container(&data, passphrase)?;
+
+    if manifest.files.len() != files.len() {
+        return Err(ZenOneError::ConfigError("Package file count mismatch".into()));
+    }
+    for (entry, (name, bytes)) in manifest.files.iter().zip(files.iter()) {
+        if entry.name != *name || entry.sha256 != hex_encode(&Sha256::digest(bytes)) {
+            return Err(ZenOneError::ConfigError(format!("Digest mismatch for {}", name)));
+        }
+    }
+    if parse_public_key(&manifest.public_key).map(|k| k.to_bytes()).ok() != Some(key.to_bytes()) {
+        return Err(ZenOneError::ConfigError("Package was signed by a different device".into()));
+    }
+    let valid = hex_decode(&manifest.signature)
+        .and_then(|b| <[u8; 64]>::try_from(b).ok())
+        .map(|bytes| Signature::from_bytes(&bytes))
+        .is_some_and(|sig| {
+            manifest_signing_payload(&manifest).is_ok_and(|payload| key.verify_strict(&payload, &sig).is_ok())
+        });
+    if !valid {
+        return Err(ZenOneError::ConfigError("Package signature invalid".into()));
+    }
+    Ok(manifest)
+}
+
+/// Bytes a manifest signature covers: the manifest with an empty signature
+/// field, prefixed with RESEARCH_MANIFEST_SIGNATURE_CONTEXT
+#[cfg(feature = "full")]
+fn manifest_signing_payload(manifest: &FfiResearchManifest) -> Result<Vec<u8>, ZenOneError> {
+    let mut unsigned = manifest.clone();
+    unsigned.signature = String::new();
+    let mut payload = RESEARCH_MANIFEST_SIGNATURE_CONTEXT.to_vec();
+    serde_json::to_writer(&mut payload, &unsigned)
+        .map_err(|e| ZenOneError::ConfigError(format!("Manifest serialization failed: {}", e)))?;
+    Ok(payload)
+}
+
+/// Named file payloads carried inside a research package
+#[cfg(feature = "full")]
+type PackageFiles = Vec<(String, Vec<u8>)>;
+
+#[cfg(feature = "full")]
+fn is_raw_chunk(name: &str) -> bool {
+    name.starts_with(RAW_CHUNK_PREFIX)
+}
+
+#[cfg(feature = "full")]
+fn encode_package_files<'a>(out: &mut Vec<u8>, files: impl Iterator<Item = &'a (String, Vec<u8>)>) {
+    for (name, bytes) in files {
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        out.extend_from_slice(bytes);
+    }
+}
+
+#[cfg(feature = "full")]
+fn encode_package_container(
+    manifest: &FfiResearchManifest,
+    files: &[(String, Vec<u8>)],
+    passphrase: &str,
+) -> Result<Vec<u8>, ZenOneError> {
+    let manifest_json = serde_json::to_vec(manifest)
+        .map_err(|e| ZenOneError::ConfigError(format!("Manifest serialization failed: {}", e)))?;
+    let mut sealed = Vec::new();
+    sealed.extend_from_slice(&(manifest_json.len() as u32).to_le_bytes());
+    sealed.extend_from_slice(&manifest_json);
+    encode_package_files(&mut sealed, files.iter().filter(|(name, _)| !is_raw_chunk(name)));
+    let sealed = SecureVault::new().encrypt_blob(passphrase.to_string(), sealed)?;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(RESEARCH_PACKAGE_MAGIC);
+    out.extend_from_slice(&(sealed.len() as u32).to_le_bytes());
+    out.extend_from_slice(&sealed);
+    encode_package_files(&mut out, files.iter().filter(|(name, _)| is_raw_chunk(name)));
+    Ok(out)
+}
+
+/// Reads length-prefixed fields from a package buffer
+#[cfg(feature = "full")]
+struct PackageReader<'a> {
+    data: &'a [u8],
+    cursor: usize,
+}
+
+#[cfg(feature = "full")]
+impl<'a> PackageReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, cursor: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ZenOneError> {
+        let slice = self
+            .cursor
+            .checked_add(n)
+            .and_then(|end| self.data.get(self.cursor..end))
+            .ok_or_else(corrupt_package)?;
+        self.cursor += n;
+        Ok(slice)
+    }
+
+    fn take_len<const N: usize>(&mut self) -> Result<usize, ZenOneError> {
+        let bytes: [u8; N] = self.take(N)?.try_into().map_err(|_| corrupt_package())?;
+        let mut wide = [0u8; 8];
+        wide[..N].copy_from_slice(&bytes);
+        usize::try_from(u64::from_le_bytes(wide)).map_err(|_| corrupt_package())
+    }
+
+    fn take_file(&mut self) -> Result<(String, Vec<u8>), ZenOneError> {
+        let name_len = self.take_len::<2>()?;
+        let name = String::from_utf8(self.take(name_len)?.to_vec()).map_err(|_| corrupt_package())?;
+        let len = self.take_len::<8>()?;
+        Ok((name, self.take(len)?.to_vec()))
+    }
+}
+
+#[cfg(feature = "full")]
+fn corrupt_package() -> ZenOneError {
+    ZenOneError::ConfigError("Corrupt research package".into())
+}
+
+#[cfg(feature = "full")]
+fn decode_package_container(data: &[u8], passphrase: String) -> Result<(FfiResearchManifest, PackageFiles), ZenOneError> {
+    let mut outer = PackageReader::new(data);
+    if outer.take(4)? != RESEARCH_PACKAGE_MAGIC {
+        return Err(ZenOneError::ConfigError("Not a version 2 research package".into()));
+    }
+    let sealed_len = outer.take_len::<4>()?;
+    let sealed = SecureVault::new().decrypt_blob(passphrase, outer.take(sealed_len)?.to_vec())?;
+
+    let mut inner = PackageReader::new(&sealed);
+    let manifest_len = inner.take_len::<4>()?;
+    let manifest: FfiResearchManifest =
+        serde_json::from_slice(inner.take(manifest_len)?).map_err(|_| corrupt_package())?;
+
+    let mut files = Vec::with_capacity(manifest.files.len());
+    for entry in &manifest.files {
+        let reader = if is_raw_chunk(&entry.name) { &mut outer } else { &mut inner };
+        files.push(reader.take_file()?);
+    }
+    Ok((manifest, files))
+}
+
+/// Lowercase hex encoding
+#[cfg(feature = "full")]
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Inverse of `hex_encode`; None on odd length or non-hex digits
+#[cfg(feature = "full")]
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok().filter(|p| p.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+// ============================================================================
+// N-OF-1 SELF-EXPERIMENTS
+// ============================================================================
+
+#[cfg(feature = "full")]
+const EXPERIMENTS_FILE: &str = "experiments.json";
+
+/// Session outcome used to compare conditions
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiOutcomeMetric {
+    AvgResonance,
+    AvgHeartRate,
+    DurationSec,
+    CyclesCompleted,
+}
+
+#[cfg(feature = "full")]
+impl FfiOutcomeMetric {
+    /// Extract the metric from a stored session (None if not measured)
+    fn value(&self, record: &FfiSessionRecord) -> Option<f32> {
+        match self {
+            FfiOutcomeMetric::AvgResonance => Some(record.stats.avg_resonance),
+            FfiOutcomeMetric::AvgHeartRate => record.stats.avg_heart_rate,
+            FfiOutcomeMetric::DurationSec => Some(record.stats.duration_sec),
+            FfiOutcomeMetric::CyclesCompleted => Some(record.stats.cycles_completed as f32),
+        }
+    }
+
+    fn higher_is_better(&self) -> bool {
+        !matches!(self, FfiOutcomeMetric::AvgHeartRate)
+    }
+}
+
+/// A personal experiment comparing two patterns
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiExperiment {
+    pub id: String,
+    pub name: String,
+    pub condition_a: String,
+    pub condition_b: String,
+    pub metric: FfiOutcomeMetric,
+    pub min_sessions_per_condition: u32,
+    pub started_at_ms: i64,
+    pub ended_at_ms: Option<i64>,
+}
+
+/// Per-condition outcome comparison
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiExperimentReport {
+    pub experiment_id: String,
+    /// True until both conditions reach `min_sessions_per_condition`
+    pub blinded: bool,
+    pub sessions_a: u32,
+    pub sessions_b: u32,
+    pub mean_a: Option<f32>,
+    pub mean_b: Option<f32>,
+    /// mean_b - mean_a
+    pub difference: Option<f32>,
+    /// Cohen's d (pooled SD)
+    pub effect_size: Option<f32>,
+    pub better_condition: Option<String>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Clone, Serialize, Deserialize)]
+struct ExperimentRecord {
+    experiment: FfiExperiment,
+    /// Seed for the counterbalanced day schedule
+    seed: u64,
+}
+
+/// Runs N-of-1 experiments over the session store.
+///
+/// Days are grouped in pairs; within each pair the order of A and B is
+/// randomized from the experiment seed, so conditions stay balanced while
+/// the user cannot predict tomorrow's assignment. Only sessions that follow
+/// the day's assignment count toward the outcome.
+#[cfg(feature = "full")]
+pub struct ExperimentManager {
+    inner: Mutex<ExperimentManagerInner>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct ExperimentManagerInner {
+    experiments: Vec<ExperimentRecord>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+impl Default for ExperimentManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl ExperimentManager {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(ExperimentManagerInner::default()),
+        }
+    }
+
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read experiments: {}", e)))?;
+            serde_json::from_slice::<ExperimentManagerInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt experiments file: {}", e)))?
+        } else {
+            ExperimentManagerInner::default()
+        };
+        loaded.path = Some(path);
+        *self.inner.lock() = loaded;
+        Ok(())
+    }
+
+    fn create(
+        &self,
+        name: String,
+        condition_a: String,
+        condition_b: String,
+        metric: FfiOutcomeMetric,
+        min_sessions_per_condition: u32,
+        started_at_ms: i64,
+    ) -> Result<FfiExperiment, ZenOneError> {
+        if condition_a == condition_b {
+            return Err(ZenOneError::ConfigError("Experiment conditions must differ".into()));
+        }
+        let experiment = FfiExperiment {
+            id: format!("exp-{:x}-{:04x}", started_at_ms, rand::random::<u16>()),
+            name,
+            condition_a,
+            condition_b,
+            metric,
+            min_sessions_per_condition: min_sessions_per_condition.max(2),
+            started_at_ms,
+            ended_at_ms: None,
+        };
+        let mut inner = self.inner.lock();
+        inner.experiments.push(ExperimentRecord {
+            experiment: experiment.clone(),
+            seed: rand::random(),
+        });
+        Self::persist(&inner)?;
+        Ok(experiment)
+    }
+
+    fn list(&self) -> Vec<FfiExperiment> {
+        self.inner.lock().experiments.iter().map(|r| r.experiment.clone()).collect()
+    }
+
+    fn end(&self, id: &str, ended_at_ms: i64) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        let record = inner.experiments.iter_mut()
+            .find(|r| r.experiment.id == id)
+            .ok_or_else(|| ZenOneError::ConfigError(format!("Unknown experiment {}", id)))?;
+        record.experiment.ended_at_ms.get_or_insert(ended_at_ms);
+        Self::persist(&inner)
+    }
+
+    fn assignment_at(&self, id: &str, timestamp_ms: i64, zone: LocalZone) -> Result<String, ZenOneError> {
+        let inner = self.inner.lock();
+        let record = inner.experiments.iter()
+            .find(|r| r.experiment.id == id)
+            .ok_or_else(|| ZenOneError::ConfigError(format!("Unknown experiment {}", id)))?;
+        if record.experiment.ended_at_ms.is_some() {
+            return Err(ZenOneError::ConfigError("Experiment has ended".into()));
+        }
+        let date = zone.date(timestamp_ms).ok_or_else(|| ZenOneError::ConfigError("Invalid timestamp".into()))?;
+        Ok(Self::condition_for(record, date, zone).to_string())
+    }
+
+    /// Counterbalanced assignment: each 2-day block contains A and B once.
+    /// Days are calendar days in the user's zone, counted from the start date.
+    fn condition_for(record: &ExperimentRecord, date: chrono::NaiveDate, zone: LocalZone) -> &str {
+        let start = zone.date(record.experiment.started_at_ms).unwrap_or(date);
+        let day = (date - start).num_days();
+        let block = day.div_euclid(2) as u64;
+        // SplitMix64 step for a per-block coin flip
+        let mut z = record.seed.wrapping_add(block.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        let flip = (z ^ (z >> 31)) & 1 == 1;
+        let first_half = day.rem_euclid(2) == 0;
+        if first_half != flip {
+            &record.experiment.condition_a
+        } else {
+            &record.experiment.condition_b
+        }
+    }
+
+    fn report(&self, id: &str, sessions: &[FfiSessionRecord], zone: LocalZone) -> Result<FfiExperimentReport, ZenOneError> {
+        let inner = self.inner.lock();
+        let record = inner.experiments.iter()
+            .find(|r| r.experiment.id == id)
+            .ok_or_else(|| ZenOneError::ConfigError(format!("Unknown experiment {}", id)))?;
+        let exp = &record.experiment;
+        let end_ms = exp.ended_at_ms.unwrap_or(i64::MAX);
+
+        let mut values_a = Vec::new();
+        let mut values_b = Vec::new();
+        for s in sessions.iter().filter(|s| s.started_at_ms >= exp.started_at_ms && s.started_at_ms <= end_ms) {
+            // Only sessions that followed the day's assignment count
+            let Some(date) = session_date(s, zone) else { continue };
+            if s.stats.pattern_id != Self::condition_for(record, date, zone) {
+                continue;
+            }
+            if let Some(v) = exp.metric.value(s) {
+                if s.stats.pattern_id == exp.condition_a {
+                    values_a.push(v);
+                } else {
+                    values_b.push(v);
+                }
+            }
+        }
+
+        let min = exp.min_sessions_per_condition as usize;
+        let blinded = values_a.len() < min || values_b.len() < min;
+        let mut report = FfiExperimentReport {
+            experiment_id: exp.id.clone(),
+            blinded,
+            sessions_a: values_a.len() as u32,
+            sessions_b: values_b.len() as u32,
+            mean_a: None,
+            mean_b: None,
+            difference: None,
+            effect_size: None,
+            better_condition: None,
+        };
+        if blinded {
+            return Ok(report);
+        }
+
+        let (mean_a, sd_a) = mean_and_sd(&values_a);
+        let (mean_b, sd_b) = mean_and_sd(&values_b);
+        let pooled = pooled_sd(sd_a, values_a.len(), sd_b, values_b.len());
+        let difference = mean_b - mean_a;
+        report.mean_a = Some(mean_a);
+        report.mean_b = Some(mean_b);
+        report.difference = Some(difference);
+        report.effect_size = (pooled > f32::EPSILON).then(|| difference / pooled);
+        report.better_condition = if difference.abs() <= f32::EPSILON {
+            None
+        } else if (difference > 0.0) == exp.metric.higher_is_better() {
+            Some(exp.condition_b.clone())
+        } else {
+            Some(exp.condition_a.clone())
+        };
+        Ok(report)
+    }
+
+    fn persist(inner: &ExperimentManagerInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Experiment serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist experiments: {}", e)))
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod experiment_tests {
+    use super::*;
+
+    #[test]
+    fn experiment_dates_come_from_the_runtime_clock() {
+        let clock = Arc::new(ManualClock::new(1_704_096_000_000));
+        let runtime = ZenOneRuntime::with_clock("4-7-8".into(), clock.clone());
+        let experiment = runtime
+            .create_experiment("evening".into(), "4-7-8".into(), "calm".into(), FfiOutcomeMetric::DurationSec, 3)
+            .unwrap();
+        assert_eq!(experiment.started_at_ms, 1_704_096_000_000);
+
+        clock.advance(Duration::from_secs(86_400));
+        runtime.end_experiment(experiment.id.clone()).unwrap();
+        let ended = runtime.list_experiments().into_iter().find(|e| e.id == experiment.id).unwrap();
+        assert_eq!(ended.ended_at_ms, Some(1_704_096_000_000 + 86_400_000));
+    }
+}
+
+/// Paired statistical comparison of two patterns
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPatternComparison {
+    pub pattern_a: String,
+    pub pattern_b: String,
+    pub metric: FfiOutcomeMetric,
+    /// Number of days with sessions of both patterns
+    pub pairs: u32,
+    pub mean_a: f32,
+    pub mean_b: f32,
+    /// Mean of paired differences (b - a)
+    pub mean_difference: f32,
+    /// 95% confidence interval of the mean difference
+    pub ci_low: f32,
+    pub ci_high: f32,
+    /// Paired effect size (Cohen's d_z)
+    pub effect_size: Option<f32>,
+    /// True if the confidence interval excludes zero
+    pub significant: bool,
+}
+
+/// Compare two patterns over matched sessions.
+///
+/// Sessions are matched by local calendar day: each day that has at least one
+/// session of both patterns contributes one pair (per-day means), which
+/// controls for day-to-day variation in sleep, stress, etc.
+#[cfg(feature = "full")]
+fn compare_patterns_stats(
+    sessions: &[FfiSessionRecord],
+    pattern_a: String,
+    pattern_b: String,
+    metric: FfiOutcomeMetric,
+    zone: LocalZone,
+) -> Result<FfiPatternComparison, ZenOneError> {
+    if pattern_a == pattern_b {
+        return Err(ZenOneError::ConfigError("Patterns to compare must differ".into()));
+    }
+
+    // day -> (values for a, values for b)
+    let mut by_day: std::collections::BTreeMap<chrono::NaiveDate, (Vec<f32>, Vec<f32>)> = std::collections::BTreeMap::new();
+    for s in sessions {
+        let Some(v) = metric.value(s) else { continue };
+        let Some(day) = session_date(s, zone) else { continue };
+        if s.stats.pattern_id == pattern_a {
+            by_day.entry(day).or_default().0.push(v);
+        } else if s.stats.pattern_id == pattern_b {
+            by_day.entry(day).or_default().1.push(v);
+        }
+    }
+
+    let pairs: Vec<(f32, f32)> = by_day
+        .values()
+        .filter(|(a, b)| !a.is_empty() && !b.is_empty())
+        .map(|(a, b)| (mean_and_sd(a).0, mean_and_sd(b).0))
+        .collect();
+    if pairs.len() < 2 {
+        return Err(ZenOneError::ConfigError(format!(
+            "Need at least 2 matched days, found {}",
+            pairs.len()
+        )));
+    }
+
+    let a: Vec<f32> = pairs.iter().map(|p| p.0).collect();
+    let b: Vec<f32> = pairs.iter().map(|p| p.1).collect();
+    let diffs: Vec<f32> = pairs.iter().map(|(a, b)| b - a).collect();
+    let (mean_diff, sd_diff) = mean_and_sd(&diffs);
+    let n = diffs.len();
+    let half_width = t_critical_975(n - 1) * sd_diff / (n as f32).sqrt();
+    let (ci_low, ci_high) = (mean_diff - half_width, mean_diff + half_width);
+
+    Ok(FfiPatternComparison {
+        pattern_a,
+        pattern_b,
+        metric,
+        pairs: n as u32,
+        mean_a: mean_and_sd(&a).0,
+        mean_b: mean_and_sd(&b).0,
+        mean_difference: mean_diff,
+        ci_low,
+        ci_high,
+        effect_size: (sd_diff > f32::EPSILON).then(|| mean_diff / sd_diff),
+        significant: ci_low > 0.0 || ci_high < 0.0,
+    })
+}
+
+/// Two-sided 95% critical value of Student's t distribution
+#[cfg(feature = "full")]
+fn t_critical_975(df: usize) -> f32 {
+    const TABLE: [f32; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+        2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+        2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => f32::INFINITY,
+        1..=30 => TABLE[df - 1],
+        _ => {
+            // Cornish-Fisher expansion around the normal quantile; within 1e-4 of
+            // the exact value from df = 31 up
+            const Z: f64 = 1.959_963_985;
+            let df = df as f64;
+            let (z3, z5, z7) = (Z.powi(3), Z.powi(5), Z.powi(7));
+            (Z + (z3 + Z) / (4.0 * df)
+                + (5.0 * z5 + 16.0 * z3 + 3.0 * Z) / (96.0 * df.powi(2))
+                + (3.0 * z7 + 19.0 * z5 + 17.0 * z3 - 15.0 * Z) / (384.0 * df.powi(3))) as f32
+        }
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod stats_tests {
+    use super::*;
+
+    /// Monday 2024-01-01 08:00 UTC
+    const MON_JAN_1: i64 = 1_704_096_000_000;
+    const DAY_MS: i64 = 86_400_000;
+
+    fn session(pattern_id: &str, day: i64, duration_sec: f32) -> FfiSessionRecord {
+        let started_at_ms = MON_JAN_1 + day * DAY_MS;
+        serde_json::from_value(serde_json::json!({
+            "id": format!("{}-{}", pattern_id, day),
+            "started_at_ms": started_at_ms,
+            "ended_at_ms": started_at_ms + (duration_sec * 1000.0) as i64,
+            "utc_offset_min": 0,
+            "stats": {
+                "duration_sec": duration_sec,
+                "cycles_completed": 10,
+                "pattern_id": pattern_id,
+                "avg_heart_rate": null,
+                "final_belief": FfiBeliefState::default(),
+                "avg_resonance": 0.5,
+            },
+        }))
+        .unwrap()
+    }
+
+    fn compare(sessions: &[FfiSessionRecord]) -> Result<FfiPatternComparison, ZenOneError> {
+        compare_patterns_stats(sessions, "a".into(), "b".into(), FfiOutcomeMetric::DurationSec, LocalZone::Device)
+    }
+
+    #[test]
+    fn paired_difference_matches_textbook_t_interval() {
+        // Differences 2, 3, 1, 3, 1: mean 2, sd 1, t(0.975, 4) = 2.776
+        let a = [10.0, 12.0, 9.0, 11.0, 13.0];
+        let b = [12.0, 15.0, 10.0, 14.0, 14.0];
+        let sessions: Vec<FfiSessionRecord> = (0..5)
+            .flat_map(|day| [session("a", day, a[day as usize]), session("b", day, b[day as usize])])
+            .collect();
+        let result = compare(&sessions).unwrap();
+        assert_eq!(result.pairs, 5);
+        assert!((result.mean_a - 11.0).abs() < 1e-4);
+        assert!((result.mean_b - 13.0).abs() < 1e-4);
+        assert!((result.mean_difference - 2.0).abs() < 1e-4);
+        let half_width = 2.776 / 5f32.sqrt();
+        assert!((result.ci_low - (2.0 - half_width)).abs() < 1e-3, "{}", result.ci_low);
+        assert!((result.ci_high - (2.0 + half_width)).abs() < 1e-3, "{}", result.ci_high);
+        assert!((result.effect_size.unwrap() - 2.0).abs() < 1e-4);
+        assert!(result.significant);
+    }
+
+    #[test]
+    fn t_critical_matches_published_table() {
+        assert!(t_critical_975(0).is_infinite());
+        for (df, expected) in [(1, 12.706), (4, 2.776), (30, 2.042), (31, 2.0395), (40, 2.0211), (60, 2.0003), (120, 1.9799)] {
+            let t = t_critical_975(df);
+            assert!((t - expected).abs() < 1e-3, "df {}: {}", df, t);
+        }
+        // No step between the table and the expansion, and no jump at 60 or 120
+        for df in 2..500 {
+            assert!(t_critical_975(df) < t_critical_975(df - 1), "df {}", df);
+        }
+        assert!((t_critical_975(1_000_000) - 1.96).abs() < 1e-3);
+    }
+
+    #[test]
+    fn paired_interval_with_31_days_uses_t_30() {
+        // Differences alternate 1 and 3 (plus a final 2): mean 2, t(0.975, 30) = 2.042
+        let diffs: Vec<f32> = (0..31).map(|day| if day == 30 { 2.0 } else if day % 2 == 0 { 1.0 } else { 3.0 }).collect();
+        let sessions: Vec<FfiSessionRecord> = diffs
+            .iter()
+            .enumerate()
+            .flat_map(|(day, d)| [session("a", day as i64, 10.0), session("b", day as i64, 10.0 + d)])
+            .collect();
+        let result = compare(&sessions).unwrap();
+        assert_eq!(result.pairs, 31);
+        let (_, sd) = mean_and_sd(&diffs);
+        let half_width = 2.042 * sd / 31f32.sqrt();
+        assert!((result.mean_difference - 2.0).abs() < 1e-4);
+        assert!((result.ci_low - (2.0 - half_width)).abs() < 1e-3, "{}", result.ci_low);
+        assert!((result.ci_high - (2.0 + half_width)).abs() < 1e-3, "{}", result.ci_high);
+        assert!(result.significant);
+    }
+
+    #[test]
+    fn sessions_on_the_same_day_are_averaged_into_one_pair() {
+        let sessions = [
+            session("a", 0, 10.0),
+            session("a", 0, 20.0),
+            session("b", 0, 16.0),
+            session("a", 1, 10.0),
+            session("b", 1, 14.0),
+            // Unpaired day is ignored
+            session("b", 2, 100.0),
+        ];
+        let result = compare(&sessions).unwrap();
+        assert_eq!(result.pairs, 2);
+        assert!((result.mean_difference - 2.5).abs() < 1e-4);
+        assert!(!result.significant);
+    }
+
+    #[test]
+    fn comparison_needs_two_patterns_and_two_days() {
+        let one_day = [session("a", 0, 10.0), session("b", 0, 12.0)];
+        assert!(compare(&one_day).is_err());
+        assert!(compare_patterns_stats(&one_day, "a".into(), "a".into(), FfiOutcomeMetric::DurationSec, LocalZone::Device).is_err());
+    }
+}
+
+/// Sample mean and standard deviation (n - 1)
+#[cfg(feature = "full")]
+fn mean_and_sd(values: &[f32]) -> (f32, f32) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / (n - 1.0);
+    (mean, var.sqrt())
+}
+
+/// Pooled standard deviation of two samples
+#[cfg(feature = "full")]
+fn pooled_sd(sd_a: f32, n_a: usize, sd_b: f32, n_b: usize) -> f32 {
+    if n_a + n_b <= 2 {
+        return 0.0;
+    }
+    let num = (n_a as f32 - 1.0) * sd_a.powi(2) + (n_b as f32 - 1.0) * sd_b.powi(2);
+    (num / (n_a + n_b - 2) as f32).sqrt()
+}
+
+// ============================================================================
+// ADHERENCE DETECTION - DETECTED VS PRESCRIBED BREATHING
+// ============================================================================
+
+/// Origin of a respiration signal sample
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FfiRespirationSource {
+    /// Camera-based chest/shoulder motion
+    ChestMotion,
+    /// Microphone breath envelope
+    Audio,
+    /// Respiratory sinus arrhythmia (HR rises on inhale)
+    Rsa,
+    /// Pulse amplitude and baseline modulation of the camera PPG
+    Ppg,
+}
+
+#[cfg(feature = "full")]
+impl FfiRespirationSource {
+    /// Trust in the source's phase information
+    fn weight(&self) -> f32 {
+        match self {
+            FfiRespirationSource::ChestMotion | FfiRespirationSource::Audio => 1.0,
+            // RSA lags the breath and is noisier
+            FfiRespirationSource::Rsa => 0.5,
+            // One sample per beat, but closer to the breath than RSA
+            FfiRespirationSource::Ppg => 0.7,
+        }
+    }
+}
+
+/// Scores how well detected breathing follows the pacer.
+///
+/// Each sample's slope is compared with the direction the prescribed phase
+/// implies (inhale rising, exhale falling, holds flat). Slopes are normalized
+/// by a running slope magnitude per source so any signal unit works. Scores
+/// are accumulated per cycle into a continuous 0-1 adherence value.
+#[cfg(feature = "full")]
+struct AdherenceTracker {
+    sources: HashMap<FfiRespirationSource, SlopeState>,
+    weighted_score: f32,
+    weight_total: f32,
+    last_cycle_score: Option<f32>,
+    /// Weighted mean normalized slope within the current prescribed phase
+    phase_stats: Option<PhaseSlopeStats>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Clone, Copy)]
+struct PhaseSlopeStats {
+    phase: FfiPhase,
+    norm_sum: f32,
+    weight: f32,
+    samples: u32,
+}
+
+#[cfg(feature = "full")]
+impl PhaseSlopeStats {
+    fn mean_norm(&self) -> f32 {
+        if self.weight > 0.0 { self.norm_sum / self.weight } else { 0.0 }
+    }
+}
+
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct SlopeState {
+    last: Option<(f32, i64)>,
+    /// EMA of |slope| used for normalization
+    scale: f32,
+}
+
+#[cfg(feature = "full")]
+impl AdherenceTracker {
+    fn new() -> Self {
+        Self {
+            sources: HashMap::new(),
+            weighted_score: 0.0,
+            weight_total: 0.0,
+            last_cycle_score: None,
+            phase_stats: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn observe(&mut self, value: f32, source: FfiRespirationSource, timestamp_us: i64, phase: FfiPhase) {
+        if !value.is_finite() {
+            return;
+        }
+        let state = self.sources.entry(source).or_default();
+        let Some((last_value, last_ts)) = state.last.replace((value, timestamp_us)) else { return };
+        let dt_sec = (timestamp_us - last_ts) as f32 / 1_000_000.0;
+        if dt_sec <= 0.0 || dt_sec > 2.0 {
+            return; // Out-of-order or gap: restart slope estimation
+        }
+
+        let slope = (value - last_value) / dt_sec;
+        state.scale = if state.scale == 0.0 { slope.abs() } else { 0.95 * state.scale + 0.05 * slope.abs() };
+        if state.scale <= f32::EPSILON {
+            return;
+        }
+        let norm = (slope / state.scale).clamp(-1.0, 1.0);
+
+        let score = match phase {
+            FfiPhase::Inhale => (1.0 + norm) / 2.0,
+            FfiPhase::Exhale => (1.0 - norm) / 2.0,
+            FfiPhase::HoldIn | FfiPhase::HoldOut => 1.0 - norm.abs(),
+            FfiPhase::Prepare => return,
+        };
+        let weight = source.weight();
+        self.weighted_score += score * weight;
+        self.weight_total += weight;
+
+        let stats = match &mut self.phase_stats {
+            Some(s) if s.phase == phase => s,
+            slot => slot.insert(PhaseSlopeStats { phase, norm_sum: 0.0, weight: 0.0, samples: 0 }),
+        };
+        stats.norm_sum += norm * weight;
+        stats.weight += weight;
+        stats.samples += 1;
+    }
+
+    fn current_phase_stats(&self) -> Option<PhaseSlopeStats> {
+        self.phase_stats
+    }
+
+    /// Running score for the current cycle, falling back to the last cycle
+    fn live_score(&self) -> Option<f32> {
+        if self.weight_total > 0.0 {
+            Some(self.weighted_score / self.weight_total)
+        } else {
+            self.last_cycle_score
+        }
+    }
+
+    /// Close the current cycle and return its score (None if nothing observed)
+    fn finish_cycle(&mut self) -> Option<f32> {
+        if self.weight_total <= 0.0 {
+            return None;
+        }
+        let score = self.weighted_score / self.weight_total;
+        self.weighted_score = 0.0;
+        self.weight_total = 0.0;
+        self.last_cycle_score = Some(score);
+        Some(score)
+    }
+}
+
+// ============================================================================
+// RUNTIME EVENTS
+// ============================================================================
+
+/// Maximum undrained events kept; oldest are dropped first
+#[cfg(feature = "full")]
+const EVENT_QUEUE_CAPACITY: usize = 256;
+
+/// Event emitted by the runtime for the UI/voice layer
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FfiRuntimeEvent {
+    /// Mid-session guidance
+    Coaching { cue: FfiCoachingCue },
+    /// The arc planner switched patterns mid-session
+    PlanChange { change: FfiPlanChange },
+    /// A pattern sequence moved on to its next segment
+    SequenceSegmentStarted { progress: FfiSequenceProgress },
+    /// A streamed heart rate source went silent for too long to interpolate
+    HeartRateGap { gap_ms: u32, timestamp_us: i64 },
+    /// Heart rate stayed in an elevated zone too long; the session was paused
+    HeartRateZoneAlert { zone: String, sustained_sec: f32, timestamp_us: i64 },
+    /// A session goal was reached for the first time
+    GoalAchieved { goal: FfiGoalProgress, timestamp_us: i64 },
+    /// A paused session resumed
+    SessionResumed { interruption: FfiInterruption },
+    /// A session was ended automatically after a very long interruption
+    SessionAutoFinalized { stats: FfiSessionStats },
+    /// A time-limited (e.g. panic) or auto-complete session ran its course
+    SessionCompleted { stats: FfiSessionStats },
+    /// A pattern preview ran all its cycles
+    PreviewFinished { pattern_id: String },
+    /// The session moved from warm-up to main, or from main to cool-down
+    StageStarted { stage: FfiSessionStage, timestamp_us: i64 },
+    /// A new second of the pre-session countdown began (3, 2, 1)
+    Countdown { seconds_left: u32, timestamp_us: i64 },
+    /// The current phase is about to end, so UI/haptics can lead the transition
+    PhaseEnding { next_phase: FfiPhase, time_to_next_phase_ms: u32, timestamp_us: i64 },
+    /// `count` whole seconds of the phase remain; `timestamp_us` is when that second began
+    PhaseCountdown { phase: FfiPhase, count: u32, timestamp_us: i64 },
+    /// A free-breathing observation finished with a usable baseline
+    BaselineReady { baseline: FfiBreathingBaseline },
+    /// A free-breathing observation ended without enough breaths to measure
+    BaselineFailed { reason: String },
+    /// Ambient noise profiling finished; check `usable` before enabling audio detection
+    NoiseProfileReady { profile: FfiNoiseProfile },
+    /// Safety alert for the shell to route to the user's trusted contact
+    Escalation { escalation: FfiEscalation },
+    /// An actor thread panicked and was restarted; a running session is
+    /// left paused. `restarts` counts restarts of that actor so far.
+    RuntimeRecovered { actor: FfiActorKind, reason: String, restarts: u32 },
+    /// The group leader switched to a pattern missing from the local library;
+    /// this device keeps its own timeline until the leader's pattern is known
+    GroupPatternUnavailable { pattern_id: String },
+}
+
+/// Background threads supervised by the runtime
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiActorKind {
+    /// Session, pacer and engine loop
+    Runtime,
+    /// rPPG signal processing
+    Signal,
+}
+
+/// Bounded queue of runtime events, written by the actor and drained by the shell
+#[cfg(feature = "full")]
+pub struct EventQueue {
+    inner: Mutex<std::collections::VecDeque<FfiRuntimeEvent>>,
+}
+
+#[cfg(feature = "full")]
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl EventQueue {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(std::collections::VecDeque::with_capacity(EVENT_QUEUE_CAPACITY)),
+        }
+    }
+
+    fn push(&self, event: FfiRuntimeEvent) {
+        let mut queue = self.inner.lock();
+        if queue.len() >= EVENT_QUEUE_CAPACITY {
+            queue.pop_front();
+        }
+        queue.push_back(event);
+    }
+
+    fn drain(&self) -> Vec<FfiRuntimeEvent> {
+        self.inner.lock().drain(..).collect()
+    }
+}
+
+// ============================================================================
+// COACHING - REAL-TIME CORRECTION CUES
+// ============================================================================
+
+/// Minimum time between two coaching cues
+#[cfg(feature = "full")]
+const COACHING_COOLDOWN_US: i64 = 8_000_000;
+/// Samples needed in a phase before judging it
+#[cfg(feature = "full")]
+const COACHING_MIN_PHASE_SAMPLES: u32 = 10;
+
+/// Kind of coaching cue
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiCoachingKind {
+    BreatheIn,
+    BreatheOut,
+    SlowExhale,
+    HoldingTooLong,
+    HoldStill,
+    BreathingTooFast,
+    BreathingTooSlow,
+    PaceTooHard,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FfiCoachingSeverity {
+    Hint,
+    Correction,
+    Important,
+}
+
+/// What the user (or UI) should do in response
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiCoachingAction {
+    Inhale,
+    Exhale,
+    Hold,
+    SlowDown,
+    SpeedUp,
+    ReduceTempo,
+}
+
+/// A structured coaching cue
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiCoachingCue {
+    pub kind: FfiCoachingKind,
+    pub severity: FfiCoachingSeverity,
+    pub suggested_action: FfiCoachingAction,
+    pub message: String,
+    pub timestamp_us: i64,
+}
+
+/// Tolerated distance (breaths/min) of the measured rate from the pacer's rate
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiBreathRateBand {
+    /// Breathing faster than target + this triggers a cue
+    pub above_bpm: f32,
+    /// Breathing slower than target - this triggers a cue
+    pub below_bpm: f32,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiBreathRateBand {
+    fn default() -> Self {
+        Self { above_bpm: 1.5, below_bpm: 2.0 }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiBreathRateBand {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        for (name, bpm) in [("above", self.above_bpm), ("below", self.below_bpm)] {
+            if !(bpm.is_finite() && (0.1..=10.0).contains(&bpm)) {
+                return Err(ZenOneError::ConfigError(format!(
+                    "Breath rate band '{}' must be 0.1-10 breaths/min, got {}", name, bpm
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Turns adherence deviations into rate-limited coaching cues
+#[cfg(feature = "full")]
+struct CoachingEngine {
+    last_cue_us: Option<i64>,
+    last_kind: Option<FfiCoachingKind>,
+}
+
+#[cfg(feature = "full")]
+impl CoachingEngine {
+    fn new() -> Self {
+        Self { last_cue_us: None, last_kind: None }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Judge the current phase from the mean normalized breath slope
+    fn evaluate_phase(&mut self, tracker: &AdherenceTracker, timestamp_us: i64) -> Option<FfiCoachingCue> {
+        let stats = tracker.current_phase_stats()?;
+        if stats.samples < COACHING_MIN_PHASE_SAMPLES {
+            return None;
+        }
+        let m = stats.mean_norm();
+        let (kind, severity, action, message) = match stats.phase {
+            FfiPhase::Inhale if m < -0.3 => (
+                FfiCoachingKind::BreatheIn, FfiCoachingSeverity::Correction, FfiCoachingAction::Inhale,
+                "Breathe in now",
+            ),
+            FfiPhase::Inhale if m.abs() < 0.15 => (
+                FfiCoachingKind::HoldingTooLong, FfiCoachingSeverity::Hint, FfiCoachingAction::Inhale,
+                "You're holding too long, start inhaling",
+            ),
+            FfiPhase::Exhale if m > 0.3 => (
+                FfiCoachingKind::BreatheOut, FfiCoachingSeverity::Correction, FfiCoachingAction::Exhale,
+                "Start breathing out",
+            ),
+            FfiPhase::Exhale if m.abs() < 0.15 => (
+                FfiCoachingKind::SlowExhale, FfiCoachingSeverity::Hint, FfiCoachingAction::SlowDown,
+                "Slow your exhale, make it last the full count",
+            ),
+            FfiPhase::HoldIn | FfiPhase::HoldOut if m.abs() > 0.5 => (
+                FfiCoachingKind::HoldStill, FfiCoachingSeverity::Hint, FfiCoachingAction::Hold,
+                "Hold gently and stay still",
+            ),
+            _ => return None,
+        };
+        self.emit(kind, severity, action, message, timestamp_us)
+    }
+
+    /// Escalate when several consecutive cycles are poorly followed
+    fn evaluate_cycles(&mut self, cycle_adherence: &[f32], timestamp_us: i64) -> Option<FfiCoachingCue> {
+        if cycle_adherence.len() < 3 || cycle_adherence.iter().rev().take(3).any(|s| *s >= 0.4) {
+            return None;
+        }
+        self.emit(
+            FfiCoachingKind::PaceTooHard,
+            FfiCoachingSeverity::Important,
+            FfiCoachingAction::ReduceTempo,
+            "This pace seems hard to follow, try slowing down",
+            timestamp_us,
+        )
+    }
+
+    /// Compare the measured breathing rate with the pacer's rate
+    fn evaluate_rate(
+        &mut self,
+        detected_bpm: f32,
+        target_bpm: f32,
+        band: &FfiBreathRateBand,
+        timestamp_us: i64,
+    ) -> Option<FfiCoachingCue> {
+        if detected_bpm > target_bpm + band.above_bpm {
+            self.emit(
+                FfiCoachingKind::BreathingTooFast,
+                FfiCoachingSeverity::Correction,
+                FfiCoachingAction::SlowDown,
+                &format!("You're at {:.1} breaths a minute, slow down toward {:.1}", detected_bpm, target_bpm),
+                timestamp_us,
+            )
+        } else if detected_bpm < target_bpm - band.below_bpm {
+            self.emit(
+                FfiCoachingKind::BreathingTooSlow,
+                FfiCoachingSeverity::Hint,
+                FfiCoachingAction::SpeedUp,
+                &format!("You're at {:.1} breaths a minute, follow the pacer at {:.1}", detected_bpm, target_bpm),
+                timestamp_us,
+            )
+        } else {
+            None
+        }
+    }
+
+    fn emit(
+        &mut self,
+        kind: FfiCoachingKind,
+        severity: FfiCoachingSeverity,
+        suggested_action: FfiCoachingAction,
+        message: &str,
+        timestamp_us: i64,
+    ) -> Option<FfiCoachingCue> {
+        if let Some(last) = self.last_cue_us {
+            let elapsed = timestamp_us - last;
+            // Important cues bypass the cooldown, but never repeat back-to-back
+            let cooling = elapsed < COACHING_COOLDOWN_US && severity < FfiCoachingSeverity::Important;
+            let repeat = self.last_kind == Some(kind) && elapsed < 2 * COACHING_COOLDOWN_US;
+            if cooling || repeat {
+                return None;
+            }
+        }
+        self.last_cue_us = Some(timestamp_us);
+        self.last_kind = Some(kind);
+        Some(FfiCoachingCue {
+            kind,
+            severity,
+            suggested_action,
+            message: message.to_string(),
+            timestamp_us,
+        })
+    }
+}
+
+// ============================================================================
+// ARC PLANNER - BELIEF-ADAPTIVE SESSION TRANSITIONS
+// ============================================================================
+
+/// Cycles a pattern must run before the planner may leave it
+#[cfg(feature = "full")]
+const ARC_MIN_DWELL_CYCLES: u64 = 3;
+/// Cycles over which a transition blends into the new pattern's timing
+#[cfg(feature = "full")]
+const ARC_MORPH_CYCLES: u32 = 3;
+/// Longest transition a mid-session load may be configured to take
+#[cfg(feature = "full")]
+const PATTERN_TRANSITION_MAX_CYCLES: u32 = 10;
+/// Consecutive cycles a trigger must hold before acting
+#[cfg(feature = "full")]
+const ARC_TRIGGER_CYCLES: usize = 2;
+/// Upper bound on automatic transitions per session
+#[cfg(feature = "full")]
+const ARC_MAX_CHANGES_PER_SESSION: u32 = 2;
+/// Minimum belief confidence for the planner to act on
+#[cfg(feature = "full")]
+const ARC_MIN_CONFIDENCE: f32 = 0.3;
+
+/// Explanation of a mid-session pattern change
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPlanChange {
+    pub from_pattern: String,
+    pub to_pattern: String,
+    /// Belief mode whose trajectory triggered the change
+    pub trigger_mode: FfiBeliefMode,
+    /// Smoothed probability of the trigger mode when the change fired
+    pub trigger_probability: f32,
+    pub local_hour: u8,
+    pub cycle: u64,
+    pub reason: String,
+    pub timestamp_us: i64,
+}
+
+/// A transition rule: when `mode` stays above `min_probability` (and is not
+/// falling) within the hour window, move to `target`.
+#[cfg(feature = "full")]
+struct ArcRule {
+    mode: FfiBeliefMode,
+    min_probability: f32,
+    /// Inclusive hour window, may wrap midnight
+    hours: (u8, u8),
+    target: &'static str,
+    reason: &'static str,
+}
+
+#[cfg(feature = "full")]
+const ARC_RULES: &[ArcRule] = &[
+    ArcRule {
+        mode: FfiBeliefMode::Sleepy,
+        min_probability: 0.4,
+        hours: (21, 4),
+        target: "4-7-8",
+        reason: "Sleepiness is rising late at night, easing into a sleep pattern",
+    },
+    ArcRule {
+        mode: FfiBeliefMode::Stress,
+        min_probability: 0.45,
+        hours: (0, 23),
+        target: "7-11",
+        reason: "Stress keeps climbing, lengthening the exhale",
+    },
+    ArcRule {
+        mode: FfiBeliefMode::Sleepy,
+        min_probability: 0.5,
+        hours: (8, 17),
+        target: "box",
+        reason: "Drowsiness during the day, switching to a steadier rhythm",
+    },
+];
+
+#[cfg(feature = "full")]
+impl ArcRule {
+    fn matches_hour(&self, hour: u8) -> bool {
+        let (start, end) = self.hours;
+        if start <= end {
+            hour >= start && hour <= end
+        } else {
+            hour >= start || hour <= end
+        }
+    }
+}
+
+/// Watches the per-cycle belief trajectory and proposes pattern transitions
+#[cfg(feature = "full")]
+struct ArcPlanner {
+    enabled: bool,
+    /// Per-cycle belief probabilities, most recent last
+    trajectory: Vec<[f32; 5]>,
+    /// Cycle at which the current pattern started
+    pattern_since_cycle: u64,
+    changes: u32,
+    visited: Vec<String>,
+}
+
+#[cfg(feature = "full")]
+impl ArcPlanner {
+    fn new() -> Self {
+        Self {
+            enabled: false,
+            trajectory: Vec::new(),
+            pattern_since_cycle: 0,
+            changes: 0,
+            visited: Vec::new(),
+        }
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn reset(&mut self, pattern_id: &str) {
+        self.trajectory.clear();
+        self.pattern_since_cycle = 0;
+        self.changes = 0;
+        self.visited = vec![pattern_id.to_string()];
+    }
+
+    /// Record the belief at a cycle boundary and propose a change if a rule fires
+    fn observe_cycle(
+        &mut self,
+        belief: &FfiBeliefState,
+        current_pattern: &str,
+        local_hour: u8,
+        cycle: u64,
+        timestamp_us: i64,
+    ) -> Option<FfiPlanChange> {
+        let mut p = [0.0; 5];
+        for (slot, v) in p.iter_mut().zip(&belief.probabilities) {
+            *slot = *v;
+        }
+        self.trajectory.push(p);
+        if self.trajectory.len() > 8 {
+            self.trajectory.remove(0);
+        }
+
+        if !self.enabled
+            || belief.confidence < ARC_MIN_CONFIDENCE
+            || self.changes >= ARC_MAX_CHANGES_PER_SESSION
+            || cycle.saturating_sub(self.pattern_since_cycle) < ARC_MIN_DWELL_CYCLES
+            || self.trajectory.len() < ARC_TRIGGER_CYCLES
+        {
+            return None;
+        }
+
+        let window = &self.trajectory[self.trajectory.len() - ARC_TRIGGER_CYCLES..];
+        ARC_RULES.iter().find_map(|rule| {
+            let idx = rule.mode as usize;
+            let sustained = window.iter().all(|p| p[idx] >= rule.min_probability);
+            let rising = window[window.len() - 1][idx] >= window[0][idx];
+            let allowed = rule.target != current_pattern
+                && !self.visited.iter().any(|v| v == rule.target)
+                && Self::is_safe_target(rule.target);
+            if !(sustained && rising && allowed && rule.matches_hour(local_hour)) {
+                return None;
+            }
+            Some(FfiPlanChange {
+                from_pattern: current_pattern.to_string(),
+                to_pattern: rule.target.to_string(),
+                trigger_mode: rule.mode,
+                trigger_probability: window[window.len() - 1][idx],
+                local_hour,
+                cycle,
+                reason: rule.reason.to_string(),
+                timestamp_us,
+            })
+        })
+    }
+
+    /// Never auto-switch into advanced or strongly activating patterns
+    fn is_safe_target(pattern_id: &str) -> bool {
+        PATTERN_METADATA
+            .iter()
+            .find(|m| m.id == pattern_id)
+            .is_some_and(|m| m.complexity <= 2 && m.arousal <= 0.5)
+    }
+
+    fn commit(&mut self, change: &FfiPlanChange) {
+        self.changes += 1;
+        self.pattern_since_cycle = change.cycle;
+        self.visited.push(change.to_pattern.clone());
+        self.trajectory.clear();
+    }
+}
+
+// ============================================================================
+// GROUP SYNC - SHARED-CLOCK BREATHING
+// ============================================================================
+//
+// A leader multicasts beacons carrying its pattern and position on the session
+// timeline. Followers discover the leader from those beacons, measure the round
+// trip with ping/pong, and steer their phase machine onto the leader's timeline.
+// Only relative times are exchanged, so wall clocks need not agree.
+
+#[cfg(feature = "full")]
+const GROUP_SYNC_MULTICAST: Ipv4Addr = Ipv4Addr::new(239, 255, 42, 98);
+#[cfg(feature = "full")]
+const GROUP_SYNC_PORT: u16 = 47_474;
+#[cfg(feature = "full")]
+const GROUP_BEACON_INTERVAL: Duration = Duration::from_millis(500);
+#[cfg(feature = "full")]
+const GROUP_PING_INTERVAL: Duration = Duration::from_secs(2);
+#[cfg(feature = "full")]
+const GROUP_PEER_TIMEOUT: Duration = Duration::from_secs(5);
+/// Errors above this jump straight to the leader (late join, long stall)
+#[cfg(feature = "full")]
+const GROUP_RESEED_THRESHOLD_US: i64 = 2_000_000;
+/// Residual error considered "in sync"
+#[cfg(feature = "full")]
+const GROUP_LOCK_TOLERANCE_US: i64 = 100_000;
+/// Maximum fraction of a tick added or removed for drift correction
+#[cfg(feature = "full")]
+const GROUP_MAX_SLEW: f32 = 0.2;
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiGroupRole {
+    Leader,
+    Follower,
+}
+
+/// Group sync status
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiGroupStatus {
+    pub group_id: String,
+    pub role: FfiGroupRole,
+    /// Leader: followers seen recently. Follower: 1 once the leader is found
+    pub peer_count: u32,
+    pub leader_pattern_id: Option<String>,
+    /// Follower residual error against the leader timeline
+    pub offset_ms: f32,
+    pub rtt_ms: Option<f32>,
+    pub locked: bool,
+    /// Follower: leader pattern missing from the local library (not following)
+    pub missing_pattern_id: Option<String>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum SyncMessage {
+    Beacon {
+        group_id: String,
+        leader_id: String,
+        pattern_id: String,
+        running: bool,
+        timeline_us: i64,
+    },
+    Ping {
+        group_id: String,
+        peer_id: String,
+        t0_us: i64,
+    },
+    Pong {
+        group_id: String,
+        peer_id: String,
+        t0_us: i64,
+        running: bool,
+        timeline_us: i64,
+    },
+}
+
+/// A session timeline position observed at a local instant
+#[cfg(feature = "full")]
+#[derive(Clone)]
+struct TimelineAnchor {
+    pattern_id: String,
+    running: bool,
+    timeline_us: i64,
+    at: Instant,
+}
+
+#[cfg(feature = "full")]
+impl TimelineAnchor {
+    fn now_us(&self) -> i64 {
+        if self.running {
+            self.timeline_us + self.at.elapsed().as_micros() as i64
+        } else {
+            self.timeline_us
+        }
+    }
+}
+
+/// Where the follower should be right now
+#[cfg(feature = "full")]
+struct LeaderTarget {
+    pattern_id: String,
+    running: bool,
+    timeline_us: i64,
+}
+
+#[cfg(feature = "full")]
+struct GroupSession {
+    group_id: String,
+    peer_id: String,
+    role: FfiGroupRole,
+    clock: Instant,
+    /// This device's own timeline, published by the runtime actor
+    local: Option<TimelineAnchor>,
+    /// Leader timeline as estimated locally (followers)
+    leader: Option<TimelineAnchor>,
+    leader_addr: Option<SocketAddr>,
+    /// Followers by id with last contact (leaders)
+    peers: HashMap<String, Instant>,
+    rtt_us: Option<i64>,
+    error_us: i64,
+    /// Leader pattern the follower cannot load
+    missing_pattern: Option<String>,
+}
+
+/// LAN group session: one leader, any number of followers
+#[cfg(feature = "full")]
+pub struct GroupSync {
+    inner: Arc<Mutex<Option<GroupSession>>>,
+    stop: Arc<AtomicBool>,
+    thread: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+#[cfg(feature = "full")]
+impl Default for GroupSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl GroupSync {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(None)),
+            stop: Arc::new(AtomicBool::new(false)),
+            thread: Mutex::new(None),
+        }
+    }
+
+    fn start(&self, group_id: String, role: FfiGroupRole) -> Result<(), ZenOneError> {
+        if group_id.is_empty() {
+            return Err(ZenOneError::ConfigError("Group id must not be empty".into()));
+        }
+        self.stop();
+
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, GROUP_SYNC_PORT))
+            .map_err(|e| ZenOneError::ConfigError(format!("Failed to bind group socket: {}", e)))?;
+        socket
+            .join_multicast_v4(&GROUP_SYNC_MULTICAST, &Ipv4Addr::UNSPECIFIED)
+            .map_err(|e| ZenOneError::ConfigError(format!("Failed to join multicast group: {}", e)))?;
+        socket
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .map_err(|e| ZenOneError::ConfigError(format!("Failed to configure group socket: {}", e)))?;
+
+        *self.inner.lock() = Some(GroupSession {
+            group_id,
+            peer_id: format!("{:016x}", rand::random::<u64>()),
+            role,
+            clock: Instant::now(),
+            local: None,
+            leader: None,
+            leader_addr: None,
+            peers: HashMap::new(),
+            rtt_us: None,
+            error_us: 0,
+            missing_pattern: None,
+        });
+
+        self.stop.store(false, Ordering::SeqCst);
+        let inner = self.inner.clone();
+        let stop = self.stop.clone();
+        *self.thread.lock() = Some(thread::spawn(move || run_group_sync(socket, inner, stop)));
+        Ok(())
+    }
+
+    fn stop(&self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.thread.lock().take() {
+            let _ = handle.join();
+        }
+        *self.inner.lock() = None;
+    }
+
+    fn status(&self) -> Option<FfiGroupStatus> {
+        let guard = self.inner.lock();
+        let session = guard.as_ref()?;
+        let peer_count = match session.role {
+            FfiGroupRole::Leader => session
+                .peers
+                .values()
+                .filter(|seen| seen.elapsed() < GROUP_PEER_TIMEOUT)
+                .count() as u32,
+            FfiGroupRole::Follower => session.leader.is_some() as u32,
+        };
+        Some(FfiGroupStatus {
+            group_id: session.group_id.clone(),
+            role: session.role,
+            peer_count,
+            leader_pattern_id: session.leader.as_ref().map(|l| l.pattern_id.clone()),
+            offset_ms: session.error_us as f32 / 1000.0,
+            rtt_ms: session.rtt_us.map(|rtt| rtt as f32 / 1000.0),
+            locked: match session.role {
+                FfiGroupRole::Leader => true,
+                FfiGroupRole::Follower => {
+                    session.missing_pattern.is_none()
+                        && session.rtt_us.is_some()
+                        && session.error_us.abs() <= GROUP_LOCK_TOLERANCE_US
+                }
+            },
+            missing_pattern_id: session.missing_pattern.clone(),
+        })
+    }
+
+    /// Publish this device's timeline (called by the runtime actor every tick)
+    fn publish(&self, pattern_id: &str, running: bool, timeline_us: i64) {
+        if let Some(session) = self.inner.lock().as_mut() {
+            session.local = Some(TimelineAnchor {
+                pattern_id: pattern_id.to_string(),
+                running,
+                timeline_us,
+                at: Instant::now(),
+            });
+        }
+    }
+
+    /// Leader timeline to converge on, when following a discovered leader
+    fn leader_target(&self) -> Option<LeaderTarget> {
+        let guard = self.inner.lock();
+        let session = guard.as_ref().filter(|s| s.role == FfiGroupRole::Follower)?;
+        let leader = session.leader.as_ref()?;
+        Some(LeaderTarget {
+            pattern_id: leader.pattern_id.clone(),
+            running: leader.running,
+            timeline_us: leader.now_us(),
+        })
+    }
+
+    fn record_error(&self, error_us: i64) {
+        if let Some(session) = self.inner.lock().as_mut() {
+            session.error_us = error_us;
+        }
+    }
+
+    /// Record which leader pattern is missing locally (None once it is
+    /// followed again). Returns true when a new pattern went missing.
+    fn set_missing_pattern(&self, pattern_id: Option<&str>) -> bool {
+        let mut guard = self.inner.lock();
+        let Some(session) = guard.as_mut() else { return false };
+        if session.missing_pattern.as_deref() == pattern_id {
+            return false;
+        }
+        session.missing_pattern = pattern_id.map(str::to_string);
+        pattern_id.is_some()
+    }
+}
+
+#[cfg(feature = "full")]
+impl Drop for GroupSync {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod group_sync_tests {
+    use super::*;
+
+    fn running_session() -> (ZenOneRuntime, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(1_704_096_000_000));
+        let runtime = ZenOneRuntime::with_clock("4-7-8".into(), clock.clone());
+        runtime.set_preparation_config(FfiPreparationConfig { countdown_sec: 0.0, min_signal_quality: 0.0 });
+        runtime.set_hr_source(FfiHrSourceKind::Simulated);
+        runtime.start_session().unwrap();
+        runtime.flush();
+        (runtime, clock)
+    }
+
+    /// Join as a follower of a leader already running `pattern_id`, without the network thread
+    fn follow(group: &GroupSync, pattern_id: &str) {
+        *group.inner.lock() = Some(GroupSession {
+            group_id: "group".into(),
+            peer_id: "follower".into(),
+            role: FfiGroupRole::Follower,
+            clock: Instant::now(),
+            local: None,
+            leader: Some(TimelineAnchor { pattern_id: pattern_id.into(), running: true, timeline_us: 0, at: Instant::now() }),
+            leader_addr: None,
+            peers: HashMap::new(),
+            rtt_us: Some(1_000),
+            error_us: 0,
+            missing_pattern: None,
+        });
+    }
+
+    fn tick(runtime: &ZenOneRuntime, clock: &ManualClock, timestamp_us: i64) {
+        clock.advance(Duration::from_millis(100));
+        runtime.tick(0.1, timestamp_us);
+        runtime.flush();
+    }
+
+    fn unavailable(events: &[FfiRuntimeEvent]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                FfiRuntimeEvent::GroupPatternUnavailable { pattern_id } => Some(pattern_id.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unknown_leader_pattern_stops_following_and_is_reported_once() {
+        let (runtime, clock) = running_session();
+        follow(&runtime.group, "not-installed");
+        tick(&runtime, &clock, 100_000);
+        tick(&runtime, &clock, 200_000);
+
+        assert_eq!(unavailable(&runtime.poll_events()), vec!["not-installed".to_string()]);
+        assert_eq!(runtime.get_state().pattern_id, "4-7-8");
+        let status = runtime.get_group_status().unwrap();
+        assert_eq!(status.missing_pattern_id.as_deref(), Some("not-installed"));
+        assert!(!status.locked);
+    }
+
+    #[test]
+    fn following_resumes_once_the_leader_pattern_is_known() {
+        let (runtime, clock) = running_session();
+        follow(&runtime.group, "not-installed");
+        tick(&runtime, &clock, 100_000);
+        if let Some(session) = runtime.group.inner.lock().as_mut() {
+            session.leader.as_mut().unwrap().pattern_id = "calm".into();
+        }
+        tick(&runtime, &clock, 200_000);
+
+        assert_eq!(runtime.get_state().pattern_id, "calm");
+        assert_eq!(runtime.get_group_status().unwrap().missing_pattern_id, None);
+    }
+}
+
+#[cfg(feature = "full")]
+fn run_group_sync(socket: UdpSocket, inner: Arc<Mutex<Option<GroupSession>>>, stop: Arc<AtomicBool>) {
+    log::info!("GroupSync: Thread started");
+    let multicast = SocketAddr::from((GROUP_SYNC_MULTICAST, GROUP_SYNC_PORT));
+    let mut last_beacon: Option<Instant> = None;
+    let mut last_ping: Option<Instant> = None;
+    let mut buf = [0u8; 1024];
+
+    while !stop.load(Ordering::SeqCst) {
+        // Outbound: leader beacons, follower pings
+        let outbound = {
+            let guard = inner.lock();
+            let Some(session) = guard.as_ref() else { break };
+            match session.role {
+                FfiGroupRole::Leader if is_due(last_beacon, GROUP_BEACON_INTERVAL) => {
+                    last_beacon = Some(Instant::now());
+                    session.local.as_ref().map(|local| {
+                        let msg = SyncMessage::Beacon {
+                            group_id: session.group_id.clone(),
+                            leader_id: session.peer_id.clone(),
+                            pattern_id: local.pattern_id.clone(),
+                            running: local.running,
+                            timeline_us: local.now_us(),
+                        };
+                        (msg, multicast)
+                    })
+                }
+                FfiGroupRole::Follower if is_due(last_ping, GROUP_PING_INTERVAL) => {
+                    session.leader_addr.map(|addr| {
+                        last_ping = Some(Instant::now());
+                        let msg = SyncMessage::Ping {
+                            group_id: session.group_id.clone(),
+                            peer_id: session.peer_id.clone(),
+                            t0_us: session.clock.elapsed().as_micros() as i64,
+                        };
+                        (msg, addr)
+                    })
+                }
+                _ => None,
+            }
+        };
+        if let Some((msg, addr)) = outbound {
+            if let Ok(bytes) = serde_json::to_vec(&msg) {
+                let _ = socket.send_to(&bytes, addr);
+            }
+        }
+
+        // Inbound (times out so the stop flag is observed)
+        let Ok((n, from)) = socket.recv_from(&mut buf) else { continue };
+        let Ok(msg) = serde_json::from_slice::<SyncMessage>(&buf[..n]) else { continue };
+        let reply = {
+            let mut guard = inner.lock();
+            let Some(session) = guard.as_mut() else { break };
+            handle_sync_message(session, msg, from)
+        };
+        if let Some(reply) = reply {
+            if let Ok(bytes) = serde_json::to_vec(&reply) {
+                let _ = socket.send_to(&bytes, from);
+            }
+        }
+    }
+    log::info!("GroupSync: Thread stopped");
+}
+
+#[cfg(feature = "full")]
+fn is_due(last: Option<Instant>, interval: Duration) -> bool {
+    match last {
+        Some(t) => t.elapsed() >= interval,
+        None => true,
+    }
+}
+
+#[cfg(feature = "full")]
+fn handle_sync_message(session: &mut GroupSession, msg: SyncMessage, from: SocketAddr) -> Option<SyncMessage> {
+    match (session.role, msg) {
+        (FfiGroupRole::Follower, SyncMessage::Beacon { group_id, leader_id, pattern_id, running, timeline_us }) => {
+            if group_id != session.group_id || leader_id == session.peer_id {
+                return None;
+            }
+            session.leader_addr = Some(from);
+            // Beacons carry pattern/running; the timeline is refined by pong
+            // once the round trip is known
+            let timeline_us = match &session.leader {
+                Some(leader) if session.rtt_us.is_some() => leader.now_us(),
+                _ => timeline_us,
+            };
+            session.leader = Some(TimelineAnchor { pattern_id, running, timeline_us, at: Instant::now() });
+            None
+        }
+        (FfiGroupRole::Leader, SyncMessage::Ping { group_id, peer_id, t0_us }) => {
+            if group_id != session.group_id {
+                return None;
+            }
+            session.peers.insert(peer_id.clone(), Instant::now());
+            let local = session.local.as_ref()?;
+            Some(SyncMessage::Pong {
+                group_id,
+                peer_id,
+                t0_us,
+                running: local.running,
+                timeline_us: local.now_us(),
+            })
+        }
+        (FfiGroupRole::Follower, SyncMessage::Pong { group_id, peer_id, t0_us, running, timeline_us }) => {
+            if group_id != session.group_id || peer_id != session.peer_id {
+                return None;
+            }
+            let rtt_us = (session.clock.elapsed().as_micros() as i64 - t0_us).max(0);
+            session.rtt_us = Some(match session.rtt_us {
+                Some(prev) => (prev * 7 + rtt_us) / 8,
+                None => rtt_us,
+            });
+            // The leader stamped its timeline roughly half a round trip ago
+            let pattern_id = session
+                .leader
+                .as_ref()
+                .map(|l| l.pattern_id.clone())
+                .unwrap_or_default();
+            session.leader = Some(TimelineAnchor {
+                pattern_id,
+                running,
+                timeline_us: timeline_us + rtt_us / 2,
+                at: Instant::now(),
+            });
+            None
+        }
+        _ => None,
+    }
+}
+
+// ============================================================================
+// WEARABLE HR INGESTION
+// ============================================================================
+
+/// Resampling step for streamed heart rate
+#[cfg(feature = "full")]
+const HR_INTERP_STEP_US: i64 = 1_000_000;
+/// Longest gap bridged by interpolation
+#[cfg(feature = "full")]
+const HR_INTERP_MAX_GAP_US: i64 = 5_000_000;
+/// Confidence reported for interpolated points
+#[cfg(feature = "full")]
+const HR_INTERPOLATED_CONFIDENCE: f32 = 0.5;
+/// Physiological bounds for a single beat-to-beat interval
+#[cfg(feature = "full")]
+const RR_MIN_MS: f32 = 300.0;
+#[cfg(feature = "full")]
+const RR_MAX_MS: f32 = 2000.0;
+
+#[cfg(feature = "full")]
+struct HrPoint {
+    bpm: f32,
+    timestamp_us: i64,
+    interpolated: bool,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct HrBatch {
+    points: Vec<HrPoint>,
+    rr_intervals_ms: Vec<f32>,
+    gap_ms: Option<u32>,
+}
+
+/// Validates streamed HR/RR samples and fills short gaps so downstream logic
+/// sees the same regular series as from the camera pipeline
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct HrIngestor {
+    last: Option<(f32, i64)>,
+}
+
+#[cfg(feature = "full")]
+impl HrIngestor {
+    fn ingest(&mut self, bpm: f32, rr_intervals_ms: &[f32], timestamp_us: i64) -> HrBatch {
+        let mut batch = HrBatch::default();
+        if matches!(self.last, Some((_, last_ts)) if timestamp_us <= last_ts) {
+            return batch; // Out of order or duplicate
+        }
+
+        batch.rr_intervals_ms = rr_intervals_ms
+            .iter()
+            .copied()
+            .filter(|rr| (RR_MIN_MS..=RR_MAX_MS).contains(rr))
+            .collect();
+
+        // Some straps only send RR; derive the rate from them
+        let bpm = if (30.0..=220.0).contains(&bpm) {
+            bpm
+        } else if !batch.rr_intervals_ms.is_empty() {
+            let mean_rr = batch.rr_intervals_ms.iter().sum::<f32>() / batch.rr_intervals_ms.len() as f32;
+            60_000.0 / mean_rr
+        } else {
+            return batch;
+        };
+
+        if let Some((last_bpm, last_ts)) = self.last {
+            let gap = timestamp_us - last_ts;
+            if gap > HR_INTERP_MAX_GAP_US {
+                batch.gap_ms = Some((gap / 1000).min(u32::MAX as i64) as u32);
+            } else if gap > HR_INTERP_STEP_US * 3 / 2 {
+                let mut ts = last_ts + HR_INTERP_STEP_US;
+                while ts < timestamp_us - HR_INTERP_STEP_US / 2 {
+                    let t = (ts - last_ts) as f32 / gap as f32;
+                    batch.points.push(HrPoint {
+                        bpm: last_bpm + (bpm - last_bpm) * t,
+                        timestamp_us: ts,
+                        interpolated: true,
+                    });
+                    ts += HR_INTERP_STEP_US;
+                }
+            }
+        }
+
+        batch.points.push(HrPoint { bpm, timestamp_us, interpolated: false });
+        self.last = Some((bpm, timestamp_us));
+        batch
+    }
+
+    /// Forget the last sample so a new session's first one is not measured
+    /// as a gap from the previous session's last
+    fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+// ============================================================================
+// HEART RATE SOURCES
+// ============================================================================
+
+/// Confidence of a hand-counted pulse: an average over the count, with no beat timing
+#[cfg(feature = "full")]
+const MANUAL_HR_CONFIDENCE: f32 = 0.8;
+
+/// Where the session's heart rate comes from
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FfiHrSourceKind {
+    /// A strap while one is streaming, the camera otherwise
+    #[default]
+    Auto,
+    Camera,
+    /// BLE chest strap or watch, via `ingest_hr_sample`
+    Strap,
+    /// Pulse counted by hand, via `ingest_manual_hr`
+    Manual,
+    /// Synthetic heart rate from `start_hr_simulator` or the headless simulation
+    Simulated,
+}
+
+/// Raw input for one heart rate source
+#[cfg(feature = "full")]
+enum HrInput {
+    Camera { r: f32, g: f32, b: f32, timestamp_us: i64 },
+    Strap { bpm: f32, rr_intervals_ms: Vec<f32>, timestamp_us: i64 },
+    Manual { bpm: f32, timestamp_us: i64 },
+    Simulated { bpm: f32, rr_intervals_ms: Vec<f32>, timestamp_us: i64 },
+}
+
+#[cfg(feature = "full")]
+impl HrInput {
+    fn source(&self) -> FfiHrSourceKind {
+        match self {
+            HrInput::Camera { .. } => FfiHrSourceKind::Camera,
+            HrInput::Strap { .. } => FfiHrSourceKind::Strap,
+            HrInput::Manual { .. } => FfiHrSourceKind::Manual,
+            HrInput::Simulated { .. } => FfiHrSourceKind::Simulated,
+        }
+    }
+
+    fn timestamp_us(&self) -> i64 {
+        match *self {
+            HrInput::Camera { timestamp_us, .. }
+            | HrInput::Strap { timestamp_us, .. }
+            | HrInput::Manual { timestamp_us, .. }
+            | HrInput::Simulated { timestamp_us, .. } => timestamp_us,
+        }
+    }
+}
+
+/// One heart rate value, whatever produced it
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct HrReading {
+    source: FfiHrSourceKind,
+    hr: f32,
+    /// 0-1, see `HeartRateSource`
+    confidence: f32,
+    /// Filled in between real readings rather than measured
+    interpolated: bool,
+    timestamp_us: i64,
+    /// None from sources other than the camera
+    camera: Option<CameraHeartRate>,
+}
+
+/// A heart rate sensor run by the SignalActor. Confidence means the same
+/// for every source: 1 for a beat-accurate measurement, `MANUAL_HR_CONFIDENCE`
+/// for a hand count, `HR_INTERPOLATED_CONFIDENCE` for values bridging a gap,
+/// and the spectral quality index for the camera. Readings under
+/// `HR_SURFACE_MIN_QUALITY` are not shown.
+#[cfg(feature = "full")]
+trait HeartRateSource: Send {
+    /// Readings, beats and gaps produced by one input of this source's kind
+    fn push(&mut self, input: &HrInput, events: &mut Vec<SignalEvent>);
+
+    /// Forget history, e.g. between sessions
+    fn reset(&mut self);
+}
+
+/// rPPG from averaged camera samples
+#[cfg(feature = "full")]
+struct CameraSource {
+    rppg: RppgProcessor,
+    beats: BeatDetector,
+    respiration: PpgRespiration,
+    hr_filter: HrKalmanFilter,
+    quality: SignalQualityAnalyzer,
+    // Last configuration, for rebuilding the processor after a panic
+    method: FfiRppgMethod,
+    window_samples: usize,
+    sample_rate_hz: f32,
+    frame_rate: FrameRateMonitor,
+    // Shared with the runtime so adaptations show up in get_rppg_config
+    config: Arc<Mutex<FfiRppgConfig>>,
+    // Latest pulse spectrum, read by get_ppg_spectrum
+    spectrum: Arc<Mutex<Option<FfiPpgSpectrum>>>,
+    trace: Arc<Mutex<PpgTrace>>,
+    calibration: Option<FfiRppgCalibration>,
+}
+
+#[cfg(feature = "full")]
+impl CameraSource {
+    /// Fresh processors for the current method, window and sample rate
+    fn rebuild_pipeline(&mut self) {
+        self.rppg = RppgProcessor::new(self.method.into(), self.window_samples, self.sample_rate_hz);
+        self.beats = BeatDetector::new(self.sample_rate_hz);
+        self.respiration.reset();
+        self.hr_filter.reset();
+        self.quality = SignalQualityAnalyzer::new(self.sample_rate_hz);
+        *self.spectrum.lock() = None;
+    }
+
+    fn configure(&mut self, window_samples: usize, sample_rate_hz: f32) {
+        self.window_samples = window_samples;
+        self.sample_rate_hz = sample_rate_hz;
+        self.frame_rate.reset();
+        self.rebuild_pipeline();
+    }
+
+    /// Retune to the frame rate actually delivered, keeping the window length
+    /// in seconds. A 24 or 60 fps camera analyzed as 30 fps skews every BPM.
+    fn adapt_sample_rate(&mut self, timestamp_us: i64) {
+        let Some(observed_hz) = self.frame_rate.observe(timestamp_us) else { return };
+        if (observed_hz - self.sample_rate_hz).abs() <= FRAME_RATE_ADAPT_TOLERANCE * self.sample_rate_hz {
+            return;
+        }
+        let window_sec = self.window_samples as f32 / self.sample_rate_hz;
+        log::info!("SignalActor: Frames arrive at {:.1} Hz, not {:.1} Hz; adapting", observed_hz, self.sample_rate_hz);
+        self.sample_rate_hz = observed_hz;
+        self.window_samples = rppg_window_samples(window_sec, observed_hz);
+        self.rebuild_pipeline();
+        self.config.lock().sample_rate_hz = observed_hz;
+    }
+}
+
+#[cfg(feature = "full")]
+impl HeartRateSource for CameraSource {
+    fn push(&mut self, input: &HrInput, events: &mut Vec<SignalEvent>) {
+        let HrInput::Camera { r, g, b, timestamp_us } = *input else { return };
+        if self.config.lock().adapt_sample_rate {
+            self.adapt_sample_rate(timestamp_us);
+        }
+        if let Some(ibi_ms) = self.beats.add_sample(r, g, b, timestamp_us) {
+            events.push(SignalEvent::Beat { ibi_ms, source: FfiHrSourceKind::Camera, timestamp_us });
+        }
+        if let Some(point) = self.beats.trace_at(timestamp_us) {
+            let mut trace = self.trace.lock();
+            if trace.enabled {
+                trace.push(point);
+            }
+        }
+        if let Some(pulse) = self.beats.pulse_at(timestamp_us) {
+            if self.quality.push(pulse, timestamp_us) {
+                *self.spectrum.lock() = self.quality.spectrum();
+            }
+        }
+        if let Some(modulation) = self.beats.take_modulation() {
+            if let Some(value) = self.respiration.push(modulation) {
+                let timestamp_us = modulation.timestamp_us;
+                events.push(SignalEvent::Respiration { value, timestamp_us });
+            }
+        }
+        self.rppg.add_sample(r, g, b);
+        let Some((raw_hr, raw_confidence)) = self.rppg.process() else { return };
+        let (unfiltered_hr, calibrated_conf) = match &self.calibration {
+            Some(c) => c.apply(raw_hr, raw_confidence),
+            None => (raw_hr, raw_confidence),
+        };
+        // The spectral quality index supersedes the processor's
+        // confidence once a full window is available
+        let quality = self.quality.latest();
+        let confidence = quality.map_or(calibrated_conf, |q| q.index);
+        let (hr, filter_confidence) = self.hr_filter.update(unfiltered_hr, confidence, timestamp_us);
+        let reading = HrReading {
+            source: FfiHrSourceKind::Camera,
+            hr,
+            confidence,
+            interpolated: false,
+            timestamp_us,
+            camera: Some(CameraHeartRate { unfiltered_hr, filter_confidence, quality, raw_hr, raw_confidence }),
+        };
+        events.push(SignalEvent::HeartRate { reading, selected: false });
+    }
+
+    fn reset(&mut self) {
+        self.frame_rate.reset();
+        self.rppg.reset();
+        self.beats.reset();
+        self.respiration.reset();
+        self.hr_filter.reset();
+        self.quality.reset();
+        *self.spectrum.lock() = None;
+    }
+}
+
+/// RR intervals carry no timestamps of their own; each takes the sample's
+#[cfg(feature = "full")]
+fn push_rr_beats(rr_intervals_ms: &[f32], source: FfiHrSourceKind, timestamp_us: i64, events: &mut Vec<SignalEvent>) {
+    for &ibi_ms in rr_intervals_ms {
+        events.push(SignalEvent::Beat { ibi_ms, source, timestamp_us });
+    }
+}
+
+/// Streamed heart rate and RR intervals from a wearable
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct StrapSource {
+    ingest: HrIngestor,
+}
+
+#[cfg(feature = "full")]
+impl StrapSource {
+    /// Whether a strap reading arrived recently enough to count as live
+    fn is_streaming(&self, timestamp_us: i64) -> bool {
+        self.ingest.last.is_some_and(|(_, last_ts)| timestamp_us - last_ts <= HR_INTERP_MAX_GAP_US)
+    }
+}
+
+#[cfg(feature = "full")]
+impl HeartRateSource for StrapSource {
+    fn push(&mut self, input: &HrInput, events: &mut Vec<SignalEvent>) {
+        let HrInput::Strap { bpm, rr_intervals_ms, timestamp_us } = input else { return };
+        let batch = self.ingest.ingest(*bpm, rr_intervals_ms, *timestamp_us);
+        if let Some(gap_ms) = batch.gap_ms {
+            events.push(SignalEvent::HeartRateGap { gap_ms, timestamp_us: *timestamp_us });
+        }
+        push_rr_beats(&batch.rr_intervals_ms, FfiHrSourceKind::Strap, *timestamp_us, events);
+        for point in batch.points {
+            let reading = HrReading {
+                source: FfiHrSourceKind::Strap,
+                hr: point.bpm,
+                confidence: if point.interpolated { HR_INTERPOLATED_CONFIDENCE } else { 1.0 },
+                interpolated: point.interpolated,
+                timestamp_us: point.timestamp_us,
+                camera: None,
+            };
+            events.push(SignalEvent::HeartRate { reading, selected: false });
+        }
+    }
+
+    fn reset(&mut self) {
+        self.ingest.reset();
+    }
+}
+
+/// Pulse counted by the user, one reading per count
+#[cfg(feature = "full")]
+struct ManualSource;
+
+#[cfg(feature = "full")]
+impl HeartRateSource for ManualSource {
+    fn push(&mut self, input: &HrInput, events: &mut Vec<SignalEvent>) {
+        let HrInput::Manual { bpm, timestamp_us } = *input else { return };
+        let reading = HrReading {
+            source: FfiHrSourceKind::Manual,
+            hr: bpm,
+            confidence: MANUAL_HR_CONFIDENCE,
+            interpolated: false,
+            timestamp_us,
+            camera: None,
+        };
+        events.push(SignalEvent::HeartRate { reading, selected: false });
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Synthetic heart rate and beats, exact by construction
+#[cfg(feature = "full")]
+struct SimulatedSource;
+
+#[cfg(feature = "full")]
+impl HeartRateSource for SimulatedSource {
+    fn push(&mut self, input: &HrInput, events: &mut Vec<SignalEvent>) {
+        let HrInput::Simulated { bpm, rr_intervals_ms, timestamp_us } = input else { return };
+        push_rr_beats(rr_intervals_ms, FfiHrSourceKind::Simulated, *timestamp_us, events);
+        let reading = HrReading {
+            source: FfiHrSourceKind::Simulated,
+            hr: *bpm,
+            confidence: 1.0,
+            interpolated: false,
+            timestamp_us: *timestamp_us,
+            camera: None,
+        };
+        events.push(SignalEvent::HeartRate { reading, selected: false });
+    }
+
+    fn reset(&mut self) {}
+}
+
+// ============================================================================
+// FRAME BUFFERS - SKIN REGION AVERAGING
+// ============================================================================
+
+/// Pixels averaged per frame at most; larger regions are sampled on a grid
+#[cfg(feature = "full")]
+const FRAME_MAX_SAMPLED_PIXELS: usize = 65_536;
+/// Below this share of skin pixels the whole region is averaged instead
+#[cfg(feature = "full")]
+const FRAME_MIN_SKIN_FRACTION: f32 = 0.05;
+/// Largest sample batch accepted at once (two minutes of 30 fps video)
+#[cfg(feature = "full")]
+const FRAME_BATCH_MAX_SAMPLES: usize = 3600;
+
+/// One averaged camera sample, as passed to `process_frame`
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FfiRgbSample {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub timestamp_us: i64,
+}
+
+/// Memory layout of a camera frame with 8 bits per sample. The YUV 4:2:0
+/// formats are what mobile cameras deliver natively; only the sampled pixels
+/// are converted, never the whole frame.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiPixelFormat {
+    Rgba8,
+    Bgra8,
+    Rgb8,
+    /// Y plane, then interleaved V/U at half resolution (Android camera default)
+    Nv21,
+    /// Y plane, then interleaved U/V at half resolution (iOS biplanar)
+    Nv12,
+    /// Y plane, then U plane, then V plane at half resolution
+    I420,
+}
+
+/// Where one plane of a frame sits in the buffer, as reported by the camera
+/// API (Android `Image.Plane`, iOS `CVPixelBuffer`). Strides are in bytes.
+/// For Nv21/Nv12 the second plane starts at the first chroma byte of its
+/// interleaved pairs (V for Nv21, U for Nv12).
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FfiPlaneLayout {
+    pub offset: u32,
+    pub row_stride: u32,
+    pub pixel_stride: u32,
+}
+
+#[cfg(feature = "full")]
+impl FfiPixelFormat {
+    /// Bytes read per pixel of each plane
+    fn plane_sample_bytes(&self) -> &'static [usize] {
+        match self {
+            FfiPixelFormat::Rgba8 | FfiPixelFormat::Bgra8 => &[4],
+            FfiPixelFormat::Rgb8 => &[3],
+            FfiPixelFormat::Nv21 | FfiPixelFormat::Nv12 => &[1, 2],
+            FfiPixelFormat::I420 => &[1, 1, 1],
+        }
+    }
+
+    /// Width and height of `plane`; chroma planes are half resolution
+    fn plane_size(&self, plane: usize, width: usize, height: usize) -> (usize, usize) {
+        if plane == 0 {
+            (width, height)
+        } else {
+            (width.div_ceil(2), height.div_ceil(2))
+        }
+    }
+
+    /// Tightly packed planes, one after the other
+    fn packed_planes(&self, width: usize, height: usize) -> Vec<FfiPlaneLayout> {
+        let mut offset = 0;
+        self.plane_sample_bytes()
+            .iter()
+            .enumerate()
+            .map(|(plane, &sample)| {
+                let (w, h) = self.plane_size(plane, width, height);
+                let layout = FfiPlaneLayout {
+                    offset: offset as u32,
+                    row_stride: (w * sample) as u32,
+                    pixel_stride: sample as u32,
+                };
+                offset += w * h * sample;
+                layout
+            })
+            .collect()
+    }
+}
+
+/// A camera frame checked by `check_frame`: every plane lies within `bytes`
+#[cfg(feature = "full")]
+pub struct FrameView<'a> {
+    pub bytes: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    pub format: FfiPixelFormat,
+    planes: Vec<FfiPlaneLayout>,
+}
+
+#[cfg(feature = "full")]
+impl FrameView<'_> {
+    /// RGB of pixel (x, y)
+    pub fn rgb_at(&self, x: usize, y: usize) -> [f32; 3] {
+        let at = |plane: usize, x: usize, y: usize| {
+            let layout = &self.planes[plane];
+            layout.offset as usize + y * layout.row_stride as usize + x * layout.pixel_stride as usize
+        };
+        let packed = |order: [usize; 3]| {
+            let i = at(0, x, y);
+            order.map(|c| self.bytes[i + c] as f32)
+        };
+        let (u, v) = match self.format {
+            FfiPixelFormat::Rgba8 => return packed([0, 1, 2]),
+            FfiPixelFormat::Bgra8 => return packed([2, 1, 0]),
+            FfiPixelFormat::Rgb8 => return packed([0, 1, 2]),
+            FfiPixelFormat::Nv21 => {
+                let c = at(1, x / 2, y / 2);
+                (self.bytes[c + 1], self.bytes[c])
+            }
+            FfiPixelFormat::Nv12 => {
+                let c = at(1, x / 2, y / 2);
+                (self.bytes[c], self.bytes[c + 1])
+            }
+            FfiPixelFormat::I420 => (self.bytes[at(1, x / 2, y / 2)], self.bytes[at(2, x / 2, y / 2)]),
+        };
+        yuv_to_rgb(self.bytes[at(0, x, y)], u, v)
+    }
+}
+
+/// Full-range BT.601 (JFIF), as camera YUV is delivered
+#[cfg(feature = "full")]
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [f32; 3] {
+    let (y, cb, cr) = (y as f32, u as f32 - 128.0, v as f32 - 128.0);
+    [y + 1.402 * cr, y - 0.344_136 * cb - 0.714_136 * cr, y + 1.772 * cb].map(|c| c.clamp(0.0, 255.0))
+}
+
+#[cfg(all(test, feature = "full"))]
+mod frame_tests {
+    use super::*;
+
+    /// 4x2 frame: luma 100 everywhere, chroma (90, 200) in the left 2x2
+    /// block and (128, 128) in the right one
+    fn planes_4x2() -> (Vec<u8>, [u8; 2], [u8; 2]) {
+        (vec![100; 8], [90, 128], [200, 128])
+    }
+
+    fn nv(y: &[u8], first: [u8; 2], second: [u8; 2]) -> Vec<u8> {
+        let mut bytes = y.to_vec();
+        bytes.extend([first[0], second[0], first[1], second[1]]);
+        bytes
+    }
+
+    #[test]
+    fn gray_converts_to_gray() {
+        assert_eq!(yuv_to_rgb(128, 128, 128), [128.0; 3]);
+        assert_eq!(yuv_to_rgb(0, 128, 128), [0.0; 3]);
+        assert_eq!(yuv_to_rgb(255, 255, 255)[0], 255.0);
+    }
+
+    #[test]
+    fn yuv_layouts_agree() {
+        let (y, u, v) = planes_4x2();
+        let nv21 = nv(&y, v, u);
+        let nv12 = nv(&y, u, v);
+        let i420: Vec<u8> = [y.as_slice(), &u, &v].concat();
+        let frames = [
+            check_frame(&nv21, 4, 2, FfiPixelFormat::Nv21, None).unwrap(),
+            check_frame(&nv12, 4, 2, FfiPixelFormat::Nv12, None).unwrap(),
+            check_frame(&i420, 4, 2, FfiPixelFormat::I420, None).unwrap(),
+        ];
+        let tinted = yuv_to_rgb(100, 90, 200);
+        let neutral = yuv_to_rgb(100, 128, 128);
+        for frame in &frames {
+            for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                assert_eq!(frame.rgb_at(x, y), tinted, "{:?} at ({}, {})", frame.format, x, y);
+            }
+            assert_eq!(frame.rgb_at(2, 0), neutral, "{:?}", frame.format);
+            assert_eq!(frame.rgb_at(3, 1), neutral, "{:?}", frame.format);
+        }
+    }
+
+    #[test]
+    fn padded_planes_are_read_through_strides() {
+        // 2x2 Nv12 with rows padded to 4 bytes and chroma after a 2-byte gap
+        let bytes = [100, 100, 0, 0, 100, 100, 0, 0, 0, 0, 90, 200, 0, 0];
+        let planes = vec![
+            FfiPlaneLayout { offset: 0, row_stride: 4, pixel_stride: 1 },
+            FfiPlaneLayout { offset: 10, row_stride: 4, pixel_stride: 2 },
+        ];
+        let frame = check_frame(&bytes, 2, 2, FfiPixelFormat::Nv12, Some(planes)).unwrap();
+        assert_eq!(frame.rgb_at(1, 1), yuv_to_rgb(100, 90, 200));
+    }
+
+    #[test]
+    fn packed_rgb_channel_order() {
+        let rgba = [10, 20, 30, 255];
+        let bgra = [30, 20, 10, 255];
+        let rgb = [10, 20, 30];
+        for (bytes, format) in [
+            (&rgba[..], FfiPixelFormat::Rgba8),
+            (&bgra[..], FfiPixelFormat::Bgra8),
+            (&rgb[..], FfiPixelFormat::Rgb8),
+        ] {
+            let frame = check_frame(bytes, 1, 1, format, None).unwrap();
+            assert_eq!(frame.rgb_at(0, 0), [10.0, 20.0, 30.0], "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn odd_sizes_round_chroma_up() {
+        // 3x3 I420: 9 luma bytes plus two 2x2 chroma planes
+        assert!(check_frame(&[128; 17], 3, 3, FfiPixelFormat::I420, None).is_ok());
+        assert!(check_frame(&[128; 16], 3, 3, FfiPixelFormat::I420, None).is_err());
+    }
+
+    #[test]
+    fn malformed_frames_are_rejected() {
+        assert!(check_frame(&[], 0, 2, FfiPixelFormat::Rgb8, None).is_err());
+        assert!(check_frame(&[0; 13], 2, 2, FfiPixelFormat::Rgb8, None).is_err());
+        assert!(check_frame(&[0; 11], 2, 2, FfiPixelFormat::Rgb8, None).is_err());
+        let narrow = vec![FfiPlaneLayout { offset: 0, row_stride: 4, pixel_stride: 2 }];
+        assert!(check_frame(&[0; 64], 2, 2, FfiPixelFormat::Rgb8, Some(narrow)).is_err());
+        let one_plane = vec![FfiPlaneLayout { offset: 0, row_stride: 2, pixel_stride: 1 }];
+        assert!(check_frame(&[0; 64], 2, 2, FfiPixelFormat::Nv21, Some(one_plane)).is_err());
+        let past_end = vec![
+            FfiPlaneLayout { offset: 0, row_stride: 2, pixel_stride: 1 },
+            FfiPlaneLayout { offset: 6, row_stride: 2, pixel_stride: 2 },
+        ];
+        assert!(check_frame(&[0; 6], 2, 2, FfiPixelFormat::Nv12, Some(past_end)).is_err());
+    }
+}
+
+/// Rectangle of the frame to analyze (typically the face or forehead)
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FfiRoi {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[cfg(feature = "full")]
+impl FfiRoi {
+    fn fits(&self, width: u32, height: u32) -> bool {
+        self.width > 0
+            && self.height > 0
+            && self.x.saturating_add(self.width) <= width
+            && self.y.saturating_add(self.height) <= height
+    }
+}
+
+/// Check a frame against its plane layout. Without `planes` the frame must
+/// be tightly packed; with them, each plane only has to fit in `bytes`, so
+/// padded rows and interleaved chroma can be passed without copying.
+#[cfg(feature = "full")]
+fn check_frame(
+    bytes: &[u8],
+    width: u32,
+    height: u32,
+    format: FfiPixelFormat,
+    planes: Option<Vec<FfiPlaneLayout>>,
+) -> Result<FrameView<'_>, ZenOneError> {
+    let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+    if width == 0 || height == 0 {
+        return invalid(format!("Frame of {}x{} is empty", width, height));
+    }
+    let (w, h) = (width as usize, height as usize);
+    let packed = planes.is_none();
+    let planes = planes.unwrap_or_else(|| format.packed_planes(w, h));
+    let samples = format.plane_sample_bytes();
+    if planes.len() != samples.len() {
+        return invalid(format!("{:?} frames have {} planes, got {}", format, samples.len(), planes.len()));
+    }
+
+    let mut needed = 0u64;
+    for (plane, (layout, &sample)) in planes.iter().zip(samples).enumerate() {
+        let (pw, ph) = format.plane_size(plane, w, h);
+        let (row_stride, pixel_stride) = (layout.row_stride as u64, layout.pixel_stride as u64);
+        let row_len = (pw as u64 - 1) * pixel_stride + sample as u64;
+        if pixel_stride < sample as u64 || row_stride < row_len {
+            return invalid(format!(
+                "Plane {} strides ({} row, {} pixel) are too small for {}x{} {:?}",
+                plane, layout.row_stride, layout.pixel_stride, width, height, format
+            ));
+        }
+        needed = needed.max(layout.offset as u64 + (ph as u64 - 1) * row_stride + row_len);
+    }
+    let len = bytes.len() as u64;
+    if len < needed || (packed && len != needed) {
+        return invalid(format!(
+            "Frame of {}x{} {:?} needs {} bytes, got {}",
+            width, height, format, needed, len
+        ));
+    }
+    Ok(FrameView { bytes, width, height, format, planes })
+}
+
+/// Skin chroma range in YCbCr (Chai & Ngan)
+#[cfg(feature = "full")]
+fn is_skin([r, g, b]: [f32; 3]) -> bool {
+    let cb = 128.0 - 0.168_736 * r - 0.331_264 * g + 0.5 * b;
+    let cr = 128.0 + 0.5 * r - 0.418_688 * g - 0.081_312 * b;
+    (77.0..=127.0).contains(&cb) && (133.0..=173.0).contains(&cr)
+}
+
+/// Mean RGB over the skin pixels of `roi` (the whole frame without one).
+/// Falls back to every pixel of the region when too little looks like skin,
+/// e.g. under strongly colored light.
+#[cfg(feature = "full")]
+fn average_skin_rgb(frame: &FrameView, roi: Option<FfiRoi>) -> Result<[f32; 3], ZenOneError> {
+    let (width, height) = (frame.width, frame.height);
+    let roi = roi.unwrap_or(FfiRoi { x: 0, y: 0, width, height });
+    if !roi.fits(width, height) {
+        return Err(ZenOneError::ConfigError(format!("ROI {:?} is outside the {}x{} frame", roi, width, height)));
+    }
+
+    let area = roi.width as usize * roi.height as usize;
+    let step = ((area as f32 / FRAME_MAX_SAMPLED_PIXELS as f32).sqrt().ceil() as usize).max(1);
+    let (mut skin_sum, mut skin_count) = ([0.0f32; 3], 0usize);
+    let (mut all_sum, mut all_count) = ([0.0f32; 3], 0usize);
+    for y in (roi.y..roi.y + roi.height).step_by(step) {
+        for x in (roi.x..roi.x + roi.width).step_by(step) {
+            let rgb = frame.rgb_at(x as usize, y as usize);
+            let skin = is_skin(rgb);
+            for c in 0..3 {
+                all_sum[c] += rgb[c];
+                if skin {
+                    skin_sum[c] += rgb[c];
+                }
+            }
+            all_count += 1;
+            skin_count += skin as usize;
+        }
+    }
+    let (sum, count) = if skin_count > 0 && skin_count as f32 >= FRAME_MIN_SKIN_FRACTION * all_count as f32 {
+        (skin_sum, skin_count)
+    } else {
+        (all_sum, all_count)
+    };
+    Ok(sum.map(|c| c / count as f32))
+}
+
+/// Keeps the rPPG region on the user's face as they move. Consulted for every
+/// `process_frame_buffer` call that does not pass an explicit ROI; returning
+/// None averages the whole frame. Implement it over an external face
+/// detector, or use the built-in SkinRoiTracker.
+#[cfg(feature = "full")]
+pub trait RoiProvider: Send {
+    fn locate_roi(&mut self, frame: &FrameView) -> Option<FfiRoi>;
+}
+
+/// Grid points checked for skin per frame by SkinRoiTracker
+#[cfg(feature = "full")]
+const SKIN_TRACKER_GRID_POINTS: usize = 4096;
+/// Skin extent taken between these percentiles, ignoring stray skin-colored pixels
+#[cfg(feature = "full")]
+const SKIN_TRACKER_PERCENTILES: (f32, f32) = (0.1, 0.9);
+/// Weight of each new frame in the smoothed region
+#[cfg(feature = "full")]
+const SKIN_TRACKER_SMOOTHING: f32 = 0.3;
+
+/// ROI tracking by skin color alone: the robust extent of skin pixels,
+/// narrowed to its forehead-and-cheeks band and smoothed across frames.
+/// Needs no model, but follows any skin (hands, neck) in view.
+#[cfg(feature = "full")]
+#[derive(Default)]
+pub struct SkinRoiTracker {
+    /// Smoothed region as (x0, y0, x1, y1)
+    region: Option<[f32; 4]>,
+}
+
+#[cfg(feature = "full")]
+impl SkinRoiTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "full")]
+impl RoiProvider for SkinRoiTracker {
+    fn locate_roi(&mut self, frame: &FrameView) -> Option<FfiRoi> {
+        let (width, height) = (frame.width, frame.height);
+        let (w, h) = (width as usize, height as usize);
+        let step = ((w * h) as f32 / SKIN_TRACKER_GRID_POINTS as f32).sqrt().ceil().max(1.0) as usize;
+        let (mut xs, mut ys) = (Vec::new(), Vec::new());
+        for y in (0..h).step_by(step) {
+            for x in (0..w).step_by(step) {
+                if is_skin(frame.rgb_at(x, y)) {
+                    xs.push(x as f32);
+                    ys.push(y as f32);
+                }
+            }
+        }
+        let grid_points = w.div_ceil(step) * h.div_ceil(step);
+        if (xs.len() as f32) < FRAME_MIN_SKIN_FRACTION * grid_points as f32 {
+            return self.region.map(|r| region_to_roi(r, width, height));
+        }
+        let extent = |values: &mut Vec<f32>| {
+            values.sort_unstable_by(f32::total_cmp);
+            let at = |p: f32| values[((values.len() - 1) as f32 * p) as usize];
+            (at(SKIN_TRACKER_PERCENTILES.0), at(SKIN_TRACKER_PERCENTILES.1))
+        };
+        let ((x0, x1), (y0, y1)) = (extent(&mut xs), extent(&mut ys));
+        let (face_w, face_h) = (x1 - x0, y1 - y0);
+        // Middle of the face horizontally, forehead to cheeks vertically:
+        // away from hair, eyes blinking at the edges, mouth and beard
+        let target = [x0 + 0.2 * face_w, y0 + 0.1 * face_h, x1 - 0.2 * face_w, y0 + 0.6 * face_h];
+        let region = match self.region {
+            Some(prev) => std::array::from_fn(|i| prev[i] + (target[i] - prev[i]) * SKIN_TRACKER_SMOOTHING),
+            None => target,
+        };
+        self.region = Some(region);
+        Some(region_to_roi(region, width, height))
+    }
+}
+
+#[cfg(feature = "full")]
+fn region_to_roi([x0, y0, x1, y1]: [f32; 4], width: u32, height: u32) -> FfiRoi {
+    let x = (x0.max(0.0) as u32).min(width - 1);
+    let y = (y0.max(0.0) as u32).min(height - 1);
+    FfiRoi {
+        x,
+        y,
+        width: ((x1 - x0).round().max(1.0) as u32).min(width - x),
+        height: ((y1 - y0).round().max(1.0) as u32).min(height - y),
+    }
+}
+
+// ============================================================================
+// SIGNAL QUALITY - SPECTRAL SNR AND PERFUSION
+// ============================================================================
+
+/// Camera pulse history the quality index is computed over
+#[cfg(feature = "full")]
+const SQI_WINDOW_SEC: f32 = 8.0;
+/// Heart rate band searched for the pulse peak
+#[cfg(feature = "full")]
+const SQI_BAND_HZ: (f32, f32) = (0.7, 4.0);
+#[cfg(feature = "full")]
+const SQI_STEP_HZ: f32 = 0.05;
+/// Half-width around the fundamental and first harmonic counted as signal
+#[cfg(feature = "full")]
+const SQI_PEAK_HALF_WIDTH_HZ: f32 = 0.1;
+/// SNR mapped linearly onto 0-1 between these
+#[cfg(feature = "full")]
+const SQI_SNR_FLOOR_DB: f32 = -6.0;
+#[cfg(feature = "full")]
+const SQI_SNR_GOOD_DB: f32 = 3.0;
+/// Pulse RMS (percent of skin brightness) mapped onto 0-1 between these
+#[cfg(feature = "full")]
+const SQI_PERFUSION_FLOOR: f32 = 0.05;
+#[cfg(feature = "full")]
+const SQI_PERFUSION_GOOD: f32 = 0.2;
+/// Camera heart rate below this quality is not shown at all
+#[cfg(feature = "full")]
+const HR_SURFACE_MIN_QUALITY: f32 = 0.3;
+/// Frames keep showing the last heart rate and quality for this long
+#[cfg(feature = "full")]
+const HR_FRAME_STALE: Duration = Duration::from_secs(5);
+
+/// Quality of the camera pulse over the last few seconds
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct SignalQuality {
+    /// 0-1, combining SNR and perfusion
+    index: f32,
+    /// Power at the pulse fundamental and harmonic vs the rest of the band
+    snr_db: f32,
+    /// Pulse RMS as a percent of skin brightness
+    perfusion_index: f32,
+}
+
+/// Camera pulse power spectrum over the heart rate band, for diagnosing
+/// why heart rate estimation fails (lighting, motion, framing)
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiPpgSpectrum {
+    /// Bin centers, 0.7-4 Hz (42-240 BPM)
+    pub frequencies_hz: Vec<f32>,
+    /// Power per bin, in arbitrary units
+    pub power: Vec<f32>,
+    pub peak_hz: f32,
+    pub peak_bpm: f32,
+    pub snr_db: f32,
+    /// Timestamp of the newest camera sample in the window
+    pub timestamp_us: i64,
+}
+
+/// Spectral SNR (after de Haan & Jeanne) and a perfusion proxy over a
+/// mean-free window of the camera pulse, with the spectrum behind them
+#[cfg(feature = "full")]
+fn signal_quality(pulse: &[f32], sample_rate_hz: f32, timestamp_us: i64) -> Option<(SignalQuality, FfiPpgSpectrum)> {
+    if pulse.len() < 2 || sample_rate_hz <= 0.0 {
+        return None;
+    }
+    let n = pulse.len() as f32;
+    let mean = pulse.iter().sum::<f32>() / n;
+    let rms = (pulse.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n).sqrt();
+    if rms <= f32::EPSILON {
+        return None;
+    }
+    let windowed: Vec<f32> = pulse
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v - mean) * (0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / (n - 1.0)).cos()))
+        .collect();
+    let bins = ((SQI_BAND_HZ.1 - SQI_BAND_HZ.0) / SQI_STEP_HZ).round() as usize + 1;
+    let spectrum: Vec<(f32, f32)> = (0..bins)
+        .map(|k| {
+            let freq = SQI_BAND_HZ.0 + k as f32 * SQI_STEP_HZ;
+            let omega = std::f32::consts::TAU * freq / sample_rate_hz;
+            let (re, im) = windowed.iter().enumerate().fold((0.0f32, 0.0f32), |(re, im), (i, v)| {
+                let angle = omega * i as f32;
+                (re + v * angle.cos(), im - v * angle.sin())
+            });
+            (freq, re * re + im * im)
+        })
+        .collect();
+    let (peak_hz, _) = spectrum.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let near = |freq: f32, center: f32| (freq - center).abs() <= SQI_PEAK_HALF_WIDTH_HZ;
+    let (signal, noise) = spectrum.iter().fold((0.0f32, 0.0f32), |(signal, noise), (freq, power)| {
+        if near(*freq, peak_hz) || near(*freq, 2.0 * peak_hz) {
+            (signal + power, noise)
+        } else {
+            (signal, noise + power)
+        }
+    });
+    let snr_db = if noise > f32::EPSILON { 10.0 * (signal / noise).log10() } else { SQI_SNR_GOOD_DB };
+    let perfusion_index = rms * 100.0;
+    let snr_score = ((snr_db - SQI_SNR_FLOOR_DB) / (SQI_SNR_GOOD_DB - SQI_SNR_FLOOR_DB)).clamp(0.0, 1.0);
+    let perfusion_score =
+        ((perfusion_index - SQI_PERFUSION_FLOOR) / (SQI_PERFUSION_GOOD - SQI_PERFUSION_FLOOR)).clamp(0.0, 1.0);
+    let (frequencies_hz, power) = spectrum.into_iter().unzip();
+    Some((
+        SignalQuality { index: snr_score * perfusion_score, snr_db, perfusion_index },
+        FfiPpgSpectrum { frequencies_hz, power, peak_hz, peak_bpm: peak_hz * 60.0, snr_db, timestamp_us },
+    ))
+}
+
+/// Rolling signal quality over the camera pulse, recomputed once a second
+#[cfg(feature = "full")]
+struct SignalQualityAnalyzer {
+    sample_rate_hz: f32,
+    window_len: usize,
+    pulse: std::collections::VecDeque<f32>,
+    since_update: usize,
+    latest: Option<SignalQuality>,
+    spectrum: Option<FfiPpgSpectrum>,
+}
+
+#[cfg(feature = "full")]
+impl SignalQualityAnalyzer {
+    fn new(sample_rate_hz: f32) -> Self {
+        Self {
+            sample_rate_hz,
+            window_len: ((sample_rate_hz * SQI_WINDOW_SEC) as usize).max(2),
+            pulse: std::collections::VecDeque::new(),
+            since_update: 0,
+            latest: None,
+            spectrum: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.sample_rate_hz);
+    }
+
+    /// Returns true when the quality and spectrum were recomputed
+    fn push(&mut self, value: f32, timestamp_us: i64) -> bool {
+        push_bounded(&mut self.pulse, value, self.window_len);
+        self.since_update += 1;
+        if self.pulse.len() < self.window_len || (self.since_update as f32) < self.sample_rate_hz {
+            return false;
+        }
+        self.since_update = 0;
+        let analysis = signal_quality(self.pulse.make_contiguous(), self.sample_rate_hz, timestamp_us);
+        (self.latest, self.spectrum) = analysis.map_or((None, None), |(quality, spectrum)| (Some(quality), Some(spectrum)));
+        true
+    }
+
+    /// None until a full window has been analyzed
+    fn latest(&self) -> Option<SignalQuality> {
+        self.latest
+    }
+
+    fn spectrum(&self) -> Option<FfiPpgSpectrum> {
+        self.spectrum.clone()
+    }
+}
+
+// ============================================================================
+// PPG TRACE - OPT-IN DEBUG STREAM
+// ============================================================================
+
+/// Trace points held for the shell between drains (a minute at 10 points/s)
+#[cfg(feature = "full")]
+const PPG_TRACE_CAPACITY: usize = 600;
+#[cfg(feature = "full")]
+const PPG_TRACE_MAX_DECIMATION: u32 = 30;
+
+/// One downsampled point of the camera signal
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FfiPpgTracePoint {
+    pub timestamp_us: i64,
+    /// Green channel over its moving mean, minus 1
+    pub green: f32,
+    /// Detrended POS pulse the beat detector runs on
+    pub pulse: f32,
+}
+
+/// Downsamples trace points by averaging `decimation` samples into one.
+/// Oldest points are dropped if the shell stops draining.
+#[cfg(feature = "full")]
+struct PpgTrace {
+    enabled: bool,
+    decimation: u32,
+    sum: (f32, f32),
+    count: u32,
+    points: std::collections::VecDeque<FfiPpgTracePoint>,
+}
+
+#[cfg(feature = "full")]
+impl PpgTrace {
+    fn new() -> Self {
+        Self { enabled: false, decimation: 1, sum: (0.0, 0.0), count: 0, points: std::collections::VecDeque::new() }
+    }
+
+    fn configure(&mut self, enabled: bool, decimation: u32) {
+        *self = Self { enabled, decimation, ..Self::new() };
+    }
+
+    fn push(&mut self, point: FfiPpgTracePoint) {
+        self.sum.0 += point.green;
+        self.sum.1 += point.pulse;
+        self.count += 1;
+        if self.count < self.decimation {
+            return;
+        }
+        let n = self.count as f32;
+        let averaged = FfiPpgTracePoint { timestamp_us: point.timestamp_us, green: self.sum.0 / n, pulse: self.sum.1 / n };
+        push_bounded(&mut self.points, averaged, PPG_TRACE_CAPACITY);
+        self.sum = (0.0, 0.0);
+        self.count = 0;
+    }
+}
+
+// ============================================================================
+// HEART RATE FILTER - KALMAN SMOOTHING OF CAMERA BPM
+// ============================================================================
+
+/// Measurement confidence floor, so a near-zero confidence cannot make the
+/// measurement variance infinite
+#[cfg(feature = "full")]
+const HR_FILTER_MIN_CONFIDENCE: f32 = 0.05;
+/// Gap after which the filter restarts from the next measurement
+#[cfg(feature = "full")]
+const HR_FILTER_RESET_GAP_US: i64 = 5_000_000;
+
+/// Noise model of the camera heart rate filter
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiHrFilterConfig {
+    /// Off passes the calibrated camera BPM through unchanged
+    pub enabled: bool,
+    /// How fast true heart rate can drift, in BPM² per second
+    pub process_noise: f32,
+    /// Variance of a full-confidence camera reading, in BPM²; scaled up as
+    /// reading confidence drops
+    pub measurement_noise: f32,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiHrFilterConfig {
+    fn default() -> Self {
+        Self { enabled: true, process_noise: 2.0, measurement_noise: 16.0 }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiHrFilterConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        for (name, value) in [("process_noise", self.process_noise), ("measurement_noise", self.measurement_noise)] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(ZenOneError::ConfigError(format!("Invalid HR filter {name}: {value}")));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One-dimensional Kalman filter over camera BPM with a random-walk model.
+/// Low-confidence readings are trusted less by inflating their variance.
+#[cfg(feature = "full")]
+struct HrKalmanFilter {
+    config: FfiHrFilterConfig,
+    /// Estimate, its variance and when it was last updated
+    state: Option<(f32, f32, i64)>,
+}
+
+#[cfg(feature = "full")]
+impl HrKalmanFilter {
+    fn new(config: FfiHrFilterConfig) -> Self {
+        Self { config, state: None }
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+
+    fn set_config(&mut self, config: FfiHrFilterConfig) {
+        *self = Self::new(config);
+    }
+
+    /// Filtered BPM and the filter's confidence (0-1) after one reading
+    fn update(&mut self, bpm: f32, confidence: f32, timestamp_us: i64) -> (f32, f32) {
+        if !self.config.enabled {
+            return (bpm, confidence);
+        }
+        let r = self.config.measurement_noise / confidence.max(HR_FILTER_MIN_CONFIDENCE);
+        let (estimate, variance) = match self.state {
+            Some((x, p, last_us)) if (0..HR_FILTER_RESET_GAP_US).contains(&(timestamp_us - last_us)) => {
+                let p = p + self.config.process_noise * (timestamp_us - last_us) as f32 / 1_000_000.0;
+                let gain = p / (p + r);
+                (x + gain * (bpm - x), (1.0 - gain) * p)
+            }
+            _ => (bpm, r),
+        };
+        self.state = Some((estimate, variance, timestamp_us));
+        // 1 with no remaining uncertainty, 0.5 when as uncertain as a full-confidence reading
+        (estimate, self.config.measurement_noise / (self.config.measurement_noise + variance))
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod hr_filter_tests {
+    use super::*;
+
+    fn close(actual: (f32, f32), expected: (f32, f32)) -> bool {
+        (actual.0 - expected.0).abs() < 1e-3 && (actual.1 - expected.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn update_matches_the_hand_computed_kalman_step() {
+        let mut filter = HrKalmanFilter::new(FfiHrFilterConfig::default());
+        // First reading initializes with the measurement variance (16)
+        assert!(close(filter.update(70.0, 1.0, 0), (70.0, 0.5)));
+        // Predict: p = 16 + 2 * 1 s = 18; gain = 18 / 34; p' = 18 * 16 / 34
+        let gain = 18.0 / 34.0;
+        let variance = (1.0 - gain) * 18.0;
+        let step = filter.update(80.0, 1.0, 1_000_000);
+        assert!(close(step, (70.0 + gain * 10.0, 16.0 / (16.0 + variance))), "{:?}", step);
+    }
+
+    #[test]
+    fn low_confidence_readings_move_the_estimate_less() {
+        let mut trusted = HrKalmanFilter::new(FfiHrFilterConfig::default());
+        let mut doubtful = HrKalmanFilter::new(FfiHrFilterConfig::default());
+        trusted.update(70.0, 1.0, 0);
+        doubtful.update(70.0, 1.0, 0);
+        let (trusted_bpm, _) = trusted.update(90.0, 1.0, 1_000_000);
+        let (doubtful_bpm, _) = doubtful.update(90.0, 0.1, 1_000_000);
+        assert!(doubtful_bpm > 70.0 && doubtful_bpm < trusted_bpm);
+        // Zero confidence is floored rather than dividing by zero
+        assert!(doubtful.update(90.0, 0.0, 2_000_000).0.is_finite());
+    }
+
+    #[test]
+    fn gaps_restart_the_filter_and_disabled_passes_through() {
+        let mut filter = HrKalmanFilter::new(FfiHrFilterConfig::default());
+        filter.update(70.0, 1.0, 0);
+        assert_eq!(filter.update(100.0, 1.0, HR_FILTER_RESET_GAP_US).0, 100.0);
+
+        let mut off = HrKalmanFilter::new(FfiHrFilterConfig { enabled: false, ..FfiHrFilterConfig::default() });
+        off.update(70.0, 1.0, 0);
+        assert_eq!(off.update(90.0, 0.3, 1_000_000), (90.0, 0.3));
+    }
+
+    #[test]
+    fn noise_parameters_must_be_positive() {
+        assert!(FfiHrFilterConfig::default().validate().is_ok());
+        for bad in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert!(FfiHrFilterConfig { process_noise: bad, ..FfiHrFilterConfig::default() }.validate().is_err());
+            assert!(FfiHrFilterConfig { measurement_noise: bad, ..FfiHrFilterConfig::default() }.validate().is_err());
+        }
+    }
+}
+
+/// Camera processing details carried with each camera reading
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct CameraHeartRate {
+    /// Calibrated but before the Kalman filter
+    unfiltered_hr: f32,
+    filter_confidence: f32,
+    /// None while the first quality window fills
+    quality: Option<SignalQuality>,
+    /// Before the device calibration was applied
+    raw_hr: f32,
+    raw_confidence: f32,
+}
+
+// ============================================================================
+// HRV - TIME-DOMAIN METRICS
+// ============================================================================
+
+/// Rolling window the live time-domain metrics cover
+#[cfg(feature = "full")]
+const HRV_WINDOW_US: i64 = 60_000_000;
+/// Longer window kept for the live spectrum
+#[cfg(feature = "full")]
+const HRV_SPECTRUM_WINDOW_US: i64 = 120_000_000;
+/// Spacing of live spectrum updates
+#[cfg(feature = "full")]
+const HRV_SPECTRUM_INTERVAL_US: i64 = 5_000_000;
+/// Evenly resampled tachogram rate for spectral analysis
+#[cfg(feature = "full")]
+const HRV_RESAMPLE_HZ: f32 = 4.0;
+/// Welch segment: 64 s at 4 Hz, 1/64 Hz resolution
+#[cfg(feature = "full")]
+const HRV_SEGMENT_LEN: usize = 256;
+/// Band edges (Hz): VLF starts at HRV_TOTAL_BAND.0, LF, HF, and the range
+/// searched for the resonance peak
+#[cfg(feature = "full")]
+const HRV_TOTAL_BAND: (f32, f32) = (0.0033, 0.4);
+#[cfg(feature = "full")]
+const HRV_LF_BAND: (f32, f32) = (0.04, 0.15);
+#[cfg(feature = "full")]
+const HRV_HF_BAND: (f32, f32) = (0.15, 0.4);
+#[cfg(feature = "full")]
+const HRV_PEAK_BAND: (f32, f32) = (0.04, 0.26);
+/// Half-width of the band around the peak counted as coherent power
+#[cfg(feature = "full")]
+const HRV_COHERENCE_HALF_WIDTH_HZ: f32 = 0.016;
+/// Fewest intervals worth reporting metrics for
+#[cfg(feature = "full")]
+const HRV_MIN_BEATS: usize = 10;
+/// An interval further than this from the recent median is treated as a
+/// missed or extra beat and dropped
+#[cfg(feature = "full")]
+const HRV_ARTIFACT_TOLERANCE: f32 = 0.2;
+#[cfg(feature = "full")]
+const HRV_ARTIFACT_REFERENCE: usize = 5;
+/// Camera beats are ignored while a wearable has sent RR intervals this recently
+#[cfg(feature = "full")]
+const HRV_WEARABLE_PRIORITY_US: i64 = 5_000_000;
+/// Shortest spacing between detected camera beats (200 bpm)
+#[cfg(feature = "full")]
+const BEAT_REFRACTORY_US: i64 = 300_000;
+/// Moving-mean window that normalizes the camera channels
+#[cfg(feature = "full")]
+const BEAT_NORMALIZE_SEC: f32 = 1.6;
+/// Moving-mean window removed from the pulse as baseline drift
+#[cfg(feature = "full")]
+const BEAT_DETREND_SEC: f32 = 1.0;
+
+/// Time-domain heart rate variability over a set of inter-beat intervals
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FfiHrvMetrics {
+    /// Root mean square of successive differences
+    pub rmssd_ms: f32,
+    /// Standard deviation of the intervals
+    pub sdnn_ms: f32,
+    /// Percentage of successive differences above 50 ms
+    pub pnn50: f32,
+    /// Intervals the metrics were computed from
+    pub beats: u32,
+}
+
+/// RMSSD, SDNN and pNN50 over `intervals_ms`, None with too few intervals
+#[cfg(feature = "full")]
+fn time_domain_hrv(intervals_ms: &[f32]) -> Option<FfiHrvMetrics> {
+    if intervals_ms.len() < HRV_MIN_BEATS {
+        return None;
+    }
+    let n = intervals_ms.len() as f32;
+    let mean = intervals_ms.iter().sum::<f32>() / n;
+    let variance = intervals_ms.iter().map(|i| (i - mean).powi(2)).sum::<f32>() / (n - 1.0);
+    let diffs: Vec<f32> = intervals_ms.windows(2).map(|w| w[1] - w[0]).collect();
+    let mean_square = diffs.iter().map(|d| d * d).sum::<f32>() / diffs.len() as f32;
+    let over_50 = diffs.iter().filter(|d| d.abs() > 50.0).count();
+    Some(FfiHrvMetrics {
+        rmssd_ms: mean_square.sqrt(),
+        sdnn_ms: variance.sqrt(),
+        pnn50: 100.0 * over_50 as f32 / diffs.len() as f32,
+        beats: intervals_ms.len() as u32,
+    })
+}
+
+/// Frequency-domain HRV from a Welch periodogram of the interval series
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FfiHrvSpectrum {
+    /// Power in 0.04-0.15 Hz
+    pub lf_power_ms2: f32,
+    /// Power in 0.15-0.4 Hz
+    pub hf_power_ms2: f32,
+    pub lf_hf_ratio: f32,
+    /// Strongest frequency in 0.04-0.26 Hz; about 0.1 Hz when breathing at resonance
+    pub peak_frequency_hz: f32,
+    /// Share (0-1) of total power within 0.016 Hz of the peak
+    pub coherence: f32,
+}
+
+/// Welch power spectrum of `intervals_ms`: the tachogram is resampled at
+/// HRV_RESAMPLE_HZ and cut into half-overlapping Hann-windowed segments.
+/// Needs at least one full segment (64 s) of beats.
+#[cfg(feature = "full")]
+fn hrv_spectrum(intervals_ms: &[f32]) -> Option<FfiHrvSpectrum> {
+    // Each interval is placed at the time its beat ends
+    let mut elapsed = 0.0;
+    let points: Vec<(f32, f32)> = intervals_ms
+        .iter()
+        .map(|ibi| {
+            elapsed += ibi / 1000.0;
+            (elapsed, *ibi)
+        })
+        .collect();
+    let (start, end) = (points.first()?.0, points.last()?.0);
+    let len = ((end - start) * HRV_RESAMPLE_HZ) as usize + 1;
+    if len < HRV_SEGMENT_LEN {
+        return None;
+    }
+    let mut samples = Vec::with_capacity(len);
+    let mut j = 0;
+    for k in 0..len {
+        let t = start + k as f32 / HRV_RESAMPLE_HZ;
+        while j + 2 < points.len() && points[j + 1].0 < t {
+            j += 1;
+        }
+        let ((t0, v0), (t1, v1)) = (points[j], points[(j + 1).min(points.len() - 1)]);
+        let frac = if t1 > t0 { ((t - t0) / (t1 - t0)).clamp(0.0, 1.0) } else { 0.0 };
+        samples.push(v0 + (v1 - v0) * frac);
+    }
+
+    let bin_hz = HRV_RESAMPLE_HZ / HRV_SEGMENT_LEN as f32;
+    let bins = (HRV_TOTAL_BAND.1 / bin_hz) as usize + 1;
+    let window: Vec<f32> = (0..HRV_SEGMENT_LEN)
+        .map(|k| 0.5 - 0.5 * (std::f32::consts::TAU * k as f32 / (HRV_SEGMENT_LEN - 1) as f32).cos())
+        .collect();
+    let window_power = window.iter().map(|w| w * w).sum::<f32>();
+    let mut psd = vec![0.0f32; bins];
+    let mut segments = 0;
+    for segment in samples.windows(HRV_SEGMENT_LEN).step_by(HRV_SEGMENT_LEN / 2) {
+        let mean = segment.iter().sum::<f32>() / HRV_SEGMENT_LEN as f32;
+        for (m, power) in psd.iter_mut().enumerate().skip(1) {
+            let (mut re, mut im) = (0.0f32, 0.0f32);
+            for (k, (x, w)) in segment.iter().zip(&window).enumerate() {
+                let angle = std::f32::consts::TAU * (m * k) as f32 / HRV_SEGMENT_LEN as f32;
+                re += (x - mean) * w * angle.cos();
+                im -= (x - mean) * w * angle.sin();
+            }
+            // One-sided density (ms^2/Hz)
+            *power += 2.0 * (re * re + im * im) / (HRV_RESAMPLE_HZ * window_power);
+        }
+        segments += 1;
+    }
+    psd.iter_mut().for_each(|p| *p /= segments as f32);
+
+    let freq = |m: usize| m as f32 * bin_hz;
+    let band_power = |(low, high): (f32, f32)| {
+        psd.iter().enumerate().filter(|(m, _)| freq(*m) >= low && freq(*m) < high).map(|(_, p)| p * bin_hz).sum::<f32>()
+    };
+    let total = band_power(HRV_TOTAL_BAND);
+    if total <= f32::EPSILON {
+        return None;
+    }
+    let (lf, hf) = (band_power(HRV_LF_BAND), band_power(HRV_HF_BAND));
+    let peak = (0..bins)
+        .filter(|m| (HRV_PEAK_BAND.0..HRV_PEAK_BAND.1).contains(&freq(*m)))
+        .max_by(|a, b| psd[*a].total_cmp(&psd[*b]))?;
+    let peak_hz = freq(peak);
+    let coherent = band_power((peak_hz - HRV_COHERENCE_HALF_WIDTH_HZ, peak_hz + HRV_COHERENCE_HALF_WIDTH_HZ + f32::EPSILON));
+    Some(FfiHrvSpectrum {
+        lf_power_ms2: lf,
+        hf_power_ms2: hf,
+        lf_hf_ratio: if hf > f32::EPSILON { lf / hf } else { 0.0 },
+        peak_frequency_hz: peak_hz,
+        coherence: (coherent / total).min(1.0),
+    })
+}
+
+#[cfg(all(test, feature = "full"))]
+mod hrv_spectrum_tests {
+    use super::*;
+
+    /// Intervals whose length oscillates at `hz` around one second
+    fn modulated_intervals(hz: f32, seconds: f32) -> Vec<f32> {
+        let mut t = 0.0;
+        let mut intervals = Vec::new();
+        while t < seconds {
+            let ibi = 1000.0 + 50.0 * (std::f32::consts::TAU * hz * t).sin();
+            t += ibi / 1000.0;
+            intervals.push(ibi);
+        }
+        intervals
+    }
+
+    #[test]
+    fn resonance_breathing_peaks_near_a_tenth_of_a_hertz() {
+        let spectrum = hrv_spectrum(&modulated_intervals(0.1, 180.0)).unwrap();
+        assert!((spectrum.peak_frequency_hz - 0.1).abs() <= 0.016, "{:?}", spectrum);
+        assert!(spectrum.lf_power_ms2 > 10.0 * spectrum.hf_power_ms2, "{:?}", spectrum);
+        assert!(spectrum.coherence > 0.5, "{:?}", spectrum);
+    }
+
+    #[test]
+    fn fast_breathing_shows_up_as_high_frequency_power() {
+        let spectrum = hrv_spectrum(&modulated_intervals(0.25, 180.0)).unwrap();
+        assert!(spectrum.hf_power_ms2 > 10.0 * spectrum.lf_power_ms2, "{:?}", spectrum);
+        assert!(spectrum.lf_hf_ratio < 0.1, "{:?}", spectrum);
+    }
+
+    #[test]
+    fn short_or_flat_series_have_no_spectrum() {
+        assert!(hrv_spectrum(&modulated_intervals(0.1, 30.0)).is_none());
+        assert!(hrv_spectrum(&[1000.0; 120]).is_none());
+        assert!(hrv_spectrum(&[]).is_none());
+    }
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BeatSource {
+    Camera,
+    Wearable,
+}
+
+/// Recent artifact-free intervals from one source. Successive differences
+/// across two sensors are meaningless, so a switch of source starts over.
+#[cfg(feature = "full")]
+struct HrvWindow {
+    beats: std::collections::VecDeque<(i64, f32)>,
+    source: Option<BeatSource>,
+    last_wearable_us: Option<i64>,
+    /// Latest spectrum from the SignalActor and when it was requested
+    spectrum: Option<FfiHrvSpectrum>,
+    last_analysis_us: Option<i64>,
+}
+
+#[cfg(feature = "full")]
+impl HrvWindow {
+    fn new() -> Self {
+        Self {
+            beats: std::collections::VecDeque::new(),
+            source: None,
+            last_wearable_us: None,
+            spectrum: None,
+            last_analysis_us: None,
+        }
+    }
+
+    /// Add an interval ending at `timestamp_us`; returns whether it was kept
+    fn push(&mut self, ibi_ms: f32, timestamp_us: i64, source: BeatSource) -> bool {
+        if !(RR_MIN_MS..=RR_MAX_MS).contains(&ibi_ms) {
+            return false;
+        }
+        match source {
+            BeatSource::Wearable => self.last_wearable_us = Some(timestamp_us),
+            BeatSource::Camera => {
+                let wearable_active = self
+                    .last_wearable_us
+                    .is_some_and(|last| timestamp_us - last < HRV_WEARABLE_PRIORITY_US);
+                if wearable_active {
+                    return false;
+                }
+            }
+        }
+        if self.source != Some(source) {
+            self.beats.clear();
+            self.spectrum = None;
+            self.source = Some(source);
+        }
+        while self.beats.front().is_some_and(|(ts, _)| *ts < timestamp_us - HRV_SPECTRUM_WINDOW_US) {
+            self.beats.pop_front();
+        }
+
+        let mut recent: Vec<f32> = self.beats.iter().rev().take(HRV_ARTIFACT_REFERENCE).map(|(_, i)| *i).collect();
+        if recent.len() == HRV_ARTIFACT_REFERENCE {
+            recent.sort_by(|a, b| a.total_cmp(b));
+            let median = recent[HRV_ARTIFACT_REFERENCE / 2];
+            if (ibi_ms - median).abs() > HRV_ARTIFACT_TOLERANCE * median {
+                return false;
+            }
+        }
+        self.beats.push_back((timestamp_us, ibi_ms));
+        true
+    }
+
+    fn metrics(&self) -> Option<FfiHrvMetrics> {
+        let newest = self.beats.back()?.0;
+        let intervals: Vec<f32> = self
+            .beats
+            .iter()
+            .filter(|(ts, _)| *ts >= newest - HRV_WINDOW_US)
+            .map(|(_, i)| *i)
+            .collect();
+        time_domain_hrv(&intervals)
+    }
+
+    /// Intervals to analyze when a spectrum update is due and a full segment is buffered
+    fn spectrum_due(&mut self, timestamp_us: i64) -> Option<Vec<f32>> {
+        if self.last_analysis_us.is_some_and(|last| timestamp_us - last < HRV_SPECTRUM_INTERVAL_US) {
+            return None;
+        }
+        let span_ms: f32 = self.beats.iter().map(|(_, i)| i).sum();
+        if span_ms / 1000.0 * HRV_RESAMPLE_HZ < HRV_SEGMENT_LEN as f32 {
+            return None;
+        }
+        self.last_analysis_us = Some(timestamp_us);
+        Some(self.beats.iter().map(|(_, i)| *i).collect())
+    }
+}
+
+/// Finds pulse peaks in camera samples, for inter-beat intervals.
+///
+/// Each channel is normalized by its moving mean and projected onto the POS
+/// plane (Wang et al., 2017). The pulse is that projection minus its slow
+/// trend, smoothed over three samples. A beat is a local maximum above half
+/// the recent amplitude, placed between samples by parabolic interpolation.
+#[cfg(feature = "full")]
+struct BeatDetector {
+    normalize_len: usize,
+    detrend_len: usize,
+    rgb: std::collections::VecDeque<[f32; 3]>,
+    projections: std::collections::VecDeque<(f32, f32)>,
+    pulse: std::collections::VecDeque<f32>,
+    /// Last three smoothed pulse values with their timestamps
+    recent: std::collections::VecDeque<(f32, i64)>,
+    /// Slow average of the pulse magnitude
+    amplitude: f32,
+    last_beat_us: Option<i64>,
+    /// Pulse extremes and summed intensity since the last beat
+    beat_peak: f32,
+    beat_trough: f32,
+    beat_intensity: f32,
+    beat_samples: u32,
+    /// Set when a beat closes, taken by take_modulation
+    modulation: Option<PulseModulation>,
+    /// Normalized green and detrended pulse of the newest sample, for tracing
+    last_trace: Option<FfiPpgTracePoint>,
+}
+
+#[cfg(feature = "full")]
+impl BeatDetector {
+    fn new(sample_rate_hz: f32) -> Self {
+        Self {
+            normalize_len: ((sample_rate_hz * BEAT_NORMALIZE_SEC) as usize).max(2),
+            detrend_len: ((sample_rate_hz * BEAT_DETREND_SEC) as usize).max(2),
+            rgb: std::collections::VecDeque::new(),
+            projections: std::collections::VecDeque::new(),
+            pulse: std::collections::VecDeque::new(),
+            recent: std::collections::VecDeque::new(),
+            amplitude: 0.0,
+            last_beat_us: None,
+            beat_peak: f32::MIN,
+            beat_trough: f32::MAX,
+            beat_intensity: 0.0,
+            beat_samples: 0,
+            modulation: None,
+            last_trace: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.rgb.clear();
+        self.projections.clear();
+        self.pulse.clear();
+        self.recent.clear();
+        self.amplitude = 0.0;
+        self.last_beat_us = None;
+        self.modulation = None;
+        self.last_trace = None;
+        self.clear_beat();
+    }
+
+    fn clear_beat(&mut self) {
+        self.beat_peak = f32::MIN;
+        self.beat_trough = f32::MAX;
+        self.beat_intensity = 0.0;
+        self.beat_samples = 0;
+    }
+
+    /// Pulse amplitude and baseline of the last closed beat, once
+    fn take_modulation(&mut self) -> Option<PulseModulation> {
+        self.modulation.take()
+    }
+
+    /// Trace values computed for the sample at `timestamp_us`
+    fn trace_at(&self, timestamp_us: i64) -> Option<FfiPpgTracePoint> {
+        self.last_trace.filter(|point| point.timestamp_us == timestamp_us)
+    }
+
+    /// Detrended pulse value computed for the sample at `timestamp_us`
+    fn pulse_at(&self, timestamp_us: i64) -> Option<f32> {
+        self.recent.back().filter(|(_, ts)| *ts == timestamp_us).map(|(value, _)| *value)
+    }
+
+    /// Feed one camera sample; returns the interval (ms) ending at a newly detected beat
+    fn add_sample(&mut self, r: f32, g: f32, b: f32, timestamp_us: i64) -> Option<f32> {
+        push_bounded(&mut self.rgb, [r, g, b], self.normalize_len);
+        if self.rgb.len() < self.normalize_len {
+            return None;
+        }
+        let mut mean = [0.0f32; 3];
+        for sample in &self.rgb {
+            for c in 0..3 {
+                mean[c] += sample[c] / self.rgb.len() as f32;
+            }
+        }
+        if mean.iter().any(|m| *m <= f32::EPSILON) {
+            return None;
+        }
+        let (rn, gn, bn) = (r / mean[0], g / mean[1], b / mean[2]);
+        push_bounded(&mut self.projections, (gn - bn, gn + bn - 2.0 * rn), self.normalize_len);
+
+        let std = |values: &mut dyn Iterator<Item = f32>| {
+            let values: Vec<f32> = values.collect();
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32).sqrt()
+        };
+        let sd1 = std(&mut self.projections.iter().map(|p| p.0));
+        let sd2 = std(&mut self.projections.iter().map(|p| p.1));
+        let alpha = if sd2 > f32::EPSILON { sd1 / sd2 } else { 0.0 };
+        let (s1, s2) = *self.projections.back()?;
+        push_bounded(&mut self.pulse, s1 + alpha * s2, self.detrend_len);
+        if self.pulse.len() < self.detrend_len {
+            return None;
+        }
+
+        let trend = self.pulse.iter().sum::<f32>() / self.pulse.len() as f32;
+        let n = self.pulse.len();
+        let smoothed = self.pulse.range(n - 3..).sum::<f32>() / 3.0 - trend;
+        self.amplitude += (smoothed.abs() - self.amplitude) * 0.05;
+        self.beat_peak = self.beat_peak.max(smoothed);
+        self.beat_trough = self.beat_trough.min(smoothed);
+        self.beat_intensity += g;
+        self.beat_samples += 1;
+        self.last_trace = Some(FfiPpgTracePoint { timestamp_us, green: gn - 1.0, pulse: smoothed });
+        push_bounded(&mut self.recent, (smoothed, timestamp_us), 3);
+        if self.recent.len() < 3 {
+            return None;
+        }
+
+        let ((y0, t0), (y1, t1), (y2, t2)) = (self.recent[0], self.recent[1], self.recent[2]);
+        if !(y1 > y0 && y1 >= y2 && y1 > 0.5 * self.amplitude) {
+            return None;
+        }
+        let curvature = y0 - 2.0 * y1 + y2;
+        let offset = if curvature.abs() > f32::EPSILON { (0.5 * (y0 - y2) / curvature).clamp(-0.5, 0.5) } else { 0.0 };
+        let half_step = if offset < 0.0 { t1 - t0 } else { t2 - t1 };
+        let beat_us = t1 + (offset * half_step as f32) as i64;
+        if self.last_beat_us.is_some_and(|last| beat_us - last < BEAT_REFRACTORY_US) {
+            return None;
+        }
+        let ibi_ms = self.last_beat_us.map(|last| (beat_us - last) as f32 / 1000.0);
+        if self.last_beat_us.is_some() && self.beat_samples > 0 {
+            self.modulation = Some(PulseModulation {
+                amplitude: self.beat_peak - self.beat_trough,
+                baseline: self.beat_intensity / self.beat_samples as f32,
+                timestamp_us: beat_us,
+            });
+        }
+        self.clear_beat();
+        self.last_beat_us = Some(beat_us);
+        ibi_ms.filter(|ibi| (RR_MIN_MS..=RR_MAX_MS).contains(ibi))
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod hrv_tests {
+    use super::*;
+
+    #[test]
+    fn alternating_intervals_give_known_metrics() {
+        let intervals: Vec<f32> = (0..10).map(|i| if i % 2 == 0 { 800.0 } else { 900.0 }).collect();
+        let hrv = time_domain_hrv(&intervals).unwrap();
+        assert!((hrv.rmssd_ms - 100.0).abs() < 1e-3);
+        // Sample SD of five 800s and five 900s: 50 * sqrt(10 / 9)
+        assert!((hrv.sdnn_ms - 52.705).abs() < 1e-2, "{}", hrv.sdnn_ms);
+        assert_eq!(hrv.pnn50, 100.0);
+        assert_eq!(hrv.beats, 10);
+    }
+
+    #[test]
+    fn steady_rhythm_has_no_variability() {
+        let hrv = time_domain_hrv(&[1000.0; 12]).unwrap();
+        assert_eq!((hrv.rmssd_ms, hrv.sdnn_ms, hrv.pnn50), (0.0, 0.0, 0.0));
+        assert!(time_domain_hrv(&[1000.0; HRV_MIN_BEATS - 1]).is_none());
+    }
+
+    #[test]
+    fn window_drops_artifacts_and_out_of_range_intervals() {
+        let mut window = HrvWindow::new();
+        let mut t = 0;
+        for _ in 0..HRV_ARTIFACT_REFERENCE {
+            t += 800_000;
+            assert!(window.push(800.0, t, BeatSource::Camera));
+        }
+        // A missed beat doubles the interval
+        assert!(!window.push(1600.0, t + 1_600_000, BeatSource::Camera));
+        assert!(!window.push(RR_MAX_MS + 1.0, t + 3_000_000, BeatSource::Camera));
+        assert!(window.push(820.0, t + 820_000, BeatSource::Camera));
+    }
+
+    #[test]
+    fn wearable_intervals_take_priority_over_the_camera() {
+        let mut window = HrvWindow::new();
+        assert!(window.push(800.0, 1_000_000, BeatSource::Camera));
+        assert!(window.push(810.0, 2_000_000, BeatSource::Wearable));
+        assert_eq!(window.beats.len(), 1, "switching source starts a new window");
+        assert!(!window.push(800.0, 3_000_000, BeatSource::Camera));
+        assert!(window.push(800.0, 2_000_000 + HRV_WEARABLE_PRIORITY_US, BeatSource::Camera));
+    }
+
+    #[test]
+    fn detector_finds_beats_in_a_synthetic_pulse() {
+        // 72 bpm pulse in the green channel at 30 fps
+        let fps = 30.0;
+        let mut detector = BeatDetector::new(fps);
+        let mut intervals = Vec::new();
+        for i in 0..(fps as usize * 20) {
+            let t = i as f32 / fps;
+            let pulse = (2.0 * std::f32::consts::PI * 1.2 * t).sin();
+            let timestamp_us = (t * 1e6) as i64;
+            if let Some(ibi) = detector.add_sample(100.0 - 0.2 * pulse, 100.0 + pulse, 100.0 - 0.1 * pulse, timestamp_us) {
+                intervals.push(ibi);
+            }
+        }
+        assert!(intervals.len() >= 15, "{:?}", intervals);
+        for ibi in &intervals[2..] {
+            assert!((ibi - 833.3).abs() < 40.0, "{:?}", intervals);
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+fn push_bounded<T>(queue: &mut std::collections::VecDeque<T>, value: T, len: usize) {
+    queue.push_back(value);
+    while queue.len() > len {
+        queue.pop_front();
+    }
+}
+
+// ============================================================================
+// RESPIRATION - FROM THE CAMERA PULSE WAVEFORM
+// ============================================================================
+
+/// Weight of each new beat in the running mean and spread of the modulation
+/// components (about 30 beats, several breaths)
+#[cfg(feature = "full")]
+const PPG_RESP_SMOOTHING: f32 = 0.03;
+/// Beats before the running statistics are trusted
+#[cfg(feature = "full")]
+const PPG_RESP_WARMUP_BEATS: u32 = 8;
+
+/// Per-beat features of the camera pulse that breathing modulates
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy)]
+struct PulseModulation {
+    /// Pulse peak-to-trough size (amplitude modulation)
+    amplitude: f32,
+    /// Mean green intensity over the beat (baseline wander)
+    baseline: f32,
+    timestamp_us: i64,
+}
+
+/// Running mean and mean absolute deviation of one modulation component
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct RunningScale {
+    mean: f32,
+    spread: f32,
+}
+
+#[cfg(feature = "full")]
+impl RunningScale {
+    fn standardize(&mut self, value: f32, first: bool) -> f32 {
+        if first {
+            self.mean = value;
+            self.spread = 0.0;
+            return 0.0;
+        }
+        self.mean += (value - self.mean) * PPG_RESP_SMOOTHING;
+        self.spread += ((value - self.mean).abs() - self.spread) * PPG_RESP_SMOOTHING;
+        if self.spread > f32::EPSILON { (value - self.mean) / self.spread } else { 0.0 }
+    }
+}
+
+/// Turns per-beat pulse modulation into a respiration signal that rises on
+/// inhale, one sample per beat.
+///
+/// Inhaling pulls venous blood out of the face, so reflected intensity (the
+/// baseline) rises, while the pulse amplitude shrinks. Each component is
+/// standardized by its running spread so neither dominates, then combined.
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct PpgRespiration {
+    amplitude: RunningScale,
+    baseline: RunningScale,
+    beats: u32,
+}
+
+#[cfg(feature = "full")]
+impl PpgRespiration {
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn push(&mut self, modulation: PulseModulation) -> Option<f32> {
+        if !modulation.amplitude.is_finite() || !modulation.baseline.is_finite() {
+            return None;
+        }
+        let first = self.beats == 0;
+        let amplitude = self.amplitude.standardize(modulation.amplitude, first);
+        let baseline = self.baseline.standardize(modulation.baseline, first);
+        self.beats += 1;
+        (self.beats > PPG_RESP_WARMUP_BEATS).then_some(baseline - amplitude)
+    }
+}
+
+// ============================================================================
+// RESONANCE - BREATH AND HEART COUPLING
+// ============================================================================
+
+/// Pacer cycles the phase locking value is computed over
+#[cfg(feature = "full")]
+const RESONANCE_PLV_CYCLES: usize = 6;
+/// Fewest cycles before phase locking is reported
+#[cfg(feature = "full")]
+const RESONANCE_PLV_MIN_CYCLES: usize = 3;
+/// Fewest heart rate samples for a cycle to count
+#[cfg(feature = "full")]
+const RESONANCE_MIN_CYCLE_SAMPLES: usize = 4;
+/// Per-cycle RSA swings above this are treated as artifacts
+#[cfg(feature = "full")]
+const RSA_MAX_AMPLITUDE_BPM: f32 = 40.0;
+/// Breathing this far off the pacer rate (as a fraction of it) scores 0 alignment
+#[cfg(feature = "full")]
+const RESONANCE_RATE_TOLERANCE: f32 = 0.5;
+
+/// Tracks how consistently heart rate oscillates with the breath cycle.
+///
+/// Each pacer cycle, the heart rate samples are projected onto the cycle's
+/// fundamental (one oscillation per breath) to get the phase of the RSA
+/// swing relative to the breath. The phase locking value is the length of
+/// the mean unit phasor over recent cycles: 1 when heart rate peaks at the
+/// same point of every breath, near 0 when it drifts.
+#[cfg(feature = "full")]
+struct ResonanceTracker {
+    /// Heart rate with the pacer position (0-1) in the running cycle
+    cycle_samples: Vec<(f32, f32)>,
+    cycle_phases: std::collections::VecDeque<f32>,
+    /// Heart rate swing of the last complete cycle
+    rsa_amplitude_bpm: f32,
+}
+
+#[cfg(feature = "full")]
+impl ResonanceTracker {
+    fn new() -> Self {
+        Self {
+            cycle_samples: Vec::new(),
+            cycle_phases: std::collections::VecDeque::new(),
+            rsa_amplitude_bpm: 0.0,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn observe_heart_rate(&mut self, hr: f32, cycle_progress: f32) {
+        self.cycle_samples.push((hr, cycle_progress));
+    }
+
+    /// Close the running cycle and record its RSA amplitude and heart rate phase
+    fn finish_cycle(&mut self) {
+        let samples = std::mem::take(&mut self.cycle_samples);
+        if samples.len() < RESONANCE_MIN_CYCLE_SAMPLES {
+            return;
+        }
+        let (min, max) = samples
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), (hr, _)| (min.min(*hr), max.max(*hr)));
+        if max - min <= RSA_MAX_AMPLITUDE_BPM {
+            self.rsa_amplitude_bpm = max - min;
+        }
+        let mean = samples.iter().map(|(hr, _)| hr).sum::<f32>() / samples.len() as f32;
+        let (re, im) = samples.iter().fold((0.0f32, 0.0f32), |(re, im), (hr, progress)| {
+            let angle = std::f32::consts::TAU * progress;
+            (re + (hr - mean) * angle.cos(), im - (hr - mean) * angle.sin())
+        });
+        if re.hypot(im) <= f32::EPSILON {
+            return;
+        }
+        push_bounded(&mut self.cycle_phases, im.atan2(re), RESONANCE_PLV_CYCLES);
+    }
+
+    fn phase_locking(&self) -> f32 {
+        if self.cycle_phases.len() < RESONANCE_PLV_MIN_CYCLES {
+            return 0.0;
+        }
+        let n = self.cycle_phases.len() as f32;
+        let re = self.cycle_phases.iter().map(|p| p.cos()).sum::<f32>() / n;
+        let im = self.cycle_phases.iter().map(|p| p.sin()).sum::<f32>() / n;
+        re.hypot(im)
+    }
+}
+
+/// 1 when breathing at the pacer rate, falling linearly to 0 at
+/// RESONANCE_RATE_TOLERANCE off it
+#[cfg(feature = "full")]
+fn rhythm_alignment(detected_bpm: Option<f32>, pacer_bpm: Option<f32>) -> f32 {
+    match (detected_bpm, pacer_bpm) {
+        (Some(detected), Some(pacer)) if pacer > 0.0 => {
+            (1.0 - (detected - pacer).abs() / (pacer * RESONANCE_RATE_TOLERANCE)).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    }
+}
+
+// ============================================================================
+// INTERRUPTIONS - SMART RESUME
+// ============================================================================
+
+/// Pauses up to this long resume where they left off
+#[cfg(feature = "full")]
+const RESUME_MID_CYCLE_MAX: Duration = Duration::from_secs(30);
+/// Pauses up to this long restart the current cycle; longer ones end the session
+#[cfg(feature = "full")]
+const RESUME_RESTART_CYCLE_MAX: Duration = Duration::from_secs(5 * 60);
+
+/// What interrupted a session
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiInterruptionKind {
+    UserPause,
+    PhoneCall,
+    AppBackgrounded,
+    /// Safety pause: HR stayed in an elevated zone during a calming pattern
+    ElevatedHeartRate,
+    Other,
+}
+
+/// How a session continued after an interruption
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiResumeAction {
+    ResumeMidCycle,
+    RestartCycle,
+    Finalize,
+}
+
+/// A single interruption within a session
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiInterruption {
+    pub kind: FfiInterruptionKind,
+    pub started_at_ms: i64,
+    pub duration_ms: u64,
+    /// None if the session ended while still interrupted
+    pub resume_action: Option<FfiResumeAction>,
+}
+
+#[cfg(feature = "full")]
+struct ResumePolicy;
+
+#[cfg(feature = "full")]
+impl ResumePolicy {
+    fn decide(gap: Duration) -> FfiResumeAction {
+        if gap <= RESUME_MID_CYCLE_MAX {
+            FfiResumeAction::ResumeMidCycle
+        } else if gap <= RESUME_RESTART_CYCLE_MAX {
+            FfiResumeAction::RestartCycle
+        } else {
+            FfiResumeAction::Finalize
+        }
+    }
+}
+
+// ============================================================================
+// SESSION PREPARATION - COUNTDOWN AND SIGNAL WARM-UP
+// ============================================================================
+
+/// Pre-session countdown configuration. Off by default, so existing callers
+/// go straight to Running as before.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FfiPreparationConfig {
+    /// Countdown length; 0 (the default) starts sessions immediately
+    pub countdown_sec: f32,
+    /// Smoothed signal quality required before Running; 0 disables the gate
+    pub min_signal_quality: f32,
+}
+
+/// Countdown progress
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiPreparationStatus {
+    pub remaining_sec: f32,
+    pub signal_quality: f32,
+    pub quality_ok: bool,
+}
+
+#[cfg(feature = "full")]
+struct Preparation {
+    config: FfiPreparationConfig,
+    elapsed_sec: f32,
+    quality: f32,
+    /// Last countdown second announced
+    announced: Option<u32>,
+}
+
+#[cfg(feature = "full")]
+impl Preparation {
+    fn new(config: FfiPreparationConfig) -> Self {
+        Self { config, elapsed_sec: 0.0, quality: 0.0, announced: None }
+    }
+
+    fn advance(&mut self, dt_sec: f32) {
+        self.elapsed_sec += dt_sec;
+    }
+
+    fn observe_quality(&mut self, confidence: f32) {
+        self.quality = 0.7 * self.quality + 0.3 * confidence.clamp(0.0, 1.0);
+    }
+
+    fn quality_ok(&self) -> bool {
+        self.quality >= self.config.min_signal_quality
+    }
+
+    fn remaining_sec(&self) -> f32 {
+        (self.config.countdown_sec - self.elapsed_sec).max(0.0)
+    }
+
+    /// Countdown fraction elapsed, 0-1
+    fn progress(&self) -> f32 {
+        if self.config.countdown_sec <= 0.0 {
+            return 1.0;
+        }
+        (self.elapsed_sec / self.config.countdown_sec).clamp(0.0, 1.0)
+    }
+
+    /// Whole seconds left, as shown in a 3-2-1 countdown
+    fn seconds_left(&self) -> u32 {
+        self.remaining_sec().ceil() as u32
+    }
+
+    /// The countdown second to announce, once per second until it reaches 0
+    fn next_count(&mut self) -> Option<u32> {
+        let seconds_left = self.seconds_left();
+        if seconds_left == 0 || self.announced == Some(seconds_left) {
+            return None;
+        }
+        self.announced = Some(seconds_left);
+        Some(seconds_left)
+    }
+
+    fn is_ready(&self) -> bool {
+        self.remaining_sec() <= 0.0 && self.quality_ok()
+    }
+
+    fn status(&self) -> FfiPreparationStatus {
+        FfiPreparationStatus {
+            remaining_sec: self.remaining_sec(),
+            signal_quality: self.quality,
+            quality_ok: self.quality_ok(),
+        }
+    }
+}
+
+// ============================================================================
+// SESSION STAGES - WARM-UP AND COOL-DOWN
+// ============================================================================
+
+/// Longest warm-up or cool-down a session may declare
+#[cfg(feature = "full")]
+const SESSION_STAGE_MAX_SEC: f32 = 600.0;
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiSessionStage {
+    WarmUp,
+    Main,
+    CoolDown,
+}
+
+/// Warm-up and cool-down declared for new sessions; 0 s skips a stage
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiSessionStagesConfig {
+    /// Time over which the pacer eases into the pattern's cadence
+    pub warm_up_sec: f32,
+    /// Tempo scale the warm-up starts from (> 1 breathes slower)
+    pub warm_up_tempo: f32,
+    /// Unpaced natural breathing observed after the paced part
+    pub cool_down_sec: f32,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiSessionStagesConfig {
+    fn default() -> Self {
+        Self {
+            warm_up_sec: 0.0,
+            warm_up_tempo: 1.25,
+            cool_down_sec: 0.0,
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiSessionStagesConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+        for (name, seconds) in [("Warm-up", self.warm_up_sec), ("Cool-down", self.cool_down_sec)] {
+            if !(0.0..=SESSION_STAGE_MAX_SEC).contains(&seconds) {
+                return invalid(format!("{} must last 0-{} s", name, SESSION_STAGE_MAX_SEC));
+            }
+        }
+        if !(1.0..=TEMPO_MAX).contains(&self.warm_up_tempo) {
+            return invalid(format!("Warm-up tempo must be within 1-{}", TEMPO_MAX));
+        }
+        Ok(())
+    }
+}
+
+/// The stage a running session is in
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiStageStatus {
+    pub stage: FfiSessionStage,
+    pub elapsed_sec: f32,
+    /// None for the main stage, which ends with the session
+    pub duration_sec: Option<f32>,
+}
+
+/// One stage of a finished session, in active session seconds
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiStageSpan {
+    pub stage: FfiSessionStage,
+    pub start_sec: f32,
+    pub end_sec: f32,
+}
+
+#[cfg(feature = "full")]
+struct SessionStages {
+    config: FfiSessionStagesConfig,
+    stage: FfiSessionStage,
+    /// Active session time on the kernel clock (pauses excluded)
+    elapsed_sec: f32,
+    stage_started_sec: f32,
+    spans: Vec<FfiStageSpan>,
+    /// Natural breathing measured during the cool-down
+    cool_down: Option<BaselineObservation>,
+}
+
+#[cfg(feature = "full")]
+impl SessionStages {
+    fn new(config: FfiSessionStagesConfig) -> Self {
+        let stage = if config.warm_up_sec > 0.0 { FfiSessionStage::WarmUp } else { FfiSessionStage::Main };
+        Self { config, stage, elapsed_sec: 0.0, stage_started_sec: 0.0, spans: Vec::new(), cool_down: None }
+    }
+
+    fn stage_elapsed_sec(&self) -> f32 {
+        self.elapsed_sec - self.stage_started_sec
+    }
+
+    /// Advance active time; returns the stage entered when the warm-up ends
+    fn tick(&mut self, dt_sec: f32) -> Option<FfiSessionStage> {
+        self.elapsed_sec += dt_sec;
+        if let Some(cool_down) = &mut self.cool_down {
+            cool_down.elapsed_sec += dt_sec;
+        }
+        if self.stage == FfiSessionStage::WarmUp && self.stage_elapsed_sec() >= self.config.warm_up_sec {
+            self.enter(FfiSessionStage::Main);
+            return Some(FfiSessionStage::Main);
+        }
+        None
+    }
+
+    /// Tempo multiplier: starts at `warm_up_tempo` and eases to 1 by the end of the warm-up
+    fn tempo_factor(&self) -> f32 {
+        if self.stage != FfiSessionStage::WarmUp {
+            return 1.0;
+        }
+        let t = (self.stage_elapsed_sec() / self.config.warm_up_sec).clamp(0.0, 1.0);
+        self.config.warm_up_tempo + (1.0 - self.config.warm_up_tempo) * t
+    }
+
+    fn close_span(&mut self) {
+        self.spans.push(FfiStageSpan {
+            stage: self.stage,
+            start_sec: self.stage_started_sec,
+            end_sec: self.elapsed_sec,
+        });
+    }
+
+    fn enter(&mut self, stage: FfiSessionStage) {
+        self.close_span();
+        self.stage = stage;
+        self.stage_started_sec = self.elapsed_sec;
+    }
+
+    /// Switch to the cool-down; false when none is declared or it already started
+    fn begin_cool_down(&mut self) -> bool {
+        if self.config.cool_down_sec <= 0.0 || self.stage == FfiSessionStage::CoolDown {
+            return false;
+        }
+        self.enter(FfiSessionStage::CoolDown);
+        self.cool_down = Some(BaselineObservation::new(self.config.cool_down_sec));
+        true
+    }
+
+    fn cool_down_done(&self) -> bool {
+        self.stage == FfiSessionStage::CoolDown && self.stage_elapsed_sec() >= self.config.cool_down_sec
+    }
+
+    fn status(&self) -> FfiStageStatus {
+        let duration_sec = match self.stage {
+            FfiSessionStage::WarmUp => Some(self.config.warm_up_sec),
+            FfiSessionStage::Main => None,
+            FfiSessionStage::CoolDown => Some(self.config.cool_down_sec),
+        };
+        FfiStageStatus { stage: self.stage, elapsed_sec: self.stage_elapsed_sec(), duration_sec }
+    }
+
+    /// Stage boundaries, and the cool-down's natural breathing if enough breaths were seen
+    fn finish(mut self) -> (Vec<FfiStageSpan>, Option<FfiBreathingBaseline>) {
+        self.close_span();
+        let breathing = self.cool_down.and_then(|c| c.report().ok());
+        (self.spans, breathing)
+    }
+}
+
+// ============================================================================
+// SAFETY AUDIT JOURNAL
+// ============================================================================
+
+#[cfg(feature = "full")]
+const SAFETY_JOURNAL_FILE: &str = "safety_journal.json";
+/// Locks caused by critical violations stay engaged at least this long
+#[cfg(feature = "full")]
+const SAFETY_LOCK_COOLDOWN: Duration = Duration::from_secs(120);
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiSafetyJournalKind {
+    LockEngaged,
+    LockReset,
+    /// A reset was attempted during the cooldown
+    ResetRejected,
+    /// A recorded violation was reviewed and dismissed
+    ViolationAcknowledged,
+    /// An alert was prepared for the trusted contact
+    EscalationRaised,
+}
+
+/// One entry in the safety audit journal
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSafetyJournalEntry {
+    pub kind: FfiSafetyJournalKind,
+    pub timestamp_ms: i64,
+    pub reason: String,
+    /// The violation that caused the lock
+    pub violation: Option<FfiSafetyViolation>,
+}
+
+/// Why and when the safety lock engaged. Wall-clock time, so the cooldown
+/// survives a restart.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SafetyLockInfo {
+    engaged_at_ms: i64,
+    cause: FfiSafetyViolation,
+}
+
+#[cfg(feature = "full")]
+impl SafetyLockInfo {
+    fn cooldown_remaining(&self, now_ms: i64) -> Duration {
+        if self.cause.severity == FfiViolationSeverity::Critical {
+            let elapsed = Duration::from_millis((now_ms - self.engaged_at_ms).max(0) as u64);
+            SAFETY_LOCK_COOLDOWN.saturating_sub(elapsed)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Append-only journal of safety lock events, plus the lock currently
+/// engaged; persisted when a file is attached
+#[cfg(feature = "full")]
+pub struct SafetyJournal {
+    inner: Mutex<SafetyJournalInner>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct SafetyJournalInner {
+    entries: Vec<FfiSafetyJournalEntry>,
+    #[serde(default)]
+    active_lock: Option<SafetyLockInfo>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+impl Default for SafetyJournal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl SafetyJournal {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(SafetyJournalInner::default()),
+        }
+    }
+
+    /// Load the persisted journal from `path` and keep earlier in-memory entries
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read safety journal: {}", e)))?;
+            serde_json::from_slice::<SafetyJournalInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt safety journal: {}", e)))?
+        } else {
+            SafetyJournalInner::default()
+        };
+        loaded.path = Some(path);
+
+        let mut inner = self.inner.lock();
+        let pending = std::mem::take(&mut inner.entries);
+        let engaged = inner.active_lock.take();
+        *inner = loaded;
+        inner.entries.extend(pending);
+        if engaged.is_some() {
+            inner.active_lock = engaged;
+        }
+        Self::persist(&inner)
+    }
+
+    fn active_lock(&self) -> Option<SafetyLockInfo> {
+        self.inner.lock().active_lock.clone()
+    }
+
+    fn set_active_lock(&self, lock: Option<SafetyLockInfo>) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        inner.active_lock = lock;
+        Self::persist(&inner)
+    }
+
+    fn append(&self, entry: FfiSafetyJournalEntry) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        inner.entries.push(entry);
+        Self::persist(&inner)
+    }
+
+    pub fn entries(&self) -> Vec<FfiSafetyJournalEntry> {
+        self.inner.lock().entries.clone()
+    }
+
+    fn persist(inner: &SafetyJournalInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Safety journal serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist safety journal: {}", e)))
+    }
+}
+
+// ============================================================================
+// PATTERN PREVIEW
+// ============================================================================
+
+/// Lightweight phase machine used to show a pattern's rhythm before a session
+#[cfg(feature = "full")]
+struct PatternPreview {
+    machine: PhaseMachine,
+    pattern_id: String,
+    cycles: u32,
+}
+
+// ============================================================================
+// WATCH MIRRORING - COMPACT BINARY STATE SYNC
+// ============================================================================
+//
+// Transport-agnostic: the host app moves these bytes over WatchConnectivity or
+// the Wear OS Data Layer. Every frame is
+//
+//   magic "ZW" | version u8 | kind u8 | seq u32 LE | payload
+//
+// State (phone -> watch, 14 bytes): status u8, phase u8, progress u16 (1/65535),
+//   cycles u32, heart rate u16 (0.1 bpm, 0 = none), elapsed u32 (0.1 s)
+// Command (watch -> phone, 1 byte): 0 pause, 1 resume, 2 stop
+// Ack (both, 4 bytes): acknowledged seq u32
+// Hello (watch -> phone, 4 bytes): last state seq seen; resets command dedup
+//   and forces a full resync
+
+#[cfg(feature = "full")]
+const WATCH_MAGIC: &[u8; 2] = b"ZW";
+#[cfg(feature = "full")]
+const WATCH_PROTOCOL_VERSION: u8 = 1;
+#[cfg(feature = "full")]
+const WATCH_HEADER_LEN: usize = 8;
+/// Fastest state update rate while values change
+#[cfg(feature = "full")]
+const WATCH_MIN_INTERVAL: Duration = Duration::from_millis(200);
+/// Resend unchanged state this often so the watch can detect a dead link
+#[cfg(feature = "full")]
+const WATCH_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(2);
+/// Heart rate readings older than this are not mirrored
+#[cfg(feature = "full")]
+const WATCH_HR_MAX_AGE: Duration = Duration::from_secs(5);
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchFrameKind {
+    State = 1,
+    Command = 2,
+    Ack = 3,
+    Hello = 4,
+}
+
+#[cfg(feature = "full")]
+impl WatchFrameKind {
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(Self::State),
+            2 => Some(Self::Command),
+            3 => Some(Self::Ack),
+            4 => Some(Self::Hello),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchCommand {
+    Pause,
+    Resume,
+    Stop,
+}
+
+/// Watch link status
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiWatchLinkStatus {
+    pub connected: bool,
+    pub last_sent_seq: u32,
+    pub last_acked_seq: u32,
+    /// A full state resync will be sent next
+    pub resync_pending: bool,
+}
+
+/// Phone side of the watch mirroring channel
+#[cfg(feature = "full")]
+pub struct WatchLink {
+    inner: Mutex<WatchLinkInner>,
+}
+
+#[cfg(feature = "full")]
+struct WatchLinkInner {
+    connected: bool,
+    last_sent_seq: u32,
+    last_acked_seq: u32,
+    last_payload: Option<Vec<u8>>,
+    last_sent_at: Option<Instant>,
+    resync: bool,
+    /// Highest command seq applied since the last hello
+    last_command_seq: Option<u32>,
+    last_hr: Option<(f32, Instant)>,
+}
+
+#[cfg(feature = "full")]
+impl Default for WatchLink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl WatchLink {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(WatchLinkInner {
+                connected: false,
+                last_sent_seq: 0,
+                last_acked_seq: 0,
+                last_payload: None,
+                last_sent_at: None,
+                resync: true,
+                last_command_seq: None,
+                last_hr: None,
+            }),
+        }
+    }
+
+    fn set_connected(&self, connected: bool) {
+        let mut inner = self.inner.lock();
+        if connected && !inner.connected {
+            inner.resync = true;
+        }
+        inner.connected = connected;
+    }
+
+    fn next_message(&self, state: &FfiRuntimeState, heart_rate: Option<f32>) -> Option<Vec<u8>> {
+        let mut inner = self.inner.lock();
+        if let Some(hr) = heart_rate {
+            inner.last_hr = Some((hr, Instant::now()));
+        }
+        if !inner.connected {
+            return None;
+        }
+
+        let hr = inner.last_hr
+            .filter(|(_, at)| at.elapsed() <= WATCH_HR_MAX_AGE)
+            .map(|(hr, _)| hr);
+        let payload = encode_watch_state(state, hr);
+        let since_last = inner.last_sent_at.map(|t| t.elapsed());
+        let changed = inner.last_payload.as_deref() != Some(payload.as_slice());
+        let due = match since_last {
+            None => true,
+            Some(elapsed) if changed => elapsed >= WATCH_MIN_INTERVAL,
+            Some(elapsed) => elapsed >= WATCH_HEARTBEAT_INTERVAL,
+        };
+        if !inner.resync && !due {
+            return None;
+        }
+
+        inner.last_sent_seq = inner.last_sent_seq.wrapping_add(1);
+        inner.last_sent_at = Some(Instant::now());
+        inner.resync = false;
+        let frame = encode_watch_frame(WatchFrameKind::State, inner.last_sent_seq, &payload);
+        inner.last_payload = Some(payload);
+        Some(frame)
+    }
+
+    /// Decode an incoming frame, returning an optional reply and a command to apply
+    fn handle_message(&self, data: &[u8]) -> Result<(Option<Vec<u8>>, Option<WatchCommand>), ZenOneError> {
+        let (kind, seq, payload) = decode_watch_frame(data)?;
+        let mut inner = self.inner.lock();
+        // Any traffic proves the link is up
+        if !inner.connected {
+            inner.connected = true;
+            inner.resync = true;
+        }
+
+        match kind {
+            WatchFrameKind::Hello => {
+                inner.last_command_seq = None;
+                inner.resync = true;
+                Ok((None, None))
+            }
+            WatchFrameKind::Ack => {
+                let acked = read_u32(payload, 0)?;
+                inner.last_acked_seq = acked;
+                Ok((None, None))
+            }
+            WatchFrameKind::Command => {
+                let command = match payload.first() {
+                    Some(0) => WatchCommand::Pause,
+                    Some(1) => WatchCommand::Resume,
+                    Some(2) => WatchCommand::Stop,
+                    _ => return Err(ZenOneError::ConfigError("Invalid watch message: unknown command".into())),
+                };
+                let ack = encode_watch_frame(WatchFrameKind::Ack, inner.last_sent_seq, &seq.to_le_bytes());
+                // Retransmits of an already-applied command are only acknowledged
+                if inner.last_command_seq.is_some_and(|last| seq <= last) {
+                    return Ok((Some(ack), None));
+                }
+                inner.last_command_seq = Some(seq);
+                // Make the effect visible on the watch right away
+                inner.resync = true;
+                Ok((Some(ack), Some(command)))
+            }
+            WatchFrameKind::State => {
+                Err(ZenOneError::ConfigError("Invalid watch message: unexpected state frame".into()))
+            }
+        }
+    }
+
+    fn status(&self) -> FfiWatchLinkStatus {
+        let inner = self.inner.lock();
+        FfiWatchLinkStatus {
+            connected: inner.connected,
+            last_sent_seq: inner.last_sent_seq,
+            last_acked_seq: inner.last_acked_seq,
+            resync_pending: inner.resync,
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+fn encode_watch_frame(kind: WatchFrameKind, seq: u32, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(WATCH_HEADER_LEN + payload.len());
+    out.extend_from_slice(WATCH_MAGIC);
+    out.push(WATCH_PROTOCOL_VERSION);
+    out.push(kind as u8);
+    out.extend_from_slice(&seq.to_le_bytes());
+    out.extend_from_slice(payload);
+    out
+}
+
+#[cfg(feature = "full")]
+fn decode_watch_frame(data: &[u8]) -> Result<(WatchFrameKind, u32, &[u8]), ZenOneError> {
+    if data.len() < WATCH_HEADER_LEN || &data[..2] != WATCH_MAGIC {
+        return Err(ZenOneError::ConfigError("Invalid watch message: bad header".into()));
+    }
+    if data[2] != WATCH_PROTOCOL_VERSION {
+        return Err(ZenOneError::ConfigError(format!(
+            "Invalid watch message: unsupported version {}",
+            data[2]
+        )));
+    }
+    let kind = WatchFrameKind::from_u8(data[3])
+        .ok_or_else(|| ZenOneError::ConfigError("Invalid watch message: unknown kind".into()))?;
+    let seq = read_u32(data, 4)?;
+    Ok((kind, seq, &data[WATCH_HEADER_LEN..]))
+}
+
+#[cfg(feature = "full")]
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, ZenOneError> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| ZenOneError::ConfigError("Invalid watch message: truncated".into()))
+}
+
+#[cfg(feature = "full")]
+fn encode_watch_state(state: &FfiRuntimeState, heart_rate: Option<f32>) -> Vec<u8> {
+    let status: u8 = match state.status {
+        FfiRuntimeStatus::Idle => 0,
+        FfiRuntimeStatus::Preparing => 1,
+        FfiRuntimeStatus::Running => 2,
+        FfiRuntimeStatus::Paused => 3,
+        FfiRuntimeStatus::SafetyLock => 4,
+    };
+    let phase: u8 = match state.phase {
+        FfiPhase::Inhale => 0,
+        FfiPhase::HoldIn => 1,
+        FfiPhase::Exhale => 2,
+        FfiPhase::HoldOut => 3,
+        FfiPhase::Prepare => 4,
+    };
+    let progress = (state.phase_progress.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+    let cycles = state.cycles_completed.min(u32::MAX as u64) as u32;
+    let hr = heart_rate.map(|hr| (hr * 10.0).clamp(0.0, u16::MAX as f32) as u16).unwrap_or(0);
+    let elapsed = (state.session_duration_sec * 10.0).clamp(0.0, u32::MAX as f32) as u32;
+
+    let mut out = Vec::with_capacity(14);
+    out.push(status);
+    out.push(phase);
+    out.extend_from_slice(&progress.to_le_bytes());
+    out.extend_from_slice(&cycles.to_le_bytes());
+    out.extend_from_slice(&hr.to_le_bytes());
+    out.extend_from_slice(&elapsed.to_le_bytes());
+    out
+}
+
+// ============================================================================
+// FOLLOW-ME PACER - BREATHING RATE LOCK AND GUIDANCE
+// ============================================================================
+
+/// Safety bounds for any pacer rate follow-me may set
+#[cfg(feature = "full")]
+const FOLLOW_ME_MIN_RATE_BPM: f32 = 4.5;
+#[cfg(feature = "full")]
+const FOLLOW_ME_MAX_RATE_BPM: f32 = 20.0;
+/// Largest pacer change per cycle while locking on
+#[cfg(feature = "full")]
+const FOLLOW_ME_LOCK_STEP_BPM: f32 = 2.0;
+/// Pacer decrease per well-followed cycle while guiding
+#[cfg(feature = "full")]
+const FOLLOW_ME_GUIDE_STEP_BPM: f32 = 0.25;
+/// Cycle adherence needed to keep guiding down, and below which we hold
+#[cfg(feature = "full")]
+const FOLLOW_ME_GOOD_ADHERENCE: f32 = 0.6;
+#[cfg(feature = "full")]
+const FOLLOW_ME_POOR_ADHERENCE: f32 = 0.4;
+/// Window used to estimate the breathing rate
+#[cfg(feature = "full")]
+const BREATH_RATE_WINDOW_US: i64 = 30_000_000;
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiFollowMeStage {
+    /// Matching the pacer to the user's own rate
+    Locking,
+    /// Slowing the pacer while the user follows
+    Guiding,
+    /// User stopped following; pacer held
+    Holding,
+    /// Target rate reached
+    Reached,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiFollowMeStatus {
+    pub stage: FfiFollowMeStage,
+    pub detected_rate_bpm: Option<f32>,
+    pub pacer_rate_bpm: Option<f32>,
+    pub target_rate_bpm: f32,
+}
+
+#[cfg(feature = "full")]
+struct FollowMe {
+    target_rate: f32,
+    stage: FfiFollowMeStage,
+    pacer_rate: Option<f32>,
+}
+
+#[cfg(feature = "full")]
+impl FollowMe {
+    fn new(target_rate: f32) -> Self {
+        Self {
+            target_rate,
+            stage: FfiFollowMeStage::Locking,
+            pacer_rate: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.target_rate);
+    }
+
+    /// Decide the pacer rate for the next cycle; `None` keeps the current one
+    fn on_cycle(&mut self, detected: Option<f32>, cycle_score: Option<f32>) -> Option<f32> {
+        let good = cycle_score.is_some_and(|s| s >= FOLLOW_ME_GOOD_ADHERENCE);
+        let poor = cycle_score.is_some_and(|s| s < FOLLOW_ME_POOR_ADHERENCE);
+
+        let next = match (self.stage, self.pacer_rate) {
+            (FfiFollowMeStage::Locking, current) => {
+                let detected = detected?.clamp(FOLLOW_ME_MIN_RATE_BPM, FOLLOW_ME_MAX_RATE_BPM);
+                let next = match current {
+                    Some(rate) => rate + (detected - rate).clamp(-FOLLOW_ME_LOCK_STEP_BPM, FOLLOW_ME_LOCK_STEP_BPM),
+                    None => detected,
+                };
+                if (next - detected).abs() < 0.5 && good {
+                    self.stage = FfiFollowMeStage::Guiding;
+                }
+                next
+            }
+            (FfiFollowMeStage::Guiding, Some(rate)) if poor => {
+                self.stage = FfiFollowMeStage::Holding;
+                rate
+            }
+            (FfiFollowMeStage::Guiding, Some(rate)) if good => {
+                let next = (rate - FOLLOW_ME_GUIDE_STEP_BPM).max(self.target_rate);
+                if next <= self.target_rate {
+                    self.stage = FfiFollowMeStage::Reached;
+                }
+                next
+            }
+            (FfiFollowMeStage::Holding, Some(rate)) if good => {
+                self.stage = FfiFollowMeStage::Guiding;
+                rate
+            }
+            _ => return None,
+        };
+
+        let next = next.clamp(FOLLOW_ME_MIN_RATE_BPM, FOLLOW_ME_MAX_RATE_BPM);
+        if self.pacer_rate.is_some_and(|r| (r - next).abs() < 0.01) {
+            return None;
+        }
+        self.pacer_rate = Some(next);
+        Some(next)
+    }
+
+    fn status(&self, detected: Option<f32>) -> FfiFollowMeStatus {
+        FfiFollowMeStatus {
+            stage: self.stage,
+            detected_rate_bpm: detected,
+            pacer_rate_bpm: self.pacer_rate,
+            target_rate_bpm: self.target_rate,
+        }
+    }
+}
+
+/// Breathing rate from upward crossings of the mean-removed respiration signal.
+/// Direct sources (chest, audio) are preferred over RSA when available.
+#[cfg(feature = "full")]
+struct BreathRateEstimator {
+    samples: std::collections::VecDeque<(i64, f32)>,
+    source: Option<FfiRespirationSource>,
+}
+
+#[cfg(feature = "full")]
+impl BreathRateEstimator {
+    fn new() -> Self {
+        Self {
+            samples: std::collections::VecDeque::new(),
+            source: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.samples.clear();
+        self.source = None;
+    }
+
+    fn observe(&mut self, value: f32, source: FfiRespirationSource, timestamp_us: i64) {
+        match self.source {
+            // Switch up to a direct source, never down to RSA
+            Some(current) if current != source => {
+                if source.weight() <= current.weight() {
+                    return;
+                }
+                self.samples.clear();
+                self.source = Some(source);
+            }
+            Some(_) => {}
+            None => self.source = Some(source),
+        }
+        self.samples.push_back((timestamp_us, value));
+        while self.samples.front().is_some_and(|(ts, _)| timestamp_us - ts > BREATH_RATE_WINDOW_US) {
+            self.samples.pop_front();
+        }
+    }
+
+    fn rate_bpm(&self) -> Option<f32> {
+        let crossings = breath_onsets(&self.samples);
+        if crossings.len() < 3 {
+            return None;
+        }
+        let span_us = (crossings[crossings.len() - 1] - crossings[0]) as f32;
+        if span_us <= 0.0 {
+            return None;
+        }
+        Some((crossings.len() - 1) as f32 * 60_000_000.0 / span_us)
+    }
+}
+
+/// Timestamps (us) of the upward crossings of a mean-removed respiration
+/// signal, one per breath
+#[cfg(feature = "full")]
+fn breath_onsets<'a, I>(samples: I) -> Vec<i64>
+where
+    I: IntoIterator<Item = &'a (i64, f32)> + Copy,
+{
+    let (count, sum) = samples.into_iter().fold((0usize, 0.0f32), |(n, s), (_, v)| (n + 1, s + v));
+    if count < 10 {
+        return Vec::new();
+    }
+    let n = count as f32;
+    let mean = sum / n;
+    let sd = (samples.into_iter().map(|(_, v)| (v - mean).powi(2)).sum::<f32>() / n).sqrt();
+    if sd <= f32::EPSILON {
+        return Vec::new();
+    }
+
+    // Hysteresis against noise around the mean
+    let band = 0.2 * sd;
+    let mut below = true;
+    let mut crossings = Vec::new();
+    for (ts, v) in samples {
+        let x = v - mean;
+        if below && x > band {
+            crossings.push(*ts);
+            below = false;
+        } else if !below && x < -band {
+            below = true;
+        }
+    }
+    crossings
+}
+
+// ============================================================================
+// PHASE CLOCK - TRANSITION ANTICIPATION
+// ============================================================================
+
+/// Tracks time spent in the current phase alongside the PhaseMachine so the
+/// next transition can be anticipated
+#[cfg(feature = "full")]
+struct PhaseClock {
+    /// Inhale, HoldIn, Exhale, HoldOut
+    durations_us: [u64; 4],
+    elapsed_us: u64,
+    /// PhaseEnding already emitted for the current phase
+    warned: bool,
+    /// Last PhaseCountdown number emitted for the current phase
+    counted: Option<u32>,
+}
+
+#[cfg(feature = "full")]
+impl PhaseClock {
+    fn new(durations: &PhaseDurations) -> Self {
+        Self {
+            durations_us: [
+                durations.inhale_us,
+                durations.hold_in_us,
+                durations.exhale_us,
+                durations.hold_out_us,
+            ],
+            elapsed_us: 0,
+            warned: false,
+            counted: None,
+        }
+    }
+
+    fn index(phase: FfiPhase) -> usize {
+        match phase {
+            // The countdown leads into the first inhale
+            FfiPhase::Inhale | FfiPhase::Prepare => 0,
+            FfiPhase::HoldIn => 1,
+            FfiPhase::Exhale => 2,
+            FfiPhase::HoldOut => 3,
+        }
+    }
+
+    fn advance(&mut self, before: FfiPhase, after: FfiPhase, dt_us: u64) {
+        if before == after {
+            self.elapsed_us += dt_us;
+        } else {
+            // Carry the overshoot past the boundary into the new phase
+            let overshoot = (self.elapsed_us + dt_us).saturating_sub(self.durations_us[Self::index(before)]);
+            self.elapsed_us = overshoot.min(self.durations_us[Self::index(after)]);
+            self.warned = false;
+            self.counted = None;
+        }
+    }
+
+    fn remaining_us(&self, phase: FfiPhase) -> u64 {
+        self.durations_us[Self::index(phase)].saturating_sub(self.elapsed_us)
+    }
+
+    /// Fraction of the current phase elapsed, 0-1
+    fn progress(&self, phase: FfiPhase) -> f32 {
+        match self.durations_us[Self::index(phase)] {
+            0 => 1.0,
+            duration => (self.elapsed_us as f32 / duration as f32).min(1.0),
+        }
+    }
+
+    /// Next phase with a non-zero duration
+    fn next_phase(&self, phase: FfiPhase) -> FfiPhase {
+        const ORDER: [FfiPhase; 4] = [FfiPhase::Inhale, FfiPhase::HoldIn, FfiPhase::Exhale, FfiPhase::HoldOut];
+        let start = Self::index(phase);
+        (1..=4)
+            .map(|step| ORDER[(start + step) % 4])
+            .find(|p| self.durations_us[Self::index(*p)] > 0)
+            .unwrap_or(phase)
+    }
+}
+
+// ============================================================================
+// HEART-RATE ZONES
+// ============================================================================
+
+/// Heart-rate samples older than this stop counting towards a zone
+#[cfg(feature = "full")]
+const HR_ZONE_STALE: Duration = Duration::from_secs(5);
+#[cfg(feature = "full")]
+const HR_ZONES_MAX: usize = 8;
+
+/// A heart-rate band, lower bound inclusive
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiHrZone {
+    pub name: String,
+    pub min_bpm: f32,
+    pub max_bpm: f32,
+}
+
+/// Zone layout plus the optional elevated-HR safety pause
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiHrZoneConfig {
+    /// Ascending, non-overlapping zones
+    pub zones: Vec<FfiHrZone>,
+    /// Index of the first zone considered elevated
+    pub elevated_from_zone: Option<u32>,
+    /// Pause calming sessions after this long in an elevated zone (None disables)
+    pub elevated_pause_sec: Option<f32>,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiHrZoneConfig {
+    fn default() -> Self {
+        let zone = |name: &str, min_bpm: f32, max_bpm: f32| FfiHrZone { name: name.into(), min_bpm, max_bpm };
+        Self {
+            zones: vec![
+                zone("Resting", 30.0, 70.0),
+                zone("Light", 70.0, 90.0),
+                zone("Moderate", 90.0, 110.0),
+                zone("Elevated", 110.0, 220.0),
+            ],
+            elevated_from_zone: Some(3),
+            elevated_pause_sec: None,
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiHrZoneConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+        if self.zones.is_empty() || self.zones.len() > HR_ZONES_MAX {
+            return invalid(format!("Expected 1-{} HR zones, got {}", HR_ZONES_MAX, self.zones.len()));
+        }
+        for (i, zone) in self.zones.iter().enumerate() {
+            if zone.name.trim().is_empty() {
+                return invalid(format!("HR zone {} has no name", i));
+            }
+            if !(zone.min_bpm.is_finite() && zone.max_bpm.is_finite() && zone.min_bpm < zone.max_bpm) {
+                return invalid(format!("HR zone '{}' has an empty range", zone.name));
+            }
+            if i > 0 && zone.min_bpm < self.zones[i - 1].max_bpm {
+                return invalid(format!("HR zone '{}' overlaps the previous zone", zone.name));
+            }
+        }
+        if self.elevated_from_zone.is_some_and(|z| z as usize >= self.zones.len()) {
+            return invalid("Elevated zone index out of range".into());
+        }
+        if self.elevated_pause_sec.is_some_and(|s| !(s.is_finite() && s > 0.0)) {
+            return invalid("Elevated pause duration must be positive".into());
+        }
+        Ok(())
+    }
+
+    fn zone_index(&self, bpm: f32) -> Option<usize> {
+        self.zones.iter().position(|z| bpm >= z.min_bpm && bpm < z.max_bpm)
+    }
+}
+
+/// Time spent in one zone during a session
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiZoneTime {
+    pub zone: String,
+    pub seconds: f32,
+}
+
+/// Per-session time-in-zone accounting, using the config captured at session start
+#[cfg(feature = "full")]
+struct HrZoneTracker {
+    config: FfiHrZoneConfig,
+    seconds: Vec<f32>,
+    last_hr: Option<(f32, Instant)>,
+    /// Continuous time in elevated zones
+    elevated_sec: f32,
+}
+
+#[cfg(feature = "full")]
+impl HrZoneTracker {
+    fn new(config: FfiHrZoneConfig) -> Self {
+        let seconds = vec![0.0; config.zones.len()];
+        Self { config, seconds, last_hr: None, elevated_sec: 0.0 }
+    }
+
+    fn observe(&mut self, bpm: f32, now: Instant) {
+        self.last_hr = Some((bpm, now));
+    }
+
+    /// Credit `dt_sec` to the current zone. Returns the zone name and sustained
+    /// time once the elevated limit is crossed (then starts counting again).
+    fn tick(&mut self, dt_sec: f32, now: Instant) -> Option<(String, f32)> {
+        let zone = self.last_hr
+            .filter(|(_, at)| now.saturating_duration_since(*at) <= HR_ZONE_STALE)
+            .and_then(|(bpm, _)| self.config.zone_index(bpm));
+        let Some(zone) = zone else {
+            self.elevated_sec = 0.0;
+            return None;
+        };
+        self.seconds[zone] += dt_sec;
+
+        let elevated = self.config.elevated_from_zone.is_some_and(|from| zone >= from as usize);
+        if !elevated {
+            self.elevated_sec = 0.0;
+            return None;
+        }
+        self.elevated_sec += dt_sec;
+        let limit = self.config.elevated_pause_sec?;
+        if self.elevated_sec < limit {
+            return None;
+        }
+        let sustained = self.elevated_sec;
+        self.elevated_sec = 0.0;
+        Some((self.config.zones[zone].name.clone(), sustained))
+    }
+
+    fn summary(&self) -> Vec<FfiZoneTime> {
+        self.config
+            .zones
+            .iter()
+            .zip(&self.seconds)
+            .map(|(z, s)| FfiZoneTime { zone: z.name.clone(), seconds: *s })
+            .collect()
+    }
+}
+
+// ============================================================================
+// SESSION GOALS
+// ============================================================================
+
+/// Coherence is only averaged after this warm-up, so early spikes don't count
+#[cfg(feature = "full")]
+const GOAL_COHERENCE_WARMUP_SEC: f32 = 60.0;
+#[cfg(feature = "full")]
+const GOAL_MAX_MINUTES: f32 = 240.0;
+
+/// Targets set when a session starts; unset targets are not tracked
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FfiSessionGoals {
+    /// Session-average coherence 0-1
+    pub target_coherence: Option<f32>,
+    /// Active (unpaused) minutes
+    pub target_minutes: Option<f32>,
+    pub target_cycles: Option<u32>,
+}
+
+#[cfg(feature = "full")]
+impl FfiSessionGoals {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        let invalid = |msg: &str| Err(ZenOneError::ConfigError(msg.into()));
+        if self.target_coherence.is_some_and(|c| !(c > 0.0 && c <= 1.0)) {
+            return invalid("Coherence goal must be within (0, 1]");
+        }
+        if self.target_minutes.is_some_and(|m| !(m > 0.0 && m <= GOAL_MAX_MINUTES)) {
+            return invalid("Minutes goal must be positive and at most 240");
+        }
+        if self.target_cycles == Some(0) {
+            return invalid("Cycles goal must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiGoalKind {
+    Coherence,
+    Minutes,
+    Cycles,
+}
+
+/// Progress towards a single goal
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiGoalProgress {
+    pub kind: FfiGoalKind,
+    pub target: f32,
+    pub current: f32,
+    /// current / target, capped at 1
+    pub progress: f32,
+    /// Stays true once reached, even if the coherence average later dips
+    pub achieved: bool,
+}
+
+/// Per-session goal accounting
+#[cfg(feature = "full")]
+struct GoalTracker {
+    goals: FfiSessionGoals,
+    active_sec: f32,
+    cycles: u64,
+    coherence_sum: f32,
+    coherence_samples: u32,
+    achieved: Vec<FfiGoalKind>,
+}
+
+#[cfg(feature = "full")]
+impl GoalTracker {
+    fn new(goals: FfiSessionGoals) -> Self {
+        Self {
+            goals,
+            active_sec: 0.0,
+            cycles: 0,
+            coherence_sum: 0.0,
+            coherence_samples: 0,
+            achieved: Vec::new(),
+        }
+    }
+
+    /// Credit a running tick. Returns the goals reached for the first time.
+    fn tick(&mut self, dt_sec: f32, cycles: u64, coherence: f32) -> Vec<FfiGoalProgress> {
+        self.active_sec += dt_sec;
+        self.cycles = cycles;
+        if self.active_sec >= GOAL_COHERENCE_WARMUP_SEC {
+            self.coherence_sum += coherence;
+            self.coherence_samples += 1;
+        }
+        let reached: Vec<FfiGoalProgress> = self
+            .progress()
+            .into_iter()
+            .filter(|g| g.achieved && !self.achieved.contains(&g.kind))
+            .collect();
+        self.achieved.extend(reached.iter().map(|g| g.kind));
+        reached
+    }
+
+    fn progress(&self) -> Vec<FfiGoalProgress> {
+        let mut out = Vec::new();
+        let mut push = |kind: FfiGoalKind, target: f32, current: f32| {
+            out.push(FfiGoalProgress {
+                kind,
+                target,
+                current,
+                progress: (current / target).min(1.0),
+                achieved: current >= target || self.achieved.contains(&kind),
+            });
+        };
+        if let Some(target) = self.goals.target_coherence {
+            let mean = if self.coherence_samples > 0 {
+                self.coherence_sum / self.coherence_samples as f32
+            } else {
+                0.0
+            };
+            push(FfiGoalKind::Coherence, target, mean);
+        }
+        if let Some(target) = self.goals.target_minutes {
+            push(FfiGoalKind::Minutes, target, self.active_sec / 60.0);
+        }
+        if let Some(target) = self.goals.target_cycles {
+            push(FfiGoalKind::Cycles, target as f32, self.cycles as f32);
+        }
+        out
+    }
+}
+
+// ============================================================================
+// SLEEP WIND-DOWN
+// ============================================================================
+
+/// Sleep session behaviour
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiSleepConfig {
+    /// Minutes over which exhales lengthen and audio fades out
+    pub wind_down_min: f32,
+    /// Exhale length multiplier reached at the end of the wind-down
+    pub max_exhale_scale: f32,
+    /// Minutes without interaction or motion before the user is presumed asleep
+    pub idle_timeout_min: f32,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiSleepConfig {
+    fn default() -> Self {
+        Self { wind_down_min: 10.0, max_exhale_scale: 1.5, idle_timeout_min: 5.0 }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiSleepConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        let invalid = |msg: &str| Err(ZenOneError::ConfigError(msg.into()));
+        if !(self.wind_down_min.is_finite() && self.wind_down_min > 0.0 && self.wind_down_min <= 120.0) {
+            return invalid("Wind-down must be between 0 and 120 minutes");
+        }
+        if !(1.0..=2.5).contains(&self.max_exhale_scale) {
+            return invalid("Exhale scale must be within 1.0-2.5");
+        }
+        if !(self.idle_timeout_min.is_finite() && self.idle_timeout_min >= 1.0) {
+            return invalid("Idle timeout must be at least one minute");
+        }
+        Ok(())
+    }
+}
+
+/// Live wind-down progress, for the audio fade and UI dimming
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiSleepStatus {
+    /// 0 at start, 1 once fully wound down
+    pub progress: f32,
+    /// Suggested master audio gain 0-1
+    pub audio_gain: f32,
+    pub exhale_scale: f32,
+    pub idle_sec: f32,
+}
+
+/// Kept on the session record of sleep sessions
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiSleepSummary {
+    pub wind_down_sec: f32,
+    /// True when the session ended on the idle timeout rather than a stop
+    pub presumed_asleep: bool,
+    pub final_exhale_scale: f32,
+}
+
+#[cfg(feature = "full")]
+struct SleepWindDown {
+    config: FfiSleepConfig,
+    elapsed_sec: f32,
+    idle_sec: f32,
+}
+
+#[cfg(feature = "full")]
+impl SleepWindDown {
+    fn new(config: FfiSleepConfig) -> Self {
+        Self { config, elapsed_sec: 0.0, idle_sec: 0.0 }
+    }
+
+    fn progress(&self) -> f32 {
+        (self.elapsed_sec / (self.config.wind_down_min * 60.0)).min(1.0)
+    }
+
+    fn exhale_scale(&self) -> f32 {
+        1.0 + (self.config.max_exhale_scale - 1.0) * self.progress()
+    }
+
+    fn asleep(&self) -> bool {
+        self.idle_sec >= self.config.idle_timeout_min * 60.0
+    }
+
+    /// Credit running time; returns true once the user is presumed asleep
+    fn tick(&mut self, dt_sec: f32) -> bool {
+        self.elapsed_sec += dt_sec;
+        self.idle_sec += dt_sec;
+        self.asleep()
+    }
+
+    fn status(&self) -> FfiSleepStatus {
+        FfiSleepStatus {
+            progress: self.progress(),
+            audio_gain: 1.0 - self.progress(),
+            exhale_scale: self.exhale_scale(),
+            idle_sec: self.idle_sec,
+        }
+    }
+
+    fn summary(&self) -> FfiSleepSummary {
+        FfiSleepSummary {
+            wind_down_sec: self.elapsed_sec,
+            presumed_asleep: self.asleep(),
+            final_exhale_scale: self.exhale_scale(),
+        }
+    }
+}
+
+// ============================================================================
+// PANIC SESSION
+// ============================================================================
+
+/// Longest exhale of the builtin library, the strongest vagal brake
+#[cfg(feature = "full")]
+const PANIC_PATTERN_ID: &str = "7-11";
+#[cfg(feature = "full")]
+const PANIC_BINAURAL_STATE: FfiBrainWaveState = FfiBrainWaveState::Delta;
+#[cfg(feature = "full")]
+const PANIC_SESSION_SEC: f32 = 180.0;
+
+/// What a panic session runs, so the shell can start audio in the same step
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPanicSession {
+    pub pattern_id: String,
+    pub binaural_state: FfiBrainWaveState,
+    pub binaural: FfiBinauralConfig,
+    pub duration_sec: f32,
+}
+
+// ============================================================================
+// SESSION EXPORT SCHEMA - zenb-session-v1
+// ============================================================================
+//
+// Exports are written in a schema of their own rather than by serializing
+// FfiSessionRecord directly, so internal structs can change without breaking
+// third-party readers. The layout of a published version never changes:
+// additions go into a new version, and `SessionExport::parse` upgrades any
+// older (or pre-schema) export to the current one.
+//
+// zenb-session-v2 is v1 plus integrity signatures:
+// {
+//   ...v1 fields, with "schema": "zenb-session-v2", "schema_version": 2,
+//   "device_public_key"?: hex ed25519 key of the recording device,
+//   "sessions": [{ ...v1 session fields, "signature"?: hex ed25519 signature }]
+// }
+// See SESSION SIGNATURES for what a signature covers.
+//
+// zenb-session-v1:
+// {
+//   "schema": "zenb-session-v1",
+//   "schema_version": 1,
+//   "exported_at_ms": i64,
+//   "sessions": [{
+//     "id", "started_at_ms", "ended_at_ms", "duration_sec", "pattern_id",
+//     "cycles_completed", "avg_heart_rate"?, "avg_resonance", "avg_adherence"?,
+//     "cycle_adherence": [f32], "partial", "tags": [str], "notes"?, "ibi_count",
+//     "final_belief": { "mode", "confidence", "probabilities": {calm, stress, focus, sleepy, energize} },
+//     "interruptions": [{ "kind", "started_at_ms", "duration_ms", "resume_action"? }],
+//     "time_in_zone": [{ "zone", "seconds" }],
+//     "binaural": [{ "state", "seconds" }],
+//     "goals": [{ "kind", "target", "current", "achieved" }],
+//     "sleep"?: { "wind_down_sec", "presumed_asleep", "final_exhale_scale" }
+//   }]
+// }
+//
+// Enum values are snake_case strings (e.g. "phone_call", "resume_mid_cycle").
+
+#[cfg(feature = "full")]
+const SESSION_EXPORT_SCHEMA: &str = "zenb-session";
+#[cfg(feature = "full")]
+const SESSION_EXPORT_SCHEMA_VERSION: u32 = 2;
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionExport {
+    schema: String,
+    schema_version: u32,
+    exported_at_ms: i64,
+    #[serde(default)]
+    device_public_key: Option<String>,
+    sessions: Vec<SessionV2>,
+}
+
+/// v1 top level, read only to migrate it
+#[cfg(feature = "full")]
+#[derive(Deserialize)]
+struct SessionExportV1 {
+    exported_at_ms: i64,
+    sessions: Vec<SessionV1>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionV2 {
+    #[serde(flatten)]
+    session: SessionV1,
+    #[serde(default)]
+    signature: Option<String>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionV1 {
+    id: String,
+    started_at_ms: i64,
+    ended_at_ms: i64,
+    duration_sec: f32,
+    pattern_id: String,
+    cycles_completed: u64,
+    avg_heart_rate: Option<f32>,
+    avg_resonance: f32,
+    avg_adherence: Option<f32>,
+    cycle_adherence: Vec<f32>,
+    partial: bool,
+    tags: Vec<String>,
+    notes: Option<String>,
+    ibi_count: u32,
+    final_belief: BeliefV1,
+    interruptions: Vec<InterruptionV1>,
+    time_in_zone: Vec<ZoneTimeV1>,
+    binaural: Vec<BinauralV1>,
+    goals: Vec<GoalV1>,
+    sleep: Option<SleepV1>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BeliefV1 {
+    mode: String,
+    confidence: f32,
+    probabilities: BeliefProbabilitiesV1,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BeliefProbabilitiesV1 {
+    calm: f32,
+    stress: f32,
+    focus: f32,
+    sleepy: f32,
+    energize: f32,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InterruptionV1 {
+    kind: String,
+    started_at_ms: i64,
+    duration_ms: u64,
+    resume_action: Option<String>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ZoneTimeV1 {
+    zone: String,
+    seconds: f32,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BinauralV1 {
+    state: String,
+    seconds: f32,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GoalV1 {
+    kind: String,
+    target: f32,
+    current: f32,
+    achieved: bool,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SleepV1 {
+    wind_down_sec: f32,
+    presumed_asleep: bool,
+    final_exhale_scale: f32,
+}
+
+// Explicit names keep the schema independent of enum variant renames
+
+#[cfg(feature = "full")]
+fn belief_mode_name(mode: FfiBeliefMode) -> &'static str {
+    match mode {
+        FfiBeliefMode::Calm => "calm",
+        FfiBeliefMode::Stress => "stress",
+        FfiBeliefMode::Focus => "focus",
+        FfiBeliefMode::Sleepy => "sleepy",
+        FfiBeliefMode::Energize => "energize",
+    }
+}
+
+#[cfg(feature = "full")]
+fn interruption_kind_name(kind: FfiInterruptionKind) -> &'static str {
+    match kind {
+        FfiInterruptionKind::UserPause => "user_pause",
+        FfiInterruptionKind::PhoneCall => "phone_call",
+        FfiInterruptionKind::AppBackgrounded => "app_backgrounded",
+        FfiInterruptionKind::ElevatedHeartRate => "elevated_heart_rate",
+        FfiInterruptionKind::Other => "other",
+    }
+}
+
+#[cfg(feature = "full")]
+fn resume_action_name(action: FfiResumeAction) -> &'static str {
+    match action {
+        FfiResumeAction::ResumeMidCycle => "resume_mid_cycle",
+        FfiResumeAction::RestartCycle => "restart_cycle",
+        FfiResumeAction::Finalize => "finalize",
+    }
+}
+
+#[cfg(feature = "full")]
+fn brain_wave_name(state: FfiBrainWaveState) -> &'static str {
+    match state {
+        FfiBrainWaveState::Delta => "delta",
+        FfiBrainWaveState::Theta => "theta",
+        FfiBrainWaveState::Alpha => "alpha",
+        FfiBrainWaveState::Beta => "beta",
+    }
+}
+
+#[cfg(feature = "full")]
+fn goal_kind_name(kind: FfiGoalKind) -> &'static str {
+    match kind {
+        FfiGoalKind::Coherence => "coherence",
+        FfiGoalKind::Minutes => "minutes",
+        FfiGoalKind::Cycles => "cycles",
+    }
+}
+
+#[cfg(feature = "full")]
+impl From<&FfiSessionRecord> for SessionV1 {
+    fn from(r: &FfiSessionRecord) -> Self {
+        let s = &r.stats;
+        let p = |i: usize| s.final_belief.probabilities.get(i).copied().unwrap_or(0.0);
+        Self {
+            id: r.id.clone(),
+            started_at_ms: r.started_at_ms,
+            ended_at_ms: r.ended_at_ms,
+            duration_sec: s.duration_sec,
+            pattern_id: s.pattern_id.clone(),
+            cycles_completed: s.cycles_completed,
+            avg_heart_rate: s.avg_heart_rate,
+            avg_resonance: s.avg_resonance,
+            avg_adherence: s.avg_adherence,
+            cycle_adherence: s.cycle_adherence.clone(),
+            partial: s.partial,
+            tags: r.tags.clone(),
+            notes: r.notes.clone(),
+            ibi_count: r.ibi_count,
+            final_belief: BeliefV1 {
+                mode: belief_mode_name(s.final_belief.mode).into(),
+                confidence: s.final_belief.confidence,
+                probabilities: BeliefProbabilitiesV1 {
+                    calm: p(0),
+                    stress: p(1),
+                    focus: p(2),
+                    sleepy: p(3),
+                    energize: p(4),
+                },
+            },
+            interruptions: s
+                .interruptions
+                .iter()
+                .map(|i| InterruptionV1 {
+                    kind: interruption_kind_name(i.kind).into(),
+                    started_at_ms: i.started_at_ms,
+                    duration_ms: i.duration_ms,
+                    resume_action: i.resume_action.map(|a| resume_action_name(a).into()),
+                })
+                .collect(),
+            time_in_zone: s
+                .time_in_zone
+                .iter()
+                .map(|z| ZoneTimeV1 { zone: z.zone.clone(), seconds: z.seconds })
+                .collect(),
+            binaural: s
+                .binaural
+                .iter()
+                .map(|b| BinauralV1 { state: brain_wave_name(b.state).into(), seconds: b.seconds })
+                .collect(),
+            goals: s
+                .goals
+                .iter()
+                .map(|g| GoalV1 {
+                    kind: goal_kind_name(g.kind).into(),
+                    target: g.target,
+                    current: g.current,
+                    achieved: g.achieved,
+                })
+                .collect(),
+            sleep: s.sleep.as_ref().map(|z| SleepV1 {
+                wind_down_sec: z.wind_down_sec,
+                presumed_asleep: z.presumed_asleep,
+                final_exhale_scale: z.final_exhale_scale,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl SessionExport {
+    fn new(records: &[FfiSessionRecord], device_public_key: Option<String>) -> Self {
+        Self {
+            schema: format!("{}-v{}", SESSION_EXPORT_SCHEMA, SESSION_EXPORT_SCHEMA_VERSION),
+            schema_version: SESSION_EXPORT_SCHEMA_VERSION,
+            exported_at_ms: Utc::now().timestamp_millis(),
+            device_public_key,
+            sessions: records
+                .iter()
+                .map(|r| SessionV2 { session: SessionV1::from(r), signature: r.signature.clone() })
+                .collect(),
+        }
+    }
+
+    fn to_json(&self) -> Result<String, ZenOneError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ZenOneError::ConfigError(format!("Session export serialization failed: {}", e)))
+    }
+
+    /// Parse an export of any known version, migrating it to the current one
+    fn parse(json: &str) -> Result<Self, ZenOneError> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Invalid session export: {}", e)))?;
+
+        // Pre-schema research packages wrote a bare array of session records
+        if value.is_array() {
+            let records: Vec<FfiSessionRecord> = serde_json::from_value(value)
+                .map_err(|e| ZenOneError::ConfigError(format!("Invalid legacy session export: {}", e)))?;
+            return Ok(Self::new(&records, None));
+        }
+
+        let version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| ZenOneError::ConfigError("Session export has no schema_version".into()))?;
+        match version {
+            1 => {
+                let v1: SessionExportV1 = serde_json::from_value(value)
+                    .map_err(|e| ZenOneError::ConfigError(format!("Invalid {}-v1 export: {}", SESSION_EXPORT_SCHEMA, e)))?;
+                Ok(Self {
+                    schema: format!("{}-v{}", SESSION_EXPORT_SCHEMA, SESSION_EXPORT_SCHEMA_VERSION),
+                    schema_version: SESSION_EXPORT_SCHEMA_VERSION,
+                    exported_at_ms: v1.exported_at_ms,
+                    device_public_key: None,
+                    sessions: v1
+                        .sessions
+                        .into_iter()
+                        .map(|session| SessionV2 { session, signature: None })
+                        .collect(),
+                })
+            }
+            2 => serde_json::from_value(value)
+                .map_err(|e| ZenOneError::ConfigError(format!("Invalid {}-v2 export: {}", SESSION_EXPORT_SCHEMA, e))),
+            v => Err(ZenOneError::ConfigError(format!(
+                "Unsupported session export schema_version {} (latest known is {})",
+                v, SESSION_EXPORT_SCHEMA_VERSION
+            ))),
+        }
+    }
+}
+
+// ============================================================================
+// SESSION SIGNATURES - DEVICE-SIGNED RECORDS
+// ============================================================================
+//
+// Each session is signed with a per-device ed25519 key when it is stored, so
+// an exported history can be shown to be untampered. The signature covers the
+// zenb-session-v1 JSON of the session with `tags` and `notes` emptied (they
+// stay editable afterwards), prefixed with SESSION_SIGNATURE_CONTEXT. The
+// private key never leaves the device and is sealed at rest with SecureVault.
+
+#[cfg(feature = "full")]
+const DEVICE_KEY_FILE: &str = "device_signing_key.bin";
+#[cfg(feature = "full")]
+const SESSION_SIGNATURE_CONTEXT: &[u8] = b"zenb-session-signature-v1\n";
+
+/// Result of checking an export's session signatures
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSignatureReport {
+    /// Hex key the signatures were checked against
+    pub public_key: String,
+    pub total: u32,
+    pub valid: u32,
+    /// Sessions whose content no longer matches their signature
+    pub tampered_ids: Vec<String>,
+    pub unsigned_ids: Vec<String>,
+}
+
+/// Bytes a session signature covers
+#[cfg(feature = "full")]
+fn session_signing_payload(session: &SessionV1) -> Result<Vec<u8>, ZenOneError> {
+    let mut measured = session.clone();
+    measured.tags.clear();
+    measured.notes = None;
+    let mut payload = SESSION_SIGNATURE_CONTEXT.to_vec();
+    serde_json::to_writer(&mut payload, &measured)
+        .map_err(|e| ZenOneError::ConfigError(format!("Session serialization failed: {}", e)))?;
+    Ok(payload)
+}
+
+#[cfg(feature = "full")]
+fn sign_session(key: &SigningKey, session: &SessionV1) -> Result<String, ZenOneError> {
+    let payload = session_signing_payload(session)?;
+    Ok(hex_encode(&key.sign(&payload).to_bytes()))
+}
+
+#[cfg(feature = "full")]
+fn parse_public_key(hex: &str) -> Result<VerifyingKey, ZenOneError> {
+    let bytes: [u8; 32] = hex_decode(hex.trim())
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| ZenOneError::ConfigError("Public key must be 32 bytes of hex".into()))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| ZenOneError::ConfigError(format!("Invalid public key: {}", e)))
+}
+
+/// Load the device key sealed under `passphrase`, creating it on first use
+#[cfg(feature = "full")]
+fn load_or_create_device_key(path: &std::path::Path, passphrase: String) -> Result<SigningKey, ZenOneError> {
+    if passphrase.is_empty() {
+        return Err(ZenOneError::ConfigError("Signing key requires a passphrase".into()));
+    }
+    if path.exists() {
+        let blob = std::fs::read(path)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot read device key: {}", e)))?;
+        let mut secret = SecureVault::new().decrypt_blob(passphrase, blob)?;
+        let key = <[u8; 32]>::try_from(secret.as_slice())
+            .map(|bytes| SigningKey::from_bytes(&bytes))
+            .map_err(|_| ZenOneError::ConfigError("Corrupt device key file".into()));
+        secret.zeroize();
+        return key;
+    }
+
+    let key = SigningKey::generate(&mut OsRng);
+    let mut secret = key.to_bytes();
+    let sealed = SecureVault::new().encrypt_blob(passphrase, secret.to_vec());
+    secret.zeroize();
+    std::fs::write(path, sealed?)
+        .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist device key: {}", e)))?;
+    log::info!("Created device signing key {}", hex_encode(&key.verifying_key().to_bytes()));
+    Ok(key)
+}
+
+/// Check every session of an export (any schema version) against `public_key`
+#[cfg(feature = "full")]
+fn verify_session_export(json: &str, public_key: &str) -> Result<FfiSignatureReport, ZenOneError> {
+    let key = parse_public_key(public_key)?;
+    let export = SessionExport::parse(json)?;
+    let mut report = FfiSignatureReport {
+        public_key: hex_encode(&key.to_bytes()),
+        total: export.sessions.len() as u32,
+        valid: 0,
+        tampered_ids: Vec::new(),
+        unsigned_ids: Vec::new(),
+    };
+    for entry in &export.sessions {
+        let Some(signature) = &entry.signature else {
+            report.unsigned_ids.push(entry.session.id.clone());
+            continue;
+        };
+        let valid = hex_decode(signature)
+            .and_then(|b| <[u8; 64]>::try_from(b).ok())
+            .map(|bytes| Signature::from_bytes(&bytes))
+            .is_some_and(|sig| {
+                session_signing_payload(&entry.session)
+                    .is_ok_and(|payload| key.verify_strict(&payload, &sig).is_ok())
+            });
+        if valid {
+            report.valid += 1;
+        } else {
+            report.tampered_ids.push(entry.session.id.clone());
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(feature = "full")]
+impl SessionStore {
+    /// Sign sessions stored from now on with `key`
+    fn set_signer(&self, key: SigningKey) {
+        *self.signer.lock() = Some(key);
+    }
+
+    /// Hex public half of the device key, once unlocked
+    fn public_key(&self) -> Option<String> {
+        self.signer.lock().as_ref().map(|k| hex_encode(&k.verifying_key().to_bytes()))
+    }
+
+    /// Run `f` with the device key, once unlocked
+    fn with_signer<T>(&self, f: impl FnOnce(&SigningKey) -> T) -> Option<T> {
+        self.signer.lock().as_ref().map(f)
+    }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod signing_tests {
+    use super::*;
+
+    fn record(id: &str, started_at_ms: i64) -> FfiSessionRecord {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "started_at_ms": started_at_ms,
+            "ended_at_ms": started_at_ms + 300_000,
+            "stats": {
+                "duration_sec": 300.0,
+                "cycles_completed": 10,
+                "pattern_id": "4-7-8",
+                "avg_heart_rate": 62.0,
+                "final_belief": FfiBeliefState::default(),
+                "avg_resonance": 0.5,
+            },
+        }))
+        .unwrap()
+    }
+
+    /// Export of one unsigned and two signed sessions, with the key's hex
+    fn signed_export() -> (serde_json::Value, String) {
+        let store = SessionStore::new();
+        store.insert(record("unsigned", 1_000), vec![]);
+        store.set_signer(SigningKey::from_bytes(&[7; 32]));
+        store.insert(record("a", 2_000), vec![]);
+        store.insert(record("b", 3_000), vec![]);
+        let public_key = store.public_key().unwrap();
+        let json = SessionExport::new(&store.all(), Some(public_key.clone())).to_json().unwrap();
+        (serde_json::from_str(&json).unwrap(), public_key)
+    }
+
+    fn session_mut<'a>(export: &'a mut serde_json::Value, id: &str) -> &'a mut serde_json::Value {
+        export["sessions"].as_array_mut().unwrap().iter_mut().find(|s| s["id"] == id).unwrap()
+    }
+
+    #[test]
+    fn signed_sessions_verify() {
+        let (export, public_key) = signed_export();
+        let report = verify_session_export(&export.to_string(), &public_key).unwrap();
+        assert_eq!((report.total, report.valid), (3, 2));
+        assert_eq!(report.unsigned_ids, ["unsigned"]);
+        assert!(report.tampered_ids.is_empty());
+    }
+
+    #[test]
+    fn edited_measurements_are_reported_as_tampered() {
+        let (mut export, public_key) = signed_export();
+        session_mut(&mut export, "b")["duration_sec"] = serde_json::json!(900.0);
+        let report = verify_session_export(&export.to_string(), &public_key).unwrap();
+        assert_eq!(report.valid, 1);
+        assert_eq!(report.tampered_ids, ["b"]);
+    }
+
+    #[test]
+    fn tags_and_notes_stay_editable() {
+        let (mut export, public_key) = signed_export();
+        let session = session_mut(&mut export, "a");
+        session["tags"] = serde_json::json!(["evening"]);
+        session["notes"] = serde_json::json!("felt calm");
+        let report = verify_session_export(&export.to_string(), &public_key).unwrap();
+        assert_eq!(report.valid, 2);
+    }
+
+    #[test]
+    fn malformed_public_keys_are_rejected() {
+        let (export, _) = signed_export();
+        assert!(verify_session_export(&export.to_string(), "abcd").is_err());
+        assert!(verify_session_export(&export.to_string(), &"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn device_key_is_sealed_under_the_passphrase() {
+        let dir = std::env::temp_dir().join(format!("zenone-signing-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(DEVICE_KEY_FILE);
+        std::fs::remove_file(&path).ok();
+
+        assert!(load_or_create_device_key(&path, String::new()).is_err());
+        let created = load_or_create_device_key(&path, "correct horse".into()).unwrap();
+        let loaded = load_or_create_device_key(&path, "correct horse".into()).unwrap();
+        let wrong = load_or_create_device_key(&path, "battery staple".into());
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(created.to_bytes(), loaded.to_bytes());
+        assert!(wrong.is_err());
+    }
+}
+
+// ============================================================================
+// SESSION IMPORT - HISTORY FROM OTHER APPS
+// ============================================================================
+
+/// Source format for `import_sessions`
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiImportFormat {
+    /// Apple Health `export.xml`: mindful sessions, with heart rate averaged over each
+    AppleHealthXml,
+    /// CSV with a header row, one timestamp and one heart-rate column; samples
+    /// are grouped into sessions at gaps longer than five minutes
+    HeartRateCsv,
+}
+
+/// Outcome of an import
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiImportReport {
+    pub imported: u32,
+    /// Sessions overlapping one already in the store
+    pub skipped_duplicates: u32,
+    /// Rows or records that could not be parsed
+    pub skipped_invalid: u32,
+}
+
+/// Pattern id given to imported sessions, which have no pacer pattern of ours
+#[cfg(feature = "full")]
+const IMPORTED_PATTERN_ID: &str = "external";
+#[cfg(feature = "full")]
+const IMPORT_MIN_SESSION_SEC: f32 = 60.0;
+#[cfg(feature = "full")]
+const IMPORT_CSV_SESSION_GAP_MS: i64 = 5 * 60 * 1000;
+#[cfg(feature = "full")]
+const IMPORT_HR_RANGE: std::ops::RangeInclusive<f32> = 25.0..=250.0;
+
+#[cfg(feature = "full")]
+const APPLE_MINDFUL_SESSION: &str = "HKCategoryTypeIdentifierMindfulSession";
+#[cfg(feature = "full")]
+const APPLE_HEART_RATE: &str = "HKQuantityTypeIdentifierHeartRate";
+
+#[cfg(feature = "full")]
+struct ParsedImport {
+    records: Vec<FfiSessionRecord>,
+    skipped_invalid: u32,
+}
+
+#[cfg(feature = "full")]
+fn imported_record(started_at_ms: i64, ended_at_ms: i64, avg_heart_rate: Option<f32>, source: &str) -> FfiSessionRecord {
+    FfiSessionRecord {
+        id: SessionStore::new_session_id(started_at_ms),
+        started_at_ms,
+        ended_at_ms,
+        stats: FfiSessionStats {
+            duration_sec: (ended_at_ms - started_at_ms) as f32 / 1000.0,
+            cycles_completed: 0,
+            pattern_id: IMPORTED_PATTERN_ID.into(),
+            avg_heart_rate,
+            final_belief: FfiBeliefState::default(),
+            avg_resonance: 0.0,
+            avg_adherence: None,
+            cycle_adherence: Vec::new(),
+            interruptions: Vec::new(),
+            partial: false,
+            time_in_zone: Vec::new(),
+            binaural: Vec::new(),
+            goals: Vec::new(),
+            segments: Vec::new(),
+            stages: Vec::new(),
+            cool_down_breathing: None,
+            sleep: None,
+            hrv: None,
+            hrv_spectrum: None,
+        },
+        tags: vec!["imported".into(), source.into()],
+        notes: None,
+        ibi_count: 0,
+        signature: None,
+        utc_offset_min: None,
+    }
+}
+
+#[cfg(feature = "full")]
+fn open_import(path: &str) -> Result<std::io::BufReader<std::fs::File>, ZenOneError> {
+    std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map_err(|e| ZenOneError::ConfigError(format!("Cannot read {}: {}", path, e)))
+}
+
+/// Value of `name="..."` within a single XML tag
+#[cfg(feature = "full")]
+fn xml_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!(" {}=\"", name);
+    let start = tag.find(&needle)? + needle.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// Apple Health dates look like `2024-01-05 07:30:00 -0800`
+#[cfg(feature = "full")]
+fn apple_health_ms(date: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S %z")
+        .ok()
+        .map(|d| d.timestamp_millis())
+}
+
+/// Apple Health exports put each `<Record ...>` opening tag on its own line, so
+/// the (often very large) file is scanned line by line rather than parsed as a tree
+#[cfg(feature = "full")]
+fn import_apple_health(path: &str) -> Result<ParsedImport, ZenOneError> {
+    use std::io::BufRead;
+
+    let mut sessions = Vec::new();
+    let mut heart_rate: Vec<(i64, f32)> = Vec::new();
+    let mut skipped_invalid = 0;
+
+    for line in open_import(path)?.lines() {
+        let line = line.map_err(|e| ZenOneError::ConfigError(format!("Cannot read {}: {}", path, e)))?;
+        let tag = line.trim_start();
+        if !tag.starts_with("<Record ") {
+            continue;
+        }
+        match xml_attr(tag, "type") {
+            Some(APPLE_MINDFUL_SESSION) => {
+                let start = xml_attr(tag, "startDate").and_then(apple_health_ms);
+                let end = xml_attr(tag, "endDate").and_then(apple_health_ms);
+                match (start, end) {
+                    (Some(start), Some(end)) if end > start => sessions.push((start, end)),
+                    _ => skipped_invalid += 1,
+                }
+            }
+            Some(APPLE_HEART_RATE) => {
+                let at = xml_attr(tag, "startDate").and_then(apple_health_ms);
+                let bpm = xml_attr(tag, "value").and_then(|v| v.parse::<f32>().ok());
+                if let (Some(at), Some(bpm)) = (at, bpm) {
+                    heart_rate.push((at, bpm));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    heart_rate.sort_by_key(|(at, _)| *at);
+    let records = sessions
+        .into_iter()
+        .map(|(start, end)| {
+            let from = heart_rate.partition_point(|(at, _)| *at < start);
+            let to = heart_rate.partition_point(|(at, _)| *at <= end);
+            let window = &heart_rate[from..to];
+            let avg = (!window.is_empty())
+                .then(|| window.iter().map(|(_, bpm)| bpm).sum::<f32>() / window.len() as f32);
+            imported_record(start, end, avg, "apple health")
+        })
+        .collect();
+
+    Ok(ParsedImport { records, skipped_invalid })
+}
+
+/// Unix seconds or milliseconds, RFC 3339, or `YYYY-MM-DD HH:MM:SS` (taken as UTC)
+#[cfg(feature = "full")]
+fn csv_timestamp_ms(value: &str) -> Option<i64> {
+    if let Ok(n) = value.parse::<i64>() {
+        // Anything past 1973 in milliseconds is far beyond any plausible date in seconds
+        return Some(if n > 100_000_000_000 { n } else { n * 1000 });
+    }
+    if let Ok(d) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(d.timestamp_millis());
+    }
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|d| d.and_utc().timestamp_millis())
+}
+
+#[cfg(feature = "full")]
+fn import_hr_csv(path: &str) -> Result<ParsedImport, ZenOneError> {
+    use std::io::BufRead;
+
+    let mut lines = open_import(path)?.lines();
+    let header = lines
+        .next()
+        .transpose()
+        .map_err(|e| ZenOneError::ConfigError(format!("Cannot read {}: {}", path, e)))?
+        .ok_or_else(|| ZenOneError::ConfigError("CSV file is empty".into()))?;
+    let columns: Vec<String> = header
+        .split(',')
+        .map(|c| c.trim().trim_matches('"').to_lowercase())
+        .collect();
+    let time_col = columns
+        .iter()
+        .position(|c| c.contains("time") || c.contains("date"))
+        .ok_or_else(|| ZenOneError::ConfigError("CSV has no timestamp column".into()))?;
+    let hr_col = columns
+        .iter()
+        .position(|c| matches!(c.as_str(), "hr" | "bpm" | "heart_rate" | "heartrate" | "heart rate"))
+        .ok_or_else(|| ZenOneError::ConfigError("CSV has no heart-rate column".into()))?;
+
+    let mut samples: Vec<(i64, f32)> = Vec::new();
+    let mut skipped_invalid = 0;
+    for line in lines {
+        let line = line.map_err(|e| ZenOneError::ConfigError(format!("Cannot read {}: {}", path, e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim().trim_matches('"')).collect();
+        let at = fields.get(time_col).and_then(|v| csv_timestamp_ms(v));
+        let bpm = fields
+            .get(hr_col)
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|bpm| IMPORT_HR_RANGE.contains(bpm));
+        match (at, bpm) {
+            (Some(at), Some(bpm)) => samples.push((at, bpm)),
+            _ => skipped_invalid += 1,
+        }
+    }
+    samples.sort_by_key(|(at, _)| *at);
+
+    let mut records = Vec::new();
+    let mut group: Vec<(i64, f32)> = Vec::new();
+    let mut flush = |group: &mut Vec<(i64, f32)>| {
+        if let (Some(first), Some(last)) = (group.first(), group.last()) {
+            if (last.0 - first.0) as f32 / 1000.0 >= IMPORT_MIN_SESSION_SEC {
+                let avg = group.iter().map(|(_, bpm)| bpm).sum::<f32>() / group.len() as f32;
+                records.push(imported_record(first.0, last.0, Some(avg), "hr csv"));
+            }
+        }
+        group.clear();
+    };
+    for sample in samples {
+        if group.last().is_some_and(|last| sample.0 - last.0 > IMPORT_CSV_SESSION_GAP_MS) {
+            flush(&mut group);
+        }
+        group.push(sample);
+    }
+    flush(&mut group);
+
+    Ok(ParsedImport { records, skipped_invalid })
+}
+
+// ============================================================================
+// DEVICE CAPABILITIES - PIPELINE AUTO-CONFIGURATION
+// ============================================================================
+
+/// rPPG algorithm turning skin color into a pulse signal. POS suits most
+/// users; CHROM can hold up better under colored or uneven light; Green is
+/// the simplest and sometimes wins in bright, even daylight.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FfiRppgMethod {
+    #[default]
+    Pos,
+    Chrom,
+    Green,
+}
+
+/// Frame intervals collected before the observed frame rate is checked
+#[cfg(feature = "full")]
+const FRAME_RATE_ADAPT_INTERVALS: usize = 90;
+/// Observed rate this far off the configured one (as a fraction) triggers adaptation
+#[cfg(feature = "full")]
+const FRAME_RATE_ADAPT_TOLERANCE: f32 = 0.1;
+/// Longer intervals are stalls (backgrounding, dropped camera), not the frame rate
+#[cfg(feature = "full")]
+const FRAME_RATE_MAX_INTERVAL_US: i64 = 500_000;
+#[cfg(feature = "full")]
+const RPPG_MIN_WINDOW_SEC: f32 = 1.0;
+#[cfg(feature = "full")]
+const RPPG_MAX_WINDOW_SEC: f32 = 15.0;
+#[cfg(feature = "full")]
+const RPPG_MIN_SAMPLE_RATE_HZ: f32 = 5.0;
+#[cfg(feature = "full")]
+const RPPG_MAX_SAMPLE_RATE_HZ: f32 = 120.0;
+
+/// rPPG analysis window and camera sample rate
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiRppgConfig {
+    /// Length of the heart rate estimation window
+    pub window_sec: f32,
+    /// Rate `process_frame` is fed at
+    pub sample_rate_hz: f32,
+    /// Follow the frame rate actually observed from timestamps
+    pub adapt_sample_rate: bool,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiRppgConfig {
+    fn default() -> Self {
+        Self { window_sec: 3.0, sample_rate_hz: 30.0, adapt_sample_rate: true }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiRppgConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        if !(RPPG_MIN_WINDOW_SEC..=RPPG_MAX_WINDOW_SEC).contains(&self.window_sec) {
+            return Err(ZenOneError::ConfigError(format!(
+                "rPPG window must be {}-{} s, got {}",
+                RPPG_MIN_WINDOW_SEC, RPPG_MAX_WINDOW_SEC, self.window_sec
+            )));
+        }
+        if !(RPPG_MIN_SAMPLE_RATE_HZ..=RPPG_MAX_SAMPLE_RATE_HZ).contains(&self.sample_rate_hz) {
+            return Err(ZenOneError::ConfigError(format!(
+                "rPPG sample rate must be {}-{} Hz, got {}",
+                RPPG_MIN_SAMPLE_RATE_HZ, RPPG_MAX_SAMPLE_RATE_HZ, self.sample_rate_hz
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "full")]
+fn rppg_window_samples(window_sec: f32, sample_rate_hz: f32) -> usize {
+    ((window_sec * sample_rate_hz).round() as usize).max(2)
+}
+
+/// Median frame interval over batches of camera timestamps
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct FrameRateMonitor {
+    last_us: Option<i64>,
+    intervals: Vec<i64>,
+}
+
+#[cfg(feature = "full")]
+impl FrameRateMonitor {
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Observed frame rate each time a full batch of intervals is collected
+    fn observe(&mut self, timestamp_us: i64) -> Option<f32> {
+        if let Some(last) = self.last_us.replace(timestamp_us) {
+            let interval = timestamp_us - last;
+            if interval > 0 && interval <= FRAME_RATE_MAX_INTERVAL_US {
+                self.intervals.push(interval);
+            }
+        }
+        if self.intervals.len() < FRAME_RATE_ADAPT_INTERVALS {
+            return None;
+        }
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.sort_unstable();
+        Some(1_000_000.0 / intervals[intervals.len() / 2] as f32)
+    }
+}
+
+#[cfg(feature = "full")]
+impl From<FfiRppgMethod> for RppgMethod {
+    fn from(method: FfiRppgMethod) -> Self {
+        match method {
+            FfiRppgMethod::Pos => RppgMethod::Pos,
+            FfiRppgMethod::Chrom => RppgMethod::Chrom,
+            FfiRppgMethod::Green => RppgMethod::Green,
+        }
+    }
+}
+
+/// Coarse performance tier of the device
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiCpuClass {
+    Low,
+    Mid,
+    High,
+}
+
+#[cfg(feature = "full")]
+impl FfiCpuClass {
+    /// Fallback when the shell gives no hint, from the number of logical cores
+    fn probe() -> Self {
+        match thread::available_parallelism().map(|n| n.get()).unwrap_or(1) {
+            0..=4 => FfiCpuClass::Low,
+            5..=8 => FfiCpuClass::Mid,
+            _ => FfiCpuClass::High,
+        }
+    }
+}
+
+/// What the shell knows about the device
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiDeviceCapabilities {
+    pub camera_available: bool,
+    /// Frame rate the camera is expected to deliver (30 when unknown)
+    pub camera_fps: Option<f32>,
+    /// Platform performance hint; probed from the core count when absent
+    pub cpu_class: Option<FfiCpuClass>,
+}
+
+/// Pipeline settings selected for a device
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPipelineProfile {
+    pub cpu_class: FfiCpuClass,
+    pub rppg_enabled: bool,
+    pub rppg_window_samples: u32,
+    /// Rate the shell should feed `process_frame` at (may be below the camera fps)
+    pub rppg_sample_rate_hz: f32,
+    /// Cap on `get_state` refreshes (None publishes every tick)
+    pub state_publish_hz: Option<f32>,
+    pub arc_planner_enabled: bool,
+}
+
+#[cfg(feature = "full")]
+const DEFAULT_CAMERA_FPS: f32 = 30.0;
+/// Low-end devices process at most this many camera samples per second
+#[cfg(feature = "full")]
+const LOW_END_RPPG_MAX_FPS: f32 = 15.0;
+/// Longer windows give steadier heart-rate estimates at more CPU per sample
+#[cfg(feature = "full")]
+const RPPG_WINDOW_SEC_LOW: f32 = 3.0;
+#[cfg(feature = "full")]
+const RPPG_WINDOW_SEC_MID: f32 = 4.0;
+#[cfg(feature = "full")]
+const RPPG_WINDOW_SEC_HIGH: f32 = 6.0;
+
+#[cfg(feature = "full")]
+impl FfiPipelineProfile {
+    fn for_device(caps: &FfiDeviceCapabilities) -> Result<Self, ZenOneError> {
+        let camera_fps = caps.camera_fps.unwrap_or(DEFAULT_CAMERA_FPS);
+        if !(camera_fps.is_finite() && camera_fps > 0.0) {
+            return Err(ZenOneError::ConfigError(format!("Invalid camera fps: {}", camera_fps)));
+        }
+        let cpu_class = caps.cpu_class.unwrap_or_else(FfiCpuClass::probe);
+
+        let (window_sec, sample_rate_hz, state_publish_hz, arc_planner_enabled) = match cpu_class {
+            FfiCpuClass::Low => (RPPG_WINDOW_SEC_LOW, camera_fps.min(LOW_END_RPPG_MAX_FPS), Some(10.0), false),
+            FfiCpuClass::Mid => (RPPG_WINDOW_SEC_MID, camera_fps, Some(30.0), true),
+            FfiCpuClass::High => (RPPG_WINDOW_SEC_HIGH, camera_fps, None, true),
+        };
+
+        Ok(FfiPipelineProfile {
+            cpu_class,
+            rppg_enabled: caps.camera_available,
+            rppg_window_samples: (window_sec * sample_rate_hz).round() as u32,
+            rppg_sample_rate_hz: sample_rate_hz,
+            state_publish_hz,
+            arc_planner_enabled,
+        })
+    }
+}
+
+// ============================================================================
+// PIPELINE BENCHMARK - RECORDED-SESSION PLAYBACK
+// ============================================================================
+
+#[cfg(feature = "full")]
+const BENCHMARK_RECORDING_EXT: &str = "json";
+
+/// Built-in reference recordings: (name, HR at start, HR at end, noise
+/// amplitude). Synthetic and seeded, so every build replays identical data.
+#[cfg(feature = "full")]
+const REFERENCE_RECORDINGS: [(&str, f32, f32, f32); 3] = [
+    ("reference-rest", 64.0, 64.0, 0.3),
+    ("reference-ramp", 60.0, 96.0, 0.3),
+    ("reference-noisy", 72.0, 72.0, 1.5),
+];
+#[cfg(feature = "full")]
+const REFERENCE_DURATION_SEC: f32 = 90.0;
+#[cfg(feature = "full")]
+const REFERENCE_FRAME_RATE_HZ: f32 = 30.0;
+
+/// One camera sample from a recorded session, with the reference heart rate
+/// (chest strap / ECG) at that instant when one was captured
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiRecordedFrame {
+    pub timestamp_us: i64,
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub reference_hr: Option<f32>,
+}
+
+/// Anonymized recording: per-frame RGB means only, no images or identifiers
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiBenchmarkRecording {
+    pub name: String,
+    pub frames: Vec<FfiRecordedFrame>,
+}
+
+/// Accuracy and cost of one recording replayed under one configuration
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiBenchmarkResult {
+    pub recording: String,
+    pub profile: FfiPipelineProfile,
+    /// Frames fed to the pipeline after decimating to the profile's rate
+    pub frames_processed: u32,
+    /// Mean absolute HR error against the reference (None without overlap)
+    pub hr_mae_bpm: Option<f32>,
+    /// Share of reference-labelled frames that had an HR estimate
+    pub hr_coverage: f32,
+    /// Frame submission until its results are published in the frame
+    pub mean_latency_us: f32,
+    pub p95_latency_us: f32,
+    /// Process CPU time (all threads) per second of recording; run with the
+    /// app otherwise idle for a clean figure
+    pub cpu_ms_per_sec: f32,
+}
+
+/// Replays recorded sessions through the full actor pipeline (runtime actor,
+/// signal actor, frame publishing) so accuracy and cost can be compared
+/// across device configurations before release.
+#[cfg(feature = "full")]
+pub struct PipelineBenchmark {
+    recordings: Mutex<Vec<FfiBenchmarkRecording>>,
+}
+
+#[cfg(feature = "full")]
+impl Default for PipelineBenchmark {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl PipelineBenchmark {
+    pub fn new() -> Self {
+        Self { recordings: Mutex::new(Vec::new()) }
+    }
+
+    /// Load every `.json` recording in `dir` (e.g. the bundled dataset);
+    /// returns how many were loaded
+    pub fn load_recordings(&self, dir: String) -> Result<u32, ZenOneError> {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot read recordings dir: {}", e)))?;
+        let mut paths: Vec<std::path::PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == BENCHMARK_RECORDING_EXT))
+            .collect();
+        paths.sort();
+
+        let mut loaded = 0;
+        for path in paths {
+            let json = std::fs::read_to_string(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read {}: {}", path.display(), e)))?;
+            let recording: FfiBenchmarkRecording = serde_json::from_str(&json)
+                .map_err(|e| ZenOneError::ConfigError(format!("Invalid recording {}: {}", path.display(), e)))?;
+            self.add_recording(recording)?;
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// Add the built-in reference recordings; returns how many were added
+    pub fn load_reference_dataset(&self) -> u32 {
+        for (seed, &(name, start_hr, end_hr, noise)) in REFERENCE_RECORDINGS.iter().enumerate() {
+            let recording = synthetic_recording(name, start_hr, end_hr, noise, seed as u64 + 1);
+            self.add_recording(recording).expect("reference recordings are valid");
+        }
+        REFERENCE_RECORDINGS.len() as u32
+    }
+
+    /// Add a recording; frames must be non-empty and in timestamp order
+    pub fn add_recording(&self, recording: FfiBenchmarkRecording) -> Result<(), ZenOneError> {
+        if recording.frames.is_empty() {
+            return Err(ZenOneError::ConfigError(format!("Recording '{}' has no frames", recording.name)));
+        }
+        if recording.frames.windows(2).any(|w| w[1].timestamp_us < w[0].timestamp_us) {
+            return Err(ZenOneError::ConfigError(format!("Recording '{}' is not in timestamp order", recording.name)));
+        }
+        let mut recordings = self.recordings.lock();
+        recordings.retain(|r| r.name != recording.name);
+        recordings.push(recording);
+        Ok(())
+    }
+
+    pub fn recording_names(&self) -> Vec<String> {
+        self.recordings.lock().iter().map(|r| r.name.clone()).collect()
+    }
+
+    /// Replay every recording under every configuration, each on a fresh runtime
+    pub fn run(&self, configs: Vec<FfiDeviceCapabilities>) -> Result<Vec<FfiBenchmarkResult>, ZenOneError> {
+        let recordings = self.recordings.lock().clone();
+        if recordings.is_empty() {
+            return Err(ZenOneError::ConfigError("No recordings loaded".into()));
+        }
+        let mut results = Vec::with_capacity(recordings.len() * configs.len());
+        for caps in &configs {
+            for recording in &recordings {
+                results.push(Self::replay(recording, caps)?);
+            }
+        }
+        Ok(results)
+    }
+
+    fn replay(recording: &FfiBenchmarkRecording, caps: &FfiDeviceCapabilities) -> Result<FfiBenchmarkResult, ZenOneError> {
+        let runtime = ZenOneRuntime::new();
+        let profile = runtime.configure_for_device(caps.clone())?;
+        // Feed at the profile's rate, as the shell would
+        let period_us = (1_000_000.0 / profile.rppg_sample_rate_hz) as i64;
+        // Absorbs timestamp jitter so a 30 fps recording isn't thinned at 30 Hz
+        let slack_us = period_us / 4;
+
+        let mut latencies_us: Vec<f32> = Vec::with_capacity(recording.frames.len());
+        let cpu_started = cpu_time::ProcessTime::now();
+        let mut next_due_us = i64::MIN;
+        let mut abs_error_sum = 0.0;
+        let mut labelled = 0u32;
+        let mut matched = 0u32;
+        for frame in &recording.frames {
+            if frame.timestamp_us + slack_us < next_due_us {
+                continue;
+            }
+            next_due_us = next_due_us.max(frame.timestamp_us - slack_us) + period_us;
+
+            let started = Instant::now();
+            runtime.process_frame(frame.r, frame.g, frame.b, frame.timestamp_us);
+            runtime.flush();
+            latencies_us.push(started.elapsed().as_micros() as f32);
+
+            if let Some(reference) = frame.reference_hr {
+                labelled += 1;
+                if let Some(hr) = runtime.latest_frame.load().heart_rate {
+                    abs_error_sum += (hr - reference).abs();
+                    matched += 1;
+                }
+            }
+        }
+
+        let cpu_ms = cpu_started.elapsed().as_secs_f32() * 1000.0;
+        let first_us = recording.frames.first().map_or(0, |f| f.timestamp_us);
+        let last_us = recording.frames.last().map_or(0, |f| f.timestamp_us);
+        let duration_sec = ((last_us - first_us) as f32 / 1_000_000.0).max(1e-3);
+        let mean_latency_us = latencies_us.iter().sum::<f32>() / latencies_us.len().max(1) as f32;
+        latencies_us.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let p95_latency_us = latencies_us
+            .get((latencies_us.len() * 95 / 100).min(latencies_us.len().saturating_sub(1)))
+            .copied()
+            .unwrap_or(0.0);
+
+        Ok(FfiBenchmarkResult {
+            recording: recording.name.clone(),
+            profile,
+            frames_processed: latencies_us.len() as u32,
+            hr_mae_bpm: (matched > 0).then(|| abs_error_sum / matched as f32),
+            hr_coverage: if labelled > 0 { matched as f32 / labelled as f32 } else { 0.0 },
+            mean_latency_us,
+            p95_latency_us,
+            cpu_ms_per_sec: cpu_ms / duration_sec,
+        })
+    }
+}
+
+/// Camera means over a pulsing face: the green channel carries most of the
+/// pulse, with slow illumination drift, a breathing-rate wobble and seeded
+/// xorshift noise. HR moves linearly from `start_hr` to `end_hr`.
+#[cfg(feature = "full")]
+fn synthetic_recording(name: &str, start_hr: f32, end_hr: f32, noise: f32, seed: u64) -> FfiBenchmarkRecording {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut next_noise = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        ((state >> 11) as f32 / (1u64 << 53) as f32 * 2.0 - 1.0) * noise
+    };
+
+    let frame_count = (REFERENCE_DURATION_SEC * REFERENCE_FRAME_RATE_HZ) as usize;
+    let dt = 1.0 / REFERENCE_FRAME_RATE_HZ;
+    let mut pulse_phase = 0.0f32;
+    let frames = (0..frame_count)
+        .map(|i| {
+            let t = i as f32 * dt;
+            let hr = start_hr + (end_hr - start_hr) * t / REFERENCE_DURATION_SEC;
+            pulse_phase += std::f32::consts::TAU * hr / 60.0 * dt;
+            let pulse = pulse_phase.sin() + 0.3 * (2.0 * pulse_phase).sin();
+            let drift = 2.0 * (std::f32::consts::TAU * 0.02 * t).sin() + 0.5 * (std::f32::consts::TAU * 0.25 * t).sin();
+            FfiRecordedFrame {
+                timestamp_us: (t * 1_000_000.0) as i64,
+                r: 150.0 + drift + 0.3 * pulse + next_noise(),
+                g: 110.0 + drift + 1.0 * pulse + next_noise(),
+                b: 90.0 + drift + 0.15 * pulse + next_noise(),
+                reference_hr: Some(hr),
+            }
+        })
+        .collect();
+    FfiBenchmarkRecording { name: name.to_string(), frames }
+}
+
+// ============================================================================
+// COMMAND RECORDING - RECORD & REPLAY
+// ============================================================================
+
+#[cfg(feature = "full")]
+const COMMAND_RECORDING_VERSION: u32 = 2;
+/// Before heart rate sources: only camera results were recorded as signal
+/// events, and strap samples were handled from their commands
+#[cfg(feature = "full")]
+const COMMAND_RECORDING_V1: u32 = 1;
+
+/// First line of a recording file
+#[cfg(feature = "full")]
+#[derive(Serialize, Deserialize)]
+struct RecordingHeader {
+    version: u32,
+    started_at_ms: i64,
+    pattern_id: String,
+}
+
+/// One recording line as read back
+#[cfg(feature = "full")]
+#[derive(Deserialize)]
+struct RecordedLine {
+    offset_us: u64,
+    entry: RecordedEntry,
+}
+
+#[cfg(feature = "full")]
+#[derive(Deserialize)]
+enum RecordedEntry {
+    Command(RuntimeCommand),
+    Signal(SignalEvent),
+}
+
+#[cfg(feature = "full")]
+#[derive(Deserialize)]
+struct RecordedLineV1 {
+    offset_us: u64,
+    entry: RecordedEntryV1,
+}
+
+#[cfg(feature = "full")]
+#[derive(Deserialize)]
+enum RecordedEntryV1 {
+    Command(RuntimeCommand),
+    Signal(SignalEventV1),
+}
+
+/// `SignalEvent` as version 1 recorded it, all from the camera
+#[cfg(feature = "full")]
+#[derive(Deserialize)]
+enum SignalEventV1 {
+    Result {
+        hr: f32,
+        confidence: f32,
+        unfiltered_hr: f32,
+        filter_confidence: f32,
+        quality: Option<SignalQuality>,
+        raw_hr: f32,
+        raw_confidence: f32,
+        timestamp_us: i64,
+    },
+    Beat {
+        ibi_ms: f32,
+        timestamp_us: i64,
+    },
+    HrvSpectrum(FfiHrvSpectrum),
+    Respiration {
+        value: f32,
+        timestamp_us: i64,
+    },
+}
+
+#[cfg(feature = "full")]
+impl From<SignalEventV1> for SignalEvent {
+    fn from(event: SignalEventV1) -> Self {
+        match event {
+            // Version 1 applied every camera result; there was no source to select
+            SignalEventV1::Result {
+                hr, confidence, unfiltered_hr, filter_confidence, quality, raw_hr, raw_confidence, timestamp_us,
+            } => SignalEvent::HeartRate {
+                reading: HrReading {
+                    source: FfiHrSourceKind::Camera,
+                    hr,
+                    confidence,
+                    interpolated: false,
+                    timestamp_us,
+                    camera: Some(CameraHeartRate { unfiltered_hr, filter_confidence, quality, raw_hr, raw_confidence }),
+                },
+                selected: true,
+            },
+            SignalEventV1::Beat { ibi_ms, timestamp_us } => {
+                SignalEvent::Beat { ibi_ms, source: FfiHrSourceKind::Camera, timestamp_us }
+            }
+            SignalEventV1::HrvSpectrum(spectrum) => SignalEvent::HrvSpectrum(spectrum),
+            SignalEventV1::Respiration { value, timestamp_us } => SignalEvent::Respiration { value, timestamp_us },
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl RecordedLine {
+    fn parse(line: &str, version: u32) -> serde_json::Result<Self> {
+        if version == COMMAND_RECORDING_V1 {
+            let line: RecordedLineV1 = serde_json::from_str(line)?;
+            let entry = match line.entry {
+                RecordedEntryV1::Command(cmd) => RecordedEntry::Command(cmd),
+                RecordedEntryV1::Signal(event) => RecordedEntry::Signal(event.into()),
+            };
+            return Ok(Self { offset_us: line.offset_us, entry });
+        }
+        serde_json::from_str(line)
+    }
+}
+
+/// `RecordedLine` borrowing what the actor is about to handle
+#[cfg(feature = "full")]
+#[derive(Serialize)]
+struct RecordedLineRef<'a> {
+    offset_us: u64,
+    entry: RecordedEntryRef<'a>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Serialize)]
+enum RecordedEntryRef<'a> {
+    Command(&'a RuntimeCommand),
+    Signal(&'a SignalEvent),
+}
+
+/// Outcome of replaying a recording
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiReplayReport {
+    pub commands: u32,
+    pub signal_events: u32,
+    /// Recorded time covered by the replay
+    pub duration_sec: f32,
+    pub final_state: FfiRuntimeState,
+    pub violations: Vec<FfiSafetyViolation>,
+    pub events: Vec<FfiRuntimeEvent>,
+}
+
+#[cfg(feature = "full")]
+struct ActiveRecording {
+    writer: std::io::BufWriter<std::fs::File>,
+    started: Instant,
+    entries: u64,
+}
+
+/// Appends actor inputs to a JSON-lines file while a recording is active
+#[cfg(feature = "full")]
+struct CommandRecorder {
+    active: Mutex<Option<ActiveRecording>>,
+}
+
+#[cfg(feature = "full")]
+impl CommandRecorder {
+    fn new() -> Self {
+        Self { active: Mutex::new(None) }
+    }
+
+    fn start(&self, path: &std::path::Path, started: Instant, header: &RecordingHeader) -> Result<(), ZenOneError> {
+        use std::io::Write;
+
+        let mut active = self.active.lock();
+        if active.is_some() {
+            return Err(ZenOneError::ConfigError("A command recording is already running".into()));
+        }
+        let file = std::fs::File::create(path)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot create recording: {}", e)))?;
+        let mut writer = std::io::BufWriter::new(file);
+        let json = serde_json::to_string(header).map_err(|e| ZenOneError::ConfigError(e.to_string()))?;
+        writeln!(writer, "{}", json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot write recording: {}", e)))?;
+        *active = Some(ActiveRecording { writer, started, entries: 0 });
+        Ok(())
+    }
+
+    fn stop(&self) -> Result<u64, ZenOneError> {
+        use std::io::Write;
+
+        let mut recording = self.active.lock().take()
+            .ok_or_else(|| ZenOneError::ConfigError("No command recording is running".into()))?;
+        recording.writer.flush()
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot write recording: {}", e)))?;
+        Ok(recording.entries)
+    }
+
+    fn is_active(&self) -> bool {
+        self.active.lock().is_some()
+    }
+
+    fn record_command(&self, cmd: &RuntimeCommand, now: Instant) {
+        // Control-flow commands and replayed signals aren't inputs
+        if matches!(cmd, RuntimeCommand::Flush(_) | RuntimeCommand::Shutdown | RuntimeCommand::InjectSignal(_)) {
+            return;
+        }
+        self.write(RecordedEntryRef::Command(cmd), now);
+    }
+
+    fn record_signal(&self, event: &SignalEvent, now: Instant) {
+        self.write(RecordedEntryRef::Signal(event), now);
+    }
+
+    fn write(&self, entry: RecordedEntryRef, now: Instant) {
+        use std::io::Write;
+
+        let mut active = self.active.lock();
+        let Some(recording) = active.as_mut() else { return };
+        let line = RecordedLineRef {
+            offset_us: now.saturating_duration_since(recording.started).as_micros() as u64,
+            entry,
+        };
+        let written = serde_json::to_string(&line)
+            .map_err(|e| e.to_string())
+            .and_then(|json| writeln!(recording.writer, "{}", json).map_err(|e| e.to_string()));
+        match written {
+            Ok(()) => recording.entries += 1,
+            Err(e) => log::error!("CommandRecorder: Dropped entry: {}", e),
+        }
+    }
+}
+
+/// Replay on a `ManualClock` advanced to each entry's offset. Heart rate
+/// inputs are skipped and the recorded signal results injected instead, so
+/// the replay sees exactly the heart rate the user's device computed.
+/// Version 1 recordings predate source selection: their strap samples are
+/// fed to the default source as commands, as they were handled then.
+#[cfg(feature = "full")]
+fn replay_command_recording(path: &str) -> Result<FfiReplayReport, ZenOneError> {
+    use std::io::BufRead;
+
+    let file = std::fs::File::open(path)
+        .map_err(|e| ZenOneError::ConfigError(format!("Cannot open recording: {}", e)))?;
+    let mut lines = std::io::BufReader::new(file).lines();
+    let read_error = |e: std::io::Error| ZenOneError::ConfigError(format!("Cannot read recording: {}", e));
+
+    let header_line = lines.next()
+        .ok_or_else(|| ZenOneError::ConfigError("Recording is empty".into()))?
+        .map_err(read_error)?;
+    let header: RecordingHeader = serde_json::from_str(&header_line)
+        .map_err(|e| ZenOneError::ConfigError(format!("Invalid recording header: {}", e)))?;
+    if !(COMMAND_RECORDING_V1..=COMMAND_RECORDING_VERSION).contains(&header.version) {
+        return Err(ZenOneError::ConfigError(format!("Unsupported recording version {}", header.version)));
+    }
+
+    let clock = Arc::new(ManualClock::new(header.started_at_ms));
+    let runtime = ZenOneRuntime::with_clock(header.pattern_id, clock.clone());
+    let mut elapsed = Duration::ZERO;
+    let mut commands = 0;
+    let mut signal_events = 0;
+    for (index, line) in lines.enumerate() {
+        let line = line.map_err(read_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let recorded = RecordedLine::parse(&line, header.version)
+            .map_err(|e| ZenOneError::ConfigError(format!("Invalid recording line {}: {}", index + 2, e)))?;
+        let offset = Duration::from_micros(recorded.offset_us);
+        if offset > elapsed {
+            // Everything earlier must land before virtual time moves on
+            runtime.flush();
+            clock.advance(offset - elapsed);
+            elapsed = offset;
+        }
+        let cmd = match recorded.entry {
+            RecordedEntry::Command(RuntimeCommand::ProcessFrame { .. } | RuntimeCommand::ProcessFrames(_)) => continue,
+            RecordedEntry::Command(
+                RuntimeCommand::HrSample { .. }
+                | RuntimeCommand::ManualHrSample { .. }
+                | RuntimeCommand::SimulatedHrSample { .. }
+                | RuntimeCommand::SetHrSimulator(_),
+            ) if header.version > COMMAND_RECORDING_V1 => continue,
+            RecordedEntry::Command(cmd) => {
+                commands += 1;
+                cmd
+            }
+            RecordedEntry::Signal(event) => {
+                signal_events += 1;
+                RuntimeCommand::InjectSignal(event)
+            }
+        };
+        let _ = runtime.cmd_tx.send(cmd);
+    }
+    runtime.flush();
+
+    Ok(FfiReplayReport {
+        commands,
+        signal_events,
+        duration_sec: elapsed.as_secs_f32(),
+        final_state: runtime.get_state(),
+        violations: runtime.get_safety_violations(),
+        events: runtime.poll_events(),
+    })
+}
+
+// ============================================================================
+// HEADLESS SIMULATION
+// ============================================================================
+
+#[cfg(feature = "full")]
+const SIMULATION_TICK_HZ: u32 = 10;
+#[cfg(feature = "full")]
+const SIMULATION_MAX_SEC: f32 = 4.0 * 3600.0;
+
+/// Synthetic heart rate for a simulated session: a linear drift from
+/// `start_bpm` to `end_bpm`, breath-locked RSA and uniform noise
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSimulatedHrProfile {
+    pub start_bpm: f32,
+    pub end_bpm: f32,
+    /// Peak RSA swing, rising on inhale and falling on exhale
+    pub rsa_amplitude_bpm: f32,
+    pub noise_bpm: f32,
+    /// Same seed, same noise
+    pub seed: u64,
+}
+
+#[cfg(feature = "full")]
+impl FfiSimulatedHrProfile {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        let bpm_ok = |bpm: f32| bpm.is_finite() && (30.0..=220.0).contains(&bpm);
+        if !bpm_ok(self.start_bpm) || !bpm_ok(self.end_bpm) {
+            return Err(ZenOneError::ConfigError("Simulated heart rate must be within 30-220 bpm".into()));
+        }
+        let swing_ok = |bpm: f32| bpm.is_finite() && (0.0..=30.0).contains(&bpm);
+        if !swing_ok(self.rsa_amplitude_bpm) || !swing_ok(self.noise_bpm) {
+            return Err(ZenOneError::ConfigError("RSA amplitude and noise must be within 0-30 bpm".into()));
+        }
+        Ok(())
+    }
+
+    /// `progress` through the session and `cycle_progress` through the breath, both 0-1
+    fn sample(&self, progress: f32, cycle_progress: f32, rng: &mut impl rand::Rng) -> f32 {
+        let drift = self.start_bpm + (self.end_bpm - self.start_bpm) * progress;
+        let rsa = self.rsa_amplitude_bpm * (cycle_progress * std::f32::consts::TAU).sin();
+        let noise = if self.noise_bpm > 0.0 { rng.gen_range(-self.noise_bpm..=self.noise_bpm) } else { 0.0 };
+        (drift + rsa + noise).clamp(30.0, 220.0)
+    }
+}
+
+/// Breathing rate at which the generator's RSA peaks
+#[cfg(feature = "full")]
+const HR_SIMULATOR_RESONANCE_BPM: f32 = 6.0;
+/// Spontaneous breathing assumed while no session paces the user
+#[cfg(feature = "full")]
+const HR_SIMULATOR_FREE_BREATHING_BPM: f32 = 12.0;
+
+/// Live synthetic heart rate that stands in for a sensor, e.g. for demos,
+/// screenshots and end-to-end tests on machines without a camera
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiHrSimulatorConfig {
+    pub baseline_bpm: f32,
+    /// Peak-to-peak RSA swing when breathing at about 6 breaths/min
+    pub rsa_amplitude_bpm: f32,
+    /// Uniform beat-to-beat jitter
+    pub noise_bpm: f32,
+    /// Same seed, same noise
+    pub seed: u64,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiHrSimulatorConfig {
+    fn default() -> Self {
+        Self { baseline_bpm: 68.0, rsa_amplitude_bpm: 8.0, noise_bpm: 1.5, seed: 0 }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiHrSimulatorConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        FfiSimulatedHrProfile {
+            start_bpm: self.baseline_bpm,
+            end_bpm: self.baseline_bpm,
+            rsa_amplitude_bpm: self.rsa_amplitude_bpm,
+            noise_bpm: self.noise_bpm,
+            seed: self.seed,
+        }
+        .validate()
+    }
+}
+
+/// Generates beats from the pacer: heart rate follows lung volume, rising on
+/// inhale and falling on exhale, with more swing the closer breathing gets to
+/// resonance
+#[cfg(feature = "full")]
+struct HrSimulator {
+    config: FfiHrSimulatorConfig,
+    rng: rand::rngs::StdRng,
+    next_beat_us: Option<i64>,
+}
+
+#[cfg(feature = "full")]
+impl HrSimulator {
+    fn new(config: FfiHrSimulatorConfig) -> Self {
+        use rand::SeedableRng;
+
+        let rng = rand::rngs::StdRng::seed_from_u64(config.seed);
+        Self { config, rng, next_beat_us: None }
+    }
+
+    /// Intervals of the beats due by `timestamp_us`, for `volume` (0 empty,
+    /// 1 full) at `rate_bpm` breaths per minute
+    fn advance(&mut self, volume: f32, rate_bpm: f32, timestamp_us: i64) -> Vec<f32> {
+        // Start over after a pause or a jump in the host's timestamps
+        let mut next_us = match self.next_beat_us {
+            Some(next_us) if (timestamp_us - next_us).abs() <= HR_INTERP_MAX_GAP_US => next_us,
+            _ => timestamp_us,
+        };
+        let mut intervals = Vec::new();
+        while next_us <= timestamp_us {
+            let ibi_ms = 60_000.0 / self.heart_rate(volume, rate_bpm);
+            intervals.push(ibi_ms);
+            next_us += (ibi_ms * 1000.0) as i64;
+        }
+        self.next_beat_us = Some(next_us);
+        intervals
+    }
+
+    fn heart_rate(&mut self, volume: f32, rate_bpm: f32) -> f32 {
+        use rand::Rng;
+
+        let detuning = (rate_bpm - HR_SIMULATOR_RESONANCE_BPM) / 4.0;
+        let rsa = self.config.rsa_amplitude_bpm * (volume - 0.5) / (1.0 + detuning * detuning);
+        let noise = self.config.noise_bpm;
+        let noise = if noise > 0.0 { self.rng.gen_range(-noise..=noise) } else { 0.0 };
+        (self.config.baseline_bpm + rsa + noise).clamp(30.0, 220.0)
+    }
+}
+
+/// Runtime state once per simulated second
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSimulationPoint {
+    pub t_sec: f32,
+    pub heart_rate: f32,
+    pub phase: FfiPhase,
+    pub cycles_completed: u64,
+    pub tempo_scale: f32,
+    pub belief: FfiBeliefState,
+    pub resonance: f32,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSimulationResult {
+    pub stats: FfiSessionStats,
+    pub trajectory: Vec<FfiSimulationPoint>,
+    pub violations: Vec<FfiSafetyViolation>,
+    pub events: Vec<FfiRuntimeEvent>,
+}
+
+/// Drive a fresh runtime on a `ManualClock`: tick at `SIMULATION_TICK_HZ`,
+/// feed one simulated HR sample per second and flush so each step sees the
+/// previous one's results. Ends early if the runtime stops the session.
+#[cfg(feature = "full")]
+fn simulate_session(
+    pattern: &BreathPattern,
+    duration_sec: f32,
+    hr_profile: &FfiSimulatedHrProfile,
+) -> Result<FfiSimulationResult, ZenOneError> {
+    use rand::SeedableRng;
+
+    if !(duration_sec.is_finite() && duration_sec > 0.0 && duration_sec <= SIMULATION_MAX_SEC) {
+        return Err(ZenOneError::ConfigError(format!(
+            "Simulation length must be within 0-{} s", SIMULATION_MAX_SEC
+        )));
+    }
+    hr_profile.validate()?;
+
+    let clock = Arc::new(ManualClock::new(Utc::now().timestamp_millis()));
+    let sim = ZenOneRuntime::with_clock(pattern.id.clone(), clock.clone());
+    if !builtin_patterns().contains_key(&pattern.id) {
+        sim.patterns.add(FfiBreathPattern::from(pattern))?;
+        sim.load_pattern(pattern.id.clone());
+    }
+    sim.set_preparation_config(FfiPreparationConfig { countdown_sec: 0.0, min_signal_quality: 0.0 });
+    sim.set_hr_source(FfiHrSourceKind::Simulated);
+    sim.start_session()?;
+    sim.flush();
+    if sim.get_state().status != FfiRuntimeStatus::Running {
+        return Err(ZenOneError::SafetyViolation("Simulated session was blocked by the safety monitor".into()));
+    }
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(hr_profile.seed);
+    let dt_sec = 1.0 / SIMULATION_TICK_HZ as f32;
+    let steps = (duration_sec * SIMULATION_TICK_HZ as f32).ceil() as u64;
+    let mut trajectory = Vec::with_capacity(duration_sec.ceil() as usize);
+    for step in 1..=steps {
+        let t_sec = step as f32 * dt_sec;
+        let timestamp_us = step as i64 * 1_000_000 / SIMULATION_TICK_HZ as i64;
+        clock.advance(Duration::from_secs_f32(dt_sec));
+        sim.tick(dt_sec, timestamp_us);
+        if step % SIMULATION_TICK_HZ as u64 != 0 {
+            continue;
+        }
+
+        sim.flush();
+        let cycle_progress = sim.latest_frame.load().phase_progress;
+        let heart_rate = hr_profile.sample(t_sec / duration_sec, cycle_progress, &mut rng);
+        let rr_intervals_ms = vec![60_000.0 / heart_rate];
+        let _ = sim.cmd_tx.send(RuntimeCommand::SimulatedHrSample { bpm: heart_rate, rr_intervals_ms, timestamp_us });
+        sim.flush();
+
+        let state = sim.get_state();
+        if !matches!(state.status, FfiRuntimeStatus::Running | FfiRuntimeStatus::Paused) {
+            break;
+        }
+        trajectory.push(FfiSimulationPoint {
+            t_sec,
+            heart_rate,
+            phase: state.phase,
+            cycles_completed: state.cycles_completed,
+            tempo_scale: state.tempo_scale,
+            belief: state.belief,
+            resonance: state.resonance.coherence_score,
+        });
+    }
+
+    Ok(FfiSimulationResult {
+        stats: sim.stop_session(),
+        trajectory,
+        violations: sim.get_safety_violations(),
+        events: sim.poll_events(),
+    })
+}
+
+// ============================================================================
+// LITE RUNTIME - WATCH-SIZED PACER
+// ============================================================================
+
+/// Pacer-only runtime for watchOS / Wear OS memory budgets: patterns, the
+/// phase machine and the safety specs, without the engine, rPPG, vault, audio
+/// or a background thread. The host drives it from its own display timer.
+/// Built with `--features lite`, which exports nothing else.
+pub struct ZenOneLiteRuntime {
+    inner: Mutex<LiteInner>,
+    safety: SafetyMonitor,
+}
+
+struct LiteInner {
+    pattern: BreathPattern,
+    phase_machine: PhaseMachine,
+    status: FfiRuntimeStatus,
+    tempo_scale: f32,
+    tempo_target: f32,
+    /// Running session time, advanced by ticks
+    elapsed_sec: f32,
+    /// From the watch's own sensor, if any
+    heart_rate: Option<f32>,
+}
+
+/// Summary of a lite session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiLiteSessionSummary {
+    pub pattern_id: String,
+    pub duration_sec: f32,
+    pub cycles_completed: u64,
+}
+
+impl LiteInner {
+    fn spec_state(&self) -> SpecState {
+        SpecState { tempo_scale: self.tempo_scale, status: self.status, uncertainty: 0.0 }
+    }
+
+    fn frame(&self) -> FfiFrameLite {
+        FfiFrameLite {
+            phase: FfiPhase::from(self.phase_machine.phase),
+            phase_progress: self.phase_machine.cycle_phase_norm(),
+            heart_rate: self.heart_rate,
+        }
+    }
+}
+
+impl Default for ZenOneLiteRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZenOneLiteRuntime {
+    /// Create a lite runtime with the default pattern (4-7-8)
+    pub fn new() -> Self {
+        let pattern = builtin_patterns().remove("4-7-8").expect("4-7-8 is a builtin pattern");
+        ZenOneLiteRuntime {
+            inner: Mutex::new(LiteInner {
+                phase_machine: PhaseMachine::new(pattern.to_phase_durations()),
+                pattern,
+                status: FfiRuntimeStatus::Idle,
+                tempo_scale: 1.0,
+                tempo_target: 1.0,
+                elapsed_sec: 0.0,
+                heart_rate: None,
+            }),
+            safety: SafetyMonitor::new(),
+        }
+    }
+
+    /// Run `event_type` through the safety specs, failing on anything that
+    /// would block it in the full runtime
+    fn verify(
+        &self,
+        event_type: FfiKernelEventType,
+        payload: Option<String>,
+        state: SpecState,
+    ) -> Result<FfiSafetyCheckResult, ZenOneError> {
+        let event = FfiKernelEvent { event_type, timestamp_ms: Utc::now().timestamp_millis(), payload };
+        let result = self.safety.check_spec_event(event, state);
+        let blocking = result.violations.iter().find(|v| {
+            matches!(v.corrective_action, Some(FfiCorrectiveAction::BlockEvent))
+                || matches!(v.severity, FfiViolationSeverity::Critical | FfiViolationSeverity::Error)
+        });
+        match blocking {
+            Some(v) => Err(ZenOneError::SafetyViolation(v.description.clone())),
+            None => Ok(result),
+        }
+    }
+
+    pub fn get_patterns(&self) -> Vec<FfiBreathPattern> {
+        builtin_patterns().values().map(FfiBreathPattern::from).collect()
+    }
+
+    pub fn current_pattern_id(&self) -> String {
+        self.inner.lock().pattern.id.clone()
+    }
+
+    pub fn load_pattern(&self, pattern_id: String) -> Result<(), ZenOneError> {
+        let pattern = builtin_patterns()
+            .remove(&pattern_id)
+            .ok_or(ZenOneError::PatternNotFound)?;
+        let mut inner = self.inner.lock();
+        self.verify(FfiKernelEventType::LoadPattern, Some(pattern_id), inner.spec_state())?;
+        inner.phase_machine = PhaseMachine::new(pattern.to_phase_durations());
+        inner.pattern = pattern;
+        Ok(())
+    }
+
+    pub fn start_session(&self) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        self.verify(FfiKernelEventType::StartSession, None, inner.spec_state())?;
+        inner.phase_machine = PhaseMachine::new(inner.pattern.to_phase_durations());
+        inner.elapsed_sec = 0.0;
+        inner.status = FfiRuntimeStatus::Running;
+        Ok(())
+    }
+
+    pub fn stop_session(&self) -> Result<FfiLiteSessionSummary, ZenOneError> {
+        let mut inner = self.inner.lock();
+        if inner.status != FfiRuntimeStatus::Running {
+            return Err(ZenOneError::SessionNotActive);
+        }
+        inner.status = FfiRuntimeStatus::Idle;
+        Ok(FfiLiteSessionSummary {
+            pattern_id: inner.pattern.id.clone(),
+            duration_sec: inner.elapsed_sec,
+            cycles_completed: inner.phase_machine.cycle_index,
+        })
+    }
+
+    /// Advance the pacer by `dt_sec` (no-op outside a session)
+    pub fn tick(&self, dt_sec: f32) -> FfiFrameLite {
+        let mut inner = self.inner.lock();
+        if inner.status == FfiRuntimeStatus::Running && dt_sec > 0.0 {
+            inner.tempo_scale = ramp_tempo(inner.tempo_scale, inner.tempo_target, dt_sec);
+            let dt_us = scaled_phase_dt((dt_sec * 1_000_000.0) as u64, inner.tempo_scale);
+            inner.phase_machine.tick(dt_us);
+            inner.elapsed_sec += dt_sec;
+        }
+        inner.frame()
+    }
+
+    /// Latest heart rate from the watch sensor (None when off-wrist)
+    pub fn set_heart_rate(&self, bpm: Option<f32>) {
+        self.inner.lock().heart_rate = bpm;
+    }
+
+    pub fn adjust_tempo(&self, scale: f32) -> Result<f32, ZenOneError> {
+        let mut inner = self.inner.lock();
+        let proposed = SpecState { tempo_scale: scale, ..inner.spec_state() };
+        let result = self.verify(FfiKernelEventType::AdjustTempo, Some(scale.to_string()), proposed)?;
+        // A rate-limited change only goes as far as the limit allows
+        let applied = result.clamped_tempo().unwrap_or(scale);
+        inner.tempo_target = applied;
+        Ok(applied)
+    }
+
+    pub fn emergency_halt(&self, reason: String) {
+        log::error!("EMERGENCY HALT (lite): {}", reason);
+        let mut inner = self.inner.lock();
+        let _ = self.verify(FfiKernelEventType::EmergencyHalt, Some(reason), inner.spec_state());
+        inner.status = FfiRuntimeStatus::SafetyLock;
+    }
+
+    pub fn get_status(&self) -> FfiRuntimeStatus {
+        self.inner.lock().status
+    }
+}
+
+// ============================================================================
+// LOCAL TIME - USER TIME ZONE AND DAY BOUNDARIES
+// ============================================================================
+
+/// Time zone that decides what "today" and "this hour" mean for streaks,
+/// rollups, experiment days and time-of-day scoring. Days are calendar dates
+/// in the zone, never 24 h buckets, so a 23 h or 25 h DST day is still one day.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum LocalZone {
+    /// Follow the device clock, which moves with the user when travelling
+    #[default]
+    Device,
+    /// IANA zone chosen by the user
+    Named(chrono_tz::Tz),
+}
+
+#[cfg(feature = "full")]
+impl LocalZone {
+    fn parse(name: Option<&str>) -> Result<Self, ZenOneError> {
+        match name {
+            None => Ok(Self::Device),
+            Some(name) => name
+                .parse::<chrono_tz::Tz>()
+                .map(Self::Named)
+                .map_err(|_| ZenOneError::ConfigError(format!("Unknown time zone: {}", name))),
+        }
+    }
+
+    fn name(&self) -> Option<String> {
+        match self {
+            Self::Device => None,
+            Self::Named(tz) => Some(tz.name().to_string()),
+        }
+    }
+
+    /// Wall-clock time in this zone at `timestamp_ms`
+    fn at(&self, timestamp_ms: i64) -> Option<chrono::NaiveDateTime> {
+        let utc = chrono::DateTime::from_timestamp_millis(timestamp_ms)?;
+        Some(match self {
+            Self::Device => utc.with_timezone(&Local).naive_local(),
+            Self::Named(tz) => utc.with_timezone(tz).naive_local(),
+        })
+    }
+
+    fn date(&self, timestamp_ms: i64) -> Option<chrono::NaiveDate> {
+        self.at(timestamp_ms).map(|t| t.date())
+    }
+
+    /// UTC offset in minutes at `timestamp_ms`, DST included
+    fn offset_min(&self, timestamp_ms: i64) -> Option<i32> {
+        let utc = self.at(timestamp_ms)?.and_utc().timestamp_millis();
+        Some(((utc - timestamp_ms) / 60_000) as i32)
+    }
+
+    fn today(&self, now_ms: i64) -> chrono::NaiveDate {
+        self.date(now_ms).unwrap_or_else(|| Utc::now().date_naive())
+    }
+
+    fn local_time(&self, timestamp_ms: i64) -> FfiLocalTime {
+        let local = self.at(timestamp_ms).unwrap_or_else(|| Utc::now().naive_utc());
+        FfiLocalTime {
+            time_zone: self.name(),
+            date: local.date().to_string(),
+            hour: local.hour() as u8,
+            utc_offset_min: self.offset_min(timestamp_ms).unwrap_or(0),
+        }
+    }
+}
+
+/// The user's current calendar day and hour
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiLocalTime {
+    /// IANA zone in use; None when following the device
+    pub time_zone: Option<String>,
+    /// ISO date (YYYY-MM-DD)
+    pub date: String,
+    /// 0-23
+    pub hour: u8,
+    pub utc_offset_min: i32,
+}
+
+/// Calendar day a session counts toward: its start at the offset it was
+/// recorded with, so sessions done while travelling keep the day they were
+/// done on. Imported and older records are dated in `zone`.
+#[cfg(feature = "full")]
+fn session_date(record: &FfiSessionRecord, zone: LocalZone) -> Option<chrono::NaiveDate> {
+    local_date(record.started_at_ms, record.utc_offset_min, zone)
+}
+
+/// Date at `timestamp_ms` under a recorded UTC offset, or in `zone` without one
+#[cfg(feature = "full")]
+fn local_date(timestamp_ms: i64, utc_offset_min: Option<i32>, zone: LocalZone) -> Option<chrono::NaiveDate> {
+    match utc_offset_min {
+        Some(offset) => {
+            chrono::DateTime::from_timestamp_millis(timestamp_ms + offset as i64 * 60_000)
+                .map(|t| t.date_naive())
+        }
+        None => zone.date(timestamp_ms),
+    }
+}
+
+// ============================================================================
+// STREAKS - REST DAYS AND FREEZES
+// ============================================================================
+
+#[cfg(feature = "full")]
+const STREAK_MAX_GRACE_HOURS: u32 = 6;
+
+/// Streak rules shared by every frontend
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiStreakConfig {
+    /// Weekdays (0 = Monday .. 6 = Sunday) that neither extend nor break a streak
+    pub rest_days: Vec<u8>,
+    /// Missed days per ISO week that are covered automatically
+    pub freezes_per_week: u32,
+    /// Shortest session that counts for the day
+    pub min_session_sec: f32,
+    /// Hours after local midnight that still count as the previous day, so a
+    /// late-night session keeps yesterday's streak
+    #[serde(default)]
+    pub grace_hours: u32,
+}
+
+#[cfg(feature = "full")]
+impl Default for FfiStreakConfig {
+    fn default() -> Self {
+        Self { rest_days: Vec::new(), freezes_per_week: 1, min_session_sec: 30.0, grace_hours: 0 }
+    }
+}
+
+#[cfg(feature = "full")]
+impl FfiStreakConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+        if let Some(day) = self.rest_days.iter().find(|d| **d > 6) {
+            return invalid(format!("Rest day {} is not a weekday (0-6)", day));
+        }
+        let mut days = self.rest_days.clone();
+        days.sort_unstable();
+        days.dedup();
+        if days.len() == 7 {
+            return invalid("At least one day a week must not be a rest day".into());
+        }
+        if self.freezes_per_week > 7 {
+            return invalid(format!("At most 7 freezes per week, got {}", self.freezes_per_week));
+        }
+        if !(self.min_session_sec.is_finite() && self.min_session_sec >= 0.0) {
+            return invalid("Minimum session length must be non-negative".into());
+        }
+        if self.grace_hours > STREAK_MAX_GRACE_HOURS {
+            return invalid(format!("Grace window is at most {} hours", STREAK_MAX_GRACE_HOURS));
+        }
+        Ok(())
+    }
+}
+
+/// Current streak and how it has been protected
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiStreakStatus {
+    /// Active days in the running streak (rest and frozen days not counted)
+    pub current: u32,
+    pub longest: u32,
+    /// ISO date (YYYY-MM-DD) of the last day with a qualifying session
+    pub last_active_date: Option<String>,
+    /// Missed days covered by a freeze within the running streak
+    pub frozen_days: Vec<String>,
+    pub freezes_used_this_week: u32,
+    /// Freeze tokens left this week
+    pub freezes_left: u32,
+    pub freeze_available: bool,
+    pub today_done: bool,
+    /// The streak ends tonight unless a session is done today
+    pub at_risk: bool,
+}
+
+/// Local day a session counts for, with the first `grace_hours` after
+/// midnight going to the day before
+#[cfg(feature = "full")]
+fn streak_day(entry: &RollupEntry, zone: LocalZone, grace_hours: u32) -> Option<chrono::NaiveDate> {
+    let grace_ms = grace_hours as i64 * 3_600_000;
+    local_date(entry.started_at_ms - grace_ms, entry.utc_offset_min, zone)
+}
+
+#[cfg(feature = "full")]
+impl SessionStore {
+    /// Daily streak as of `now_ms`, in the user's zone. Read from the rollup
+    /// contributions, so sessions pruned by retention still count.
+    pub fn streak(&self, config: &FfiStreakConfig, now_ms: i64) -> FfiStreakStatus {
+        let zone = self.zone();
+        let today = zone.today(now_ms - config.grace_hours as i64 * 3_600_000);
+        compute_streak(self.rollups.lock().entries.values(), config, zone, today)
+    }
+}
+
+/// Walk every day from the first qualifying session up to `today`. A missed day
+/// that is neither a rest day nor today (still in progress) uses up a freeze
+/// from its week if one is left, and otherwise ends the streak.
+#[cfg(feature = "full")]
+fn compute_streak<'a>(
+    entries: impl IntoIterator<Item = &'a RollupEntry>,
+    config: &FfiStreakConfig,
+    zone: LocalZone,
+    today: chrono::NaiveDate,
+) -> FfiStreakStatus {
+    use chrono::Datelike;
+
+    let active: std::collections::BTreeSet<chrono::NaiveDate> = entries
+        .into_iter()
+        .filter(|e| e.duration_sec >= config.min_session_sec)
+        .filter_map(|e| streak_day(e, zone, config.grace_hours))
+        .filter(|d| *d <= today)
+        .collect();
+    let is_rest = |day: chrono::NaiveDate| config.rest_days.contains(&(day.weekday().num_days_from_monday() as u8));
+
+    let mut current = 0;
+    let mut longest = 0;
+    let mut frozen_days = Vec::new();
+    let mut week = today.iso_week();
+    let mut freezes_used = 0;
+
+    if let Some(first) = active.first() {
+        for day in first.iter_days().take_while(|d| *d <= today) {
+            if day.iso_week() != week {
+                week = day.iso_week();
+                freezes_used = 0;
+            }
+            if active.contains(&day) {
+                current += 1;
+                longest = longest.max(current);
+            } else if day != today && !is_rest(day) && current > 0 {
+                if freezes_used < config.freezes_per_week {
+                    freezes_used += 1;
+                    frozen_days.push(day.to_string());
+                } else {
+                    current = 0;
+                    frozen_days.clear();
+                }
+            }
+        }
+    }
+
+    let today_done = active.contains(&today);
+    let freeze_available = freezes_used < config.freezes_per_week;
+    FfiStreakStatus {
+        current,
+        longest,
+        last_active_date: active.last().map(|d| d.to_string()),
+        frozen_days,
+        freezes_used_this_week: freezes_used,
+        freezes_left: config.freezes_per_week.saturating_sub(freezes_used),
+        freeze_available,
+        today_done,
+        at_risk: current > 0 && !today_done && !is_rest(today) && !freeze_available,
+    }
+}
+
+// ============================================================================
+// BREATHING BASELINE - FREE-BREATHING OBSERVATION
+// ============================================================================
+
+#[cfg(feature = "full")]
+const BASELINE_MIN_SEC: f32 = 60.0;
+#[cfg(feature = "full")]
+const BASELINE_MAX_SEC: f32 = 600.0;
+#[cfg(feature = "full")]
+const BASELINE_MIN_BREATHS: usize = 5;
+/// Resting adults typically breathe at 12-14 breaths/min
+#[cfg(feature = "full")]
+const BASELINE_FAST_RATE_BPM: f32 = 14.0;
+/// Coefficient of variation of breath length above which breathing counts as irregular
+#[cfg(feature = "full")]
+const BASELINE_IRREGULAR_VARIABILITY: f32 = 0.3;
+
+/// Natural breathing measured without a pacer
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiBreathingBaseline {
+    pub measured_at_ms: i64,
+    pub duration_sec: f32,
+    pub breaths: u32,
+    pub rate_bpm: f32,
+    /// Standard deviation of breath length
+    pub interval_sd_sec: f32,
+    /// Breath length coefficient of variation (sd / mean)
+    pub variability: f32,
+    pub source: FfiRespirationSource,
+}
+
+#[cfg(feature = "full")]
+struct BaselineObservation {
+    duration_sec: f32,
+    elapsed_sec: f32,
+    samples: Vec<(i64, f32)>,
+    source: Option<FfiRespirationSource>,
+}
+
+#[cfg(feature = "full")]
+impl BaselineObservation {
+    fn new(duration_sec: f32) -> Self {
+        Self { duration_sec, elapsed_sec: 0.0, samples: Vec::new(), source: None }
+    }
+
+    /// Same source preference as BreathRateEstimator: switch up to a direct source, never down
+    fn observe(&mut self, value: f32, source: FfiRespirationSource, timestamp_us: i64) {
+        match self.source {
+            Some(current) if current != source => {
+                if source.weight() <= current.weight() {
+                    return;
+                }
+                self.samples.clear();
+                self.source = Some(source);
+            }
+            Some(_) => {}
+            None => self.source = Some(source),
+        }
+        self.samples.push((timestamp_us, value));
+    }
+
+    fn report(&self) -> Result<FfiBreathingBaseline, String> {
+        let source = self.source.ok_or("No respiration signal during the observation")?;
+        let onsets = breath_onsets(&self.samples);
+        if onsets.len() < BASELINE_MIN_BREATHS + 1 {
+            return Err(format!("Only {} breaths detected", onsets.len().saturating_sub(1)));
+        }
+        let intervals: Vec<f32> = onsets.windows(2).map(|w| (w[1] - w[0]) as f32 / 1_000_000.0).collect();
+        let n = intervals.len() as f32;
+        let mean = intervals.iter().sum::<f32>() / n;
+        let sd = (intervals.iter().map(|i| (i - mean).powi(2)).sum::<f32>() / n).sqrt();
+        Ok(FfiBreathingBaseline {
+            measured_at_ms: Utc::now().timestamp_millis(),
+            duration_sec: self.elapsed_sec,
+            breaths: intervals.len() as u32,
+            rate_bpm: 60.0 / mean,
+            interval_sd_sec: sd,
+            variability: sd / mean,
+            source,
+        })
+    }
+}
+
+// ============================================================================
+// PATTERN ADJUSTMENTS - PER-PHASE USER OVERRIDES
+// ============================================================================
+
+#[cfg(feature = "full")]
+const PATTERN_ADJUSTMENTS_FILE: &str = "pattern_adjustments.json";
+
+/// A user's replacement duration for one phase of a pattern
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiPhaseOverride {
+    pub pattern_id: String,
+    pub phase: FfiPhase,
+    pub seconds: f32,
+}
+
+/// Per-pattern phase overrides, applied on top of the builtin timings
+#[cfg(feature = "full")]
+pub struct PatternAdjustments {
+    inner: Mutex<PatternAdjustmentsInner>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct PatternAdjustmentsInner {
+    overrides: Vec<FfiPhaseOverride>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+/// The pattern timing for `phase`; the countdown is not part of a pattern
+#[cfg(feature = "full")]
+fn phase_seconds(timings: &mut BreathTimings, phase: FfiPhase) -> Option<&mut f32> {
+    match phase {
+        FfiPhase::Inhale => Some(&mut timings.inhale),
+        FfiPhase::HoldIn => Some(&mut timings.hold_in),
+        FfiPhase::Exhale => Some(&mut timings.exhale),
+        FfiPhase::HoldOut => Some(&mut timings.hold_out),
+        FfiPhase::Prepare => None,
+    }
+}
+
+#[cfg(feature = "full")]
+impl Default for PatternAdjustments {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl PatternAdjustments {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(PatternAdjustmentsInner::default()),
+        }
+    }
+
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read pattern adjustments: {}", e)))?;
+            serde_json::from_slice::<PatternAdjustmentsInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt pattern adjustments file: {}", e)))?
+        } else {
+            PatternAdjustmentsInner::default()
+        };
+        loaded.path = Some(path);
+        *self.inner.lock() = loaded;
+        Ok(())
+    }
+
+    fn apply(&self, mut pattern: BreathPattern) -> BreathPattern {
+        for o in self.inner.lock().overrides.iter().filter(|o| o.pattern_id == pattern.id) {
+            if let Some(slot) = phase_seconds(&mut pattern.timings, o.phase) {
+                *slot = o.seconds;
+            }
+        }
+        pattern
+    }
+
+    /// Validate the pattern with `seconds` and its other overrides, then store it
+    fn set(&self, pattern: BreathPattern, phase: FfiPhase, seconds: f32) -> Result<(), ZenOneError> {
+        let mut adjusted = self.apply(pattern);
+        *phase_seconds(&mut adjusted.timings, phase)
+            .ok_or_else(|| ZenOneError::ConfigError("The countdown has no pattern timing".into()))? = seconds;
+        PatternValidator::check(&adjusted)?;
+
+        let mut inner = self.inner.lock();
+        inner.overrides.retain(|o| !(o.pattern_id == adjusted.id && o.phase == phase));
+        inner.overrides.push(FfiPhaseOverride { pattern_id: adjusted.id, phase, seconds });
+        Self::persist(&inner)
+    }
+
+    fn clear(&self, pattern_id: &str) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        inner.overrides.retain(|o| o.pattern_id != pattern_id);
+        Self::persist(&inner)
+    }
+
+    fn list(&self) -> Vec<FfiPhaseOverride> {
+        self.inner.lock().overrides.clone()
+    }
+
+    fn persist(inner: &PatternAdjustmentsInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Pattern adjustment serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist pattern adjustments: {}", e)))
+    }
+}
+
+// ============================================================================
+// CUSTOM PATTERNS - USER-DEFINED TIMINGS
+// ============================================================================
+
+#[cfg(feature = "full")]
+const CUSTOM_PATTERNS_FILE: &str = "custom_patterns.json";
+#[cfg(feature = "full")]
+const CUSTOM_PATTERN_TAG: &str = "custom";
+#[cfg(feature = "full")]
+const CUSTOM_PATTERN_MAX_CYCLES: u32 = 100;
+/// Identifies a shared pattern file
+#[cfg(feature = "full")]
+const PATTERN_EXPORT_FORMAT: &str = "zenone.breath-pattern";
+/// Bump when the exported layout changes incompatibly
+#[cfg(feature = "full")]
+const PATTERN_EXPORT_VERSION: u32 = 1;
+/// Refuse anything larger than a pattern could plausibly be
+#[cfg(feature = "full")]
+const PATTERN_IMPORT_MAX_BYTES: usize = 64 * 1024;
+
+/// Envelope written by `export_pattern`
+#[cfg(feature = "full")]
+#[derive(Serialize, Deserialize)]
+struct PatternExport {
+    format: String,
+    version: u32,
+    pattern: FfiBreathPattern,
+}
+
+#[cfg(feature = "full")]
+impl PatternExport {
+    fn parse(json: &str) -> Result<FfiBreathPattern, ZenOneError> {
+        let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+        if json.len() > PATTERN_IMPORT_MAX_BYTES {
+            return invalid(format!("Pattern file exceeds {} bytes", PATTERN_IMPORT_MAX_BYTES));
+        }
+        let export: PatternExport = serde_json::from_str(json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Not a pattern file: {}", e)))?;
+        if export.format != PATTERN_EXPORT_FORMAT {
+            return invalid(format!("Unknown pattern file format '{}'", export.format));
+        }
+        if export.version == 0 || export.version > PATTERN_EXPORT_VERSION {
+            return invalid(format!(
+                "Pattern file version {} is not supported (expected 1-{})",
+                export.version, PATTERN_EXPORT_VERSION
+            ));
+        }
+        Ok(export.pattern)
+    }
+}
+
+#[cfg(feature = "full")]
+impl From<&FfiBreathPattern> for BreathPattern {
+    fn from(p: &FfiBreathPattern) -> Self {
+        BreathPattern {
+            id: p.id.clone(),
+            label: p.label.clone(),
+            tag: p.tag.clone(),
+            description: p.description.clone(),
+            timings: BreathTimings {
+                inhale: p.inhale_sec,
+                hold_in: p.hold_in_sec,
+                exhale: p.exhale_sec,
+                hold_out: p.hold_out_sec,
+            },
+            recommended_cycles: p.recommended_cycles,
+            arousal_impact: p.arousal_impact,
+        }
+    }
+}
+
+/// Builtin patterns plus the user's own, looked up by id
+#[cfg(feature = "full")]
+pub struct PatternLibrary {
+    inner: Mutex<PatternLibraryInner>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct PatternLibraryInner {
+    custom: Vec<FfiBreathPattern>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+impl Default for PatternLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl PatternLibrary {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(PatternLibraryInner::default()),
+        }
+    }
+
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read custom patterns: {}", e)))?;
+            serde_json::from_slice::<PatternLibraryInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt custom patterns file: {}", e)))?
+        } else {
+            PatternLibraryInner::default()
+        };
+        loaded.path = Some(path);
+        *self.inner.lock() = loaded;
+        Ok(())
+    }
+
+    /// Builtin pattern by id, falling back to the custom ones
+    fn get(&self, id: &str) -> Option<BreathPattern> {
+        builtin_patterns().remove(id).or_else(|| {
+            self.inner.lock().custom.iter().find(|p| p.id == id).map(BreathPattern::from)
+        })
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    fn all(&self) -> Vec<BreathPattern> {
+        let mut all: Vec<BreathPattern> = builtin_patterns().into_values().collect();
+        all.extend(self.inner.lock().custom.iter().map(BreathPattern::from));
+        all
+    }
+
+    fn add(&self, mut pattern: FfiBreathPattern) -> Result<FfiBreathPattern, ZenOneError> {
+        let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+        pattern.id = pattern.id.trim().to_string();
+        if pattern.id.is_empty() {
+            return invalid("Pattern id must not be empty".into());
+        }
+        if builtin_patterns().contains_key(&pattern.id) {
+            return invalid(format!("'{}' is a builtin pattern", pattern.id));
+        }
+        if pattern.label.trim().is_empty() {
+            return invalid("Pattern label must not be empty".into());
+        }
+        if pattern.tag.trim().is_empty() {
+            pattern.tag = CUSTOM_PATTERN_TAG.into();
+        }
+        if !(1..=CUSTOM_PATTERN_MAX_CYCLES).contains(&pattern.recommended_cycles) {
+            return invalid(format!("recommended_cycles must be 1-{}", CUSTOM_PATTERN_MAX_CYCLES));
+        }
+        if !(pattern.arousal_impact.is_finite() && (-1.0..=1.0).contains(&pattern.arousal_impact)) {
+            return invalid("arousal_impact must be within -1..=1".into());
+        }
+        PatternValidator::check(&BreathPattern::from(&pattern))?;
+
+        let mut inner = self.inner.lock();
+        inner.custom.retain(|p| p.id != pattern.id);
+        inner.custom.push(pattern.clone());
+        Self::persist(&inner)?;
+        Ok(pattern)
+    }
+
+    fn remove(&self, id: &str) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        let before = inner.custom.len();
+        inner.custom.retain(|p| p.id != id);
+        if inner.custom.len() == before {
+            return Err(ZenOneError::PatternNotFound);
+        }
+        Self::persist(&inner)
+    }
+
+    fn persist(inner: &PatternLibraryInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Custom pattern serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist custom patterns: {}", e)))
+    }
+}
+
+// ============================================================================
+// PATTERN CHANGES - PROPOSE, THEN CONFIRM
+// ============================================================================
+
+/// How long a proposal stays confirmable
+#[cfg(feature = "full")]
+const PATTERN_PROPOSAL_TTL: Duration = Duration::from_secs(60);
+
+/// What loading a pattern right now would do, shown before it is applied
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPatternChangeProposal {
+    pub proposal_id: u64,
+    pub from_pattern_id: String,
+    pub to_pattern_id: String,
+    /// Dry run of the safety specs against the current runtime state
+    pub safety: FfiSafetyCheckResult,
+    /// Timing issues of the target pattern, with the user's overrides applied
+    pub issues: Vec<FfiPatternIssue>,
+    /// False when the safety specs, the safety lock or a blocking timing
+    /// issue would refuse the change
+    pub can_confirm: bool,
+    pub expires_in_ms: u32,
+}
+
+#[cfg(feature = "full")]
+struct PendingPatternChange {
+    id: u64,
+    from_pattern_id: String,
+    to_pattern_id: String,
+    /// Runtime status when proposed; a change invalidates the proposal
+    status: FfiRuntimeStatus,
+    can_confirm: bool,
+    created: Instant,
+}
+
+/// The single outstanding proposal, if any
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct PatternChangeSlot {
+    next_id: u64,
+    pending: Option<PendingPatternChange>,
+}
+
+// ============================================================================
+// SEQUENCES - PATTERN PLAYLISTS
+// ============================================================================
+
+#[cfg(feature = "full")]
+const SEQUENCE_MAX_SEGMENTS: usize = 12;
+#[cfg(feature = "full")]
+const SEQUENCE_MIN_SEGMENT_SEC: f32 = 30.0;
+#[cfg(feature = "full")]
+const SEQUENCE_MAX_SEGMENT_SEC: f32 = 3600.0;
+
+/// One step of a sequence: a pattern held for a while
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiSequenceSegment {
+    pub pattern_id: String,
+    pub duration_sec: f32,
+}
+
+/// Where the loaded sequence is
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiSequenceProgress {
+    pub segment_index: u32,
+    pub segment_count: u32,
+    pub pattern_id: String,
+    pub segment_elapsed_sec: f32,
+    pub segment_duration_sec: f32,
+    pub total_elapsed_sec: f32,
+    pub total_duration_sec: f32,
+}
+
+/// How one segment of a sequenced session went
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSegmentStats {
+    pub pattern_id: String,
+    /// Active time, including the wait for the closing cycle boundary
+    pub duration_sec: f32,
+    pub cycles_completed: u64,
+    pub avg_heart_rate: Option<f32>,
+    pub avg_adherence: Option<f32>,
+}
+
+/// A sequence being (or about to be) run by the actor
+#[cfg(feature = "full")]
+struct SequenceRun {
+    segments: Vec<FfiSequenceSegment>,
+    index: usize,
+    /// Active seconds spent in the current segment
+    elapsed_sec: f32,
+    /// Session counters when the current segment began
+    start_cycle: u64,
+    start_hr_sample: usize,
+    start_adherence: usize,
+    completed: Vec<FfiSegmentStats>,
+}
+
+#[cfg(feature = "full")]
+impl SequenceRun {
+    fn new(segments: Vec<FfiSequenceSegment>) -> Self {
+        Self {
+            segments,
+            index: 0,
+            elapsed_sec: 0.0,
+            start_cycle: 0,
+            start_hr_sample: 0,
+            start_adherence: 0,
+            completed: Vec::new(),
+        }
+    }
+
+    fn restart(&mut self) {
+        *self = Self::new(std::mem::take(&mut self.segments));
+    }
+
+    fn current(&self) -> &FfiSequenceSegment {
+        &self.segments[self.index]
+    }
+
+    fn segment_done(&self) -> bool {
+        self.elapsed_sec >= self.current().duration_sec
+    }
+
+    fn next_pattern_id(&self) -> Option<String> {
+        self.segments.get(self.index + 1).map(|s| s.pattern_id.clone())
+    }
+
+    /// Close the current segment and move on to the next one
+    fn advance(&mut self, session: &SessionState, cycle: u64) {
+        self.close_segment(session, cycle);
+        self.index += 1;
+        self.elapsed_sec = 0.0;
+        self.start_cycle = cycle;
+        self.start_hr_sample = session.hr_samples.len();
+        self.start_adherence = session.cycle_adherence.len();
+    }
+
+    /// Close the segment in progress and hand back every segment's stats
+    fn finish(mut self, session: &SessionState, cycle: u64) -> Vec<FfiSegmentStats> {
+        self.close_segment(session, cycle);
+        self.completed
+    }
+
+    fn close_segment(&mut self, session: &SessionState, cycle: u64) {
+        let mean = |v: &[f32]| (!v.is_empty()).then(|| v.iter().sum::<f32>() / v.len() as f32);
+        self.completed.push(FfiSegmentStats {
+            pattern_id: self.current().pattern_id.clone(),
+            duration_sec: self.elapsed_sec,
+            cycles_completed: cycle.saturating_sub(self.start_cycle),
+            avg_heart_rate: mean(session.hr_samples.get(self.start_hr_sample..).unwrap_or_default()),
+            avg_adherence: mean(session.cycle_adherence.get(self.start_adherence..).unwrap_or_default()),
+        });
+    }
+
+    fn progress(&self) -> FfiSequenceProgress {
+        let done: f32 = self.completed.iter().map(|s| s.duration_sec).sum();
+        FfiSequenceProgress {
+            segment_index: self.index as u32,
+            segment_count: self.segments.len() as u32,
+            pattern_id: self.current().pattern_id.clone(),
+            segment_elapsed_sec: self.elapsed_sec,
+            segment_duration_sec: self.current().duration_sec,
+            total_elapsed_sec: done + self.elapsed_sec,
+            total_duration_sec: self.segments.iter().map(|s| s.duration_sec).sum(),
+        }
+    }
+}
+
+// ============================================================================
+// RUNTIME LISTENER - PUSHED PHASE, CYCLE, STATUS, HEART-RATE AND STATE CHANGES
+// ============================================================================
+
+/// Receives pacer and status changes as they happen, so frontends can
+/// subscribe instead of polling `tick`/`get_state`. Called on the runtime
+/// thread: implementations must return quickly.
+#[cfg(feature = "full")]
+pub trait RuntimeListener: Send + Sync {
+    /// The displayed phase changed (including into and out of the countdown)
+    fn on_phase_changed(&self, phase: FfiPhase, time_to_next_phase_ms: u32, timestamp_us: i64);
+    /// A breathing cycle finished, with its adherence score if one was measured
+    fn on_cycle_complete(&self, cycles_completed: u64, adherence: Option<f32>, timestamp_us: i64);
+    fn on_status_changed(&self, status: FfiRuntimeStatus);
+    /// A new heart-rate estimate, from the camera or a strap
+    fn on_heart_rate(&self, bpm: f32, confidence: f32, timestamp_us: i64);
+    /// The published state changed; only the changed fields are set
+    fn on_state_changed(&self, diff: FfiRuntimeStateDiff);
+}
+
+#[cfg(feature = "full")]
+type SharedListener = Arc<Mutex<Option<Box<dyn RuntimeListener>>>>;
+
+/// What the listener was last told
+#[cfg(feature = "full")]
+struct ListenerMark {
+    phase: FfiPhase,
+    cycles: u64,
+    status: FfiRuntimeStatus,
+}
+
+#[cfg(feature = "full")]
+impl ListenerMark {
+    fn new(inner: &RuntimeInner) -> Self {
+        Self {
+            phase: inner.display_phase().0,
+            cycles: inner.phase_machine.cycle_index,
+            status: inner.status,
+        }
+    }
+}
+
+// ============================================================================
+// STATE DIFFS - CHANGED FIELDS ONLY, PUSHED OR BY SEQUENCE
+// ============================================================================
+
+/// Optional `FfiRuntimeState` fields, named in a diff when they go back to None
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiStateField {
+    Preparation,
+    FollowMe,
+    Sequence,
+    Stage,
+    Sleep,
+}
+
+/// The `FfiRuntimeState` fields that changed since the previous publish.
+/// Unchanged fields are None and left out of the serialized form, so a
+/// 60 Hz UI mostly receives just the phase progress and session time.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FfiRuntimeStateDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<FfiRuntimeStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<FfiPhase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase_progress: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycles_completed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_duration_sec: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tempo_scale: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub belief: Option<FfiBeliefState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resonance: Option<FfiResonance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety: Option<FfiSafetyStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preparation: Option<FfiPreparationStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub follow_me: Option<FfiFollowMeStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<FfiSequenceProgress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<FfiStageStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sleep: Option<FfiSleepStatus>,
+    /// Optional fields that changed to None
+    #[serde(default)]
+    pub cleared: Vec<FfiStateField>,
+    /// Publish this diff brings the state up to; increases with every publish
+    #[serde(default)]
+    pub state_sequence: u64,
+}
+
+#[cfg(feature = "full")]
+impl FfiRuntimeStateDiff {
+    fn between(old: &FfiRuntimeState, new: &FfiRuntimeState, sequence: u64) -> Self {
+        let mut cleared = Vec::new();
+        Self {
+            status: changed(&old.status, &new.status),
+            pattern_id: changed(&old.pattern_id, &new.pattern_id),
+            phase: changed(&old.phase, &new.phase),
+            phase_progress: changed(&old.phase_progress, &new.phase_progress),
+            cycles_completed: changed(&old.cycles_completed, &new.cycles_completed),
+            session_duration_sec: changed(&old.session_duration_sec, &new.session_duration_sec),
+            tempo_scale: changed(&old.tempo_scale, &new.tempo_scale),
+            belief: changed(&old.belief, &new.belief),
+            resonance: changed(&old.resonance, &new.resonance),
+            safety: changed(&old.safety, &new.safety),
+            preparation: changed_optional(FfiStateField::Preparation, &old.preparation, &new.preparation, &mut cleared),
+            follow_me: changed_optional(FfiStateField::FollowMe, &old.follow_me, &new.follow_me, &mut cleared),
+            sequence: changed_optional(FfiStateField::Sequence, &old.sequence, &new.sequence, &mut cleared),
+            stage: changed_optional(FfiStateField::Stage, &old.stage, &new.stage, &mut cleared),
+            sleep: changed_optional(FfiStateField::Sleep, &old.sleep, &new.sleep, &mut cleared),
+            cleared,
+            state_sequence: sequence,
+        }
+    }
+}
+
+/// Bounds for `set_state_publish_rate`
+#[cfg(feature = "full")]
+const STATE_PUBLISH_MIN_HZ: f32 = 0.1;
+#[cfg(feature = "full")]
+const STATE_PUBLISH_MAX_HZ: f32 = 240.0;
+
+/// Publish sequence at which each `FfiRuntimeState` field last changed.
+/// Stamps start at 1 (the initial snapshot), so a delta since 0 is complete.
+#[cfg(feature = "full")]
+#[derive(Clone)]
+struct StateStamps {
+    sequence: u64,
+    status: u64,
+    pattern_id: u64,
+    phase: u64,
+    phase_progress: u64,
+    cycles_completed: u64,
+    session_duration_sec: u64,
+    tempo_scale: u64,
+    belief: u64,
+    resonance: u64,
+    safety: u64,
+    preparation: u64,
+    follow_me: u64,
+    sequence_progress: u64,
+    stage: u64,
+    sleep: u64,
+}
+
+#[cfg(feature = "full")]
+impl StateStamps {
+    fn new() -> Self {
+        Self {
+            sequence: 1,
+            status: 1,
+            pattern_id: 1,
+            phase: 1,
+            phase_progress: 1,
+            cycles_completed: 1,
+            session_duration_sec: 1,
+            tempo_scale: 1,
+            belief: 1,
+            resonance: 1,
+            safety: 1,
+            preparation: 1,
+            follow_me: 1,
+            sequence_progress: 1,
+            stage: 1,
+            sleep: 1,
+        }
     }
-    
-    /// Reset controller state
-    pub fn reset(&self) {
-        let mut inner = self.inner.lock();
-        inner.integral = 0.0;
-        inner.last_error = 0.0;
-        inner.last_derivative = 0.0;
-        inner.last_p = 0.0;
-        inner.last_i = 0.0;
-        inner.last_d = 0.0;
+
+    fn record(&mut self, diff: &FfiRuntimeStateDiff) {
+        let seq = diff.state_sequence;
+        let optional = |set: bool, field: FfiStateField| set || diff.cleared.contains(&field);
+        self.sequence = seq;
+        let stamp = |stamp: &mut u64, changed: bool| {
+            if changed {
+                *stamp = seq;
+            }
+        };
+        stamp(&mut self.status, diff.status.is_some());
+        stamp(&mut self.pattern_id, diff.pattern_id.is_some());
+        stamp(&mut self.phase, diff.phase.is_some());
+        stamp(&mut self.phase_progress, diff.phase_progress.is_some());
+        stamp(&mut self.cycles_completed, diff.cycles_completed.is_some());
+        stamp(&mut self.session_duration_sec, diff.session_duration_sec.is_some());
+        stamp(&mut self.tempo_scale, diff.tempo_scale.is_some());
+        stamp(&mut self.belief, diff.belief.is_some());
+        stamp(&mut self.resonance, diff.resonance.is_some());
+        stamp(&mut self.safety, diff.safety.is_some());
+        stamp(&mut self.preparation, optional(diff.preparation.is_some(), FfiStateField::Preparation));
+        stamp(&mut self.follow_me, optional(diff.follow_me.is_some(), FfiStateField::FollowMe));
+        stamp(&mut self.sequence_progress, optional(diff.sequence.is_some(), FfiStateField::Sequence));
+        stamp(&mut self.stage, optional(diff.stage.is_some(), FfiStateField::Stage));
+        stamp(&mut self.sleep, optional(diff.sleep.is_some(), FfiStateField::Sleep));
     }
-    
-    /// Get diagnostics
-    pub fn get_diagnostics(&self) -> FfiPidDiagnostics {
-        let inner = self.inner.lock();
-        FfiPidDiagnostics {
-            p_term: inner.last_p,
-            i_term: inner.last_i,
-            d_term: inner.last_d,
-            integral: inner.integral,
-            total: inner.last_p + inner.last_i + inner.last_d,
+
+    /// Current values of the fields stamped after `since`. A field that
+    /// changed and changed back is still sent, which is harmless.
+    fn delta(&self, state: &FfiRuntimeState, since: u64) -> FfiRuntimeStateDiff {
+        let mut cleared = Vec::new();
+        let mut optional = |stamp: u64, field: FfiStateField, value: bool| {
+            if stamp > since && !value {
+                cleared.push(field);
+            }
+            stamp > since
+        };
+        let preparation = optional(self.preparation, FfiStateField::Preparation, state.preparation.is_some());
+        let follow_me = optional(self.follow_me, FfiStateField::FollowMe, state.follow_me.is_some());
+        let sequence = optional(self.sequence_progress, FfiStateField::Sequence, state.sequence.is_some());
+        let stage = optional(self.stage, FfiStateField::Stage, state.stage.is_some());
+        let sleep = optional(self.sleep, FfiStateField::Sleep, state.sleep.is_some());
+        FfiRuntimeStateDiff {
+            status: (self.status > since).then_some(state.status),
+            pattern_id: (self.pattern_id > since).then(|| state.pattern_id.clone()),
+            phase: (self.phase > since).then_some(state.phase),
+            phase_progress: (self.phase_progress > since).then_some(state.phase_progress),
+            cycles_completed: (self.cycles_completed > since).then_some(state.cycles_completed),
+            session_duration_sec: (self.session_duration_sec > since).then_some(state.session_duration_sec),
+            tempo_scale: (self.tempo_scale > since).then_some(state.tempo_scale),
+            belief: (self.belief > since).then(|| state.belief.clone()),
+            resonance: (self.resonance > since).then(|| state.resonance.clone()),
+            safety: (self.safety > since).then(|| state.safety.clone()),
+            preparation: state.preparation.clone().filter(|_| preparation),
+            follow_me: state.follow_me.clone().filter(|_| follow_me),
+            sequence: state.sequence.clone().filter(|_| sequence),
+            stage: state.stage.clone().filter(|_| stage),
+            sleep: state.sleep.clone().filter(|_| sleep),
+            cleared,
+            state_sequence: self.sequence,
         }
     }
-    
-    /// Update gains dynamically
-    pub fn set_gains(&self, kp: Option<f32>, ki: Option<f32>, kd: Option<f32>) {
-        let mut inner = self.inner.lock();
-        if let Some(p) = kp { inner.config.kp = p; }
-        if let Some(i) = ki { inner.config.ki = i; }
-        if let Some(d) = kd { inner.config.kd = d; }
+}
+
+#[cfg(all(test, feature = "full"))]
+mod state_publish_tests {
+    use super::*;
+
+    #[test]
+    fn publish_rate_must_be_within_bounds() {
+        let runtime = ZenOneRuntime::new();
+        for hz in [0.1, 10.0, 240.0] {
+            assert!(runtime.set_state_publish_rate(Some(hz)).is_ok(), "{}", hz);
+        }
+        for hz in [0.0, -1.0, 1e-40, 0.09, 240.5, f32::INFINITY, f32::NAN] {
+            assert!(runtime.set_state_publish_rate(Some(hz)).is_err(), "{}", hz);
+        }
+        assert!(runtime.set_state_publish_rate(None).is_ok());
+    }
+
+    #[test]
+    fn state_snapshot_shares_the_published_state() {
+        let runtime = ZenOneRuntime::new();
+        runtime.flush();
+        let snapshot = runtime.get_state_snapshot();
+        assert!(Arc::ptr_eq(&snapshot.0, &runtime.state_snapshot()));
+        let state = runtime.get_state();
+        assert_eq!(snapshot.to_state(), state);
+        assert_eq!(snapshot.pattern_id(), state.pattern_id);
+        assert_eq!(snapshot.status(), state.status);
+        assert_eq!(snapshot.belief(), state.belief);
+    }
+
+    #[test]
+    fn session_time_alone_republishes_once_per_second() {
+        let clock = Arc::new(ManualClock::new(1_704_096_000_000));
+        let runtime = ZenOneRuntime::with_clock("4-7-8".into(), clock.clone());
+        runtime.set_preparation_config(FfiPreparationConfig { countdown_sec: 0.0, min_signal_quality: 0.0 });
+        runtime.set_hr_source(FfiHrSourceKind::Simulated);
+        runtime.start_session().unwrap();
+        runtime.pause_session();
+        runtime.flush();
+        assert_eq!(runtime.get_state().status, FfiRuntimeStatus::Paused);
+
+        // The pacer is frozen while paused, so only the session time moves
+        let sequence = || runtime.get_state_delta(0).state_sequence;
+        let paused_at = sequence();
+        for step in 1..=5 {
+            clock.advance(Duration::from_millis(100));
+            runtime.tick(0.1, step * 100_000);
+            runtime.flush();
+        }
+        assert_eq!(sequence(), paused_at);
+
+        clock.advance(Duration::from_millis(600));
+        runtime.tick(0.6, 1_100_000);
+        runtime.flush();
+        assert_eq!(sequence(), paused_at + 1);
+        assert!(runtime.get_state().session_duration_sec >= 1.0);
     }
 }
 
-/// Factory for pre-tuned tempo controller
-/// 
-/// Gains derived from:
-/// - Ziegler-Nichols (initial estimate)
-/// - Simulated annealing optimization
-/// - User testing (n=50)
-pub fn create_tempo_controller() -> PidController {
-    PidController::with_config(FfiPidConfig {
-        kp: 0.003,      // Quick response to misalignment
-        ki: 0.0002,     // Small to avoid overshoot
-        kd: 0.008,      // Moderate damping
-        integral_max: 5.0,
-        output_min: -0.6,  // Max decrease: 1.0 - 0.6 = 0.4
-        output_max: 0.4,   // Max increase: 1.0 + 0.4 = 1.4
-        derivative_alpha: 0.15,
-    })
+#[cfg(feature = "full")]
+fn changed<T: PartialEq + Clone>(old: &T, new: &T) -> Option<T> {
+    (old != new).then(|| new.clone())
+}
+
+#[cfg(feature = "full")]
+fn changed_optional<T: PartialEq + Clone>(
+    field: FfiStateField,
+    old: &Option<T>,
+    new: &Option<T>,
+    cleared: &mut Vec<FfiStateField>,
+) -> Option<T> {
+    if old != new && new.is_none() {
+        cleared.push(field);
+    }
+    changed(old, new).flatten()
 }
 
 // ============================================================================
-// SAFETY MONITOR - LTL VERIFICATION
+// ESCALATION - TRUSTED CONTACT ALERTS
 // ============================================================================
 
-/// Safety violation severity
+#[cfg(feature = "full")]
+const ESCALATION_FILE: &str = "escalation.json";
+
+/// What triggered an escalation
+#[cfg(feature = "full")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum FfiViolationSeverity {
-    Warning,
-    Error,
-    Critical,
+pub enum FfiEscalationReason {
+    /// Several critical safety violations within the configured window
+    RepeatedCriticalViolations,
+    /// Heart rate stayed outside the configured bounds
+    SustainedHeartRateAnomaly,
 }
 
-/// A recorded safety violation
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FfiSafetyViolation {
-    pub spec_name: String,
-    pub description: String,
-    pub severity: FfiViolationSeverity,
-    pub timestamp_ms: i64,
-    pub corrective_action: Option<String>,
+/// When to prepare an alert for the user's trusted contact. Delivery is left
+/// to the shell; the runtime only decides and drafts the message.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FfiEscalationConfig {
+    pub enabled: bool,
+    /// Name used to address the contact in the prepared message
+    pub contact_name: String,
+    /// Critical violations within `violation_window_sec` that escalate
+    pub critical_violation_count: u32,
+    pub violation_window_sec: u32,
+    pub hr_low_bpm: f32,
+    pub hr_high_bpm: f32,
+    /// How long heart rate must stay out of bounds before escalating
+    pub hr_anomaly_sec: f32,
+    /// Minimum time between two escalations
+    pub cooldown_sec: u32,
 }
 
-/// Event types that can be checked by safety monitor
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum FfiKernelEventType {
-    StartSession,
-    StopSession,
-    LoadPattern,
-    AdjustTempo,
-    EmergencyHalt,
-    Tick,
-    PhaseChange,
-    CycleComplete,
+#[cfg(feature = "full")]
+impl Default for FfiEscalationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            contact_name: String::new(),
+            critical_violation_count: 3,
+            violation_window_sec: 600,
+            hr_low_bpm: 40.0,
+            hr_high_bpm: 160.0,
+            hr_anomaly_sec: 60.0,
+            cooldown_sec: 1800,
+        }
+    }
 }
 
-/// An event to be verified by safety monitor
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FfiKernelEvent {
-    pub event_type: FfiKernelEventType,
-    pub timestamp_ms: i64,
-    pub payload: Option<String>,
+#[cfg(feature = "full")]
+impl FfiEscalationConfig {
+    fn validate(&self) -> Result<(), ZenOneError> {
+        let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+        if self.enabled && self.contact_name.trim().is_empty() {
+            return invalid("Escalation needs a contact name".into());
+        }
+        if self.critical_violation_count == 0 || self.violation_window_sec == 0 {
+            return invalid("Violation count and window must be positive".into());
+        }
+        if !(self.hr_low_bpm.is_finite() && self.hr_high_bpm.is_finite() && 0.0 < self.hr_low_bpm && self.hr_low_bpm < self.hr_high_bpm) {
+            return invalid(format!("Invalid heart rate bounds {}-{} bpm", self.hr_low_bpm, self.hr_high_bpm));
+        }
+        if !(self.hr_anomaly_sec.is_finite() && self.hr_anomaly_sec > 0.0) {
+            return invalid("Heart rate anomaly duration must be positive".into());
+        }
+        Ok(())
+    }
 }
 
-/// Result of safety check
+/// Alert prepared for the trusted contact
+#[cfg(feature = "full")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FfiSafetyCheckResult {
-    pub is_safe: bool,
-    pub violations: Vec<FfiSafetyViolation>,
-    pub corrected_event: Option<FfiKernelEvent>,
+pub struct FfiEscalation {
+    pub reason: FfiEscalationReason,
+    pub contact_name: String,
+    pub message: String,
+    pub timestamp_ms: i64,
 }
 
-/// Safety Monitor with LTL verification
-pub struct SafetyMonitor {
-    inner: Mutex<SafetyMonitorInner>,
+/// Watches critical violations and heart rate against the escalation config.
+/// The config is persisted with the profile; counters live in memory only.
+#[cfg(feature = "full")]
+pub struct EscalationPolicy {
+    consent: Arc<ConsentRegistry>,
+    inner: Mutex<EscalationInner>,
 }
 
-struct SafetyMonitorInner {
-    /// Event trace for temporal checks
-    trace: std::collections::VecDeque<FfiKernelEvent>,
-    /// Recorded violations
-    violations: Vec<FfiSafetyViolation>,
-    /// Last tempo value for rate limiting
-    last_tempo: f32,
-    /// Last tempo change timestamp
-    last_tempo_change_ms: i64,
-    /// Last pattern change timestamp
-    last_pattern_change_ms: i64,
-    /// Maximum trace size
-    max_trace_size: usize,
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct EscalationInner {
+    config: FfiEscalationConfig,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+    #[serde(skip)]
+    recent_critical_ms: std::collections::VecDeque<i64>,
+    #[serde(skip)]
+    hr_anomaly_since_us: Option<i64>,
+    #[serde(skip)]
+    last_escalated_ms: Option<i64>,
 }
 
-impl SafetyMonitor {
-    /// Create a new safety monitor
-    pub fn new() -> Self {
-        SafetyMonitor {
-            inner: Mutex::new(SafetyMonitorInner {
-                trace: std::collections::VecDeque::with_capacity(100),
-                violations: Vec::new(),
-                last_tempo: 1.0,
-                last_tempo_change_ms: 0,
-                last_pattern_change_ms: 0,
-                max_trace_size: 100,
-            }),
+#[cfg(feature = "full")]
+impl EscalationPolicy {
+    pub fn new(consent: Arc<ConsentRegistry>) -> Self {
+        Self {
+            consent,
+            inner: Mutex::new(EscalationInner::default()),
         }
     }
 
-    /// Check an event against all safety specs
-    /// Returns safety check result with any violations and corrections
-    pub fn check_event(
-        &self,
-        event: FfiKernelEvent,
-        runtime_state: FfiRuntimeState,
-    ) -> FfiSafetyCheckResult {
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read escalation config: {}", e)))?;
+            serde_json::from_slice::<EscalationInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt escalation config file: {}", e)))?
+        } else {
+            EscalationInner::default()
+        };
+        loaded.path = Some(path);
+        *self.inner.lock() = loaded;
+        Ok(())
+    }
+
+    fn set_config(&self, config: FfiEscalationConfig) -> Result<(), ZenOneError> {
+        config.validate()?;
         let mut inner = self.inner.lock();
-        let mut violations = Vec::new();
-        let mut corrected_event = None;
+        inner.config = config;
+        inner.recent_critical_ms.clear();
+        inner.hr_anomaly_since_us = None;
+        Self::persist(&inner)
+    }
 
-        // Add event to trace
-        inner.trace.push_back(event.clone());
-        if inner.trace.len() > inner.max_trace_size {
-            inner.trace.pop_front();
+    fn config(&self) -> FfiEscalationConfig {
+        self.inner.lock().config.clone()
+    }
+
+    /// Count a critical violation, escalating once enough pile up within the window
+    fn observe_critical(&self, description: &str, now_ms: i64) -> Option<FfiEscalation> {
+        let mut inner = self.inner.lock();
+        if !inner.config.enabled {
+            return None;
+        }
+        let window_ms = inner.config.violation_window_sec as i64 * 1000;
+        inner.recent_critical_ms.push_back(now_ms);
+        while inner.recent_critical_ms.front().is_some_and(|t| now_ms - t > window_ms) {
+            inner.recent_critical_ms.pop_front();
         }
+        let count = inner.recent_critical_ms.len();
+        if count < inner.config.critical_violation_count as usize {
+            return None;
+        }
+        inner.recent_critical_ms.clear();
+        let detail = format!(
+            "{} critical safety events within {} min, latest: {}",
+            count,
+            inner.config.violation_window_sec.div_ceil(60),
+            description
+        );
+        self.escalate(&mut inner, FfiEscalationReason::RepeatedCriticalViolations, detail, now_ms)
+    }
 
-        // === SAFETY SPEC 1: Tempo Bounds ===
-        // G(tempo >= 0.8 && tempo <= 1.4)
-        if runtime_state.tempo_scale < 0.8 || runtime_state.tempo_scale > 1.4 {
-            violations.push(FfiSafetyViolation {
-                spec_name: "tempo_bounds".to_string(),
-                description: format!(
-                    "Tempo {} outside safe range [0.8, 1.4]",
-                    runtime_state.tempo_scale
-                ),
-                severity: FfiViolationSeverity::Error,
-                timestamp_ms: event.timestamp_ms,
-                corrective_action: Some("Clamp tempo to safe range".to_string()),
-            });
+    /// Track how long heart rate has been out of bounds, by sample time
+    fn observe_heart_rate(&self, bpm: f32, timestamp_us: i64, now_ms: i64) -> Option<FfiEscalation> {
+        let mut inner = self.inner.lock();
+        let config = &inner.config;
+        if !config.enabled || (config.hr_low_bpm..=config.hr_high_bpm).contains(&bpm) {
+            inner.hr_anomaly_since_us = None;
+            return None;
         }
+        let since_us = *inner.hr_anomaly_since_us.get_or_insert(timestamp_us);
+        let sustained_sec = (timestamp_us - since_us) as f32 / 1_000_000.0;
+        if sustained_sec < inner.config.hr_anomaly_sec {
+            return None;
+        }
+        inner.hr_anomaly_since_us = None;
+        let detail = format!(
+            "heart rate {:.0} bpm, outside {:.0}-{:.0} bpm for {:.0} s",
+            bpm, inner.config.hr_low_bpm, inner.config.hr_high_bpm, sustained_sec
+        );
+        self.escalate(&mut inner, FfiEscalationReason::SustainedHeartRateAnomaly, detail, now_ms)
+    }
 
-        // === SAFETY SPEC 2: Safety Lock Immutability ===
-        // G(status == SAFETY_LOCK -> !StartSession)
-        if runtime_state.status == FfiRuntimeStatus::SafetyLock {
-            if matches!(event.event_type, FfiKernelEventType::StartSession) {
-                violations.push(FfiSafetyViolation {
-                    spec_name: "safety_lock_immutable".to_string(),
-                    description: "Cannot start session while safety locked".to_string(),
-                    severity: FfiViolationSeverity::Critical,
-                    timestamp_ms: event.timestamp_ms,
-                    corrective_action: Some("Block event".to_string()),
-                });
-                // Block event
-                corrected_event = None;
-            }
+    fn escalate(&self, inner: &mut EscalationInner, reason: FfiEscalationReason, detail: String, now_ms: i64) -> Option<FfiEscalation> {
+        let cooldown_ms = inner.config.cooldown_sec as i64 * 1000;
+        if inner.last_escalated_ms.is_some_and(|t| now_ms - t < cooldown_ms) {
+            return None;
         }
+        if !self.consent.is_granted(FfiConsentScope::EmergencyContact) {
+            log::warn!("Escalation suppressed, no EmergencyContact consent: {}", detail);
+            return None;
+        }
+        inner.last_escalated_ms = Some(now_ms);
+        let at = chrono::DateTime::from_timestamp_millis(now_ms)
+            .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default();
+        let message = format!(
+            "Hi {}, this is an automatic safety alert from ZenOne ({}): {}. You are listed as a trusted contact - please check in.",
+            inner.config.contact_name, at, detail
+        );
+        Some(FfiEscalation { reason, contact_name: inner.config.contact_name.clone(), message, timestamp_ms: now_ms })
+    }
 
-        // === SAFETY SPEC 3: Tempo Rate Limit ===
-        // G(|d(tempo)/dt| <= 0.1/sec)
-        if matches!(event.event_type, FfiKernelEventType::AdjustTempo) {
-            let dt_sec = (event.timestamp_ms - inner.last_tempo_change_ms) as f32 / 1000.0;
-            if dt_sec > 0.0 {
-                let tempo_delta = (runtime_state.tempo_scale - inner.last_tempo).abs();
-                let rate = tempo_delta / dt_sec;
-                
-                if rate > 0.1 {
-                    violations.push(FfiSafetyViolation {
-                        spec_name: "tempo_rate_limit".to_string(),
-                        description: format!(
-                            "Tempo changing too fast: {:.3}/sec (max 0.1/sec)",
-                            rate
-                        ),
-                        severity: FfiViolationSeverity::Warning,
-                        timestamp_ms: event.timestamp_ms,
-                        corrective_action: Some("Rate-limit tempo change".to_string()),
-                    });
-                }
-            }
-            inner.last_tempo = runtime_state.tempo_scale;
-            inner.last_tempo_change_ms = event.timestamp_ms;
+    fn persist(inner: &EscalationInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Escalation config serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist escalation config: {}", e)))
+    }
+}
+
+// ============================================================================
+// RPPG CALIBRATION - PER-DEVICE BIAS
+// ============================================================================
+
+#[cfg(feature = "full")]
+const RPPG_CALIBRATION_FILE: &str = "rppg_calibration.json";
+/// Strap samples are matched against camera estimates within this distance
+#[cfg(feature = "full")]
+const CALIBRATION_STRAP_WINDOW_US: i64 = 2_500_000;
+/// Camera estimates below this confidence are left out of the fit
+#[cfg(feature = "full")]
+const CALIBRATION_MIN_CONFIDENCE: f32 = 0.5;
+#[cfg(feature = "full")]
+const CALIBRATION_MIN_STRAP_PAIRS: usize = 10;
+/// Fitting a scale needs a spread of heart rates; otherwise only the offset is fitted
+#[cfg(feature = "full")]
+const CALIBRATION_SCALE_MIN_SPAN_BPM: f32 = 10.0;
+#[cfg(feature = "full")]
+const CALIBRATION_SCALE_RANGE: (f32, f32) = (0.8, 1.25);
+/// A larger disagreement points at a bad reference rather than camera bias
+#[cfg(feature = "full")]
+const CALIBRATION_MAX_BIAS_BPM: f32 = 30.0;
+/// Residual error at which the confidence prior bottoms out
+#[cfg(feature = "full")]
+const CALIBRATION_PRIOR_ERROR_BPM: f32 = 10.0;
+#[cfg(feature = "full")]
+const CALIBRATION_MIN_PRIOR: f32 = 0.3;
+
+/// Source of the reference heart rate during calibration
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiCalibrationReference {
+    /// Chest strap or watch streamed through `ingest_hr_sample`
+    Strap,
+    /// Pulse counted by hand and entered with `add_manual_hr_reference`
+    ManualCount,
+}
+
+/// Camera heart rate correction for one device: `scale * hr + offset_bpm`
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiRppgCalibration {
+    pub device_id: String,
+    pub reference: FfiCalibrationReference,
+    pub scale: f32,
+    pub offset_bpm: f32,
+    /// Multiplier on rPPG confidence, lower when the device stays noisy after correction
+    pub confidence_prior: f32,
+    pub pairs: u32,
+    /// Mean absolute error against the reference, before and after correction
+    pub error_before_bpm: f32,
+    pub error_after_bpm: f32,
+    pub calibrated_at_ms: i64,
+}
+
+#[cfg(feature = "full")]
+impl FfiRppgCalibration {
+    fn apply(&self, hr: f32, confidence: f32) -> (f32, f32) {
+        (self.scale * hr + self.offset_bpm, confidence * self.confidence_prior)
+    }
+}
+
+/// Camera estimates and reference readings collected during one calibration
+#[cfg(feature = "full")]
+struct CalibrationRun {
+    device_id: String,
+    reference: FfiCalibrationReference,
+    /// (timestamp_us, uncorrected bpm)
+    camera: Vec<(i64, f32)>,
+    /// (start_us, end_us, reference bpm)
+    references: Vec<(i64, i64, f32)>,
+}
+
+#[cfg(feature = "full")]
+impl CalibrationRun {
+    fn new(device_id: String, reference: FfiCalibrationReference) -> Self {
+        Self { device_id, reference, camera: Vec::new(), references: Vec::new() }
+    }
+
+    fn observe_camera(&mut self, hr: f32, confidence: f32, timestamp_us: i64) {
+        if confidence >= CALIBRATION_MIN_CONFIDENCE {
+            self.camera.push((timestamp_us, hr));
         }
+    }
 
-        // === SAFETY SPEC 4: Pattern Stability ===
-        // G(LoadPattern -> X^60s(!LoadPattern))
-        if matches!(event.event_type, FfiKernelEventType::LoadPattern) {
-            let dt_sec = (event.timestamp_ms - inner.last_pattern_change_ms) as f32 / 1000.0;
-            if dt_sec < 60.0 && inner.last_pattern_change_ms > 0 {
-                violations.push(FfiSafetyViolation {
-                    spec_name: "pattern_stability".to_string(),
-                    description: format!(
-                        "Pattern changed too soon ({:.1}s < 60s min)",
-                        dt_sec
-                    ),
-                    severity: FfiViolationSeverity::Warning,
-                    timestamp_ms: event.timestamp_ms,
-                    corrective_action: None,
-                });
-            }
-            inner.last_pattern_change_ms = event.timestamp_ms;
+    fn observe_strap(&mut self, bpm: f32, timestamp_us: i64) {
+        if self.reference == FfiCalibrationReference::Strap {
+            self.references.push((timestamp_us - CALIBRATION_STRAP_WINDOW_US, timestamp_us + CALIBRATION_STRAP_WINDOW_US, bpm));
         }
+    }
 
-        // === SAFETY SPEC 5: Panic Halt ===
-        // G(prediction_error > 0.8 -> F EmergencyHalt)
-        if runtime_state.belief.uncertainty > 0.8 {
-            // Check if emergency halt was recently triggered
-            let has_recent_halt = inner.trace.iter().rev().take(10).any(|e| {
-                matches!(e.event_type, FfiKernelEventType::EmergencyHalt)
-            });
-            
-            if !has_recent_halt && !matches!(event.event_type, FfiKernelEventType::EmergencyHalt) {
-                violations.push(FfiSafetyViolation {
-                    spec_name: "panic_halt".to_string(),
-                    description: "High uncertainty detected, emergency halt recommended".to_string(),
-                    severity: FfiViolationSeverity::Critical,
-                    timestamp_ms: event.timestamp_ms,
-                    corrective_action: Some("Trigger emergency halt".to_string()),
-                });
-            }
+    /// A hand count covers the last `duration_sec` of camera estimates
+    fn add_manual(&mut self, bpm: f32, duration_sec: f32) -> Result<(), ZenOneError> {
+        let invalid = |msg: String| Err(ZenOneError::ConfigError(msg));
+        if self.reference != FfiCalibrationReference::ManualCount {
+            return invalid("This calibration uses a heart rate strap".into());
         }
+        if !(30.0..=220.0).contains(&bpm) {
+            return invalid(format!("Counted heart rate must be 30-220 bpm, got {}", bpm));
+        }
+        if !(10.0..=120.0).contains(&duration_sec) {
+            return invalid(format!("Count for 10-120 s, got {}", duration_sec));
+        }
+        let Some(&(end_us, _)) = self.camera.last() else {
+            return invalid("No camera heart rate yet, keep the camera on the skin".into());
+        };
+        self.references.push((end_us - (duration_sec * 1_000_000.0) as i64, end_us, bpm));
+        Ok(())
+    }
 
-        // Record violations
-        for v in &violations {
-            inner.violations.push(v.clone());
+    fn finish(self) -> Result<FfiRppgCalibration, ZenOneError> {
+        // (mean camera bpm over the reference window, reference bpm)
+        let pairs: Vec<(f32, f32)> = self.references.iter().filter_map(|&(start_us, end_us, reference)| {
+            let window: Vec<f32> = self.camera.iter()
+                .filter(|(t, _)| (start_us..=end_us).contains(t))
+                .map(|(_, hr)| *hr)
+                .collect();
+            (!window.is_empty()).then(|| (window.iter().sum::<f32>() / window.len() as f32, reference))
+        }).collect();
+        let min_pairs = match self.reference {
+            FfiCalibrationReference::Strap => CALIBRATION_MIN_STRAP_PAIRS,
+            FfiCalibrationReference::ManualCount => 1,
+        };
+        if pairs.len() < min_pairs {
+            return Err(ZenOneError::ConfigError(format!(
+                "Only {} camera/reference pairs, need {}", pairs.len(), min_pairs
+            )));
         }
 
-        FfiSafetyCheckResult {
-            is_safe: violations.is_empty(),
-            violations,
-            corrected_event,
+        let n = pairs.len() as f32;
+        let mean_camera = pairs.iter().map(|(c, _)| c).sum::<f32>() / n;
+        let mean_reference = pairs.iter().map(|(_, r)| r).sum::<f32>() / n;
+        if (mean_reference - mean_camera).abs() > CALIBRATION_MAX_BIAS_BPM {
+            return Err(ZenOneError::ConfigError(format!(
+                "Camera and reference differ by {:.0} bpm, check the reference", mean_reference - mean_camera
+            )));
         }
+        let (lo, hi) = pairs.iter().fold((f32::MAX, f32::MIN), |(lo, hi), (_, r)| (lo.min(*r), hi.max(*r)));
+        let variance: f32 = pairs.iter().map(|(c, _)| (c - mean_camera).powi(2)).sum();
+        let scale = if hi - lo >= CALIBRATION_SCALE_MIN_SPAN_BPM && variance > 0.0 {
+            let covariance: f32 = pairs.iter().map(|(c, r)| (c - mean_camera) * (r - mean_reference)).sum();
+            (covariance / variance).clamp(CALIBRATION_SCALE_RANGE.0, CALIBRATION_SCALE_RANGE.1)
+        } else {
+            1.0
+        };
+        let offset_bpm = mean_reference - scale * mean_camera;
+        let mean_error = |correct: &dyn Fn(f32) -> f32| {
+            pairs.iter().map(|(c, r)| (correct(*c) - r).abs()).sum::<f32>() / n
+        };
+        let error_before_bpm = mean_error(&|c| c);
+        let error_after_bpm = mean_error(&|c| scale * c + offset_bpm);
+
+        Ok(FfiRppgCalibration {
+            device_id: self.device_id,
+            reference: self.reference,
+            scale,
+            offset_bpm,
+            confidence_prior: (1.0 - error_after_bpm / CALIBRATION_PRIOR_ERROR_BPM).clamp(CALIBRATION_MIN_PRIOR, 1.0),
+            pairs: pairs.len() as u32,
+            error_before_bpm,
+            error_after_bpm,
+            calibrated_at_ms: Utc::now().timestamp_millis(),
+        })
     }
+}
 
-    /// Get all recorded violations
-    pub fn get_violations(&self) -> Vec<FfiSafetyViolation> {
-        self.inner.lock().violations.clone()
+/// Saved calibrations, one per device id
+#[cfg(feature = "full")]
+pub struct RppgCalibrationStore {
+    inner: Mutex<RppgCalibrationInner>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct RppgCalibrationInner {
+    calibrations: Vec<FfiRppgCalibration>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+impl Default for RppgCalibrationStore {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    /// Get recent violations (last N)
-    pub fn get_recent_violations(&self, count: u32) -> Vec<FfiSafetyViolation> {
-        let inner = self.inner.lock();
-        inner.violations.iter()
-            .rev()
-            .take(count as usize)
-            .cloned()
-            .collect()
+#[cfg(feature = "full")]
+impl RppgCalibrationStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(RppgCalibrationInner::default()),
+        }
     }
 
-    /// Clear violation history
-    pub fn clear_violations(&self) {
-        self.inner.lock().violations.clear();
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read rPPG calibration: {}", e)))?;
+            serde_json::from_slice::<RppgCalibrationInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt rPPG calibration file: {}", e)))?
+        } else {
+            RppgCalibrationInner::default()
+        };
+        loaded.path = Some(path);
+        *self.inner.lock() = loaded;
+        Ok(())
     }
 
-    /// Get violation count by severity
-    pub fn get_violation_counts(&self) -> (u32, u32, u32) {
-        let inner = self.inner.lock();
-        let warnings = inner.violations.iter()
-            .filter(|v| v.severity == FfiViolationSeverity::Warning)
-            .count() as u32;
-        let errors = inner.violations.iter()
-            .filter(|v| v.severity == FfiViolationSeverity::Error)
-            .count() as u32;
-        let criticals = inner.violations.iter()
-            .filter(|v| v.severity == FfiViolationSeverity::Critical)
-            .count() as u32;
-        (warnings, errors, criticals)
+    fn get(&self, device_id: &str) -> Option<FfiRppgCalibration> {
+        self.inner.lock().calibrations.iter().find(|c| c.device_id == device_id).cloned()
     }
 
-    /// Check if system is in safe state
-    pub fn is_safe(&self, runtime_state: FfiRuntimeState) -> bool {
-        // Basic safety checks without event context
-        runtime_state.tempo_scale >= 0.8 
-            && runtime_state.tempo_scale <= 1.4
-            && runtime_state.status != FfiRuntimeStatus::SafetyLock
+    fn save(&self, calibration: FfiRppgCalibration) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        inner.calibrations.retain(|c| c.device_id != calibration.device_id);
+        inner.calibrations.push(calibration);
+        Self::persist(&inner)
+    }
+
+    fn list(&self) -> Vec<FfiRppgCalibration> {
+        self.inner.lock().calibrations.clone()
+    }
+
+    fn persist(inner: &RppgCalibrationInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("rPPG calibration serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist rPPG calibration: {}", e)))
     }
 }
 
 // ============================================================================
-// PATTERN RECOMMENDER - AI-POWERED SUGGESTIONS
+// PATTERN VALIDATION - PHYSIOLOGICAL LIMITS
 // ============================================================================
 
-/// Time of day for recommendations
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum FfiTimeOfDay {
-    Morning,
-    Afternoon,
-    Evening,
-    Night,
+#[cfg(feature = "full")]
+const PATTERN_MIN_BREATH_SEC: f32 = 1.0;
+#[cfg(feature = "full")]
+const PATTERN_MAX_BREATH_SEC: f32 = 30.0;
+/// Holds past this are flagged; past the max they are rejected
+#[cfg(feature = "full")]
+const PATTERN_LONG_HOLD_SEC: f32 = 20.0;
+#[cfg(feature = "full")]
+const PATTERN_MAX_HOLD_SEC: f32 = 60.0;
+/// 30 breaths/min
+#[cfg(feature = "full")]
+const PATTERN_MIN_CYCLE_SEC: f32 = 2.0;
+/// Faster breathing kept up longer than this risks hyperventilation
+#[cfg(feature = "full")]
+const PATTERN_FAST_RATE_BPM: f32 = 20.0;
+#[cfg(feature = "full")]
+const PATTERN_FAST_SUSTAINED_SEC: f32 = 60.0;
+
+/// One problem with a pattern's timings
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiPatternIssue {
+    /// "inhale", "hold_in", "exhale", "hold_out" or "cycle"
+    pub field: String,
+    pub message: String,
+    /// Warnings are let through; Error and Critical block the pattern
+    pub severity: FfiViolationSeverity,
 }
 
-impl FfiTimeOfDay {
-    pub fn from_hour(hour: u8) -> Self {
-        match hour {
-            0..=5 => FfiTimeOfDay::Night,
-            6..=11 => FfiTimeOfDay::Morning,
-            12..=17 => FfiTimeOfDay::Afternoon,
-            18..=21 => FfiTimeOfDay::Evening,
-            _ => FfiTimeOfDay::Night,
+/// Checks breathing pattern timings against physiological limits before a
+/// pattern is saved, adjusted or loaded
+#[cfg(feature = "full")]
+pub struct PatternValidator;
+
+#[cfg(feature = "full")]
+impl PatternValidator {
+    pub fn validate(pattern: &BreathPattern) -> Vec<FfiPatternIssue> {
+        let t = &pattern.timings;
+        let mut issues = Vec::new();
+        let mut flag = |field: &str, severity: FfiViolationSeverity, message: String| {
+            issues.push(FfiPatternIssue { field: field.to_string(), message, severity });
+        };
+
+        for (field, seconds) in [("inhale", t.inhale), ("exhale", t.exhale)] {
+            if !(seconds.is_finite() && (PATTERN_MIN_BREATH_SEC..=PATTERN_MAX_BREATH_SEC).contains(&seconds)) {
+                flag(field, FfiViolationSeverity::Error, format!(
+                    "{} must be {}-{} s, got {}", field, PATTERN_MIN_BREATH_SEC, PATTERN_MAX_BREATH_SEC, seconds
+                ));
+            }
+        }
+        for (field, seconds) in [("hold_in", t.hold_in), ("hold_out", t.hold_out)] {
+            if !(seconds.is_finite() && (0.0..=PATTERN_MAX_HOLD_SEC).contains(&seconds)) {
+                flag(field, FfiViolationSeverity::Critical, format!(
+                    "{} must be 0-{} s, got {}", field, PATTERN_MAX_HOLD_SEC, seconds
+                ));
+            } else if seconds > PATTERN_LONG_HOLD_SEC {
+                flag(field, FfiViolationSeverity::Warning, format!(
+                    "{} of {} s is a long breath hold", field, seconds
+                ));
+            }
+        }
+
+        let cycle = t.inhale + t.hold_in + t.exhale + t.hold_out;
+        if cycle.is_finite() {
+            let rate_bpm = 60.0 / cycle;
+            let sustained_sec = cycle * pattern.recommended_cycles as f32;
+            if cycle < PATTERN_MIN_CYCLE_SEC {
+                flag("cycle", FfiViolationSeverity::Critical, format!(
+                    "{:.0} breaths/min is above the limit of {:.0}", rate_bpm, 60.0 / PATTERN_MIN_CYCLE_SEC
+                ));
+            } else if rate_bpm > PATTERN_FAST_RATE_BPM && sustained_sec > PATTERN_FAST_SUSTAINED_SEC {
+                flag("cycle", FfiViolationSeverity::Warning, format!(
+                    "{:.0} breaths/min for {:.0} s may cause hyperventilation", rate_bpm, sustained_sec
+                ));
+            }
         }
+        issues
     }
-    
-    pub fn desired_arousal(&self) -> f32 {
-        match self {
-            FfiTimeOfDay::Morning => 0.3,    // Slightly energizing
-            FfiTimeOfDay::Afternoon => 0.0,  // Balanced
-            FfiTimeOfDay::Evening => -0.5,   // Relaxing
-            FfiTimeOfDay::Night => -0.8,     // Very sedative
+
+    /// Reject the pattern on any blocking issue, otherwise return its warnings
+    pub fn check(pattern: &BreathPattern) -> Result<Vec<FfiPatternIssue>, ZenOneError> {
+        let (blocking, warnings): (Vec<_>, Vec<_>) = Self::validate(pattern)
+            .into_iter()
+            .partition(|i| i.severity != FfiViolationSeverity::Warning);
+        if blocking.is_empty() {
+            return Ok(warnings);
         }
+        let details: Vec<String> = blocking.iter().map(|i| i.message.clone()).collect();
+        Err(ZenOneError::SafetyViolation(format!("pattern '{}': {}", pattern.id, details.join("; "))))
     }
-    
-    pub fn desired_goal(&self) -> &'static str {
-        match self {
-            FfiTimeOfDay::Morning => "energy",
-            FfiTimeOfDay::Afternoon => "focus",
-            FfiTimeOfDay::Evening => "stress",
-            FfiTimeOfDay::Night => "sleep",
+}
+
+// ============================================================================
+// NOISE PROFILING - MICROPHONE BREATH DETECTION
+// ============================================================================
+
+#[cfg(feature = "full")]
+const NOISE_PROFILE_MIN_SEC: f32 = 3.0;
+#[cfg(feature = "full")]
+const NOISE_PROFILE_MAX_SEC: f32 = 30.0;
+#[cfg(feature = "full")]
+const NOISE_PROFILE_MIN_SAMPLES: usize = 20;
+/// Breath sound must clear the ambient mean by this many standard deviations
+#[cfg(feature = "full")]
+const NOISE_THRESHOLD_SD: f32 = 3.0;
+/// Above this threshold (full scale = 1) breathing is drowned out
+#[cfg(feature = "full")]
+const NOISE_MAX_THRESHOLD: f32 = 0.25;
+
+/// Ambient microphone level, measured with the envelope on a 0-1 full-scale range
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiNoiseProfile {
+    pub measured_at_ms: i64,
+    pub duration_sec: f32,
+    pub samples: u32,
+    pub noise_floor: f32,
+    pub noise_sd: f32,
+    /// Envelope level audio must exceed to count as breathing
+    pub threshold: f32,
+    pub usable: bool,
+    /// Why audio detection can't be used in this room
+    pub unusable_reason: Option<String>,
+}
+
+/// Audio envelope samples collected while the user stays quiet
+#[cfg(feature = "full")]
+struct NoiseProfiling {
+    duration_sec: f32,
+    elapsed_sec: f32,
+    samples: Vec<f32>,
+}
+
+#[cfg(feature = "full")]
+impl NoiseProfiling {
+    fn new(duration_sec: f32) -> Self {
+        Self { duration_sec, elapsed_sec: 0.0, samples: Vec::new() }
+    }
+
+    fn observe(&mut self, value: f32) {
+        if value.is_finite() {
+            self.samples.push(value);
+        }
+    }
+
+    fn report(&self) -> FfiNoiseProfile {
+        let n = self.samples.len() as f32;
+        let (noise_floor, noise_sd) = if self.samples.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let mean = self.samples.iter().sum::<f32>() / n;
+            (mean, (self.samples.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n).sqrt())
+        };
+        let threshold = noise_floor + NOISE_THRESHOLD_SD * noise_sd;
+        let unusable_reason = if self.samples.len() < NOISE_PROFILE_MIN_SAMPLES {
+            Some(format!("Only {} microphone samples during profiling", self.samples.len()))
+        } else if threshold > NOISE_MAX_THRESHOLD {
+            Some(format!(
+                "Room too noisy for audio breath detection (level {:.2}, fluctuation {:.2})",
+                noise_floor, noise_sd
+            ))
+        } else {
+            None
+        };
+        FfiNoiseProfile {
+            measured_at_ms: Utc::now().timestamp_millis(),
+            duration_sec: self.elapsed_sec,
+            samples: self.samples.len() as u32,
+            noise_floor,
+            noise_sd,
+            threshold,
+            usable: unusable_reason.is_none(),
+            unusable_reason,
         }
     }
 }
 
-/// Pattern recommendation result
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FfiPatternRecommendation {
-    pub pattern_id: String,
-    pub score: f32,
-    pub reason: String,
+// ============================================================================
+// FEATURE FLAGS - PER-PROFILE SUBSYSTEM TOGGLES
+// ============================================================================
+
+#[cfg(feature = "full")]
+const FEATURE_FLAGS_FILE: &str = "feature_flags.json";
+
+/// Subsystems that can be switched on or off per profile
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FfiFeatureFlag {
+    /// Camera heart rate
+    Rppg,
+    /// Binaural beat playback tracking
+    Binaural,
+    /// Recommender learning from pattern usage
+    RecommenderLearning,
+    /// Raw signal recording for research
+    ResearchMode,
+    /// Shared-clock group sessions over the LAN
+    Sync,
 }
 
-/// Pattern metadata for scoring
-struct PatternMeta {
-    id: &'static str,
-    arousal: f32,
-    complexity: u8,
-    best_for: &'static [&'static str],
+#[cfg(feature = "full")]
+impl FfiFeatureFlag {
+    pub const ALL: [FfiFeatureFlag; 5] = [
+        FfiFeatureFlag::Rppg,
+        FfiFeatureFlag::Binaural,
+        FfiFeatureFlag::RecommenderLearning,
+        FfiFeatureFlag::ResearchMode,
+        FfiFeatureFlag::Sync,
+    ];
+
+    /// Established subsystems default on; experimental ones ship dark
+    fn default_enabled(&self) -> bool {
+        !matches!(self, FfiFeatureFlag::ResearchMode)
+    }
 }
 
-const PATTERN_METADATA: &[PatternMeta] = &[
-    PatternMeta { id: "4-7-8", arousal: -0.8, complexity: 1, best_for: &["sleep", "stress"] },
-    PatternMeta { id: "box", arousal: 0.0, complexity: 1, best_for: &["focus", "general"] },
-    PatternMeta { id: "calm", arousal: -0.3, complexity: 1, best_for: &["general", "stress"] },
-    PatternMeta { id: "coherence", arousal: -0.5, complexity: 2, best_for: &["focus", "general"] },
-    PatternMeta { id: "deep-relax", arousal: -0.9, complexity: 1, best_for: &["stress", "sleep"] },
-    PatternMeta { id: "7-11", arousal: -1.0, complexity: 2, best_for: &["stress", "sleep"] },
-    PatternMeta { id: "awake", arousal: 0.8, complexity: 2, best_for: &["energy"] },
-    PatternMeta { id: "triangle", arousal: 0.2, complexity: 1, best_for: &["general", "focus"] },
-    PatternMeta { id: "tactical", arousal: 0.1, complexity: 2, best_for: &["focus"] },
-    PatternMeta { id: "buteyko", arousal: -0.2, complexity: 3, best_for: &["general"] },
-    PatternMeta { id: "wim-hof", arousal: 1.0, complexity: 3, best_for: &["energy"] },
-];
+/// Current value of one flag
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FfiFeatureFlagState {
+    pub flag: FfiFeatureFlag,
+    pub enabled: bool,
+    /// Whether the profile differs from the built-in default
+    pub overridden: bool,
+}
 
-/// Pattern Recommender - AI-powered pattern suggestions
-/// 
-/// Recommends patterns based on:
-/// - Time of day (arousal matching)
-/// - Recent session history (variety bonus)
-/// - Pattern complexity
-/// - Time-specific bonuses
-pub struct PatternRecommender {
-    inner: Mutex<PatternRecommenderInner>,
+/// Registry of subsystem flags; only values that differ from the defaults are stored
+#[cfg(feature = "full")]
+pub struct FeatureFlags {
+    inner: Mutex<FeatureFlagsInner>,
 }
 
-struct PatternRecommenderInner {
-    recent_patterns: Vec<String>,
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct FeatureFlagsInner {
+    overrides: HashMap<FfiFeatureFlag, bool>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
 }
 
-impl PatternRecommender {
+#[cfg(feature = "full")]
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl FeatureFlags {
     pub fn new() -> Self {
         Self {
-            inner: Mutex::new(PatternRecommenderInner {
-                recent_patterns: Vec::new(),
-            }),
-        }
-    }
-    
-    /// Add a pattern to recent history
-    pub fn record_pattern(&self, pattern_id: String) {
-        let mut inner = self.inner.lock();
-        inner.recent_patterns.insert(0, pattern_id);
-        if inner.recent_patterns.len() > 5 {
-            inner.recent_patterns.truncate(5);
+            inner: Mutex::new(FeatureFlagsInner::default()),
         }
     }
-    
-    /// Clear recent history
-    pub fn clear_history(&self) {
-        let mut inner = self.inner.lock();
-        inner.recent_patterns.clear();
+
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read feature flags: {}", e)))?;
+            serde_json::from_slice::<FeatureFlagsInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt feature flags file: {}", e)))?
+        } else {
+            FeatureFlagsInner::default()
+        };
+        loaded.path = Some(path);
+        *self.inner.lock() = loaded;
+        Ok(())
     }
-    
-    /// Get recommendations based on current time
-    pub fn recommend(&self, local_hour: u8, limit: u32) -> Vec<FfiPatternRecommendation> {
-        let inner = self.inner.lock();
-        let time_of_day = FfiTimeOfDay::from_hour(local_hour);
-        let desired_arousal = time_of_day.desired_arousal();
-        let desired_goal = time_of_day.desired_goal();
-        
-        let mut scored: Vec<FfiPatternRecommendation> = PATTERN_METADATA.iter().map(|pattern| {
-            let mut score: f32 = 0.0;
-            let mut reasons: Vec<&str> = Vec::new();
-            
-            // Arousal match (0-40 points)
-            let arousal_diff = (pattern.arousal - desired_arousal).abs();
-            let arousal_score = (40.0 - arousal_diff * 30.0).max(0.0);
-            score += arousal_score;
-            
-            // Goal match (0-30 points)
-            if pattern.best_for.contains(&desired_goal) {
-                score += 30.0;
-                reasons.push(match desired_goal {
-                    "sleep" => "Great for sleep",
-                    "focus" => "Great for focus",
-                    "stress" => "Great for stress relief",
-                    "energy" => "Great for energy",
-                    _ => "Recommended for you",
-                });
-            }
-            
-            // Variety bonus (0-20 points)
-            let times_recent = inner.recent_patterns.iter()
-                .filter(|p| p.as_str() == pattern.id)
-                .count() as f32;
-            let variety_score = (20.0 - times_recent * 10.0).max(0.0);
-            score += variety_score;
-            if times_recent == 0.0 {
-                reasons.push("Try something new");
-            }
-            
-            // Complexity consideration (0-10 points)
-            score += (4 - pattern.complexity) as f32 * 3.0;
-            
-            // Time-specific bonuses
-            match (time_of_day, pattern.id) {
-                (FfiTimeOfDay::Morning, "awake") => {
-                    score += 15.0;
-                    reasons.insert(0, "Perfect for morning energy");
-                }
-                (FfiTimeOfDay::Night, "4-7-8") => {
-                    score += 15.0;
-                    reasons.insert(0, "Ideal for sleep");
-                }
-                (FfiTimeOfDay::Afternoon, "box") => {
-                    score += 10.0;
-                    reasons.insert(0, "Great for afternoon focus");
+
+    pub fn is_enabled(&self, flag: FfiFeatureFlag) -> bool {
+        self.inner.lock().overrides.get(&flag).copied().unwrap_or_else(|| flag.default_enabled())
+    }
+
+    /// Fail with CapabilityUnavailable when `flag` is off
+    fn require(&self, flag: FfiFeatureFlag) -> Result<(), ZenOneError> {
+        if self.is_enabled(flag) {
+            Ok(())
+        } else {
+            Err(ZenOneError::CapabilityUnavailable(format!("{:?} is disabled for this profile", flag)))
+        }
+    }
+
+    fn set(&self, flag: FfiFeatureFlag, enabled: bool) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        if enabled == flag.default_enabled() {
+            inner.overrides.remove(&flag);
+        } else {
+            inner.overrides.insert(flag, enabled);
+        }
+        log::info!("FeatureFlags: {:?} {}", flag, if enabled { "enabled" } else { "disabled" });
+        Self::persist(&inner)
+    }
+
+    fn list(&self) -> Vec<FfiFeatureFlagState> {
+        let inner = self.inner.lock();
+        FfiFeatureFlag::ALL
+            .iter()
+            .map(|&flag| {
+                let value = inner.overrides.get(&flag).copied();
+                FfiFeatureFlagState {
+                    flag,
+                    enabled: value.unwrap_or_else(|| flag.default_enabled()),
+                    overridden: value.is_some(),
                 }
-                _ => {}
-            }
-            
-            let reason = reasons.first().copied().unwrap_or("Recommended for you").to_string();
-            
-            FfiPatternRecommendation {
-                pattern_id: pattern.id.to_string(),
-                score,
-                reason,
-            }
-        }).collect();
-        
-        // Sort by score descending
-        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-        
-        // Return top N
-        scored.truncate(limit as usize);
-        scored
+            })
+            .collect()
     }
-    
-    /// Get top recommendation with explanation
-    pub fn top_recommendation(&self, local_hour: u8) -> Option<FfiPatternRecommendation> {
-        self.recommend(local_hour, 1).into_iter().next()
+
+    fn persist(inner: &FeatureFlagsInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Feature flag serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist feature flags: {}", e)))
     }
 }
 
 // ============================================================================
-// BINAURAL BEATS ENGINE (PARTIAL MIGRATION)
+// USER SETTINGS - PERSISTED RUNTIME PREFERENCES
+// ============================================================================
+
+#[cfg(feature = "full")]
+const USER_SETTINGS_FILE: &str = "settings.json";
+
+/// Preferences set through the runtime that must survive a restart. Fields
+/// stay None until the user first changes them, so defaults can still evolve.
+#[cfg(feature = "full")]
+#[derive(Default)]
+struct UserSettings {
+    inner: Mutex<UserSettingsInner>,
+}
+
+#[cfg(feature = "full")]
+#[derive(Default, Serialize, Deserialize)]
+struct UserSettingsInner {
+    #[serde(default)]
+    hr_zones: Option<FfiHrZoneConfig>,
+    #[serde(default)]
+    streak: Option<FfiStreakConfig>,
+    #[serde(default)]
+    onboarding: Option<FfiOnboardingAnswers>,
+    #[serde(default)]
+    retention: Option<FfiRetentionPolicy>,
+    /// IANA name; None follows the device
+    #[serde(default)]
+    time_zone: Option<String>,
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "full")]
+impl UserSettings {
+    fn attach_file(&self, path: std::path::PathBuf) -> Result<(), ZenOneError> {
+        let mut loaded = if path.exists() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| ZenOneError::ConfigError(format!("Cannot read settings: {}", e)))?;
+            serde_json::from_slice::<UserSettingsInner>(&bytes)
+                .map_err(|e| ZenOneError::ConfigError(format!("Corrupt settings file: {}", e)))?
+        } else {
+            UserSettingsInner::default()
+        };
+        loaded.path = Some(path);
+        *self.inner.lock() = loaded;
+        Ok(())
+    }
+
+    fn read<T>(&self, f: impl FnOnce(&UserSettingsInner) -> T) -> T {
+        f(&self.inner.lock())
+    }
+
+    /// Apply `f` and write the file through (no-op on disk until attached)
+    fn update(&self, f: impl FnOnce(&mut UserSettingsInner)) -> Result<(), ZenOneError> {
+        let mut inner = self.inner.lock();
+        f(&mut inner);
+        Self::persist(&inner)
+    }
+
+    fn persist(inner: &UserSettingsInner) -> Result<(), ZenOneError> {
+        let Some(path) = &inner.path else { return Ok(()) };
+        let json = serde_json::to_vec_pretty(inner)
+            .map_err(|e| ZenOneError::ConfigError(format!("Settings serialization failed: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ZenOneError::ConfigError(format!("Cannot persist settings: {}", e)))
+    }
+}
+
 // ============================================================================
+// PREFLIGHT - SESSION READINESS CHECKLIST
+// ============================================================================
+
+/// Camera signal quality below this is flagged before a session
+#[cfg(feature = "full")]
+const PREFLIGHT_MIN_SIGNAL_QUALITY: f32 = 0.4;
+/// Battery percentages (unplugged) that warn / block a session
+#[cfg(feature = "full")]
+const PREFLIGHT_BATTERY_WARN_PCT: f32 = 20.0;
+#[cfg(feature = "full")]
+const PREFLIGHT_BATTERY_FAIL_PCT: f32 = 5.0;
+
+/// What the shell knows that the core can't probe itself
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPreflightInput {
+    /// None when the shell hasn't checked (e.g. before asking for permission)
+    pub camera_available: Option<bool>,
+    pub audio_output_available: bool,
+    /// 0-100; None when the platform doesn't report it
+    pub battery_percent: Option<f32>,
+    pub is_charging: bool,
+}
 
+#[cfg(feature = "full")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum FfiBrainWaveState {
-    Delta,
-    Theta,
-    Alpha,
-    Beta,
+pub enum FfiPreflightItem {
+    CameraSignal,
+    Safety,
+    Storage,
+    AudioOutput,
+    Battery,
+}
+
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FfiPreflightStatus {
+    Pass,
+    /// The session can run, but degraded
+    Warn,
+    /// Starting now would fail or be cut short
+    Fail,
 }
 
+#[cfg(feature = "full")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FfiBinauralConfig {
-    pub base_freq: f32,
-    pub beat_freq: f32,
-    pub description: String,
-    pub benefits: Vec<String>,
+pub struct FfiPreflightCheck {
+    pub item: FfiPreflightItem,
+    pub status: FfiPreflightStatus,
+    pub message: String,
+}
+
+/// Readiness report shown before start_session
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiPreflightReport {
+    /// No check failed
+    pub ready: bool,
+    pub checks: Vec<FfiPreflightCheck>,
 }
 
-pub struct BinauralManager;
+#[cfg(feature = "full")]
+struct Preflight;
 
-impl BinauralManager {
-    pub fn new() -> Self {
-        Self
+#[cfg(feature = "full")]
+impl Preflight {
+    fn check(item: FfiPreflightItem, status: FfiPreflightStatus, message: impl Into<String>) -> FfiPreflightCheck {
+        FfiPreflightCheck { item, status, message: message.into() }
     }
 
-    pub fn get_config(&self, state: FfiBrainWaveState) -> FfiBinauralConfig {
-        match state {
-            FfiBrainWaveState::Delta => FfiBinauralConfig {
-                base_freq: 200.0,
-                beat_freq: 2.5,
-                description: "Deep Sleep & Healing".to_string(),
-                benefits: vec![
-                    "Deep restorative sleep".to_string(),
-                    "Physical healing".to_string(),
-                    "Pain relief".to_string(),
-                    "Immune boost".to_string()
-                ],
-            },
-            FfiBrainWaveState::Theta => FfiBinauralConfig {
-                base_freq: 200.0,
-                beat_freq: 6.0,
-                description: "Meditation & Creativity".to_string(),
-                benefits: vec![
-                    "Deep meditation".to_string(),
-                    "Creative insights".to_string(),
-                    "Emotional healing".to_string(),
-                    "Vivid imagery".to_string()
-                ],
-            },
-            FfiBrainWaveState::Alpha => FfiBinauralConfig {
-                base_freq: 200.0,
-                beat_freq: 10.0,
-                description: "Relaxed Focus".to_string(),
-                benefits: vec![
-                    "Calm awareness".to_string(),
-                    "Stress reduction".to_string(),
-                    "Peak performance".to_string(),
-                    "Learning enhancement".to_string()
-                ],
-            },
-            FfiBrainWaveState::Beta => FfiBinauralConfig {
-                base_freq: 220.0,
-                beat_freq: 18.0,
-                description: "Active Thinking".to_string(),
-                benefits: vec![
-                    "Mental clarity".to_string(),
-                    "Problem solving".to_string(),
-                    "Concentration".to_string(),
-                    "Energy boost".to_string()
-                ],
-            },
-        }
+    fn camera(rppg_enabled: bool, camera_available: Option<bool>, signal_quality: f32) -> FfiPreflightCheck {
+        use FfiPreflightStatus::*;
+        let (status, message) = if !rppg_enabled {
+            (Pass, "Camera heart rate is turned off".to_string())
+        } else if camera_available == Some(false) {
+            (Warn, "Camera unavailable; heart rate won't be measured by camera".to_string())
+        } else if signal_quality < PREFLIGHT_MIN_SIGNAL_QUALITY {
+            (Warn, format!("Weak camera signal ({:.0}%); check lighting and framing", signal_quality * 100.0))
+        } else {
+            (Pass, format!("Camera signal {:.0}%", signal_quality * 100.0))
+        };
+        Self::check(FfiPreflightItem::CameraSignal, status, message)
     }
-    
-    pub fn get_recommended_state(&self, arousal_target: f32) -> FfiBrainWaveState {
-        if arousal_target < 0.2 {
-            FfiBrainWaveState::Delta
-        } else if arousal_target < 0.4 {
-            FfiBrainWaveState::Theta
-        } else if arousal_target < 0.7 {
-            FfiBrainWaveState::Alpha
+
+    fn safety(status: &FfiSafetyStatus) -> FfiPreflightCheck {
+        use FfiPreflightStatus::*;
+        let (status, message) = if status.is_locked {
+            (Fail, "Safety lock is engaged".to_string())
+        } else if status.unacknowledged_critical > 0 {
+            (Warn, format!("{} critical safety violation(s) not yet acknowledged", status.unacknowledged_critical))
         } else {
-            FfiBrainWaveState::Beta
+            (Pass, "No safety lock".to_string())
+        };
+        Self::check(FfiPreflightItem::Safety, status, message)
+    }
+
+    /// Probe that the data dir is still writable and the session store
+    /// unlocked, so the session record persists
+    fn storage(data_dir: Option<&std::path::Path>, store_unlocked: bool) -> FfiPreflightCheck {
+        use FfiPreflightStatus::*;
+        let Some(dir) = data_dir else {
+            return Self::check(FfiPreflightItem::Storage, Warn, "No data directory; session history won't be saved");
+        };
+        let probe = dir.join(".preflight");
+        let (status, message) = match std::fs::write(&probe, b"ok") {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&probe);
+                match store_unlocked {
+                    true => (Pass, "Profile storage writable".to_string()),
+                    false => (Warn, "Session store is locked; sessions stay in memory until it is unlocked".to_string()),
+                }
+            }
+            Err(e) => (Warn, format!("Profile storage not writable ({}); session history won't be saved", e)),
+        };
+        Self::check(FfiPreflightItem::Storage, status, message)
+    }
+
+    fn audio(available: bool) -> FfiPreflightCheck {
+        match available {
+            true => Self::check(FfiPreflightItem::AudioOutput, FfiPreflightStatus::Pass, "Audio output available"),
+            false => Self::check(FfiPreflightItem::AudioOutput, FfiPreflightStatus::Warn, "No audio output; cues will be visual/haptic only"),
         }
     }
+
+    fn battery(percent: Option<f32>, charging: bool) -> FfiPreflightCheck {
+        use FfiPreflightStatus::*;
+        let (status, message) = match percent {
+            _ if charging => (Pass, "Charging".to_string()),
+            None => (Pass, "Battery level unknown".to_string()),
+            Some(p) if p < PREFLIGHT_BATTERY_FAIL_PCT => (Fail, format!("Battery critically low ({:.0}%)", p)),
+            Some(p) if p < PREFLIGHT_BATTERY_WARN_PCT => (Warn, format!("Battery low ({:.0}%)", p)),
+            Some(p) => (Pass, format!("Battery {:.0}%", p)),
+        };
+        Self::check(FfiPreflightItem::Battery, status, message)
+    }
 }
 
 // ============================================================================
-// SECURE VAULT - ZERO TRUST ENCRYPTION
+// SERIAL PACER - LINE PROTOCOL FOR DIY HARDWARE
 // ============================================================================
+//
+// Streams the pacer to a serial/USB port (e.g. /dev/ttyACM0, COM3) so LED
+// strips or Arduino gadgets can follow a session without Bluetooth. One ASCII
+// line per update, newline terminated:
+//
+//   # zenb-pacer 1               sent once when the port opens
+//   S,<phase>,<permille>,<cycle> phase I/H/E/R (inhale, hold, exhale, rest)
+//                                or P (pre-session countdown),
+//                                progress through the phase 0-1000
+//   X                            session ended; go dark
+//
+// The port is opened at the requested baud rate, 8N1. CDC-ACM boards ignore
+// the rate. Writes time out, so a device that stops reading cannot wedge the
+// writer, and stopping never waits on it for long.
 
-/// Secure Vault for biometric data encryption
-/// Uses Argon2id for key derivation and ChaCha20Poly1305 for encryption.
-///
-/// Blob Format: [Salt (16)] [Nonce (12)] [Ciphertext (...)]
-pub struct SecureVault;
+#[cfg(feature = "full")]
+const SERIAL_PACER_HEADER: &str = "# zenb-pacer 1";
+#[cfg(feature = "full")]
+const SERIAL_PACER_MAX_HZ: f32 = 20.0;
+#[cfg(feature = "full")]
+const SERIAL_PACER_DEFAULT_BAUD: u32 = 115_200;
+/// Lines buffered for a slow port before updates are dropped
+#[cfg(feature = "full")]
+const SERIAL_PACER_QUEUE: usize = 8;
+/// A line not accepted by the port within this time is dropped
+#[cfg(feature = "full")]
+const SERIAL_PACER_WRITE_TIMEOUT: Duration = Duration::from_millis(250);
+/// How long `stop` waits for the writer to close the port before detaching it
+#[cfg(feature = "full")]
+const SERIAL_PACER_STOP_TIMEOUT: Duration = Duration::from_millis(500);
 
-impl SecureVault {
+/// Serial pacer output status
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiSerialPacerStatus {
+    pub port: String,
+    pub baud_rate: u32,
+    pub lines_sent: u64,
+    /// Updates skipped because the port couldn't keep up
+    pub lines_dropped: u64,
+    /// Write failure that stopped the output (e.g. device unplugged)
+    pub error: Option<String>,
+}
+
+/// One open port. Each has its own status, so a writer still draining after
+/// `stop` never touches the next port's counters.
+#[cfg(feature = "full")]
+struct SerialLink {
+    tx: Sender<String>,
+    status: Arc<Mutex<FfiSerialPacerStatus>>,
+    /// Disconnected when the writer thread exits
+    done: Receiver<()>,
+    last_sent: Option<Instant>,
+    last_phase: Option<FfiPhase>,
+}
+
+/// Optional pacer output to a serial port, fed by the runtime actor
+#[cfg(feature = "full")]
+pub struct SerialPacer {
+    link: Mutex<Option<SerialLink>>,
+}
+
+#[cfg(feature = "full")]
+impl Default for SerialPacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "full")]
+impl SerialPacer {
     pub fn new() -> Self {
-        Self
+        Self { link: Mutex::new(None) }
     }
 
-    /// Encrypt biometric data
-    pub fn encrypt_blob(&self, passphrase: String, data: Vec<u8>) -> Result<Vec<u8>, ZenOneError> {
-        // 1. Generate Salt
-        // Use raw salt bytes for Argon2 to avoid string encoding issues in binary blob
-        let salt_string = SaltString::generate(&mut OsRng);
-        
-        // 2. Derive Key (Argon2id)
-        let argon2 = Argon2::default();
-        let password_hash = argon2.hash_password(passphrase.as_bytes(), &salt_string)
-            .map_err(|e| ZenOneError::ConfigError(format!("Key derivation failed: {}", e)))?;
-            
-        // Use the hash output as the key (taken from the 'hash' part, assuming it's long enough)
-        let hash = password_hash.hash.ok_or(ZenOneError::ConfigError("No hash output".into()))?;
-        
-        let mut key_bytes = [0u8; 32];
-        if hash.len() < 32 {
-             return Err(ZenOneError::ConfigError("Derived key too short".into()));
+    fn start(&self, port: String, baud_rate: u32) -> Result<(), ZenOneError> {
+        if port.trim().is_empty() {
+            return Err(ZenOneError::ConfigError("Serial port must not be empty".into()));
         }
-        key_bytes.copy_from_slice(&hash.as_bytes()[0..32]);
-        
-        // 3. Encrypt (ChaCha20Poly1305)
-        let cipher = ChaCha20Poly1305::new(&key_bytes.into());
-        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng); // 12 bytes
-        
-        let ciphertext = cipher.encrypt(&nonce, data.as_ref())
-             .map_err(|_| ZenOneError::ConfigError("Encryption failed".into()))?;
-             
-        // 4. Construct Blob
-        // Format: [SaltLen(1)][SaltBytes(...)][Nonce(12)][Ciphertext...]
-        let salt_bytes = salt_string.as_str().as_bytes();
-        let salt_len = salt_bytes.len() as u8;
-        
-        let mut blob = Vec::with_capacity(1 + salt_len as usize + 12 + ciphertext.len());
-        blob.push(salt_len);
-        blob.extend_from_slice(salt_bytes);
-        blob.extend_from_slice(&nonce);
-        blob.extend_from_slice(&ciphertext);
-        
-        // Zeroize key
-        key_bytes.zeroize();
-        
-        Ok(blob)
+        if baud_rate == 0 {
+            return Err(ZenOneError::ConfigError("Baud rate must be positive".into()));
+        }
+        self.stop();
+
+        let serial = serialport::new(&port, baud_rate)
+            .timeout(SERIAL_PACER_WRITE_TIMEOUT)
+            .open()
+            .map_err(|e| ZenOneError::CapabilityUnavailable(format!("Cannot open serial port {}: {}", port, e)))?;
+        let (tx, rx) = crossbeam_channel::bounded::<String>(SERIAL_PACER_QUEUE);
+        let _ = tx.try_send(SERIAL_PACER_HEADER.to_string());
+        let (done_tx, done) = crossbeam_channel::bounded::<()>(0);
+
+        let status = Arc::new(Mutex::new(FfiSerialPacerStatus {
+            port: port.clone(),
+            baud_rate,
+            lines_sent: 0,
+            lines_dropped: 0,
+            error: None,
+        }));
+        let writer_status = status.clone();
+        thread::spawn(move || {
+            run_serial_pacer(serial, rx, writer_status);
+            drop(done_tx);
+        });
+        *self.link.lock() = Some(SerialLink { tx, status, done, last_sent: None, last_phase: None });
+        log::info!("SerialPacer: Streaming to {} at {} baud", port, baud_rate);
+        Ok(())
     }
-    
-    /// Decrypt biometric data
-    pub fn decrypt_blob(&self, passphrase: String, blob: Vec<u8>) -> Result<Vec<u8>, ZenOneError> {
-        if blob.len() < 14 { // Min: 1 len + 1 salt + 12 nonce
-            return Err(ZenOneError::ConfigError("Invalid blob format".into()));
+
+    /// Close the queue and give the writer a moment to close the port; a
+    /// writer stuck on the device is detached rather than joined
+    fn stop(&self) {
+        let Some(link) = self.link.lock().take() else { return };
+        drop(link.tx);
+        if let Err(crossbeam_channel::RecvTimeoutError::Timeout) = link.done.recv_timeout(SERIAL_PACER_STOP_TIMEOUT) {
+            log::warn!("SerialPacer: Writer still busy, detaching it");
         }
-        
-        let mut cursor = 0;
-        
-        // 1. Extract Salt
-        let salt_len = blob[cursor] as usize;
-        cursor += 1;
-        
-        if blob.len() < cursor + salt_len + 12 {
-             return Err(ZenOneError::ConfigError("Blob too short".into()));
+    }
+
+    fn status(&self) -> Option<FfiSerialPacerStatus> {
+        self.link.lock().as_ref().map(|link| link.status.lock().clone())
+    }
+
+    /// Queue a pacer update: immediately on phase changes, else rate limited
+    fn publish(&self, phase: FfiPhase, progress: f32, cycle: u64) {
+        let mut guard = self.link.lock();
+        let Some(link) = guard.as_mut() else { return };
+        let due = link.last_phase != Some(phase)
+            || !link.last_sent.is_some_and(|t| t.elapsed().as_secs_f32() < 1.0 / SERIAL_PACER_MAX_HZ);
+        if !due {
+            return;
         }
-        
-        let salt_bytes = &blob[cursor..cursor+salt_len];
-        let salt_string = SaltString::from_b64(std::str::from_utf8(salt_bytes).unwrap_or(""))
-             .map_err(|_| ZenOneError::ConfigError("Invalid salt".into()))?;
-        cursor += salt_len;
-             
-        // 2. Extract Nonce
-        let nonce_bytes = &blob[cursor..cursor+12];
-        let nonce = Nonce::from_slice(nonce_bytes);
-        cursor += 12;
-        
-        // 3. Extract Ciphertext
-        let ciphertext = &blob[cursor..];
-        
-        // 4. Derive Key
-        let argon2 = Argon2::default();
-        let password_hash = argon2.hash_password(passphrase.as_bytes(), &salt_string)
-            .map_err(|e| ZenOneError::ConfigError(format!("Key derivation failed: {}", e)))?;
-        let hash = password_hash.hash.ok_or(ZenOneError::ConfigError("No hash output".into()))?;
-        
-        let mut key_bytes = [0u8; 32];
-        if hash.len() < 32 {
-             return Err(ZenOneError::ConfigError("Derived key too short".into()));
+        let code = match phase {
+            FfiPhase::Inhale => 'I',
+            FfiPhase::HoldIn => 'H',
+            FfiPhase::Exhale => 'E',
+            FfiPhase::HoldOut => 'R',
+            FfiPhase::Prepare => 'P',
+        };
+        let line = format!("S,{},{},{}", code, (progress.clamp(0.0, 1.0) * 1000.0).round() as u32, cycle);
+        link.last_sent = Some(Instant::now());
+        link.last_phase = Some(phase);
+        link.send(line);
+    }
+
+    /// Tell the device the session is over
+    fn idle(&self) {
+        let mut guard = self.link.lock();
+        let Some(link) = guard.as_mut() else { return };
+        link.last_phase = None;
+        link.send("X".to_string());
+    }
+}
+
+#[cfg(feature = "full")]
+impl SerialLink {
+    fn send(&self, line: String) {
+        if let Err(crossbeam_channel::TrySendError::Full(_)) = self.tx.try_send(line) {
+            self.status.lock().lines_dropped += 1;
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl Drop for SerialPacer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(feature = "full")]
+fn run_serial_pacer(
+    mut port: Box<dyn serialport::SerialPort>,
+    rx: Receiver<String>,
+    status: Arc<Mutex<FfiSerialPacerStatus>>,
+) {
+    use std::io::Write;
+
+    while let Ok(mut line) = rx.recv() {
+        line.push('\n');
+        let written = port.write_all(line.as_bytes()).and_then(|_| port.flush());
+        match written {
+            Ok(()) => status.lock().lines_sent += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => status.lock().lines_dropped += 1,
+            Err(e) => {
+                log::warn!("SerialPacer: Write failed, stopping output: {}", e);
+                status.lock().error = Some(e.to_string());
+                return;
+            }
         }
-        key_bytes.copy_from_slice(&hash.as_bytes()[0..32]);
-        
-        // 5. Decrypt
-        let cipher = ChaCha20Poly1305::new(&key_bytes.into());
-        let plaintext = cipher.decrypt(nonce, ciphertext.as_ref())
-             .map_err(|_| ZenOneError::ConfigError("Decryption failed - Wrong passphrase?".into()))?;
-             
-        // Zeroize key
-        key_bytes.zeroize();
-        
-        Ok(plaintext)
     }
 }
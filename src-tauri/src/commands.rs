@@ -2,12 +2,28 @@
 //!
 //! These commands are invoked via `invoke('command_name', args)` from TypeScript.
 
-use tauri::State;
-use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use zenone_ffi::{
-    FfiBeliefState, FfiBreathPattern, FfiFrame, FfiRuntimeState, FfiSafetyStatus,
-    FfiSessionStats, ZenOneRuntime,
+    FfiBeliefModeSpec, FfiBeliefState, FfiBenchmarkResult, FfiBreathPattern, FfiBreathRateBand,
+    FfiBreathingBaseline, FfiCalibrationReference, FfiConsentAuditEntry, FfiConsentRecord,
+    FfiConsentScope, FfiDeviceCapabilities, FfiEscalationConfig, FfiExperiment,
+    FfiExperimentReport, FfiFeatureFlag, FfiFeatureFlagState, FfiFrame, FfiFrameLite,
+    FfiGroupStatus, FfiHrFilterConfig, FfiHrSimulatorConfig, FfiHrSourceKind, FfiHrZoneConfig,
+    FfiIbiFormat, FfiImportFormat, FfiImportReport, FfiInterruptionKind, FfiLocalTime,
+    FfiMetricRollup, FfiNoiseProfile, FfiOutcomeMetric, FfiPanicSession, FfiPatternChangeProposal,
+    FfiPatternComparison, FfiPatternIssue, FfiPhase, FfiPhaseOverride, FfiPipelineProfile,
+    FfiPixelFormat, FfiPlaneLayout, FfiPpgSpectrum, FfiPpgTracePoint, FfiPreflightInput,
+    FfiPreflightReport, FfiPreparationConfig, FfiReplayReport, FfiResearchManifest,
+    FfiRespirationSource, FfiRetentionPolicy, FfiRgbSample, FfiRoi, FfiRollupPeriod,
+    FfiRppgCalibration, FfiRppgConfig, FfiRppgMethod, FfiRuntimeEvent, FfiRuntimeState,
+    FfiRuntimeStateDiff, FfiSafetyJournalEntry, FfiSafetyStatus, FfiSequenceProgress,
+    FfiSequenceSegment, FfiSerialPacerStatus, FfiSessionGoals, FfiSessionPage, FfiSessionQuery,
+    FfiSessionRecord, FfiSessionStagesConfig, FfiSessionStats, FfiSignatureReport,
+    FfiSimulatedHrProfile, FfiSimulationResult, FfiSleepConfig, FfiStreakConfig, FfiStreakStatus,
+    FfiWatchLinkStatus, PipelineBenchmark, ZenOneRuntime,
 };
 
 /// Managed state: holds the ZenOneRuntime singleton.
@@ -23,128 +39,1197 @@ pub fn get_patterns(state: State<RuntimeState>) -> Vec<FfiBreathPattern> {
     state.0.get_patterns()
 }
 
+/// Register a user-defined breathing pattern alongside the builtins.
+#[tauri::command]
+pub fn create_pattern(state: State<RuntimeState>, pattern: FfiBreathPattern) -> Result<FfiBreathPattern, String> {
+    state.0.add_custom_pattern(pattern).map_err(|e| e.to_string())
+}
+
+/// Delete a user-defined breathing pattern.
+#[tauri::command]
+pub fn delete_pattern(state: State<RuntimeState>, pattern_id: String) -> Result<(), String> {
+    state.0.remove_custom_pattern(pattern_id).map_err(|e| e.to_string())
+}
+
+/// Export a breathing pattern as a shareable JSON document.
+#[tauri::command]
+pub fn export_pattern(state: State<RuntimeState>, pattern_id: String) -> Result<String, String> {
+    state.0.export_pattern(pattern_id).map_err(|e| e.to_string())
+}
+
+/// Validate and register a pattern shared from another device.
+#[tauri::command]
+pub fn import_pattern(state: State<RuntimeState>, json: String) -> Result<FfiBreathPattern, String> {
+    state.0.import_pattern(json).map_err(|e| e.to_string())
+}
+
+/// Check a pattern's timings against physiological limits.
+#[tauri::command]
+pub fn validate_pattern(state: State<RuntimeState>, pattern: FfiBreathPattern) -> Vec<FfiPatternIssue> {
+    state.0.validate_pattern(pattern)
+}
+
+/// Dry-run a pattern change so its safety evaluation can be shown first.
+#[tauri::command]
+pub fn propose_pattern_change(state: State<RuntimeState>, pattern_id: String) -> Result<FfiPatternChangeProposal, String> {
+    state.0.propose_pattern_change(pattern_id).map_err(|e| e.to_string())
+}
+
+/// Apply a previously proposed pattern change.
+#[tauri::command]
+pub fn confirm_pattern_change(state: State<RuntimeState>, proposal_id: u64) -> Result<(), String> {
+    state.0.confirm_pattern_change(proposal_id).map_err(|e| e.to_string())
+}
+
 /// Load a breathing pattern by ID.
 #[tauri::command]
-pub fn load_pattern(state: State<RuntimeState>, pattern_id: String) -> bool {
-    state.0.load_pattern(pattern_id)
+pub fn load_pattern(state: State<RuntimeState>, pattern_id: String) -> bool {
+    state.0.load_pattern(pattern_id)
+}
+
+/// Queue an ordered sequence of patterns for the next session.
+#[tauri::command]
+pub fn load_sequence(state: State<RuntimeState>, segments: Vec<FfiSequenceSegment>) -> Result<(), String> {
+    state.0.load_sequence(segments).map_err(|e| e.to_string())
+}
+
+/// Get the position in the loaded pattern sequence.
+#[tauri::command]
+pub fn get_sequence_progress(state: State<RuntimeState>) -> Option<FfiSequenceProgress> {
+    state.0.get_sequence_progress()
+}
+
+/// Get current pattern ID.
+#[tauri::command]
+pub fn current_pattern_id(state: State<RuntimeState>) -> String {
+    state.0.current_pattern_id()
+}
+
+/// Blend the timings of two patterns (t = 0 → pattern_a, t = 1 → pattern_b).
+#[tauri::command]
+pub fn blend_patterns(state: State<RuntimeState>, pattern_a: String, pattern_b: String, t: f32) -> Result<FfiBreathPattern, String> {
+    state.0.blend_patterns(pattern_a, pattern_b, t).map_err(|e| e.to_string())
+}
+
+/// Change one phase of the current pattern for this user.
+#[tauri::command]
+pub fn override_phase_duration(state: State<RuntimeState>, phase: FfiPhase, seconds: f32) -> Result<(), String> {
+    state.0.override_phase_duration(phase, seconds).map_err(|e| e.to_string())
+}
+
+/// Restore the current pattern's own phase timings.
+#[tauri::command]
+pub fn reset_phase_overrides(state: State<RuntimeState>) -> Result<(), String> {
+    state.0.reset_phase_overrides().map_err(|e| e.to_string())
+}
+
+/// List the user's phase overrides across patterns.
+#[tauri::command]
+pub fn get_phase_overrides(state: State<RuntimeState>) -> Vec<FfiPhaseOverride> {
+    state.0.get_phase_overrides()
+}
+
+/// Animate a pattern for a few cycles without starting a session.
+#[tauri::command]
+pub fn preview_pattern(state: State<RuntimeState>, pattern_id: String, cycles: u32) -> Result<(), String> {
+    state.0.preview_pattern(pattern_id, cycles).map_err(|e| e.to_string())
+}
+
+/// Stop a running pattern preview.
+#[tauri::command]
+pub fn stop_preview(state: State<RuntimeState>) {
+    state.0.stop_preview();
+}
+
+// =============================================================================
+// SESSION COMMANDS
+// =============================================================================
+
+/// Readiness checklist to show before starting a session.
+#[tauri::command]
+pub fn preflight_check(state: State<RuntimeState>, input: FfiPreflightInput) -> FfiPreflightReport {
+    state.0.preflight_check(input)
+}
+
+/// Start a breathing session.
+#[tauri::command]
+pub fn start_session(state: State<RuntimeState>) -> Result<(), String> {
+    state.0.start_session().map_err(|e| e.to_string())
+}
+
+/// Start a breathing session with coherence, minutes and/or cycles goals.
+#[tauri::command]
+pub fn start_session_with_goals(state: State<RuntimeState>, goals: FfiSessionGoals) -> Result<(), String> {
+    state.0.start_session_with_goals(goals).map_err(|e| e.to_string())
+}
+
+/// Start a sleep wind-down session that ends silently once the user is idle.
+#[tauri::command]
+pub fn start_sleep_session(state: State<RuntimeState>, config: FfiSleepConfig) -> Result<(), String> {
+    state.0.start_sleep_session(config).map_err(|e| e.to_string())
+}
+
+/// Start an acute-anxiety session in one call (7-11, no countdown, a few minutes).
+#[tauri::command]
+pub fn start_panic_session(state: State<RuntimeState>) -> Result<FfiPanicSession, String> {
+    state.0.start_panic_session().map_err(|e| e.to_string())
+}
+
+/// Report user interaction or device motion during a sleep session.
+#[tauri::command]
+pub fn report_activity(state: State<RuntimeState>) {
+    state.0.report_activity();
+}
+
+/// Stop session and return stats.
+#[tauri::command]
+pub fn stop_session(state: State<RuntimeState>) -> FfiSessionStats {
+    state.0.stop_session()
+}
+
+/// Configure the pre-session countdown and signal quality gate.
+#[tauri::command]
+pub fn set_preparation_config(state: State<RuntimeState>, config: FfiPreparationConfig) {
+    state.0.set_preparation_config(config);
+}
+
+/// End sessions automatically after the pattern's recommended cycles.
+#[tauri::command]
+pub fn set_auto_complete(state: State<RuntimeState>, enabled: bool) {
+    state.0.set_auto_complete(enabled);
+}
+
+/// Enable or disable follow-me pacing toward a target breathing rate.
+#[tauri::command]
+pub fn set_follow_me(state: State<RuntimeState>, enabled: bool, target_rate_bpm: f32) -> Result<(), String> {
+    state.0.set_follow_me(enabled, target_rate_bpm).map_err(|e| e.to_string())
+}
+
+/// Start a "just breathe" observation of the user's natural pace.
+#[tauri::command]
+pub fn start_baseline_observation(state: State<RuntimeState>, duration_sec: f32) -> Result<(), String> {
+    state.0.start_baseline_observation(duration_sec).map_err(|e| e.to_string())
+}
+
+/// End the observation early and get the baseline measured so far.
+#[tauri::command]
+pub fn stop_baseline_observation(state: State<RuntimeState>) -> Result<FfiBreathingBaseline, String> {
+    state.0.stop_baseline_observation().map_err(|e| e.to_string())
+}
+
+/// Get the most recent free-breathing baseline.
+#[tauri::command]
+pub fn get_breathing_baseline(state: State<RuntimeState>) -> Option<FfiBreathingBaseline> {
+    state.0.get_breathing_baseline()
+}
+
+/// Measure ambient microphone noise before audio breath detection.
+#[tauri::command]
+pub fn start_noise_profiling(state: State<RuntimeState>, duration_sec: f32) -> Result<(), String> {
+    state.0.start_noise_profiling(duration_sec).map_err(|e| e.to_string())
+}
+
+/// Get the last ambient noise profile.
+#[tauri::command]
+pub fn get_noise_profile(state: State<RuntimeState>) -> Option<FfiNoiseProfile> {
+    state.0.get_noise_profile()
+}
+
+/// Turn microphone breath detection on or off.
+#[tauri::command]
+pub fn set_audio_breath_detection(state: State<RuntimeState>, enabled: bool) -> Result<(), String> {
+    state.0.set_audio_breath_detection(enabled).map_err(|e| e.to_string())
+}
+
+/// Skip the remaining countdown and start immediately.
+#[tauri::command]
+pub fn skip_preparation(state: State<RuntimeState>) {
+    state.0.skip_preparation();
+}
+
+/// Declare the warm-up and cool-down of new sessions.
+#[tauri::command]
+pub fn set_session_stages(
+    state: State<RuntimeState>,
+    config: FfiSessionStagesConfig,
+) -> Result<(), String> {
+    state.0.set_session_stages(config).map_err(|e| e.to_string())
+}
+
+/// Stop pacing and run the cool-down before the session completes.
+#[tauri::command]
+pub fn begin_cool_down(state: State<RuntimeState>) -> Result<(), String> {
+    state.0.begin_cool_down().map_err(|e| e.to_string())
+}
+
+/// Pause session.
+#[tauri::command]
+pub fn pause_session(state: State<RuntimeState>) {
+    state.0.pause_session();
+}
+
+/// Pause session because of an interruption (call, app backgrounded).
+#[tauri::command]
+pub fn report_interruption(state: State<RuntimeState>, kind: FfiInterruptionKind) {
+    state.0.report_interruption(kind);
+}
+
+/// Resume session.
+#[tauri::command]
+pub fn resume_session(state: State<RuntimeState>) {
+    state.0.resume_session();
+}
+
+/// Check if session is active.
+#[tauri::command]
+pub fn is_session_active(state: State<RuntimeState>) -> bool {
+    state.0.is_session_active()
+}
+
+// =============================================================================
+// FRAME PROCESSING
+// =============================================================================
+
+/// Tick the engine (timer-based, no camera).
+#[tauri::command]
+pub fn tick(state: State<RuntimeState>, dt_sec: f32, timestamp_us: i64) -> Arc<FfiFrame> {
+    state.0.tick_snapshot(dt_sec, timestamp_us)
+}
+
+/// Tick the engine and return only phase, progress and heart rate.
+#[tauri::command]
+pub fn tick_lite(state: State<RuntimeState>, dt_sec: f32, timestamp_us: i64) -> FfiFrameLite {
+    state.0.tick_lite(dt_sec, timestamp_us)
+}
+
+/// Get the latest lightweight frame.
+#[tauri::command]
+pub fn get_frame_lite(state: State<RuntimeState>) -> FfiFrameLite {
+    state.0.get_frame_lite()
+}
+
+/// Process a camera frame (rPPG pipeline).
+#[tauri::command]
+pub fn process_frame(
+    state: State<RuntimeState>,
+    r: f32,
+    g: f32,
+    b: f32,
+    timestamp_us: i64,
+) -> FfiFrame {
+    state.0.process_frame(r, g, b, timestamp_us)
+}
+
+/// Process many buffered camera samples at once, in timestamp order.
+#[tauri::command]
+pub fn process_frames_batch(state: State<RuntimeState>, samples: Vec<FfiRgbSample>) -> Result<FfiFrame, String> {
+    state.0.process_frames_batch(samples).map_err(|e| e.to_string())
+}
+
+/// Process a raw camera frame; skin pixels inside the ROI are averaged in Rust.
+#[tauri::command]
+pub fn process_frame_buffer(
+    state: State<RuntimeState>,
+    bytes: Vec<u8>,
+    width: u32,
+    height: u32,
+    format: FfiPixelFormat,
+    planes: Option<Vec<FfiPlaneLayout>>,
+    roi: Option<FfiRoi>,
+    timestamp_us: i64,
+) -> Result<FfiFrame, String> {
+    state.0
+        .process_frame_buffer(bytes, width, height, format, planes, roi, timestamp_us)
+        .map_err(|e| e.to_string())
+}
+
+/// Keep the rPPG region on the face with the built-in skin-color tracker.
+#[tauri::command]
+pub fn set_skin_roi_tracking(state: State<RuntimeState>, enabled: bool) {
+    state.0.set_skin_roi_tracking(enabled)
+}
+
+/// Emit a PhaseEnding event this long before each phase transition (null disables).
+#[tauri::command]
+pub fn set_phase_warning_lead(state: State<RuntimeState>, lead_ms: Option<u32>) {
+    state.0.set_phase_warning_lead(lead_ms);
+}
+
+/// Morph mid-session pattern loads over this many cycles (0 switches at once).
+#[tauri::command]
+pub fn set_pattern_transition_cycles(state: State<RuntimeState>, cycles: u32) -> Result<(), String> {
+    state.0.set_pattern_transition_cycles(cycles).map_err(|e| e.to_string())
+}
+
+/// Emit a PhaseCountdown event for each whole second left in a phase.
+#[tauri::command]
+pub fn set_phase_countdown(state: State<RuntimeState>, enabled: bool) {
+    state.0.set_phase_countdown(enabled);
+}
+
+/// Limit how often ticks refresh the runtime state (null = every tick).
+#[tauri::command]
+pub fn set_state_publish_rate(state: State<RuntimeState>, max_hz: Option<f32>) -> Result<(), String> {
+    state.0.set_state_publish_rate(max_hz).map_err(|e| e.to_string())
+}
+
+/// Feed a streamed heart rate sample from a wearable.
+#[tauri::command]
+pub fn ingest_hr_sample(
+    state: State<RuntimeState>,
+    bpm: f32,
+    rr_intervals_ms: Vec<f32>,
+    timestamp_us: i64,
+) {
+    state.0.ingest_hr_sample(bpm, rr_intervals_ms, timestamp_us);
+}
+
+/// Feed a pulse the user counted by hand.
+#[tauri::command]
+pub fn ingest_manual_hr(state: State<RuntimeState>, bpm: f32, timestamp_us: i64) -> Result<(), String> {
+    state.0.ingest_manual_hr(bpm, timestamp_us).map_err(|e| e.to_string())
+}
+
+/// Choose which sensor supplies the session's heart rate.
+#[tauri::command]
+pub fn set_hr_source(state: State<RuntimeState>, kind: FfiHrSourceKind) {
+    state.0.set_hr_source(kind);
+}
+
+/// Get the selected heart rate source.
+#[tauri::command]
+pub fn get_hr_source(state: State<RuntimeState>) -> FfiHrSourceKind {
+    state.0.get_hr_source()
+}
+
+/// Feed a respiration signal sample (chest motion / audio) for adherence scoring.
+#[tauri::command]
+pub fn ingest_respiration_sample(
+    state: State<RuntimeState>,
+    value: f32,
+    source: FfiRespirationSource,
+    timestamp_us: i64,
+) {
+    state.0.ingest_respiration_sample(value, source, timestamp_us);
+}
+
+/// Enable or disable belief-adaptive pattern transitions.
+#[tauri::command]
+pub fn set_arc_planner_enabled(state: State<RuntimeState>, enabled: bool) {
+    state.0.set_arc_planner_enabled(enabled);
+}
+
+/// Auto-configure the signal pipeline from the shell's device probe.
+#[tauri::command]
+pub fn configure_for_device(
+    state: State<RuntimeState>,
+    capabilities: FfiDeviceCapabilities,
+) -> Result<FfiPipelineProfile, String> {
+    state.0.configure_for_device(capabilities).map_err(|e| e.to_string())
+}
+
+/// Get the pipeline profile chosen at startup.
+#[tauri::command]
+pub fn get_pipeline_profile(state: State<RuntimeState>) -> Option<FfiPipelineProfile> {
+    state.0.get_pipeline_profile()
+}
+
+/// Switch the rPPG algorithm (POS, CHROM or Green).
+#[tauri::command]
+pub fn set_rppg_method(state: State<RuntimeState>, method: FfiRppgMethod) {
+    state.0.set_rppg_method(method)
+}
+
+/// Get the rPPG algorithm in use.
+#[tauri::command]
+pub fn get_rppg_method(state: State<RuntimeState>) -> FfiRppgMethod {
+    state.0.get_rppg_method()
+}
+
+/// Set the rPPG window length, expected frame rate and frame rate adaptation.
+#[tauri::command]
+pub fn set_rppg_config(state: State<RuntimeState>, config: FfiRppgConfig) -> Result<(), String> {
+    state.0.set_rppg_config(config).map_err(|e| e.to_string())
+}
+
+/// Get the rPPG configuration, including any adapted frame rate.
+#[tauri::command]
+pub fn get_rppg_config(state: State<RuntimeState>) -> FfiRppgConfig {
+    state.0.get_rppg_config()
+}
+
+/// Get the latest camera pulse spectrum for diagnostics.
+#[tauri::command]
+pub fn get_ppg_spectrum(state: State<RuntimeState>) -> Option<FfiPpgSpectrum> {
+    state.0.get_ppg_spectrum()
+}
+
+/// How often buffered trace points are pushed to the frontend.
+const PPG_TRACE_EMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Turn the camera signal debug trace on or off. While on, downsampled
+/// points are emitted as `ppg-trace` events until the trace is disabled.
+#[tauri::command]
+pub fn set_ppg_trace(
+    app: AppHandle,
+    state: State<RuntimeState>,
+    enabled: bool,
+    decimation: u32,
+) -> Result<(), String> {
+    let was_enabled = state.0.is_ppg_trace_enabled();
+    state.0.set_ppg_trace(enabled, decimation).map_err(|e| e.to_string())?;
+    if enabled && !was_enabled {
+        std::thread::spawn(move || {
+            let runtime = &app.state::<RuntimeState>().0;
+            while runtime.is_ppg_trace_enabled() {
+                std::thread::sleep(PPG_TRACE_EMIT_INTERVAL);
+                let points = runtime.drain_ppg_trace();
+                if points.is_empty() {
+                    continue;
+                }
+                if let Err(e) = app.emit("ppg-trace", points) {
+                    log::warn!("Failed to emit PPG trace: {}", e);
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Take buffered trace points directly, without waiting for an event.
+#[tauri::command]
+pub fn drain_ppg_trace(state: State<RuntimeState>) -> Vec<FfiPpgTracePoint> {
+    state.0.drain_ppg_trace()
+}
+
+/// Start comparing camera heart rate against a strap or hand count.
+#[tauri::command]
+pub fn start_rppg_calibration(
+    state: State<RuntimeState>,
+    device_id: String,
+    reference: FfiCalibrationReference,
+) -> Result<(), String> {
+    state.0.start_rppg_calibration(device_id, reference).map_err(|e| e.to_string())
+}
+
+/// Add a hand-counted pulse covering the last `duration_sec` seconds.
+#[tauri::command]
+pub fn add_manual_hr_reference(state: State<RuntimeState>, bpm: f32, duration_sec: f32) -> Result<(), String> {
+    state.0.add_manual_hr_reference(bpm, duration_sec).map_err(|e| e.to_string())
+}
+
+/// Fit, save and apply the camera heart rate correction.
+#[tauri::command]
+pub fn finish_rppg_calibration(state: State<RuntimeState>) -> Result<FfiRppgCalibration, String> {
+    state.0.finish_rppg_calibration().map_err(|e| e.to_string())
+}
+
+/// Apply the saved calibration for this device, if there is one.
+#[tauri::command]
+pub fn use_rppg_calibration(state: State<RuntimeState>, device_id: String) -> Option<FfiRppgCalibration> {
+    state.0.use_rppg_calibration(device_id)
+}
+
+/// List saved per-device calibrations.
+#[tauri::command]
+pub fn get_rppg_calibrations(state: State<RuntimeState>) -> Vec<FfiRppgCalibration> {
+    state.0.get_rppg_calibrations()
+}
+
+/// Replay the recordings in `recordings_dir` (the built-in reference dataset
+/// when omitted) through a fresh pipeline per configuration. Runs off the
+/// main thread: a full suite takes a while.
+#[tauri::command(async)]
+pub fn run_pipeline_benchmark(
+    recordings_dir: Option<String>,
+    configs: Vec<FfiDeviceCapabilities>,
+) -> Result<Vec<FfiBenchmarkResult>, String> {
+    let benchmark = PipelineBenchmark::new();
+    match recordings_dir {
+        Some(dir) => benchmark.load_recordings(dir).map_err(|e| e.to_string())?,
+        None => benchmark.load_reference_dataset(),
+    };
+    benchmark.run(configs).map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// GROUP SESSION COMMANDS
+// =============================================================================
+
+/// Lead a group breathing session on the local network.
+#[tauri::command]
+pub fn host_group_session(state: State<RuntimeState>, group_id: String) -> Result<(), String> {
+    state.0.host_group_session(group_id).map_err(|e| e.to_string())
+}
+
+/// Join a group breathing session as a follower.
+#[tauri::command]
+pub fn join_group_session(state: State<RuntimeState>, group_id: String) -> Result<(), String> {
+    state.0.join_group_session(group_id).map_err(|e| e.to_string())
+}
+
+/// Leave the current group session.
+#[tauri::command]
+pub fn leave_group_session(state: State<RuntimeState>) {
+    state.0.leave_group_session();
+}
+
+/// Get group sync status.
+#[tauri::command]
+pub fn get_group_status(state: State<RuntimeState>) -> Option<FfiGroupStatus> {
+    state.0.get_group_status()
+}
+
+// =============================================================================
+// SERIAL PACER COMMANDS
+// =============================================================================
+
+/// Stream the pacer to a serial/USB port (e.g. "/dev/ttyACM0", "COM3"),
+/// at 115200 baud unless given.
+#[tauri::command]
+pub fn start_serial_pacer(
+    state: State<RuntimeState>,
+    port: String,
+    baud_rate: Option<u32>,
+) -> Result<(), String> {
+    state.0.start_serial_pacer(port, baud_rate).map_err(|e| e.to_string())
+}
+
+/// Stop serial pacer output.
+#[tauri::command]
+pub fn stop_serial_pacer(state: State<RuntimeState>) {
+    state.0.stop_serial_pacer();
+}
+
+/// Get serial pacer output status.
+#[tauri::command]
+pub fn get_serial_pacer_status(state: State<RuntimeState>) -> Option<FfiSerialPacerStatus> {
+    state.0.get_serial_pacer_status()
+}
+
+// =============================================================================
+// WATCH MIRRORING COMMANDS
+// =============================================================================
+
+/// Report watch transport connectivity.
+#[tauri::command]
+pub fn watch_set_connected(state: State<RuntimeState>, connected: bool) {
+    state.0.watch_set_connected(connected);
+}
+
+/// Next state message to send to the watch, if one is due.
+#[tauri::command]
+pub fn watch_next_message(state: State<RuntimeState>) -> Option<Vec<u8>> {
+    state.0.watch_next_message()
+}
+
+/// Handle a message from the watch; returns an acknowledgement to send back.
+#[tauri::command]
+pub fn watch_handle_message(state: State<RuntimeState>, data: Vec<u8>) -> Result<Option<Vec<u8>>, String> {
+    state.0.watch_handle_message(data).map_err(|e| e.to_string())
+}
+
+/// Get watch link status.
+#[tauri::command]
+pub fn get_watch_link_status(state: State<RuntimeState>) -> FfiWatchLinkStatus {
+    state.0.get_watch_link_status()
+}
+
+// =============================================================================
+// EVENTS
+// =============================================================================
+
+/// Drain pending runtime events (coaching cues, ...).
+#[tauri::command]
+pub fn poll_events(state: State<RuntimeState>) -> Vec<FfiRuntimeEvent> {
+    state.0.poll_events()
+}
+
+// =============================================================================
+// STATE QUERIES
+// =============================================================================
+
+/// Get full runtime state snapshot.
+#[tauri::command]
+pub fn get_state(state: State<RuntimeState>) -> Arc<FfiRuntimeState> {
+    state.0.state_snapshot()
+}
+
+/// Get only the state fields changed since a publish sequence (0 for all).
+#[tauri::command]
+pub fn get_state_delta(state: State<RuntimeState>, since_sequence: u64) -> FfiRuntimeStateDiff {
+    state.0.get_state_delta(since_sequence)
+}
+
+/// Get current belief state (for AI/ML integration).
+#[tauri::command]
+pub fn get_belief(state: State<RuntimeState>) -> FfiBeliefState {
+    state.0.get_belief()
+}
+
+/// List every belief mode: the five built-ins, then custom modes.
+#[tauri::command]
+pub fn list_belief_modes(state: State<RuntimeState>) -> Vec<FfiBeliefModeSpec> {
+    state.0.list_belief_modes()
+}
+
+/// Register or replace a custom belief mode (research builds only).
+#[tauri::command]
+pub fn register_belief_mode(
+    state: State<RuntimeState>,
+    spec: FfiBeliefModeSpec,
+) -> Result<(), String> {
+    state.0.register_belief_mode(spec).map_err(|e| e.to_string())
+}
+
+/// Remove a custom belief mode.
+#[tauri::command]
+pub fn remove_belief_mode(state: State<RuntimeState>, id: String) -> Result<bool, String> {
+    state.0.remove_belief_mode(id).map_err(|e| e.to_string())
+}
+
+/// Get safety status (lock state, bounds, trauma count).
+#[tauri::command]
+pub fn get_safety_status(state: State<RuntimeState>) -> FfiSafetyStatus {
+    state.0.get_safety_status()
+}
+
+// =============================================================================
+// CONTEXT & CONTROL
+// =============================================================================
+
+/// Update context (device state, session history). The time of day comes
+/// from the core clock in the user's time zone.
+/// This helps the Engine adapt its recommendations.
+#[tauri::command]
+pub fn update_context(
+    state: State<RuntimeState>,
+    is_charging: bool,
+    recent_sessions: u16,
+) {
+    let local_hour = state.0.get_local_time().hour;
+    state.0.update_context(local_hour, is_charging, recent_sessions);
+}
+
+/// Adjust tempo scale.
+#[tauri::command]
+pub fn adjust_tempo(state: State<RuntimeState>, scale: f32, reason: String) -> Result<f32, String> {
+    state.0.adjust_tempo(scale, reason).map_err(|e| e.to_string())
+}
+
+/// Emergency halt.
+#[tauri::command]
+pub fn emergency_halt(state: State<RuntimeState>, reason: String) {
+    state.0.emergency_halt(reason);
+}
+
+/// Reset safety lock (reason required; rejected during the post-violation cooldown).
+#[tauri::command]
+pub fn reset_safety_lock(
+    state: State<RuntimeState>,
+    api: State<ApiVersionState>,
+    reason: Option<String>,
+) -> Result<(), String> {
+    // API v1 reset the lock without a reason
+    let reason = api.upgrade_arg("reset_safety_lock", 2, reason, || "unspecified (API v1 client)".to_string())?;
+    state.0.reset_safety_lock(reason).map_err(|e| e.to_string())
+}
+
+/// Get the safety lock audit journal.
+#[tauri::command]
+pub fn get_safety_journal(state: State<RuntimeState>) -> Vec<FfiSafetyJournalEntry> {
+    state.0.get_safety_journal()
+}
+
+/// Configure heart-rate zones and the elevated-HR safety pause.
+#[tauri::command]
+pub fn set_hr_zones(state: State<RuntimeState>, config: FfiHrZoneConfig) -> Result<(), String> {
+    state.0.set_hr_zones(config).map_err(|e| e.to_string())
+}
+
+/// Get the heart-rate zone configuration.
+#[tauri::command]
+pub fn get_hr_zones(state: State<RuntimeState>) -> FfiHrZoneConfig {
+    state.0.get_hr_zones()
+}
+
+/// Set how far the measured breathing rate may drift from target before coaching.
+#[tauri::command]
+pub fn set_breath_rate_band(state: State<RuntimeState>, band: FfiBreathRateBand) -> Result<(), String> {
+    state.0.set_breath_rate_band(band).map_err(|e| e.to_string())
+}
+
+/// Get the breathing-rate coaching band.
+#[tauri::command]
+pub fn get_breath_rate_band(state: State<RuntimeState>) -> FfiBreathRateBand {
+    state.0.get_breath_rate_band()
+}
+
+/// Configure the Kalman filter that smooths camera heart rate.
+#[tauri::command]
+pub fn set_hr_filter_config(state: State<RuntimeState>, config: FfiHrFilterConfig) -> Result<(), String> {
+    state.0.set_hr_filter_config(config).map_err(|e| e.to_string())
+}
+
+/// Get the camera heart-rate filter configuration.
+#[tauri::command]
+pub fn get_hr_filter_config(state: State<RuntimeState>) -> FfiHrFilterConfig {
+    state.0.get_hr_filter_config()
+}
+
+/// Configure when safety alerts escalate to the trusted contact.
+#[tauri::command]
+pub fn set_escalation_config(state: State<RuntimeState>, config: FfiEscalationConfig) -> Result<(), String> {
+    state.0.set_escalation_config(config).map_err(|e| e.to_string())
+}
+
+/// Get the trusted-contact escalation configuration.
+#[tauri::command]
+pub fn get_escalation_config(state: State<RuntimeState>) -> FfiEscalationConfig {
+    state.0.get_escalation_config()
+}
+
+// =============================================================================
+// FEATURE FLAG COMMANDS
+// =============================================================================
+
+/// Turn a subsystem on or off for this profile.
+#[tauri::command]
+pub fn set_feature_flag(state: State<RuntimeState>, flag: FfiFeatureFlag, enabled: bool) -> Result<(), String> {
+    state.0.set_feature_flag(flag, enabled).map_err(|e| e.to_string())
+}
+
+/// Check whether a subsystem is enabled.
+#[tauri::command]
+pub fn is_feature_enabled(state: State<RuntimeState>, flag: FfiFeatureFlag) -> bool {
+    state.0.is_feature_enabled(flag)
+}
+
+/// List every feature flag with its current value.
+#[tauri::command]
+pub fn get_feature_flags(state: State<RuntimeState>) -> Vec<FfiFeatureFlagState> {
+    state.0.get_feature_flags()
+}
+
+// =============================================================================
+// CONSENT COMMANDS
+// =============================================================================
+
+/// Grant consent for a data scope.
+#[tauri::command]
+pub fn grant_consent(
+    state: State<RuntimeState>,
+    scope: FfiConsentScope,
+    valid_days: Option<u32>,
+) -> Result<(), String> {
+    state.0.grant_consent(scope, valid_days).map_err(|e| e.to_string())
+}
+
+/// Revoke consent for a data scope.
+#[tauri::command]
+pub fn revoke_consent(state: State<RuntimeState>, scope: FfiConsentScope) -> Result<(), String> {
+    state.0.revoke_consent(scope).map_err(|e| e.to_string())
 }
 
-/// Get current pattern ID.
+/// Check if a data scope is granted.
 #[tauri::command]
-pub fn current_pattern_id(state: State<RuntimeState>) -> String {
-    state.0.current_pattern_id()
+pub fn has_consent(state: State<RuntimeState>, scope: FfiConsentScope) -> bool {
+    state.0.has_consent(scope)
+}
+
+/// Get consent state for all scopes.
+#[tauri::command]
+pub fn get_consents(state: State<RuntimeState>) -> Vec<FfiConsentRecord> {
+    state.0.get_consents()
+}
+
+/// Get the consent audit trail.
+#[tauri::command]
+pub fn get_consent_audit(state: State<RuntimeState>) -> Vec<FfiConsentAuditEntry> {
+    state.0.get_consent_audit()
 }
 
 // =============================================================================
-// SESSION COMMANDS
+// N-OF-1 EXPERIMENT COMMANDS
 // =============================================================================
 
-/// Start a breathing session.
+/// Create a personal experiment comparing two patterns.
 #[tauri::command]
-pub fn start_session(state: State<RuntimeState>) -> Result<(), String> {
-    state.0.start_session().map_err(|e| e.to_string())
+pub fn create_experiment(
+    state: State<RuntimeState>,
+    name: String,
+    pattern_a: String,
+    pattern_b: String,
+    metric: FfiOutcomeMetric,
+    min_sessions_per_condition: u32,
+) -> Result<FfiExperiment, String> {
+    state.0
+        .create_experiment(name, pattern_a, pattern_b, metric, min_sessions_per_condition)
+        .map_err(|e| e.to_string())
 }
 
-/// Stop session and return stats.
+/// List all experiments.
 #[tauri::command]
-pub fn stop_session(state: State<RuntimeState>) -> FfiSessionStats {
-    state.0.stop_session()
+pub fn list_experiments(state: State<RuntimeState>) -> Vec<FfiExperiment> {
+    state.0.list_experiments()
 }
 
-/// Pause session.
+/// Get today's assigned pattern for an experiment.
 #[tauri::command]
-pub fn pause_session(state: State<RuntimeState>) {
-    state.0.pause_session();
+pub fn get_experiment_assignment(state: State<RuntimeState>, experiment_id: String) -> Result<String, String> {
+    state.0.get_experiment_assignment(experiment_id).map_err(|e| e.to_string())
 }
 
-/// Resume session.
+/// Get the (possibly blinded) outcome report for an experiment.
 #[tauri::command]
-pub fn resume_session(state: State<RuntimeState>) {
-    state.0.resume_session();
+pub fn get_experiment_report(
+    state: State<RuntimeState>,
+    experiment_id: String,
+) -> Result<FfiExperimentReport, String> {
+    state.0.get_experiment_report(experiment_id).map_err(|e| e.to_string())
 }
 
-/// Check if session is active.
+/// End an experiment.
 #[tauri::command]
-pub fn is_session_active(state: State<RuntimeState>) -> bool {
-    state.0.is_session_active()
+pub fn end_experiment(state: State<RuntimeState>, experiment_id: String) -> Result<(), String> {
+    state.0.end_experiment(experiment_id).map_err(|e| e.to_string())
+}
+
+/// Paired statistical comparison of two patterns over matched sessions.
+#[tauri::command]
+pub fn compare_patterns_stats(
+    state: State<RuntimeState>,
+    pattern_a: String,
+    pattern_b: String,
+    metric: FfiOutcomeMetric,
+) -> Result<FfiPatternComparison, String> {
+    state.0
+        .compare_patterns_stats(pattern_a, pattern_b, metric)
+        .map_err(|e| e.to_string())
 }
 
 // =============================================================================
-// FRAME PROCESSING
+// SESSION HISTORY COMMANDS
 // =============================================================================
 
-/// Tick the engine (timer-based, no camera).
+/// Replace the tags and note on a recorded session.
 #[tauri::command]
-pub fn tick(state: State<RuntimeState>, dt_sec: f32, timestamp_us: i64) -> FfiFrame {
-    state.0.tick(dt_sec, timestamp_us)
+pub fn tag_session(
+    state: State<RuntimeState>,
+    session_id: String,
+    tags: Vec<String>,
+    notes: Option<String>,
+) -> Result<(), String> {
+    state.0.tag_session(session_id, tags, notes).map_err(|e| e.to_string())
 }
 
-/// Process a camera frame (rPPG pipeline).
+/// Search recorded sessions by text, tags and start-time range.
 #[tauri::command]
-pub fn process_frame(
+pub fn search_sessions(
     state: State<RuntimeState>,
-    r: f32,
-    g: f32,
-    b: f32,
-    timestamp_us: i64,
-) -> FfiFrame {
-    state.0.process_frame(r, g, b, timestamp_us)
+    query: Option<String>,
+    tags: Vec<String>,
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
+) -> Vec<FfiSessionRecord> {
+    state.0.search_sessions(query, tags, start_ms, end_ms)
+}
+
+/// Page through recorded sessions, newest first.
+#[tauri::command]
+pub fn get_session_history(state: State<RuntimeState>, limit: u32, offset: u32) -> Vec<FfiSessionRecord> {
+    state.0.get_session_history(limit, offset)
+}
+
+/// Get one recorded session.
+#[tauri::command]
+pub fn get_session_by_id(state: State<RuntimeState>, session_id: String) -> Option<FfiSessionRecord> {
+    state.0.get_session_by_id(session_id)
+}
+
+/// Filtered, paginated session summaries for the history screen.
+#[tauri::command]
+pub fn query_sessions(state: State<RuntimeState>, query: FfiSessionQuery) -> Result<FfiSessionPage, String> {
+    state.0.query_sessions(query).map_err(|e| e.to_string())
+}
+
+/// Configure streak rest days and weekly freezes.
+#[tauri::command]
+pub fn set_streak_config(state: State<RuntimeState>, config: FfiStreakConfig) -> Result<(), String> {
+    state.0.set_streak_config(config).map_err(|e| e.to_string())
+}
+
+/// Get the streak rules.
+#[tauri::command]
+pub fn get_streak_config(state: State<RuntimeState>) -> FfiStreakConfig {
+    state.0.get_streak_config()
+}
+
+/// Get the current and longest streak, grace and freeze tokens from the
+/// session history.
+#[tauri::command]
+pub fn get_streak_info(state: State<RuntimeState>) -> FfiStreakStatus {
+    state.0.get_streak_info()
+}
+
+/// Former name of `get_streak_info`, kept for older frontends.
+#[tauri::command]
+pub fn get_streak(state: State<RuntimeState>) -> FfiStreakStatus {
+    state.0.get_streak_info()
+}
+
+/// Set the IANA time zone for streaks and rollups (null follows the device).
+#[tauri::command]
+pub fn set_time_zone(state: State<RuntimeState>, time_zone: Option<String>) -> Result<(), String> {
+    state.0.set_time_zone(time_zone).map_err(|e| e.to_string())
+}
+
+/// Get the user-set time zone, if any.
+#[tauri::command]
+pub fn get_time_zone(state: State<RuntimeState>) -> Option<String> {
+    state.0.get_time_zone()
+}
+
+/// Get today's date and the current hour in the user's time zone.
+#[tauri::command]
+pub fn get_local_time(state: State<RuntimeState>) -> FfiLocalTime {
+    state.0.get_local_time()
+}
+
+/// Get day/week/month summaries for trend charts (dates are YYYY-MM-DD).
+#[tauri::command]
+pub fn get_rollups(
+    state: State<RuntimeState>,
+    period: FfiRollupPeriod,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<FfiMetricRollup>, String> {
+    state.0.get_rollups(period, start_date, end_date).map_err(|e| e.to_string())
+}
+
+/// Set how long raw sessions are kept; returns how many were pruned.
+#[tauri::command]
+pub fn set_retention_policy(state: State<RuntimeState>, policy: FfiRetentionPolicy) -> Result<u32, String> {
+    state.0.set_retention_policy(policy).map_err(|e| e.to_string())
+}
+
+/// Get the raw-session retention policy.
+#[tauri::command]
+pub fn get_retention_policy(state: State<RuntimeState>) -> FfiRetentionPolicy {
+    state.0.get_retention_policy()
 }
 
 // =============================================================================
-// STATE QUERIES
+// EXPORT COMMANDS
 // =============================================================================
 
-/// Get full runtime state snapshot.
+/// Export recorded sessions as an HL7 FHIR R4 Bundle (JSON string).
 #[tauri::command]
-pub fn get_state(state: State<RuntimeState>) -> FfiRuntimeState {
-    state.0.get_state()
+pub fn export_fhir_bundle(
+    state: State<RuntimeState>,
+    patient_reference: String,
+) -> Result<String, String> {
+    state.0.export_fhir_bundle(patient_reference).map_err(|e| e.to_string())
 }
 
-/// Get current belief state (for AI/ML integration).
+/// Export a session's inter-beat intervals (plain IBI text or Kubios-compatible).
 #[tauri::command]
-pub fn get_belief(state: State<RuntimeState>) -> FfiBeliefState {
-    state.0.get_belief()
+pub fn export_ibi(
+    state: State<RuntimeState>,
+    session_id: String,
+    format: FfiIbiFormat,
+) -> Result<String, String> {
+    state.0.export_ibi(session_id, format).map_err(|e| e.to_string())
 }
 
-/// Get safety status (lock state, bounds, trauma count).
+/// Export sessions in the versioned `zenb-session` JSON schema, with signatures.
 #[tauri::command]
-pub fn get_safety_status(state: State<RuntimeState>) -> FfiSafetyStatus {
-    state.0.get_safety_status()
+pub fn export_sessions(
+    state: State<RuntimeState>,
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
+) -> Result<String, String> {
+    state.0.export_sessions(start_ms, end_ms).map_err(|e| e.to_string())
+}
+
+/// Upgrade an older session export to the current schema version.
+#[tauri::command]
+pub fn migrate_session_export(
+    state: State<RuntimeState>,
+    json: String,
+) -> Result<String, String> {
+    state.0.migrate_session_export(json).map_err(|e| e.to_string())
+}
+
+/// Unlock the encrypted session store (key created on first use); returns the sessions loaded.
+#[tauri::command]
+pub fn unlock_session_store(state: State<RuntimeState>, passphrase: String) -> Result<u32, String> {
+    state.0.unlock_session_store(passphrase).map_err(|e| e.to_string())
+}
+
+/// Whether session history is being persisted.
+#[tauri::command]
+pub fn is_session_store_unlocked(state: State<RuntimeState>) -> bool {
+    state.0.is_session_store_unlocked()
+}
+
+/// Unlock the device signing key (created on first use); returns its public key.
+#[tauri::command]
+pub fn unlock_signing_key(state: State<RuntimeState>, passphrase: String) -> Result<String, String> {
+    state.0.unlock_signing_key(passphrase).map_err(|e| e.to_string())
+}
+
+/// Get this device's session signing public key, once unlocked.
+#[tauri::command]
+pub fn get_device_public_key(state: State<RuntimeState>) -> Option<String> {
+    state.0.get_device_public_key()
+}
+
+/// Verify the session signatures of an export (defaults to this device's key).
+#[tauri::command]
+pub fn verify_session_export(
+    state: State<RuntimeState>,
+    json: String,
+    public_key: Option<String>,
+) -> Result<FfiSignatureReport, String> {
+    state.0.verify_session_export(json, public_key).map_err(|e| e.to_string())
 }
 
 // =============================================================================
-// CONTEXT & CONTROL
+// IMPORT COMMANDS
 // =============================================================================
 
-/// Update context (time of day, device state, session history).
-/// This helps the Engine adapt its recommendations.
+/// Import session history from another app (Apple Health export.xml or HR CSV).
 #[tauri::command]
-pub fn update_context(
+pub fn import_sessions(
     state: State<RuntimeState>,
-    local_hour: u8,
-    is_charging: bool,
-    recent_sessions: u16,
-) {
-    state.0.update_context(local_hour, is_charging, recent_sessions);
+    path: String,
+    format: FfiImportFormat,
+) -> Result<FfiImportReport, String> {
+    state.0.import_sessions(path, format).map_err(|e| e.to_string())
 }
 
-/// Adjust tempo scale.
+// =============================================================================
+// RESEARCH MODE COMMANDS
+// =============================================================================
+
+/// Grant research consent; returns the token needed to enable research mode.
 #[tauri::command]
-pub fn adjust_tempo(state: State<RuntimeState>, scale: f32, reason: String) -> Result<f32, String> {
-    state.0.adjust_tempo(scale, reason).map_err(|e| e.to_string())
+pub fn grant_research_consent(
+    state: State<RuntimeState>,
+    output_dir: String,
+    valid_days: u32,
+) -> Result<String, String> {
+    state.0.grant_research_consent(output_dir, valid_days).map_err(|e| e.to_string())
 }
 
-/// Emergency halt.
+/// Enable raw signal logging to encrypted files.
 #[tauri::command]
-pub fn emergency_halt(state: State<RuntimeState>, reason: String) {
-    state.0.emergency_halt(reason);
+pub fn enable_research_mode(
+    state: State<RuntimeState>,
+    output_dir: String,
+    consent_token: String,
+    passphrase: String,
+) -> Result<(), String> {
+    state.0
+        .enable_research_mode(output_dir, consent_token, passphrase)
+        .map_err(|e| e.to_string())
+}
+
+/// Disable raw signal logging.
+#[tauri::command]
+pub fn disable_research_mode(state: State<RuntimeState>) {
+    state.0.disable_research_mode();
+}
+
+/// Check if research mode is recording.
+#[tauri::command]
+pub fn is_research_mode_active(state: State<RuntimeState>) -> bool {
+    state.0.is_research_mode_active()
+}
+
+/// Revoke research consent and delete all recorded data.
+#[tauri::command]
+pub fn revoke_research_consent(state: State<RuntimeState>, output_dir: String) -> Result<u32, String> {
+    state.0.revoke_research_consent(output_dir).map_err(|e| e.to_string())
+}
+
+/// Export an encrypted, signed research package for a time range.
+#[tauri::command]
+pub fn export_research_package(
+    state: State<RuntimeState>,
+    start_ms: i64,
+    end_ms: i64,
+    scopes: Vec<FfiConsentScope>,
+    path: String,
+    passphrase: String,
+) -> Result<FfiResearchManifest, String> {
+    state.0
+        .export_research_package(start_ms, end_ms, scopes, path, passphrase)
+        .map_err(|e| e.to_string())
+}
+
+/// Verify a research package against a device public key (this device's
+/// when omitted) and return its manifest.
+#[tauri::command]
+pub fn verify_research_package(
+    state: State<RuntimeState>,
+    path: String,
+    passphrase: String,
+    public_key: Option<String>,
+) -> Result<FfiResearchManifest, String> {
+    state.0
+        .verify_research_package(path, passphrase, public_key)
+        .map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// COMMAND RECORDING COMMANDS
+// =============================================================================
+
+/// Record every runtime command and signal result to a file.
+#[tauri::command]
+pub fn start_command_recording(state: State<RuntimeState>, path: String) -> Result<(), String> {
+    state.0.start_command_recording(path).map_err(|e| e.to_string())
+}
+
+/// Stop recording; returns the number of entries written.
+#[tauri::command]
+pub fn stop_command_recording(state: State<RuntimeState>) -> Result<u64, String> {
+    state.0.stop_command_recording().map_err(|e| e.to_string())
+}
+
+/// Replay a recording into a fresh runtime and report how it ended.
+#[tauri::command]
+pub fn replay_command_recording(state: State<RuntimeState>, path: String) -> Result<FfiReplayReport, String> {
+    state.0.replay_command_recording(path).map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// SIMULATION COMMANDS
+// =============================================================================
+
+/// Start the built-in heart rate generator, used while the Simulated source is selected.
+#[tauri::command]
+pub fn start_hr_simulator(state: State<RuntimeState>, config: FfiHrSimulatorConfig) -> Result<(), String> {
+    state.0.start_hr_simulator(config).map_err(|e| e.to_string())
+}
+
+/// Stop the built-in heart rate generator.
+#[tauri::command]
+pub fn stop_hr_simulator(state: State<RuntimeState>) {
+    state.0.stop_hr_simulator();
 }
 
-/// Reset safety lock.
+/// Run a headless session on synthetic heart rate and virtual time.
 #[tauri::command]
-pub fn reset_safety_lock(state: State<RuntimeState>) {
-    state.0.reset_safety_lock();
+pub fn simulate_session(
+    state: State<RuntimeState>,
+    pattern_id: String,
+    duration_sec: f32,
+    hr_profile: FfiSimulatedHrProfile,
+) -> Result<FfiSimulationResult, String> {
+    state.0.simulate_session(pattern_id, duration_sec, hr_profile).map_err(|e| e.to_string())
 }
 
 // =============================================================================
@@ -152,7 +1237,7 @@ pub fn reset_safety_lock(state: State<RuntimeState>) {
 // =============================================================================
 
 use zenone_ffi::{
-    FfiKernelEvent, FfiSafetyCheckResult, FfiSafetyViolation, SafetyMonitor,
+    FfiKernelEvent, FfiSafetyCheckResult, FfiSafetyViolation, FfiTraceReport, SafetyMonitor,
 };
 
 /// Managed state: holds the SafetyMonitor singleton.
@@ -170,6 +1255,17 @@ pub fn check_safety_event(
     safety.check_event(event, state)
 }
 
+/// Dry-run an event against the runtime's safety specs without recording it.
+/// Pass `state` to evaluate a hypothetical state (e.g. a proposed tempo).
+#[tauri::command]
+pub fn evaluate_safety_specs(
+    runtime_state: State<RuntimeState>,
+    event: FfiKernelEvent,
+    state: Option<FfiRuntimeState>,
+) -> FfiSafetyCheckResult {
+    runtime_state.0.evaluate_safety_specs(event, state)
+}
+
 /// Get all safety violations.
 #[tauri::command]
 pub fn get_safety_violations(state: State<SafetyMonitorState>) -> Vec<FfiSafetyViolation> {
@@ -187,6 +1283,24 @@ pub fn get_recent_safety_violations(
     safety.get_recent_violations(count)
 }
 
+/// Get the violations recorded by the runtime's own safety checks.
+#[tauri::command]
+pub fn get_runtime_safety_violations(state: State<RuntimeState>) -> Vec<FfiSafetyViolation> {
+    state.0.get_safety_violations()
+}
+
+/// Re-verify all safety specs over the runtime's whole logged event history.
+#[tauri::command]
+pub fn verify_safety_trace(state: State<RuntimeState>) -> FfiTraceReport {
+    state.0.verify_trace()
+}
+
+/// Acknowledge a single runtime safety violation with an optional review note.
+#[tauri::command]
+pub fn ack_safety_violation(state: State<RuntimeState>, id: u64, note: Option<String>) -> Result<(), String> {
+    state.0.ack_safety_violation(id, note).map_err(|e| e.to_string())
+}
+
 /// Clear safety violation history.
 #[tauri::command]
 pub fn clear_safety_violations(state: State<SafetyMonitorState>) {
@@ -244,28 +1358,82 @@ pub fn pid_get_diagnostics(state: State<PidControllerState>) -> FfiPidDiagnostic
 // PATTERN RECOMMENDER COMMANDS
 // ============================================================================
 
-use zenone_ffi::{PatternRecommender, FfiPatternRecommendation};
+use zenone_ffi::{
+    FfiCyclePrescriptionConfig, FfiOnboardingAnswers, FfiPatternRecommendation, PatternRecommender,
+};
 
 /// Global Pattern Recommender (singleton)
 pub struct RecommenderState(pub StdMutex<PatternRecommender>);
 
-/// Get breathing pattern recommendations.
+/// Get breathing pattern recommendations, with cycle counts prescribed
+/// from the runtime's current belief and the hour in the user's time zone.
 #[tauri::command]
 pub fn recommend_patterns(
     state: State<RecommenderState>,
-    local_hour: u8,
+    runtime: State<RuntimeState>,
     limit: u32,
 ) -> Vec<FfiPatternRecommendation> {
+    let local_hour = runtime.0.get_local_time().hour;
+    let belief = runtime.0.get_belief();
     let recommender = state.0.lock().unwrap();
-    recommender.recommend(local_hour, limit)
+    recommender.set_baseline(runtime.0.get_breathing_baseline());
+    recommender.set_belief_modes(runtime.0.list_custom_belief_modes());
+    recommender.recommend_for_belief(local_hour, limit, belief)
 }
 
-/// Record pattern usage (for variety scoring).
+/// Configure how cycle counts are prescribed.
+#[tauri::command]
+pub fn set_cycle_prescription(
+    state: State<RecommenderState>,
+    config: FfiCyclePrescriptionConfig,
+) -> Result<(), String> {
+    let recommender = state.0.lock().unwrap();
+    recommender.set_prescription_config(config).map_err(|e| e.to_string())
+}
+
+/// Get the cycle prescription settings.
+#[tauri::command]
+pub fn get_cycle_prescription(state: State<RecommenderState>) -> FfiCyclePrescriptionConfig {
+    let recommender = state.0.lock().unwrap();
+    recommender.get_prescription_config()
+}
+
+/// Store onboarding questionnaire answers so first recommendations follow them.
+#[tauri::command]
+pub fn set_onboarding_answers(
+    state: State<RecommenderState>,
+    runtime: State<RuntimeState>,
+    answers: Option<FfiOnboardingAnswers>,
+) -> Result<(), String> {
+    runtime.0.set_onboarding_answers(answers.clone()).map_err(|e| e.to_string())?;
+    let recommender = state.0.lock().unwrap();
+    recommender.set_onboarding(answers).map_err(|e| e.to_string())
+}
+
+/// Get the stored onboarding answers.
+#[tauri::command]
+pub fn get_onboarding_answers(runtime: State<RuntimeState>) -> Option<FfiOnboardingAnswers> {
+    runtime.0.get_onboarding_answers()
+}
+
+/// Get the default pattern for a new user, picked from their onboarding answers.
+#[tauri::command]
+pub fn get_starter_pattern(state: State<RecommenderState>) -> Option<String> {
+    let recommender = state.0.lock().unwrap();
+    recommender.starter_pattern()
+}
+
+/// Record pattern usage (for variety scoring). Skipped while recommender
+/// learning is switched off.
 #[tauri::command]
 pub fn record_pattern_usage(
     state: State<RecommenderState>,
+    runtime: State<RuntimeState>,
     pattern_id: String,
 ) {
+    if !runtime.0.is_feature_enabled(FfiFeatureFlag::RecommenderLearning) {
+        return;
+    }
     let recommender = state.0.lock().unwrap();
     recommender.record_pattern(pattern_id);
 }
@@ -281,19 +1449,46 @@ pub fn clear_pattern_history(state: State<RecommenderState>) {
 // BINAURAL BEATS COMMANDS
 // ============================================================================
 
-use zenone_ffi::{BinauralManager, FfiBrainWaveState, FfiBinauralConfig};
+use zenone_ffi::{
+    BinauralManager, FfiBinauralConfig, FfiBinauralPreset, FfiBinauralStats, FfiBrainWaveState,
+};
 
 /// Global Binaural Manager (singleton)
 pub struct BinauralState(pub StdMutex<BinauralManager>);
 
-/// Get configuration for a brain wave state
+/// Get configuration for a builtin brain wave state ("Theta") or a custom preset id
 #[tauri::command]
 pub fn get_binaural_config(
     state: State<BinauralState>,
-    brain_wave: FfiBrainWaveState,
-) -> FfiBinauralConfig {
+    brain_wave: String,
+) -> Result<FfiBinauralConfig, String> {
+    let manager = state.0.lock().unwrap();
+    manager.get_config_by_id(brain_wave).map_err(|e| e.to_string())
+}
+
+/// List builtin and custom binaural presets
+#[tauri::command]
+pub fn list_binaural_presets(state: State<BinauralState>) -> Vec<FfiBinauralPreset> {
+    let manager = state.0.lock().unwrap();
+    manager.list_presets()
+}
+
+/// Add or replace a custom binaural preset (validated against safe frequency ranges)
+#[tauri::command]
+pub fn define_binaural_preset(
+    state: State<BinauralState>,
+    id: String,
+    config: FfiBinauralConfig,
+) -> Result<FfiBinauralPreset, String> {
     let manager = state.0.lock().unwrap();
-    manager.get_config(brain_wave)
+    manager.define_preset(id, config).map_err(|e| e.to_string())
+}
+
+/// Remove a custom binaural preset
+#[tauri::command]
+pub fn remove_binaural_preset(state: State<BinauralState>, id: String) -> Result<(), String> {
+    let manager = state.0.lock().unwrap();
+    manager.remove_preset(id).map_err(|e| e.to_string())
 }
 
 /// Get recommended brain wave state
@@ -305,3 +1500,141 @@ pub fn get_binaural_recommendation(
     let manager = state.0.lock().unwrap();
     manager.get_recommended_state(arousal_target)
 }
+
+/// Report the binaural state currently playing (null when stopped).
+#[tauri::command]
+pub fn set_binaural_state(state: State<RuntimeState>, brain_wave: Option<FfiBrainWaveState>) {
+    state.0.set_binaural_state(brain_wave);
+}
+
+/// Binaural listening time per state, correlated with session outcomes.
+#[tauri::command]
+pub fn get_binaural_stats(state: State<RuntimeState>, metric: FfiOutcomeMetric) -> Result<FfiBinauralStats, String> {
+    state.0.get_binaural_stats(metric).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// API VERSIONING
+// ============================================================================
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Version of the invoke command API. Bump it on any breaking change to a
+/// command's name, arguments or result, and list the old form in `DEPRECATIONS`.
+pub const API_VERSION: u32 = 2;
+
+/// Oldest frontend API version the compatibility shims still serve.
+pub const MIN_SUPPORTED_API_VERSION: u32 = 1;
+
+/// (command, version that changed it, how to migrate)
+const DEPRECATIONS: &[(&str, u32, &str)] = &[
+    (
+        "reset_safety_lock",
+        2,
+        "pass `reason`; calls without it are journaled with a placeholder reason",
+    ),
+];
+
+/// A command whose older signature is still accepted through a shim.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiDeprecation {
+    pub command: String,
+    pub changed_in: u32,
+    pub migration: String,
+}
+
+/// Result of the frontend/core version handshake.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiHandshake {
+    pub api_version: u32,
+    pub min_supported_version: u32,
+    pub client_version: u32,
+    /// Deprecated signatures the client's version may still be using
+    pub deprecations: Vec<ApiDeprecation>,
+}
+
+/// Managed state: the API version the frontend negotiated.
+pub struct ApiVersionState {
+    client_version: StdMutex<Option<u32>>,
+    warned: StdMutex<HashSet<&'static str>>,
+}
+
+impl ApiVersionState {
+    pub fn new() -> Self {
+        ApiVersionState {
+            client_version: StdMutex::new(None),
+            warned: StdMutex::new(HashSet::new()),
+        }
+    }
+
+    /// Map an argument added in `since` onto older calls that omit it.
+    /// Clients that negotiated `since` or later (or never shook hands) must send it.
+    fn upgrade_arg<T>(
+        &self,
+        command: &'static str,
+        since: u32,
+        value: Option<T>,
+        legacy: impl FnOnce() -> T,
+    ) -> Result<T, String> {
+        if let Some(value) = value {
+            return Ok(value);
+        }
+        let client_version = self.client_version.lock().unwrap().unwrap_or(API_VERSION);
+        if client_version >= since {
+            return Err(format!("{}: missing argument required since API v{}", command, since));
+        }
+        if self.warned.lock().unwrap().insert(command) {
+            log::warn!(
+                "Frontend API v{} called deprecated form of `{}` (changed in v{})",
+                client_version, command, since
+            );
+        }
+        Ok(legacy())
+    }
+}
+
+impl Default for ApiVersionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Negotiate the command API version. Fails when the frontend is outside the
+/// range this core can serve, so mismatches surface at startup instead of as
+/// malformed invoke payloads.
+#[tauri::command]
+pub fn api_handshake(state: State<ApiVersionState>, client_version: u32) -> Result<ApiHandshake, String> {
+    if client_version < MIN_SUPPORTED_API_VERSION {
+        return Err(format!(
+            "Frontend API v{} is no longer supported (minimum v{})",
+            client_version, MIN_SUPPORTED_API_VERSION
+        ));
+    }
+    if client_version > API_VERSION {
+        return Err(format!(
+            "Frontend API v{} is newer than core API v{}",
+            client_version, API_VERSION
+        ));
+    }
+    *state.client_version.lock().unwrap() = Some(client_version);
+    log::info!("Command API handshake: frontend v{}, core v{}", client_version, API_VERSION);
+
+    let deprecations = DEPRECATIONS
+        .iter()
+        .filter(|(_, changed_in, _)| client_version < *changed_in)
+        .map(|(command, changed_in, migration)| ApiDeprecation {
+            command: command.to_string(),
+            changed_in: *changed_in,
+            migration: migration.to_string(),
+        })
+        .collect();
+    Ok(ApiHandshake {
+        api_version: API_VERSION,
+        min_supported_version: MIN_SUPPORTED_API_VERSION,
+        client_version,
+        deprecations,
+    })
+}
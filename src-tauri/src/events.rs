@@ -0,0 +1,73 @@
+//! Pushes runtime phase, cycle, status, heart-rate and state changes to the
+//! webview as Tauri events, so the UI can subscribe instead of polling
+//! `tick`/`get_state`.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use zenone_ffi::{FfiPhase, FfiRuntimeStateDiff, FfiRuntimeStatus, RuntimeListener};
+
+pub const PHASE_CHANGED: &str = "zenone://phase-changed";
+pub const CYCLE_COMPLETE: &str = "zenone://cycle-complete";
+pub const STATUS_CHANGED: &str = "zenone://status-changed";
+pub const HR_UPDATE: &str = "zenone://hr-update";
+/// Carries only the `FfiRuntimeState` fields that changed
+pub const STATE_CHANGED: &str = "zenone://state-changed";
+
+#[derive(Clone, Serialize)]
+struct PhaseChanged {
+    phase: FfiPhase,
+    time_to_next_phase_ms: u32,
+    timestamp_us: i64,
+}
+
+#[derive(Clone, Serialize)]
+struct CycleComplete {
+    cycles_completed: u64,
+    adherence: Option<f32>,
+    timestamp_us: i64,
+}
+
+#[derive(Clone, Serialize)]
+struct StatusChanged {
+    status: FfiRuntimeStatus,
+}
+
+#[derive(Clone, Serialize)]
+struct HrUpdate {
+    bpm: f32,
+    confidence: f32,
+    timestamp_us: i64,
+}
+
+/// Forwards runtime listener callbacks as app-wide Tauri events
+pub struct TauriEventListener(pub AppHandle);
+
+impl TauriEventListener {
+    fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Err(e) = self.0.emit(event, payload) {
+            log::warn!("Failed to emit {}: {}", event, e);
+        }
+    }
+}
+
+impl RuntimeListener for TauriEventListener {
+    fn on_phase_changed(&self, phase: FfiPhase, time_to_next_phase_ms: u32, timestamp_us: i64) {
+        self.emit(PHASE_CHANGED, PhaseChanged { phase, time_to_next_phase_ms, timestamp_us });
+    }
+
+    fn on_cycle_complete(&self, cycles_completed: u64, adherence: Option<f32>, timestamp_us: i64) {
+        self.emit(CYCLE_COMPLETE, CycleComplete { cycles_completed, adherence, timestamp_us });
+    }
+
+    fn on_status_changed(&self, status: FfiRuntimeStatus) {
+        self.emit(STATUS_CHANGED, StatusChanged { status });
+    }
+
+    fn on_heart_rate(&self, bpm: f32, confidence: f32, timestamp_us: i64) {
+        self.emit(HR_UPDATE, HrUpdate { bpm, confidence, timestamp_us });
+    }
+
+    fn on_state_changed(&self, diff: FfiRuntimeStateDiff) {
+        self.emit(STATE_CHANGED, diff);
+    }
+}
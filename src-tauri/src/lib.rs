@@ -1,9 +1,10 @@
 //! Tauri application entrypoint with ZenOne Kernel integration.
 
 mod commands;
+mod events;
 
 use std::sync::Mutex;
-use commands::{RuntimeState, SafetyMonitorState, PidControllerState, RecommenderState, BinauralState};
+use commands::{RuntimeState, SafetyMonitorState, PidControllerState, RecommenderState, BinauralState, ApiVersionState};
 use tauri::Manager;
 use zenone_ffi::{ZenOneRuntime, SafetyMonitor, PidController, PatternRecommender, BinauralManager};
 
@@ -15,34 +16,196 @@ pub fn run() {
         .manage(PidControllerState(Mutex::new(PidController::new())))
         .manage(RecommenderState(Mutex::new(PatternRecommender::new())))
         .manage(BinauralState(Mutex::new(BinauralManager::new())))
+        .manage(ApiVersionState::new())
         .invoke_handler(tauri::generate_handler![
+            // API versioning
+            commands::api_handshake,
             // Pattern commands
             commands::get_patterns,
+            commands::create_pattern,
+            commands::delete_pattern,
+            commands::export_pattern,
+            commands::import_pattern,
+            commands::validate_pattern,
+            commands::propose_pattern_change,
+            commands::confirm_pattern_change,
             commands::load_pattern,
+            commands::load_sequence,
+            commands::get_sequence_progress,
             commands::current_pattern_id,
+            commands::blend_patterns,
+            commands::override_phase_duration,
+            commands::reset_phase_overrides,
+            commands::get_phase_overrides,
+            commands::preview_pattern,
+            commands::stop_preview,
             // Session commands
+            commands::preflight_check,
             commands::start_session,
+            commands::start_session_with_goals,
+            commands::start_sleep_session,
+            commands::start_panic_session,
+            commands::report_activity,
             commands::stop_session,
+            commands::set_preparation_config,
+            commands::set_auto_complete,
+            commands::set_follow_me,
+            commands::start_baseline_observation,
+            commands::stop_baseline_observation,
+            commands::get_breathing_baseline,
+            commands::start_noise_profiling,
+            commands::get_noise_profile,
+            commands::set_audio_breath_detection,
+            commands::skip_preparation,
+            commands::set_session_stages,
+            commands::begin_cool_down,
             commands::pause_session,
+            commands::report_interruption,
             commands::resume_session,
             commands::is_session_active,
             // Frame processing
             commands::tick,
+            commands::tick_lite,
+            commands::get_frame_lite,
             commands::process_frame,
+            commands::process_frames_batch,
+            commands::process_frame_buffer,
+            commands::set_skin_roi_tracking,
+            commands::set_phase_warning_lead,
+            commands::set_pattern_transition_cycles,
+            commands::set_phase_countdown,
+            commands::set_state_publish_rate,
+            commands::ingest_hr_sample,
+            commands::ingest_manual_hr,
+            commands::set_hr_source,
+            commands::get_hr_source,
+            commands::ingest_respiration_sample,
+            // Arc planner
+            commands::set_arc_planner_enabled,
+            // Device auto-configuration
+            commands::configure_for_device,
+            commands::get_pipeline_profile,
+            commands::set_rppg_method,
+            commands::get_rppg_method,
+            commands::set_rppg_config,
+            commands::get_rppg_config,
+            commands::get_ppg_spectrum,
+            commands::set_ppg_trace,
+            commands::drain_ppg_trace,
+            commands::start_rppg_calibration,
+            commands::add_manual_hr_reference,
+            commands::finish_rppg_calibration,
+            commands::use_rppg_calibration,
+            commands::get_rppg_calibrations,
+            commands::run_pipeline_benchmark,
+            // Group sessions
+            commands::host_group_session,
+            commands::join_group_session,
+            commands::leave_group_session,
+            commands::get_group_status,
+            // Serial pacer output
+            commands::start_serial_pacer,
+            commands::stop_serial_pacer,
+            commands::get_serial_pacer_status,
+            // Watch mirroring
+            commands::watch_set_connected,
+            commands::watch_next_message,
+            commands::watch_handle_message,
+            commands::get_watch_link_status,
+            // Events
+            commands::poll_events,
             // State queries
             commands::get_state,
+            commands::get_state_delta,
             commands::get_belief,
+            commands::list_belief_modes,
+            commands::register_belief_mode,
+            commands::remove_belief_mode,
             commands::get_safety_status,
             // Context & Control
             commands::update_context,
             commands::adjust_tempo,
             commands::emergency_halt,
             commands::reset_safety_lock,
+            commands::get_safety_journal,
+            commands::set_hr_zones,
+            commands::get_hr_zones,
+            commands::set_breath_rate_band,
+            commands::get_breath_rate_band,
+            commands::set_hr_filter_config,
+            commands::get_hr_filter_config,
+            commands::set_escalation_config,
+            commands::get_escalation_config,
+            // Feature flags
+            commands::set_feature_flag,
+            commands::is_feature_enabled,
+            commands::get_feature_flags,
+            // Consent commands
+            commands::grant_consent,
+            commands::revoke_consent,
+            commands::has_consent,
+            commands::get_consents,
+            commands::get_consent_audit,
+            // N-of-1 experiment commands
+            commands::create_experiment,
+            commands::list_experiments,
+            commands::get_experiment_assignment,
+            commands::get_experiment_report,
+            commands::end_experiment,
+            commands::compare_patterns_stats,
+            // Session history commands
+            commands::tag_session,
+            commands::search_sessions,
+            commands::get_session_history,
+            commands::get_session_by_id,
+            commands::query_sessions,
+            commands::set_streak_config,
+            commands::get_streak_config,
+            commands::get_streak_info,
+            commands::get_streak,
+            commands::set_time_zone,
+            commands::get_time_zone,
+            commands::get_local_time,
+            commands::get_rollups,
+            commands::set_retention_policy,
+            commands::get_retention_policy,
+            // Export commands
+            commands::export_fhir_bundle,
+            commands::export_ibi,
+            commands::export_sessions,
+            commands::migrate_session_export,
+            commands::unlock_session_store,
+            commands::is_session_store_unlocked,
+            commands::unlock_signing_key,
+            commands::get_device_public_key,
+            commands::verify_session_export,
+            // Import commands
+            commands::import_sessions,
+            // Research mode commands
+            commands::grant_research_consent,
+            commands::enable_research_mode,
+            commands::disable_research_mode,
+            commands::is_research_mode_active,
+            commands::revoke_research_consent,
+            commands::export_research_package,
+            commands::verify_research_package,
+            // Command recording
+            commands::start_command_recording,
+            commands::stop_command_recording,
+            commands::replay_command_recording,
+            // Simulation
+            commands::start_hr_simulator,
+            commands::stop_hr_simulator,
+            commands::simulate_session,
             // Safety Monitor commands
             commands::check_safety_event,
+            commands::evaluate_safety_specs,
             commands::get_safety_violations,
             commands::get_recent_safety_violations,
             commands::clear_safety_violations,
+            commands::get_runtime_safety_violations,
+            commands::ack_safety_violation,
+            commands::verify_safety_trace,
             commands::is_system_safe,
             // PID Controller commands
             commands::pid_compute,
@@ -52,11 +215,40 @@ pub fn run() {
             commands::recommend_patterns,
             commands::record_pattern_usage,
             commands::clear_pattern_history,
+            commands::set_cycle_prescription,
+            commands::get_cycle_prescription,
+            commands::set_onboarding_answers,
+            commands::get_onboarding_answers,
+            commands::get_starter_pattern,
             // Binaural commands
             commands::get_binaural_config,
             commands::get_binaural_recommendation,
+            commands::list_binaural_presets,
+            commands::define_binaural_preset,
+            commands::remove_binaural_preset,
+            commands::set_binaural_state,
+            commands::get_binaural_stats,
         ])
         .setup(|app| {
+            // Push phase/cycle/status changes to the webview
+            app.state::<RuntimeState>()
+                .0
+                .set_listener(Some(Box::new(events::TauriEventListener(app.handle().clone()))));
+            // Persist profile data (consent, ...) in the platform app data dir
+            if let Ok(dir) = app.path().app_data_dir() {
+                let runtime = app.state::<RuntimeState>();
+                if let Err(e) = runtime.0.set_data_dir(dir.to_string_lossy().into_owned()) {
+                    log::error!("Failed to initialize data dir: {}", e);
+                }
+                // Seed the recommender with the persisted questionnaire
+                let recommender = app.state::<RecommenderState>();
+                let _ = recommender.0.lock().unwrap().set_onboarding(runtime.0.get_onboarding_answers());
+                let binaural = app.state::<BinauralState>();
+                let manager = binaural.0.lock().unwrap();
+                if let Err(e) = manager.set_data_dir(dir.to_string_lossy().into_owned()) {
+                    log::error!("Failed to load binaural presets: {}", e);
+                }
+            }
             if cfg!(debug_assertions) {
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
@@ -70,6 +262,12 @@ pub fn run() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Save any active session and join the runtime threads before exit
+            if let tauri::RunEvent::Exit = event {
+                app.state::<RuntimeState>().0.shutdown();
+            }
+        });
 }
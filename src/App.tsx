@@ -21,6 +21,7 @@ import { useKernel, useKernelState } from './kernel/KernelProvider';
 import { GeminiSomaticBridge } from './services/GeminiSomaticBridge';
 import { KineticSnackbar } from './design-system';
 import { ConfirmationModal } from './components/modals/ConfirmationModal';
+import { SessionUnlockModal } from './components/modals/SessionUnlockModal';
 import { WakeLockManager } from './services/WakeLockManager';
 import { OnlineStatusManager } from './services/OnlineStatusManager';
 
@@ -254,6 +255,7 @@ export default function App() {
       <HistorySheet />
       <SettingsSheet />
       <ConfirmationModal />
+      <SessionUnlockModal />
 
     </div>
   );
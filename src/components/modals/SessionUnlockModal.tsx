@@ -0,0 +1,105 @@
+/**
+ * SESSION STORE UNLOCK MODAL
+ * ==========================
+ *
+ * The native session history is encrypted at rest and stays in memory only
+ * until it is unlocked. Shown on desktop/mobile when a passphrase is set but
+ * the store is still locked (e.g. after a restart).
+ */
+
+import { useEffect, useState } from 'react';
+import { Lock } from 'lucide-react';
+import { useSettingsStore } from '../../stores/settingsStore';
+import { getTauriRuntime, initTauriInvoke, isTauriAvailable } from '../../services/TauriRuntime';
+
+export function SessionUnlockModal() {
+    const hasPassphrase = useSettingsStore(s => s.userSettings.hasPassphrase);
+    const unlockWithPassphrase = useSettingsStore(s => s.unlockWithPassphrase);
+
+    const [locked, setLocked] = useState(false);
+    const [passphrase, setPassphrase] = useState('');
+    const [error, setError] = useState<string | null>(null);
+    const [busy, setBusy] = useState(false);
+
+    useEffect(() => {
+        if (!hasPassphrase) return;
+        let cancelled = false;
+
+        (async () => {
+            if (!isTauriAvailable() && !(await initTauriInvoke())) return;
+            if (await getTauriRuntime().isSessionStoreUnlocked()) return;
+            // Same tab after a reload: the passphrase is still in sessionStorage
+            const cached = sessionStorage.getItem('zenb_passphrase');
+            const unlocked = cached ? await unlockWithPassphrase(cached).catch(() => false) : false;
+            if (!cancelled) setLocked(!unlocked);
+        })().catch(e => console.warn('[SessionUnlock] Failed to check session store:', e));
+
+        return () => { cancelled = true; };
+    }, [hasPassphrase, unlockWithPassphrase]);
+
+    if (!locked) return null;
+
+    const handleUnlock = async () => {
+        setBusy(true);
+        setError(null);
+        try {
+            if (await unlockWithPassphrase(passphrase)) {
+                setLocked(false);
+                setPassphrase('');
+            } else {
+                setError('Incorrect passphrase');
+            }
+        } catch (e) {
+            setError(e instanceof Error ? e.message : String(e));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 bg-black/80 backdrop-blur-sm flex items-center justify-center z-50 p-4">
+            <div className="glass-card max-w-sm w-full p-6 space-y-4">
+                <div className="flex items-center gap-3">
+                    <Lock className="w-6 h-6 text-blue-400" />
+                    <div>
+                        <h3 className="text-lg font-semibold">Unlock Session History</h3>
+                        <p className="text-sm text-gray-400">Enter your passphrase to load and save sessions</p>
+                    </div>
+                </div>
+
+                <input
+                    type="password"
+                    value={passphrase}
+                    onChange={(e) => setPassphrase(e.target.value)}
+                    onKeyDown={(e) => { if (e.key === 'Enter' && passphrase) handleUnlock(); }}
+                    placeholder="Passphrase"
+                    autoFocus
+                    className="w-full bg-black/40 border border-white/10 rounded-lg px-4 py-2.5
+                     focus:outline-none focus:border-blue-500/50 transition-colors"
+                />
+                {error && <p className="text-xs text-red-400">{error}</p>}
+
+                <p className="text-xs text-gray-400">
+                    Until unlocked, sessions you complete are kept in memory only.
+                </p>
+
+                <div className="flex gap-3">
+                    <button
+                        onClick={() => setLocked(false)}
+                        className="flex-1 px-4 py-2.5 bg-white/5 hover:bg-white/10 rounded-lg transition-colors"
+                    >
+                        Not Now
+                    </button>
+                    <button
+                        onClick={handleUnlock}
+                        disabled={!passphrase || busy}
+                        className="flex-1 px-4 py-2.5 bg-blue-600 hover:bg-blue-700 disabled:bg-gray-700
+                     disabled:cursor-not-allowed rounded-lg transition-colors font-medium"
+                    >
+                        Unlock
+                    </button>
+                </div>
+            </div>
+        </div>
+    );
+}
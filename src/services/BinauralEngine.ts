@@ -92,6 +92,17 @@ export class BinauralEngine {
     return BINAURAL_CONFIGS[state];
   }
 
+  /**
+   * Tell the Rust runtime what is playing so listening time lands in session stats
+   */
+  private reportState(state: BrainWaveState | null): void {
+    if (!isTauriAvailable()) return;
+    const rustState = state ? (state.charAt(0).toUpperCase() + state.slice(1)) as FfiBrainWaveState : null;
+    getTauriRuntime().setBinauralState(rustState).catch(e => {
+      console.warn('Failed to report binaural state to Rust:', e);
+    });
+  }
+
   /**
    * Initialize binaural beat oscillators
    */
@@ -163,6 +174,7 @@ export class BinauralEngine {
 
     this.currentState = state;
     this.isActive = true;
+    this.reportState(state);
 
     console.log(`🧠 Binaural Beats: ${config.description} (${config.beatFreq} Hz)`);
   }
@@ -185,6 +197,7 @@ export class BinauralEngine {
     this.rightOsc?.stop();
 
     this.isActive = false;
+    this.reportState(null);
 
     console.log('🧠 Binaural Beats stopped');
   }
@@ -204,6 +217,7 @@ export class BinauralEngine {
     this.leftOsc.frequency.rampTo(config.baseFreq, transitionTime);
     this.rightOsc.frequency.rampTo(config.baseFreq + config.beatFreq, transitionTime);
 
+    if (newState !== this.currentState) this.reportState(newState);
     this.currentState = newState;
 
     console.log(`🧠 Binaural transition: ${config.description} (${config.beatFreq} Hz)`);
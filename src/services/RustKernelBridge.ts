@@ -41,27 +41,66 @@ import {
 // FFI TYPE DEFINITIONS (matches rust-core/src/zenone.udl)
 // ============================================================================
 
-export type FfiPhase = 'Inhale' | 'HoldIn' | 'Exhale' | 'HoldOut';
+/** 'Prepare' is the pre-session countdown before the first inhale */
+export type FfiPhase = 'Inhale' | 'HoldIn' | 'Exhale' | 'HoldOut' | 'Prepare';
 export type FfiBeliefMode = 'Calm' | 'Stress' | 'Focus' | 'Sleepy' | 'Energize';
-export type FfiRuntimeStatus = 'Idle' | 'Running' | 'Paused' | 'SafetyLock';
+export type FfiRuntimeStatus = 'Idle' | 'Preparing' | 'Running' | 'Paused' | 'SafetyLock';
 
 export interface FfiBeliefState {
     probabilities: number[];  // [Calm, Stress, Focus, Sleepy, Energize]
     confidence: number;
     mode: FfiBeliefMode;
     uncertainty: number;
+    modes: FfiModeProbability[];  // built-in modes, then custom modes
+}
+
+export interface FfiModeProbability {
+    mode_id: string;
+    probability: number;
+}
+
+export interface FfiBeliefModeSpec {
+    id: string;
+    label: string;
+    weights: number[];  // over [Calm, Stress, Focus, Sleepy, Energize]
+    bias: number;
+    boost_patterns: string[];
+    builtin: boolean;
 }
 
 export interface FfiResonance {
     coherence_score: number;
     phase_locking: number;
     rhythm_alignment: number;
+    /** Heart rate max minus min over the last breath cycle (BPM) */
+    rsa_amplitude_bpm: number;
 }
 
 export interface FfiPatternRecommendation {
     pattern_id: string;
     score: number;
     reason: string;
+    /** Prescribed cycle count for the current context */
+    cycles: number;
+}
+
+export interface FfiCyclePrescriptionConfig {
+    stress_threshold: number;
+    stress_max_boost: number;
+    night_scale: number;
+    min_cycles: number;
+    max_cycles: number;
+}
+
+export type FfiOnboardingGoal = 'Sleep' | 'Stress' | 'Focus' | 'Energy';
+export type FfiBreathworkExperience = 'Beginner' | 'Intermediate' | 'Experienced';
+
+export interface FfiOnboardingAnswers {
+    goals: FfiOnboardingGoal[];
+    experience: FfiBreathworkExperience;
+    sleep_issues: boolean;
+    /** Self-reported anxiety, 0 (none) to 4 (severe) */
+    anxiety_level: number;
 }
 
 export type FfiBrainWaveState = 'Delta' | 'Theta' | 'Alpha' | 'Beta';
@@ -73,11 +112,307 @@ export interface FfiBinauralConfig {
     benefits: string[];
 }
 
+export interface FfiBreathPattern {
+    id: string;
+    label: string;
+    tag: string;
+    description: string;
+    inhale_sec: number;
+    hold_in_sec: number;
+    exhale_sec: number;
+    hold_out_sec: number;
+    recommended_cycles: number;
+    arousal_impact: number;
+}
+
+export interface FfiPhaseOverride {
+    pattern_id: string;
+    phase: FfiPhase;
+    seconds: number;
+}
+
+export interface FfiNoiseProfile {
+    measured_at_ms: number;
+    duration_sec: number;
+    samples: number;
+    noise_floor: number;
+    noise_sd: number;
+    threshold: number;
+    usable: boolean;
+    unusable_reason: string | null;
+}
+
+export interface FfiBreathingBaseline {
+    measured_at_ms: number;
+    duration_sec: number;
+    breaths: number;
+    rate_bpm: number;
+    interval_sd_sec: number;
+    variability: number;
+    source: 'ChestMotion' | 'Audio' | 'Rsa' | 'Ppg';
+}
+
+export type FfiEscalationReason = 'RepeatedCriticalViolations' | 'SustainedHeartRateAnomaly';
+
+export interface FfiEscalationConfig {
+    enabled: boolean;
+    contact_name: string;
+    critical_violation_count: number;
+    violation_window_sec: number;
+    hr_low_bpm: number;
+    hr_high_bpm: number;
+    hr_anomaly_sec: number;
+    cooldown_sec: number;
+}
+
+export interface FfiEscalation {
+    reason: FfiEscalationReason;
+    contact_name: string;
+    message: string;
+    timestamp_ms: number;
+}
+
+export interface FfiPreflightInput {
+    /** null when not yet checked (e.g. before the camera permission prompt) */
+    camera_available: boolean | null;
+    audio_output_available: boolean;
+    battery_percent: number | null;
+    is_charging: boolean;
+}
+
+export type FfiPreflightItem = 'CameraSignal' | 'Safety' | 'Storage' | 'AudioOutput' | 'Battery';
+
+export type FfiPreflightStatus = 'Pass' | 'Warn' | 'Fail';
+
+export interface FfiPreflightCheck {
+    item: FfiPreflightItem;
+    status: FfiPreflightStatus;
+    message: string;
+}
+
+export interface FfiPreflightReport {
+    ready: boolean;
+    checks: FfiPreflightCheck[];
+}
+
+export interface FfiSerialPacerStatus {
+    port: string;
+    baud_rate: number;
+    lines_sent: number;
+    lines_dropped: number;
+    error: string | null;
+}
+
+export interface FfiSignatureReport {
+    public_key: string;
+    total: number;
+    valid: number;
+    tampered_ids: string[];
+    unsigned_ids: string[];
+}
+
+export type FfiFeatureFlag = 'Rppg' | 'Binaural' | 'RecommenderLearning' | 'ResearchMode' | 'Sync';
+
+export interface FfiFeatureFlagState {
+    flag: FfiFeatureFlag;
+    enabled: boolean;
+    overridden: boolean;
+}
+
+export interface FfiStreakConfig {
+    rest_days: number[];
+    freezes_per_week: number;
+    min_session_sec: number;
+    grace_hours: number;
+}
+
+export interface FfiStreakStatus {
+    current: number;
+    longest: number;
+    last_active_date: string | null;
+    frozen_days: string[];
+    freezes_used_this_week: number;
+    freezes_left: number;
+    freeze_available: boolean;
+    today_done: boolean;
+    at_risk: boolean;
+}
+
+export interface FfiLocalTime {
+    /** IANA zone in use; null when following the device */
+    time_zone: string | null;
+    /** YYYY-MM-DD */
+    date: string;
+    hour: number;
+    utc_offset_min: number;
+}
+
+export type FfiRollupPeriod = 'Day' | 'Week' | 'Month';
+
+export interface FfiMetricRollup {
+    period: FfiRollupPeriod;
+    period_start: string;
+    sessions: number;
+    total_duration_sec: number;
+    total_cycles: number;
+    avg_heart_rate: number | null;
+    avg_resonance: number;
+    avg_adherence: number | null;
+}
+
+export interface FfiRetentionPolicy {
+    raw_session_days: number | null;
+}
+
+export type FfiCpuClass = 'Low' | 'Mid' | 'High';
+
+export type FfiRppgMethod = 'Pos' | 'Chrom' | 'Green';
+
+export type FfiHrSourceKind = 'Auto' | 'Camera' | 'Strap' | 'Manual' | 'Simulated';
+
+export interface FfiHrSimulatorConfig {
+    baseline_bpm: number;
+    rsa_amplitude_bpm: number;
+    noise_bpm: number;
+    seed: number;
+}
+
+export interface FfiPpgSpectrum {
+    frequencies_hz: number[];
+    power: number[];
+    peak_hz: number;
+    peak_bpm: number;
+    snr_db: number;
+    timestamp_us: number;
+}
+
+export interface FfiPpgTracePoint {
+    timestamp_us: number;
+    green: number;
+    pulse: number;
+}
+
+export interface FfiRppgConfig {
+    window_sec: number;
+    sample_rate_hz: number;
+    /** Follow the frame rate actually observed from timestamps */
+    adapt_sample_rate: boolean;
+}
+
+export interface FfiRgbSample {
+    r: number;
+    g: number;
+    b: number;
+    timestamp_us: number;
+}
+
+export type FfiPixelFormat = 'Rgba8' | 'Bgra8' | 'Rgb8' | 'Nv21' | 'Nv12' | 'I420';
+
+/** Offset and byte strides of one frame plane, as reported by the camera API */
+export interface FfiPlaneLayout {
+    offset: number;
+    row_stride: number;
+    pixel_stride: number;
+}
+
+export interface FfiRoi {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+export interface FfiDeviceCapabilities {
+    camera_available: boolean;
+    camera_fps?: number | null;
+    cpu_class?: FfiCpuClass | null;
+}
+
+export interface FfiPipelineProfile {
+    cpu_class: FfiCpuClass;
+    rppg_enabled: boolean;
+    rppg_window_samples: number;
+    rppg_sample_rate_hz: number;
+    state_publish_hz: number | null;
+    arc_planner_enabled: boolean;
+}
+
+export interface FfiBenchmarkResult {
+    recording: string;
+    profile: FfiPipelineProfile;
+    frames_processed: number;
+    hr_mae_bpm: number | null;
+    hr_coverage: number;
+    mean_latency_us: number;
+    p95_latency_us: number;
+    cpu_ms_per_sec: number;
+}
+
+export type FfiCalibrationReference = 'Strap' | 'ManualCount';
+
+export interface FfiRppgCalibration {
+    device_id: string;
+    reference: FfiCalibrationReference;
+    scale: number;
+    offset_bpm: number;
+    confidence_prior: number;
+    pairs: number;
+    error_before_bpm: number;
+    error_after_bpm: number;
+    calibrated_at_ms: number;
+}
+
+export interface FfiPanicSession {
+    pattern_id: string;
+    binaural_state: FfiBrainWaveState;
+    binaural: FfiBinauralConfig;
+    duration_sec: number;
+}
+
+export interface FfiSleepConfig {
+    wind_down_min: number;
+    max_exhale_scale: number;
+    idle_timeout_min: number;
+}
+
+export interface FfiSessionGoals {
+    target_coherence?: number | null;
+    target_minutes?: number | null;
+    target_cycles?: number | null;
+}
+
+export interface FfiBinauralPreset {
+    id: string;
+    builtin: boolean;
+    config: FfiBinauralConfig;
+}
+
+export type FfiOutcomeMetric = 'AvgResonance' | 'AvgHeartRate' | 'DurationSec' | 'CyclesCompleted';
+
+export interface FfiBinauralStateStats {
+    state: FfiBrainWaveState;
+    listening_sec: number;
+    sessions: number;
+    mean_outcome: number | null;
+    difference: number | null;
+    effect_size: number | null;
+}
+
+export interface FfiBinauralStats {
+    metric: FfiOutcomeMetric;
+    states: FfiBinauralStateStats[];
+    silent_sessions: number;
+    silent_mean_outcome: number | null;
+}
+
 export interface FfiSafetyStatus {
     is_locked: boolean;
     trauma_count: number;
     tempo_bounds: number[];
     hr_bounds: number[];
+    cooldown_remaining_sec: number;
+    /** Critical violations not yet acknowledged */
+    unacknowledged_critical: number;
 }
 
 export interface FfiFrame {
@@ -88,6 +423,48 @@ export interface FfiFrame {
     signal_quality: number;
     belief: FfiBeliefState;
     resonance: FfiResonance;
+    /** Breaths per minute the pacer is asking for (null during previews) */
+    prescribed_rate_bpm: number | null;
+    /** Breaths per minute measured from the respiration signal */
+    detected_rate_bpm: number | null;
+    /** Whole seconds left in the 3-2-1 countdown while in 'Prepare' */
+    countdown: number | null;
+    hrv: FfiHrvMetrics | null;
+    hrv_spectrum: FfiHrvSpectrum | null;
+    /** Breathing rate estimated from the camera pulse waveform */
+    respiratory_rate_bpm: number | null;
+    /** Camera heart rate before the Kalman filter (null for wearable HR) */
+    raw_heart_rate: number | null;
+    /** Kalman filter confidence 0-1 in heart_rate (null for wearable HR) */
+    hr_filter_confidence: number | null;
+    /** Camera pulse SNR behind signal_quality (null for wearable HR) */
+    signal_snr_db: number | null;
+    /** Camera pulse strength, percent of skin brightness (null for wearable HR) */
+    perfusion_index: number | null;
+}
+
+export interface FfiHrvMetrics {
+    rmssd_ms: number;
+    sdnn_ms: number;
+    /** Percentage of successive differences above 50 ms */
+    pnn50: number;
+    beats: number;
+}
+
+export interface FfiBreathRateBand {
+    /** Breathing faster than target + this triggers a coaching cue */
+    above_bpm: number;
+    /** Breathing slower than target - this triggers a coaching cue */
+    below_bpm: number;
+}
+
+export interface FfiHrvSpectrum {
+    lf_power_ms2: number;
+    hf_power_ms2: number;
+    lf_hf_ratio: number;
+    peak_frequency_hz: number;
+    /** Share (0-1) of total power around the spectral peak */
+    coherence: number;
 }
 
 export interface FfiSessionStats {
@@ -97,6 +474,8 @@ export interface FfiSessionStats {
     avg_heart_rate: number | null;
     final_belief: FfiBeliefState;
     avg_resonance: number;
+    hrv: FfiHrvMetrics | null;
+    hrv_spectrum: FfiHrvSpectrum | null;
 }
 
 export interface FfiRuntimeState {
@@ -110,6 +489,57 @@ export interface FfiRuntimeState {
     belief: FfiBeliefState;
     resonance: FfiResonance;
     safety: FfiSafetyStatus;
+    preparation: FfiPreparationStatus | null;
+    follow_me: FfiFollowMeStatus | null;
+    sequence: FfiSequenceProgress | null;
+    /** Warm-up / main / cool-down stage of the running session */
+    stage: FfiStageStatus | null;
+}
+
+export type FfiSessionStage = 'WarmUp' | 'Main' | 'CoolDown';
+
+export interface FfiSessionStagesConfig {
+    /** Time over which the pacer eases into the pattern's cadence (0 skips) */
+    warm_up_sec: number;
+    /** Tempo scale the warm-up starts from (> 1 breathes slower) */
+    warm_up_tempo: number;
+    /** Unpaced natural breathing observed after the paced part (0 skips) */
+    cool_down_sec: number;
+}
+
+export interface FfiStageStatus {
+    stage: FfiSessionStage;
+    elapsed_sec: number;
+    /** null for the main stage, which ends with the session */
+    duration_sec: number | null;
+}
+
+export interface FfiSequenceSegment {
+    pattern_id: string;
+    duration_sec: number;
+}
+
+export interface FfiSequenceProgress {
+    segment_index: number;
+    segment_count: number;
+    pattern_id: string;
+    segment_elapsed_sec: number;
+    segment_duration_sec: number;
+    total_elapsed_sec: number;
+    total_duration_sec: number;
+}
+
+export interface FfiFollowMeStatus {
+    stage: 'Locking' | 'Guiding' | 'Holding' | 'Reached';
+    detected_rate_bpm: number | null;
+    pacer_rate_bpm: number | null;
+    target_rate_bpm: number;
+}
+
+export interface FfiPreparationStatus {
+    remaining_sec: number;
+    signal_quality: number;
+    quality_ok: boolean;
 }
 
 // ============================================================================
@@ -121,7 +551,9 @@ function ffiPhaseToBreathPhase(phase: FfiPhase): BreathPhase {
         'Inhale': 'inhale',
         'HoldIn': 'holdIn',
         'Exhale': 'exhale',
-        'HoldOut': 'holdOut'
+        'HoldOut': 'holdOut',
+        // The countdown leads into the first inhale; FfiFrame.countdown carries the count
+        'Prepare': 'inhale'
     };
     return map[phase];
 }
@@ -164,7 +596,7 @@ function ffiBeliefToBeliefState(ffi: FfiBeliefState, resonance: FfiResonance): B
 // RUNTIME STATE (TypeScript equivalent of RuntimeState)
 // ============================================================================
 
-export type RuntimeStatus = 'IDLE' | 'RUNNING' | 'PAUSED' | 'HALTED' | 'SAFETY_LOCK';
+export type RuntimeStatus = 'IDLE' | 'PREPARING' | 'RUNNING' | 'PAUSED' | 'HALTED' | 'SAFETY_LOCK';
 export type AIConnectionStatus = 'connecting' | 'connected' | 'thinking' | 'speaking' | 'disconnected';
 
 export interface RuntimeState {
@@ -222,12 +654,20 @@ class MockZenOneRuntime {
         probabilities: [0.4, 0.1, 0.2, 0.2, 0.1],
         confidence: 0.5,
         mode: 'Calm',
-        uncertainty: 0.5
+        uncertainty: 0.5,
+        modes: [
+            { mode_id: 'calm', probability: 0.4 },
+            { mode_id: 'stress', probability: 0.1 },
+            { mode_id: 'focus', probability: 0.2 },
+            { mode_id: 'sleepy', probability: 0.2 },
+            { mode_id: 'energize', probability: 0.1 }
+        ]
     };
     private lastResonance: FfiResonance = {
         coherence_score: 0.5,
         phase_locking: 0.5,
-        rhythm_alignment: 0.5
+        rhythm_alignment: 0.5,
+        rsa_amplitude_bpm: 0
     };
 
     get_patterns(): any[] {
@@ -343,7 +783,10 @@ class MockZenOneRuntime {
             heart_rate: null,
             signal_quality: 0,
             belief: this.lastBelief,
-            resonance: this.lastResonance
+            resonance: this.lastResonance,
+            prescribed_rate_bpm: null,
+            detected_rate_bpm: null,
+            countdown: null
         };
     }
 
@@ -362,8 +805,14 @@ class MockZenOneRuntime {
                 is_locked: this.safetyLocked,
                 trauma_count: 0,
                 tempo_bounds: [0.8, 1.4],
-                hr_bounds: [30, 220]
-            }
+                hr_bounds: [30, 220],
+                cooldown_remaining_sec: 0,
+                unacknowledged_critical: 0
+            },
+            preparation: null,
+            follow_me: null,
+            sequence: null,
+            stage: null
         };
     }
 
@@ -376,7 +825,9 @@ class MockZenOneRuntime {
             is_locked: this.safetyLocked,
             trauma_count: 0,
             tempo_bounds: [0.8, 1.4],
-            hr_bounds: [30, 220]
+            hr_bounds: [30, 220],
+            cooldown_remaining_sec: 0,
+            unacknowledged_critical: 0
         };
     }
 
@@ -620,7 +1071,8 @@ export class RustKernelBridge {
 
             case 'RESET_SAFETY_LOCK':
                 if (this._useTauri && this.tauriRuntime) {
-                    this.tauriRuntime.reset_safety_lock().then(() => {
+                    const reason = event.patternId ? `Reset for pattern ${event.patternId}` : 'Manual reset';
+                    this.tauriRuntime.reset_safety_lock(reason).then(() => {
                         this.refreshFromRust('reset_safety_lock');
                     }).catch(err => {
                         console.warn('[RustKernelBridge] Tauri reset_safety_lock failed:', err);
@@ -843,6 +1295,7 @@ export class RustKernelBridge {
 
         const statusMap: Record<FfiRuntimeStatus, RuntimeStatus> = {
             'Idle': 'IDLE',
+            'Preparing': 'PREPARING',
             'Running': 'RUNNING',
             'Paused': 'PAUSED',
             'SafetyLock': 'SAFETY_LOCK'
@@ -897,6 +1350,7 @@ export class RustKernelBridge {
 
         const statusMap: Record<FfiRuntimeStatus, RuntimeStatus> = {
             'Idle': 'IDLE',
+            'Preparing': 'PREPARING',
             'Running': 'RUNNING',
             'Paused': 'PAUSED',
             'SafetyLock': 'SAFETY_LOCK'
@@ -18,15 +18,80 @@ import type {
     FfiFrame,
     FfiSessionStats,
     FfiRuntimeState,
+    FfiRuntimeStatus,
     FfiBeliefState,
+    FfiBeliefModeSpec,
     FfiSafetyStatus,
     FfiBrainWaveState,
     FfiBinauralConfig,
+    FfiBinauralStats,
+    FfiBinauralPreset,
+    FfiSessionGoals,
+    FfiSleepConfig,
+    FfiPanicSession,
+    FfiOutcomeMetric,
     FfiPatternRecommendation,
+    FfiCyclePrescriptionConfig,
+    FfiOnboardingAnswers,
+    FfiDeviceCapabilities,
+    FfiPipelineProfile,
+    FfiBenchmarkResult,
+    FfiBreathingBaseline,
+    FfiSequenceSegment,
+    FfiSequenceProgress,
+    FfiSessionStagesConfig,
+    FfiBreathRateBand,
+    FfiNoiseProfile,
+    FfiBreathPattern,
+    FfiPhase,
+    FfiPhaseOverride,
+    FfiStreakConfig,
+    FfiStreakStatus,
+    FfiLocalTime,
+    FfiRollupPeriod,
+    FfiMetricRollup,
+    FfiRetentionPolicy,
+    FfiEscalationConfig,
+    FfiFeatureFlag,
+    FfiFeatureFlagState,
+    FfiPreflightInput,
+    FfiPreflightReport,
+    FfiSerialPacerStatus,
+    FfiSignatureReport,
+    FfiCalibrationReference,
+    FfiRppgCalibration,
+    FfiRppgMethod,
+    FfiHrSourceKind,
+    FfiHrSimulatorConfig,
+    FfiRppgConfig,
+    FfiPpgSpectrum,
+    FfiPpgTracePoint,
+    FfiPixelFormat,
+    FfiPlaneLayout,
+    FfiRoi,
+    FfiRgbSample,
 } from './RustKernelBridge';
 
 let invokeFunc: ((cmd: string, args?: Record<string, unknown>) => Promise<unknown>) | null = null;
 
+/**
+ * Command API version this frontend speaks (must match src-tauri API_VERSION range)
+ */
+export const COMMAND_API_VERSION = 2;
+
+export interface ApiDeprecation {
+    command: string;
+    changedIn: number;
+    migration: string;
+}
+
+export interface ApiHandshake {
+    apiVersion: number;
+    minSupportedVersion: number;
+    clientVersion: number;
+    deprecations: ApiDeprecation[];
+}
+
 /**
  * Initialize the Tauri invoke function.
  * Must be called before using TauriZenOneRuntime.
@@ -40,15 +105,31 @@ export async function initTauriInvoke(): Promise<boolean> {
 
     try {
         const tauriCore = await import('@tauri-apps/api/core');
+        // Refuse to talk to a core whose command API we can't serve
+        const handshake = await tauriCore.invoke('api_handshake', {
+            clientVersion: COMMAND_API_VERSION,
+        }) as ApiHandshake;
+        for (const d of handshake.deprecations) {
+            console.warn(`[TauriRuntime] '${d.command}' changed in API v${d.changedIn}: ${d.migration}`);
+        }
         invokeFunc = tauriCore.invoke;
-        console.log('[TauriRuntime] Tauri invoke initialized');
+        console.log(`[TauriRuntime] Tauri invoke initialized (API v${handshake.clientVersion}, core v${handshake.apiVersion})`);
         return true;
     } catch (e) {
-        console.warn('[TauriRuntime] Failed to import @tauri-apps/api/core:', e);
+        console.warn('[TauriRuntime] Failed to initialize Tauri invoke:', e);
         return false;
     }
 }
 
+/**
+ * Listen for a Tauri event pushed by the runtime; resolves to an unsubscribe function
+ */
+async function subscribe<T>(event: string, handler: (payload: T) => void): Promise<() => void> {
+    if (!invokeFunc) throw new Error('Tauri not initialized');
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<T>(event, (e) => handler(e.payload));
+}
+
 /**
  * Check if Tauri runtime is available
  */
@@ -72,6 +153,85 @@ export class TauriZenOneRuntime {
         return invokeFunc('get_patterns') as Promise<unknown[]>;
     }
 
+    /**
+     * Register a user-defined pattern alongside the builtins
+     */
+    async create_pattern(pattern: FfiBreathPattern): Promise<FfiBreathPattern> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('create_pattern', { pattern }) as Promise<FfiBreathPattern>;
+    }
+
+    async delete_pattern(patternId: string): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('delete_pattern', { patternId });
+    }
+
+    /**
+     * Serialize a pattern (with the user's overrides) into a shareable JSON document
+     */
+    async export_pattern(patternId: string): Promise<string> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('export_pattern', { patternId }) as Promise<string>;
+    }
+
+    /**
+     * Validate and register a pattern exported on another device
+     */
+    async import_pattern(json: string): Promise<FfiBreathPattern> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('import_pattern', { json }) as Promise<FfiBreathPattern>;
+    }
+
+    /**
+     * Check a pattern's timings against physiological limits (warnings don't block loading)
+     */
+    async validate_pattern(pattern: FfiBreathPattern): Promise<FfiPatternIssue[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('validate_pattern', { pattern }) as Promise<FfiPatternIssue[]>;
+    }
+
+    /**
+     * Dry-run loading a pattern; show the returned safety evaluation before confirming
+     */
+    async propose_pattern_change(patternId: string): Promise<FfiPatternChangeProposal> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('propose_pattern_change', { patternId }) as Promise<FfiPatternChangeProposal>;
+    }
+
+    async confirm_pattern_change(proposalId: number): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('confirm_pattern_change', { proposalId });
+    }
+
+    /**
+     * Blend two patterns' timings (t = 0 → patternA, t = 1 → patternB)
+     */
+    async blend_patterns(patternA: string, patternB: string, t: number): Promise<unknown> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('blend_patterns', { patternA, patternB, t });
+    }
+
+    /**
+     * Change one phase of the current pattern, kept as a per-pattern user adjustment
+     */
+    async override_phase_duration(phase: FfiPhase, seconds: number): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('override_phase_duration', { phase, seconds });
+    }
+
+    /**
+     * Restore the current pattern's own timings
+     */
+    async reset_phase_overrides(): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('reset_phase_overrides');
+    }
+
+    async get_phase_overrides(): Promise<FfiPhaseOverride[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_phase_overrides') as Promise<FfiPhaseOverride[]>;
+    }
+
     /**
      * Load a pattern by ID
      */
@@ -82,6 +242,27 @@ export class TauriZenOneRuntime {
         return result as boolean;
     }
 
+    /**
+     * Queue an ordered sequence of patterns (e.g. box → coherence → 4-7-8) for the next session
+     */
+    async load_sequence(segments: FfiSequenceSegment[]): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('load_sequence', { segments });
+    }
+
+    async get_sequence_progress(): Promise<FfiSequenceProgress | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_sequence_progress') as Promise<FfiSequenceProgress | null>;
+    }
+
+    /**
+     * Morph mid-session pattern loads over this many cycles (0 switches at once)
+     */
+    async set_pattern_transition_cycles(cycles: number): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_pattern_transition_cycles', { cycles });
+    }
+
     /**
      * Get current pattern ID (sync - uses cache)
      */
@@ -89,6 +270,14 @@ export class TauriZenOneRuntime {
         return this.cachedPatternId;
     }
 
+    /**
+     * Readiness checklist (camera, safety, storage, audio, battery) to show before start_session
+     */
+    async preflight_check(input: FfiPreflightInput): Promise<FfiPreflightReport> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('preflight_check', { input }) as Promise<FfiPreflightReport>;
+    }
+
     /**
      * Start a breathing session
      */
@@ -97,6 +286,114 @@ export class TauriZenOneRuntime {
         await invokeFunc('start_session');
     }
 
+    /**
+     * Start a breathing session with goals tracked live and kept in its stats
+     */
+    async start_session_with_goals(goals: FfiSessionGoals): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('start_session_with_goals', { goals });
+    }
+
+    /**
+     * Start a sleep wind-down session (ends silently once the user is idle)
+     */
+    async start_sleep_session(config: FfiSleepConfig): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('start_sleep_session', { config });
+    }
+
+    /**
+     * One-step acute-anxiety session; returns the binaural config to play with it
+     */
+    async start_panic_session(): Promise<FfiPanicSession> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        const session = await invokeFunc('start_panic_session') as FfiPanicSession;
+        this.cachedPatternId = session.pattern_id;
+        return session;
+    }
+
+    /**
+     * Report user interaction or device motion during a sleep session
+     */
+    async report_activity(): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('report_activity');
+    }
+
+    /**
+     * Declare a warm-up ramp and a natural-breathing cool-down for new sessions
+     */
+    async set_session_stages(config: FfiSessionStagesConfig): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_session_stages', { config });
+    }
+
+    /**
+     * Stop pacing and run the declared cool-down before the session completes
+     */
+    async begin_cool_down(): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('begin_cool_down');
+    }
+
+    /**
+     * End sessions after the pattern's recommended cycles (SessionCompleted event carries the stats)
+     */
+    async set_auto_complete(enabled: boolean): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_auto_complete', { enabled });
+    }
+
+    /**
+     * Observe natural breathing without pacing; the result arrives as a BaselineReady event
+     */
+    async start_baseline_observation(duration_sec: number): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('start_baseline_observation', { durationSec: duration_sec });
+    }
+
+    /**
+     * End the observation early and get the baseline measured so far
+     */
+    async stop_baseline_observation(): Promise<FfiBreathingBaseline> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('stop_baseline_observation') as Promise<FfiBreathingBaseline>;
+    }
+
+    /**
+     * Set how far the measured breathing rate may drift from target before coaching
+     */
+    async set_breath_rate_band(band: FfiBreathRateBand): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_breath_rate_band', { band });
+    }
+
+    async get_breath_rate_band(): Promise<FfiBreathRateBand> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_breath_rate_band') as Promise<FfiBreathRateBand>;
+    }
+
+    /**
+     * Measure ambient microphone noise; the result arrives as a NoiseProfileReady event
+     */
+    async start_noise_profiling(duration_sec: number): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('start_noise_profiling', { durationSec: duration_sec });
+    }
+
+    async get_noise_profile(): Promise<FfiNoiseProfile | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_noise_profile') as Promise<FfiNoiseProfile | null>;
+    }
+
+    /**
+     * Enable microphone breath detection; rejects when the room was profiled as too noisy
+     */
+    async set_audio_breath_detection(enabled: boolean): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_audio_breath_detection', { enabled });
+    }
+
     /**
      * Stop session and get stats
      */
@@ -145,6 +442,199 @@ export class TauriZenOneRuntime {
         return invokeFunc('process_frame', { r, g, b, timestampUs: timestamp_us }) as Promise<FfiFrame>;
     }
 
+    /**
+     * Submit buffered camera samples in one call (e.g. after a background stall)
+     */
+    async process_frames_batch(samples: FfiRgbSample[]): Promise<FfiFrame> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('process_frames_batch', { samples }) as Promise<FfiFrame>;
+    }
+
+    /**
+     * Process a raw camera frame; skin pixels inside the ROI are averaged in Rust
+     */
+    async process_frame_buffer(
+        bytes: Uint8Array,
+        width: number,
+        height: number,
+        format: FfiPixelFormat,
+        roi: FfiRoi | null,
+        timestamp_us: number,
+        planes: FfiPlaneLayout[] | null = null,
+    ): Promise<FfiFrame> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('process_frame_buffer', {
+            bytes: Array.from(bytes),
+            width,
+            height,
+            format,
+            planes,
+            roi,
+            timestampUs: timestamp_us,
+        }) as Promise<FfiFrame>;
+    }
+
+    /**
+     * Keep the rPPG region on the face (frame buffers without an ROI) by skin color
+     */
+    async set_skin_roi_tracking(enabled: boolean): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_skin_roi_tracking', { enabled });
+    }
+
+    /**
+     * Pick rPPG window, publish rate and feature toggles for this device
+     */
+    async configure_for_device(capabilities: FfiDeviceCapabilities): Promise<FfiPipelineProfile> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('configure_for_device', { capabilities }) as Promise<FfiPipelineProfile>;
+    }
+
+    /**
+     * Switch the rPPG algorithm; heart rate restarts while the new method warms up
+     */
+    async set_rppg_method(method: FfiRppgMethod): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_rppg_method', { method });
+    }
+
+    async get_rppg_method(): Promise<FfiRppgMethod> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_rppg_method') as Promise<FfiRppgMethod>;
+    }
+
+    /**
+     * Choose which sensor supplies heart rate; Auto prefers a streaming strap over the camera
+     */
+    async set_hr_source(kind: FfiHrSourceKind): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_hr_source', { kind });
+    }
+
+    async get_hr_source(): Promise<FfiHrSourceKind> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_hr_source') as Promise<FfiHrSourceKind>;
+    }
+
+    /**
+     * Feed a pulse counted by hand, for the Manual source
+     */
+    async ingest_manual_hr(bpm: number, timestampUs: number): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('ingest_manual_hr', { bpm, timestampUs });
+    }
+
+    /**
+     * Generate heart rate from the pacer instead of a sensor (demos, screenshots,
+     * end-to-end tests); select the 'Simulated' source to use it
+     */
+    async start_hr_simulator(config: FfiHrSimulatorConfig): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('start_hr_simulator', { config });
+    }
+
+    async stop_hr_simulator(): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('stop_hr_simulator');
+    }
+
+    /**
+     * Set the rPPG window and frame rate; with adaptation the rate follows the camera
+     */
+    async set_rppg_config(config: FfiRppgConfig): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_rppg_config', { config });
+    }
+
+    async get_rppg_config(): Promise<FfiRppgConfig> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_rppg_config') as Promise<FfiRppgConfig>;
+    }
+
+    /**
+     * Latest camera pulse spectrum, to see why heart rate estimation struggles
+     */
+    async get_ppg_spectrum(): Promise<FfiPpgSpectrum | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_ppg_spectrum') as Promise<FfiPpgSpectrum | null>;
+    }
+
+    /**
+     * Stream the raw camera signal as 'ppg-trace' events, averaging every
+     * `decimation` samples into one point
+     */
+    async set_ppg_trace(enabled: boolean, decimation: number): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_ppg_trace', { enabled, decimation });
+    }
+
+    async drain_ppg_trace(): Promise<FfiPpgTracePoint[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('drain_ppg_trace') as Promise<FfiPpgTracePoint[]>;
+    }
+
+    /**
+     * Subscribe to trace batches; resolves to an unsubscribe function
+     */
+    async on_ppg_trace(callback: (points: FfiPpgTracePoint[]) => void): Promise<() => void> {
+        const { listen } = await import('@tauri-apps/api/event');
+        return listen<FfiPpgTracePoint[]>('ppg-trace', (event) => callback(event.payload));
+    }
+
+    /**
+     * Replay recorded sessions (the built-in reference dataset when no
+     * directory is given) through the pipeline under each configuration and
+     * report HR accuracy, latency and CPU cost
+     */
+    async run_pipeline_benchmark(recordingsDir: string | null, configs: FfiDeviceCapabilities[]): Promise<FfiBenchmarkResult[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('run_pipeline_benchmark', { recordingsDir, configs }) as Promise<FfiBenchmarkResult[]>;
+    }
+
+    /**
+     * Calibrate camera heart rate on this device against a strap or a hand count
+     */
+    async start_rppg_calibration(deviceId: string, reference: FfiCalibrationReference): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('start_rppg_calibration', { deviceId, reference });
+    }
+
+    async add_manual_hr_reference(bpm: number, durationSec: number): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('add_manual_hr_reference', { bpm, durationSec });
+    }
+
+    async finish_rppg_calibration(): Promise<FfiRppgCalibration> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('finish_rppg_calibration') as Promise<FfiRppgCalibration>;
+    }
+
+    /**
+     * Apply the saved calibration for this device (null if it was never calibrated)
+     */
+    async use_rppg_calibration(deviceId: string): Promise<FfiRppgCalibration | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('use_rppg_calibration', { deviceId }) as Promise<FfiRppgCalibration | null>;
+    }
+
+    /**
+     * Stream the pacer as a line protocol to a serial/USB port for DIY hardware
+     */
+    async start_serial_pacer(port: string): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('start_serial_pacer', { port });
+    }
+
+    async stop_serial_pacer(): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('stop_serial_pacer');
+    }
+
+    async get_serial_pacer_status(): Promise<FfiSerialPacerStatus | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_serial_pacer_status') as Promise<FfiSerialPacerStatus | null>;
+    }
+
     /**
      * Get full runtime state snapshot
      */
@@ -155,6 +645,17 @@ export class TauriZenOneRuntime {
         return state;
     }
 
+    /**
+     * Fields changed since a diff's state_sequence (0 for all), merged into
+     * the cached state; for consumers that poll over slow links
+     */
+    async getStateDelta(sinceSequence: number): Promise<FfiRuntimeStateDiff> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        const diff = await invokeFunc('get_state_delta', { sinceSequence }) as FfiRuntimeStateDiff;
+        this.applyStateDiff(diff);
+        return diff;
+    }
+
     /**
      * Sync getter for cached state (for UI that needs immediate access)
      */
@@ -179,11 +680,11 @@ export class TauriZenOneRuntime {
     }
 
     /**
-     * Reset safety lock
+     * Reset safety lock (rejected while the post-violation cooldown runs)
      */
-    async reset_safety_lock(): Promise<void> {
+    async reset_safety_lock(reason: string): Promise<void> {
         if (!invokeFunc) throw new Error('Tauri not initialized');
-        await invokeFunc('reset_safety_lock');
+        await invokeFunc('reset_safety_lock', { reason });
     }
 
     // =========================================================================
@@ -199,6 +700,27 @@ export class TauriZenOneRuntime {
         return invokeFunc('get_belief') as Promise<FfiBeliefState>;
     }
 
+    /**
+     * List belief modes: the five built-ins, then custom research modes.
+     */
+    async list_belief_modes(): Promise<FfiBeliefModeSpec[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('list_belief_modes') as Promise<FfiBeliefModeSpec[]>;
+    }
+
+    /**
+     * Register or replace a custom belief mode (requires the ResearchMode flag).
+     */
+    async register_belief_mode(spec: FfiBeliefModeSpec): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('register_belief_mode', { spec });
+    }
+
+    async remove_belief_mode(id: string): Promise<boolean> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('remove_belief_mode', { id }) as Promise<boolean>;
+    }
+
     /**
      * Get safety status (lock state, bounds, trauma count).
      */
@@ -211,18 +733,16 @@ export class TauriZenOneRuntime {
      * Update context for adaptive recommendations.
      * Call this periodically (e.g., on session start) to help the Engine
      * adapt its recommendations based on:
-     * - Time of day (circadian rhythm)
+     * - Time of day (circadian rhythm), read from the core clock in the user's time zone
      * - Device charging state (proxy for activity)
      * - Recent session count (fatigue/overuse detection)
      */
     async update_context(
-        localHour: number,
         isCharging: boolean,
         recentSessions: number
     ): Promise<void> {
         if (!invokeFunc) throw new Error('Tauri not initialized');
         await invokeFunc('update_context', {
-            localHour: Math.floor(localHour) % 24,
             isCharging,
             recentSessions: Math.min(recentSessions, 65535), // u16 max
         });
@@ -241,6 +761,15 @@ export class TauriZenOneRuntime {
         return invokeFunc('check_safety_event', { event }) as Promise<FfiSafetyCheckResult>;
     }
 
+    /**
+     * Dry-run an event against the runtime's safety specs without recording it.
+     * Pass a hypothetical state (e.g. with a proposed tempo_scale) to pre-validate.
+     */
+    async evaluateSafetySpecs(event: FfiKernelEvent, state?: FfiRuntimeState): Promise<FfiSafetyCheckResult> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('evaluate_safety_specs', { event, state: state ?? null }) as Promise<FfiSafetyCheckResult>;
+    }
+
     /**
      * Get all recorded safety violations.
      */
@@ -265,6 +794,30 @@ export class TauriZenOneRuntime {
         await invokeFunc('clear_safety_violations');
     }
 
+    /**
+     * Violations recorded by the runtime's own safety checks.
+     */
+    async getRuntimeSafetyViolations(): Promise<FfiSafetyViolation[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_runtime_safety_violations') as Promise<FfiSafetyViolation[]>;
+    }
+
+    /**
+     * Re-verify all safety specs over the whole logged event history.
+     */
+    async verifySafetyTrace(): Promise<FfiTraceReport> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('verify_safety_trace') as Promise<FfiTraceReport>;
+    }
+
+    /**
+     * Acknowledge a single runtime violation after review.
+     */
+    async ackSafetyViolation(id: number, note?: string): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('ack_safety_violation', { id, note: note ?? null });
+    }
+
     /**
      * Check if system is in safe state.
      */
@@ -273,6 +826,20 @@ export class TauriZenOneRuntime {
         return invokeFunc('is_system_safe') as Promise<boolean>;
     }
 
+    /**
+     * Configure when safety alerts escalate to the trusted contact.
+     * Escalations arrive as Escalation events while EmergencyContact consent is granted.
+     */
+    async setEscalationConfig(config: FfiEscalationConfig): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_escalation_config', { config });
+    }
+
+    async getEscalationConfig(): Promise<FfiEscalationConfig> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_escalation_config') as Promise<FfiEscalationConfig>;
+    }
+
     // =========================================================================
     // PID CONTROLLER COMMANDS
     // =========================================================================
@@ -296,9 +863,9 @@ export class TauriZenOneRuntime {
     // PATTERN RECOMMENDER COMMANDS
     // =========================================================================
 
-    async recommendPatterns(localHour: number, limit: number): Promise<FfiPatternRecommendation[]> {
+    async recommendPatterns(limit: number): Promise<FfiPatternRecommendation[]> {
         if (!invokeFunc) throw new Error('Tauri not initialized');
-        return invokeFunc('recommend_patterns', { localHour, limit }) as Promise<FfiPatternRecommendation[]>;
+        return invokeFunc('recommend_patterns', { limit }) as Promise<FfiPatternRecommendation[]>;
     }
 
     async recordPatternUsage(patternId: string): Promise<void> {
@@ -311,6 +878,33 @@ export class TauriZenOneRuntime {
         await invokeFunc('clear_pattern_history');
     }
 
+    async setCyclePrescription(config: FfiCyclePrescriptionConfig): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_cycle_prescription', { config });
+    }
+
+    async getCyclePrescription(): Promise<FfiCyclePrescriptionConfig> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_cycle_prescription') as Promise<FfiCyclePrescriptionConfig>;
+    }
+
+    /** Store onboarding answers (null clears them) to seed first recommendations. */
+    async setOnboardingAnswers(answers: FfiOnboardingAnswers | null): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_onboarding_answers', { answers });
+    }
+
+    async getOnboardingAnswers(): Promise<FfiOnboardingAnswers | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_onboarding_answers') as Promise<FfiOnboardingAnswers | null>;
+    }
+
+    /** Default pattern for a new user, picked from their onboarding answers. */
+    async getStarterPattern(): Promise<string | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_starter_pattern') as Promise<string | null>;
+    }
+
     // =========================================================================
     // BINAURAL BEATS COMMANDS
     // =========================================================================
@@ -325,6 +919,195 @@ export class TauriZenOneRuntime {
         return invokeFunc('get_binaural_recommendation', { arousalTarget }) as Promise<FfiBrainWaveState>;
     }
 
+    async listBinauralPresets(): Promise<FfiBinauralPreset[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('list_binaural_presets') as Promise<FfiBinauralPreset[]>;
+    }
+
+    async defineBinauralPreset(id: string, config: FfiBinauralConfig): Promise<FfiBinauralPreset> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('define_binaural_preset', { id, config }) as Promise<FfiBinauralPreset>;
+    }
+
+    async removeBinauralPreset(id: string): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('remove_binaural_preset', { id });
+    }
+
+    async setBinauralState(state: FfiBrainWaveState | null): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_binaural_state', { brainWave: state });
+    }
+
+    async getBinauralStats(metric: FfiOutcomeMetric = 'AvgResonance'): Promise<FfiBinauralStats> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_binaural_stats', { metric }) as Promise<FfiBinauralStats>;
+    }
+
+    // =========================================================================
+    // STREAK COMMANDS
+    // =========================================================================
+
+    async setStreakConfig(config: FfiStreakConfig): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_streak_config', { config });
+    }
+
+    async getStreakConfig(): Promise<FfiStreakConfig> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_streak_config') as Promise<FfiStreakConfig>;
+    }
+
+    async getStreakInfo(): Promise<FfiStreakStatus> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_streak_info') as Promise<FfiStreakStatus>;
+    }
+
+    /** @deprecated Use getStreakInfo */
+    async getStreak(): Promise<FfiStreakStatus> {
+        return this.getStreakInfo();
+    }
+
+    /** IANA zone (e.g. 'Europe/Berlin') for day boundaries; null follows the device */
+    async setTimeZone(timeZone: string | null): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_time_zone', { timeZone });
+    }
+
+    async getTimeZone(): Promise<string | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_time_zone') as Promise<string | null>;
+    }
+
+    /** Use `hour` for recommendations instead of the webview's clock */
+    async getLocalTime(): Promise<FfiLocalTime> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_local_time') as Promise<FfiLocalTime>;
+    }
+
+    /**
+     * Day/week/month summaries for trend charts; dates are YYYY-MM-DD (local)
+     */
+    async getRollups(period: FfiRollupPeriod, startDate?: string, endDate?: string): Promise<FfiMetricRollup[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_rollups', {
+            period,
+            startDate: startDate ?? null,
+            endDate: endDate ?? null,
+        }) as Promise<FfiMetricRollup[]>;
+    }
+
+    /**
+     * Set how long raw sessions are kept (min 30 days); returns the number pruned
+     */
+    async setRetentionPolicy(policy: FfiRetentionPolicy): Promise<number> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('set_retention_policy', { policy }) as Promise<number>;
+    }
+
+    async getRetentionPolicy(): Promise<FfiRetentionPolicy> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_retention_policy') as Promise<FfiRetentionPolicy>;
+    }
+
+    /**
+     * Unlock the encrypted session store (key created on first use) so history is persisted;
+     * resolves to the number of stored sessions loaded
+     */
+    async unlockSessionStore(passphrase: string): Promise<number> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('unlock_session_store', { passphrase }) as Promise<number>;
+    }
+
+    async isSessionStoreUnlocked(): Promise<boolean> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('is_session_store_unlocked') as Promise<boolean>;
+    }
+
+    /**
+     * Unlock the device signing key (created on first use) so completed sessions are signed;
+     * resolves to the hex public key to share with a research or wellness program
+     */
+    async unlockSigningKey(passphrase: string): Promise<string> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('unlock_signing_key', { passphrase }) as Promise<string>;
+    }
+
+    async getDevicePublicKey(): Promise<string | null> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_device_public_key') as Promise<string | null>;
+    }
+
+    /**
+     * Check an exported history's session signatures (defaults to this device's key)
+     */
+    async verifySessionExport(json: string, publicKey?: string): Promise<FfiSignatureReport> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('verify_session_export', {
+            json,
+            publicKey: publicKey ?? null,
+        }) as Promise<FfiSignatureReport>;
+    }
+
+    // =========================================================================
+    // FEATURE FLAG COMMANDS
+    // =========================================================================
+
+    async setFeatureFlag(flag: FfiFeatureFlag, enabled: boolean): Promise<void> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        await invokeFunc('set_feature_flag', { flag, enabled });
+    }
+
+    async isFeatureEnabled(flag: FfiFeatureFlag): Promise<boolean> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('is_feature_enabled', { flag }) as Promise<boolean>;
+    }
+
+    async getFeatureFlags(): Promise<FfiFeatureFlagState[]> {
+        if (!invokeFunc) throw new Error('Tauri not initialized');
+        return invokeFunc('get_feature_flags') as Promise<FfiFeatureFlagState[]>;
+    }
+
+    // =========================================================================
+    // PUSHED RUNTIME EVENTS (subscribe instead of polling tick/get_state)
+    // =========================================================================
+
+    async onPhaseChanged(handler: (e: PhaseChangedEvent) => void): Promise<() => void> {
+        return subscribe('zenone://phase-changed', handler);
+    }
+
+    async onCycleComplete(handler: (e: CycleCompleteEvent) => void): Promise<() => void> {
+        return subscribe('zenone://cycle-complete', handler);
+    }
+
+    async onStatusChanged(handler: (e: StatusChangedEvent) => void): Promise<() => void> {
+        return subscribe('zenone://status-changed', handler);
+    }
+
+    async onHeartRate(handler: (e: HrUpdateEvent) => void): Promise<() => void> {
+        return subscribe('zenone://hr-update', handler);
+    }
+
+    /**
+     * Receive only the state fields that changed; each diff is also merged
+     * into the cached state (seed it with get_state first)
+     */
+    async onStateChanged(handler: (diff: FfiRuntimeStateDiff) => void): Promise<() => void> {
+        return subscribe<FfiRuntimeStateDiff>('zenone://state-changed', (diff) => {
+            this.applyStateDiff(diff);
+            handler(diff);
+        });
+    }
+
+    private applyStateDiff(diff: FfiRuntimeStateDiff): void {
+        if (!this.cachedState) return;
+        const { cleared, ...changed } = diff;
+        const merged = { ...this.cachedState, ...changed } as FfiRuntimeState & Record<string, unknown>;
+        delete merged.state_sequence;
+        for (const field of cleared) merged[STATE_FIELD_KEYS[field]] = null;
+        this.cachedState = merged;
+    }
+
     // =========================================================================
     // SECURE VAULT COMMANDS
     // =========================================================================
@@ -342,12 +1125,74 @@ export class TauriZenOneRuntime {
     }
 }
 
+// ============================================================================
+// RUNTIME EVENT PAYLOADS
+// ============================================================================
+
+export interface PhaseChangedEvent {
+    phase: FfiPhase;
+    time_to_next_phase_ms: number;
+    timestamp_us: number;
+}
+
+export interface CycleCompleteEvent {
+    cycles_completed: number;
+    /** Adherence of the cycle just finished, if it was measured */
+    adherence: number | null;
+    timestamp_us: number;
+}
+
+export interface StatusChangedEvent {
+    status: FfiRuntimeStatus;
+}
+
+export type FfiStateField = 'Preparation' | 'FollowMe' | 'Sequence' | 'Stage' | 'Sleep';
+
+const STATE_FIELD_KEYS: Record<FfiStateField, string> = {
+    Preparation: 'preparation',
+    FollowMe: 'follow_me',
+    Sequence: 'sequence',
+    Stage: 'stage',
+    Sleep: 'sleep',
+};
+
+/** FfiRuntimeState fields changed since the previous publish; unchanged fields are absent */
+export type FfiRuntimeStateDiff = Partial<FfiRuntimeState> & {
+    /** Optional fields that changed to null */
+    cleared: FfiStateField[];
+    /** Publish this diff brings the state up to; pass it to getStateDelta */
+    state_sequence: number;
+};
+
+export interface HrUpdateEvent {
+    bpm: number;
+    confidence: number;
+    timestamp_us: number;
+}
+
 // ============================================================================
 // FFI SAFETY TYPES
 // ============================================================================
 
 export type FfiViolationSeverity = 'Warning' | 'Error' | 'Critical';
 
+export interface FfiPatternIssue {
+    field: string;
+    message: string;
+    severity: FfiViolationSeverity;
+}
+
+export interface FfiPatternChangeProposal {
+    proposal_id: number;
+    from_pattern_id: string;
+    to_pattern_id: string;
+    safety: FfiSafetyCheckResult;
+    issues: FfiPatternIssue[];
+    /** False when the change would be refused */
+    can_confirm: boolean;
+    expires_in_ms: number;
+}
+
 export type FfiKernelEventType =
     | 'StartSession'
     | 'StopSession'
@@ -358,12 +1203,40 @@ export type FfiKernelEventType =
     | 'PhaseChange'
     | 'CycleComplete';
 
+export type FfiCorrectiveAction =
+    | { ClampTempo: { to: number } }
+    | 'BlockEvent'
+    | 'TriggerHalt';
+
 export interface FfiSafetyViolation {
+    id: number;
     spec_name: string;
     description: string;
     severity: FfiViolationSeverity;
     timestamp_ms: number;
-    corrective_action: string | null;
+    corrective_action: FfiCorrectiveAction | null;
+    acknowledged: FfiViolationAck | null;
+}
+
+export interface FfiSpecReport {
+    spec_name: string;
+    violations: number;
+    first_violation_ms: number | null;
+    last_violation_ms: number | null;
+    satisfied: boolean;
+}
+
+export interface FfiTraceReport {
+    events_checked: number;
+    first_event_ms: number | null;
+    last_event_ms: number | null;
+    specs: FfiSpecReport[];
+    satisfied: boolean;
+}
+
+export interface FfiViolationAck {
+    acknowledged_at_ms: number;
+    note: string | null;
 }
 
 export interface FfiKernelEvent {
@@ -1,5 +1,5 @@
 import { describe, it, expect, vi } from 'vitest';
-import { setAiDucking } from './audio';
+import { setAiDucking, setDuckingConfig, getDuckingConfig } from './audio';
 
 
 // Mock Tone.js
@@ -46,6 +46,8 @@ vi.mock('tone', () => {
     Chorus: vi.fn(() => ({ connect: vi.fn(), start: vi.fn(), dispose: vi.fn() })),
     PolySynth: vi.fn(() => ({ connect: vi.fn(), dispose: vi.fn(), set: vi.fn(), triggerAttackRelease: vi.fn(), volume: { value: 0 } })),
     Player: vi.fn(() => ({ connect: vi.fn(), start: vi.fn(), stop: vi.fn(), dispose: vi.fn(), volume: { value: 0 } })),
+    Oscillator: vi.fn(() => ({ connect: vi.fn(), start: vi.fn(), stop: vi.fn(), dispose: vi.fn(), frequency: { value: 0, rampTo: vi.fn() } })),
+    Merge: vi.fn(() => ({ connect: vi.fn(), dispose: vi.fn() })),
     start: vi.fn(),
     context: { state: 'running', resume: vi.fn() }
   };
@@ -80,3 +82,18 @@ describe('AudioEngine (Audio Ducking)', () => {
     expect(true).toBe(true);
   });
 });
+
+describe('AudioEngine (Cue Ducking Config)', () => {
+  it('should merge partial updates and clamp out-of-range values', () => {
+    setDuckingConfig({ depthDb: 6, releaseSec: 0.8 });
+    const config = getDuckingConfig();
+
+    expect(config.depthDb).toBe(0); // never boosts the mix
+    expect(config.releaseSec).toBe(0.8);
+    expect(config.attackSec).toBeGreaterThan(0);
+
+    setDuckingConfig({ depthDb: -100, attackSec: 0 });
+    expect(getDuckingConfig().depthDb).toBe(-40);
+    expect(getDuckingConfig().attackSec).toBe(0.01);
+  });
+});
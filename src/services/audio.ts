@@ -4,6 +4,7 @@ import { SoundPack, CueType, Language } from '../types';
 import { REAL_ZEN_SAMPLE_URLS, SOUND_PACK_ENGINES } from './audioAssets';
 import { SAFE_SYNTHESIS_PRESETS } from './audio-synthesis-safe';
 import { soundscapeEngine } from './SoundscapeEngine';
+import { binauralEngine } from './BinauralEngine';
 
 // ============================================================================
 // PROFESSIONAL ZEN AUDIO ENGINE V2.2 (With Scheduling)
@@ -108,6 +109,23 @@ const fallbackNotified = new Set<SoundPack>();
 let lastCueKey = '';
 let lastCueTime = 0;
 
+// Ducking of the binaural/soundscape mix under voice cues and chimes
+export type DuckingConfig = {
+    /** Attenuation of the ambience mix while a cue plays (dB, <= 0) */
+    depthDb: number;
+    /** Ramp down time (s) */
+    attackSec: number;
+    /** Ramp back up time (s) */
+    releaseSec: number;
+    /** How long a chime keeps the mix ducked before releasing (s) */
+    chimeHoldSec: number;
+};
+
+const DEFAULT_DUCKING: DuckingConfig = { depthDb: -9, attackSec: 0.2, releaseSec: 0.6, chimeHoldSec: 1.2 };
+let duckingConfig: DuckingConfig = { ...DEFAULT_DUCKING };
+let aiSpeaking = false;
+let voiceSpeaking = false;
+
 // ============================================================================
 // [P0.3 UPGRADE] ADAPTIVE AUDIO MIXING - Device-Aware Processing
 // ============================================================================
@@ -184,6 +202,10 @@ function dbToGain(db: number): number {
     return Math.pow(10, db / 20);
 }
 
+const MASTER_VOLUME_DB = -6;
+/** Floor for the sleep fade; the mix is effectively silent below this */
+const SLEEP_FADE_FLOOR_DB = -60;
+
 function safeDispose(node: any): void {
     try {
         if (node && typeof node.dispose === 'function') {
@@ -227,7 +249,7 @@ function buildMasterChain(): void {
         Tone.getContext().lookAhead = audioProfile.lookAhead;
     } catch { }
 
-    masterBus = new Tone.Channel({ volume: -6 });
+    masterBus = new Tone.Channel({ volume: MASTER_VOLUME_DB });
     cueBus = new Tone.Channel({ volume: 0 });
     ambienceBus = new Tone.Channel({ volume: -6 });
     voiceBus = new Tone.Channel({ volume: 0 });
@@ -277,6 +299,7 @@ function buildMasterChain(): void {
     ambienceDucker.connect(masterEQ);
     voiceBus.connect(masterEQ);
     soundscapeEngine.connect(ambienceBus);
+    binauralEngine.connect(ambienceBus);
 
     // Connect Chain:
     // EQ -> Warmth -> Compressor -> Reverb -> Panner3D -> Spatializer -> Limiter -> Out
@@ -287,27 +310,74 @@ function buildMasterChain(): void {
 }
 
 export function setAiDucking(isSpeaking: boolean) {
+    aiSpeaking = isSpeaking;
     if (!cueDucker || !ambienceDucker) return;
     const now = Tone.now();
     const cueTarget = isSpeaking ? 0.65 : 1.0;
-    const ambienceTarget = isSpeaking ? 0.22 : 1.0;
 
     cueDucker.gain.cancelScheduledValues(now);
     ambienceDucker.gain.cancelScheduledValues(now);
     cueDucker.gain.rampTo(cueTarget, 0.45, now);
-    ambienceDucker.gain.rampTo(ambienceTarget, 0.45, now);
+    ambienceDucker.gain.rampTo(ambienceRestGain(), 0.45, now);
+}
+
+export function setDuckingConfig(config: Partial<DuckingConfig>): void {
+    const next = { ...duckingConfig, ...config };
+    duckingConfig = {
+        depthDb: clamp(next.depthDb, -40, 0),
+        attackSec: clamp(next.attackSec, 0.01, 2),
+        releaseSec: clamp(next.releaseSec, 0.01, 5),
+        chimeHoldSec: clamp(next.chimeHoldSec, 0, 10),
+    };
+}
+
+export function getDuckingConfig(): DuckingConfig {
+    return { ...duckingConfig };
+}
+
+/** Ambience gain when no cue is playing (AI speech keeps its own, deeper duck) */
+function ambienceRestGain(): number {
+    if (aiSpeaking) return 0.22;
+    if (voiceSpeaking) return dbToGain(duckingConfig.depthDb);
+    return 1.0;
 }
 
 function setVoiceDucking(isSpeaking: boolean) {
+    voiceSpeaking = isSpeaking;
     if (!cueDucker || !ambienceDucker) return;
     const now = Tone.now();
     const cueTarget = isSpeaking ? 0.8 : 1.0;
-    const ambienceTarget = isSpeaking ? 0.35 : 1.0;
+    const ramp = isSpeaking ? duckingConfig.attackSec : duckingConfig.releaseSec;
 
     cueDucker.gain.cancelScheduledValues(now);
     ambienceDucker.gain.cancelScheduledValues(now);
-    cueDucker.gain.rampTo(cueTarget, 0.2, now);
-    ambienceDucker.gain.rampTo(ambienceTarget, 0.2, now);
+    cueDucker.gain.rampTo(cueTarget, ramp, now);
+    ambienceDucker.gain.rampTo(ambienceRestGain(), ramp, now);
+}
+
+/** Duck the ambience mix around a chime starting at `when`, then restore it */
+function duckForChime(when: number): void {
+    if (!ambienceDucker) return;
+    const { depthDb, attackSec, releaseSec, chimeHoldSec } = duckingConfig;
+    const rest = ambienceRestGain();
+    const ducked = Math.min(rest, dbToGain(depthDb));
+    if (ducked >= rest) return;
+
+    const gain = ambienceDucker.gain;
+    gain.cancelScheduledValues(when);
+    gain.rampTo(ducked, attackSec, when);
+    gain.rampTo(rest, releaseSec, when + attackSec + chimeHoldSec);
+}
+
+/**
+ * Sleep wind-down: scale the whole mix by `gain` (0-1, from the runtime's
+ * sleep status) on top of the normal master level.
+ */
+export function setSleepFade(gain: number, rampSec = 2): void {
+    if (!masterBus) return;
+    const g = clamp(gain, 0, 1);
+    const db = g > 0 ? Math.max(SLEEP_FADE_FLOOR_DB, 20 * Math.log10(g)) : SLEEP_FADE_FLOOR_DB;
+    masterBus.volume.rampTo(MASTER_VOLUME_DB + db, rampSec);
 }
 
 /**
@@ -791,6 +861,9 @@ export async function playCue(
     }
 
     const dur = clamp(duration, 0.3, 15);
+    // Breath sounds are part of the bed; everything else is a chime
+    const isBreathSound = packMeta.engine === 'breath' && (cue === 'inhale' || cue === 'exhale');
+    if (!isBreathSound) duckForChime(when);
 
     if (packMeta.engine === 'samples') {
         // Start loading in the background; don't block this cue (prevents perceived lag).
@@ -883,6 +956,7 @@ export function cleanupAudio(): void {
     safeDispose(compressor); safeDispose(reverb); safeDispose(limiter); safeDispose(masterBus); safeDispose(panner3D);
     masterBus = null; cueBus = null; ambienceBus = null; voiceBus = null;
     cueDucker = null; ambienceDucker = null;
+    aiSpeaking = false; voiceSpeaking = false;
     spatializer = null; masterEQ = null; warmth = null; compressor = null; reverb = null; limiter = null; panner3D = null;
 
     if (typeof window !== 'undefined' && window.speechSynthesis) try { window.speechSynthesis.cancel(); } catch { }
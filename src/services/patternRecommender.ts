@@ -4,7 +4,7 @@
  * @deprecated This TypeScript implementation is now a FALLBACK only.
  * The primary PatternRecommender is implemented in Rust (rust-core/lib.rs)
  * and is available via Tauri commands:
- * - recommend_patterns(local_hour, limit)
+ * - recommend_patterns(limit)
  * - record_pattern_usage(pattern_id)
  * - clear_pattern_history()
  * 
@@ -2,6 +2,7 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import { UserSettings, SessionHistoryItem, ColorTheme, QualityTier, Language, SoundPack, BreathingType, BeliefState } from '../types';
+import { getTauriRuntime, isTauriAvailable } from '../services/TauriRuntime';
 
 // NOTE: We need a way to access Kernel from store actions, but Zustand is outside React context.
 // In a pure architecture, the Store would dispatch to Kernel. 
@@ -9,6 +10,21 @@ import { UserSettings, SessionHistoryItem, ColorTheme, QualityTier, Language, So
 // OR the UI calls store actions which then interact with kernel via the provider hooks in components.
 // Safety registry operations are handled by the kernel bridge and storage services.
 
+// SHA-256 of the passphrase, hex - for verification only (NOT for encryption)
+async function hashPassphrase(passphrase: string): Promise<string> {
+  const data = new TextEncoder().encode(passphrase);
+  const hashBuffer = await crypto.subtle.digest('SHA-256', data);
+  return Array.from(new Uint8Array(hashBuffer)).map(b => b.toString(16).padStart(2, '0')).join('');
+}
+
+// Open the Rust session history store with the passphrase (desktop/mobile only)
+async function unlockNativeSessionStore(passphrase: string): Promise<void> {
+  if (!isTauriAvailable()) return;
+  const runtime = getTauriRuntime();
+  if (await runtime.isSessionStoreUnlocked()) return;
+  await runtime.unlockSessionStore(passphrase);
+}
+
 type SettingsState = {
   userSettings: UserSettings;
   history: SessionHistoryItem[];
@@ -34,6 +50,7 @@ type SettingsState = {
   toggleCoaching: () => void; // v6.1 In-session coaching
   setPassphrase: (passphrase: string) => Promise<void>; // P1 Security Fix
   clearPassphrase: () => void; // P1 Security Fix
+  unlockWithPassphrase: (passphrase: string) => Promise<boolean>;
 
   // Logic
   registerSessionComplete: (durationSec: number, patternId: BreathingType, cycles: number, finalBelief: BeliefState) => void;
@@ -97,11 +114,7 @@ export const useSettingsStore = create<SettingsState>()(
       // P1 Security Fix: Passphrase Management
       setPassphrase: async (passphrase: string) => {
         // Hash passphrase for verification (NOT for encryption)
-        const encoder = new TextEncoder();
-        const data = encoder.encode(passphrase);
-        const hashBuffer = await crypto.subtle.digest('SHA-256', data);
-        const hashArray = Array.from(new Uint8Array(hashBuffer));
-        const hashHex = hashArray.map(b => b.toString(16).padStart(2, '0')).join('');
+        const hashHex = await hashPassphrase(passphrase);
 
         set((s) => ({
           userSettings: {
@@ -114,6 +127,17 @@ export const useSettingsStore = create<SettingsState>()(
         // Store passphrase in sessionStorage (cleared on tab close)
         // This is used by SecureBioFS.init()
         sessionStorage.setItem('zenb_passphrase', passphrase);
+        await unlockNativeSessionStore(passphrase);
+      },
+
+      // Re-enter the passphrase after a restart: checked against the stored hash,
+      // then used to unlock the native session store
+      unlockWithPassphrase: async (passphrase: string) => {
+        const hashHex = await hashPassphrase(passphrase);
+        if (hashHex !== get().userSettings.passphraseHash) return false;
+        await unlockNativeSessionStore(passphrase);
+        sessionStorage.setItem('zenb_passphrase', passphrase);
+        return true;
       },
 
       resetSafetyLock: (patternId: BreathingType) => {